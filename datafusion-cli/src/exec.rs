@@ -25,6 +25,7 @@ use crate::{
     helper::CliHelper,
     object_storage::get_object_store,
     print_options::{MaxRows, PrintOptions},
+    progress::ProgressBar,
 };
 use datafusion::common::instant::Instant;
 use datafusion::common::{plan_datafusion_err, plan_err};
@@ -278,14 +279,24 @@ impl StatementExecutor {
             }
             // As the input stream comes, we can generate results.
             // However, memory safety is not guaranteed.
-            let stream = execute_stream(physical_plan, task_ctx.clone())?;
+            let mut stream = execute_stream(physical_plan, task_ctx.clone())?;
+            let progress_bar = print_options
+                .progress
+                .then(|| ProgressBar::start(task_ctx.clone()));
+            if let Some(progress_bar) = &progress_bar {
+                stream = progress_bar.wrap_stream(stream);
+            }
             print_options
                 .print_stream(stream, now, &options.format)
                 .await?;
+            drop(progress_bar);
         } else {
             // Bounded stream; collected results size is limited by the maxrows option
             let schema = physical_plan.schema();
             let mut stream = execute_stream(physical_plan, task_ctx.clone())?;
+            let progress_bar = print_options
+                .progress
+                .then(|| ProgressBar::start(task_ctx.clone()));
             let mut results = vec![];
             let mut row_count = 0_usize;
             let max_rows = match print_options.maxrows {
@@ -304,6 +315,7 @@ impl StatementExecutor {
                 }
                 row_count += curr_num_rows;
             }
+            drop(progress_bar);
             adjusted.into_inner().print_batches(
                 schema,
                 &results,
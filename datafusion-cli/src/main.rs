@@ -137,6 +137,9 @@ struct Args {
     #[clap(long, help = "Enables console syntax highlighting")]
     color: bool,
 
+    #[clap(long, help = "Displays a progress bar while the queries execute")]
+    progress: bool,
+
     #[clap(
         short = 'd',
         long,
@@ -224,6 +227,7 @@ async fn main_inner() -> Result<()> {
         quiet: args.quiet,
         maxrows: args.maxrows,
         color: args.color,
+        progress: args.progress,
     };
 
     let commands = args.command;
@@ -73,6 +73,8 @@ pub struct PrintOptions {
     pub quiet: bool,
     pub maxrows: MaxRows,
     pub color: bool,
+    /// Render a progress bar while the queries execute
+    pub progress: bool,
 }
 
 // Returns the query execution details formatted
@@ -0,0 +1,241 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Progress bar of the running queries
+
+use std::io::{IsTerminal, Write};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::instant::Instant;
+use datafusion::error::Result;
+use datafusion::execution::memory_pool::human_readable_size;
+use datafusion::execution::progress::QueryProgress;
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use futures::{Stream, StreamExt};
+
+/// Width of the bar, in characters
+const BAR_WIDTH: usize = 30;
+/// Queries completing faster than this are not displayed a progress bar
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+/// Interval between two renderings of the progress bar
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Renders the progress of the queries executed with a [`TaskContext`] on
+/// stderr, until it is dropped
+///
+/// Nothing is rendered if stderr is not a terminal.
+pub struct ProgressBar {
+    stop: Arc<AtomicBool>,
+    line: Arc<Mutex<ProgressLine>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// The state of the line of the progress bar on the terminal
+#[derive(Default)]
+struct ProgressLine {
+    /// Whether the progress bar is displayed
+    rendered: bool,
+    /// Whether the progress bar is hidden while results are printed
+    suspended: bool,
+}
+
+impl ProgressLine {
+    fn clear(&mut self) {
+        if self.rendered {
+            eprint!("\r\x1b[2K");
+            let _ = std::io::stderr().flush();
+            self.rendered = false;
+        }
+    }
+}
+
+impl ProgressBar {
+    /// Start rendering the progress of the queries of `task_ctx`
+    pub fn start(task_ctx: Arc<TaskContext>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let line = Arc::new(Mutex::new(ProgressLine::default()));
+        let handle = std::io::stderr().is_terminal().then(|| {
+            let stop = Arc::clone(&stop);
+            let line = Arc::clone(&line);
+            std::thread::spawn(move || render(&task_ctx, &stop, &line))
+        });
+        Self { stop, line, handle }
+    }
+
+    /// Wraps a stream whose batches are printed as they are received: the
+    /// progress bar is hidden from the time a batch is returned until the
+    /// next batch is polled, so that it does not garble the printed rows
+    pub fn wrap_stream(
+        &self,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(ProgressStream {
+            inner: stream,
+            line: Arc::clone(&self.line),
+        })
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Renders the progress of the queries of `task_ctx` until `stop` is set,
+/// then clears it
+fn render(task_ctx: &TaskContext, stop: &AtomicBool, line: &Mutex<ProgressLine>) {
+    let start = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(20));
+        if start.elapsed() < INITIAL_DELAY {
+            continue;
+        }
+        if let Some(progress) = task_ctx.progress().last() {
+            let mut line = line.lock().unwrap();
+            if !line.suspended {
+                eprint!("\r{}", format_progress(progress));
+                let _ = std::io::stderr().flush();
+                line.rendered = true;
+            }
+        }
+        sleep_unless_stopped(REFRESH_INTERVAL, stop);
+    }
+    // clear the line before the results are printed
+    line.lock().unwrap().clear();
+}
+
+/// A stream hiding the progress bar while its batches are printed, see
+/// [`ProgressBar::wrap_stream`]
+struct ProgressStream {
+    inner: SendableRecordBatchStream,
+    line: Arc<Mutex<ProgressLine>>,
+}
+
+impl Stream for ProgressStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // the previous batch is printed
+        self.line.lock().unwrap().suspended = false;
+        let poll = self.inner.poll_next_unpin(cx);
+        if poll.is_ready() {
+            let mut line = self.line.lock().unwrap();
+            line.clear();
+            line.suspended = true;
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for ProgressStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+fn sleep_unless_stopped(duration: Duration, stop: &AtomicBool) {
+    let start = Instant::now();
+    while start.elapsed() < duration && !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Formats the progress of a query as a single line
+pub fn format_progress(progress: &QueryProgress) -> String {
+    let bar = match progress.percent_complete {
+        Some(percent) => {
+            let filled = ((percent / 100.0) * BAR_WIDTH as f64).round() as usize;
+            let filled = filled.min(BAR_WIDTH);
+            format!(
+                "[{}{}] {percent:>3.0}%",
+                "=".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled)
+            )
+        }
+        None => format!("[{}]    ?", "-".repeat(BAR_WIDTH)),
+    };
+    format!(
+        "{bar} | {} rows | {} | {} spills | {:.1}s",
+        progress.output_rows().unwrap_or_default(),
+        human_readable_size(progress.memory_used),
+        progress.spill_count(),
+        progress.elapsed.as_secs_f64()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::array::Int32Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+
+    #[test]
+    fn format_query_progress() {
+        let mut progress = QueryProgress {
+            operators: vec![],
+            memory_used: 3 * 1024 * 1024,
+            elapsed: Duration::from_millis(1500),
+            percent_complete: Some(50.0),
+        };
+        assert_eq!(
+            format_progress(&progress),
+            "[===============               ]  50% | 0 rows | 3.0 MB | 0 spills | 1.5s"
+        );
+
+        progress.percent_complete = None;
+        assert_eq!(
+            format_progress(&progress),
+            "[------------------------------]    ? | 0 rows | 3.0 MB | 0 spills | 1.5s"
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_suspended_while_batches_are_printed() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+        )?;
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(vec![Ok(batch.clone())]),
+        ));
+        let progress_bar = ProgressBar::start(Arc::new(TaskContext::default()));
+        let mut stream = progress_bar.wrap_stream(stream);
+
+        assert_eq!(stream.next().await.unwrap()?, batch);
+        assert!(progress_bar.line.lock().unwrap().suspended);
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
+}
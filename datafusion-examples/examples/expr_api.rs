@@ -296,13 +296,10 @@ fn boundary_analysis_and_selectivity_demo() -> Result<()> {
     // these estimates represented by the `ColumnStatistics` type describe
     // properties such as the maximum and minimum value, the number of distinct
     // values and the number of null values.
-    let column_stats = ColumnStatistics {
-        null_count: Precision::Exact(0),
-        max_value: Precision::Exact(ScalarValue::Int64(Some(10000))),
-        min_value: Precision::Exact(ScalarValue::Int64(Some(1))),
-        sum_value: Precision::Absent,
-        distinct_count: Precision::Absent,
-    };
+    let column_stats = ColumnStatistics::new_unknown()
+        .with_null_count(Precision::Exact(0))
+        .with_max_value(Precision::Exact(ScalarValue::Int64(Some(10000))))
+        .with_min_value(Precision::Exact(ScalarValue::Int64(Some(1))));
 
     // We can then build our expression boundaries from the column statistics
     // allowing the analysis to be more precise.
@@ -363,13 +360,10 @@ fn boundary_analysis_in_conjuctions_demo() -> Result<()> {
     // Similarly to the example in `boundary_analysis_and_selectivity_demo` we
     // can establish column statistics that can be used to describe certain
     // column properties.
-    let column_stats = ColumnStatistics {
-        null_count: Precision::Exact(0),
-        max_value: Precision::Exact(ScalarValue::Int64(Some(79))),
-        min_value: Precision::Exact(ScalarValue::Int64(Some(14))),
-        sum_value: Precision::Absent,
-        distinct_count: Precision::Absent,
-    };
+    let column_stats = ColumnStatistics::new_unknown()
+        .with_null_count(Precision::Exact(0))
+        .with_max_value(Precision::Exact(ScalarValue::Int64(Some(79))))
+        .with_min_value(Precision::Exact(ScalarValue::Int64(Some(14))));
 
     let initial_boundaries =
         vec![ExprBoundaries::try_from_column(&schema, &column_stats, 0)?];
@@ -23,7 +23,7 @@ use std::{any::Any, borrow::Cow};
 use crate::TableProvider;
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{internal_err, Constraints};
+use datafusion_common::{internal_err, Constraints, Statistics};
 use datafusion_expr::{Expr, TableProviderFilterPushDown, TableSource, TableType};
 
 /// Implements [`TableSource`] for a [`TableProvider`]
@@ -38,12 +38,25 @@ use datafusion_expr::{Expr, TableProviderFilterPushDown, TableSource, TableType}
 pub struct DefaultTableSource {
     /// table provider
     pub table_provider: Arc<dyn TableProvider>,
+    /// Statistics of the table that supplement the statistics of the
+    /// provider, e.g. computed by `ANALYZE TABLE`
+    statistics: Option<Arc<Statistics>>,
 }
 
 impl DefaultTableSource {
     /// Create a new DefaultTableSource to wrap a TableProvider
     pub fn new(table_provider: Arc<dyn TableProvider>) -> Self {
-        Self { table_provider }
+        Self {
+            table_provider,
+            statistics: None,
+        }
+    }
+
+    /// Supplement the statistics of the provider with `statistics`, see
+    /// [`Statistics::fill_absent`]
+    pub fn with_statistics(mut self, statistics: Option<Arc<Statistics>>) -> Self {
+        self.statistics = statistics;
+        self
     }
 }
 
@@ -85,6 +98,14 @@ impl TableSource for DefaultTableSource {
     fn get_column_default(&self, column: &str) -> Option<&Expr> {
         self.table_provider.get_column_default(column)
     }
+
+    fn statistics(&self) -> Option<Statistics> {
+        match (self.table_provider.statistics(), &self.statistics) {
+            (Some(provided), Some(statistics)) => Some(provided.fill_absent(statistics)),
+            (None, Some(statistics)) => Some(statistics.as_ref().clone()),
+            (provided, None) => provided,
+        }
+    }
 }
 
 /// Wrap TableProvider in TableSource
@@ -27,7 +27,9 @@ use crate::TableProvider;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::error::Result;
-use datafusion_common::{not_impl_err, plan_err, Constraints, DFSchema, SchemaExt};
+use datafusion_common::{
+    not_impl_err, plan_err, Constraints, DFSchema, SchemaExt, Statistics,
+};
 use datafusion_common_runtime::JoinSet;
 use datafusion_datasource::memory::{MemSink, MemorySourceConfig};
 use datafusion_datasource::sink::DataSinkExec;
@@ -212,6 +214,20 @@ impl TableProvider for MemTable {
         TableType::Base
     }
 
+    /// Returns the number of rows and the null counts of the batches, or
+    /// `None` if they are being written to.
+    fn statistics(&self) -> Option<Statistics> {
+        let mut partitions = Vec::with_capacity(self.batches.len());
+        for partition in self.batches.iter() {
+            partitions.push(partition.try_read().ok()?.clone());
+        }
+        Some(common::compute_record_batch_statistics(
+            &partitions,
+            &self.schema,
+            None,
+        ))
+    }
+
     async fn scan(
         &self,
         state: &dyn Session,
@@ -262,9 +262,9 @@ pub trait TableProvider: Debug + Sync + Send {
     }
 
     /// Get statistics for this table, if available
-    /// Although not presently used in mainline DataFusion, this allows implementation specific
-    /// behavior for downstream repositories, in conjunction with specialized optimizer rules to
-    /// perform operations such as re-ordering of joins.
+    ///
+    /// These are used by the logical optimizer, for example by the
+    /// `ReorderJoins` rule to estimate the cost of the possible join orders.
     fn statistics(&self) -> Option<Statistics> {
         None
     }
@@ -360,6 +360,15 @@ config_namespace! {
         /// `ListingTableProvider` in DataFusion. Defaults to true.
         pub collect_statistics: bool, default = true
 
+        /// The maximum number of buckets of the equi-depth histograms of the
+        /// columns computed by `ANALYZE TABLE`. Set to 0 to not compute the
+        /// histograms.
+        pub analyze_histogram_buckets: usize, default = 100
+
+        /// The maximum number of most common values of the columns computed
+        /// by `ANALYZE TABLE`. Set to 0 to not compute the most common values.
+        pub analyze_most_common_values: usize, default = 100
+
         /// Number of partitions for query execution. Increasing partitions can increase
         /// concurrency.
         ///
@@ -403,6 +412,53 @@ config_namespace! {
         /// higher compression ratios at the cost of slower (de)compression speed.
         pub spill_compression: SpillCompression, default = SpillCompression::Uncompressed
 
+        /// Maximum number of bytes of the files spilled by a single query. When
+        /// the spill files of a query exceed this limit, the query fails. If
+        /// not set, only the limit of the `DiskManager` applies.
+        pub max_spill_size_per_query: Option<usize>, default = None
+
+        /// Maximum duration of a query, in milliseconds. When a query runs
+        /// longer, it is cancelled and fails. If not set, queries never time out
+        pub query_timeout: Option<u64>, default = None
+
+        /// Maximum number of bytes output by the data sources of a single
+        /// query, measured as the in-memory size of their decoded and projected
+        /// batches. This is not the number of bytes read from storage, which is
+        /// usually much smaller for compressed formats such as Parquet. When
+        /// the data sources of a query output more, it is cancelled and fails.
+        /// If not set, the output of the data sources is not limited
+        pub max_scanned_bytes: Option<usize>, default = None
+
+        /// Maximum number of rows returned by a single query. When a query
+        /// returns more, it is cancelled and fails. If not set, the rows
+        /// returned are not limited
+        pub max_result_rows: Option<usize>, default = None
+
+        /// Priority of the queries in the memory pool of the runtime, higher values
+        /// having a higher priority. Only used by the pools that reserve the memory
+        /// of each query in a pool of its own, such as the `HierarchicalMemoryPool`,
+        /// and ignored by the other pools, which are shared by all the queries.
+        /// When a query can not reserve the memory it needs, the spillable
+        /// operators of the queries with a strictly lower priority are asked to
+        /// spill the missing memory, and with the `CancelLowerPriority` overcommit
+        /// policy the queries with a lower priority are cancelled, lowest priority
+        /// first, until enough memory is freed. The queries with the same or a
+        /// higher priority are not affected: the reservation that could not grow
+        /// fails instead
+        pub query_priority: i32, default = 0
+
+        /// When set to true, the results of the queries, and of their subplans which only
+        /// read files, are cached in the query result cache of the runtime, see
+        /// `datafusion.runtime.query_result_cache_limit`. A cached result is returned to
+        /// the identical plans executed later, as long as the files they read have the
+        /// same size and last modification time. The plans with volatile functions are
+        /// not cached
+        pub enable_result_cache: bool, default = false
+
+        /// Maximum number of bytes of the result of a plan to cache. The larger results
+        /// are not cached. See `enable_result_cache`
+        pub result_cache_max_entry_size: usize, default = 16 * 1024 * 1024
+
         /// Specifies the reserved memory for each spillable sort operation to
         /// facilitate an in-memory merge.
         ///
@@ -698,11 +754,18 @@ config_namespace! {
         /// during aggregations, if possible
         pub enable_topk_aggregation: bool, default = true
 
+        /// When set to true, the optimizer will replace a `ROW_NUMBER` or `RANK`
+        /// window followed by a filter keeping its first rows, such as `rn <= 3`,
+        /// by a partitioned TopK that keeps the first rows of each partition
+        /// instead of sorting the whole input
+        pub enable_partitioned_topk: bool, default = true
+
         /// When set to true attempts to push down dynamic filters generated by operators into the file scan phase.
         /// For example, for a query such as `SELECT * FROM t ORDER BY timestamp DESC LIMIT 10`, the optimizer
         /// will attempt to push down the current top 10 timestamps that the TopK operator references into the file scans.
         /// This means that if we already have 10 timestamps in the year 2025
         /// any files that only have timestamps in the year 2024 can be skipped / pruned at various stages in the scan.
+        /// Similarly, a HashJoin pushes down the bounds of its build side join keys into the scan of its probe side.
         pub enable_dynamic_filter_pushdown: bool, default = true
 
         /// When set to true, the optimizer will insert filters before a join between
@@ -711,6 +774,74 @@ config_namespace! {
         /// predicate push down.
         pub filter_null_join_keys: bool, default = false
 
+        /// When set to true, the optimizer will reorder the inputs of trees of inner
+        /// joins to minimize the estimated size of the intermediate results. The
+        /// estimates are based on the row counts and distinct counts of the
+        /// `Statistics` of the joined tables, and the joins are only reordered when
+        /// the row counts of all their inputs are known.
+        pub enable_join_reordering: bool, default = false
+
+        /// The maximum number of inputs of a tree of inner joins for which the join
+        /// reordering enumerates all the join orders (using the DPccp algorithm).
+        /// Larger trees of joins are reordered greedily, by repeatedly joining the
+        /// pair of inputs with the smallest estimated result.
+        /// See `enable_join_reordering`.
+        pub join_reordering_dp_threshold: usize, default = 12
+
+        /// When set to true, the optimizer will push partial aggregates below inner
+        /// joins and into the inputs of unions (eager aggregation), if they are
+        /// estimated to at least halve the number of rows of their inputs. The
+        /// estimates are based on the row counts and distinct counts of the
+        /// `Statistics` of the scanned tables.
+        pub enable_eager_aggregation: bool, default = false
+
+        /// When set to true, the analyzer will look for identical subplans that occur
+        /// several times in a query, such as a common table expression referenced more
+        /// than once or the inputs of a self-join, and evaluate each of them once,
+        /// sharing the result between its references. Common table expressions declared
+        /// `MATERIALIZED` are always shared, and `NOT MATERIALIZED` ones never are.
+        pub enable_subplan_reuse: bool, default = false
+
+        /// The minimum number of joins, aggregates, sorts and window functions a
+        /// subplan must contain to be shared between its occurrences.
+        /// See `enable_subplan_reuse`.
+        pub subplan_reuse_threshold: usize, default = 1
+
+        /// When set to true, the optimizer will answer queries, or parts of queries, from
+        /// the tables registered as rewrite candidates that store the results of plans they
+        /// contain, such as materialized views and summary tables
+        pub enable_materialized_view_rewrite: bool, default = true
+
+        /// When set to true, the partitioned hash joins are re-optimized during execution
+        /// using the actual sizes of their inputs. The build side of each join is
+        /// materialized before the join executes, and the join is switched to `CollectLeft`
+        /// mode, has its inputs swapped or has its small partitions coalesced based on the
+        /// materialized row counts and sizes. The decisions are reported by `EXPLAIN ANALYZE`
+        pub enable_adaptive_execution: bool, default = false
+
+        /// The target size in bytes of the partitions of the hash joins re-optimized by
+        /// adaptive execution, used to coalesce small partitions.
+        /// See `enable_adaptive_execution`
+        pub adaptive_target_partition_size: usize, default = 64 * 1024 * 1024
+
+        /// When set to true, the hash repartitions detect the hot keys of their inputs by
+        /// sampling the keys of the first rows of each input partition, and report the rows
+        /// sent to each output partition in their metrics. The rows of the hot keys of the
+        /// partitioned aggregates are spread across all the partitions, and merged by a
+        /// final aggregation. The rows of the hot keys of the probe side of the partitioned
+        /// hash joins are spread across all the partitions, and the matching build rows are
+        /// replicated to all the partitions
+        pub enable_skew_handling: bool, default = false
+
+        /// The number of rows sampled from each input partition of a hash repartition to
+        /// detect its hot keys. See `enable_skew_handling`
+        pub skew_sample_rows: usize, default = 10000
+
+        /// A key is hot when its share of the sampled rows is more than this ratio of the
+        /// share of an output partition, i.e. `1 / partitions`.
+        /// See `enable_skew_handling`
+        pub skew_hot_key_ratio: f64, default = 0.5
+
         /// Should DataFusion repartition data using the aggregate keys to execute aggregates
         /// in parallel using the provided `target_partitions` level
         pub repartition_aggregations: bool, default = true
@@ -801,6 +932,12 @@ config_namespace! {
         /// will be collected into a single partition
         pub hash_join_single_partition_threshold_rows: usize, default = 1024 * 128
 
+        /// The maximum number of rows of the build side of a HashJoin for which the
+        /// dynamic filter pushed down to the probe side lists the build side join key
+        /// values (`key IN (...)`) in addition to their min/max bounds. Set to 0 to
+        /// only push down the bounds. See `enable_dynamic_filter_pushdown`.
+        pub hash_join_inlist_pushdown_max_rows: usize, default = 128
+
         /// The default filter selectivity used by Filter Statistics
         /// when an exact selectivity cannot be determined. Valid values are
         /// between 0 (no selectivity) and 100 (all rows are selected).
@@ -830,7 +967,8 @@ config_namespace! {
         pub physical_plan_only: bool, default = false
 
         /// When set to true, the explain statement will print operator statistics
-        /// for physical plans
+        /// for physical plans, and the estimated number of rows of the nodes of
+        /// the final logical plan
         pub show_statistics: bool, default = false
 
         /// When set to true, the explain statement will print the partition sizes
@@ -1336,6 +1474,7 @@ config_field!(bool, value => default_config_transform(value.to_lowercase().as_st
 config_field!(usize);
 config_field!(f64);
 config_field!(u64);
+config_field!(i32);
 
 impl ConfigField for u8 {
     fn visit<V: Visit>(&self, v: &mut V, key: &str, description: &'static str) {
@@ -17,7 +17,9 @@
 
 //! This module provides data structures to represent statistics
 
+use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
 
 use crate::{Result, ScalarValue};
 
@@ -385,6 +387,26 @@ impl Statistics {
         self
     }
 
+    /// Fills the statistics that are absent from `self` with the statistics of
+    /// `other` for the same columns, for example statistics computed by
+    /// `ANALYZE TABLE`. The statistics taken from `other` are marked as
+    /// inexact, as the data may have changed since they were computed.
+    pub fn fill_absent(mut self, other: &Statistics) -> Self {
+        if self.num_rows == Precision::Absent {
+            self.num_rows = other.num_rows.to_inexact();
+        }
+        if self.total_byte_size == Precision::Absent {
+            self.total_byte_size = other.total_byte_size.to_inexact();
+        }
+        self.column_statistics = self
+            .column_statistics
+            .into_iter()
+            .zip(other.column_statistics.iter())
+            .map(|(col_stats, other_col_stats)| col_stats.fill_absent(other_col_stats))
+            .collect();
+        self
+    }
+
     /// Calculates the statistics after applying `fetch` and `skip` operations.
     ///
     /// Here, `self` denotes per-partition statistics. Use the `n_partitions`
@@ -538,6 +560,9 @@ impl Statistics {
             col_stats.max_value = col_stats.max_value.max(&item_col_stats.max_value);
             col_stats.min_value = col_stats.min_value.min(&item_col_stats.min_value);
             col_stats.sum_value = col_stats.sum_value.add(&item_col_stats.sum_value);
+            // The distribution of the merged values is unknown
+            col_stats.histogram = None;
+            col_stats.most_common_values = None;
         }
 
         Ok(Statistics {
@@ -599,6 +624,16 @@ impl Display for Statistics {
                 } else {
                     s
                 };
+                let s = if let Some(histogram) = &cs.histogram {
+                    format!("{} Histogram={}", s, histogram.buckets().len())
+                } else {
+                    s
+                };
+                let s = if let Some(most_common_values) = &cs.most_common_values {
+                    format!("{} MostCommon={}", s, most_common_values.values().len())
+                } else {
+                    s
+                };
 
                 s + ")"
             })
@@ -616,7 +651,12 @@ impl Display for Statistics {
 }
 
 /// Statistics for a column within a relation
+///
+/// New statistics may be added in the future: create them with
+/// [`ColumnStatistics::new_unknown`] and the `with_*` methods, such as
+/// [`ColumnStatistics::with_null_count`].
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub struct ColumnStatistics {
     /// Number of null values on column
     pub null_count: Precision<usize>,
@@ -628,6 +668,10 @@ pub struct ColumnStatistics {
     pub sum_value: Precision<ScalarValue>,
     /// Number of distinct values
     pub distinct_count: Precision<usize>,
+    /// Equi-depth histogram of the values of the column
+    pub histogram: Option<Arc<Histogram>>,
+    /// The most common values of the column and their number of rows
+    pub most_common_values: Option<Arc<MostCommonValues>>,
 }
 
 impl ColumnStatistics {
@@ -650,6 +694,8 @@ impl ColumnStatistics {
             min_value: Precision::Absent,
             sum_value: Precision::Absent,
             distinct_count: Precision::Absent,
+            histogram: None,
+            most_common_values: None,
         }
     }
 
@@ -683,6 +729,21 @@ impl ColumnStatistics {
         self
     }
 
+    /// Set the histogram
+    pub fn with_histogram(mut self, histogram: Option<Arc<Histogram>>) -> Self {
+        self.histogram = histogram;
+        self
+    }
+
+    /// Set the most common values
+    pub fn with_most_common_values(
+        mut self,
+        most_common_values: Option<Arc<MostCommonValues>>,
+    ) -> Self {
+        self.most_common_values = most_common_values;
+        self
+    }
+
     /// If the exactness of a [`ColumnStatistics`] instance is lost, this
     /// function relaxes the exactness of all information by converting them
     /// [`Precision::Inexact`].
@@ -694,6 +755,272 @@ impl ColumnStatistics {
         self.distinct_count = self.distinct_count.to_inexact();
         self
     }
+
+    /// Fills the statistics that are absent from `self` with the (inexact)
+    /// statistics of `other`. See [`Statistics::fill_absent`].
+    pub fn fill_absent(mut self, other: &ColumnStatistics) -> Self {
+        if self.null_count == Precision::Absent {
+            self.null_count = other.null_count.to_inexact();
+        }
+        if self.max_value == Precision::Absent {
+            self.max_value = other.max_value.clone().to_inexact();
+        }
+        if self.min_value == Precision::Absent {
+            self.min_value = other.min_value.clone().to_inexact();
+        }
+        if self.sum_value == Precision::Absent {
+            self.sum_value = other.sum_value.clone().to_inexact();
+        }
+        if self.distinct_count == Precision::Absent {
+            self.distinct_count = other.distinct_count.to_inexact();
+        }
+        if self.histogram.is_none() {
+            self.histogram.clone_from(&other.histogram);
+        }
+        if self.most_common_values.is_none() {
+            self.most_common_values
+                .clone_from(&other.most_common_values);
+        }
+        self
+    }
+
+    /// Estimates the fraction of the rows whose value is between `lower` and
+    /// `upper` (inclusive) using the histogram and the most common values of
+    /// the column. A null bound means the range is unbounded on that side.
+    ///
+    /// Returns `None` if the estimate can't be computed from the histogram
+    /// and the most common values, e.g. if neither is known.
+    pub fn range_selectivity(
+        &self,
+        lower: &ScalarValue,
+        upper: &ScalarValue,
+    ) -> Option<f64> {
+        let selectivity = match (&self.histogram, &self.most_common_values) {
+            (Some(histogram), Some(most_common_values)) => {
+                // The histogram doesn't include the most common values
+                histogram.range_selectivity(lower, upper)
+                    + most_common_values.range_selectivity(lower, upper)
+            }
+            (Some(histogram), None) => histogram.range_selectivity(lower, upper),
+            // Without a histogram, only the frequency of the most common
+            // values is known
+            (None, Some(most_common_values)) if !lower.is_null() && lower == upper => {
+                most_common_values.frequency(lower)?
+            }
+            _ => return None,
+        };
+        Some(selectivity.min(1.0))
+    }
+}
+
+/// An equi-depth histogram of the non null values of a column: the values are
+/// split into buckets of consecutive values that contain approximately the
+/// same number of rows.
+///
+/// When it is computed along with the [`MostCommonValues`] of the column (e.g.
+/// by `ANALYZE TABLE`), the histogram doesn't include the most common values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    /// The buckets, ordered by their values
+    buckets: Vec<HistogramBucket>,
+    /// The total number of rows of the column, including nulls
+    num_rows: usize,
+}
+
+/// A bucket of a [`Histogram`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// The smallest value of the bucket
+    pub lower: ScalarValue,
+    /// The largest value of the bucket
+    pub upper: ScalarValue,
+    /// The number of rows with a value in the bucket
+    pub num_rows: usize,
+    /// The number of distinct values in the bucket
+    pub distinct_count: usize,
+}
+
+impl Histogram {
+    /// Create a histogram from its buckets, ordered by their values, for a
+    /// column with `num_rows` rows in total (including nulls and values that
+    /// are not in the histogram).
+    pub fn new(buckets: Vec<HistogramBucket>, num_rows: usize) -> Self {
+        Self { buckets, num_rows }
+    }
+
+    /// Create an equi-depth histogram with at most `num_buckets` buckets from
+    /// distinct values sorted in ascending order and their number of rows.
+    ///
+    /// Returns `None` if there are no values or buckets.
+    pub fn try_new_equi_depth(
+        values: &[(ScalarValue, usize)],
+        num_buckets: usize,
+        num_rows: usize,
+    ) -> Option<Self> {
+        if values.is_empty() || num_buckets == 0 {
+            return None;
+        }
+        let num_buckets = num_buckets.min(values.len());
+        let total_rows: usize = values.iter().map(|(_, count)| count).sum();
+
+        let mut buckets = Vec::with_capacity(num_buckets);
+        let (mut start, mut bucket_rows, mut remaining_rows) = (0, 0, total_rows);
+        for (index, (value, count)) in values.iter().enumerate() {
+            bucket_rows += count;
+            // Close the bucket once it contains its share of the rows that
+            // are not in the previous buckets. A frequent value may fill more
+            // than one share, the next buckets then share the other rows.
+            if bucket_rows * num_buckets.saturating_sub(buckets.len()) >= remaining_rows
+                || index == values.len() - 1
+            {
+                remaining_rows -= bucket_rows;
+                buckets.push(HistogramBucket {
+                    lower: values[start].0.clone(),
+                    upper: value.clone(),
+                    num_rows: bucket_rows,
+                    distinct_count: index + 1 - start,
+                });
+                start = index + 1;
+                bucket_rows = 0;
+            }
+        }
+        Some(Self::new(buckets, num_rows))
+    }
+
+    /// Returns the buckets of the histogram, ordered by their values
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+
+    /// Returns the total number of rows of the column, including nulls
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Estimates the fraction of the rows of the column with a value of the
+    /// histogram between `lower` and `upper` (inclusive). A null bound means
+    /// the range is unbounded on that side.
+    ///
+    /// The values of a bucket that partially overlaps the range are assumed
+    /// to be uniformly distributed.
+    pub fn range_selectivity(&self, lower: &ScalarValue, upper: &ScalarValue) -> f64 {
+        if self.num_rows == 0 {
+            return 0.0;
+        }
+        let num_rows: f64 = self
+            .buckets
+            .iter()
+            .map(|bucket| {
+                let start = if lower.is_null() || &bucket.lower >= lower {
+                    &bucket.lower
+                } else {
+                    lower
+                };
+                let end = if upper.is_null() || &bucket.upper <= upper {
+                    &bucket.upper
+                } else {
+                    upper
+                };
+                if start > end || start > &bucket.upper || end < &bucket.lower {
+                    // No overlap
+                    0.0
+                } else if start == &bucket.lower && end == &bucket.upper {
+                    bucket.num_rows as f64
+                } else {
+                    // At least one of the distinct values of the bucket
+                    let value_rows =
+                        bucket.num_rows as f64 / bucket.distinct_count.max(1) as f64;
+                    if start == end {
+                        value_rows
+                    } else {
+                        let fraction =
+                            overlap_fraction(&bucket.lower, &bucket.upper, start, end)
+                                // Assume half of the bucket overlaps
+                                .unwrap_or(0.5);
+                        (bucket.num_rows as f64 * fraction).max(value_rows)
+                    }
+                }
+            })
+            .sum();
+        num_rows / self.num_rows as f64
+    }
+}
+
+/// Returns the fraction of the values between `lower` and `upper` that are
+/// between `start` and `end`, assuming uniformly distributed values, or `None`
+/// for non numeric values
+fn overlap_fraction(
+    lower: &ScalarValue,
+    upper: &ScalarValue,
+    start: &ScalarValue,
+    end: &ScalarValue,
+) -> Option<f64> {
+    if lower.data_type().is_integer() {
+        let width = lower.distance(upper)? + 1;
+        let overlap = start.distance(end)? + 1;
+        return Some(overlap as f64 / width as f64);
+    }
+    let as_f64 = |value: &ScalarValue| match value.cast_to(&DataType::Float64) {
+        Ok(ScalarValue::Float64(Some(value))) => Some(value),
+        _ => None,
+    };
+    let width = as_f64(upper)? - as_f64(lower)?;
+    let overlap = as_f64(end)? - as_f64(start)?;
+    (width > 0.0).then(|| (overlap / width).clamp(0.0, 1.0))
+}
+
+/// The most common values of a column and their number of rows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MostCommonValues {
+    /// The values and their number of rows, the most common first
+    values: Vec<(ScalarValue, usize)>,
+    /// The total number of rows of the column, including nulls
+    num_rows: usize,
+}
+
+impl MostCommonValues {
+    /// Create a list of most common values from values and their number of
+    /// rows, for a column with `num_rows` rows in total (including nulls).
+    pub fn new(mut values: Vec<(ScalarValue, usize)>, num_rows: usize) -> Self {
+        values.sort_by(|(value1, count1), (value2, count2)| {
+            count2
+                .cmp(count1)
+                .then_with(|| value1.partial_cmp(value2).unwrap_or(Ordering::Equal))
+        });
+        Self { values, num_rows }
+    }
+
+    /// Returns the values and their number of rows, the most common first
+    pub fn values(&self) -> &[(ScalarValue, usize)] {
+        &self.values
+    }
+
+    /// Returns the total number of rows of the column, including nulls
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns the fraction of the rows of the column equal to `value`, if it
+    /// is one of the most common values
+    pub fn frequency(&self, value: &ScalarValue) -> Option<f64> {
+        let (_, count) = self.values.iter().find(|(v, _)| v == value)?;
+        Some(*count as f64 / self.num_rows.max(1) as f64)
+    }
+
+    /// Returns the fraction of the rows of the column equal to one of the most
+    /// common values between `lower` and `upper` (inclusive). A null bound
+    /// means the range is unbounded on that side.
+    pub fn range_selectivity(&self, lower: &ScalarValue, upper: &ScalarValue) -> f64 {
+        let num_rows: usize = self
+            .values
+            .iter()
+            .filter(|(value, _)| {
+                (lower.is_null() || value >= lower) && (upper.is_null() || value <= upper)
+            })
+            .map(|(_, count)| count)
+            .sum();
+        num_rows as f64 / self.num_rows.max(1) as f64
+    }
 }
 
 #[cfg(test)]
@@ -961,6 +1288,8 @@ mod tests {
             min_value: Precision::Exact(ScalarValue::Int64(Some(64))),
             sum_value: Precision::Exact(ScalarValue::Int64(Some(4600))),
             distinct_count: Precision::Exact(100),
+            histogram: None,
+            most_common_values: None,
         }
     }
 
@@ -983,6 +1312,8 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(500))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(2),
@@ -990,6 +1321,8 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(10))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(1000))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
             ],
         };
@@ -1004,6 +1337,8 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(-10))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(600))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(3),
@@ -1011,6 +1346,8 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(5))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(1200))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
             ],
         };
@@ -1074,6 +1411,8 @@ mod tests {
                 min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
                 sum_value: Precision::Exact(ScalarValue::Int32(Some(500))),
                 distinct_count: Precision::Absent,
+                histogram: None,
+                most_common_values: None,
             }],
         };
 
@@ -1086,6 +1425,8 @@ mod tests {
                 min_value: Precision::Exact(ScalarValue::Int32(Some(-10))),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
+                most_common_values: None,
             }],
         };
 
@@ -1152,4 +1493,131 @@ mod tests {
         let e = Statistics::try_merge_iter(&items, &schema).unwrap_err();
         assert_contains!(e.to_string(), "Error during planning: Cannot merge statistics with different number of columns: 0 vs 1");
     }
+
+    /// 10 rows for each value between 1 and 10
+    fn uniform_values() -> Vec<(ScalarValue, usize)> {
+        (1..=10)
+            .map(|v| (ScalarValue::Int32(Some(v)), 10))
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram_equi_depth() {
+        let histogram = Histogram::try_new_equi_depth(&uniform_values(), 5, 100).unwrap();
+        let buckets = histogram
+            .buckets()
+            .iter()
+            .map(|b| {
+                (
+                    b.lower.clone(),
+                    b.upper.clone(),
+                    b.num_rows,
+                    b.distinct_count,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            (0..5)
+                .map(|i| (
+                    ScalarValue::Int32(Some(2 * i + 1)),
+                    ScalarValue::Int32(Some(2 * i + 2)),
+                    20,
+                    2
+                ))
+                .collect::<Vec<_>>()
+        );
+
+        // A frequent value fills more than one bucket
+        let mut values = uniform_values();
+        values[0].1 = 60;
+        let histogram = Histogram::try_new_equi_depth(&values, 5, 150).unwrap();
+        let rows = histogram
+            .buckets()
+            .iter()
+            .map(|b| b.num_rows)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![60, 30, 20, 20, 20]);
+
+        assert_eq!(Histogram::try_new_equi_depth(&[], 5, 0), None);
+        assert_eq!(Histogram::try_new_equi_depth(&values, 0, 150), None);
+    }
+
+    #[test]
+    fn test_histogram_range_selectivity() {
+        let histogram = Histogram::try_new_equi_depth(&uniform_values(), 5, 100).unwrap();
+        let int = |v| ScalarValue::Int32(Some(v));
+        let null = ScalarValue::Int32(None);
+
+        assert_eq!(histogram.range_selectivity(&int(3), &int(4)), 0.2);
+        assert_eq!(histogram.range_selectivity(&int(3), &int(3)), 0.1);
+        assert_eq!(histogram.range_selectivity(&int(1), &int(5)), 0.5);
+        assert_eq!(histogram.range_selectivity(&null, &int(4)), 0.4);
+        assert_eq!(histogram.range_selectivity(&int(7), &null), 0.4);
+        assert_eq!(histogram.range_selectivity(&int(11), &int(20)), 0.0);
+    }
+
+    #[test]
+    fn test_column_range_selectivity() {
+        let int = |v| ScalarValue::Int32(Some(v));
+        // 900 rows with the value 100 and 10 rows for each value between 1
+        // and 10
+        let histogram = Histogram::try_new_equi_depth(&uniform_values(), 5, 1000);
+        let most_common = MostCommonValues::new(vec![(int(100), 900)], 1000);
+        let statistics = ColumnStatistics::new_unknown()
+            .with_histogram(histogram.map(Arc::new))
+            .with_most_common_values(Some(Arc::new(most_common.clone())));
+
+        assert_eq!(
+            statistics.range_selectivity(&int(100), &int(100)),
+            Some(0.9)
+        );
+        assert_eq!(statistics.range_selectivity(&int(5), &int(5)), Some(0.01));
+        assert_eq!(statistics.range_selectivity(&int(1), &int(10)), Some(0.1));
+
+        // Only the frequency of the most common values is known without a
+        // histogram
+        let statistics = ColumnStatistics::new_unknown()
+            .with_most_common_values(Some(Arc::new(most_common)));
+        assert_eq!(
+            statistics.range_selectivity(&int(100), &int(100)),
+            Some(0.9)
+        );
+        assert_eq!(statistics.range_selectivity(&int(5), &int(5)), None);
+        assert_eq!(statistics.range_selectivity(&int(1), &int(10)), None);
+
+        assert_eq!(
+            ColumnStatistics::new_unknown().range_selectivity(&int(1), &int(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fill_absent() {
+        let statistics = Statistics {
+            num_rows: Precision::Absent,
+            total_byte_size: Precision::Exact(100),
+            column_statistics: vec![
+                ColumnStatistics::new_unknown().with_null_count(Precision::Exact(1))
+            ],
+        };
+        let table_statistics = Statistics {
+            num_rows: Precision::Exact(10),
+            total_byte_size: Precision::Exact(200),
+            column_statistics: vec![ColumnStatistics::new_unknown()
+                .with_null_count(Precision::Exact(2))
+                .with_distinct_count(Precision::Exact(5))],
+        };
+        let statistics = statistics.fill_absent(&table_statistics);
+        assert_eq!(statistics.num_rows, Precision::Inexact(10));
+        assert_eq!(statistics.total_byte_size, Precision::Exact(100));
+        assert_eq!(
+            statistics.column_statistics[0].null_count,
+            Precision::Exact(1)
+        );
+        assert_eq!(
+            statistics.column_statistics[0].distinct_count,
+            Precision::Inexact(5)
+        );
+    }
 }
@@ -799,6 +799,93 @@ pub mod datafusion_strsim {
         1.0 - (levenshtein(a, b) as f64)
             / (a.chars().count().max(b.chars().count()) as f64)
     }
+
+    /// Calculates the Jaro similarity between two strings. The similarity is a
+    /// value between 0.0 and 1.0, where 1.0 indicates that the strings are identical.
+    ///
+    /// ```
+    /// use datafusion_common::utils::datafusion_strsim::jaro;
+    ///
+    /// assert!((jaro("martha", "marhta") - 0.94444).abs() < 0.00001);
+    ///
+    /// assert_eq!(1.0, jaro("", ""));
+    ///
+    /// assert_eq!(0.0, jaro("", "second"));
+    /// ```
+    pub fn jaro(a: &str, b: &str) -> f64 {
+        let a = a.chars().collect::<Vec<_>>();
+        let b = b.chars().collect::<Vec<_>>();
+        if a.is_empty() && b.is_empty() {
+            return 1.0;
+        } else if a.is_empty() || b.is_empty() {
+            return 0.0;
+        }
+
+        // Characters only match if they are no further apart than this
+        let search_range = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+        let mut b_matched = vec![false; b.len()];
+        let mut a_matches = Vec::with_capacity(a.len());
+        for (i, a_elem) in a.iter().enumerate() {
+            let start = i.saturating_sub(search_range);
+            let end = min(b.len(), i + search_range + 1);
+            for j in start..end {
+                if !b_matched[j] && *a_elem == b[j] {
+                    b_matched[j] = true;
+                    a_matches.push(*a_elem);
+                    break;
+                }
+            }
+        }
+
+        let matches = a_matches.len();
+        if matches == 0 {
+            return 0.0;
+        }
+
+        // Matching characters that appear in a different order
+        let b_matches = b
+            .iter()
+            .zip(&b_matched)
+            .filter_map(|(b_elem, matched)| matched.then_some(b_elem));
+        let transpositions = a_matches
+            .iter()
+            .zip(b_matches)
+            .filter(|(a_elem, b_elem)| a_elem != b_elem)
+            .count()
+            / 2;
+
+        let matches = matches as f64;
+        (matches / a.len() as f64
+            + matches / b.len() as f64
+            + (matches - transpositions as f64) / matches)
+            / 3.0
+    }
+
+    /// Calculates the Jaro-Winkler similarity between two strings, which is the
+    /// [`jaro`] similarity boosted for strings sharing a common prefix of up to
+    /// four characters.
+    ///
+    /// ```
+    /// use datafusion_common::utils::datafusion_strsim::jaro_winkler;
+    ///
+    /// assert!((jaro_winkler("martha", "marhta") - 0.96111).abs() < 0.00001);
+    ///
+    /// assert!((jaro_winkler("dixon", "dicksonx") - 0.81333).abs() < 0.00001);
+    /// ```
+    pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+        let similarity = jaro(a, b);
+        if similarity <= 0.7 {
+            return similarity;
+        }
+        let prefix_length = a
+            .chars()
+            .zip(b.chars())
+            .take(4)
+            .take_while(|(a_elem, b_elem)| a_elem == b_elem)
+            .count();
+        similarity + 0.1 * prefix_length as f64 * (1.0 - similarity)
+    }
 }
 
 /// Merges collections `first` and `second`, removes duplicates and sorts the
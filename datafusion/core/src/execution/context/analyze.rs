@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray};
+use arrow::compute::{concat, sort_to_indices, SortOptions};
+use arrow::datatypes::{DataType, Int64Type};
+use datafusion_common::stats::{Histogram, MostCommonValues, Precision};
+use datafusion_common::{
+    exec_datafusion_err, ColumnStatistics, ScalarValue, Statistics, TableReference,
+};
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_execution::cache::cache_manager::TableVersion;
+use datafusion_expr::{ident, lit, AnalyzeTable};
+use datafusion_functions_aggregate::count::count;
+
+use super::{DataFrame, ExecutionPlan, Result, SessionContext, TableProvider};
+
+impl SessionContext {
+    /// Computes the statistics of the columns of a table for `ANALYZE TABLE`
+    /// and stores them in the table statistics cache of the
+    /// [`CacheManager`](datafusion_execution::cache::cache_manager::CacheManager),
+    /// where the physical planner finds them.
+    ///
+    /// The number of rows, null count, min, max and distinct count of the
+    /// columns are exact. The [`MostCommonValues`] and equi-depth
+    /// [`Histogram`] sizes are limited by
+    /// `datafusion.execution.analyze_most_common_values` and
+    /// `datafusion.execution.analyze_histogram_buckets`.
+    pub(super) async fn analyze_table(&self, cmd: AnalyzeTable) -> Result<DataFrame> {
+        let cache = self
+            .runtime_env()
+            .cache_manager
+            .get_table_statistics_cache()
+            .ok_or_else(|| {
+                exec_datafusion_err!("ANALYZE TABLE requires a table statistics cache")
+            })?;
+        let (max_buckets, max_most_common) = {
+            let state = self.state.read();
+            let options = &state.config_options().execution;
+            (
+                options.analyze_histogram_buckets,
+                options.analyze_most_common_values,
+            )
+        };
+
+        let key =
+            TableReference::from(self.state.read().resolve_table_ref(cmd.table_name));
+        let provider = self.table_provider(key.clone()).await?;
+        let schema = provider.schema();
+        let scan = provider.scan(&self.state(), None, &[], None).await?;
+        let version = table_version(&provider, &scan);
+        let mut statistics = cache
+            .get_with_extra(&key, &version)
+            .map(|statistics| statistics.as_ref().clone())
+            .unwrap_or_else(|| Statistics::new_unknown(&schema));
+
+        let mut num_rows = None;
+        for (index, field) in schema.fields().iter().enumerate() {
+            if !cmd.columns.is_empty() && !cmd.columns.contains(field.name()) {
+                continue;
+            }
+            if !is_supported_type(field.data_type()) {
+                continue;
+            }
+            let column = analyze_column(
+                Arc::clone(&provider),
+                self,
+                field.name(),
+                max_buckets,
+                max_most_common,
+            )
+            .await?;
+            num_rows = Some(column.num_rows);
+            statistics.column_statistics[index] = column.statistics;
+        }
+
+        let num_rows = match num_rows {
+            Some(num_rows) => num_rows,
+            None => self.read_table(Arc::clone(&provider))?.count().await?,
+        };
+        statistics.num_rows = Precision::Exact(num_rows);
+
+        cache.put_with_extra(&key, Arc::new(statistics), &version);
+        self.return_empty_dataframe()
+    }
+}
+
+/// Returns the [`TableVersion`] of the table of `provider`, including the
+/// files read by `scan`, a scan of the table, if it reads files
+pub(crate) fn table_version(
+    provider: &Arc<dyn TableProvider>,
+    scan: &Arc<dyn ExecutionPlan>,
+) -> TableVersion {
+    let files = scan
+        .as_any()
+        .downcast_ref::<DataSourceExec>()
+        .and_then(|exec| exec.data_source().as_any().downcast_ref::<FileScanConfig>())
+        .map(|config| {
+            config
+                .file_groups
+                .iter()
+                .flat_map(|group| group.iter())
+                .map(|file| file.object_meta.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    TableVersion::new(provider, provider.schema()).with_files(files)
+}
+
+/// Returns true if the statistics of columns of type `data_type` can be
+/// computed by `ANALYZE TABLE`
+fn is_supported_type(data_type: &DataType) -> bool {
+    data_type.is_primitive()
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+                | DataType::FixedSizeBinary(_)
+        )
+}
+
+/// The statistics of a column computed by `ANALYZE TABLE`
+struct AnalyzedColumn {
+    statistics: ColumnStatistics,
+    num_rows: usize,
+}
+
+/// Computes the statistics of a column from the number of rows of each of its
+/// distinct values
+async fn analyze_column(
+    provider: Arc<dyn TableProvider>,
+    ctx: &SessionContext,
+    name: &str,
+    max_buckets: usize,
+    max_most_common: usize,
+) -> Result<AnalyzedColumn> {
+    let batches = ctx
+        .read_table(provider)?
+        .aggregate(vec![ident(name)], vec![count(lit(1)).alias("count")])?
+        .collect()
+        .await?;
+
+    if batches.is_empty() {
+        return Ok(AnalyzedColumn {
+            statistics: ColumnStatistics::new_unknown()
+                .with_null_count(Precision::Exact(0))
+                .with_distinct_count(Precision::Exact(0)),
+            num_rows: 0,
+        });
+    }
+    let values = concat(
+        &batches
+            .iter()
+            .map(|batch| batch.column(0).as_ref())
+            .collect::<Vec<_>>(),
+    )?;
+    let counts = concat(
+        &batches
+            .iter()
+            .map(|batch| batch.column(1).as_ref())
+            .collect::<Vec<_>>(),
+    )?;
+    let counts = counts.as_primitive::<Int64Type>();
+
+    // The distinct non null values in ascending order and their number of rows
+    let indices = sort_to_indices(&values, Some(SortOptions::default()), None)?;
+    let mut null_count = 0;
+    let mut distinct_values = Vec::with_capacity(values.len());
+    for index in indices.values() {
+        let index = *index as usize;
+        let count = counts.value(index) as usize;
+        if values.is_null(index) {
+            null_count += count;
+        } else {
+            distinct_values.push((ScalarValue::try_from_array(&values, index)?, count));
+        }
+    }
+    let num_rows = null_count + distinct_values.iter().map(|(_, c)| c).sum::<usize>();
+
+    let mut statistics = ColumnStatistics::new_unknown()
+        .with_null_count(Precision::Exact(null_count))
+        .with_distinct_count(Precision::Exact(distinct_values.len()));
+    if let (Some((min, _)), Some((max, _))) =
+        (distinct_values.first(), distinct_values.last())
+    {
+        statistics = statistics
+            .with_min_value(Precision::Exact(min.clone()))
+            .with_max_value(Precision::Exact(max.clone()));
+    }
+
+    // The values that are more frequent than the average are the most common
+    // values, unless all the values fit in the list
+    let most_common = if max_most_common == 0 || distinct_values.is_empty() {
+        vec![]
+    } else if distinct_values.len() <= max_most_common {
+        distinct_values.clone()
+    } else {
+        let average = (num_rows - null_count) / distinct_values.len();
+        let mut candidates = distinct_values
+            .iter()
+            .filter(|(_, count)| *count > average)
+            .cloned()
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(_, count1), (_, count2)| count2.cmp(count1));
+        candidates.truncate(max_most_common);
+        candidates
+    };
+    // The histogram describes the other values
+    let remaining = distinct_values
+        .into_iter()
+        .filter(|(value, _)| !most_common.iter().any(|(v, _)| v == value))
+        .collect::<Vec<_>>();
+
+    let histogram = if remaining.is_empty() && !most_common.is_empty() {
+        // An empty histogram records that there are no other values
+        Some(Histogram::new(vec![], num_rows))
+    } else {
+        Histogram::try_new_equi_depth(&remaining, max_buckets, num_rows)
+    };
+    if !most_common.is_empty() {
+        statistics = statistics.with_most_common_values(Some(Arc::new(
+            MostCommonValues::new(most_common, num_rows),
+        )));
+    }
+    statistics = statistics.with_histogram(histogram.map(Arc::new));
+
+    Ok(AnalyzedColumn {
+        statistics,
+        num_rows,
+    })
+}
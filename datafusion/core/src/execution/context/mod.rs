@@ -71,6 +71,7 @@ use datafusion_common::{
     DFSchema, ParamValues, ScalarValue, SchemaReference, TableReference,
 };
 pub use datafusion_execution::config::SessionConfig;
+use datafusion_execution::progress::QueryProgress;
 use datafusion_execution::registry::SerializerRegistry;
 pub use datafusion_execution::TaskContext;
 pub use datafusion_expr::execution_props::ExecutionProps;
@@ -81,6 +82,7 @@ use datafusion_expr::{
     Expr, UserDefinedLogicalNode, WindowUDF,
 };
 use datafusion_optimizer::analyzer::type_coercion::TypeCoercion;
+use datafusion_optimizer::materialized_view_rewrite::RewriteCandidate;
 use datafusion_optimizer::Analyzer;
 use datafusion_optimizer::{AnalyzerRule, OptimizerRule};
 use datafusion_session::SessionStore;
@@ -91,6 +93,7 @@ use object_store::ObjectStore;
 use parking_lot::RwLock;
 use url::Url;
 
+pub(crate) mod analyze;
 mod csv;
 mod json;
 #[cfg(feature = "parquet")]
@@ -524,6 +527,40 @@ impl SessionContext {
         Arc::clone(self.state.read().runtime_env())
     }
 
+    /// Returns the progress of the queries currently executed by this
+    /// `SessionContext`, in the order they started
+    ///
+    /// A query is reported from the moment its execution starts, e.g. with
+    /// [`DataFrame::execute_stream`] or [`DataFrame::collect`], until its
+    /// output is dropped. The progress can be read at any moment, e.g. from
+    /// another task, to report the output rows, spills and memory of each
+    /// operator of the query as it executes.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let mut stream = ctx.sql("SELECT * FROM generate_series(1, 10000)").await?
+    ///     .execute_stream().await?;
+    /// stream.next().await;
+    ///
+    /// let progress = ctx.progress();
+    /// assert_eq!(progress.len(), 1);
+    /// assert!(progress[0].output_rows().unwrap() > 0);
+    ///
+    /// // the query is no longer reported once its output is dropped
+    /// drop(stream);
+    /// assert!(ctx.progress().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn progress(&self) -> Vec<QueryProgress> {
+        self.state.read().progress_registry().progress()
+    }
+
     /// Returns an id that uniquely identifies this `SessionContext`.
     pub fn session_id(&self) -> String {
         self.session_id.clone()
@@ -728,6 +765,9 @@ impl SessionContext {
             LogicalPlan::Statement(Statement::Execute(execute)) => {
                 self.execute_prepared(execute)
             }
+            LogicalPlan::Statement(Statement::AnalyzeTable(cmd)) => {
+                Box::pin(self.analyze_table(cmd)).await
+            }
             LogicalPlan::Statement(Statement::Deallocate(deallocate)) => {
                 self.state
                     .write()
@@ -1067,6 +1107,17 @@ impl SessionContext {
                     .with_runtime_env(Arc::new(builder.build()?))
                     .build();
             }
+            "query_result_cache_limit" => {
+                let limit = Self::parse_memory_limit(value)?;
+
+                let mut state = self.state.write();
+                let mut builder =
+                    RuntimeEnvBuilder::from_runtime_env(state.runtime_env());
+                builder = builder.with_query_result_cache_limit(limit);
+                *state = SessionStateBuilder::from(state.clone())
+                    .with_runtime_env(Arc::new(builder.build()?))
+                    .build();
+            }
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "Unknown runtime configuration: {variable}"
@@ -1562,6 +1613,74 @@ impl SessionContext {
             .deregister_table(&table)
     }
 
+    /// Registers a table storing the result of `definition` as a
+    /// [`RewriteCandidate`], that the optimizer may scan instead of computing
+    /// the parts of queries it contains.
+    ///
+    /// The table is not registered in the catalog, and keeping it up to date
+    /// with its definition is the responsibility of the caller.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::datasource::MemTable;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// ctx.sql("CREATE TABLE sales(region VARCHAR, amount INT) AS VALUES ('a', 1), ('a', 2), ('b', 3)")
+    ///     .await?
+    ///     .collect()
+    ///     .await?;
+    /// // Store the total amount of each region in a summary table
+    /// let definition = ctx
+    ///     .sql("SELECT region, sum(amount) AS total FROM sales GROUP BY region")
+    ///     .await?;
+    /// let schema = Arc::new(definition.schema().as_arrow().clone());
+    /// let totals = definition.logical_plan().clone();
+    /// let batches = ctx.execute_logical_plan(totals.clone()).await?.collect().await?;
+    /// let table = MemTable::try_new(schema, vec![batches])?;
+    /// ctx.register_rewrite_candidate("sales_by_region", totals, Arc::new(table))?;
+    ///
+    /// // The total of all the regions is computed from the summary table
+    /// let df = ctx.sql("SELECT sum(amount) FROM sales").await?;
+    /// let plan = df.into_optimized_plan()?;
+    /// assert!(plan.display_indent().to_string().contains("TableScan: sales_by_region"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`RewriteCandidate`]: datafusion_optimizer::materialized_view_rewrite::RewriteCandidate
+    pub fn register_rewrite_candidate(
+        &self,
+        name: impl Into<TableReference>,
+        definition: LogicalPlan,
+        provider: Arc<dyn TableProvider>,
+    ) -> Result<()> {
+        let mut state = self.state.write();
+        // The candidates are matched against analyzed plans
+        let definition = state.analyzer().execute_and_check(
+            definition,
+            state.config_options(),
+            |_, _| {},
+        )?;
+        let candidate =
+            RewriteCandidate::try_new(name, definition, provider_as_source(provider))?;
+        state.register_rewrite_candidate(Arc::new(candidate));
+        Ok(())
+    }
+
+    /// Deregisters the rewrite candidate with the given name.
+    ///
+    /// Returns `true` if it was registered
+    pub fn deregister_rewrite_candidate(&self, name: impl Into<TableReference>) -> bool {
+        self.state
+            .write()
+            .deregister_rewrite_candidate(&name.into())
+            .is_some()
+    }
+
     /// Return `true` if the specified table exists in the schema provider.
     pub fn table_exist(&self, table_ref: impl Into<TableReference>) -> Result<bool> {
         let table_ref: TableReference = table_ref.into();
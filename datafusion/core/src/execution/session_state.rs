@@ -26,7 +26,7 @@ use std::sync::Arc;
 use crate::catalog::{CatalogProviderList, SchemaProvider, TableProviderFactory};
 use crate::datasource::cte_worktable::CteWorkTable;
 use crate::datasource::file_format::{format_as_file_type, FileFormatFactory};
-use crate::datasource::provider_as_source;
+use crate::datasource::{provider_as_source, DefaultTableSource, TableProvider};
 use crate::execution::context::{EmptySerializerRegistry, FunctionFactory, QueryPlanner};
 use crate::execution::SessionStateDefaults;
 use crate::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
@@ -46,7 +46,9 @@ use datafusion_common::{
     config_err, exec_err, not_impl_err, plan_datafusion_err, DFSchema, DataFusionError,
     ResolvedTableReference, TableReference,
 };
+use datafusion_execution::cache::cache_manager::TableVersion;
 use datafusion_execution::config::SessionConfig;
+use datafusion_execution::progress::ProgressRegistry;
 use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_execution::TaskContext;
 use datafusion_expr::execution_props::ExecutionProps;
@@ -59,6 +61,7 @@ use datafusion_expr::{
     AggregateUDF, Explain, Expr, ExprSchemable, LogicalPlan, ScalarUDF, TableSource,
     WindowUDF,
 };
+use datafusion_optimizer::materialized_view_rewrite::RewriteCandidate;
 use datafusion_optimizer::simplify_expressions::ExprSimplifier;
 use datafusion_optimizer::{
     Analyzer, AnalyzerRule, Optimizer, OptimizerConfig, OptimizerRule,
@@ -79,6 +82,8 @@ use log::{debug, info};
 use object_store::ObjectStore;
 use sqlparser::ast::{Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias};
 use sqlparser::dialect::dialect_from_str;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
 use url::Url;
 use uuid::Uuid;
 
@@ -176,9 +181,15 @@ pub struct SessionState {
     /// It will be invoked on `CREATE FUNCTION` statements.
     /// thus, changing dialect o PostgreSql is required
     function_factory: Option<Arc<dyn FunctionFactory>>,
+    /// Tables storing the results of plans, that the optimizer may scan
+    /// instead of computing the parts of queries they contain
+    rewrite_candidates: Vec<Arc<RewriteCandidate>>,
     /// Cache logical plans of prepared statements for later execution.
     /// Key is the prepared statement name.
     prepared_plans: HashMap<String, Arc<PreparedPlan>>,
+    /// Running queries of the session, shared by the [`TaskContext`]s
+    /// created from the state
+    progress_registry: Arc<ProgressRegistry>,
 }
 
 impl Debug for SessionState {
@@ -206,7 +217,9 @@ impl Debug for SessionState {
             .field("scalar_functions", &self.scalar_functions)
             .field("aggregate_functions", &self.aggregate_functions)
             .field("window_functions", &self.window_functions)
+            .field("rewrite_candidates", &self.rewrite_candidates)
             .field("prepared_plans", &self.prepared_plans)
+            .field("progress_registry", &self.progress_registry)
             .finish()
     }
 }
@@ -353,6 +366,32 @@ impl SessionState {
         self.function_factory.as_ref()
     }
 
+    /// Registers a [`RewriteCandidate`], replacing any candidate with the same
+    /// name
+    pub fn register_rewrite_candidate(&mut self, candidate: Arc<RewriteCandidate>) {
+        self.rewrite_candidates
+            .retain(|existing| existing.name() != candidate.name());
+        self.rewrite_candidates.push(candidate);
+    }
+
+    /// Deregisters the [`RewriteCandidate`] with the given name, returning it
+    /// if it was registered
+    pub fn deregister_rewrite_candidate(
+        &mut self,
+        name: &TableReference,
+    ) -> Option<Arc<RewriteCandidate>> {
+        let index = self
+            .rewrite_candidates
+            .iter()
+            .position(|candidate| candidate.name() == name)?;
+        Some(self.rewrite_candidates.remove(index))
+    }
+
+    /// Get the registered [`RewriteCandidate`]s
+    pub fn rewrite_candidates(&self) -> &[Arc<RewriteCandidate>] {
+        &self.rewrite_candidates
+    }
+
     /// Get the table factories
     pub fn table_factories(&self) -> &HashMap<String, Arc<dyn TableProviderFactory>> {
         &self.table_factories
@@ -374,6 +413,9 @@ impl SessionState {
         sql: &str,
         dialect: &str,
     ) -> datafusion_common::Result<Statement> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("parse_sql").entered();
+
         let dialect = dialect_from_str(dialect).ok_or_else(|| {
             plan_datafusion_err!(
                 "Unsupported SQL dialect: {dialect}. Available dialects: \
@@ -475,16 +517,39 @@ impl SessionState {
                 let resolved = v.key();
                 if let Ok(schema) = self.schema_for_ref(resolved.clone()) {
                     if let Some(table) = schema.table(&resolved.table).await? {
-                        v.insert(provider_as_source(table));
+                        let source = self.table_source(resolved, table);
+                        v.insert(source);
                     }
                 }
             }
         }
 
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("create_logical_plan").entered();
+
         let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
         query.statement_to_plan(statement)
     }
 
+    /// Returns the [`TableSource`] of a table, whose statistics are
+    /// supplemented with the statistics computed by `ANALYZE TABLE`
+    fn table_source(
+        &self,
+        name: &ResolvedTableReference,
+        table: Arc<dyn TableProvider>,
+    ) -> Arc<dyn TableSource> {
+        let statistics = self
+            .runtime_env
+            .cache_manager
+            .get_table_statistics_cache()
+            .and_then(|cache| {
+                // the files of the table are not known before it is scanned
+                let version = TableVersion::new(&table, table.schema());
+                cache.get_with_extra(&TableReference::from(name.clone()), &version)
+            });
+        Arc::new(DefaultTableSource::new(table).with_statistics(statistics))
+    }
+
     fn get_parser_options(&self) -> ParserOptions {
         let sql_parser_options = &self.config.options().sql_parser;
 
@@ -654,9 +719,18 @@ impl SessionState {
         logical_plan: &LogicalPlan,
     ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
         let logical_plan = self.optimize(logical_plan)?;
-        self.query_planner
-            .create_physical_plan(&logical_plan, self)
-            .await
+        let planning = self.query_planner.create_physical_plan(&logical_plan, self);
+
+        #[cfg(feature = "tracing")]
+        let planning = planning.instrument(tracing::info_span!("create_physical_plan"));
+
+        let plan = planning.await?;
+
+        // record a span for each executed partition of each operator
+        #[cfg(feature = "tracing")]
+        let plan = datafusion_physical_plan::instrumented::instrument_plan(plan)?;
+
+        Ok(plan)
     }
 
     /// Create a [`PhysicalExpr`] from an [`Expr`] after applying type
@@ -703,6 +777,11 @@ impl SessionState {
         &self.runtime_env
     }
 
+    /// Return the [`ProgressRegistry`] of the running queries of the session
+    pub fn progress_registry(&self) -> &Arc<ProgressRegistry> {
+        &self.progress_registry
+    }
+
     /// Return the execution properties
     pub fn execution_props(&self) -> &ExecutionProps {
         &self.execution_props
@@ -910,6 +989,7 @@ pub struct SessionStateBuilder {
     table_factories: Option<HashMap<String, Arc<dyn TableProviderFactory>>>,
     runtime_env: Option<Arc<RuntimeEnv>>,
     function_factory: Option<Arc<dyn FunctionFactory>>,
+    rewrite_candidates: Option<Vec<Arc<RewriteCandidate>>>,
     // fields to support convenience functions
     analyzer_rules: Option<Vec<Arc<dyn AnalyzerRule + Send + Sync>>>,
     optimizer_rules: Option<Vec<Arc<dyn OptimizerRule + Send + Sync>>>,
@@ -946,6 +1026,7 @@ impl SessionStateBuilder {
             table_factories: None,
             runtime_env: None,
             function_factory: None,
+            rewrite_candidates: None,
             // fields to support convenience functions
             analyzer_rules: None,
             optimizer_rules: None,
@@ -997,6 +1078,7 @@ impl SessionStateBuilder {
             table_factories: Some(existing.table_factories),
             runtime_env: Some(existing.runtime_env),
             function_factory: existing.function_factory,
+            rewrite_candidates: Some(existing.rewrite_candidates),
 
             // fields to support convenience functions
             analyzer_rules: None,
@@ -1285,6 +1367,15 @@ impl SessionStateBuilder {
         self
     }
 
+    /// Set the [`RewriteCandidate`]s the optimizer may answer queries from
+    pub fn with_rewrite_candidates(
+        mut self,
+        rewrite_candidates: Vec<Arc<RewriteCandidate>>,
+    ) -> Self {
+        self.rewrite_candidates = Some(rewrite_candidates);
+        self
+    }
+
     /// Register an `ObjectStore` to the [`RuntimeEnv`]. See [`RuntimeEnv::register_object_store`]
     /// for more details.
     ///
@@ -1347,6 +1438,7 @@ impl SessionStateBuilder {
             table_factories,
             runtime_env,
             function_factory,
+            rewrite_candidates,
             analyzer_rules,
             optimizer_rules,
             physical_optimizer_rules,
@@ -1382,7 +1474,9 @@ impl SessionStateBuilder {
             table_factories: table_factories.unwrap_or_default(),
             runtime_env,
             function_factory,
+            rewrite_candidates: rewrite_candidates.unwrap_or_default(),
             prepared_plans: HashMap::new(),
+            progress_registry: Arc::new(ProgressRegistry::new()),
         };
 
         if let Some(file_formats) = file_formats {
@@ -1565,6 +1659,11 @@ impl SessionStateBuilder {
         &mut self.function_factory
     }
 
+    /// Returns the current rewrite_candidates value
+    pub fn rewrite_candidates(&mut self) -> &mut Option<Vec<Arc<RewriteCandidate>>> {
+        &mut self.rewrite_candidates
+    }
+
     /// Returns the current analyzer_rules value
     pub fn analyzer_rules(
         &mut self,
@@ -1615,6 +1714,7 @@ impl Debug for SessionStateBuilder {
             .field("scalar_functions", &self.scalar_functions)
             .field("aggregate_functions", &self.aggregate_functions)
             .field("window_functions", &self.window_functions)
+            .field("rewrite_candidates", &self.rewrite_candidates)
             .finish()
     }
 }
@@ -1894,6 +1994,10 @@ impl OptimizerConfig for SessionState {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         Some(self)
     }
+
+    fn rewrite_candidates(&self) -> &[Arc<RewriteCandidate>] {
+        &self.rewrite_candidates
+    }
 }
 
 /// Create a new task context instance from SessionState
@@ -1909,6 +2013,7 @@ impl From<&SessionState> for TaskContext {
             state.window_functions.clone(),
             Arc::clone(&state.runtime_env),
         )
+        .with_progress_registry(Arc::clone(&state.progress_registry))
     }
 }
 
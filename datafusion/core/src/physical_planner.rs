@@ -26,6 +26,7 @@ use crate::datasource::listing::ListingTableUrl;
 use crate::datasource::physical_plan::FileSinkConfig;
 use crate::datasource::{source_as_provider, DefaultTableSource};
 use crate::error::{DataFusionError, Result};
+use crate::execution::context::analyze::table_version;
 use crate::execution::context::{ExecutionProps, SessionState};
 use crate::logical_expr::utils::generate_sort_key;
 use crate::logical_expr::{
@@ -70,6 +71,7 @@ use datafusion_common::{
 };
 use datafusion_datasource::file_groups::FileGroup;
 use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::source::DataSourceExec;
 use datafusion_expr::dml::{CopyTo, InsertOp};
 use datafusion_expr::expr::{
     physical_name, AggregateFunction, AggregateFunctionParams, Alias, GroupingSet,
@@ -79,9 +81,10 @@ use datafusion_expr::expr_rewriter::unnormalize_cols;
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
 use datafusion_expr::{
     Analyze, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension, FetchType,
-    Filter, JoinType, RecursiveQuery, SkipType, StringifiedPlan, WindowFrame,
-    WindowFrameBound, WriteOp,
+    Filter, JoinType, RecursiveQuery, SharedSubplan, SkipType, StringifiedPlan,
+    WindowFrame, WindowFrameBound, WriteOp,
 };
+use datafusion_optimizer::cardinality::CardinalityEstimator;
 use datafusion_physical_expr::aggregate::{AggregateExprBuilder, AggregateFunctionExpr};
 use datafusion_physical_expr::expressions::{Column, Literal};
 use datafusion_physical_expr::{
@@ -92,6 +95,7 @@ use datafusion_physical_plan::empty::EmptyExec;
 use datafusion_physical_plan::execution_plan::InvariantLevel;
 use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
 use datafusion_physical_plan::recursive_query::RecursiveQueryExec;
+use datafusion_physical_plan::shared_subplan::{link_shared_subplans, SharedSubplanExec};
 use datafusion_physical_plan::unnest::ListUnnest;
 use datafusion_sql::TableReference;
 use sqlparser::ast::NullTreatment;
@@ -448,6 +452,7 @@ impl DefaultPhysicalPlanner {
         let exec_node: Arc<dyn ExecutionPlan> = match node {
             // Leaves (no children)
             LogicalPlan::TableScan(TableScan {
+                table_name,
                 source,
                 projection,
                 filters,
@@ -459,9 +464,30 @@ impl DefaultPhysicalPlanner {
                 // doesn't know (nor should care) how the relation was
                 // referred to in the query
                 let filters = unnormalize_cols(filters.iter().cloned());
-                source
+                let plan = source
                     .scan(session_state, projection.as_ref(), &filters, *fetch)
-                    .await?
+                    .await?;
+                // Use the statistics computed by `ANALYZE TABLE`, if any, as
+                // long as the table and the files it reads are unchanged
+                let table_statistics = session_state
+                    .runtime_env()
+                    .cache_manager
+                    .get_table_statistics_cache()
+                    .and_then(|cache| {
+                        let key = TableReference::from(
+                            session_state.resolve_table_ref(table_name.clone()),
+                        );
+                        cache.get_with_extra(&key, &table_version(&source, &plan))
+                    });
+                match (
+                    table_statistics,
+                    plan.as_any().downcast_ref::<DataSourceExec>(),
+                ) {
+                    (Some(statistics), Some(exec)) => exec
+                        .with_table_statistics(&statistics)
+                        .map_or(plan, |exec| Arc::new(exec) as _),
+                    _ => plan,
+                }
             }
             LogicalPlan::Values(Values { values, schema }) => {
                 let exec_schema = schema.as_ref().to_owned().into();
@@ -884,6 +910,9 @@ impl DefaultPhysicalPlanner {
             }
             LogicalPlan::Subquery(_) => todo!(),
             LogicalPlan::SubqueryAlias(_) => children.one()?,
+            LogicalPlan::SharedSubplan(SharedSubplan { id, .. }) => {
+                Arc::new(SharedSubplanExec::new(*id, children.one()?))
+            }
             LogicalPlan::Limit(limit) => {
                 let input = children.one()?;
                 let SkipType::Literal(skip) = limit.get_skip_type()? else {
@@ -1880,7 +1909,17 @@ impl DefaultPhysicalPlanner {
         if !config.physical_plan_only {
             stringified_plans.clone_from(&e.stringified_plans);
             if e.logical_optimization_succeeded {
-                stringified_plans.push(e.plan.to_stringified(FinalLogicalPlan));
+                if config.show_statistics {
+                    // Include the estimated number of rows of the logical plan
+                    let estimator =
+                        CardinalityEstimator::new(session_state.config_options());
+                    stringified_plans.push(StringifiedPlan::new(
+                        FinalLogicalPlan,
+                        estimator.display_indent(&e.plan).to_string(),
+                    ));
+                } else {
+                    stringified_plans.push(e.plan.to_stringified(FinalLogicalPlan));
+                }
             }
         }
 
@@ -2043,6 +2082,11 @@ impl DefaultPhysicalPlanner {
 
         let mut new_plan = Arc::clone(&plan);
         for optimizer in optimizers {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::info_span!("physical_optimizer_rule", rule = optimizer.name())
+                    .entered();
+
             let before_schema = new_plan.schema();
             new_plan = optimizer
                 .optimize(new_plan, session_state.config_options())
@@ -2066,6 +2110,10 @@ impl DefaultPhysicalPlanner {
         // to verify that the plan is executable.
         InvariantChecker(InvariantLevel::Executable).check(&new_plan)?;
 
+        // The references to a shared subplan are linked once they are no
+        // longer rewritten
+        let new_plan = link_shared_subplans(new_plan)?;
+
         debug!(
             "Optimized physical plan:\n{}\n",
             displayable(new_plan.as_ref()).indent(false)
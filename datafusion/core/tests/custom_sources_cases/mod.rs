@@ -196,15 +196,21 @@ impl ExecutionPlan for CustomExecutionPlan {
                 .clone()
                 .unwrap_or_else(|| (0..batch.columns().len()).collect())
                 .iter()
-                .map(|i| ColumnStatistics {
-                    null_count: Precision::Exact(batch.column(*i).null_count()),
-                    min_value: Precision::Exact(ScalarValue::Int32(aggregate::min(
-                        as_primitive_array::<Int32Type>(batch.column(*i)).unwrap(),
-                    ))),
-                    max_value: Precision::Exact(ScalarValue::Int32(aggregate::max(
-                        as_primitive_array::<Int32Type>(batch.column(*i)).unwrap(),
-                    ))),
-                    ..Default::default()
+                .map(|i| {
+                    ColumnStatistics::new_unknown()
+                        .with_null_count(Precision::Exact(batch.column(*i).null_count()))
+                        .with_min_value(Precision::Exact(ScalarValue::Int32(
+                            aggregate::min(
+                                as_primitive_array::<Int32Type>(batch.column(*i))
+                                    .unwrap(),
+                            ),
+                        )))
+                        .with_max_value(Precision::Exact(ScalarValue::Int32(
+                            aggregate::max(
+                                as_primitive_array::<Int32Type>(batch.column(*i))
+                                    .unwrap(),
+                            ),
+                        )))
                 })
                 .collect(),
         })
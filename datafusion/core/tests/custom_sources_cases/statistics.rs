@@ -208,20 +208,18 @@ fn fully_defined() -> (Statistics, Schema) {
             num_rows: Precision::Exact(13),
             total_byte_size: Precision::Absent, // ignore byte size for now
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(2),
-                    max_value: Precision::Exact(ScalarValue::Int32(Some(1023))),
-                    min_value: Precision::Exact(ScalarValue::Int32(Some(-24))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(10))),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(13),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(5486))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-6783))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(10))),
-                    null_count: Precision::Exact(5),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(2))
+                    .with_max_value(Precision::Exact(ScalarValue::Int32(Some(1023))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int32(Some(-24))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(10))))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(13))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(5486))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-6783))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(10))))
+                    .with_null_count(Precision::Exact(5)),
             ],
         },
         Schema::new(vec![
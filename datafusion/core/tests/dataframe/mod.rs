@@ -571,8 +571,7 @@ async fn test_aggregate_with_pk() -> Result<()> {
     assert_snapshot!(
         physical_plan_to_string(&df).await,
         @r###"
-    AggregateExec: mode=Single, gby=[id@0 as id, name@1 as name], aggr=[]
-      DataSourceExec: partitions=1, partition_sizes=[1]
+    DataSourceExec: partitions=1, partition_sizes=[1]
     "###
     );
 
@@ -614,8 +613,7 @@ async fn test_aggregate_with_pk2() -> Result<()> {
         @r###"
     CoalesceBatchesExec: target_batch_size=8192
       FilterExec: id@0 = 1 AND name@1 = a
-        AggregateExec: mode=Single, gby=[id@0 as id, name@1 as name], aggr=[]
-          DataSourceExec: partitions=1, partition_sizes=[1]
+        DataSourceExec: partitions=1, partition_sizes=[1]
     "###
     );
 
@@ -663,8 +661,7 @@ async fn test_aggregate_with_pk3() -> Result<()> {
         @r###"
     CoalesceBatchesExec: target_batch_size=8192
       FilterExec: id@0 = 1
-        AggregateExec: mode=Single, gby=[id@0 as id, name@1 as name], aggr=[]
-          DataSourceExec: partitions=1, partition_sizes=[1]
+        DataSourceExec: partitions=1, partition_sizes=[1]
     "###
     );
 
@@ -707,15 +704,14 @@ async fn test_aggregate_with_pk4() -> Result<()> {
     // id
     let df = df.select(vec![col("id")])?;
 
-    // In this case aggregate shouldn't be expanded, since these
-    // columns are not used.
+    // The aggregate groups by the primary key without computing any
+    // aggregates, so it is removed.
     assert_snapshot!(
         physical_plan_to_string(&df).await,
         @r###"
     CoalesceBatchesExec: target_batch_size=8192
       FilterExec: id@0 = 1
-        AggregateExec: mode=Single, gby=[id@0 as id], aggr=[]
-          DataSourceExec: partitions=1, partition_sizes=[1]
+        DataSourceExec: partitions=1, partition_sizes=[1]
     "###
     );
 
@@ -20,6 +20,7 @@
 use std::any::Any;
 use std::num::NonZeroUsize;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 #[cfg(feature = "extended_tests")]
 mod memory_limit_validation;
@@ -40,12 +41,14 @@ use datafusion::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
 use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion_catalog::streaming::StreamingTable;
 use datafusion_catalog::Session;
-use datafusion_common::{assert_contains, Result};
+use datafusion_common::{assert_contains, assert_not_contains, Result};
 use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
 use datafusion_execution::memory_pool::{
-    FairSpillPool, GreedyMemoryPool, MemoryPool, TrackConsumersPool,
+    FairSpillPool, GreedyMemoryPool, HierarchicalMemoryPool, MemoryPool,
+    OvercommitPolicy, TrackConsumersPool,
 };
 use datafusion_execution::runtime_env::RuntimeEnv;
+use datafusion_execution::spill_storage::ObjectStoreSpillStorage;
 use datafusion_execution::TaskContext;
 use datafusion_expr::{Expr, TableType};
 use datafusion_physical_expr::{LexOrdering, PhysicalSortExpr};
@@ -59,6 +62,8 @@ use test_utils::AccessLogGenerator;
 
 use async_trait::async_trait;
 use futures::StreamExt;
+use object_store::memory::InMemory;
+use object_store::ObjectStore;
 use tokio::fs::File;
 
 #[cfg(test)]
@@ -706,6 +711,177 @@ async fn test_spill_file_compressed_with_lz4_frame() -> Result<()> {
 
     Ok(())
 }
+
+/// If the spilled bytes of a query exceed `max_spill_size_per_query`, the
+/// query should fail even though the disk manager limit is not reached
+#[tokio::test]
+async fn test_disk_spill_limit_per_query_reached() -> Result<()> {
+    let spill_compression = SpillCompression::Uncompressed;
+    let ctx = setup_context(1024 * 1024 * 1024, 1024 * 1024, spill_compression).await?; // 1GB disk limit, 1MB memory limit
+    ctx.sql("SET datafusion.execution.max_spill_size_per_query = 1048576")
+        .await?;
+
+    let df = ctx
+        .sql("select * from generate_series(1, 1000000000000) as t1(v1) order by v1")
+        .await
+        .unwrap();
+
+    let err = df.collect().await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "The used disk space during the spilling process of the query has exceeded the allowable limit of 1024.0 KB"
+    );
+    assert_eq!(ctx.runtime_env().disk_manager.used_disk_space(), 0);
+
+    Ok(())
+}
+
+/// External query should succeed when spilling to an object store, and the
+/// spilled objects are deleted after execution
+#[tokio::test]
+async fn test_spill_to_object_store() -> Result<()> {
+    let store = Arc::new(InMemory::new());
+    let spill_storage =
+        ObjectStoreSpillStorage::new(Arc::clone(&store) as _, "spill".into())
+            .with_chunk_size(16 * 1024);
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(Arc::new(FairSpillPool::new(128 * 1024)))
+        .with_disk_manager_builder(
+            DiskManagerBuilder::default()
+                .with_mode(DiskManagerMode::SpillStorage(Arc::new(spill_storage))),
+        )
+        .build_arc()?;
+    let config = SessionConfig::new()
+        .with_sort_spill_reservation_bytes(64 * 1024)
+        .with_sort_in_place_threshold_bytes(0)
+        .with_batch_size(64)
+        .with_target_partitions(1);
+    let ctx = SessionContext::new_with_config_rt(config, runtime);
+
+    let df = ctx
+        .sql("select * from generate_series(1, 100000) as t1(v1) order by v1 desc")
+        .await?;
+    let plan = df.create_physical_plan().await?;
+    let batches = collect_batches(Arc::clone(&plan), ctx.task_ctx()).await?;
+    let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+    assert_eq!(num_rows, 100000);
+    assert_batches_eq!(
+        [
+            "+--------+",
+            "| v1     |",
+            "+--------+",
+            "| 100000 |",
+            "+--------+"
+        ],
+        &[batches[0].slice(0, 1)]
+    );
+
+    let metrics = plan.metrics().unwrap();
+    assert!(metrics.spill_count().unwrap() > 0);
+    assert_eq!(
+        metrics.sum_by_name("spill_read_bytes").unwrap().as_usize(),
+        metrics.spilled_bytes().unwrap()
+    );
+    assert_eq!(ctx.runtime_env().disk_manager.used_disk_space(), 0);
+    // the objects are deleted in the background
+    for _ in 0..100 {
+        if store.list(None).count().await == 0 {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the spilled objects were not deleted");
+}
+
+/// A query is cancelled when a query with a higher priority needs the memory
+/// it uses in a [`HierarchicalMemoryPool`]
+#[tokio::test]
+async fn test_cancel_lower_priority_query() -> Result<()> {
+    let (first, second) = run_competing_sorts(-1, 1).await?;
+    assert_eq!(second?, 1000000);
+
+    // The low priority query is stopped
+    let err = first.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "with priority -1 was cancelled to free memory for memory pool"
+    );
+    Ok(())
+}
+
+/// A query is not cancelled for a query with the same priority, that fails
+/// instead
+#[tokio::test]
+async fn test_same_priority_query_not_cancelled() -> Result<()> {
+    let (first, second) = run_competing_sorts(1, 1).await?;
+    let err = second.unwrap_err();
+    assert_contains!(err.to_string(), "Resources exhausted");
+    assert_not_contains!(err.to_string(), "was cancelled");
+
+    // The first query keeps its memory and completes
+    assert_eq!(first?, 1000000);
+    Ok(())
+}
+
+/// Runs a sort with `second_priority` while a sort with `first_priority`,
+/// that holds its input in memory, is running in the same
+/// [`HierarchicalMemoryPool`] that cancels the lower priority queries.
+///
+/// Returns the number of rows, or the error, of the first and of the second
+/// query.
+async fn run_competing_sorts(
+    first_priority: i32,
+    second_priority: i32,
+) -> Result<(Result<usize>, Result<usize>)> {
+    let pool = HierarchicalMemoryPool::new_with_policy(
+        16 * 1024 * 1024,
+        OvercommitPolicy::CancelLowerPriority,
+    );
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(Arc::new(pool))
+        .with_disk_manager_builder(
+            DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+        )
+        .build_arc()?;
+    let config = SessionConfig::new()
+        .with_target_partitions(1)
+        .with_sort_spill_reservation_bytes(0);
+    let ctx = SessionContext::new_with_config_rt(config, runtime);
+    let sql = "select * from generate_series(1, 1000000) as t(v) order by v desc";
+
+    // The sort of the first query holds its input until it is dropped
+    ctx.sql(&format!(
+        "SET datafusion.execution.query_priority = {first_priority}"
+    ))
+    .await?
+    .collect()
+    .await?;
+    let mut first = ctx.sql(sql).await?.execute_stream().await?;
+    let mut first_rows = first.next().await.unwrap()?.num_rows();
+
+    ctx.sql(&format!(
+        "SET datafusion.execution.query_priority = {second_priority}"
+    ))
+    .await?
+    .collect()
+    .await?;
+    let second = ctx
+        .sql(sql)
+        .await?
+        .collect()
+        .await
+        .map(|batches| batches.iter().map(|b| b.num_rows()).sum());
+
+    let first = loop {
+        match first.next().await {
+            Some(Ok(batch)) => first_rows += batch.num_rows(),
+            Some(Err(e)) => break Err(e),
+            None => break Ok(first_rows),
+        }
+    };
+    Ok((first, second))
+}
+
 /// Run the query with the specified memory limit,
 /// and verifies the expected errors are returned
 #[derive(Clone, Debug)]
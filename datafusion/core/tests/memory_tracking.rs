@@ -0,0 +1,901 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Compares the memory tracked by the [`MemoryReservation`]s of the TPC-H
+//! query plans with the memory they actually allocate
+//!
+//! The tests are a separate binary, as they replace the global allocator.
+//!
+//! [`MemoryReservation`]: datafusion_execution::memory_pool::MemoryReservation
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fs::{self, File};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{
+    ArrayRef, Date32Array, Decimal128Array, Int32Array, Int64Array, RecordBatch,
+    StringArray,
+};
+use arrow::datatypes::SchemaRef;
+use datafusion::execution::memory_pool::{
+    MemoryConsumer, MemoryLimit, MemoryPool, MemoryReservation, UnboundedMemoryPool,
+};
+use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::physical_plan::execute_stream;
+use datafusion::prelude::{ParquetReadOptions, SessionConfig, SessionContext};
+use datafusion_common::Result;
+use futures::StreamExt;
+use parquet::arrow::ArrowWriter;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tempfile::TempDir;
+use test_utils::tpch::tpch_schemas;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+    static PEAK_ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+/// A [`GlobalAlloc`] that counts the bytes allocated by each thread
+///
+/// Only the allocations of the current thread are counted, so the plans must
+/// be executed on a current thread runtime.
+struct TrackingAllocator;
+
+impl TrackingAllocator {
+    fn record(delta: isize) {
+        // The thread locals may already be destroyed when a thread exits
+        let _ = ALLOCATED.try_with(|allocated| {
+            let value = allocated.get() + delta;
+            allocated.set(value);
+            let _ = PEAK_ALLOCATED.try_with(|peak| peak.set(peak.get().max(value)));
+        });
+    }
+
+    /// Returns the bytes currently allocated by this thread, and resets the
+    /// peak to this value
+    fn reset_peak() -> isize {
+        let allocated = ALLOCATED.with(Cell::get);
+        PEAK_ALLOCATED.with(|peak| peak.set(allocated));
+        allocated
+    }
+
+    fn peak() -> isize {
+        PEAK_ALLOCATED.with(Cell::get)
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// A [`MemoryPool`] that records the peak of the memory reserved in an inner
+/// pool
+#[derive(Debug)]
+struct PeakMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    peak: AtomicUsize,
+}
+
+impl PeakMemoryPool {
+    /// Create a pool recording the peak of the memory reserved in `inner`
+    fn new(inner: Arc<dyn MemoryPool>) -> Self {
+        Self {
+            inner,
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the peak of the memory reserved
+    fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Resets the peak to the memory currently reserved
+    fn reset_peak(&self) {
+        self.peak.store(self.inner.reserved(), Ordering::Relaxed);
+    }
+
+    fn record_peak(&self) {
+        self.peak
+            .fetch_max(self.inner.reserved(), Ordering::Relaxed);
+    }
+}
+
+impl MemoryPool for PeakMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.record_peak();
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        self.inner.try_grow(reservation, additional)?;
+        self.record_peak();
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.inner.reserved()
+    }
+
+    fn memory_limit(&self) -> MemoryLimit {
+        self.inner.memory_limit()
+    }
+}
+
+/// The peak memory used while executing a plan
+#[derive(Debug, Clone, Copy)]
+struct MemoryUsage {
+    /// The peak of the memory reserved through the memory pool
+    tracked: usize,
+    /// The peak of the memory allocated by the current thread
+    allocated: usize,
+}
+
+impl MemoryUsage {
+    /// Asserts that the tracked memory is within [`TOLERANCE`] of the
+    /// allocated memory
+    fn assert_within(&self, query: &str) {
+        let difference = self.tracked.abs_diff(self.allocated);
+        let max_difference = (self.allocated as f64 * TOLERANCE) as usize;
+        assert!(
+            difference <= max_difference + STREAMED_BATCHES,
+            "{query}: tracked memory {} is not within {TOLERANCE} of allocated memory {}",
+            self.tracked,
+            self.allocated
+        );
+    }
+}
+
+/// Plans the statements of the TPC-H query `query_no` and executes them on
+/// the current thread, and returns the peak memory tracked and allocated
+/// while executing the physical plan of each `SELECT` statement
+async fn measure_memory_usage(query_no: usize) -> Result<Vec<MemoryUsage>> {
+    let pool = Arc::new(PeakMemoryPool::new(
+        Arc::new(UnboundedMemoryPool::default()),
+    ));
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(Arc::clone(&pool) as _)
+        .build_arc()?;
+    // The memory reserved up front for merging spilled sorts is not allocated
+    let config = SessionConfig::new()
+        .with_target_partitions(4)
+        .with_sort_spill_reservation_bytes(0);
+    let ctx = SessionContext::new_with_config_rt(config, runtime);
+    for def in tpch_schemas() {
+        let path = TPCH_DIR.path().join(format!("{}.parquet", def.name));
+        ctx.register_parquet(
+            &def.name,
+            path.to_str().unwrap(),
+            ParquetReadOptions::default(),
+        )
+        .await?;
+    }
+
+    let path = format!("../../benchmarks/queries/q{query_no}.sql");
+    let sql = fs::read_to_string(path).expect("Could not read query");
+    let mut usages = vec![];
+    for statement in sql.split(';').filter(|s| !s.trim().is_empty()) {
+        let df = ctx.sql(statement).await?;
+        // Q15 creates and drops a view
+        if !statement.trim().starts_with("select") {
+            df.collect().await?;
+            continue;
+        }
+        let plan = df.create_physical_plan().await?;
+
+        pool.reset_peak();
+        let baseline = TrackingAllocator::reset_peak();
+        let mut stream = execute_stream(plan, ctx.task_ctx())?;
+        while let Some(batch) = stream.next().await {
+            batch?;
+        }
+        drop(stream);
+        let allocated = TrackingAllocator::peak() - baseline;
+
+        usages.push(MemoryUsage {
+            tracked: pool.peak(),
+            allocated: allocated.max(0) as usize,
+        });
+    }
+    Ok(usages)
+}
+
+/// The scale factor of the generated TPC-H tables
+const SCALE_FACTOR: f64 = 0.1;
+
+/// A directory with a parquet file for each table of the TPC-H benchmark,
+/// generated once for all the tests
+///
+/// The tables are read from files, so that the batches of the scans are
+/// allocated while executing the plans, like in a real query.
+static TPCH_DIR: LazyLock<TempDir> = LazyLock::new(|| {
+    let dir = TempDir::new().unwrap();
+    for (name, batch) in TpchGenerator::new(SCALE_FACTOR).generate() {
+        let file = File::create(dir.path().join(format!("{name}.parquet"))).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+    dir
+});
+
+const REGIONS: [&str; 5] = ["AFRICA", "AMERICA", "ASIA", "EUROPE", "MIDDLE EAST"];
+
+const NATIONS: [(&str, i64); 25] = [
+    ("ALGERIA", 0),
+    ("ARGENTINA", 1),
+    ("BRAZIL", 1),
+    ("CANADA", 1),
+    ("EGYPT", 4),
+    ("ETHIOPIA", 0),
+    ("FRANCE", 3),
+    ("GERMANY", 3),
+    ("INDIA", 2),
+    ("INDONESIA", 2),
+    ("IRAN", 4),
+    ("IRAQ", 4),
+    ("JAPAN", 2),
+    ("JORDAN", 4),
+    ("KENYA", 0),
+    ("MOROCCO", 0),
+    ("MOZAMBIQUE", 0),
+    ("PERU", 1),
+    ("CHINA", 2),
+    ("ROMANIA", 3),
+    ("SAUDI ARABIA", 4),
+    ("VIETNAM", 2),
+    ("RUSSIA", 3),
+    ("UNITED KINGDOM", 3),
+    ("UNITED STATES", 1),
+];
+
+const COLORS: [&str; 16] = [
+    "almond",
+    "antique",
+    "blue",
+    "chocolate",
+    "cornflower",
+    "forest",
+    "green",
+    "ivory",
+    "lavender",
+    "linen",
+    "navy",
+    "olive",
+    "peach",
+    "red",
+    "thistle",
+    "yellow",
+];
+
+const TYPES: [[&str; 6]; 3] = [
+    ["STANDARD", "SMALL", "MEDIUM", "LARGE", "ECONOMY", "PROMO"],
+    [
+        "ANODIZED",
+        "BURNISHED",
+        "PLATED",
+        "POLISHED",
+        "BRUSHED",
+        "ANODIZED",
+    ],
+    ["TIN", "NICKEL", "BRASS", "STEEL", "COPPER", "BRASS"],
+];
+
+const CONTAINERS: [[&str; 5]; 2] = [
+    ["SM", "LG", "MED", "JUMBO", "WRAP"],
+    ["CASE", "BOX", "BAG", "JAR", "PKG"],
+];
+
+const SEGMENTS: [&str; 5] = [
+    "AUTOMOBILE",
+    "BUILDING",
+    "FURNITURE",
+    "MACHINERY",
+    "HOUSEHOLD",
+];
+
+const PRIORITIES: [&str; 5] =
+    ["1-URGENT", "2-HIGH", "3-MEDIUM", "4-NOT SPECIFIED", "5-LOW"];
+
+const INSTRUCTIONS: [&str; 4] = [
+    "DELIVER IN PERSON",
+    "COLLECT COD",
+    "NONE",
+    "TAKE BACK RETURN",
+];
+
+const MODES: [&str; 7] = ["REG AIR", "AIR", "RAIL", "SHIP", "TRUCK", "MAIL", "FOB"];
+
+const WORDS: [&str; 16] = [
+    "furiously",
+    "special",
+    "requests",
+    "carefully",
+    "final",
+    "deposits",
+    "ironic",
+    "packages",
+    "slyly",
+    "regular",
+    "accounts",
+    "boldly",
+    "pending",
+    "foxes",
+    "quickly",
+    "express",
+];
+
+/// The first and last order dates, 1992-01-01 and 1998-08-02, in days since
+/// the epoch
+const ORDER_DATES: (i32, i32) = (8035, 10440);
+
+/// The date after which the line items are not shipped yet, 1995-06-17, in
+/// days since the epoch
+const CURRENT_DATE: i32 = 9298;
+
+/// Generates the tables of the TPC-H benchmark, with a distribution of values
+/// close enough to the one of `dbgen` for the predicates of the queries to
+/// select rows
+struct TpchGenerator {
+    rng: StdRng,
+    suppliers: i64,
+    parts: i64,
+    customers: i64,
+    orders: i64,
+}
+
+impl TpchGenerator {
+    fn new(scale_factor: f64) -> Self {
+        let rows = |base: f64| (base * scale_factor) as i64;
+        Self {
+            rng: StdRng::seed_from_u64(42),
+            suppliers: rows(10_000.0),
+            parts: rows(200_000.0),
+            customers: rows(150_000.0),
+            orders: rows(1_500_000.0),
+        }
+    }
+
+    /// Returns the rows of each table
+    fn generate(mut self) -> Vec<(String, RecordBatch)> {
+        let tables = vec![
+            self.lineitem_and_orders(),
+            self.part(),
+            self.supplier(),
+            self.partsupp(),
+            self.customer(),
+            self.nation(),
+            self.region(),
+        ];
+        let schemas = tpch_schemas();
+        tables
+            .into_iter()
+            .flatten()
+            .map(|(name, columns)| {
+                let def = schemas.iter().find(|def| def.name == name).unwrap();
+                let schema = SchemaRef::new(def.schema.clone());
+                (name, RecordBatch::try_new(schema, columns).unwrap())
+            })
+            .collect()
+    }
+
+    fn pick<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.rng.random_range(0..values.len())]
+    }
+
+    fn comment(&mut self) -> String {
+        let words = self.rng.random_range(3..8);
+        (0..words)
+            .map(|_| self.pick(&WORDS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn phone(&mut self, nation: i64) -> String {
+        format!(
+            "{}-{}-{}-{}",
+            nation + 10,
+            self.rng.random_range(100..1000),
+            self.rng.random_range(100..1000),
+            self.rng.random_range(1000..10000)
+        )
+    }
+
+    /// Returns a decimal with a scale of 2 between `min` and `max` cents
+    fn cents(&mut self, min: i128, max: i128) -> i128 {
+        self.rng.random_range(min..=max)
+    }
+
+    fn region(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let comments = REGIONS.iter().map(|_| self.comment()).collect::<Vec<_>>();
+        vec![(
+            "region".to_string(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..REGIONS.len() as i64)),
+                Arc::new(StringArray::from_iter_values(REGIONS)),
+                Arc::new(StringArray::from_iter_values(comments)),
+            ],
+        )]
+    }
+
+    fn nation(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let comments = NATIONS.iter().map(|_| self.comment()).collect::<Vec<_>>();
+        vec![(
+            "nation".to_string(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..NATIONS.len() as i64)),
+                Arc::new(StringArray::from_iter_values(NATIONS.map(|(name, _)| name))),
+                Arc::new(Int64Array::from_iter_values(
+                    NATIONS.map(|(_, region)| region),
+                )),
+                Arc::new(StringArray::from_iter_values(comments)),
+            ],
+        )]
+    }
+
+    fn supplier(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let keys = 1..=self.suppliers;
+        let nations = keys
+            .clone()
+            .map(|_| self.rng.random_range(0..25))
+            .collect::<Vec<_>>();
+        let phones = nations.iter().map(|n| self.phone(*n)).collect::<Vec<_>>();
+        let balances = keys
+            .clone()
+            .map(|_| self.cents(-99_999, 999_999))
+            .collect::<Vec<_>>();
+        let comments = keys
+            .clone()
+            .map(|key| match key % 20 {
+                0 => "slyly Customer pending Complaints".to_string(),
+                _ => self.comment(),
+            })
+            .collect::<Vec<_>>();
+        vec![(
+            "supplier".to_string(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(keys.clone())),
+                Arc::new(StringArray::from_iter_values(
+                    keys.clone().map(|key| format!("Supplier#{key:09}")),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    keys.map(|key| format!("{key} supplier street")),
+                )),
+                Arc::new(Int64Array::from(nations)),
+                Arc::new(StringArray::from(phones)),
+                Arc::new(decimal(balances)),
+                Arc::new(StringArray::from(comments)),
+            ],
+        )]
+    }
+
+    fn part(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let keys = 1..=self.parts;
+        let mut names = vec![];
+        let mut manufacturers = vec![];
+        let mut brands = vec![];
+        let mut types = vec![];
+        let mut sizes = vec![];
+        let mut containers = vec![];
+        let mut comments = vec![];
+        for _ in keys.clone() {
+            names.push(
+                (0..5)
+                    .map(|_| self.pick(&COLORS))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+            let manufacturer = self.rng.random_range(1..=5);
+            manufacturers.push(format!("Manufacturer#{manufacturer}"));
+            brands.push(format!(
+                "Brand#{manufacturer}{}",
+                self.rng.random_range(1..=5)
+            ));
+            let part_type = TYPES.map(|syllables| self.pick(&syllables));
+            types.push(part_type.join(" "));
+            sizes.push(self.rng.random_range(1..=50));
+            let container = CONTAINERS.map(|syllables| self.pick(&syllables));
+            containers.push(container.join(" "));
+            comments.push(self.comment());
+        }
+        vec![(
+            "part".to_string(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(keys.clone())),
+                Arc::new(StringArray::from(names)),
+                Arc::new(StringArray::from(manufacturers)),
+                Arc::new(StringArray::from(brands)),
+                Arc::new(StringArray::from(types)),
+                Arc::new(Int32Array::from(sizes)),
+                Arc::new(StringArray::from(containers)),
+                Arc::new(decimal(keys.map(|key| 90_000 + (key as i128 % 20_000)))),
+                Arc::new(StringArray::from(comments)),
+            ],
+        )]
+    }
+
+    /// Returns the key of the `i`th of the 4 suppliers of `part`
+    fn part_supplier(&self, part: i64, i: i64) -> i64 {
+        (part + i * (self.suppliers / 4 + (part - 1) / self.suppliers)) % self.suppliers
+            + 1
+    }
+
+    fn partsupp(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let mut parts = vec![];
+        let mut suppliers = vec![];
+        let mut quantities = vec![];
+        let mut costs = vec![];
+        let mut comments = vec![];
+        for part in 1..=self.parts {
+            for i in 0..4 {
+                parts.push(part);
+                suppliers.push(self.part_supplier(part, i));
+                quantities.push(self.rng.random_range(1..10_000));
+                costs.push(self.cents(100, 100_000));
+                comments.push(self.comment());
+            }
+        }
+        vec![(
+            "partsupp".to_string(),
+            vec![
+                Arc::new(Int64Array::from(parts)),
+                Arc::new(Int64Array::from(suppliers)),
+                Arc::new(Int32Array::from(quantities)),
+                Arc::new(decimal(costs)),
+                Arc::new(StringArray::from(comments)),
+            ],
+        )]
+    }
+
+    fn customer(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let keys = 1..=self.customers;
+        let nations = keys
+            .clone()
+            .map(|_| self.rng.random_range(0..25))
+            .collect::<Vec<_>>();
+        let phones = nations.iter().map(|n| self.phone(*n)).collect::<Vec<_>>();
+        let balances = keys
+            .clone()
+            .map(|_| self.cents(-99_999, 999_999))
+            .collect::<Vec<_>>();
+        let segments = keys
+            .clone()
+            .map(|_| self.pick(&SEGMENTS))
+            .collect::<Vec<_>>();
+        let comments = keys.clone().map(|_| self.comment()).collect::<Vec<_>>();
+        vec![(
+            "customer".to_string(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(keys.clone())),
+                Arc::new(StringArray::from_iter_values(
+                    keys.clone().map(|key| format!("Customer#{key:09}")),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    keys.map(|key| format!("{key} customer street")),
+                )),
+                Arc::new(Int64Array::from(nations)),
+                Arc::new(StringArray::from(phones)),
+                Arc::new(decimal(balances)),
+                Arc::new(StringArray::from(segments)),
+                Arc::new(StringArray::from(comments)),
+            ],
+        )]
+    }
+
+    fn lineitem_and_orders(&mut self) -> Vec<(String, Vec<ArrayRef>)> {
+        let mut o_keys = vec![];
+        let mut o_customers = vec![];
+        let mut o_statuses = vec![];
+        let mut o_prices = vec![];
+        let mut o_dates = vec![];
+        let mut o_priorities = vec![];
+        let mut o_clerks = vec![];
+        let mut o_comments = vec![];
+
+        let mut l_orders = vec![];
+        let mut l_parts = vec![];
+        let mut l_suppliers = vec![];
+        let mut l_numbers = vec![];
+        let mut l_quantities = vec![];
+        let mut l_prices = vec![];
+        let mut l_discounts = vec![];
+        let mut l_taxes = vec![];
+        let mut l_flags = vec![];
+        let mut l_statuses = vec![];
+        let mut l_ship_dates = vec![];
+        let mut l_commit_dates = vec![];
+        let mut l_receipt_dates = vec![];
+        let mut l_instructions = vec![];
+        let mut l_modes = vec![];
+        let mut l_comments = vec![];
+
+        for order in 1..=self.orders {
+            // A third of the customers do not have any order
+            let customer = loop {
+                let customer = self.rng.random_range(1..=self.customers);
+                if customer % 3 != 0 {
+                    break customer;
+                }
+            };
+            let order_date = self.rng.random_range(ORDER_DATES.0..=ORDER_DATES.1);
+            let mut total_price = 0;
+            let mut shipped = 0;
+            let lines = self.rng.random_range(1..=7);
+            for line in 1..=lines {
+                let part = self.rng.random_range(1..=self.parts);
+                let quantity = self.rng.random_range(1..=50);
+                let price = quantity * (90_000 + part as i128 % 20_000);
+                let ship_date = order_date + self.rng.random_range(1..=121);
+                let receipt_date = ship_date + self.rng.random_range(1..=30);
+                total_price += price;
+                l_orders.push(order);
+                l_parts.push(part);
+                let supplier = self.rng.random_range(0..4);
+                l_suppliers.push(self.part_supplier(part, supplier));
+                l_numbers.push(line);
+                l_quantities.push(quantity * 100);
+                l_prices.push(price);
+                l_discounts.push(self.cents(0, 10));
+                l_taxes.push(self.cents(0, 8));
+                l_flags.push(if receipt_date <= CURRENT_DATE {
+                    self.pick(&["R", "A"])
+                } else {
+                    "N"
+                });
+                if ship_date <= CURRENT_DATE {
+                    shipped += 1;
+                    l_statuses.push("F");
+                } else {
+                    l_statuses.push("O");
+                }
+                l_ship_dates.push(ship_date);
+                l_commit_dates.push(order_date + self.rng.random_range(30..=90));
+                l_receipt_dates.push(receipt_date);
+                l_instructions.push(self.pick(&INSTRUCTIONS));
+                l_modes.push(self.pick(&MODES));
+                l_comments.push(self.comment());
+            }
+            o_keys.push(order);
+            o_customers.push(customer);
+            o_statuses.push(match shipped {
+                0 => "O",
+                _ if shipped == lines => "F",
+                _ => "P",
+            });
+            o_prices.push(total_price);
+            o_dates.push(order_date);
+            o_priorities.push(self.pick(&PRIORITIES));
+            o_clerks.push(format!("Clerk#{:09}", self.rng.random_range(1..=1000)));
+            o_comments.push(match order % 50 {
+                0 => "pending special foxes requests".to_string(),
+                _ => self.comment(),
+            });
+        }
+
+        let orders = vec![
+            Arc::new(Int64Array::from(o_keys)) as ArrayRef,
+            Arc::new(Int64Array::from(o_customers)),
+            Arc::new(StringArray::from(o_statuses)),
+            Arc::new(decimal(o_prices)),
+            Arc::new(Date32Array::from(o_dates)),
+            Arc::new(StringArray::from(o_priorities)),
+            Arc::new(StringArray::from(o_clerks)),
+            Arc::new(Int32Array::from(vec![0; self.orders as usize])),
+            Arc::new(StringArray::from(o_comments)),
+        ];
+        let lineitem = vec![
+            Arc::new(Int64Array::from(l_orders)) as ArrayRef,
+            Arc::new(Int64Array::from(l_parts)),
+            Arc::new(Int64Array::from(l_suppliers)),
+            Arc::new(Int32Array::from(l_numbers)),
+            Arc::new(decimal(l_quantities)),
+            Arc::new(decimal(l_prices)),
+            Arc::new(decimal(l_discounts)),
+            Arc::new(decimal(l_taxes)),
+            Arc::new(StringArray::from(l_flags)),
+            Arc::new(StringArray::from(l_statuses)),
+            Arc::new(Date32Array::from(l_ship_dates)),
+            Arc::new(Date32Array::from(l_commit_dates)),
+            Arc::new(Date32Array::from(l_receipt_dates)),
+            Arc::new(StringArray::from(l_instructions)),
+            Arc::new(StringArray::from(l_modes)),
+            Arc::new(StringArray::from(l_comments)),
+        ];
+        vec![
+            ("lineitem".to_string(), lineitem),
+            ("orders".to_string(), orders),
+        ]
+    }
+}
+
+/// Returns a `Decimal128(15, 2)` array of `cents`
+fn decimal(cents: impl IntoIterator<Item = i128>) -> Decimal128Array {
+    Decimal128Array::from_iter_values(cents)
+        .with_precision_and_scale(15, 2)
+        .unwrap()
+}
+
+/// The tracked memory may not differ from the allocated memory by more
+/// than this fraction of the allocated memory, plus [`STREAMED_BATCHES`]
+const TOLERANCE: f64 = 0.5;
+
+/// The memory of the batches streamed through the operators, that are not
+/// held by any operator and so are not tracked, like the batches decoded by
+/// the scans
+const STREAMED_BATCHES: usize = 2 * 1024 * 1024;
+
+async fn assert_memory_tracked(query_no: usize) -> Result<()> {
+    for usage in measure_memory_usage(query_no).await? {
+        usage.assert_within(&format!("q{query_no}"));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn tpch_q1() -> Result<()> {
+    assert_memory_tracked(1).await
+}
+
+#[tokio::test]
+async fn tpch_q2() -> Result<()> {
+    assert_memory_tracked(2).await
+}
+
+#[tokio::test]
+async fn tpch_q3() -> Result<()> {
+    assert_memory_tracked(3).await
+}
+
+#[tokio::test]
+async fn tpch_q4() -> Result<()> {
+    assert_memory_tracked(4).await
+}
+
+#[tokio::test]
+async fn tpch_q5() -> Result<()> {
+    assert_memory_tracked(5).await
+}
+
+#[tokio::test]
+async fn tpch_q6() -> Result<()> {
+    assert_memory_tracked(6).await
+}
+
+#[tokio::test]
+async fn tpch_q7() -> Result<()> {
+    assert_memory_tracked(7).await
+}
+
+#[tokio::test]
+async fn tpch_q8() -> Result<()> {
+    assert_memory_tracked(8).await
+}
+
+#[tokio::test]
+async fn tpch_q9() -> Result<()> {
+    assert_memory_tracked(9).await
+}
+
+#[tokio::test]
+async fn tpch_q10() -> Result<()> {
+    assert_memory_tracked(10).await
+}
+
+#[tokio::test]
+async fn tpch_q11() -> Result<()> {
+    assert_memory_tracked(11).await
+}
+
+#[tokio::test]
+async fn tpch_q12() -> Result<()> {
+    assert_memory_tracked(12).await
+}
+
+#[tokio::test]
+async fn tpch_q13() -> Result<()> {
+    assert_memory_tracked(13).await
+}
+
+#[tokio::test]
+async fn tpch_q14() -> Result<()> {
+    assert_memory_tracked(14).await
+}
+
+#[tokio::test]
+async fn tpch_q15() -> Result<()> {
+    assert_memory_tracked(15).await
+}
+
+#[tokio::test]
+async fn tpch_q16() -> Result<()> {
+    assert_memory_tracked(16).await
+}
+
+#[tokio::test]
+async fn tpch_q17() -> Result<()> {
+    assert_memory_tracked(17).await
+}
+
+#[tokio::test]
+async fn tpch_q18() -> Result<()> {
+    assert_memory_tracked(18).await
+}
+
+#[tokio::test]
+async fn tpch_q19() -> Result<()> {
+    assert_memory_tracked(19).await
+}
+
+#[tokio::test]
+async fn tpch_q20() -> Result<()> {
+    assert_memory_tracked(20).await
+}
+
+#[tokio::test]
+async fn tpch_q21() -> Result<()> {
+    assert_memory_tracked(21).await
+}
+
+#[tokio::test]
+async fn tpch_q22() -> Result<()> {
+    assert_memory_tracked(22).await
+}
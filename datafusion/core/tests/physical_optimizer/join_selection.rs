@@ -124,18 +124,20 @@ fn create_column_stats(
     max: Option<u64>,
     distinct_count: Option<usize>,
 ) -> Vec<ColumnStatistics> {
-    vec![ColumnStatistics {
-        distinct_count: distinct_count
-            .map(Precision::Inexact)
-            .unwrap_or(Precision::Absent),
-        min_value: min
-            .map(|size| Precision::Inexact(ScalarValue::UInt64(Some(size))))
-            .unwrap_or(Precision::Absent),
-        max_value: max
-            .map(|size| Precision::Inexact(ScalarValue::UInt64(Some(size))))
-            .unwrap_or(Precision::Absent),
-        ..Default::default()
-    }]
+    vec![ColumnStatistics::new_unknown()
+        .with_distinct_count(
+            distinct_count
+                .map(Precision::Inexact)
+                .unwrap_or(Precision::Absent),
+        )
+        .with_min_value(
+            min.map(|size| Precision::Inexact(ScalarValue::UInt64(Some(size))))
+                .unwrap_or(Precision::Absent),
+        )
+        .with_max_value(
+            max.map(|size| Precision::Inexact(ScalarValue::UInt64(Some(size))))
+                .unwrap_or(Precision::Absent),
+        )]
 }
 
 /// Create join filter for NLJoinExec with expression `big_col > small_col`
@@ -114,22 +114,13 @@ mod test {
         max_value: i32,
         include_date_column: bool,
     ) -> Statistics {
-        let mut column_stats = vec![ColumnStatistics {
-            null_count: Precision::Exact(0),
-            max_value: Precision::Exact(ScalarValue::Int32(Some(max_value))),
-            min_value: Precision::Exact(ScalarValue::Int32(Some(min_value))),
-            sum_value: Precision::Absent,
-            distinct_count: Precision::Absent,
-        }];
+        let mut column_stats = vec![ColumnStatistics::new_unknown()
+            .with_null_count(Precision::Exact(0))
+            .with_max_value(Precision::Exact(ScalarValue::Int32(Some(max_value))))
+            .with_min_value(Precision::Exact(ScalarValue::Int32(Some(min_value))))];
 
         if include_date_column {
-            column_stats.push(ColumnStatistics {
-                null_count: Precision::Absent,
-                max_value: Precision::Absent,
-                min_value: Precision::Absent,
-                sum_value: Precision::Absent,
-                distinct_count: Precision::Absent,
-            });
+            column_stats.push(ColumnStatistics::new_unknown());
         }
 
         Statistics {
@@ -320,20 +311,18 @@ mod test {
             num_rows: Precision::Inexact(0),
             total_byte_size: Precision::Inexact(0),
             column_statistics: vec![
-                ColumnStatistics {
-                    null_count: Precision::Exact(0),
-                    max_value: Precision::Exact(ScalarValue::Null),
-                    min_value: Precision::Exact(ScalarValue::Null),
-                    sum_value: Precision::Exact(ScalarValue::Null),
-                    distinct_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    null_count: Precision::Exact(0),
-                    max_value: Precision::Exact(ScalarValue::Null),
-                    min_value: Precision::Exact(ScalarValue::Null),
-                    sum_value: Precision::Exact(ScalarValue::Null),
-                    distinct_count: Precision::Exact(0),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(0))
+                    .with_max_value(Precision::Exact(ScalarValue::Null))
+                    .with_min_value(Precision::Exact(ScalarValue::Null))
+                    .with_sum_value(Precision::Exact(ScalarValue::Null))
+                    .with_distinct_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(0))
+                    .with_max_value(Precision::Exact(ScalarValue::Null))
+                    .with_min_value(Precision::Exact(ScalarValue::Null))
+                    .with_sum_value(Precision::Exact(ScalarValue::Null))
+                    .with_distinct_count(Precision::Exact(0)),
             ],
         };
         assert_eq!(full_statistics, expected_full_statistic);
@@ -398,26 +387,20 @@ mod test {
         assert_eq!(statistics.len(), 2);
         let mut expected_statistic_partition_1 =
             create_partition_statistics(8, 48400, 1, 4, true);
-        expected_statistic_partition_1
-            .column_statistics
-            .push(ColumnStatistics {
-                null_count: Precision::Exact(0),
-                max_value: Precision::Exact(ScalarValue::Int32(Some(4))),
-                min_value: Precision::Exact(ScalarValue::Int32(Some(3))),
-                sum_value: Precision::Absent,
-                distinct_count: Precision::Absent,
-            });
+        expected_statistic_partition_1.column_statistics.push(
+            ColumnStatistics::new_unknown()
+                .with_null_count(Precision::Exact(0))
+                .with_max_value(Precision::Exact(ScalarValue::Int32(Some(4))))
+                .with_min_value(Precision::Exact(ScalarValue::Int32(Some(3)))),
+        );
         let mut expected_statistic_partition_2 =
             create_partition_statistics(8, 48400, 1, 4, true);
-        expected_statistic_partition_2
-            .column_statistics
-            .push(ColumnStatistics {
-                null_count: Precision::Exact(0),
-                max_value: Precision::Exact(ScalarValue::Int32(Some(2))),
-                min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
-                sum_value: Precision::Absent,
-                distinct_count: Precision::Absent,
-            });
+        expected_statistic_partition_2.column_statistics.push(
+            ColumnStatistics::new_unknown()
+                .with_null_count(Precision::Exact(0))
+                .with_max_value(Precision::Exact(ScalarValue::Int32(Some(2))))
+                .with_min_value(Precision::Exact(ScalarValue::Int32(Some(1)))),
+        );
         assert_eq!(statistics[0], expected_statistic_partition_1);
         assert_eq!(statistics[1], expected_statistic_partition_2);
 
@@ -557,13 +540,9 @@ mod test {
             num_rows: Precision::Inexact(2),
             total_byte_size: Precision::Absent,
             column_statistics: vec![
-                ColumnStatistics {
-                    null_count: Precision::Absent,
-                    max_value: Precision::Exact(ScalarValue::Int32(Some(4))),
-                    min_value: Precision::Exact(ScalarValue::Int32(Some(3))),
-                    sum_value: Precision::Absent,
-                    distinct_count: Precision::Absent,
-                },
+                ColumnStatistics::new_unknown()
+                    .with_max_value(Precision::Exact(ScalarValue::Int32(Some(4))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int32(Some(3)))),
                 ColumnStatistics::new_unknown(),
                 ColumnStatistics::new_unknown(),
             ],
@@ -575,13 +554,9 @@ mod test {
             num_rows: Precision::Inexact(2),
             total_byte_size: Precision::Absent,
             column_statistics: vec![
-                ColumnStatistics {
-                    null_count: Precision::Absent,
-                    max_value: Precision::Exact(ScalarValue::Int32(Some(2))),
-                    min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
-                    sum_value: Precision::Absent,
-                    distinct_count: Precision::Absent,
-                },
+                ColumnStatistics::new_unknown()
+                    .with_max_value(Precision::Exact(ScalarValue::Int32(Some(2))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int32(Some(1)))),
                 ColumnStatistics::new_unknown(),
                 ColumnStatistics::new_unknown(),
             ],
@@ -100,13 +100,9 @@ pub(crate) fn parquet_exec_with_sort(
 }
 
 fn int64_stats() -> ColumnStatistics {
-    ColumnStatistics {
-        null_count: Precision::Absent,
-        sum_value: Precision::Absent,
-        max_value: Precision::Exact(1_000_000.into()),
-        min_value: Precision::Exact(0.into()),
-        distinct_count: Precision::Absent,
-    }
+    ColumnStatistics::new_unknown()
+        .with_max_value(Precision::Exact(1_000_000.into()))
+        .with_min_value(Precision::Exact(0.into()))
 }
 
 fn column_stats() -> Vec<ColumnStatistics> {
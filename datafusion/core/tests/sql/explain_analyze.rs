@@ -826,3 +826,124 @@ async fn csv_explain_analyze_with_statistics() {
         ", statistics=[Rows=Absent, Bytes=Absent, [(Col[0]:)]]"
     );
 }
+
+#[tokio::test]
+async fn explain_analyze_adaptive_execution() -> Result<()> {
+    let config = SessionConfig::new()
+        .with_target_partitions(4)
+        .set_bool("datafusion.optimizer.enable_adaptive_execution", true);
+    let ctx = SessionContext::new_with_config(config);
+
+    // CSV files have no statistics: the join is planned as a partitioned join
+    let tmp_dir = TempDir::new()?;
+    for (table, rows) in [
+        ("small", "k,v\n1,a\n2,b\n3,c\n"),
+        ("large", "k,w\n1,10\n2,20\n3,30\n3,31\n4,40\n5,50\n"),
+    ] {
+        let path = tmp_dir.path().join(format!("{table}.csv"));
+        File::create(&path)?.write_all(rows.as_bytes())?;
+        ctx.register_csv(table, path.to_str().unwrap(), CsvReadOptions::new())
+            .await?;
+    }
+
+    let sql =
+        "EXPLAIN ANALYZE SELECT small.k, v, w FROM small JOIN large ON small.k = large.k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+
+    assert_contains!(
+        &formatted,
+        "AdaptiveExec: decisions=[HashJoinExec: build_rows=3 -> mode=CollectLeft without probe side repartitioning], metrics=[output_rows=4"
+    );
+    assert_contains!(&formatted, "HashJoinExec: mode=CollectLeft");
+    assert_contains!(&formatted, "MaterializedStageExec: rows=3, bytes=");
+    Ok(())
+}
+
+#[tokio::test]
+async fn explain_analyze_skew_handling() -> Result<()> {
+    let config = SessionConfig::new()
+        .with_target_partitions(4)
+        .set_bool("datafusion.optimizer.enable_skew_handling", true);
+    let ctx = SessionContext::new_with_config(config);
+
+    // The key 0 has 80 of the 100 rows of the probe side
+    let tmp_dir = TempDir::new()?;
+    let facts = (0..100)
+        .map(|v| format!("{},{v}\n", if v < 80 { 0 } else { v }))
+        .collect::<String>();
+    let dims = (0..100).map(|k| format!("{k},d{k}\n")).collect::<String>();
+    for (table, rows) in [
+        ("facts", format!("k,v\n{facts}")),
+        ("dims", format!("k,name\n{dims}")),
+    ] {
+        let path = tmp_dir.path().join(format!("{table}.csv"));
+        File::create(&path)?.write_all(rows.as_bytes())?;
+        ctx.register_csv(table, path.to_str().unwrap(), CsvReadOptions::new())
+            .await?;
+    }
+
+    let sql = "EXPLAIN ANALYZE SELECT name, v FROM dims JOIN facts ON dims.k = facts.k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+
+    assert_contains!(&formatted, "HashJoinExec: mode=Partitioned");
+    assert_contains!(&formatted, "output_rows=100");
+    let line = |routing: &str| {
+        formatted
+            .lines()
+            .find(|line| line.contains(&format!("hot_keys={routing}")))
+            .unwrap()
+            .to_string()
+    };
+    // The rows of the hot key are spread, the matching build row is replicated
+    let spread = line("spread");
+    assert_contains!(&spread, "hot_keys=1,");
+    assert_contains!(&spread, "hot_key_rows=80");
+    assert_contains!(&spread, "partition_rows=100");
+    let replicate = line("replicate");
+    assert_contains!(&replicate, "hot_keys=1,");
+    assert_contains!(&replicate, "hot_key_rows=1");
+    assert_contains!(&replicate, "partition_rows=103");
+    Ok(())
+}
+
+#[tokio::test]
+async fn explain_analyze_result_cache() -> Result<()> {
+    let config = SessionConfig::new()
+        .with_target_partitions(2)
+        .set_bool("datafusion.execution.enable_result_cache", true);
+    let runtime = datafusion::execution::runtime_env::RuntimeEnvBuilder::new()
+        .with_query_result_cache_limit(1024 * 1024)
+        .build_arc()?;
+    let ctx = SessionContext::new_with_config_rt(config, runtime);
+
+    let tmp_dir = TempDir::new()?;
+    let rows = (0..100)
+        .map(|v| format!("{},{v}\n", v % 3))
+        .collect::<String>();
+    let path = tmp_dir.path().join("facts.csv");
+    File::create(&path)?.write_all(format!("k,v\n{rows}").as_bytes())?;
+    ctx.register_csv("facts", path.to_str().unwrap(), CsvReadOptions::new())
+        .await?;
+
+    let sql = "SELECT k, sum(v) FROM facts GROUP BY k";
+    execute_to_batches(&ctx, sql).await;
+
+    // The result of the first execution is returned
+    let actual = execute_to_batches(&ctx, &format!("EXPLAIN ANALYZE {sql}")).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    let line = formatted
+        .lines()
+        .find(|line| line.contains("CachedResultExec"))
+        .unwrap();
+    assert_contains!(line, "cache_hits=2");
+    assert_contains!(line, "output_rows=3");
+    Ok(())
+}
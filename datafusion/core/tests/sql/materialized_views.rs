@@ -0,0 +1,110 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+use datafusion::datasource::MemTable;
+use datafusion_common::test_util::batches_to_string;
+use insta::assert_snapshot;
+
+/// Creates a `sales` table, and a `sales_by_day` summary table registered as
+/// a rewrite candidate
+async fn sales_context() -> Result<SessionContext> {
+    let ctx = SessionContext::new();
+    ctx.sql(
+        "CREATE TABLE sales(day INT, region VARCHAR, amount INT) AS VALUES
+        (1, 'east', 10), (1, 'west', 20), (1, 'east', 30),
+        (2, 'east', 40), (2, 'west', 50), (3, 'west', 60)",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    let definition = ctx
+        .sql(
+            "SELECT day, region, sum(amount) AS total, count(*) AS sales
+            FROM sales GROUP BY day, region",
+        )
+        .await?;
+    let schema = Arc::new(definition.schema().as_arrow().clone());
+    let definition = definition.logical_plan().clone();
+    let batches = ctx
+        .execute_logical_plan(definition.clone())
+        .await?
+        .collect()
+        .await?;
+    let table = MemTable::try_new(schema, vec![batches])?;
+    ctx.register_rewrite_candidate("sales_by_day", definition, Arc::new(table))?;
+    Ok(ctx)
+}
+
+#[tokio::test]
+async fn rollup_from_summary_table() -> Result<()> {
+    let ctx = sales_context().await?;
+    let sql = "SELECT region, sum(amount), count(*) FROM sales
+        WHERE day >= 2 GROUP BY region ORDER BY region";
+
+    let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+    assert_snapshot!(plan.display_indent(), @r"
+    Sort: sales.region ASC NULLS LAST
+      Projection: sales_by_day.region AS region, sum(sales_by_day.total) AS sum(sales.amount), sum(sales_by_day.sales) AS count(*)
+        Aggregate: groupBy=[[sales_by_day.region]], aggr=[[sum(sales_by_day.total), sum(sales_by_day.sales)]]
+          Projection: sales_by_day.region, sales_by_day.total, sales_by_day.sales
+            Filter: sales_by_day.day >= Int32(2)
+              TableScan: sales_by_day projection=[day, region, total, sales]
+    ");
+
+    let results = execute_to_batches(&ctx, sql).await;
+    assert_snapshot!(batches_to_string(&results), @r"
+    +--------+-------------------+----------+
+    | region | sum(sales.amount) | count(*) |
+    +--------+-------------------+----------+
+    | east   | 40                | 1        |
+    | west   | 110               | 2        |
+    +--------+-------------------+----------+
+    ");
+    Ok(())
+}
+
+#[tokio::test]
+async fn unmatched_queries_scan_the_source() -> Result<()> {
+    let ctx = sales_context().await?;
+    // `amount` is aggregated by the summary table
+    let sql = "SELECT region, sum(amount) FROM sales WHERE amount > 10 GROUP BY region";
+
+    let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+    assert_not_contains!(plan.display_indent().to_string(), "sales_by_day");
+
+    ctx.deregister_rewrite_candidate("sales_by_day");
+    let sql = "SELECT region, sum(amount) FROM sales GROUP BY region";
+    let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+    assert_not_contains!(plan.display_indent().to_string(), "sales_by_day");
+    Ok(())
+}
+
+#[tokio::test]
+async fn rewrite_disabled() -> Result<()> {
+    let ctx = sales_context().await?;
+    ctx.sql("SET datafusion.optimizer.enable_materialized_view_rewrite = false")
+        .await?
+        .collect()
+        .await?;
+    let sql = "SELECT region, sum(amount) FROM sales GROUP BY region";
+
+    let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+    assert_not_contains!(plan.display_indent().to_string(), "sales_by_day");
+    Ok(())
+}
@@ -61,6 +61,7 @@ pub mod aggregates;
 pub mod create_drop;
 pub mod explain_analyze;
 pub mod joins;
+mod materialized_views;
 mod path_partition;
 mod runtime_config;
 pub mod select;
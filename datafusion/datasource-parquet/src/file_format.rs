@@ -1145,13 +1145,14 @@ fn get_col_stats(
                 Some(min_value) => min_value.evaluate().ok(),
                 None => None,
             };
-            ColumnStatistics {
-                null_count: null_counts[i],
-                max_value: max_value.map(Precision::Exact).unwrap_or(Precision::Absent),
-                min_value: min_value.map(Precision::Exact).unwrap_or(Precision::Absent),
-                sum_value: Precision::Absent,
-                distinct_count: Precision::Absent,
-            }
+            ColumnStatistics::new_unknown()
+                .with_null_count(null_counts[i])
+                .with_max_value(
+                    max_value.map(Precision::Exact).unwrap_or(Precision::Absent),
+                )
+                .with_min_value(
+                    min_value.map(Precision::Exact).unwrap_or(Precision::Absent),
+                )
         })
         .collect()
 }
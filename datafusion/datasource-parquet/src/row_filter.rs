@@ -92,7 +92,8 @@ use super::ParquetFileMetrics;
 ///
 /// An expression can be evaluated as a `DatafusionArrowPredicate` if it:
 /// * Does not reference any projected columns
-/// * Does not reference columns with non-primitive types (e.g. structs / lists)
+/// * Does not reference columns with non-primitive types (e.g. structs / lists
+///   of structs), except lists of primitive values
 #[derive(Debug)]
 pub(crate) struct DatafusionArrowPredicate {
     /// the filter expression
@@ -296,7 +297,7 @@ impl<'schema> PushdownChecker<'schema> {
     fn check_single_column(&mut self, column_name: &str) -> Option<TreeNodeRecursion> {
         if let Ok(idx) = self.table_schema.index_of(column_name) {
             self.required_columns.insert(idx);
-            if DataType::is_nested(self.table_schema.field(idx).data_type()) {
+            if !is_single_leaf(self.table_schema.field(idx).data_type()) {
                 self.non_primitive_columns = true;
                 return Some(TreeNodeRecursion::Jump);
             }
@@ -316,6 +317,18 @@ impl<'schema> PushdownChecker<'schema> {
     }
 }
 
+/// Returns true if the values of `data_type` are stored in a single Parquet
+/// leaf column: primitive types and lists of primitive values, such as the
+/// `FixedSizeList<Float32>` columns of embeddings
+fn is_single_leaf(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _) => !field.data_type().is_nested(),
+        other => !other.is_nested(),
+    }
+}
+
 impl TreeNodeVisitor<'_> for PushdownChecker<'_> {
     type Node = Arc<dyn PhysicalExpr>;
 
@@ -365,11 +378,17 @@ pub fn can_expr_be_pushed_down_with_schemas(
 /// This value represents the total amount of IO required to evaluate the
 /// predicate.
 fn size_of_columns(columns: &[usize], metadata: &ParquetMetaData) -> Result<usize> {
+    // `columns` are root columns of the schema, that may be nested
+    let schema_descr = metadata.file_metadata().schema_descr();
+    let leaves = (0..schema_descr.num_columns())
+        .filter(|leaf| columns.contains(&schema_descr.get_column_root_idx(*leaf)))
+        .collect_vec();
+
     let mut total_size = 0;
     let row_groups = metadata.row_groups();
-    for idx in columns {
+    for idx in leaves {
         for rg in row_groups.iter() {
-            total_size += rg.column(*idx).compressed_size() as usize;
+            total_size += rg.column(idx).compressed_size() as usize;
         }
     }
 
@@ -463,6 +482,7 @@ pub fn build_row_filter(
 #[cfg(test)]
 mod test {
     use super::*;
+    use arrow::datatypes::Field;
     use datafusion_common::ScalarValue;
 
     use datafusion_expr::{col, Expr};
@@ -471,9 +491,10 @@ mod test {
     use parquet::arrow::parquet_to_arrow_schema;
     use parquet::file::reader::{FileReader, SerializedFileReader};
 
-    // We should ignore predicate that read non-primitive columns
+    // Lists of primitive values are stored in a single leaf column, so that
+    // predicates reading them can be evaluated during decoding
     #[test]
-    fn test_filter_candidate_builder_ignore_complex_types() {
+    fn test_filter_candidate_builder_list_of_primitives() {
         let testdata = datafusion_common::test_util::parquet_test_data();
         let file = std::fs::File::open(format!("{testdata}/list_columns.parquet"))
             .expect("opening file");
@@ -493,20 +514,49 @@ mod test {
 
         let candidate = FilterCandidateBuilder::new(expr, table_schema.clone())
             .build(metadata)
-            .expect("building candidate");
+            .expect("building candidate")
+            .expect("list of primitives should be a candidate");
 
-        assert!(candidate.is_none());
+        assert_eq!(candidate.projection, vec![0]);
+        assert!(candidate.required_bytes > 0);
     }
 
     #[test]
     fn nested_data_structures_prevent_pushdown() {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new_list(
+                "struct_list",
+                Field::new_list_field(
+                    DataType::Struct(vec![Field::new("a", DataType::Int32, true)].into()),
+                    true,
+                ),
+                true,
+            ),
+            Field::new_list(
+                "nested_list",
+                Field::new_list_field(DataType::new_list(DataType::Int32, true), true),
+                true,
+            ),
+        ]));
+
+        for column in ["struct_list", "nested_list"] {
+            let expr = col(column).is_not_null();
+            let expr = logical2physical(&expr, &table_schema);
+            check_expression_can_evaluate_against_schema(&expr, &table_schema);
+
+            assert!(!can_expr_be_pushed_down_with_schemas(&expr, &table_schema));
+        }
+    }
+
+    #[test]
+    fn lists_of_primitives_allow_pushdown() {
         let table_schema = Arc::new(get_lists_table_schema());
 
         let expr = col("utf8_list").is_not_null();
         let expr = logical2physical(&expr, &table_schema);
         check_expression_can_evaluate_against_schema(&expr, &table_schema);
 
-        assert!(!can_expr_be_pushed_down_with_schemas(&expr, &table_schema));
+        assert!(can_expr_be_pushed_down_with_schemas(&expr, &table_schema));
     }
 
     #[test]
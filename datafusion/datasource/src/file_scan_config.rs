@@ -489,7 +489,9 @@ impl DataSource for FileScanConfig {
         let opener = source.create_file_opener(object_store, self, partition);
 
         let stream = FileStream::new(self, partition, opener, source.metrics())?;
-        Ok(Box::pin(cooperative(stream)))
+        Ok(Box::pin(cooperative(stream).with_cancellation_token(
+            context.cancellation_token().clone(),
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -573,6 +575,17 @@ impl DataSource for FileScanConfig {
         Some(Arc::new(source))
     }
 
+    fn with_table_statistics(
+        &self,
+        statistics: &Statistics,
+    ) -> Option<Arc<dyn DataSource>> {
+        let file_statistics = self.file_source.statistics().ok()?;
+        let source = FileScanConfigBuilder::from(self.clone())
+            .with_statistics(file_statistics.fill_absent(statistics))
+            .build();
+        Some(Arc::new(source))
+    }
+
     fn fetch(&self) -> Option<usize> {
         self.limit
     }
@@ -1619,9 +1632,9 @@ mod tests {
                 // assign the column index to distinct_count to help assert
                 // the source statistic after the projection
                 column_statistics: (0..file_schema.fields().len())
-                    .map(|i| ColumnStatistics {
-                        distinct_count: Precision::Inexact(i),
-                        ..Default::default()
+                    .map(|i| {
+                        ColumnStatistics::new_unknown()
+                            .with_distinct_count(Precision::Inexact(i))
                     })
                     .collect(),
                 total_byte_size: Precision::Absent,
@@ -2141,14 +2154,14 @@ mod tests {
                             .into_iter()
                             .map(|stats| {
                                 stats
-                                    .map(|(min, max)| ColumnStatistics {
-                                        min_value: Precision::Exact(ScalarValue::from(
-                                            min,
-                                        )),
-                                        max_value: Precision::Exact(ScalarValue::from(
-                                            max,
-                                        )),
-                                        ..Default::default()
+                                    .map(|(min, max)| {
+                                        ColumnStatistics::new_unknown()
+                                            .with_min_value(Precision::Exact(
+                                                ScalarValue::from(min),
+                                            ))
+                                            .with_max_value(Precision::Exact(
+                                                ScalarValue::from(max),
+                                            ))
                                     })
                                     .unwrap_or_default()
                             })
@@ -71,22 +71,28 @@ pub struct MemorySourceConfig {
     /// The maximum number of records to read from this plan. If `None`,
     /// all records after filtering are returned.
     fetch: Option<usize>,
+    /// Statistics of the table (before projection) that supplement the
+    /// statistics computed from the batches, see [`DataSource::with_table_statistics`]
+    table_statistics: Option<Arc<Statistics>>,
 }
 
 impl DataSource for MemorySourceConfig {
     fn open(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(cooperative(
-            MemoryStream::try_new(
-                self.partitions[partition].clone(),
-                Arc::clone(&self.projected_schema),
-                self.projection.clone(),
-            )?
-            .with_fetch(self.fetch),
-        )))
+        Ok(Box::pin(
+            cooperative(
+                MemoryStream::try_new(
+                    self.partitions[partition].clone(),
+                    Arc::clone(&self.projected_schema),
+                    self.projection.clone(),
+                )?
+                .with_fetch(self.fetch),
+            )
+            .with_cancellation_token(context.cancellation_token().clone()),
+        ))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -169,11 +175,13 @@ impl DataSource for MemorySourceConfig {
         };
 
         if let Some(repartitioned) = maybe_repartitioned {
-            Ok(Some(Arc::new(Self::try_new(
+            let mut source = Self::try_new(
                 &repartitioned,
                 self.original_schema(),
                 self.projection.clone(),
-            )?)))
+            )?;
+            source.table_statistics.clone_from(&self.table_statistics);
+            Ok(Some(Arc::new(source)))
         } else {
             Ok(None)
         }
@@ -195,11 +203,29 @@ impl DataSource for MemorySourceConfig {
     }
 
     fn statistics(&self) -> Result<Statistics> {
-        Ok(common::compute_record_batch_statistics(
+        let statistics = common::compute_record_batch_statistics(
             &self.partitions,
             &self.schema,
             self.projection.clone(),
-        ))
+        );
+        Ok(match &self.table_statistics {
+            Some(table_statistics) => statistics.fill_absent(
+                &table_statistics
+                    .as_ref()
+                    .clone()
+                    .project(self.projection.as_ref()),
+            ),
+            None => statistics,
+        })
+    }
+
+    fn with_table_statistics(
+        &self,
+        statistics: &Statistics,
+    ) -> Option<Arc<dyn DataSource>> {
+        let mut source = self.clone();
+        source.table_statistics = Some(Arc::new(statistics.clone()));
+        Some(Arc::new(source))
     }
 
     fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn DataSource>> {
@@ -225,12 +251,13 @@ impl DataSource for MemorySourceConfig {
                     self.projection().as_ref().unwrap_or(&all_projections),
                 );
 
-                MemorySourceConfig::try_new_exec(
+                let mut source = MemorySourceConfig::try_new(
                     self.partitions(),
                     self.original_schema(),
                     Some(new_projections),
-                )
-                .map(|e| e as _)
+                )?;
+                source.table_statistics.clone_from(&self.table_statistics);
+                Ok(DataSourceExec::from_data_source(source) as _)
             })
             .transpose()
     }
@@ -253,6 +280,7 @@ impl MemorySourceConfig {
             sort_information: vec![],
             show_sizes: true,
             fetch: None,
+            table_statistics: None,
         })
     }
 
@@ -353,6 +381,7 @@ impl MemorySourceConfig {
             sort_information: vec![],
             show_sizes: true,
             fetch: None,
+            table_statistics: None,
         };
         Ok(DataSourceExec::from_data_source(source))
     }
@@ -832,8 +861,11 @@ mod tests {
     use arrow::datatypes::{DataType, Field};
     use datafusion_common::assert_batches_eq;
     use datafusion_common::stats::{ColumnStatistics, Precision};
+    use datafusion_execution::config::SessionConfig;
     use datafusion_physical_expr::PhysicalSortExpr;
+    use datafusion_physical_plan::collect;
     use datafusion_physical_plan::expressions::lit;
+    use datafusion_physical_plan::spill::get_record_batch_memory_size;
 
     use futures::StreamExt;
 
@@ -863,6 +895,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn exec_with_max_scanned_bytes() -> Result<()> {
+        // the two halves of the batch share its buffers, only their sliced
+        // parts are counted
+        let batch = make_partition(8);
+        let halves = vec![batch.slice(0, 4), batch.slice(4, 4)];
+        let half_size = 4 * size_of::<i32>();
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.max_scanned_bytes = Some(half_size + 1);
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+
+        let exec = MemorySourceConfig::try_new_from_batches(batch.schema(), halves)?;
+        let mut it = exec.execute(0, Arc::clone(&task_ctx))?;
+        assert!(it.next().await.unwrap().is_ok());
+        let err = it.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            format!("Resources exhausted: The data sources of the query output more than {} bytes. Try increasing `datafusion.execution.max_scanned_bytes`.", half_size + 1)
+        );
+        assert!(task_ctx.cancellation_token().is_cancelled());
+        assert_eq!(task_ctx.scanned_bytes(), 2 * half_size);
+        // the size of the buffers of a slice would count the whole batch
+        assert!(get_record_batch_memory_size(&batch.slice(4, 4)) > half_size);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn max_scanned_bytes_of_each_execution() -> Result<()> {
+        let batch = make_partition(8);
+        let size = batch.num_rows() * size_of::<i32>();
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.max_scanned_bytes = Some(size);
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+
+        // the bytes scanned by a query are not added to the ones of the
+        // previous queries with the same context
+        for _ in 0..2 {
+            let exec = MemorySourceConfig::try_new_exec(
+                &[vec![batch.clone()]],
+                batch.schema(),
+                None,
+            )?;
+            let results = collect(exec, Arc::clone(&task_ctx)).await?;
+            assert_eq!(results.len(), 1);
+        }
+        assert_eq!(task_ctx.scanned_bytes(), 0);
+        assert!(!task_ctx.cancellation_token().is_cancelled());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn values_empty_case() -> Result<()> {
         let schema = aggr_test_schema();
@@ -938,13 +1020,9 @@ mod tests {
             Statistics {
                 num_rows: Precision::Exact(rows),
                 total_byte_size: Precision::Exact(8), // not important
-                column_statistics: vec![ColumnStatistics {
-                    null_count: Precision::Exact(rows), // there are only nulls
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Absent,
-                    min_value: Precision::Absent,
-                    sum_value: Precision::Absent,
-                },],
+                column_statistics: vec![ColumnStatistics::new_unknown()
+                    // there are only nulls
+                    .with_null_count(Precision::Exact(rows))],
             }
         );
 
@@ -408,13 +408,10 @@ pub fn generate_test_files(num_files: usize, overlap_factor: f64) -> Vec<FileGro
             statistics: Some(Arc::new(Statistics {
                 num_rows: Precision::Exact(100),
                 total_byte_size: Precision::Exact(1000),
-                column_statistics: vec![ColumnStatistics {
-                    null_count: Precision::Exact(0),
-                    max_value: Precision::Exact(ScalarValue::Float64(Some(max))),
-                    min_value: Precision::Exact(ScalarValue::Float64(Some(min))),
-                    sum_value: Precision::Absent,
-                    distinct_count: Precision::Absent,
-                }],
+                column_statistics: vec![ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(0))
+                    .with_max_value(Precision::Exact(ScalarValue::Float64(Some(max))))
+                    .with_min_value(Precision::Exact(ScalarValue::Float64(Some(min))))],
             })),
             extensions: None,
             metadata_size_hint: None,
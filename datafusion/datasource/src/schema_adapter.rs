@@ -500,16 +500,12 @@ mod tests {
         let mut file_stats = Statistics::default();
 
         // Statistics for column b (index 0 in file)
-        let b_stats = ColumnStatistics {
-            null_count: Precision::Exact(5),
-            ..Default::default()
-        };
+        let b_stats =
+            ColumnStatistics::new_unknown().with_null_count(Precision::Exact(5));
 
         // Statistics for column a (index 1 in file)
-        let a_stats = ColumnStatistics {
-            null_count: Precision::Exact(10),
-            ..Default::default()
-        };
+        let a_stats =
+            ColumnStatistics::new_unknown().with_null_count(Precision::Exact(10));
 
         file_stats.column_statistics = vec![b_stats, a_stats];
 
@@ -1012,12 +1008,11 @@ mod tests {
         max_value: Option<ScalarValue>,
         sum_value: Option<ScalarValue>,
     ) -> ColumnStatistics {
-        ColumnStatistics {
-            null_count: Precision::Exact(null_count),
-            distinct_count: Precision::Exact(distinct_count),
-            min_value: min_value.map_or_else(|| Precision::Absent, Precision::Exact),
-            max_value: max_value.map_or_else(|| Precision::Absent, Precision::Exact),
-            sum_value: sum_value.map_or_else(|| Precision::Absent, Precision::Exact),
-        }
+        ColumnStatistics::new_unknown()
+            .with_null_count(Precision::Exact(null_count))
+            .with_distinct_count(Precision::Exact(distinct_count))
+            .with_min_value(min_value.map_or_else(|| Precision::Absent, Precision::Exact))
+            .with_max_value(max_value.map_or_else(|| Precision::Absent, Precision::Exact))
+            .with_sum_value(sum_value.map_or_else(|| Precision::Absent, Precision::Exact))
     }
 }
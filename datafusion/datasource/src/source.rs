@@ -27,14 +27,17 @@ use datafusion_physical_plan::execution_plan::{
 };
 use datafusion_physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion_physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
 };
+use futures::StreamExt;
 use itertools::Itertools;
 
 use crate::file_scan_config::FileScanConfig;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::{Constraints, Result, Statistics};
+use datafusion_execution::cancellation::CancellationReason;
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
 use datafusion_physical_expr::{
     conjunction, EquivalenceProperties, Partitioning, PhysicalExpr,
@@ -155,6 +158,18 @@ pub trait DataSource: Send + Sync + Debug {
     fn statistics(&self) -> Result<Statistics>;
     /// Return a copy of this DataSource with a new fetch limit
     fn with_fetch(&self, _limit: Option<usize>) -> Option<Arc<dyn DataSource>>;
+    /// Return a copy of this DataSource whose statistics are supplemented with
+    /// the given statistics of all the columns of the table (before any
+    /// projection), for example computed by `ANALYZE TABLE`.
+    /// See [`Statistics::fill_absent`].
+    ///
+    /// Returns `None` (the default) if the statistics can't be supplemented.
+    fn with_table_statistics(
+        &self,
+        _statistics: &Statistics,
+    ) -> Option<Arc<dyn DataSource>> {
+        None
+    }
     fn fetch(&self) -> Option<usize>;
     fn metrics(&self) -> ExecutionPlanMetricsSet {
         ExecutionPlanMetricsSet::new()
@@ -270,7 +285,18 @@ impl ExecutionPlan for DataSourceExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        self.data_source.open(partition, Arc::clone(&context))
+        let stream = self.data_source.open(partition, Arc::clone(&context))?;
+        Ok(
+            match context
+                .session_config()
+                .options()
+                .execution
+                .max_scanned_bytes
+            {
+                Some(max_bytes) => limit_scanned_bytes(stream, max_bytes, context),
+                None => stream,
+            },
+        )
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -382,6 +408,16 @@ impl DataSourceExec {
         &self.data_source
     }
 
+    /// Returns a copy of this plan whose statistics are supplemented with the
+    /// statistics of the table, see [`DataSource::with_table_statistics`].
+    ///
+    /// Returns `None` if the data source doesn't support it.
+    pub fn with_table_statistics(&self, statistics: &Statistics) -> Option<Self> {
+        let data_source = self.data_source.with_table_statistics(statistics)?;
+        let cache = self.cache.clone();
+        Some(Self { data_source, cache })
+    }
+
     pub fn with_data_source(mut self, data_source: Arc<dyn DataSource>) -> Self {
         self.cache = Self::compute_properties(Arc::clone(&data_source));
         self.data_source = data_source;
@@ -452,3 +488,40 @@ where
         Self::new(Arc::new(source))
     }
 }
+
+/// Counts the bytes of the batches of `stream` in the scan output bytes of
+/// `context`, and fails once the data sources of the query output more than
+/// `max_bytes`
+///
+/// The bytes are measured as the in-memory size of the decoded and projected
+/// batches, only counting the sliced part of their buffers, summed over all
+/// the `DataSourceExec`s of the query. This is not the number of bytes read
+/// from storage, which is usually much smaller for compressed formats such as
+/// Parquet. The query is cancelled when the limit is exceeded, to also stop
+/// the operators executing on other tasks.
+fn limit_scanned_bytes(
+    stream: SendableRecordBatchStream,
+    max_bytes: usize,
+    context: Arc<TaskContext>,
+) -> SendableRecordBatchStream {
+    let schema = stream.schema();
+    let stream = stream.map(move |batch| {
+        let batch = batch?;
+        let batch_size = batch
+            .columns()
+            .iter()
+            .map(|array| array.to_data().get_slice_memory_size())
+            .sum::<Result<usize, _>>()?;
+        if context.add_scanned_bytes(batch_size) > max_bytes {
+            let cancellation_token = context.cancellation_token();
+            cancellation_token.cancel_with_reason(CancellationReason::ResourcesExhausted(
+                format!(
+                    "The data sources of the query output more than {max_bytes} bytes. Try increasing `datafusion.execution.max_scanned_bytes`."
+                ),
+            ));
+            cancellation_token.check()?;
+        }
+        Ok(batch)
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
@@ -376,7 +376,7 @@ pub async fn get_statistics_with_limit(
                         max_value: file_max,
                         min_value: file_min,
                         sum_value: file_sum,
-                        distinct_count: _,
+                        ..
                     } = file_col_stats;
 
                     col_stats.null_count = col_stats.null_count.add(file_nc);
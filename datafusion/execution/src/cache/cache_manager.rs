@@ -15,8 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::cache::cache_unit::DefaultTableStatisticsCache;
 use crate::cache::CacheAccessor;
-use datafusion_common::{Result, Statistics};
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use datafusion_common::{HashMap, Result, Statistics, TableReference};
 use object_store::path::Path;
 use object_store::ObjectMeta;
 use std::fmt::{Debug, Formatter};
@@ -32,6 +35,111 @@ pub type FileStatisticsCache =
 pub type ListFilesCache =
     Arc<dyn CacheAccessor<Path, Arc<Vec<ObjectMeta>>, Extra = ObjectMeta>>;
 
+/// The cache of the statistics of tables computed by `ANALYZE TABLE`, including
+/// the histograms and the most common values of their columns.
+/// The statistics are keyed by the resolved name of the table, and are used
+/// to supplement the statistics of the table scans as long as the
+/// [`TableVersion`] of the table is unchanged.
+pub type TableStatisticsCache =
+    Arc<dyn CacheAccessor<TableReference, Arc<Statistics>, Extra = TableVersion>>;
+
+/// The version of a table whose statistics are kept in a
+/// [`TableStatisticsCache`]: the statistics of a table are only returned for
+/// the version of the table they were computed for.
+///
+/// A version identifies the provider of the table, so that a table which is
+/// dropped and created again, e.g. by `CREATE OR REPLACE TABLE`, has a new
+/// version even if its schema is unchanged. It also includes the schema of the
+/// table and the versions of the files it reads, if any.
+#[derive(Clone)]
+pub struct TableVersion {
+    /// The address of the provider of the table
+    provider: usize,
+    /// Returns true while the provider of the table is alive, so that another
+    /// provider allocated at the same address is not mistaken for it
+    provider_alive: Arc<dyn Fn() -> bool + Send + Sync>,
+    schema: SchemaRef,
+    files: Vec<ObjectMeta>,
+}
+
+impl TableVersion {
+    /// Create the version of the table of `provider`, whose schema is `schema`
+    pub fn new<T: ?Sized + Send + Sync + 'static>(
+        provider: &Arc<T>,
+        schema: SchemaRef,
+    ) -> Self {
+        let weak = Arc::downgrade(provider);
+        Self {
+            provider: Arc::as_ptr(provider) as *const () as usize,
+            provider_alive: Arc::new(move || weak.strong_count() > 0),
+            schema,
+            files: vec![],
+        }
+    }
+
+    /// Set the files read by the table
+    pub fn with_files(mut self, files: Vec<ObjectMeta>) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// The schema of the table
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// The files read by the table
+    pub fn files(&self) -> &[ObjectMeta] {
+        &self.files
+    }
+
+    /// Returns true if the statistics computed for this version of the table
+    /// are valid for `version`: the table has the same provider and schema,
+    /// and each file of `version` is an unchanged file of this version.
+    ///
+    /// `version` may only have some of the files of the table, e.g. the files
+    /// of a scan whose partitions were pruned, or none of them when they are
+    /// not known. The files removed from the table are not detected.
+    pub fn is_valid_for(&self, version: &TableVersion) -> bool {
+        if self.provider != version.provider
+            || !(self.provider_alive)()
+            || self.schema != version.schema
+        {
+            return false;
+        }
+        if version.files.is_empty() {
+            return true;
+        }
+        let files = self
+            .files
+            .iter()
+            .map(|file| (&file.location, file))
+            .collect::<HashMap<_, _>>();
+        version.files.iter().all(|file| {
+            files.get(&file.location).is_some_and(|saved| {
+                saved.size == file.size && saved.last_modified == file.last_modified
+            })
+        })
+    }
+}
+
+impl Debug for TableVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableVersion")
+            .field("provider", &self.provider)
+            .field("schema", &self.schema)
+            .field("files", &self.files)
+            .finish()
+    }
+}
+
+/// The cache of the results of queries and of their subplans, keyed by their
+/// normalized physical plan. The batches of each output partition are cached,
+/// along with the versions of the files the plan reads: an entry is only
+/// returned if none of these files has changed.
+pub type QueryResultCache =
+    Arc<dyn CacheAccessor<String, Arc<Vec<Vec<RecordBatch>>>, Extra = Vec<ObjectMeta>>>;
+
 impl Debug for dyn CacheAccessor<Path, Arc<Statistics>, Extra = ObjectMeta> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cache name: {} with length: {}", self.name(), self.len())
@@ -44,10 +152,26 @@ impl Debug for dyn CacheAccessor<Path, Arc<Vec<ObjectMeta>>, Extra = ObjectMeta>
     }
 }
 
+impl Debug for dyn CacheAccessor<TableReference, Arc<Statistics>, Extra = TableVersion> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
+impl Debug
+    for dyn CacheAccessor<String, Arc<Vec<Vec<RecordBatch>>>, Extra = Vec<ObjectMeta>>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct CacheManager {
     file_statistic_cache: Option<FileStatisticsCache>,
     list_files_cache: Option<ListFilesCache>,
+    table_statistics_cache: Option<TableStatisticsCache>,
+    query_result_cache: Option<QueryResultCache>,
 }
 
 impl CacheManager {
@@ -59,6 +183,13 @@ impl CacheManager {
         if let Some(lc) = &config.list_files_cache {
             manager.list_files_cache = Some(Arc::clone(lc))
         }
+        manager.table_statistics_cache = Some(match &config.table_statistics_cache {
+            Some(tc) => Arc::clone(tc),
+            None => Arc::new(DefaultTableStatisticsCache::default()),
+        });
+        if let Some(rc) = &config.query_result_cache {
+            manager.query_result_cache = Some(Arc::clone(rc))
+        }
         Ok(Arc::new(manager))
     }
 
@@ -71,6 +202,16 @@ impl CacheManager {
     pub fn get_list_files_cache(&self) -> Option<ListFilesCache> {
         self.list_files_cache.clone()
     }
+
+    /// Get the cache of the statistics of tables computed by `ANALYZE TABLE`.
+    pub fn get_table_statistics_cache(&self) -> Option<TableStatisticsCache> {
+        self.table_statistics_cache.clone()
+    }
+
+    /// Get the cache of the results of queries and subplans.
+    pub fn get_query_result_cache(&self) -> Option<QueryResultCache> {
+        self.query_result_cache.clone()
+    }
 }
 
 #[derive(Clone, Default)]
@@ -86,6 +227,18 @@ pub struct CacheManagerConfig {
     /// location.  
     /// Default is disable.
     pub list_files_cache: Option<ListFilesCache>,
+    /// Cache of the statistics of tables computed by `ANALYZE TABLE`.
+    /// Default is a [`DefaultTableStatisticsCache`], which keeps the
+    /// statistics in memory.
+    pub table_statistics_cache: Option<TableStatisticsCache>,
+    /// Enable cache of the results of queries, and of their subplans which
+    /// only read files, when `datafusion.execution.enable_result_cache` is set.
+    /// The cached results are returned as long as the files are unchanged.
+    /// See [`DefaultQueryResultCache`] for a cache with a size limit.
+    /// Default is disable.
+    ///
+    /// [`DefaultQueryResultCache`]: crate::cache::cache_unit::DefaultQueryResultCache
+    pub query_result_cache: Option<QueryResultCache>,
 }
 
 impl CacheManagerConfig {
@@ -101,4 +254,17 @@ impl CacheManagerConfig {
         self.list_files_cache = cache;
         self
     }
+
+    pub fn with_table_statistics_cache(
+        mut self,
+        cache: Option<TableStatisticsCache>,
+    ) -> Self {
+        self.table_statistics_cache = cache;
+        self
+    }
+
+    pub fn with_query_result_cache(mut self, cache: Option<QueryResultCache>) -> Self {
+        self.query_result_cache = cache;
+        self
+    }
 }
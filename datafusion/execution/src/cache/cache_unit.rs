@@ -15,15 +15,19 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use crate::cache::cache_manager::TableVersion;
 use crate::cache::CacheAccessor;
 
-use datafusion_common::Statistics;
+use arrow::array::RecordBatch;
+use datafusion_common::{HashMap, Statistics, TableReference};
 
 use dashmap::DashMap;
 use object_store::path::Path;
 use object_store::ObjectMeta;
+use parking_lot::Mutex;
 
 /// Collected statistics for files
 /// Cache is invalided when file size or last modification has changed
@@ -157,13 +161,273 @@ impl CacheAccessor<Path, Arc<Vec<ObjectMeta>>> for DefaultListFilesCache {
     }
 }
 
+/// Statistics of tables computed by `ANALYZE TABLE`, keyed by the resolved
+/// name of the table.
+/// Cache is invalidated when the table is replaced, or when its schema or one
+/// of its files has changed, see [`TableVersion::is_valid_for`].
+#[derive(Default)]
+pub struct DefaultTableStatisticsCache {
+    statistics: DashMap<TableReference, (TableVersion, Arc<Statistics>)>,
+}
+
+impl CacheAccessor<TableReference, Arc<Statistics>> for DefaultTableStatisticsCache {
+    type Extra = TableVersion;
+
+    /// Get `Statistics` of a table.
+    fn get(&self, k: &TableReference) -> Option<Arc<Statistics>> {
+        self.statistics.get(k).map(|s| Arc::clone(&s.value().1))
+    }
+
+    /// Get `Statistics` of a table. Returns None if the table has changed or
+    /// it is not found.
+    fn get_with_extra(
+        &self,
+        k: &TableReference,
+        e: &Self::Extra,
+    ) -> Option<Arc<Statistics>> {
+        self.statistics.get(k).and_then(|s| {
+            let (saved_version, statistics) = s.value();
+            saved_version
+                .is_valid_for(e)
+                .then(|| Arc::clone(statistics))
+        })
+    }
+
+    fn put(
+        &self,
+        _key: &TableReference,
+        _value: Arc<Statistics>,
+    ) -> Option<Arc<Statistics>> {
+        panic!("Put cache in DefaultTableStatisticsCache without Extra not supported.")
+    }
+
+    fn put_with_extra(
+        &self,
+        key: &TableReference,
+        value: Arc<Statistics>,
+        e: &Self::Extra,
+    ) -> Option<Arc<Statistics>> {
+        self.statistics
+            .insert(key.clone(), (e.clone(), value))
+            .map(|x| x.1)
+    }
+
+    fn remove(&mut self, k: &TableReference) -> Option<Arc<Statistics>> {
+        self.statistics.remove(k).map(|x| x.1 .1)
+    }
+
+    fn contains_key(&self, k: &TableReference) -> bool {
+        self.statistics.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.statistics.len()
+    }
+
+    fn clear(&self) {
+        self.statistics.clear()
+    }
+
+    fn name(&self) -> String {
+        "DefaultTableStatisticsCache".to_string()
+    }
+}
+
+/// Results of queries and subplans, keyed by their normalized physical plan.
+/// Cache is invalidated when the size or the last modification of one of the
+/// files read by the plan has changed.
+///
+/// The least recently used results are evicted when the total size of the
+/// cached batches exceeds the limit of the cache. Results larger than the
+/// limit are not cached.
+pub struct DefaultQueryResultCache {
+    limit: usize,
+    state: Mutex<QueryResultCacheState>,
+}
+
+#[derive(Default)]
+struct QueryResultCacheState {
+    entries: HashMap<String, QueryResultEntry>,
+    /// The keys of the entries, by their last access
+    lru: BTreeMap<u64, String>,
+    next_access: u64,
+    size: usize,
+}
+
+struct QueryResultEntry {
+    files: Vec<ObjectMeta>,
+    batches: Arc<Vec<Vec<RecordBatch>>>,
+    size: usize,
+    last_access: u64,
+}
+
+impl DefaultQueryResultCache {
+    /// Create a new cache holding at most `limit` bytes of batches
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(QueryResultCacheState::default()),
+        }
+    }
+
+    /// The maximum size in bytes of the cached batches
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The total size in bytes of the cached batches
+    pub fn size(&self) -> usize {
+        self.state.lock().size
+    }
+}
+
+impl QueryResultCacheState {
+    /// Returns the batches of `key`, and marks them as the most recently used
+    fn get(
+        &mut self,
+        key: &str,
+        files: Option<&[ObjectMeta]>,
+    ) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        let access = self.next_access;
+        let entry = self.entries.get_mut(key)?;
+        if let Some(files) = files {
+            let unchanged = entry.files.len() == files.len()
+                && entry.files.iter().zip(files).all(|(saved, file)| {
+                    saved.location == file.location
+                        && saved.size == file.size
+                        && saved.last_modified == file.last_modified
+                });
+            if !unchanged {
+                return None;
+            }
+        }
+        self.lru.remove(&entry.last_access);
+        self.lru.insert(access, key.to_string());
+        entry.last_access = access;
+        self.next_access += 1;
+        Some(Arc::clone(&entry.batches))
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        let entry = self.entries.remove(key)?;
+        self.lru.remove(&entry.last_access);
+        self.size -= entry.size;
+        Some(entry.batches)
+    }
+
+    fn put(
+        &mut self,
+        limit: usize,
+        key: &str,
+        batches: Arc<Vec<Vec<RecordBatch>>>,
+        files: Vec<ObjectMeta>,
+    ) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        let old = self.remove(key);
+        let size = batches
+            .iter()
+            .flatten()
+            .map(|batch| batch.get_array_memory_size())
+            .sum::<usize>();
+        if size > limit {
+            return old;
+        }
+        // Evict the least recently used entries
+        while self.size + size > limit {
+            let Some((_, key)) = self.lru.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.size -= entry.size;
+            }
+        }
+        let access = self.next_access;
+        self.next_access += 1;
+        self.lru.insert(access, key.to_string());
+        self.size += size;
+        self.entries.insert(
+            key.to_string(),
+            QueryResultEntry {
+                files,
+                batches,
+                size,
+                last_access: access,
+            },
+        );
+        old
+    }
+}
+
+impl CacheAccessor<String, Arc<Vec<Vec<RecordBatch>>>> for DefaultQueryResultCache {
+    type Extra = Vec<ObjectMeta>;
+
+    /// Get the batches of a plan, whatever the versions of its files.
+    fn get(&self, k: &String) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        self.state.lock().get(k, None)
+    }
+
+    /// Get the batches of a plan. Returns None if one of its files has
+    /// changed or it is not found.
+    fn get_with_extra(
+        &self,
+        k: &String,
+        e: &Self::Extra,
+    ) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        self.state.lock().get(k, Some(e))
+    }
+
+    /// Save the batches of a plan which does not read any file
+    fn put(
+        &self,
+        key: &String,
+        value: Arc<Vec<Vec<RecordBatch>>>,
+    ) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        self.state.lock().put(self.limit, key, value, vec![])
+    }
+
+    fn put_with_extra(
+        &self,
+        key: &String,
+        value: Arc<Vec<Vec<RecordBatch>>>,
+        e: &Self::Extra,
+    ) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        self.state.lock().put(self.limit, key, value, e.clone())
+    }
+
+    fn remove(&mut self, k: &String) -> Option<Arc<Vec<Vec<RecordBatch>>>> {
+        self.state.lock().remove(k)
+    }
+
+    fn contains_key(&self, k: &String) -> bool {
+        self.state.lock().entries.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    fn clear(&self) {
+        *self.state.lock() = QueryResultCacheState::default();
+    }
+
+    fn name(&self) -> String {
+        "DefaultQueryResultCache".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::cache::cache_unit::{DefaultFileStatisticsCache, DefaultListFilesCache};
+    use std::sync::Arc;
+
+    use crate::cache::cache_manager::TableVersion;
+    use crate::cache::cache_unit::{
+        DefaultFileStatisticsCache, DefaultListFilesCache, DefaultQueryResultCache,
+        DefaultTableStatisticsCache,
+    };
     use crate::cache::CacheAccessor;
+    use arrow::array::{Int32Array, RecordBatch};
     use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
     use chrono::DateTime;
-    use datafusion_common::Statistics;
+    use datafusion_common::{Statistics, TableReference};
     use object_store::path::Path;
     use object_store::ObjectMeta;
 
@@ -232,4 +496,136 @@ mod tests {
             meta.clone()
         );
     }
+
+    #[test]
+    fn test_table_statistics_cache() {
+        let meta = ObjectMeta {
+            location: Path::from("test"),
+            last_modified: DateTime::parse_from_rfc3339("2022-09-27T22:36:00+02:00")
+                .unwrap()
+                .into(),
+            size: 1024,
+            e_tag: None,
+            version: None,
+        };
+        let table = TableReference::full("datafusion", "public", "t");
+        let provider = Arc::new("provider");
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let version = TableVersion::new(&provider, Arc::clone(&schema))
+            .with_files(vec![meta.clone()]);
+        let cache = DefaultTableStatisticsCache::default();
+        assert!(cache.get_with_extra(&table, &version).is_none());
+
+        cache.put_with_extra(&table, Statistics::new_unknown(&schema).into(), &version);
+        assert!(cache.get_with_extra(&table, &version).is_some());
+
+        // the files are not known
+        let unknown_files = TableVersion::new(&provider, Arc::clone(&schema));
+        assert!(cache.get_with_extra(&table, &unknown_files).is_some());
+
+        // a file has changed
+        let changed = TableVersion::new(&provider, Arc::clone(&schema)).with_files(vec![
+            ObjectMeta {
+                size: 2048,
+                ..meta.clone()
+            },
+        ]);
+        assert!(cache.get_with_extra(&table, &changed).is_none());
+
+        // a file was added
+        let added = TableVersion::new(&provider, Arc::clone(&schema)).with_files(vec![
+            meta.clone(),
+            ObjectMeta {
+                location: Path::from("test2"),
+                ..meta.clone()
+            },
+        ]);
+        assert!(cache.get_with_extra(&table, &added).is_none());
+
+        // table schema changed
+        let schema2 = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let version2 = TableVersion::new(&provider, schema2);
+        assert!(cache.get_with_extra(&table, &version2).is_none());
+
+        // table replaced
+        let provider2 = Arc::new("provider");
+        let replaced = TableVersion::new(&provider2, Arc::clone(&schema));
+        assert!(cache.get_with_extra(&table, &replaced).is_none());
+
+        // table dropped
+        drop(provider);
+        assert!(cache.get_with_extra(&table, &unknown_files).is_none());
+
+        // different table
+        let table2 = TableReference::full("datafusion", "public", "t2");
+        assert!(cache.get_with_extra(&table2, &replaced).is_none());
+    }
+
+    #[test]
+    fn test_query_result_cache() {
+        let meta = ObjectMeta {
+            location: Path::from("test"),
+            last_modified: DateTime::parse_from_rfc3339("2022-09-27T22:36:00+02:00")
+                .unwrap()
+                .into(),
+            size: 1024,
+            e_tag: None,
+            version: None,
+        };
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int32Array::from(vec![1; 100])) as _,
+        )])
+        .unwrap();
+        let batches = Arc::new(vec![vec![batch.clone()], vec![]]);
+        let size = batch.get_array_memory_size();
+
+        // room for two results
+        let cache = DefaultQueryResultCache::new(2 * size + 1);
+        let files = vec![meta.clone()];
+        let plan = "plan".to_string();
+        assert!(cache.get_with_extra(&plan, &files).is_none());
+
+        cache.put_with_extra(&plan, Arc::clone(&batches), &files);
+        assert_eq!(cache.get_with_extra(&plan, &files).unwrap(), batches);
+        assert_eq!(cache.size(), size);
+
+        // file size changed
+        let mut meta2 = meta.clone();
+        meta2.size = 2048;
+        assert!(cache.get_with_extra(&plan, &vec![meta2]).is_none());
+
+        // file last_modified changed
+        let mut meta2 = meta.clone();
+        meta2.last_modified = DateTime::parse_from_rfc3339("2022-09-27T22:40:00+02:00")
+            .unwrap()
+            .into();
+        assert!(cache.get_with_extra(&plan, &vec![meta2]).is_none());
+
+        // one more file
+        assert!(cache
+            .get_with_extra(&plan, &vec![meta.clone(), meta.clone()])
+            .is_none());
+
+        // the least recently used result is evicted
+        let (plan2, plan3) = ("plan2".to_string(), "plan3".to_string());
+        cache.put_with_extra(&plan2, Arc::clone(&batches), &files);
+        assert!(cache.get_with_extra(&plan, &files).is_some());
+        cache.put_with_extra(&plan3, Arc::clone(&batches), &files);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains_key(&plan));
+        assert!(!cache.contains_key(&plan2));
+        assert!(cache.contains_key(&plan3));
+        assert_eq!(cache.size(), 2 * size);
+
+        // a result larger than the limit is not cached
+        let large = Arc::new(vec![vec![batch.clone(), batch.clone(), batch]]);
+        cache.put_with_extra(&plan2, large, &files);
+        assert!(!cache.contains_key(&plan2));
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+    }
 }
@@ -0,0 +1,185 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CancellationToken`]: cancels the execution of a query
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use datafusion_common::{DataFusionError, Result};
+
+/// The reason why a query was cancelled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CancellationReason {
+    /// The query was cancelled with [`CancellationToken::cancel`]
+    Cancelled,
+    /// The query ran longer than `datafusion.execution.query_timeout`
+    Timeout(Duration),
+    /// The query exceeded a resource limit, described by the message
+    ResourcesExhausted(String),
+}
+
+impl CancellationReason {
+    /// Returns the error reported by the operators of a cancelled query
+    pub fn to_error(&self) -> DataFusionError {
+        match self {
+            Self::Cancelled => {
+                DataFusionError::Execution("The query was cancelled".to_string())
+            }
+            Self::Timeout(timeout) => DataFusionError::Execution(format!(
+                "The query timed out after {} ms. Try increasing `datafusion.execution.query_timeout`.",
+                timeout.as_millis()
+            )),
+            Self::ResourcesExhausted(message) => {
+                DataFusionError::ResourcesExhausted(message.clone())
+            }
+        }
+    }
+}
+
+/// Cancels the execution of the query of a [`TaskContext`]
+///
+/// Once cancelled, the operators of the query return the error of the
+/// [`CancellationReason`] at their next yield point, which stops the query
+/// even if its output stream is not dropped, e.g. when its partitions are
+/// executed on other tasks.
+///
+/// Cloned tokens share the same state. A [child token] is also cancelled
+/// when its parent is, but cancelling it does not cancel its parent.
+///
+/// [child token]: Self::child_token
+///
+/// [`TaskContext`]: crate::TaskContext
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    reason: OnceLock<CancellationReason>,
+    parent: Option<CancellationToken>,
+}
+
+impl CancellationToken {
+    /// Create a new token, that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new token, that is cancelled when this token is cancelled
+    pub fn child_token(&self) -> Self {
+        Self {
+            inner: Arc::new(CancellationState {
+                parent: Some(self.clone()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Cancel the query
+    pub fn cancel(&self) {
+        self.cancel_with_reason(CancellationReason::Cancelled)
+    }
+
+    /// Cancel the query for `reason`. Only the first reason is kept if the
+    /// query is cancelled several times.
+    pub fn cancel_with_reason(&self, reason: CancellationReason) {
+        let _ = self.inner.reason.set(reason);
+        self.inner.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns true if the query was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+            || self
+                .inner
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_cancelled())
+    }
+
+    /// Returns the reason why the query was cancelled, if it was
+    pub fn reason(&self) -> Option<&CancellationReason> {
+        if self.inner.cancelled.load(Ordering::Acquire) {
+            self.inner.reason.get()
+        } else {
+            self.inner
+                .parent
+                .as_ref()
+                .and_then(|parent| parent.reason())
+        }
+    }
+
+    /// Returns an error if the query was cancelled
+    pub fn check(&self) -> Result<()> {
+        match self.reason() {
+            Some(reason) => Err(reason.to_error()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let cloned = token.clone();
+        assert!(!cloned.is_cancelled());
+        cloned.check().unwrap();
+
+        token.cancel_with_reason(CancellationReason::Timeout(Duration::from_millis(10)));
+        // the first reason is kept
+        token.cancel();
+        assert!(cloned.is_cancelled());
+        assert_eq!(
+            cloned.reason(),
+            Some(&CancellationReason::Timeout(Duration::from_millis(10)))
+        );
+        assert_eq!(
+            cloned.check().unwrap_err().strip_backtrace(),
+            "Execution error: The query timed out after 10 ms. Try increasing `datafusion.execution.query_timeout`."
+        );
+    }
+
+    #[test]
+    fn child_token() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+
+        // cancelling the child does not cancel the parent
+        child.cancel_with_reason(CancellationReason::ResourcesExhausted(
+            "limit".to_string(),
+        ));
+        assert!(!parent.is_cancelled());
+        assert_eq!(
+            child.reason(),
+            Some(&CancellationReason::ResourcesExhausted("limit".to_string()))
+        );
+
+        // cancelling the parent cancels its children
+        let child = parent.child_token();
+        parent.cancel();
+        assert!(child.is_cancelled());
+        assert_eq!(child.reason(), Some(&CancellationReason::Cancelled));
+    }
+}
@@ -23,12 +23,16 @@ use datafusion_common::{
 use log::debug;
 use parking_lot::Mutex;
 use rand::{rng, Rng};
+use std::any::Any;
+use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tempfile::{Builder, NamedTempFile, TempDir};
 
 use crate::memory_pool::human_readable_size;
+use crate::spill_storage::{SpillFile, SpillFileWriter, SpillStorage};
 
 const DEFAULT_MAX_TEMP_DIRECTORY_SIZE: u64 = 100 * 1024 * 1024 * 1024; // 100GB
 
@@ -73,27 +77,30 @@ impl DiskManagerBuilder {
     /// Create a DiskManager given the builder
     pub fn build(self) -> Result<DiskManager> {
         match self.mode {
-            DiskManagerMode::OsTmpDirectory => Ok(DiskManager {
-                local_dirs: Mutex::new(Some(vec![])),
-                max_temp_directory_size: self.max_temp_directory_size,
-                used_disk_space: Arc::new(AtomicU64::new(0)),
-            }),
+            DiskManagerMode::OsTmpDirectory => Ok(DiskManager::new(
+                Some(vec![]),
+                None,
+                self.max_temp_directory_size,
+            )),
             DiskManagerMode::Directories(conf_dirs) => {
                 let local_dirs = create_local_dirs(conf_dirs)?;
                 debug!(
                     "Created local dirs {local_dirs:?} as DataFusion working directory"
                 );
-                Ok(DiskManager {
-                    local_dirs: Mutex::new(Some(local_dirs)),
-                    max_temp_directory_size: self.max_temp_directory_size,
-                    used_disk_space: Arc::new(AtomicU64::new(0)),
-                })
+                Ok(DiskManager::new(
+                    Some(local_dirs),
+                    None,
+                    self.max_temp_directory_size,
+                ))
+            }
+            DiskManagerMode::SpillStorage(spill_storage) => Ok(DiskManager::new(
+                None,
+                Some(spill_storage),
+                self.max_temp_directory_size,
+            )),
+            DiskManagerMode::Disabled => {
+                Ok(DiskManager::new(None, None, self.max_temp_directory_size))
             }
-            DiskManagerMode::Disabled => Ok(DiskManager {
-                local_dirs: Mutex::new(None),
-                max_temp_directory_size: self.max_temp_directory_size,
-                used_disk_space: Arc::new(AtomicU64::new(0)),
-            }),
         }
     }
 }
@@ -109,6 +116,10 @@ pub enum DiskManagerMode {
     /// at random for each temporary file created.
     Directories(Vec<PathBuf>),
 
+    /// Create a new [DiskManager] that creates temporary files in the
+    /// specified [`SpillStorage`], such as an object store
+    SpillStorage(Arc<dyn SpillStorage>),
+
     /// Disable disk manager, attempts to create temporary files will error
     Disabled,
 }
@@ -170,8 +181,14 @@ pub struct DiskManager {
     /// TempDirs to put temporary files in.
     ///
     /// If `Some(vec![])` a new OS specified temporary directory will be created
-    /// If `None` an error will be returned (configured not to spill)
+    /// If `None` the files are created in `spill_storage` if any, otherwise
+    /// an error will be returned (configured not to spill)
     local_dirs: Mutex<Option<Vec<Arc<TempDir>>>>,
+    /// The storage to create temporary files in, instead of the local directories
+    spill_storage: Option<Arc<dyn SpillStorage>>,
+    /// The disk manager creating the files of a scoped disk manager, see
+    /// [`Self::scoped`]
+    parent: Option<Arc<DiskManager>>,
     /// The maximum amount of data (in bytes) stored inside the temporary directories.
     /// Default to 100GB
     max_temp_directory_size: u64,
@@ -181,6 +198,20 @@ pub struct DiskManager {
 }
 
 impl DiskManager {
+    fn new(
+        local_dirs: Option<Vec<Arc<TempDir>>>,
+        spill_storage: Option<Arc<dyn SpillStorage>>,
+        max_temp_directory_size: u64,
+    ) -> Self {
+        Self {
+            local_dirs: Mutex::new(local_dirs),
+            spill_storage,
+            parent: None,
+            max_temp_directory_size,
+            used_disk_space: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
     /// Creates a builder for [DiskManager]
     pub fn builder() -> DiskManagerBuilder {
         DiskManagerBuilder::default()
@@ -192,27 +223,27 @@ impl DiskManager {
     pub fn try_new(config: DiskManagerConfig) -> Result<Arc<Self>> {
         match config {
             DiskManagerConfig::Existing(manager) => Ok(manager),
-            DiskManagerConfig::NewOs => Ok(Arc::new(Self {
-                local_dirs: Mutex::new(Some(vec![])),
-                max_temp_directory_size: DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
-                used_disk_space: Arc::new(AtomicU64::new(0)),
-            })),
+            DiskManagerConfig::NewOs => Ok(Arc::new(Self::new(
+                Some(vec![]),
+                None,
+                DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
+            ))),
             DiskManagerConfig::NewSpecified(conf_dirs) => {
                 let local_dirs = create_local_dirs(conf_dirs)?;
                 debug!(
                     "Created local dirs {local_dirs:?} as DataFusion working directory"
                 );
-                Ok(Arc::new(Self {
-                    local_dirs: Mutex::new(Some(local_dirs)),
-                    max_temp_directory_size: DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
-                    used_disk_space: Arc::new(AtomicU64::new(0)),
-                }))
+                Ok(Arc::new(Self::new(
+                    Some(local_dirs),
+                    None,
+                    DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
+                )))
             }
-            DiskManagerConfig::Disabled => Ok(Arc::new(Self {
-                local_dirs: Mutex::new(None),
-                max_temp_directory_size: DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
-                used_disk_space: Arc::new(AtomicU64::new(0)),
-            })),
+            DiskManagerConfig::Disabled => Ok(Arc::new(Self::new(
+                None,
+                None,
+                DEFAULT_MAX_TEMP_DIRECTORY_SIZE,
+            ))),
         }
     }
 
@@ -222,7 +253,7 @@ impl DiskManager {
     ) -> Result<()> {
         // If the disk manager is disabled and `max_temp_directory_size` is not 0,
         // this operation is not meaningful, fail early.
-        if self.local_dirs.lock().is_none() && max_temp_directory_size != 0 {
+        if !self.tmp_files_enabled() && max_temp_directory_size != 0 {
             return config_err!(
                 "Cannot set max temp directory size for a disk manager that spilling is disabled"
             );
@@ -260,7 +291,36 @@ impl DiskManager {
     /// files. If this returns false, any call to `create_tmp_file`
     /// will error.
     pub fn tmp_files_enabled(&self) -> bool {
-        self.local_dirs.lock().is_some()
+        match &self.parent {
+            Some(parent) => parent.tmp_files_enabled(),
+            None => self.spill_storage.is_some() || self.local_dirs.lock().is_some(),
+        }
+    }
+
+    /// Return true if this disk manager supports creating temporary files in
+    /// local directories. If this returns false, any call to
+    /// `create_local_tmp_file` will error.
+    pub fn local_tmp_files_enabled(&self) -> bool {
+        match &self.parent {
+            Some(parent) => parent.local_tmp_files_enabled(),
+            None => self.local_dirs.lock().is_some(),
+        }
+    }
+
+    /// Returns a [`DiskManager`] creating its temporary files with this disk
+    /// manager, that additionally limits the data stored in its own files to
+    /// `max_temp_directory_size` bytes.
+    ///
+    /// This is used to limit the files of a single query, see
+    /// `datafusion.execution.max_spill_size_per_query`.
+    pub fn scoped(self: &Arc<Self>, max_temp_directory_size: u64) -> Arc<Self> {
+        Arc::new(Self {
+            local_dirs: Mutex::new(None),
+            spill_storage: None,
+            parent: Some(Arc::clone(self)),
+            max_temp_directory_size,
+            used_disk_space: Arc::new(AtomicU64::new(0)),
+        })
     }
 
     /// Return a temporary file from a randomized choice in the configured locations
@@ -271,6 +331,42 @@ impl DiskManager {
         self: &Arc<Self>,
         request_description: &str,
     ) -> Result<RefCountedTempFile> {
+        Ok(RefCountedTempFile {
+            file: self.create_spill_file(request_description, false)?,
+            current_file_disk_usage: 0,
+            disk_manager: Arc::clone(self),
+        })
+    }
+
+    /// Return a temporary file in the configured local directories, even if
+    /// a [`SpillStorage`] is configured
+    ///
+    /// This is used by the operators that read their spill files on their
+    /// async tasks, as the [`SpillFile`] readers of other storages may block
+    /// to fetch the data.
+    pub fn create_local_tmp_file(
+        self: &Arc<Self>,
+        request_description: &str,
+    ) -> Result<RefCountedTempFile> {
+        Ok(RefCountedTempFile {
+            file: self.create_spill_file(request_description, true)?,
+            current_file_disk_usage: 0,
+            disk_manager: Arc::clone(self),
+        })
+    }
+
+    fn create_spill_file(
+        &self,
+        request_description: &str,
+        local: bool,
+    ) -> Result<Box<dyn SpillFile>> {
+        if let Some(parent) = &self.parent {
+            return parent.create_spill_file(request_description, local);
+        }
+        if let Some(spill_storage) = self.spill_storage.as_ref().filter(|_| !local) {
+            return spill_storage.create_file(request_description);
+        }
+
         let mut guard = self.local_dirs.lock();
         let local_dirs = guard.as_mut().ok_or_else(|| {
             resources_datafusion_err!(
@@ -292,19 +388,78 @@ impl DiskManager {
         }
 
         let dir_index = rng().random_range(0..local_dirs.len());
-        Ok(RefCountedTempFile {
+        Ok(Box::new(LocalSpillFile {
             _parent_temp_dir: Arc::clone(&local_dirs[dir_index]),
             tempfile: Builder::new()
                 .tempfile_in(local_dirs[dir_index].as_ref())
                 .map_err(DataFusionError::IoError)?,
-            current_file_disk_usage: 0,
-            disk_manager: Arc::clone(self),
-        })
+        }))
+    }
+
+    /// Updates the used disk space for a file whose size changed from
+    /// `old_size` to `new_size`, returning an error if a limit is exceeded
+    fn update_used_disk_space(&self, old_size: u64, new_size: u64) -> Result<()> {
+        self.used_disk_space.fetch_sub(old_size, Ordering::Relaxed);
+        self.used_disk_space.fetch_add(new_size, Ordering::Relaxed);
+        let parent_result = match &self.parent {
+            Some(parent) => parent.update_used_disk_space(old_size, new_size),
+            None => Ok(()),
+        };
+
+        if self.used_disk_space() > self.max_temp_directory_size {
+            let limit = human_readable_size(self.max_temp_directory_size as usize);
+            return if self.parent.is_some() {
+                resources_err!(
+                    "The used disk space during the spilling process of the query has exceeded the allowable limit of {limit}. Try increasing `datafusion.execution.max_spill_size_per_query`."
+                )
+            } else {
+                resources_err!(
+                    "The used disk space during the spilling process has exceeded the allowable limit of {limit}. Try increasing the `max_temp_directory_size` in the disk manager configuration."
+                )
+            };
+        }
+        parent_result
+    }
+}
+
+/// A temporary file in a local directory
+#[derive(Debug)]
+struct LocalSpillFile {
+    /// The reference to the directory in which temporary files are created to ensure
+    /// it is not cleaned up prior to the NamedTempFile
+    _parent_temp_dir: Arc<TempDir>,
+    tempfile: NamedTempFile,
+}
+
+impl SpillFile for LocalSpillFile {
+    fn path(&self) -> &Path {
+        self.tempfile.path()
+    }
+
+    fn writer(&self) -> Result<Box<dyn SpillFileWriter>> {
+        let path = self.path();
+        let file = File::create(path).map_err(|e| {
+            resources_datafusion_err!("Failed to create spill file at {path:?}: {e:?}")
+        })?;
+        Ok(Box::new(file))
+    }
+
+    fn reader(&self) -> Result<Box<dyn Read + Send>> {
+        Ok(Box::new(File::open(self.path())?))
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.tempfile.as_file().metadata()?.len())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
 
-/// A wrapper around a [`NamedTempFile`] that also contains
-/// a reference to its parent temporary directory.
+/// A temporary file created by a [`DiskManager`], either a [`NamedTempFile`]
+/// that also contains a reference to its parent temporary directory, or a
+/// [`SpillFile`] of the configured [`SpillStorage`].
 ///
 /// # Note
 /// After any modification to the underlying file (e.g., writing data to it), the caller
@@ -313,10 +468,7 @@ impl DiskManager {
 /// [`DiskManager::with_max_temp_directory_size`].
 #[derive(Debug)]
 pub struct RefCountedTempFile {
-    /// The reference to the directory in which temporary files are created to ensure
-    /// it is not cleaned up prior to the NamedTempFile
-    _parent_temp_dir: Arc<TempDir>,
-    tempfile: NamedTempFile,
+    file: Box<dyn SpillFile>,
     /// Tracks the current disk usage of this temporary file. See
     /// [`Self::update_disk_usage`] for more details.
     current_file_disk_usage: u64,
@@ -325,12 +477,44 @@ pub struct RefCountedTempFile {
 }
 
 impl RefCountedTempFile {
+    /// Returns the location of the file, see [`SpillFile::path`]
     pub fn path(&self) -> &Path {
-        self.tempfile.path()
+        self.file.path()
     }
 
+    /// Returns the [`NamedTempFile`] of the file
+    ///
+    /// # Panics
+    /// If the file was created in a [`SpillStorage`], see
+    /// [`Self::local_file`] and [`Self::spill_file`]
     pub fn inner(&self) -> &NamedTempFile {
-        &self.tempfile
+        self.local_file()
+            .expect("the spill file was created in a SpillStorage")
+    }
+
+    /// Returns the [`NamedTempFile`], or `None` if the file was created in a
+    /// [`SpillStorage`]
+    pub fn local_file(&self) -> Option<&NamedTempFile> {
+        self.file
+            .as_any()
+            .downcast_ref::<LocalSpillFile>()
+            .map(|file| &file.tempfile)
+    }
+
+    /// Returns the [`SpillFile`] storing the data, either a local temporary
+    /// file or a file of the configured [`SpillStorage`]
+    pub fn spill_file(&self) -> &dyn SpillFile {
+        self.file.as_ref()
+    }
+
+    /// Returns a writer replacing the content of the file
+    pub fn writer(&self) -> Result<Box<dyn SpillFileWriter>> {
+        self.file.writer()
+    }
+
+    /// Returns a reader of the content of the file, from its start
+    pub fn reader(&self) -> Result<Box<dyn Read + Send>> {
+        self.file.reader()
     }
 
     /// Updates the global disk usage counter after modifications to the underlying file.
@@ -338,33 +522,19 @@ impl RefCountedTempFile {
     /// # Errors
     /// - Returns an error if the global disk usage exceeds the configured limit.
     pub fn update_disk_usage(&mut self) -> Result<()> {
-        // Get new file size from OS
-        let metadata = self.tempfile.as_file().metadata()?;
-        let new_disk_usage = metadata.len();
-
-        // Update the global disk usage by:
-        // 1. Subtracting the old file size from the global counter
-        self.disk_manager
-            .used_disk_space
-            .fetch_sub(self.current_file_disk_usage, Ordering::Relaxed);
-        // 2. Adding the new file size to the global counter
-        self.disk_manager
-            .used_disk_space
-            .fetch_add(new_disk_usage, Ordering::Relaxed);
-
-        // 3. Check if the updated global disk usage exceeds the configured limit
-        let global_disk_usage = self.disk_manager.used_disk_space.load(Ordering::Relaxed);
-        if global_disk_usage > self.disk_manager.max_temp_directory_size {
-            return resources_err!(
-                "The used disk space during the spilling process has exceeded the allowable limit of {}. Try increasing the `max_temp_directory_size` in the disk manager configuration.",
-                human_readable_size(self.disk_manager.max_temp_directory_size as usize)
-            );
-        }
+        // Get new file size from the storage
+        let new_disk_usage = self.file.size()?;
+
+        // Update the global disk usage, and check if it exceeds the configured limits
+        let result = self
+            .disk_manager
+            .update_used_disk_space(self.current_file_disk_usage, new_disk_usage);
 
-        // 4. Update the local file size tracking
+        // Update the local file size tracking, even if a limit is exceeded, so
+        // that the file's disk usage is subtracted when it is dropped
         self.current_file_disk_usage = new_disk_usage;
 
-        Ok(())
+        result
     }
 
     pub fn current_disk_usage(&self) -> u64 {
@@ -376,9 +546,9 @@ impl RefCountedTempFile {
 impl Drop for RefCountedTempFile {
     fn drop(&mut self) {
         // Subtract the current file's disk usage from the global counter
-        self.disk_manager
-            .used_disk_space
-            .fetch_sub(self.current_file_disk_usage, Ordering::Relaxed);
+        let _ = self
+            .disk_manager
+            .update_used_disk_space(self.current_file_disk_usage, 0);
     }
 }
 
@@ -402,6 +572,9 @@ fn create_local_dirs(local_dirs: Vec<PathBuf>) -> Result<Vec<Arc<TempDir>>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spill_storage::ObjectStoreSpillStorage;
+    use object_store::memory::InMemory;
+    use std::io::Write;
 
     #[test]
     fn lazy_temp_dir_creation() -> Result<()> {
@@ -447,9 +620,12 @@ mod tests {
         );
 
         assert!(dm.tmp_files_enabled());
+        assert!(dm.local_tmp_files_enabled());
         let actual = dm.create_tmp_file("Testing")?;
 
         // the file should be in one of the specified local directories
+        assert_path_in_dirs(actual.path(), local_dirs.iter().copied());
+        let actual = dm.create_local_tmp_file("Testing")?;
         assert_path_in_dirs(actual.path(), local_dirs.into_iter());
 
         Ok(())
@@ -479,6 +655,71 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_spill_storage_disk_manager() -> Result<()> {
+        let storage =
+            ObjectStoreSpillStorage::new(Arc::new(InMemory::new()), "spill".into());
+        let dm = Arc::new(
+            DiskManagerBuilder::default()
+                .with_mode(DiskManagerMode::SpillStorage(Arc::new(storage)))
+                .build()?,
+        );
+        assert!(dm.tmp_files_enabled());
+        // the files are not created in local directories
+        assert!(!dm.local_tmp_files_enabled());
+        assert!(dm.create_local_tmp_file("Testing").is_err());
+
+        let mut file = dm.create_tmp_file("Testing")?;
+        assert!(file.local_file().is_none());
+        assert_eq!(file.spill_file().path(), file.path());
+        let mut writer = file.writer()?;
+        writer.write_all(b"spilled")?;
+        writer.finish()?;
+        file.update_disk_usage()?;
+        assert_eq!(dm.used_disk_space(), 7);
+
+        let mut content = String::new();
+        file.reader()?.read_to_string(&mut content)?;
+        assert_eq!(content, "spilled");
+
+        drop(file);
+        assert_eq!(dm.used_disk_space(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scoped_disk_manager_limit() -> Result<()> {
+        let dm = Arc::new(DiskManagerBuilder::default().build()?);
+        let scoped = dm.scoped(10);
+        assert!(scoped.tmp_files_enabled());
+
+        let mut file = scoped.create_tmp_file("Testing")?;
+        let mut writer = file.writer()?;
+        writer.write_all(&[0; 8])?;
+        writer.finish()?;
+        file.update_disk_usage()?;
+        assert_eq!(scoped.used_disk_space(), 8);
+        assert_eq!(dm.used_disk_space(), 8);
+
+        writer.write_all(&[0; 8])?;
+        writer.finish()?;
+        assert_eq!(
+            file.update_disk_usage().unwrap_err().strip_backtrace(),
+            "Resources exhausted: The used disk space during the spilling process of the query has exceeded the allowable limit of 10.0 B. Try increasing `datafusion.execution.max_spill_size_per_query`."
+        );
+
+        // the files of the disk manager are not limited by the scoped limit
+        let mut other_file = dm.create_tmp_file("Testing")?;
+        other_file.writer()?.write_all(&[0; 16])?;
+        other_file.update_disk_usage()?;
+        assert_eq!(dm.used_disk_space(), 32);
+
+        drop(file);
+        assert_eq!(scoped.used_disk_space(), 0);
+        assert_eq!(dm.used_disk_space(), 16);
+        Ok(())
+    }
+
     /// Asserts that `file_path` is found anywhere in any of `dir` directories
     fn assert_path_in_dirs<'a>(
         file_path: &'a Path,
@@ -27,11 +27,14 @@
 //! DataFusion execution configuration and runtime structures
 
 pub mod cache;
+pub mod cancellation;
 pub mod config;
 pub mod disk_manager;
 pub mod memory_pool;
 pub mod object_store;
+pub mod progress;
 pub mod runtime_env;
+pub mod spill_storage;
 mod stream;
 mod task;
 
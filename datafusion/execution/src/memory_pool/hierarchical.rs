@@ -0,0 +1,682 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`HierarchicalMemoryPool`] divides a memory limit between a tree of
+//! sub-pools, such as one sub-pool per tenant and one per query.
+
+use std::sync::Arc;
+
+use crate::cancellation::{CancellationReason, CancellationToken};
+use crate::memory_pool::pool::insufficient_capacity_err;
+use crate::memory_pool::{
+    human_readable_size, MemoryLimit, MemoryPool, MemoryReservation,
+};
+use datafusion_common::{resources_datafusion_err, HashMap, Result};
+use log::debug;
+use parking_lot::Mutex;
+
+/// What a [`HierarchicalMemoryPool`] does when a reservation can not grow
+/// without exceeding a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OvercommitPolicy {
+    /// Fail the reservation that asked for more memory
+    #[default]
+    Fail,
+    /// Cancel the sub-pools with a lower priority than the sub-pool that asked
+    /// for more memory, lowest priority first, until the memory they use
+    /// covers the request, and the queries of their
+    /// [`SubPoolOptions::with_cancellation_token`]. The reservation only fails
+    /// if that is not enough.
+    CancelLowerPriority,
+}
+
+/// The options of a sub-pool of a [`HierarchicalMemoryPool`]
+#[derive(Debug, Clone)]
+pub struct SubPoolOptions {
+    name: String,
+    min_share: usize,
+    max_share: Option<usize>,
+    priority: i32,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl SubPoolOptions {
+    /// Create the options of a sub-pool named `name`, without a minimum or
+    /// maximum share and with priority `0`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            min_share: 0,
+            max_share: None,
+            priority: 0,
+            cancellation_token: None,
+        }
+    }
+
+    /// The number of bytes guaranteed to the sub-pool. The other sub-pools
+    /// of the same parent can not reserve memory that would leave less than
+    /// this to the sub-pool.
+    pub fn with_min_share(mut self, min_share: usize) -> Self {
+        self.min_share = min_share;
+        self
+    }
+
+    /// The maximum number of bytes the sub-pool, including its own sub-pools,
+    /// can reserve
+    pub fn with_max_share(mut self, max_share: usize) -> Self {
+        self.max_share = Some(max_share);
+        self
+    }
+
+    /// The priority of the sub-pool. When the memory is exhausted, the
+    /// sub-pools with a lower priority are asked to spill, or cancelled
+    /// according to the [`OvercommitPolicy`].
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The token cancelling the query of the sub-pool when the sub-pool is
+    /// cancelled, see [`OvercommitPolicy::CancelLowerPriority`]
+    pub fn with_cancellation_token(
+        mut self,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+}
+
+/// A [`MemoryPool`] that divides a process-wide limit between a hierarchy of
+/// sub-pools, for example one sub-pool per tenant with one sub-pool per query
+/// of that tenant.
+///
+/// Each sub-pool is itself a [`MemoryPool`]. A sub-pool has a name, a
+/// priority, an optional maximum share that limits the memory it can reserve
+/// and a minimum share that the other sub-pools of its parent can not use.
+///
+/// When a pool is the memory pool of a [`RuntimeEnv`], each query executed
+/// with the runtime reserves its memory in a sub-pool of its own, see
+/// [`MemoryPool::query_pool`]. The priority of the sub-pool is
+/// `datafusion.execution.query_priority`, and cancelling the sub-pool cancels
+/// the query with the [`CancellationToken`] of its [`TaskContext`]. The root
+/// pool, or a sub-pool per tenant, is therefore typically used as the memory
+/// pool of the runtime.
+///
+/// ```text
+///                  ┌────────────────────────┐
+///                  │   root (pool_size)     │
+///                  └────────────────────────┘
+///                     ▲                 ▲
+///          ┌──────────┴─────┐     ┌─────┴──────────┐
+///          │ tenant a       │     │ tenant b       │
+///          │ max_share      │     │ min_share      │
+///          └────────────────┘     └────────────────┘
+///             ▲          ▲                ▲
+///      ┌──────┴───┐ ┌────┴─────┐    ┌─────┴────┐
+///      │ query 1  │ │ query 2  │    │ query 3  │
+///      │ priority │ │ priority │    │ priority │
+///      └──────────┘ └──────────┘    └──────────┘
+/// ```
+///
+/// When a reservation can not grow, the pools that do not have children (the
+/// queries) with a lower priority than the pool of the reservation are:
+///
+/// * asked to spill: the next attempts of their spillable reservations to grow
+///   fail until they have freed the requested amount of memory, so that the
+///   operators spill
+///
+/// * cancelled, if the [`OvercommitPolicy`] is
+///   [`OvercommitPolicy::CancelLowerPriority`]: the memory they use is no
+///   longer counted towards the limits, their queries are cancelled with
+///   their [`CancellationToken`], and every attempt of their reservations to
+///   grow fails with an error explaining the cancellation. The pool is
+///   overcommitted until the cancelled queries are dropped.
+///
+/// [`RuntimeEnv`]: crate::runtime_env::RuntimeEnv
+/// [`TaskContext`]: crate::TaskContext
+#[derive(Debug)]
+pub struct HierarchicalMemoryPool {
+    shared: Arc<SharedState>,
+    id: usize,
+    /// Keeps the parent pool registered while this pool is used
+    _parent: Option<Arc<HierarchicalMemoryPool>>,
+}
+
+#[derive(Debug)]
+struct SharedState {
+    pool_size: usize,
+    policy: OvercommitPolicy,
+    tree: Mutex<PoolTree>,
+}
+
+#[derive(Debug, Default)]
+struct PoolTree {
+    nodes: HashMap<usize, PoolNode>,
+    next_id: usize,
+}
+
+#[derive(Debug)]
+struct PoolNode {
+    name: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    min_share: usize,
+    max_share: Option<usize>,
+    priority: i32,
+    /// Memory reserved by the pool and its sub-pools
+    used: usize,
+    /// Memory reserved by the spillable consumers of the pool
+    spillable: usize,
+    /// Memory the spillable consumers of the pool were asked to free
+    spill_requested: usize,
+    /// The reason the pool was cancelled
+    cancelled: Option<String>,
+    /// Cancels the query of the pool when the pool is cancelled
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl HierarchicalMemoryPool {
+    /// Create the root of a hierarchy of pools that can allocate up to
+    /// `pool_size` bytes, failing the reservations that exceed a limit
+    pub fn new(pool_size: usize) -> Self {
+        Self::new_with_policy(pool_size, OvercommitPolicy::default())
+    }
+
+    /// Create the root of a hierarchy of pools that can allocate up to
+    /// `pool_size` bytes, using `policy` when a reservation exceeds a limit
+    pub fn new_with_policy(pool_size: usize, policy: OvercommitPolicy) -> Self {
+        debug!("Created new HierarchicalMemoryPool(pool_size={pool_size}, policy={policy:?})");
+        let mut tree = PoolTree::default();
+        let id = tree.insert(PoolNode::new(&SubPoolOptions::new("root"), None));
+        Self {
+            shared: Arc::new(SharedState {
+                pool_size,
+                policy,
+                tree: Mutex::new(tree),
+            }),
+            id,
+            _parent: None,
+        }
+    }
+
+    /// Create a sub-pool of this pool
+    pub fn sub_pool(self: &Arc<Self>, options: SubPoolOptions) -> Arc<Self> {
+        let mut tree = self.shared.tree.lock();
+        let id = tree.insert(PoolNode::new(&options, Some(self.id)));
+        tree.node_mut(self.id).children.push(id);
+        Arc::new(Self {
+            shared: Arc::clone(&self.shared),
+            id,
+            _parent: Some(Arc::clone(self)),
+        })
+    }
+
+    /// Returns the name of this pool
+    pub fn name(&self) -> String {
+        self.shared.tree.lock().node(self.id).name.clone()
+    }
+
+    /// Returns the reason this pool, or one of its parents, was cancelled
+    pub fn cancellation(&self) -> Option<String> {
+        let tree = self.shared.tree.lock();
+        tree.path(self.id)
+            .into_iter()
+            .find_map(|id| tree.node(id).cancelled.clone())
+    }
+
+    /// Returns the memory the spillable consumers of this pool were asked to
+    /// free for pools with a higher priority
+    pub fn spill_requested(&self) -> usize {
+        self.shared.tree.lock().node(self.id).spill_requested
+    }
+}
+
+impl Drop for HierarchicalMemoryPool {
+    fn drop(&mut self) {
+        let mut tree = self.shared.tree.lock();
+        if let Some(node) = tree.nodes.remove(&self.id) {
+            if let Some(parent) = node.parent.and_then(|id| tree.nodes.get_mut(&id)) {
+                parent.children.retain(|&id| id != self.id);
+            }
+        }
+    }
+}
+
+impl MemoryPool for HierarchicalMemoryPool {
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        let mut tree = self.shared.tree.lock();
+        tree.grow(self.id, additional, reservation.consumer().can_spill());
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        let mut tree = self.shared.tree.lock();
+        tree.shrink(self.id, shrink, reservation.consumer().can_spill());
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let mut tree = self.shared.tree.lock();
+        let path = tree.path(self.id);
+        if let Some(reason) = path.iter().find_map(|&id| tree.node(id).cancelled.clone())
+        {
+            return Err(resources_datafusion_err!(
+                "Failed to allocate additional {} for {}: {reason}",
+                human_readable_size(additional),
+                reservation.consumer().name()
+            ));
+        }
+
+        let can_spill = reservation.consumer().can_spill();
+        let node = tree.node(self.id);
+        if can_spill && reservation.size() > 0 && node.spill_requested > 0 {
+            return Err(resources_datafusion_err!(
+                "Failed to allocate additional {} for {}: memory pool '{}' was asked to spill {} for pools with a higher priority",
+                human_readable_size(additional),
+                reservation.consumer().name(),
+                node.name,
+                human_readable_size(node.spill_requested)
+            ));
+        }
+
+        let mut shortfall = tree.shortfall(&path, additional, self.shared.pool_size);
+        if self.shared.policy == OvercommitPolicy::CancelLowerPriority {
+            while let Some((limiting_id, _)) = shortfall {
+                if !tree.cancel_lowest_priority(&path, limiting_id) {
+                    break;
+                }
+                shortfall = tree.shortfall(&path, additional, self.shared.pool_size);
+            }
+        }
+        if let Some((limiting_id, available)) = shortfall {
+            tree.request_spill(&path, limiting_id, additional.saturating_sub(available));
+            let limiting = tree.node(limiting_id);
+            if limiting.parent.is_none() {
+                return Err(insufficient_capacity_err(
+                    reservation,
+                    additional,
+                    available,
+                ));
+            }
+            return Err(resources_datafusion_err!(
+                "Failed to allocate additional {} for {} with {} already allocated for this reservation - {} remain available for memory pool '{}'",
+                human_readable_size(additional),
+                reservation.consumer().name(),
+                human_readable_size(reservation.size()),
+                human_readable_size(available),
+                limiting.name
+            ));
+        }
+
+        tree.grow(self.id, additional, can_spill);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.shared.tree.lock().node(self.id).used
+    }
+
+    fn memory_limit(&self) -> MemoryLimit {
+        let tree = self.shared.tree.lock();
+        let limit = tree
+            .path(self.id)
+            .into_iter()
+            .filter_map(|id| tree.node(id).max_share)
+            .fold(self.shared.pool_size, usize::min);
+        MemoryLimit::Finite(limit)
+    }
+
+    /// Returns a sub-pool of this pool for the query
+    fn query_pool(
+        self: Arc<Self>,
+        name: String,
+        priority: i32,
+        cancellation_token: CancellationToken,
+    ) -> Option<Arc<dyn MemoryPool>> {
+        let options = SubPoolOptions::new(name)
+            .with_priority(priority)
+            .with_cancellation_token(cancellation_token);
+        Some(self.sub_pool(options))
+    }
+}
+
+impl PoolNode {
+    fn new(options: &SubPoolOptions, parent: Option<usize>) -> Self {
+        Self {
+            name: options.name.clone(),
+            parent,
+            children: vec![],
+            min_share: options.min_share,
+            max_share: options.max_share,
+            priority: options.priority,
+            used: 0,
+            spillable: 0,
+            spill_requested: 0,
+            cancelled: None,
+            cancellation_token: options.cancellation_token.clone(),
+        }
+    }
+}
+
+impl PoolTree {
+    fn insert(&mut self, node: PoolNode) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(id, node);
+        id
+    }
+
+    fn node(&self, id: usize) -> &PoolNode {
+        &self.nodes[&id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut PoolNode {
+        self.nodes.get_mut(&id).expect("memory pool is registered")
+    }
+
+    /// Returns the ids of the pool and of its parents, up to the root
+    fn path(&self, id: usize) -> Vec<usize> {
+        std::iter::successors(Some(id), |&id| self.node(id).parent).collect()
+    }
+
+    /// Returns the pools whose memory is accounted for in their parents: the
+    /// path up to and including the first cancelled pool
+    fn accounted_path(&self, id: usize) -> Vec<usize> {
+        let mut path = self.path(id);
+        if let Some(idx) = path
+            .iter()
+            .position(|&id| self.node(id).cancelled.is_some())
+        {
+            path.truncate(idx + 1);
+        }
+        path
+    }
+
+    /// The part of the minimum shares of the pool and of its sub-pools that is
+    /// not used yet
+    fn unused_min_share(&self, id: usize) -> usize {
+        let node = self.node(id);
+        if node.cancelled.is_some() {
+            return 0;
+        }
+        let children = node
+            .children
+            .iter()
+            .map(|&child| self.unused_min_share(child))
+            .sum::<usize>();
+        node.min_share.saturating_sub(node.used).max(children)
+    }
+
+    fn grow(&mut self, id: usize, additional: usize, can_spill: bool) {
+        if can_spill {
+            self.node_mut(id).spillable += additional;
+        }
+        for id in self.accounted_path(id) {
+            self.node_mut(id).used += additional;
+        }
+    }
+
+    fn shrink(&mut self, id: usize, shrink: usize, can_spill: bool) {
+        let node = self.node_mut(id);
+        if can_spill {
+            node.spillable -= shrink;
+        }
+        node.spill_requested = node.spill_requested.saturating_sub(shrink);
+        for id in self.accounted_path(id) {
+            self.node_mut(id).used -= shrink;
+        }
+    }
+
+    /// Returns the pool whose limit would be exceeded by growing the pool at
+    /// the start of `path` by `additional` bytes, and the memory that is
+    /// still available in that pool
+    fn shortfall(
+        &self,
+        path: &[usize],
+        additional: usize,
+        pool_size: usize,
+    ) -> Option<(usize, usize)> {
+        let mut shortfall: Option<(usize, usize, usize)> = None;
+        // The unused minimum shares of the other sub-pools below the current
+        // pool, which can not be used
+        let mut reserved_for_others = 0;
+        for (idx, &id) in path.iter().enumerate() {
+            let node = self.node(id);
+            if let Some(child_idx) = idx.checked_sub(1) {
+                reserved_for_others += node
+                    .children
+                    .iter()
+                    .filter(|&&child| child != path[child_idx])
+                    .map(|&child| self.unused_min_share(child))
+                    .sum::<usize>();
+            }
+            let limit = match (node.parent, node.max_share) {
+                (None, Some(max_share)) => max_share.min(pool_size),
+                (None, None) => pool_size,
+                (Some(_), Some(max_share)) => max_share,
+                (Some(_), None) => continue,
+            };
+            let used = node.used + reserved_for_others;
+            let missing = (used + additional).saturating_sub(limit);
+            if missing > shortfall.map_or(0, |(_, _, missing)| missing) {
+                shortfall = Some((id, limit.saturating_sub(used), missing));
+            }
+        }
+        shortfall.map(|(id, available, _)| (id, available))
+    }
+
+    /// Returns the pools without sub-pools under `limiting_id`, that are not
+    /// cancelled and have a lower priority than the first pool of `path`
+    fn lower_priority_leaves(&self, path: &[usize], limiting_id: usize) -> Vec<usize> {
+        let priority = self.node(path[0]).priority;
+        let mut leaves = self
+            .nodes
+            .iter()
+            .filter(|(id, node)| {
+                node.children.is_empty()
+                    && node.cancelled.is_none()
+                    && node.priority < priority
+                    && !path.contains(id)
+                    && self.path(**id).contains(&limiting_id)
+            })
+            .map(|(id, node)| (node.priority, std::cmp::Reverse(node.used), *id))
+            .collect::<Vec<_>>();
+        leaves.sort_unstable();
+        leaves.into_iter().map(|(_, _, id)| id).collect()
+    }
+
+    /// Cancels the lowest priority pool under `limiting_id` that uses memory,
+    /// returning false if there is none
+    fn cancel_lowest_priority(&mut self, path: &[usize], limiting_id: usize) -> bool {
+        let Some(victim_id) = self
+            .lower_priority_leaves(path, limiting_id)
+            .into_iter()
+            .find(|id| self.node(*id).used > 0)
+        else {
+            return false;
+        };
+
+        let requester = self.node(path[0]);
+        let victim = self.node(victim_id);
+        let reason = format!(
+            "memory pool '{}' with priority {} was cancelled to free memory for memory pool '{}' with priority {}",
+            victim.name, victim.priority, requester.name, requester.priority
+        );
+        debug!("HierarchicalMemoryPool: {reason}");
+
+        // The memory of the cancelled pool is no longer accounted for in its
+        // parents, which are overcommitted until it is freed
+        let used = victim.used;
+        for id in self.path(victim_id).into_iter().skip(1) {
+            self.node_mut(id).used -= used;
+        }
+        let victim = self.node_mut(victim_id);
+        if let Some(cancellation_token) = &victim.cancellation_token {
+            cancellation_token.cancel_with_reason(
+                CancellationReason::ResourcesExhausted(reason.clone()),
+            );
+        }
+        victim.cancelled = Some(reason);
+        true
+    }
+
+    /// Asks the spillable consumers of the lower priority pools under
+    /// `limiting_id` to free `requested` bytes
+    fn request_spill(&mut self, path: &[usize], limiting_id: usize, requested: usize) {
+        let mut remaining = requested;
+        for id in self.lower_priority_leaves(path, limiting_id) {
+            if remaining == 0 {
+                break;
+            }
+            let node = self.node_mut(id);
+            let spill = node.spillable.saturating_sub(node.spill_requested);
+            let spill = spill.min(remaining);
+            node.spill_requested += spill;
+            remaining -= spill;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_pool::MemoryConsumer;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_sub_pool_max_share() {
+        let root = Arc::new(HierarchicalMemoryPool::new(100));
+        let tenant = root.sub_pool(SubPoolOptions::new("tenant").with_max_share(50));
+        let query = tenant.sub_pool(SubPoolOptions::new("query")) as Arc<dyn MemoryPool>;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&query);
+        r1.try_grow(40).unwrap();
+        assert_eq!(query.reserved(), 40);
+        assert_eq!(tenant.reserved(), 40);
+        assert_eq!(root.reserved(), 40);
+        assert!(matches!(query.memory_limit(), MemoryLimit::Finite(50)));
+
+        let err = r1.try_grow(20).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 20.0 B for r1 with 40.0 B already allocated for this reservation - 10.0 B remain available for memory pool 'tenant'");
+
+        drop(r1);
+        assert_eq!(root.reserved(), 0);
+    }
+
+    #[test]
+    fn test_sub_pool_min_share() {
+        let root = Arc::new(HierarchicalMemoryPool::new(100));
+        let q1 = root.sub_pool(SubPoolOptions::new("q1")) as Arc<dyn MemoryPool>;
+        let q2 = root.sub_pool(SubPoolOptions::new("q2").with_min_share(30))
+            as Arc<dyn MemoryPool>;
+
+        // 30 bytes are kept for q2
+        let mut r1 = MemoryConsumer::new("r1").register(&q1);
+        r1.try_grow(70).unwrap();
+        let err = r1.try_grow(1).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 1.0 B for r1 with 70.0 B already allocated for this reservation - 0.0 B remain available for the total pool");
+
+        let mut r2 = MemoryConsumer::new("r2").register(&q2);
+        r2.try_grow(30).unwrap();
+        assert_eq!(root.reserved(), 100);
+    }
+
+    #[test]
+    fn test_request_spill_from_lower_priority() {
+        let root = Arc::new(HierarchicalMemoryPool::new(100));
+        let low = root.sub_pool(SubPoolOptions::new("low"));
+        let high = root.sub_pool(SubPoolOptions::new("high").with_priority(1))
+            as Arc<dyn MemoryPool>;
+
+        let mut r1 = MemoryConsumer::new("r1")
+            .with_can_spill(true)
+            .register(&(Arc::clone(&low) as _));
+        r1.try_grow(80).unwrap();
+
+        let mut r2 = MemoryConsumer::new("r2").register(&high);
+        r2.try_grow(50).unwrap_err();
+        assert_eq!(low.spill_requested(), 30);
+
+        // The low priority reservation fails to grow, so that it spills
+        let err = r1.try_grow(10).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 10.0 B for r1: memory pool 'low' was asked to spill 30.0 B for pools with a higher priority");
+
+        r1.shrink(40);
+        assert_eq!(low.spill_requested(), 0);
+        r2.try_grow(50).unwrap();
+        assert_eq!(root.reserved(), 90);
+    }
+
+    #[test]
+    fn test_cancel_lower_priority() {
+        let root = Arc::new(HierarchicalMemoryPool::new_with_policy(
+            100,
+            OvercommitPolicy::CancelLowerPriority,
+        ));
+        let low = root.sub_pool(SubPoolOptions::new("low"));
+        let token = CancellationToken::new();
+        let lowest = root.sub_pool(
+            SubPoolOptions::new("lowest")
+                .with_priority(-1)
+                .with_cancellation_token(token.clone()),
+        );
+        let high = root.sub_pool(SubPoolOptions::new("high").with_priority(1))
+            as Arc<dyn MemoryPool>;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&(Arc::clone(&low) as _));
+        r1.try_grow(50).unwrap();
+        let mut r2 = MemoryConsumer::new("r2").register(&(Arc::clone(&lowest) as _));
+        r2.try_grow(40).unwrap();
+
+        // Only the lowest priority pool is cancelled
+        let mut r3 = MemoryConsumer::new("r3").register(&high);
+        r3.try_grow(40).unwrap();
+        assert!(low.cancellation().is_none());
+        assert_eq!(root.reserved(), 90);
+
+        // The query of the cancelled pool is cancelled
+        let err = token.check().unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: memory pool 'lowest' with priority -1 was cancelled to free memory for memory pool 'high' with priority 1");
+
+        let err = r2.try_grow(1).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 1.0 B for r2: memory pool 'lowest' with priority -1 was cancelled to free memory for memory pool 'high' with priority 1");
+
+        // The memory of the cancelled pool is freed when it is dropped
+        drop(r2);
+        assert_eq!(lowest.reserved(), 0);
+        assert_eq!(root.reserved(), 90);
+
+        // A pool with the same priority is not cancelled
+        let mut r4 = MemoryConsumer::new("r4").register(&(Arc::clone(&low) as _));
+        r4.try_grow(20).unwrap_err();
+        assert!(low.cancellation().is_none());
+    }
+
+    #[test]
+    fn test_drop_sub_pool() {
+        let root = Arc::new(HierarchicalMemoryPool::new(100));
+        let query = root.sub_pool(SubPoolOptions::new("query").with_min_share(50));
+        assert_eq!(root.shared.tree.lock().nodes.len(), 2);
+
+        drop(query);
+        assert_eq!(root.shared.tree.lock().nodes.len(), 1);
+
+        // The minimum share of the dropped pool is available again
+        let pool = Arc::clone(&root) as Arc<dyn MemoryPool>;
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        r1.try_grow(100).unwrap();
+    }
+}
@@ -18,10 +18,12 @@
 //! [`MemoryPool`] for memory management during query execution, [`proxy`] for
 //! help with allocation accounting.
 
+use crate::cancellation::CancellationToken;
 use datafusion_common::{internal_err, Result};
 use std::hash::{Hash, Hasher};
 use std::{cmp::Ordering, sync::atomic, sync::Arc};
 
+mod hierarchical;
 mod pool;
 pub mod proxy {
     pub use datafusion_common::utils::proxy::{
@@ -29,6 +31,7 @@ pub mod proxy {
     };
 }
 
+pub use hierarchical::*;
 pub use pool::*;
 
 /// Tracks and potentially limits memory use across operators during execution.
@@ -176,6 +179,10 @@ pub use pool::*;
 ///
 /// * [`TrackConsumersPool`]: Wraps another [`MemoryPool`] and tracks consumers,
 ///   providing better error messages on the largest memory users.
+///
+/// * [`HierarchicalMemoryPool`]: Divides a fixed size between a tree of
+///   sub-pools, for example per tenant and per query, with minimum and maximum
+///   shares and priorities.
 pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     /// Registers a new [`MemoryConsumer`]
     ///
@@ -213,6 +220,24 @@ pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     fn memory_limit(&self) -> MemoryLimit {
         MemoryLimit::Unknown
     }
+
+    /// Returns a pool of its own for the reservations of a single query,
+    /// named `name`, with priority `priority`
+    /// (`datafusion.execution.query_priority`), which cancels the query with
+    /// `cancellation_token` if the pool needs to free its memory.
+    ///
+    /// This is called for each [`TaskContext`]. The default implementation
+    /// returns `None`, so that the queries share this pool.
+    ///
+    /// [`TaskContext`]: crate::TaskContext
+    fn query_pool(
+        self: Arc<Self>,
+        _name: String,
+        _priority: i32,
+        _cancellation_token: CancellationToken,
+    ) -> Option<Arc<dyn MemoryPool>> {
+        None
+    }
 }
 
 /// Memory limit of `MemoryPool`
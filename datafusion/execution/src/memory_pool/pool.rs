@@ -255,7 +255,7 @@ impl MemoryPool for FairSpillPool {
 /// and not the total within the collective [`MemoryPool`],
 /// nor the total across multiple reservations with the same [`MemoryConsumer`].
 #[inline(always)]
-fn insufficient_capacity_err(
+pub(super) fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
     available: usize,
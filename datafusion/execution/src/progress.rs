@@ -0,0 +1,187 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ProgressRegistry`]: reports the progress of the running queries
+
+use std::fmt::Debug;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+/// A snapshot of the progress of a single operator of a running query
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorProgress {
+    /// The name of the operator, such as `FilterExec`
+    pub name: String,
+    /// The depth of the operator in the plan, 0 for the root operator
+    pub depth: usize,
+    /// Rows produced so far by all the partitions of the operator, if the
+    /// operator reports them
+    pub output_rows: Option<usize>,
+    /// Bytes read so far by the operator, for the operators scanning files
+    pub bytes_scanned: Option<usize>,
+    /// Total rows the operator is estimated to produce, from its statistics
+    pub estimated_rows: Option<usize>,
+    /// Number of times the operator spilled so far
+    pub spill_count: Option<usize>,
+    /// Memory currently used by the operator, if the operator reports it
+    pub memory_used: Option<usize>,
+}
+
+impl OperatorProgress {
+    /// Returns the produced fraction of the estimated rows of the operator,
+    /// between 0 and 1
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.output_rows, self.estimated_rows) {
+            (Some(_), Some(0)) => Some(1.0),
+            (Some(rows), Some(estimated)) => {
+                Some((rows as f64 / estimated as f64).min(1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A snapshot of the progress of a running query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryProgress {
+    /// The operators of the query, in depth-first order from the root
+    pub operators: Vec<OperatorProgress>,
+    /// Memory currently reserved in the memory pool of the query
+    pub memory_used: usize,
+    /// Time elapsed since the query started to execute
+    pub elapsed: Duration,
+    /// Estimated percentage of the query that completed, between 0 and
+    /// 100, if the rows of its inputs can be estimated
+    pub percent_complete: Option<f64>,
+}
+
+impl QueryProgress {
+    /// Returns the rows produced so far by the root operator
+    pub fn output_rows(&self) -> Option<usize> {
+        self.operators.first().and_then(|op| op.output_rows)
+    }
+
+    /// Returns the number of times the operators spilled so far
+    pub fn spill_count(&self) -> usize {
+        self.operators.iter().filter_map(|op| op.spill_count).sum()
+    }
+}
+
+/// A running query whose progress can be reported at any moment
+pub trait ProgressSource: Debug + Send + Sync {
+    /// Returns a snapshot of the current progress of the query
+    fn progress(&self) -> QueryProgress;
+}
+
+/// Keeps track of the running queries to report their progress
+///
+/// The queries are registered when their execution starts and are
+/// automatically removed once their [`ProgressSource`] is dropped, which
+/// usually happens when their output streams are dropped.
+///
+/// A [`TaskContext`] created from a `SessionState` shares the registry of the
+/// session, so the progress of all the queries of the session can be
+/// reported with `SessionContext::progress`.
+///
+/// [`TaskContext`]: crate::TaskContext
+#[derive(Debug, Default)]
+pub struct ProgressRegistry {
+    sources: Mutex<Vec<Weak<dyn ProgressSource>>>,
+}
+
+impl ProgressRegistry {
+    /// Create a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a running query, until `source` is dropped
+    pub fn register(&self, source: &Arc<dyn ProgressSource>) {
+        let mut sources = self.sources.lock();
+        sources.retain(|source| source.strong_count() > 0);
+        sources.push(Arc::downgrade(source));
+    }
+
+    /// Returns the progress of the running queries, in the order they started
+    pub fn progress(&self) -> Vec<QueryProgress> {
+        // the sources are upgraded before releasing the lock, as they may be
+        // dropped concurrently
+        let sources = self
+            .sources
+            .lock()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect::<Vec<_>>();
+        sources.iter().map(|source| source.progress()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct ConstantProgress(QueryProgress);
+
+    impl ProgressSource for ConstantProgress {
+        fn progress(&self) -> QueryProgress {
+            self.0.clone()
+        }
+    }
+
+    fn query_progress(output_rows: usize) -> QueryProgress {
+        QueryProgress {
+            operators: vec![OperatorProgress {
+                name: "TestExec".to_string(),
+                depth: 0,
+                output_rows: Some(output_rows),
+                bytes_scanned: None,
+                estimated_rows: Some(100),
+                spill_count: Some(1),
+                memory_used: None,
+            }],
+            memory_used: 0,
+            elapsed: Duration::ZERO,
+            percent_complete: Some(output_rows as f64),
+        }
+    }
+
+    #[test]
+    fn progress_registry() {
+        let registry = ProgressRegistry::new();
+        let first: Arc<dyn ProgressSource> =
+            Arc::new(ConstantProgress(query_progress(10)));
+        let second: Arc<dyn ProgressSource> =
+            Arc::new(ConstantProgress(query_progress(20)));
+        registry.register(&first);
+        registry.register(&second);
+
+        let progress = registry.progress();
+        assert_eq!(progress, vec![query_progress(10), query_progress(20)]);
+        assert_eq!(progress[0].output_rows(), Some(10));
+        assert_eq!(progress[0].spill_count(), 1);
+        assert_eq!(progress[1].operators[0].fraction(), Some(0.2));
+
+        // finished queries are no longer reported
+        drop(first);
+        assert_eq!(registry.progress(), vec![query_progress(20)]);
+        drop(second);
+        assert!(registry.progress().is_empty());
+    }
+}
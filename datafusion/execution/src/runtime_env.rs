@@ -29,6 +29,7 @@ use crate::{
 };
 
 use crate::cache::cache_manager::{CacheManager, CacheManagerConfig};
+use crate::cache::cache_unit::DefaultQueryResultCache;
 use datafusion_common::{config::ConfigEntry, Result};
 use object_store::ObjectStore;
 use std::path::PathBuf;
@@ -254,6 +255,14 @@ impl RuntimeEnvBuilder {
         )))
     }
 
+    /// Cache the results of queries and subplans, keeping at most `limit` bytes
+    /// of batches. See [`DefaultQueryResultCache`].
+    pub fn with_query_result_cache_limit(mut self, limit: usize) -> Self {
+        self.cache_manager.query_result_cache =
+            Some(Arc::new(DefaultQueryResultCache::new(limit)));
+        self
+    }
+
     /// Use the specified path to create any needed temporary files
     pub fn with_temp_file_path(self, path: impl Into<PathBuf>) -> Self {
         self.with_disk_manager_builder(
@@ -299,6 +308,10 @@ impl RuntimeEnvBuilder {
                 .cache_manager
                 .get_file_statistic_cache(),
             list_files_cache: runtime_env.cache_manager.get_list_files_cache(),
+            table_statistics_cache: runtime_env
+                .cache_manager
+                .get_table_statistics_cache(),
+            query_result_cache: runtime_env.cache_manager.get_query_result_cache(),
         };
 
         Self {
@@ -316,11 +329,19 @@ impl RuntimeEnvBuilder {
     /// Returns a list of all available runtime configurations with their current values and descriptions
     pub fn entries(&self) -> Vec<ConfigEntry> {
         // Memory pool configuration
-        vec![ConfigEntry {
-            key: "datafusion.runtime.memory_limit".to_string(),
-            value: None, // Default is system-dependent
-            description: "Maximum memory limit for query execution. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes). Example: '2G' for 2 gigabytes.",
-        }]
+        vec![
+            ConfigEntry {
+                key: "datafusion.runtime.memory_limit".to_string(),
+                value: None, // Default is system-dependent
+                description: "Maximum memory limit for query execution. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes). Example: '2G' for 2 gigabytes.",
+            },
+            // Query result cache configuration
+            ConfigEntry {
+                key: "datafusion.runtime.query_result_cache_limit".to_string(),
+                value: None, // Default is no query result cache
+                description: "Maximum size of the cached results of queries and subplans, used when `datafusion.execution.enable_result_cache` is set. The least recently used results are evicted. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes).",
+            },
+        ]
     }
 
     /// Generate documentation that can be included in the user guide
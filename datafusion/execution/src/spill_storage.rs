@@ -0,0 +1,749 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SpillStorage`]: Stores the files spilled during query execution
+//!
+//! This crate provides the local temporary files of the [`DiskManager`],
+//! [`MmapSpillStorage`] and [`ObjectStoreSpillStorage`]. Other backends can be
+//! plugged in by implementing [`SpillStorage`].
+//!
+//! [`DiskManager`]: crate::DiskManager
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::fs::File;
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use datafusion_common::{DataFusionError, Result};
+use futures::executor::block_on;
+use memmap2::Mmap;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use parking_lot::Mutex;
+use rand::{rng, Rng};
+use tempfile::{Builder, NamedTempFile};
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Default size of the objects written by [`ObjectStoreSpillStorage`]
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024; // 8MB
+
+/// Default number of chunks of a file that [`ObjectStoreSpillStorage`]
+/// uploads concurrently
+const DEFAULT_MAX_PENDING_UPLOADS: usize = 4;
+
+/// A storage for the files spilled during query execution, e.g. when
+/// sorting a dataset larger than the available memory.
+///
+/// By default, the [`DiskManager`] creates the spill files in local temporary
+/// directories. A [`SpillStorage`] set with [`DiskManagerMode::SpillStorage`]
+/// stores them elsewhere, such as in memory-mapped files with
+/// [`MmapSpillStorage`] or in an [`ObjectStore`] with
+/// [`ObjectStoreSpillStorage`].
+///
+/// [`DiskManager`]: crate::DiskManager
+/// [`DiskManagerMode::SpillStorage`]: crate::disk_manager::DiskManagerMode::SpillStorage
+pub trait SpillStorage: Debug + Send + Sync {
+    /// Creates a new empty spill file
+    ///
+    /// If the file can not be created for some reason, returns an
+    /// error message referencing the request description
+    fn create_file(&self, request_description: &str) -> Result<Box<dyn SpillFile>>;
+}
+
+/// A file created by a [`SpillStorage`]
+///
+/// A spill file is written once, read any number of times and deleted when
+/// it is dropped.
+///
+/// The files are written from the async tasks of the operators, so
+/// [`Self::writer`] and the returned writer should only wait for IO to bound
+/// the data they buffer, and never for a task of the runtime of the query.
+/// The readers may block, as the spill files are read on blocking threads, see
+/// [`tokio::task::spawn_blocking`].
+pub trait SpillFile: Debug + Send + Sync {
+    /// Returns the location of the file, which is a local path only for the
+    /// files of local storages
+    fn path(&self) -> &Path;
+
+    /// Returns a writer replacing the content of the file
+    fn writer(&self) -> Result<Box<dyn SpillFileWriter>>;
+
+    /// Returns a reader of the content of the file, from its start
+    fn reader(&self) -> Result<Box<dyn Read + Send>>;
+
+    /// Returns the number of bytes written to the file
+    fn size(&self) -> Result<u64>;
+
+    /// Returns the file as [`Any`] so that it can be downcast to a specific
+    /// implementation
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// A writer of a [`SpillFile`]
+pub trait SpillFileWriter: Write + Send {
+    /// Completes the file after all the data is written
+    fn finish(&mut self) -> Result<()>;
+}
+
+impl SpillFileWriter for File {
+    fn finish(&mut self) -> Result<()> {
+        self.flush().map_err(DataFusionError::IoError)
+    }
+}
+
+/// A [`SpillStorage`] reading the spill files through memory maps
+///
+/// The files are written in a local directory, like the local temporary files
+/// of the [`DiskManager`], but they are read by mapping them in memory. The
+/// operating system then pages the data in and out of its page cache, instead
+/// of copying it in the buffers of the readers.
+///
+/// [`DiskManager`]: crate::DiskManager
+#[derive(Debug)]
+pub struct MmapSpillStorage {
+    directory: PathBuf,
+}
+
+impl MmapSpillStorage {
+    /// Create a storage writing the spill files in the existing `directory`
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl SpillStorage for MmapSpillStorage {
+    fn create_file(&self, request_description: &str) -> Result<Box<dyn SpillFile>> {
+        let tempfile = Builder::new()
+            .prefix("datafusion")
+            .tempfile_in(&self.directory)
+            .map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Failed to create spill file in {:?} for {request_description}: {e}",
+                    self.directory
+                ))
+            })?;
+        Ok(Box::new(MmapSpillFile { tempfile }))
+    }
+}
+
+/// A [`SpillFile`] of a [`MmapSpillStorage`]
+#[derive(Debug)]
+struct MmapSpillFile {
+    /// Deletes the file at its path when dropped
+    tempfile: NamedTempFile,
+}
+
+impl SpillFile for MmapSpillFile {
+    fn path(&self) -> &Path {
+        self.tempfile.path()
+    }
+
+    /// Returns a writer of a new file at the path of the file, as the current
+    /// file may be mapped by readers and must not be truncated
+    fn writer(&self) -> Result<Box<dyn SpillFileWriter>> {
+        std::fs::remove_file(self.path())?;
+        Ok(Box::new(File::create_new(self.path())?))
+    }
+
+    fn reader(&self) -> Result<Box<dyn Read + Send>> {
+        let file = File::open(self.path())?;
+        // Empty files can not be mapped
+        if file.metadata()?.len() == 0 {
+            return Ok(Box::new(io::empty()));
+        }
+        // SAFETY: the spill files are private to the process and never
+        // truncated, as they are replaced by the new files of the writers
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Box::new(io::Cursor::new(mmap)))
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(std::fs::metadata(self.path())?.len())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A [`SpillStorage`] writing the spill files in an [`ObjectStore`]
+///
+/// Each file is written as a sequence of objects of up to `chunk_size` bytes
+/// under `prefix`. The objects are deleted when the file is dropped.
+///
+/// The object store requests of the files created in a Tokio runtime are run
+/// on a thread of the storage, so that the files can wait for them without
+/// blocking a task the requests depend on. The chunks are uploaded in the
+/// background while the next ones are written, up to `max_pending_uploads`
+/// chunks per file, after which the writer waits for the oldest upload to
+/// bound the memory used by the file. The readers of the file wait for the
+/// pending uploads before fetching the chunks one after the other. Outside a
+/// Tokio runtime, the requests are run to completion on the calling thread.
+///
+/// An [`InMemory`] or [`LocalFileSystem`] store can be used to test the
+/// storage locally.
+///
+/// [`InMemory`]: object_store::memory::InMemory
+/// [`LocalFileSystem`]: object_store::local::LocalFileSystem
+#[derive(Debug)]
+pub struct ObjectStoreSpillStorage {
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+    chunk_size: usize,
+    max_pending_uploads: usize,
+    /// The runtime running the requests, started with the first file created
+    /// in a Tokio runtime
+    io_runtime: Mutex<Option<Arc<IoRuntime>>>,
+}
+
+impl ObjectStoreSpillStorage {
+    /// Create a storage writing the spill files under `prefix` in `store`
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: ObjectPath) -> Self {
+        Self {
+            store,
+            prefix,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_pending_uploads: DEFAULT_MAX_PENDING_UPLOADS,
+            io_runtime: Mutex::new(None),
+        }
+    }
+
+    /// Set the maximum size of the objects written for each file
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Set the maximum number of chunks of a file uploaded concurrently
+    ///
+    /// The memory buffered by a file being written is bounded by
+    /// `max_pending_uploads` times the chunk size.
+    pub fn with_max_pending_uploads(mut self, max_pending_uploads: usize) -> Self {
+        self.max_pending_uploads = max_pending_uploads.max(1);
+        self
+    }
+
+    /// Returns the runtime running the requests of the files, starting it if
+    /// needed
+    fn io_runtime(&self) -> Result<Arc<IoRuntime>> {
+        let mut io_runtime = self.io_runtime.lock();
+        match io_runtime.as_ref() {
+            Some(io_runtime) => Ok(Arc::clone(io_runtime)),
+            None => Ok(Arc::clone(io_runtime.insert(IoRuntime::try_new()?))),
+        }
+    }
+}
+
+impl SpillStorage for ObjectStoreSpillStorage {
+    fn create_file(&self, _request_description: &str) -> Result<Box<dyn SpillFile>> {
+        let location = self
+            .prefix
+            .child(format!("datafusion-{:016x}", rng().random::<u64>()));
+        let runtime = match Handle::try_current() {
+            Ok(_) => Some(self.io_runtime()?),
+            Err(_) => None,
+        };
+        Ok(Box::new(ObjectStoreSpillFile {
+            requests: ObjectStoreRequests {
+                store: Arc::clone(&self.store),
+                runtime,
+            },
+            path: PathBuf::from(location.to_string()),
+            location,
+            chunk_size: self.chunk_size,
+            max_pending_uploads: self.max_pending_uploads,
+            state: Default::default(),
+        }))
+    }
+}
+
+/// A Tokio runtime running on its own thread
+///
+/// The thread stops once the runtime is dropped, i.e. when the storage, its
+/// files and their requests are all dropped.
+#[derive(Debug)]
+struct IoRuntime {
+    handle: Handle,
+    /// Stops the thread when dropped
+    _stop: oneshot::Sender<()>,
+}
+
+impl IoRuntime {
+    fn try_new() -> Result<Arc<Self>> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let handle = runtime.handle().clone();
+        let (stop, stopped) = oneshot::channel();
+        std::thread::Builder::new()
+            .name("datafusion-spill-io".to_string())
+            .spawn(move || {
+                let _ = runtime.block_on(stopped);
+            })?;
+        Ok(Arc::new(Self {
+            handle,
+            _stop: stop,
+        }))
+    }
+}
+
+/// Runs the requests of an [`ObjectStoreSpillFile`]
+#[derive(Debug, Clone)]
+struct ObjectStoreRequests {
+    store: Arc<dyn ObjectStore>,
+    /// The runtime the requests are spawned on, if any
+    runtime: Option<Arc<IoRuntime>>,
+}
+
+impl ObjectStoreRequests {
+    /// Spawns the request on the runtime, or runs it to completion if there
+    /// is no runtime
+    fn run<T, F>(&self, request: F) -> Request<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        match &self.runtime {
+            Some(runtime) => {
+                // The runtime is kept running until the request is completed
+                let keep_running = Arc::clone(runtime);
+                Request::Spawned(runtime.handle.spawn(async move {
+                    let _keep_running = keep_running;
+                    request.await
+                }))
+            }
+            None => Request::Done(block_on(request)),
+        }
+    }
+
+    /// Deletes the `chunks` of a file, after the pending `uploads` of the
+    /// chunks are completed
+    fn delete(&self, location: ObjectPath, chunks: usize, uploads: Vec<Request<()>>) {
+        let store = Arc::clone(&self.store);
+        let request = self.run(async move {
+            for upload in uploads {
+                // the chunk is deleted whether it was uploaded or not
+                let _ = upload.completed().await;
+            }
+            for chunk in 0..chunks {
+                store
+                    .delete(&chunk_location(&location, chunk))
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            }
+            Ok(())
+        });
+        // The deletion is not awaited if it was spawned
+        if let Request::Done(Err(e)) = request {
+            log::warn!("Failed to delete spill file: {e}");
+        }
+    }
+}
+
+/// A request that is either spawned on a runtime or already completed
+#[derive(Debug)]
+enum Request<T> {
+    Spawned(JoinHandle<Result<T>>),
+    Done(Result<T>),
+}
+
+impl<T> Request<T> {
+    async fn completed(self) -> Result<T> {
+        match self {
+            Self::Spawned(handle) => handle
+                .await
+                .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))?,
+            Self::Done(result) => result,
+        }
+    }
+
+    /// Blocks the current thread until the request is completed
+    ///
+    /// The requests never need the thread to make progress, as they run on
+    /// the thread of an [`IoRuntime`].
+    fn wait(self) -> Result<T> {
+        block_on(self.completed())
+    }
+}
+
+/// The content of an [`ObjectStoreSpillFile`]
+#[derive(Debug, Default)]
+struct ChunkedObjectState {
+    /// Incremented for each writer, so that the chunks of different writers
+    /// are written at different locations
+    version: u64,
+    /// Number of chunks written
+    chunks: usize,
+    /// Number of bytes written, including the bytes not yet uploaded
+    size: u64,
+    /// The uploads of the chunks that were not yet awaited
+    uploads: Vec<Request<()>>,
+}
+
+/// A [`SpillFile`] of an [`ObjectStoreSpillStorage`]
+#[derive(Debug)]
+struct ObjectStoreSpillFile {
+    requests: ObjectStoreRequests,
+    location: ObjectPath,
+    path: PathBuf,
+    chunk_size: usize,
+    max_pending_uploads: usize,
+    state: Arc<Mutex<ChunkedObjectState>>,
+}
+
+impl ObjectStoreSpillFile {
+    /// Deletes the current chunks of the file in the background and returns
+    /// the version of the next chunks
+    fn delete_chunks(&self) -> u64 {
+        let mut state = self.state.lock();
+        let location = version_location(&self.location, state.version);
+        let chunks = std::mem::take(&mut state.chunks);
+        let uploads = std::mem::take(&mut state.uploads);
+        state.size = 0;
+        state.version += 1;
+        if chunks > 0 || !uploads.is_empty() {
+            self.requests.delete(location, chunks, uploads);
+        }
+        state.version
+    }
+}
+
+impl SpillFile for ObjectStoreSpillFile {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn writer(&self) -> Result<Box<dyn SpillFileWriter>> {
+        let version = self.delete_chunks();
+        Ok(Box::new(ChunkedObjectWriter {
+            requests: self.requests.clone(),
+            location: version_location(&self.location, version),
+            version,
+            chunk_size: self.chunk_size,
+            max_pending_uploads: self.max_pending_uploads,
+            buffer: Vec::with_capacity(self.chunk_size),
+            state: Arc::clone(&self.state),
+        }))
+    }
+
+    /// Returns a reader that blocks the calling thread to fetch the chunks
+    fn reader(&self) -> Result<Box<dyn Read + Send>> {
+        let state = self.state.lock();
+        Ok(Box::new(ChunkedObjectReader {
+            requests: self.requests.clone(),
+            location: version_location(&self.location, state.version),
+            chunks: state.chunks,
+            uploaded: false,
+            state: Arc::clone(&self.state),
+            next_chunk: 0,
+            current: io::Cursor::new(Default::default()),
+        }))
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.state.lock().size)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Drop for ObjectStoreSpillFile {
+    fn drop(&mut self) {
+        self.delete_chunks();
+    }
+}
+
+fn version_location(location: &ObjectPath, version: u64) -> ObjectPath {
+    location.child(format!("{version:010}"))
+}
+
+fn chunk_location(location: &ObjectPath, chunk: usize) -> ObjectPath {
+    location.child(format!("{chunk:010}"))
+}
+
+/// Writes the data in chunks of `chunk_size` bytes
+struct ChunkedObjectWriter {
+    requests: ObjectStoreRequests,
+    /// The location of the chunks of this writer
+    location: ObjectPath,
+    version: u64,
+    chunk_size: usize,
+    max_pending_uploads: usize,
+    buffer: Vec<u8>,
+    state: Arc<Mutex<ChunkedObjectState>>,
+}
+
+impl ChunkedObjectWriter {
+    /// Uploads the buffered data as the next chunk, without waiting for the
+    /// upload to complete unless `max_pending_uploads` uploads are pending
+    fn put_chunk(&mut self) -> Result<()> {
+        let payload = PutPayload::from(std::mem::replace(
+            &mut self.buffer,
+            Vec::with_capacity(self.chunk_size),
+        ));
+        let mut state = self.state.lock();
+        if state.version != self.version {
+            return Err(DataFusionError::Execution(format!(
+                "Spill file {} was replaced by another writer",
+                self.location
+            )));
+        }
+        if state.uploads.len() >= self.max_pending_uploads {
+            state.uploads.remove(0).wait()?;
+        }
+        let location = chunk_location(&self.location, state.chunks);
+        let store = Arc::clone(&self.requests.store);
+        let upload = self.requests.run(async move {
+            store
+                .put(&location, payload)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(())
+        });
+        if let Request::Done(Err(e)) = upload {
+            return Err(e);
+        }
+        state.chunks += 1;
+        state.uploads.push(upload);
+        Ok(())
+    }
+}
+
+impl Write for ChunkedObjectWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.chunk_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..len]);
+        self.state.lock().size += len as u64;
+        if self.buffer.len() == self.chunk_size {
+            self.put_chunk().map_err(io::Error::other)?;
+        }
+        Ok(len)
+    }
+
+    /// The buffered data is only written by [`SpillFileWriter::finish`], to
+    /// avoid writing small objects every time the writer is flushed
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SpillFileWriter for ChunkedObjectWriter {
+    fn finish(&mut self) -> Result<()> {
+        if !self.buffer.is_empty() {
+            self.put_chunk()?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the chunks written by a [`ChunkedObjectWriter`] one after the other
+struct ChunkedObjectReader {
+    requests: ObjectStoreRequests,
+    location: ObjectPath,
+    chunks: usize,
+    /// Whether the pending uploads of the file were awaited
+    uploaded: bool,
+    state: Arc<Mutex<ChunkedObjectState>>,
+    next_chunk: usize,
+    current: io::Cursor<Bytes>,
+}
+
+impl ChunkedObjectReader {
+    /// Waits for the pending uploads of the chunks of the file
+    fn wait_for_uploads(&mut self) -> Result<()> {
+        // The lock is held so that concurrent readers don't fetch the chunks
+        // before they are uploaded
+        let mut state = self.state.lock();
+        for upload in std::mem::take(&mut state.uploads) {
+            upload.wait()?;
+        }
+        self.uploaded = true;
+        Ok(())
+    }
+}
+
+impl Read for ChunkedObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let len = self.current.read(buf)?;
+            if len > 0 || buf.is_empty() || self.next_chunk == self.chunks {
+                return Ok(len);
+            }
+            if !self.uploaded {
+                self.wait_for_uploads().map_err(io::Error::other)?;
+            }
+            let location = chunk_location(&self.location, self.next_chunk);
+            let store = Arc::clone(&self.requests.store);
+            let bytes = self
+                .requests
+                .run(async move {
+                    let bytes = async { store.get(&location).await?.bytes().await };
+                    bytes
+                        .await
+                        .map_err(|e| DataFusionError::External(Box::new(e)))
+                })
+                .wait()
+                .map_err(io::Error::other)?;
+            self.current = io::Cursor::new(bytes);
+            self.next_chunk += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use object_store::memory::InMemory;
+
+    fn write_file(file: &dyn SpillFile, data: &[u8]) -> Result<()> {
+        let mut writer = file.writer()?;
+        writer.write_all(data)?;
+        writer.finish()?;
+        assert_eq!(file.size()?, data.len() as u64);
+        Ok(())
+    }
+
+    fn read_to_end(file: &dyn SpillFile) -> Result<Vec<u8>> {
+        let mut read = vec![];
+        file.reader()?.read_to_end(&mut read)?;
+        Ok(read)
+    }
+
+    async fn list(store: &InMemory) -> usize {
+        store
+            .list(None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn mmap_spill_file_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = MmapSpillStorage::new(dir.path());
+
+        let file = storage.create_file("Testing")?;
+        assert!(file.path().starts_with(dir.path()));
+        assert_eq!(read_to_end(file.as_ref())?, b"");
+
+        let data = (0..=255).collect::<Vec<u8>>();
+        write_file(file.as_ref(), &data)?;
+        let mut reader = file.reader()?;
+
+        // rewriting the file does not change the data of the current readers
+        write_file(file.as_ref(), &data[..15])?;
+        assert_eq!(read_to_end(file.as_ref())?, &data[..15]);
+        let mut read = vec![];
+        reader.read_to_end(&mut read)?;
+        assert_eq!(read, data);
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn object_store_spill_file_round_trip() -> Result<()> {
+        let store = Arc::new(InMemory::new());
+        let storage = ObjectStoreSpillStorage::new(
+            Arc::clone(&store) as _,
+            ObjectPath::from("spill"),
+        )
+        .with_chunk_size(10);
+
+        let file = storage.create_file("Testing")?;
+        let data = (0..=255).collect::<Vec<u8>>();
+        write_file(file.as_ref(), &data)?;
+        assert_eq!(block_on(list(&store)), 26);
+
+        // the file can be read several times
+        for _ in 0..2 {
+            assert_eq!(read_to_end(file.as_ref())?, data);
+        }
+
+        // rewriting the file replaces its chunks
+        write_file(file.as_ref(), &data[..15])?;
+        assert_eq!(block_on(list(&store)), 2);
+        assert_eq!(read_to_end(file.as_ref())?, &data[..15]);
+
+        drop(file);
+        assert_eq!(block_on(list(&store)), 0);
+        Ok(())
+    }
+
+    /// The requests run on the thread of the storage, so that the file can
+    /// be written from an async task of a current thread runtime, and wait
+    /// for its pending uploads
+    #[tokio::test]
+    async fn object_store_spill_file_current_thread_runtime() -> Result<()> {
+        let store = Arc::new(InMemory::new());
+        let storage = ObjectStoreSpillStorage::new(
+            Arc::clone(&store) as _,
+            ObjectPath::from("spill"),
+        )
+        .with_chunk_size(10)
+        .with_max_pending_uploads(2);
+
+        let file = storage.create_file("Testing")?;
+        let data = (0..=255).collect::<Vec<u8>>();
+        write_file(file.as_ref(), &data)?;
+
+        // the writer waited for the oldest uploads
+        let pending_uploads = file
+            .as_any()
+            .downcast_ref::<ObjectStoreSpillFile>()
+            .unwrap()
+            .state
+            .lock()
+            .uploads
+            .len();
+        assert!(pending_uploads <= 2, "{pending_uploads} pending uploads");
+
+        #[allow(clippy::disallowed_methods)] // spawn allowed only in tests
+        let (file, read) = tokio::task::spawn_blocking(move || {
+            let read = read_to_end(file.as_ref());
+            (file, read)
+        })
+        .await
+        .unwrap();
+        assert_eq!(read?, data);
+        assert_eq!(list(&store).await, 26);
+
+        // the chunks are deleted in the background
+        drop(file);
+        for _ in 0..100 {
+            if list(&store).await == 0 {
+                return Ok(());
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the chunks of the spill file were not deleted");
+    }
+}
@@ -16,13 +16,18 @@
 // under the License.
 
 use crate::{
-    config::SessionConfig, memory_pool::MemoryPool, registry::FunctionRegistry,
+    cancellation::CancellationToken,
+    config::SessionConfig,
+    memory_pool::MemoryPool,
+    progress::{ProgressRegistry, QueryProgress},
+    registry::FunctionRegistry,
     runtime_env::RuntimeEnv,
 };
 use datafusion_common::{plan_datafusion_err, DataFusionError, Result};
 use datafusion_expr::planner::ExprPlanner;
 use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{collections::HashMap, sync::Arc};
 
 /// Task Execution Context
@@ -48,6 +53,16 @@ pub struct TaskContext {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// The runtime environment before it is scoped to the task, see
+    /// [`TaskContext::new`]
+    unscoped_runtime: Arc<RuntimeEnv>,
+    /// Running queries executed with this task context
+    progress_registry: Arc<ProgressRegistry>,
+    /// Cancels the query executed with this task context
+    cancellation_token: CancellationToken,
+    /// Bytes output by the data sources of the query executed with this task
+    /// context
+    scanned_bytes: Arc<AtomicUsize>,
 }
 
 impl Default for TaskContext {
@@ -62,7 +77,11 @@ impl Default for TaskContext {
             scalar_functions: HashMap::new(),
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
-            runtime,
+            runtime: Arc::clone(&runtime),
+            unscoped_runtime: runtime,
+            progress_registry: Arc::new(ProgressRegistry::new()),
+            cancellation_token: CancellationToken::new(),
+            scanned_bytes: Arc::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -72,6 +91,17 @@ impl TaskContext {
     ///
     /// Most users will use [`SessionContext::task_ctx`] to create [`TaskContext`]s
     ///
+    /// The runtime of the task is `runtime`, scoped to the task:
+    ///
+    /// * If `datafusion.execution.max_spill_size_per_query` is set, the disk
+    ///   manager is [scoped] to limit the spill files of the task.
+    ///
+    /// * If the memory pool creates a [query pool] for the task, with the
+    ///   priority `datafusion.execution.query_priority`, the memory of the task
+    ///   is reserved in that pool.
+    ///
+    /// [scoped]: crate::DiskManager::scoped
+    /// [query pool]: crate::memory_pool::MemoryPool::query_pool
     /// [`SessionContext::task_ctx`]: https://docs.rs/datafusion/latest/datafusion/execution/context/struct.SessionContext.html#method.task_ctx
     pub fn new(
         task_id: Option<String>,
@@ -85,12 +115,45 @@ impl TaskContext {
         Self {
             task_id,
             session_id,
+            runtime: Arc::clone(&runtime),
+            unscoped_runtime: runtime,
             session_config,
             scalar_functions,
             aggregate_functions,
             window_functions,
-            runtime,
+            progress_registry: Arc::new(ProgressRegistry::new()),
+            cancellation_token: CancellationToken::new(),
+            scanned_bytes: Arc::new(AtomicUsize::new(0)),
+        }
+        .scope_runtime()
+    }
+
+    /// Returns a [TaskContext] for one execution of a plan with this context
+    ///
+    /// The returned context has a child of the [`CancellationToken`] of this
+    /// context, so that it is cancelled when this context is, and counts the
+    /// scanned bytes from zero. Cancelling an execution, e.g. once it times
+    /// out, thus does not cancel the later executions with this context, and
+    /// the limit of the scanned bytes applies to each execution. Its runtime
+    /// is scoped to the execution like in [`Self::new`].
+    ///
+    /// Used by `execute_stream` and the other functions executing a whole
+    /// plan.
+    pub fn for_execution(&self) -> Self {
+        Self {
+            task_id: self.task_id.clone(),
+            session_id: self.session_id.clone(),
+            runtime: Arc::clone(&self.unscoped_runtime),
+            unscoped_runtime: Arc::clone(&self.unscoped_runtime),
+            session_config: self.session_config.clone(),
+            scalar_functions: self.scalar_functions.clone(),
+            aggregate_functions: self.aggregate_functions.clone(),
+            window_functions: self.window_functions.clone(),
+            progress_registry: Arc::clone(&self.progress_registry),
+            cancellation_token: self.cancellation_token.child_token(),
+            scanned_bytes: Arc::new(AtomicUsize::new(0)),
         }
+        .scope_runtime()
     }
 
     /// Return the SessionConfig associated with this [TaskContext]
@@ -118,6 +181,45 @@ impl TaskContext {
         Arc::clone(&self.runtime)
     }
 
+    /// Return the [`ProgressRegistry`] of the queries executed with this
+    /// [TaskContext]
+    pub fn progress_registry(&self) -> &Arc<ProgressRegistry> {
+        &self.progress_registry
+    }
+
+    /// Returns the progress of the queries currently executed with this
+    /// [TaskContext]
+    ///
+    /// The queries are registered by `execute_stream` and the other
+    /// functions executing a whole plan, until their output is dropped.
+    pub fn progress(&self) -> Vec<QueryProgress> {
+        self.progress_registry.progress()
+    }
+
+    /// Return the [`CancellationToken`] of the query executed with this
+    /// [TaskContext]
+    ///
+    /// Operators check the token at their yield points, and stop with an
+    /// error once it is cancelled. See [`Self::for_execution`] for the token
+    /// of each execution.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Adds `bytes` to the bytes output by the data sources of the query
+    /// executed with this [TaskContext], and returns the new total
+    ///
+    /// Used to enforce `datafusion.execution.max_scanned_bytes`.
+    pub fn add_scanned_bytes(&self, bytes: usize) -> usize {
+        self.scanned_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes
+    }
+
+    /// Returns the bytes output by the data sources of the query executed
+    /// with this [TaskContext] so far
+    pub fn scanned_bytes(&self) -> usize {
+        self.scanned_bytes.load(Ordering::Relaxed)
+    }
+
     pub fn scalar_functions(&self) -> &HashMap<String, Arc<ScalarUDF>> {
         &self.scalar_functions
     }
@@ -131,14 +233,65 @@ impl TaskContext {
     }
 
     /// Update the [`SessionConfig`]
+    ///
+    /// The runtime is scoped to the task again with `session_config`, see
+    /// [`Self::new`].
     pub fn with_session_config(mut self, session_config: SessionConfig) -> Self {
         self.session_config = session_config;
-        self
+        self.scope_runtime()
     }
 
     /// Update the [`RuntimeEnv`]
+    ///
+    /// `runtime` is scoped to the task like in [`Self::new`].
     pub fn with_runtime(mut self, runtime: Arc<RuntimeEnv>) -> Self {
-        self.runtime = runtime;
+        self.unscoped_runtime = runtime;
+        self.scope_runtime()
+    }
+
+    /// Update the [`ProgressRegistry`], e.g. to share the registry of a session
+    pub fn with_progress_registry(
+        mut self,
+        progress_registry: Arc<ProgressRegistry>,
+    ) -> Self {
+        self.progress_registry = progress_registry;
+        self
+    }
+
+    /// Update the [`CancellationToken`], e.g. to cancel a query from outside
+    /// of its execution
+    pub fn with_cancellation_token(
+        mut self,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        self.cancellation_token = cancellation_token;
+        self.scope_runtime()
+    }
+
+    /// Sets the runtime of the task to `unscoped_runtime` with a disk manager
+    /// and a memory pool of its own, if needed, see [`Self::new`]
+    fn scope_runtime(mut self) -> Self {
+        let runtime = &self.unscoped_runtime;
+        let options = &self.session_config.options().execution;
+        let disk_manager = options
+            .max_spill_size_per_query
+            .map(|max_size| runtime.disk_manager.scoped(max_size as u64));
+        let memory_pool = Arc::clone(&runtime.memory_pool).query_pool(
+            format!("query[{}]", self.session_id),
+            options.query_priority,
+            self.cancellation_token.clone(),
+        );
+        self.runtime = if disk_manager.is_none() && memory_pool.is_none() {
+            Arc::clone(runtime)
+        } else {
+            Arc::new(RuntimeEnv {
+                disk_manager: disk_manager
+                    .unwrap_or_else(|| Arc::clone(&runtime.disk_manager)),
+                memory_pool: memory_pool
+                    .unwrap_or_else(|| Arc::clone(&runtime.memory_pool)),
+                ..RuntimeEnv::clone(runtime)
+            })
+        };
         self
     }
 }
@@ -206,6 +359,8 @@ impl FunctionRegistry for TaskContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory_pool::{HierarchicalMemoryPool, MemoryConsumer, MemoryPool};
+    use crate::runtime_env::RuntimeEnvBuilder;
     use datafusion_common::{
         config::{ConfigExtension, ConfigOptions, Extensions},
         extensions_options,
@@ -287,4 +442,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn task_context_scoped_disk_manager() -> Result<()> {
+        let runtime = Arc::new(RuntimeEnv::default());
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.max_spill_size_per_query = Some(1024);
+        let is_scoped = |ctx: &TaskContext| {
+            !Arc::ptr_eq(&ctx.runtime_env().disk_manager, &runtime.disk_manager)
+        };
+
+        let ctx = TaskContext::default().with_runtime(Arc::clone(&runtime));
+        assert!(!is_scoped(&ctx));
+        let ctx = ctx.with_session_config(config.clone());
+        assert!(is_scoped(&ctx));
+        let ctx = ctx.with_runtime(Arc::clone(&runtime));
+        assert!(is_scoped(&ctx));
+        let ctx = ctx.with_session_config(SessionConfig::new());
+        assert!(!is_scoped(&ctx));
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_context_query_pool() -> Result<()> {
+        let pool = Arc::new(HierarchicalMemoryPool::new(100));
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::clone(&pool) as _)
+            .build_arc()?;
+        let ctx = TaskContext::default().with_runtime(runtime);
+
+        // The memory of the task is reserved in a sub-pool of the runtime's pool
+        let mut reservation = MemoryConsumer::new("test").register(ctx.memory_pool());
+        reservation.try_grow(10)?;
+        assert_eq!(pool.reserved(), 10);
+        assert!(!Arc::ptr_eq(
+            ctx.memory_pool(),
+            &(Arc::clone(&pool) as Arc<dyn MemoryPool>)
+        ));
+        Ok(())
+    }
 }
@@ -23,8 +23,8 @@ use std::fmt;
 use crate::{
     expr_vec_fmt, Aggregate, DescribeTable, Distinct, DistinctOn, DmlStatement, Expr,
     Filter, Join, Limit, LogicalPlan, Partitioning, Projection, RecursiveQuery,
-    Repartition, Sort, Subquery, SubqueryAlias, TableProviderFilterPushDown, TableScan,
-    Unnest, Values, Window,
+    Repartition, SharedSubplan, Sort, Subquery, SubqueryAlias,
+    TableProviderFilterPushDown, TableScan, Unnest, Values, Window,
 };
 
 use crate::dml::CopyTo;
@@ -323,6 +323,15 @@ impl<'a, 'b> PgJsonVisitor<'a, 'b> {
                     "Is Distinct": is_distinct,
                 })
             }
+            LogicalPlan::SharedSubplan(SharedSubplan {
+                id, materialized, ..
+            }) => {
+                json!({
+                    "Node Type": "SharedSubplan",
+                    "Id": id,
+                    "Materialized": materialized,
+                })
+            }
             LogicalPlan::Values(Values { ref values, .. }) => {
                 let str_values = values
                     .iter()
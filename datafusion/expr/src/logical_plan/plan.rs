@@ -288,6 +288,9 @@ pub enum LogicalPlan {
     Unnest(Unnest),
     /// A variadic query (e.g. "Recursive CTEs")
     RecursiveQuery(RecursiveQuery),
+    /// A subplan that is evaluated once and whose result is shared by all
+    /// of its references (e.g. `MATERIALIZED` CTEs)
+    SharedSubplan(SharedSubplan),
 }
 
 impl Default for LogicalPlan {
@@ -352,6 +355,7 @@ impl LogicalPlan {
                 // we take the schema of the static term as the schema of the entire recursive query
                 static_term.schema()
             }
+            LogicalPlan::SharedSubplan(SharedSubplan { input, .. }) => input.schema(),
         }
     }
 
@@ -474,6 +478,7 @@ impl LogicalPlan {
                 recursive_term,
                 ..
             }) => vec![static_term, recursive_term],
+            LogicalPlan::SharedSubplan(SharedSubplan { input, .. }) => vec![input],
             LogicalPlan::Statement(stmt) => stmt.inputs(),
             // plans without inputs
             LogicalPlan::TableScan { .. }
@@ -564,6 +569,9 @@ impl LogicalPlan {
             LogicalPlan::RecursiveQuery(RecursiveQuery { static_term, .. }) => {
                 static_term.head_output_expr()
             }
+            LogicalPlan::SharedSubplan(SharedSubplan { input, .. }) => {
+                input.head_output_expr()
+            }
             LogicalPlan::Union(union) => Ok(Some(Expr::Column(Column::from(
                 union.schema.qualified_field(0),
             )))),
@@ -734,6 +742,7 @@ impl LogicalPlan {
                 Ok(LogicalPlan::Distinct(distinct))
             }
             LogicalPlan::RecursiveQuery(_) => Ok(self),
+            LogicalPlan::SharedSubplan(_) => Ok(self),
             LogicalPlan::Analyze(_) => Ok(self),
             LogicalPlan::Explain(_) => Ok(self),
             LogicalPlan::TableScan(_) => Ok(self),
@@ -1073,6 +1082,17 @@ impl LogicalPlan {
                     is_distinct: *is_distinct,
                 }))
             }
+            LogicalPlan::SharedSubplan(SharedSubplan {
+                id, materialized, ..
+            }) => {
+                self.assert_no_expressions(expr)?;
+                let input = self.only_input(inputs)?;
+                Ok(LogicalPlan::SharedSubplan(SharedSubplan {
+                    id: *id,
+                    input: Arc::new(input),
+                    materialized: *materialized,
+                }))
+            }
             LogicalPlan::Analyze(a) => {
                 self.assert_no_expressions(expr)?;
                 let input = self.only_input(inputs)?;
@@ -1358,6 +1378,7 @@ impl LogicalPlan {
             LogicalPlan::TableScan(TableScan { fetch, .. }) => *fetch,
             LogicalPlan::EmptyRelation(_) => Some(0),
             LogicalPlan::RecursiveQuery(_) => None,
+            LogicalPlan::SharedSubplan(SharedSubplan { input, .. }) => input.max_rows(),
             LogicalPlan::Subquery(_) => None,
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => input.max_rows(),
             LogicalPlan::Limit(limit) => match limit.get_fetch_type() {
@@ -1726,6 +1747,15 @@ impl LogicalPlan {
                     }) => {
                         write!(f, "RecursiveQuery: is_distinct={is_distinct}")
                     }
+                    LogicalPlan::SharedSubplan(SharedSubplan {
+                        id, materialized, ..
+                    }) => {
+                        if *materialized {
+                            write!(f, "SharedSubplan: id={id}")
+                        } else {
+                            write!(f, "SharedSubplan: id={id}, materialized=false")
+                        }
+                    }
                     LogicalPlan::Values(Values { ref values, .. }) => {
                         let str_values: Vec<_> = values
                             .iter()
@@ -2092,6 +2122,39 @@ pub struct RecursiveQuery {
     pub is_distinct: bool,
 }
 
+/// A subplan that is evaluated once, with its result shared by every
+/// `SharedSubplan` node of the plan that has the same `id`.
+///
+/// This is used for common table expressions declared `MATERIALIZED` and
+/// for identical subplans found by the `ShareSubplans` analyzer rule. The
+/// node is a fence for the optimizer: nothing is pushed into the shared
+/// input, which would make the references compute different results.
+///
+/// A `NOT MATERIALIZED` common table expression is planned with
+/// `materialized` set to `false`, which only keeps its references from
+/// being shared; the node is removed by the analyzer.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct SharedSubplan {
+    /// Identifier of the shared result
+    pub id: usize,
+    /// The subplan to evaluate once
+    pub input: Arc<LogicalPlan>,
+    /// Whether the result is shared (`MATERIALIZED`) or the subplan is
+    /// inlined into each reference (`NOT MATERIALIZED`)
+    pub materialized: bool,
+}
+
+impl SharedSubplan {
+    /// Create a new shared subplan
+    pub fn new(id: usize, input: Arc<LogicalPlan>, materialized: bool) -> Self {
+        Self {
+            id,
+            input,
+            materialized,
+        }
+    }
+}
+
 /// Values expression. See
 /// [Postgres VALUES](https://www.postgresql.org/docs/current/queries-values.html)
 /// documentation for more details.
@@ -16,7 +16,7 @@
 // under the License.
 
 use arrow::datatypes::DataType;
-use datafusion_common::{DFSchema, DFSchemaRef};
+use datafusion_common::{DFSchema, DFSchemaRef, TableReference};
 use std::fmt::{self, Display};
 use std::sync::{Arc, LazyLock};
 
@@ -45,6 +45,9 @@ pub enum Statement {
     /// Deallocate a prepared statement.
     /// This is used to implement SQL 'DEALLOCATE'.
     Deallocate(Deallocate),
+    /// Compute the statistics of a table.
+    /// This is used to implement SQL 'ANALYZE TABLE'.
+    AnalyzeTable(AnalyzeTable),
 }
 
 impl Statement {
@@ -67,6 +70,7 @@ impl Statement {
             Statement::Prepare(_) => "Prepare",
             Statement::Execute(_) => "Execute",
             Statement::Deallocate(_) => "Deallocate",
+            Statement::AnalyzeTable(_) => "AnalyzeTable",
         }
     }
 
@@ -125,6 +129,16 @@ impl Statement {
                     Statement::Deallocate(Deallocate { name }) => {
                         write!(f, "Deallocate: {name}")
                     }
+                    Statement::AnalyzeTable(AnalyzeTable {
+                        table_name,
+                        columns,
+                    }) => {
+                        write!(f, "AnalyzeTable: {table_name}")?;
+                        if !columns.is_empty() {
+                            write!(f, " columns=[{}]", columns.join(", "))?;
+                        }
+                        Ok(())
+                    }
                 }
             }
         }
@@ -211,3 +225,13 @@ pub struct Deallocate {
     /// The name of the prepared statement to deallocate
     pub name: String,
 }
+
+/// Compute the statistics of a table, including the histograms and the most
+/// common values of its columns, and cache them for planning later queries.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
+pub struct AnalyzeTable {
+    /// The name of the table to analyze
+    pub table_name: TableReference,
+    /// The names of the columns to analyze, or all the columns if empty
+    pub columns: Vec<String>,
+}
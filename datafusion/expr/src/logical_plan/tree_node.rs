@@ -41,7 +41,7 @@ use crate::{
     dml::CopyTo, Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement,
     Distinct, DistinctOn, DmlStatement, Execute, Explain, Expr, Extension, Filter, Join,
     Limit, LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition,
-    Sort, Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest,
+    SharedSubplan, Sort, Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest,
     UserDefinedLogicalNode, Values, Window,
 };
 use datafusion_common::tree_node::TreeNodeRefContainer;
@@ -337,6 +337,17 @@ impl TreeNode for LogicalPlan {
                     })
                 },
             ),
+            LogicalPlan::SharedSubplan(SharedSubplan {
+                id,
+                input,
+                materialized,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::SharedSubplan(SharedSubplan {
+                    id,
+                    input,
+                    materialized,
+                })
+            }),
             LogicalPlan::Statement(stmt) => match stmt {
                 Statement::Prepare(p) => p
                     .input
@@ -464,6 +475,7 @@ impl LogicalPlan {
             // plans without expressions
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::SharedSubplan(_)
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Analyze(_)
@@ -644,6 +656,7 @@ impl LogicalPlan {
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::Unnest(_)
             | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::SharedSubplan(_)
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Analyze(_)
@@ -20,7 +20,7 @@
 use crate::{Expr, LogicalPlan};
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{Constraints, Result};
+use datafusion_common::{Constraints, Result, Statistics};
 
 use std::{any::Any, borrow::Cow};
 
@@ -129,4 +129,12 @@ pub trait TableSource: Sync + Send {
     fn get_column_default(&self, _column: &str) -> Option<&Expr> {
         None
     }
+
+    /// Get statistics for this table, if available.
+    ///
+    /// These are used during logical optimization, e.g. to estimate the
+    /// size of the inputs of joins when reordering them.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
 }
@@ -0,0 +1,472 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Planar (cartesian) geometry algorithms
+//!
+//! Coordinates are compared exactly, without tolerance, and the predicates
+//! follow the [DE-9IM] semantics of the OGC Simple Features specification.
+//!
+//! [DE-9IM]: https://en.wikipedia.org/wiki/DE-9IM
+
+use crate::geometry::{Components, Coord, Geometry, LineString};
+use std::f64::consts::PI;
+
+/// Location of a position relative to a geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Interior,
+    Boundary,
+    Exterior,
+}
+
+/// Returns the area of the polygons of `geometry`, minus the area of their
+/// holes
+pub fn area(geometry: &Geometry) -> f64 {
+    geometry
+        .components()
+        .polygons
+        .iter()
+        .map(|rings| polygon_area(rings))
+        .sum()
+}
+
+fn polygon_area(rings: &[LineString]) -> f64 {
+    match rings.split_first() {
+        Some((exterior, holes)) => {
+            ring_area(exterior) - holes.iter().map(|hole| ring_area(hole)).sum::<f64>()
+        }
+        None => 0.0,
+    }
+}
+
+/// Returns the unsigned area of a ring using the shoelace formula
+fn ring_area(ring: &[Coord]) -> f64 {
+    let twice_area: f64 = ring
+        .windows(2)
+        .map(|segment| segment[0].x * segment[1].y - segment[1].x * segment[0].y)
+        .sum();
+    twice_area.abs() / 2.0
+}
+
+/// Returns the location of `coord` relative to `geometry`
+pub fn locate(coord: Coord, geometry: &Geometry) -> Location {
+    locate_in_components(coord, &geometry.components())
+}
+
+fn locate_in_components(coord: Coord, components: &Components) -> Location {
+    let locations = components
+        .points
+        .iter()
+        .map(|point| {
+            if *point == coord {
+                Location::Interior
+            } else {
+                Location::Exterior
+            }
+        })
+        .chain(
+            components
+                .lines
+                .iter()
+                .map(|line| locate_in_line(coord, line)),
+        )
+        .chain(
+            components
+                .polygons
+                .iter()
+                .map(|rings| locate_in_polygon(coord, rings)),
+        );
+
+    let mut result = Location::Exterior;
+    for location in locations {
+        match location {
+            Location::Interior => return Location::Interior,
+            Location::Boundary => result = Location::Boundary,
+            Location::Exterior => {}
+        }
+    }
+    result
+}
+
+/// The boundary of a line string is made of its end points, unless it is
+/// closed
+fn locate_in_line(coord: Coord, line: &[Coord]) -> Location {
+    let (Some(first), Some(last)) = (line.first(), line.last()) else {
+        return Location::Exterior;
+    };
+    if first != last && (coord == *first || coord == *last) {
+        return Location::Boundary;
+    }
+    if line
+        .windows(2)
+        .any(|segment| on_segment(coord, segment[0], segment[1]))
+    {
+        Location::Interior
+    } else {
+        Location::Exterior
+    }
+}
+
+fn locate_in_polygon(coord: Coord, rings: &[LineString]) -> Location {
+    let Some((exterior, holes)) = rings.split_first() else {
+        return Location::Exterior;
+    };
+    match locate_in_ring(coord, exterior) {
+        Location::Interior => {}
+        location => return location,
+    }
+    for hole in holes {
+        match locate_in_ring(coord, hole) {
+            Location::Interior => return Location::Exterior,
+            Location::Boundary => return Location::Boundary,
+            Location::Exterior => {}
+        }
+    }
+    Location::Interior
+}
+
+/// Locates `coord` relative to the area enclosed by `ring`, using the
+/// crossing number algorithm
+fn locate_in_ring(coord: Coord, ring: &[Coord]) -> Location {
+    let mut inside = false;
+    for segment in ring.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        if on_segment(coord, a, b) {
+            return Location::Boundary;
+        }
+        if (a.y > coord.y) != (b.y > coord.y) {
+            let x = a.x + (coord.y - a.y) * (b.x - a.x) / (b.y - a.y);
+            if coord.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Interior
+    } else {
+        Location::Exterior
+    }
+}
+
+/// Returns the sign of the cross product of `b - a` and `c - a`: positive if
+/// `c` is to the left of the line through `a` and `b`, negative if it is to
+/// the right and zero if the three positions are collinear
+fn orientation(a: Coord, b: Coord, c: Coord) -> f64 {
+    let cross = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+    if cross > 0.0 {
+        1.0
+    } else if cross < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// Returns true if `coord` lies on the segment from `a` to `b`
+fn on_segment(coord: Coord, a: Coord, b: Coord) -> bool {
+    orientation(a, b, coord) == 0.0
+        && coord.x >= a.x.min(b.x)
+        && coord.x <= a.x.max(b.x)
+        && coord.y >= a.y.min(b.y)
+        && coord.y <= a.y.max(b.y)
+}
+
+/// Returns true if the two segments share at least one position
+fn segments_intersect((a, b): (Coord, Coord), (c, d): (Coord, Coord)) -> bool {
+    let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+    let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+    (o1 * o2 < 0.0 && o3 * o4 < 0.0)
+        || on_segment(c, a, b)
+        || on_segment(d, a, b)
+        || on_segment(a, c, d)
+        || on_segment(b, c, d)
+}
+
+/// Returns true if the two segments cross at a single position that is not
+/// an end point of either of them
+fn segments_cross((a, b): (Coord, Coord), (c, d): (Coord, Coord)) -> bool {
+    orientation(a, b, c) * orientation(a, b, d) < 0.0
+        && orientation(c, d, a) * orientation(c, d, b) < 0.0
+}
+
+/// Returns true if the two geometries share at least one position
+pub fn intersects(a: &Geometry, b: &Geometry) -> bool {
+    match (a.bounding_box(), b.bounding_box()) {
+        (Some(a_bbox), Some(b_bbox)) if a_bbox.intersects(&b_bbox) => {}
+        _ => return false,
+    }
+
+    let (a, b) = (a.components(), b.components());
+    a.vertices()
+        .any(|coord| locate_in_components(coord, &b) != Location::Exterior)
+        || b.vertices()
+            .any(|coord| locate_in_components(coord, &a) != Location::Exterior)
+        || a.segments().any(|a_segment| {
+            b.segments()
+                .any(|b_segment| segments_intersect(a_segment, b_segment))
+        })
+}
+
+/// Returns true if no position of `b` lies in the exterior of `a`, and at
+/// least one position of the interior of `b` lies in the interior of `a`
+pub fn contains(a: &Geometry, b: &Geometry) -> bool {
+    match (a.bounding_box(), b.bounding_box()) {
+        (Some(a_bbox), Some(b_bbox)) if a_bbox.contains(&b_bbox) => {}
+        _ => return false,
+    }
+
+    let (a, b) = (a.components(), b.components());
+    // A geometry cannot contain a geometry of a higher dimension
+    if (!b.polygons.is_empty() && a.polygons.is_empty())
+        || (!b.lines.is_empty() && a.lines.is_empty() && a.polygons.is_empty())
+    {
+        return false;
+    }
+
+    let midpoints = b
+        .segments()
+        .map(|(start, end)| Coord::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0));
+    // The interior of a polygon of `b` that lies within `a` is always part of
+    // the interior of `a`
+    let mut has_interior = b.polygons.iter().any(|rings| polygon_area(rings) > 0.0);
+    for coord in b.vertices().chain(midpoints) {
+        match locate_in_components(coord, &a) {
+            Location::Exterior => return false,
+            Location::Interior => has_interior = true,
+            Location::Boundary => {}
+        }
+    }
+    if !has_interior {
+        return false;
+    }
+
+    // The segments of `b` may leave `a` between the positions checked above
+    if b.segments().any(|b_segment| {
+        a.segments()
+            .any(|a_segment| segments_cross(a_segment, b_segment))
+    }) {
+        return false;
+    }
+
+    // A hole of `a` (or a concave part of its exterior ring) may lie within
+    // a polygon of `b`
+    if !b.polygons.is_empty() {
+        let b_polygons = Components {
+            polygons: b.polygons.clone(),
+            ..Default::default()
+        };
+        if a.polygons
+            .iter()
+            .flat_map(|rings| rings.iter().flatten())
+            .any(|coord| locate_in_components(*coord, &b_polygons) == Location::Interior)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the minimum euclidean distance between the two geometries, or
+/// `None` if one of them is empty
+pub fn distance(a: &Geometry, b: &Geometry) -> Option<f64> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    if intersects(a, b) {
+        return Some(0.0);
+    }
+
+    // The closest positions of two disjoint geometries always include a
+    // vertex of one of them
+    let (a, b) = (a.components(), b.components());
+    let min_distance = |from: &Components, to: &Components| {
+        from.vertices()
+            .map(|coord| {
+                let to_points = to
+                    .points
+                    .iter()
+                    .map(|point| distance_to_point(coord, *point));
+                let to_segments = to
+                    .segments()
+                    .map(|(start, end)| distance_to_segment(coord, start, end));
+                to_points.chain(to_segments).fold(f64::INFINITY, f64::min)
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
+    Some(min_distance(&a, &b).min(min_distance(&b, &a)))
+}
+
+fn distance_to_point(a: Coord, b: Coord) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn distance_to_segment(coord: Coord, start: Coord, end: Coord) -> f64 {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance_to_point(coord, start);
+    }
+    let t = (((coord.x - start.x) * dx + (coord.y - start.y) * dy) / length_squared)
+        .clamp(0.0, 1.0);
+    distance_to_point(coord, Coord::new(start.x + t * dx, start.y + t * dy))
+}
+
+/// Returns a polygon approximating the circle of the given `radius` around
+/// `center`, using `segments` segments
+pub fn circle(center: Coord, radius: f64, segments: usize) -> Geometry {
+    if radius <= 0.0 || segments < 3 {
+        return Geometry::Polygon(vec![]);
+    }
+    let mut ring: LineString = (0..segments)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / segments as f64;
+            Coord::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    ring.push(ring[0]);
+    Geometry::Polygon(vec![ring])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wkt::read_wkt;
+
+    fn geometry(wkt: &str) -> Geometry {
+        read_wkt(wkt).unwrap()
+    }
+
+    #[test]
+    fn test_area() {
+        assert_eq!(area(&geometry("POLYGON((0 0,4 0,4 4,0 4,0 0))")), 16.0);
+        assert_eq!(
+            area(&geometry(
+                "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))"
+            )),
+            15.0
+        );
+        assert_eq!(
+            area(&geometry(
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 1,0 0)),((0 0,0 -2,-2 -2,-2 0,0 0)))"
+            )),
+            5.0
+        );
+        assert_eq!(area(&geometry("LINESTRING(0 0,1 1)")), 0.0);
+    }
+
+    #[test]
+    fn test_locate() {
+        let polygon = geometry("POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))");
+        let locate = |x, y| locate(Coord::new(x, y), &polygon);
+        assert_eq!(locate(3.0, 3.0), Location::Interior);
+        assert_eq!(locate(4.0, 2.0), Location::Boundary);
+        assert_eq!(locate(1.5, 1.5), Location::Exterior);
+        assert_eq!(locate(1.0, 1.5), Location::Boundary);
+        assert_eq!(locate(5.0, 0.0), Location::Exterior);
+
+        let line = geometry("LINESTRING(0 0,2 2)");
+        assert_eq!(locate_in(1.0, 1.0, &line), Location::Interior);
+        assert_eq!(locate_in(2.0, 2.0, &line), Location::Boundary);
+    }
+
+    fn locate_in(x: f64, y: f64, geometry: &Geometry) -> Location {
+        locate(Coord::new(x, y), geometry)
+    }
+
+    #[test]
+    fn test_intersects() {
+        let square = geometry("POLYGON((0 0,4 0,4 4,0 4,0 0))");
+        assert!(intersects(&square, &geometry("POINT(4 4)")));
+        assert!(intersects(&square, &geometry("POINT(1 1)")));
+        assert!(!intersects(&square, &geometry("POINT(5 1)")));
+        assert!(intersects(&square, &geometry("LINESTRING(-1 2,5 2)")));
+        assert!(intersects(&square, &geometry("POLYGON((1 1,2 1,2 2,1 1))")));
+        assert!(!intersects(
+            &geometry("LINESTRING(0 0,1 1)"),
+            &geometry("LINESTRING(0 1,0.4 0.6)")
+        ));
+        assert!(intersects(
+            &geometry("LINESTRING(0 0,1 1)"),
+            &geometry("LINESTRING(0 1,1 0)")
+        ));
+        assert!(!intersects(&square, &geometry("POINT EMPTY")));
+    }
+
+    #[test]
+    fn test_contains() {
+        let square = geometry("POLYGON((0 0,4 0,4 4,0 4,0 0))");
+        assert!(contains(&square, &geometry("POINT(1 1)")));
+        assert!(!contains(&square, &geometry("POINT(4 1)")));
+        assert!(contains(&square, &geometry("LINESTRING(0 0,4 4)")));
+        assert!(!contains(&square, &geometry("LINESTRING(0 0,4 0)")));
+        assert!(contains(&square, &square));
+        assert!(contains(&square, &geometry("POLYGON((1 1,2 1,2 2,1 1))")));
+        assert!(!contains(&geometry("POLYGON((1 1,2 1,2 2,1 1))"), &square));
+
+        let with_hole = geometry("POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))");
+        assert!(!contains(&with_hole, &square));
+        assert!(!contains(&with_hole, &geometry("POINT(1.5 1.5)")));
+        assert!(contains(&with_hole, &geometry("POINT(3 3)")));
+
+        // The line leaves the concave polygon between its end points
+        let concave = geometry("POLYGON((0 0,4 0,4 4,2 1,0 4,0 0))");
+        assert!(!contains(&concave, &geometry("LINESTRING(0.5 3,3.5 3)")));
+
+        assert!(!contains(&geometry("POINT(1 1)"), &square));
+        assert!(contains(
+            &geometry("LINESTRING(0 0,2 2)"),
+            &geometry("POINT(1 1)")
+        ));
+    }
+
+    #[test]
+    fn test_distance() {
+        let square = geometry("POLYGON((0 0,4 0,4 4,0 4,0 0))");
+        assert_eq!(distance(&square, &geometry("POINT(1 1)")), Some(0.0));
+        assert_eq!(distance(&square, &geometry("POINT(7 8)")), Some(5.0));
+        assert_eq!(distance(&square, &geometry("POINT(2 6)")), Some(2.0));
+        assert_eq!(
+            distance(&geometry("POINT(0 0)"), &geometry("POINT(3 4)")),
+            Some(5.0)
+        );
+        assert_eq!(
+            distance(&geometry("LINESTRING(0 5,10 5)"), &square),
+            Some(1.0)
+        );
+        assert_eq!(distance(&square, &geometry("POINT EMPTY")), None);
+    }
+
+    #[test]
+    fn test_circle() {
+        let Geometry::Polygon(rings) = circle(Coord::new(1.0, 1.0), 2.0, 32) else {
+            unreachable!()
+        };
+        assert_eq!(rings[0].len(), 33);
+        assert_eq!(rings[0][0], Coord::new(3.0, 1.0));
+        assert_eq!(rings[0].first(), rings[0].last());
+        let area = area(&Geometry::Polygon(rings));
+        assert!((area - 4.0 * PI).abs() < 0.1, "{area}");
+
+        assert_eq!(
+            circle(Coord::new(1.0, 1.0), 0.0, 32),
+            Geometry::Polygon(vec![])
+        );
+    }
+}
@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Axis-aligned bounding boxes
+
+use crate::geometry::Coord;
+
+/// The smallest axis-aligned rectangle containing a geometry.
+///
+/// Bounding boxes are cheap to compare and are used to skip the exact
+/// geometry computations when two geometries are far apart. Stored as
+/// `<geometry>_xmin`, `<geometry>_ymin`, `<geometry>_xmax` and
+/// `<geometry>_ymax` columns next to the geometry, their min/max statistics
+/// allow pruning Parquet row groups, see [`crate::pruning`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub xmin: f64,
+    pub ymin: f64,
+    pub xmax: f64,
+    pub ymax: f64,
+}
+
+impl BoundingBox {
+    pub fn new(xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> Self {
+        Self {
+            xmin,
+            ymin,
+            xmax,
+            ymax,
+        }
+    }
+
+    /// Returns the bounding box of a single position
+    pub fn from_coord(coord: Coord) -> Self {
+        Self::new(coord.x, coord.y, coord.x, coord.y)
+    }
+
+    /// Grows the bounding box to include `coord`
+    pub fn expand(&mut self, coord: Coord) {
+        self.xmin = self.xmin.min(coord.x);
+        self.ymin = self.ymin.min(coord.y);
+        self.xmax = self.xmax.max(coord.x);
+        self.ymax = self.ymax.max(coord.y);
+    }
+
+    /// Returns true if the two bounding boxes share at least one position
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.xmin <= other.xmax
+            && other.xmin <= self.xmax
+            && self.ymin <= other.ymax
+            && other.ymin <= self.ymax
+    }
+
+    /// Returns true if `other` lies within this bounding box
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        self.xmin <= other.xmin
+            && self.ymin <= other.ymin
+            && other.xmax <= self.xmax
+            && other.ymax <= self.ymax
+    }
+
+    /// Returns the euclidean distance between the closest positions of the
+    /// two bounding boxes
+    pub fn distance(&self, other: &BoundingBox) -> f64 {
+        let dx = (other.xmin - self.xmax)
+            .max(self.xmin - other.xmax)
+            .max(0.0);
+        let dy = (other.ymin - self.ymax)
+            .max(self.ymin - other.ymax)
+            .max(0.0);
+        dx.hypot(dy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounding_box() {
+        let mut bbox = BoundingBox::from_coord(Coord::new(1.0, 1.0));
+        bbox.expand(Coord::new(3.0, -1.0));
+        assert_eq!(bbox, BoundingBox::new(1.0, -1.0, 3.0, 1.0));
+
+        let other = BoundingBox::new(2.0, 0.0, 5.0, 5.0);
+        assert!(bbox.intersects(&other));
+        assert!(!bbox.contains(&other));
+        assert!(other.contains(&BoundingBox::new(2.0, 1.0, 3.0, 2.0)));
+        assert_eq!(bbox.distance(&other), 0.0);
+        assert_eq!(bbox.distance(&BoundingBox::new(6.0, 5.0, 7.0, 6.0)), 5.0);
+    }
+}
@@ -0,0 +1,105 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Geospatial scalar functions
+
+pub mod st_area;
+pub mod st_astext;
+pub mod st_bounds;
+pub mod st_buffer;
+pub mod st_contains;
+pub mod st_distance;
+pub mod st_geomfromwkb;
+pub mod st_geomfromwkt;
+pub mod st_intersects;
+pub mod st_point;
+pub(crate) mod utils;
+
+use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
+use st_bounds::{Bound, StBoundFunc};
+use std::sync::Arc;
+
+make_udf_function!(st_area::StAreaFunc, st_area);
+make_udf_function!(st_astext::StAsTextFunc, st_astext);
+make_udf_function!(st_buffer::StBufferFunc, st_buffer);
+make_udf_function!(st_contains::StContainsFunc, st_contains);
+make_udf_function!(st_distance::StDistanceFunc, st_distance);
+make_udf_function!(st_geomfromwkb::StGeomFromWkbFunc, st_geomfromwkb);
+make_udf_function!(st_geomfromwkt::StGeomFromWktFunc, st_geomfromwkt);
+make_udf_function!(st_intersects::StIntersectsFunc, st_intersects);
+make_udf_function!(st_point::StPointFunc, st_point);
+
+/// Creates a singleton `ScalarUDF` returning the bound `$BOUND` of the
+/// bounding box of a geometry, and a function named `$NAME` which returns
+/// that singleton.
+macro_rules! make_bound_udf_function {
+    ($BOUND:expr, $NAME:ident) => {
+        #[doc = concat!("Return a [`ScalarUDF`](datafusion_expr::ScalarUDF) implementation of ", stringify!($NAME))]
+        pub fn $NAME() -> Arc<ScalarUDF> {
+            static INSTANCE: std::sync::LazyLock<Arc<ScalarUDF>> =
+                std::sync::LazyLock::new(|| {
+                    Arc::new(ScalarUDF::new_from_impl(StBoundFunc::new($BOUND)))
+                });
+            Arc::clone(&INSTANCE)
+        }
+    };
+}
+
+make_bound_udf_function!(Bound::XMin, st_xmin);
+make_bound_udf_function!(Bound::YMin, st_ymin);
+make_bound_udf_function!(Bound::XMax, st_xmax);
+make_bound_udf_function!(Bound::YMax, st_ymax);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!(
+        (st_area, "Returns the area of the polygons of a geometry.", geom),
+        (st_astext, "Returns the WKT representation of a geometry.", geom),
+        (st_buffer, "Returns a polygon covering all the points within the given distance of a point.", geom radius),
+        (st_contains, "Returns true if geom1 contains geom2.", geom1 geom2),
+        (st_distance, "Returns the minimum distance between two geometries.", geom1 geom2),
+        (st_geomfromwkb, "Returns the geometry of a WKB representation.", wkb),
+        (st_geomfromwkt, "Returns the geometry of a WKT representation.", wkt),
+        (st_intersects, "Returns true if two geometries share at least one point.", geom1 geom2),
+        (st_point, "Returns a point geometry with the given coordinates.", x y),
+        (st_xmin, "Returns the minimum X coordinate of a geometry.", geom),
+        (st_ymin, "Returns the minimum Y coordinate of a geometry.", geom),
+        (st_xmax, "Returns the maximum X coordinate of a geometry.", geom),
+        (st_ymax, "Returns the maximum Y coordinate of a geometry.", geom)
+    );
+}
+
+/// Returns all the geospatial functions
+pub fn functions() -> Vec<Arc<ScalarUDF>> {
+    vec![
+        st_area(),
+        st_astext(),
+        st_buffer(),
+        st_contains(),
+        st_distance(),
+        st_geomfromwkb(),
+        st_geomfromwkt(),
+        st_intersects(),
+        st_point(),
+        st_xmin(),
+        st_ymin(),
+        st_xmax(),
+        st_ymax(),
+    ]
+}
@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::algorithm::area;
+use crate::function::utils::{geometries_signature, invoke_geo_function, GeometryArg};
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns the cartesian area of the polygons of a geometry, in the square units of its coordinates. The area of points and line strings is 0.",
+    syntax_example = "st_area(geom)",
+    sql_example = r#"```sql
+> select st_area(st_geomfromwkt('POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))'));
++----------------------------------------------------------------------------------------+
+| st_area(st_geomfromwkt(Utf8("POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))"))) |
++----------------------------------------------------------------------------------------+
+| 15.0                                                                                   |
++----------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "geom",
+        description = "Geometry expression to operate on. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug)]
+pub struct StAreaFunc {
+    signature: Signature,
+}
+
+impl Default for StAreaFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StAreaFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: geometries_signature(1),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StAreaFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_area"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_area)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_area(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries = GeometryArg::try_new(&args[0])?;
+    let result: Float64Array = (0..number_rows)
+        .map(|idx| geometries.get(idx).map(area))
+        .collect();
+    Ok(Arc::new(result))
+}
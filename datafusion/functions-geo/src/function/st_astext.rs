@@ -0,0 +1,104 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::function::utils::{geometries_signature, invoke_geo_function, GeometryArg};
+use crate::wkt::write_wkt;
+use arrow::array::{ArrayRef, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns the [Well-known text](https://libgeos.org/specifications/wkt/) (WKT) representation of a geometry.",
+    syntax_example = "st_astext(geom)",
+    sql_example = r#"```sql
+> select st_astext(st_point(1.5, 2));
++-------------------------------------------+
+| st_astext(st_point(Float64(1.5),Int64(2))) |
++-------------------------------------------+
+| POINT(1.5 2)                              |
++-------------------------------------------+
+```"#,
+    argument(
+        name = "geom",
+        description = "Geometry expression to operate on. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug)]
+pub struct StAsTextFunc {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for StAsTextFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StAsTextFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: geometries_signature(1),
+            aliases: vec![String::from("st_aswkt")],
+        }
+    }
+}
+
+impl ScalarUDFImpl for StAsTextFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_astext"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_astext)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_astext(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries = GeometryArg::try_new(&args[0])?;
+    let result: StringArray = (0..number_rows)
+        .map(|idx| geometries.get(idx).map(write_wkt))
+        .collect();
+    Ok(Arc::new(result))
+}
@@ -0,0 +1,163 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::bounding_box::BoundingBox;
+use crate::function::utils::{geometries_signature, invoke_geo_function, GeometryArg};
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_doc::DocSection;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+};
+use std::any::Any;
+use std::sync::{Arc, LazyLock};
+
+const DOC_SECTION_GEOSPATIAL: DocSection = DocSection {
+    include: true,
+    label: "Geospatial Functions",
+    description: None,
+};
+
+/// A bound of the bounding box of a geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    XMin,
+    YMin,
+    XMax,
+    YMax,
+}
+
+impl Bound {
+    /// Returns the bound of `bbox`
+    pub fn of(&self, bbox: &BoundingBox) -> f64 {
+        match self {
+            Bound::XMin => bbox.xmin,
+            Bound::YMin => bbox.ymin,
+            Bound::XMax => bbox.xmax,
+            Bound::YMax => bbox.ymax,
+        }
+    }
+
+    /// Returns the suffix of the column storing the bound of a geometry
+    /// column, e.g. `xmin` for `<geometry>_xmin`
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Bound::XMin => "xmin",
+            Bound::YMin => "ymin",
+            Bound::XMax => "xmax",
+            Bound::YMax => "ymax",
+        }
+    }
+
+    fn documentation(&self) -> &'static Documentation {
+        static DOCUMENTATION: LazyLock<[Documentation; 4]> = LazyLock::new(|| {
+            [Bound::XMin, Bound::YMin, Bound::XMax, Bound::YMax].map(|bound| {
+                let (extreme, axis) = match bound {
+                    Bound::XMin => ("minimum", "X"),
+                    Bound::YMin => ("minimum", "Y"),
+                    Bound::XMax => ("maximum", "X"),
+                    Bound::YMax => ("maximum", "Y"),
+                };
+                Documentation::builder(
+                    DOC_SECTION_GEOSPATIAL,
+                    format!(
+                        "Returns the {extreme} {axis} coordinate of a geometry, or NULL if the geometry is empty. \
+                        Storing the bounds of a geometry column in `<geometry>_xmin`, `<geometry>_ymin`, `<geometry>_xmax` \
+                        and `<geometry>_ymax` columns allows skipping Parquet row groups when filtering with \
+                        `st_intersects` or `st_contains`."
+                    ),
+                    format!("st_{}(geom)", bound.suffix()),
+                )
+                .with_sql_example(format!(
+                    r#"```sql
+> select st_{0}(st_geomfromwkt('LINESTRING(1 2,3 4)'));
++------------------------------------------------------+
+| st_{0}(st_geomfromwkt(Utf8("LINESTRING(1 2,3 4)"))) |
++------------------------------------------------------+
+| {1:<52} |
++------------------------------------------------------+
+```"#,
+                    bound.suffix(),
+                    format!("{:?}", bound.of(&BoundingBox::new(1.0, 2.0, 3.0, 4.0))),
+                ))
+                .with_argument(
+                    "geom",
+                    "Geometry expression to operate on. Can be a constant, column, or function.",
+                )
+                .build()
+            })
+        });
+        &DOCUMENTATION[*self as usize]
+    }
+}
+
+#[derive(Debug)]
+pub struct StBoundFunc {
+    bound: Bound,
+    name: String,
+    signature: Signature,
+}
+
+impl StBoundFunc {
+    pub fn new(bound: Bound) -> Self {
+        Self {
+            bound,
+            name: format!("st_{}", bound.suffix()),
+            signature: geometries_signature(1),
+        }
+    }
+
+    pub fn bound(&self) -> Bound {
+        self.bound
+    }
+}
+
+impl ScalarUDFImpl for StBoundFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, |args, number_rows| {
+            let geometries = GeometryArg::try_new(&args[0])?;
+            let result: Float64Array = (0..number_rows)
+                .map(|idx| {
+                    let bbox = geometries.get(idx)?.bounding_box()?;
+                    Some(self.bound.of(&bbox))
+                })
+                .collect();
+            Ok(Arc::new(result) as ArrayRef)
+        })
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        Some(self.bound.documentation())
+    }
+}
@@ -0,0 +1,134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::algorithm::circle;
+use crate::function::utils::{
+    as_float64, float64_at, float64_coercion, geometry_coercion, geometry_return_field,
+    invoke_geo_function, to_geometry_array, GeometryArg,
+};
+use crate::geometry::Geometry;
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::{internal_err, not_impl_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl,
+    Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+
+/// Number of segments used to approximate a circle, the PostGIS default of 8
+/// segments per quarter circle
+const CIRCLE_SEGMENTS: usize = 32;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns a polygon covering all the points within the given distance of a point. The circle is approximated with 32 segments. Only point geometries are supported: buffering lines and polygons requires a polygon union, that is not implemented, and returns an error.",
+    syntax_example = "st_buffer(geom, radius)",
+    sql_example = r#"```sql
+> select round(st_area(st_buffer(st_point(0, 0), 1)), 2);
++---------------------------------------------------------------------+
+| round(st_area(st_buffer(st_point(Int64(0),Int64(0)),Int64(1))),Int64(2)) |
++---------------------------------------------------------------------+
+| 3.12                                                                |
++---------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "geom",
+        description = "Point expression to operate on. Can be a constant, column, or function. An empty point returns an empty polygon."
+    ),
+    argument(
+        name = "radius",
+        description = "The distance, in the units of the coordinates. A radius of 0 or less returns an empty polygon."
+    )
+)]
+#[derive(Debug)]
+pub struct StBufferFunc {
+    signature: Signature,
+}
+
+impl Default for StBufferFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StBufferFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![geometry_coercion(), float64_coercion()],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StBufferFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_buffer"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(geometry_return_field(self.name()))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_buffer)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_buffer(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries = GeometryArg::try_new(&args[0])?;
+    let radius = as_float64(&args[1]);
+    let result = (0..number_rows)
+        .map(|idx| {
+            let (Some(geometry), Some(radius)) =
+                (geometries.get(idx), float64_at(radius, idx))
+            else {
+                return Ok(None);
+            };
+            match geometry {
+                Geometry::Point(Some(center)) => {
+                    Ok(Some(circle(*center, radius, CIRCLE_SEGMENTS)))
+                }
+                Geometry::Point(None) => Ok(Some(Geometry::Polygon(vec![]))),
+                other => not_impl_err!(
+                    "st_buffer only supports points, got a {}",
+                    other.type_name()
+                ),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(to_geometry_array(result))
+}
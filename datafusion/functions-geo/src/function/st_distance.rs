@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::algorithm::distance;
+use crate::function::utils::{geometries_signature, invoke_geo_function, GeometryArg};
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns the minimum cartesian distance between two geometries, in the units of their coordinates, or NULL if one of them is empty.",
+    syntax_example = "st_distance(geom1, geom2)",
+    sql_example = r#"```sql
+> select st_distance(st_point(0, 0), st_point(3, 4));
++------------------------------------------------------------------+
+| st_distance(st_point(Int64(0),Int64(0)),st_point(Int64(3),Int64(4))) |
++------------------------------------------------------------------+
+| 5.0                                                              |
++------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "geom1",
+        description = "First geometry expression to operate on. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "geom2",
+        description = "Second geometry expression to operate on. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug)]
+pub struct StDistanceFunc {
+    signature: Signature,
+}
+
+impl Default for StDistanceFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StDistanceFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: geometries_signature(2),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StDistanceFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_distance"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_distance)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_distance(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries1 = GeometryArg::try_new(&args[0])?;
+    let geometries2 = GeometryArg::try_new(&args[1])?;
+    let result: Float64Array = (0..number_rows)
+        .map(|idx| {
+            let (geom1, geom2) = (geometries1.get(idx)?, geometries2.get(idx)?);
+            distance(geom1, geom2)
+        })
+        .collect();
+    Ok(Arc::new(result))
+}
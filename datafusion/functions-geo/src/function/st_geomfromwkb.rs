@@ -0,0 +1,103 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::function::utils::{
+    geometries_signature, geometry_return_field, invoke_geo_function, to_geometry_array,
+    GeometryArg,
+};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::{internal_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl,
+    Signature,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns the geometry of a [Well-known binary](https://libgeos.org/specifications/wkb/) (WKB) representation. The input is validated and converted to little-endian WKB, dropping the SRID of extended WKB.",
+    syntax_example = "st_geomfromwkb(wkb)",
+    sql_example = r#"```sql
+> select st_astext(st_geomfromwkb(X'0101000000000000000000F03F0000000000000040'));
++------------------------------------------------------------------------------------+
+| st_astext(st_geomfromwkb(Binary("1,1,0,0,0,0,0,0,0,0,0,240,63,0,0,0,0,0,0,0,64"))) |
++------------------------------------------------------------------------------------+
+| POINT(1 2)                                                                         |
++------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "wkb",
+        description = "The WKB representation of a geometry. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug)]
+pub struct StGeomFromWkbFunc {
+    signature: Signature,
+}
+
+impl Default for StGeomFromWkbFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StGeomFromWkbFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: geometries_signature(1),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StGeomFromWkbFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_geomfromwkb"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(geometry_return_field(self.name()))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_geomfromwkb)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_geomfromwkb(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries = GeometryArg::try_new(&args[0])?;
+    Ok(to_geometry_array(
+        (0..number_rows).map(|idx| geometries.get(idx).cloned()),
+    ))
+}
@@ -0,0 +1,130 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::function::utils::{
+    geometry_return_field, invoke_geo_function, to_geometry_array,
+};
+use crate::geometry::Geometry;
+use crate::wkt::read_wkt;
+use arrow::array::{ArrayRef, AsArray};
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::types::logical_string;
+use datafusion_common::{exec_err, internal_err, Result};
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ReturnFieldArgs, ScalarFunctionArgs,
+    ScalarUDFImpl, Signature, TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns the geometry of a [Well-known text](https://libgeos.org/specifications/wkt/) (WKT) representation.",
+    syntax_example = "st_geomfromwkt(wkt)",
+    sql_example = r#"```sql
+> select st_area(st_geomfromwkt('POLYGON((0 0,2 0,2 2,0 2,0 0))'));
++-----------------------------------------------------------------+
+| st_area(st_geomfromwkt(Utf8("POLYGON((0 0,2 0,2 2,0 2,0 0))"))) |
++-----------------------------------------------------------------+
+| 4.0                                                             |
++-----------------------------------------------------------------+
+```"#,
+    argument(
+        name = "wkt",
+        description = "The WKT representation of a geometry, e.g. `POINT(1 2)`. An extended WKT `SRID=<srid>;` prefix is ignored."
+    )
+)]
+#[derive(Debug)]
+pub struct StGeomFromWktFunc {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for StGeomFromWktFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StGeomFromWktFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Native(
+                    logical_string(),
+                ))],
+                Volatility::Immutable,
+            ),
+            aliases: vec![String::from("st_geomfromtext")],
+        }
+    }
+}
+
+impl ScalarUDFImpl for StGeomFromWktFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_geomfromwkt"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(geometry_return_field(self.name()))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, |args, _| {
+            st_geomfromwkt(&args[0])
+        })
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_geomfromwkt(array: &ArrayRef) -> Result<ArrayRef> {
+    let geometries = match array.data_type() {
+        DataType::Utf8 => parse(array.as_string::<i32>().iter()),
+        DataType::LargeUtf8 => parse(array.as_string::<i64>().iter()),
+        DataType::Utf8View => parse(array.as_string_view().iter()),
+        DataType::Null => Ok(vec![None; array.len()]),
+        other => exec_err!("Unsupported data type {other:?} for function st_geomfromwkt"),
+    }?;
+    Ok(to_geometry_array(geometries))
+}
+
+fn parse<'a>(
+    values: impl Iterator<Item = Option<&'a str>>,
+) -> Result<Vec<Option<Geometry>>> {
+    values
+        .map(|value| value.map(read_wkt).transpose())
+        .collect()
+}
@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::algorithm::intersects;
+use crate::function::utils::{geometries_signature, invoke_geo_function, GeometryArg};
+use arrow::array::{ArrayRef, BooleanArray};
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns true if the two geometries share at least one point.",
+    syntax_example = "st_intersects(geom1, geom2)",
+    sql_example = r#"```sql
+> select st_intersects(st_geomfromwkt('LINESTRING(0 0,2 2)'), st_geomfromwkt('LINESTRING(0 2,2 0)'));
++--------------------------------------------------------------------------------------------+
+| st_intersects(st_geomfromwkt(Utf8("LINESTRING(0 0,2 2)")),st_geomfromwkt(Utf8("LINESTRING(0 2,2 0)"))) |
++--------------------------------------------------------------------------------------------+
+| true                                                                                       |
++--------------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "geom1",
+        description = "First geometry expression to operate on. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "geom2",
+        description = "Second geometry expression to operate on. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug)]
+pub struct StIntersectsFunc {
+    signature: Signature,
+}
+
+impl Default for StIntersectsFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StIntersectsFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: geometries_signature(2),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StIntersectsFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_intersects"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_intersects)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_intersects(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let geometries1 = GeometryArg::try_new(&args[0])?;
+    let geometries2 = GeometryArg::try_new(&args[1])?;
+    let result: BooleanArray = (0..number_rows)
+        .map(|idx| {
+            let (geom1, geom2) = (geometries1.get(idx)?, geometries2.get(idx)?);
+            Some(intersects(geom1, geom2))
+        })
+        .collect();
+    Ok(Arc::new(result))
+}
@@ -0,0 +1,112 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::function::utils::{
+    as_float64, float64_at, float64_coercion, geometry_return_field, invoke_geo_function,
+    to_geometry_array,
+};
+use crate::geometry::{Coord, Geometry};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, FieldRef};
+use datafusion_common::{internal_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl,
+    Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Geospatial Functions"),
+    description = "Returns a point geometry with the given coordinates.",
+    syntax_example = "st_point(x, y)",
+    sql_example = r#"```sql
+> select st_astext(st_point(1, 2));
++---------------------------------------+
+| st_astext(st_point(Int64(1),Int64(2))) |
++---------------------------------------+
+| POINT(1 2)                            |
++---------------------------------------+
+```"#,
+    argument(name = "x", description = "The X coordinate of the point."),
+    argument(name = "y", description = "The Y coordinate of the point.")
+)]
+#[derive(Debug)]
+pub struct StPointFunc {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for StPointFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StPointFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![float64_coercion(), float64_coercion()],
+                Volatility::Immutable,
+            ),
+            aliases: vec![String::from("st_makepoint")],
+        }
+    }
+}
+
+impl ScalarUDFImpl for StPointFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_point"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(geometry_return_field(self.name()))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_geo_function(&args.args, args.number_rows, st_point)
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+fn st_point(args: &[ArrayRef], number_rows: usize) -> Result<ArrayRef> {
+    let (x, y) = (as_float64(&args[0]), as_float64(&args[1]));
+    Ok(to_geometry_array((0..number_rows).map(|idx| {
+        let (x, y) = (float64_at(x, idx)?, float64_at(y, idx)?);
+        Some(Geometry::Point(Some(Coord::new(x, y))))
+    })))
+}
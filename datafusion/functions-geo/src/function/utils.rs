@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::geometry::Geometry;
+use crate::types::{geometry_field, logical_wkb};
+use crate::wkb::{read_wkb, write_wkb};
+use arrow::array::{Array, ArrayRef, AsArray, BinaryBuilder, Float64Array};
+use arrow::datatypes::{DataType, FieldRef, Float64Type};
+use datafusion_common::types::{logical_float32, logical_float64, NativeType};
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::{
+    Coercion, ColumnarValue, Signature, TypeSignatureClass, Volatility,
+};
+use std::sync::Arc;
+
+/// A geometry argument, see [`crate::types::Wkb`]
+pub(crate) fn geometry_coercion() -> Coercion {
+    Coercion::new_exact(TypeSignatureClass::Native(logical_wkb()))
+}
+
+/// A numeric argument, coerced to `Float64`
+pub(crate) fn float64_coercion() -> Coercion {
+    Coercion::new_implicit(
+        TypeSignatureClass::Native(logical_float64()),
+        vec![
+            TypeSignatureClass::Integer,
+            TypeSignatureClass::Native(logical_float32()),
+        ],
+        NativeType::Float64,
+    )
+}
+
+/// Signature of a function that takes `n` geometries
+pub(crate) fn geometries_signature(n: usize) -> Signature {
+    Signature::coercible(vec![geometry_coercion(); n], Volatility::Immutable)
+}
+
+/// Returns the return field of a function returning geometries
+pub(crate) fn geometry_return_field(name: &str) -> FieldRef {
+    Arc::new(geometry_field(name))
+}
+
+/// Invokes `func` with the arguments as arrays, scalar arguments being
+/// arrays of a single row, and the number of rows to return.
+///
+/// The result is a scalar if all the arguments are scalars.
+pub(crate) fn invoke_geo_function(
+    args: &[ColumnarValue],
+    number_rows: usize,
+    func: impl Fn(&[ArrayRef], usize) -> Result<ArrayRef>,
+) -> Result<ColumnarValue> {
+    let is_scalar = args
+        .iter()
+        .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+    let arrays = args
+        .iter()
+        .map(|arg| match arg {
+            ColumnarValue::Array(array) => Ok(Arc::clone(array)),
+            ColumnarValue::Scalar(scalar) => scalar.to_array(),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let result = func(&arrays, if is_scalar { 1 } else { number_rows })?;
+    if is_scalar {
+        ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+/// Returns the index of row `idx` in an argument array, which only has a
+/// single row if the argument was a scalar
+fn arg_index(len: usize, idx: usize) -> usize {
+    if len == 1 {
+        0
+    } else {
+        idx
+    }
+}
+
+/// The decoded geometries of a geometry argument
+pub(crate) struct GeometryArg(Vec<Option<Geometry>>);
+
+impl GeometryArg {
+    pub fn try_new(array: &ArrayRef) -> Result<Self> {
+        let geometries = match array.data_type() {
+            DataType::Binary => decode(array.as_binary::<i32>().iter()),
+            DataType::LargeBinary => decode(array.as_binary::<i64>().iter()),
+            DataType::BinaryView => decode(array.as_binary_view().iter()),
+            DataType::FixedSizeBinary(_) => decode(array.as_fixed_size_binary().iter()),
+            DataType::Null => Ok(vec![None; array.len()]),
+            other => exec_err!("Unsupported data type {other:?} for a geometry"),
+        }?;
+        Ok(Self(geometries))
+    }
+
+    /// Returns the geometry of row `idx`, `None` if it is null
+    pub fn get(&self, idx: usize) -> Option<&Geometry> {
+        self.0[arg_index(self.0.len(), idx)].as_ref()
+    }
+}
+
+fn decode<'a>(
+    values: impl Iterator<Item = Option<&'a [u8]>>,
+) -> Result<Vec<Option<Geometry>>> {
+    values
+        .map(|value| value.map(read_wkb).transpose())
+        .collect()
+}
+
+/// Returns the value of row `idx` of a `Float64` argument
+pub(crate) fn float64_at(array: &Float64Array, idx: usize) -> Option<f64> {
+    let idx = arg_index(array.len(), idx);
+    array.is_valid(idx).then(|| array.value(idx))
+}
+
+/// Returns the `Float64` argument `array`
+pub(crate) fn as_float64(array: &ArrayRef) -> &Float64Array {
+    array.as_primitive::<Float64Type>()
+}
+
+/// Encodes geometries as a `Binary` array of WKB
+pub(crate) fn to_geometry_array(
+    geometries: impl IntoIterator<Item = Option<Geometry>>,
+) -> ArrayRef {
+    let mut builder = BinaryBuilder::new();
+    for geometry in geometries {
+        match geometry {
+            Some(geometry) => builder.append_value(write_wkb(&geometry)),
+            None => builder.append_null(),
+        }
+    }
+    Arc::new(builder.finish())
+}
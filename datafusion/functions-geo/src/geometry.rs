@@ -0,0 +1,169 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! In-memory representation of two dimensional geometries
+
+use crate::bounding_box::BoundingBox;
+
+/// A position in the plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coord {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Coord {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A sequence of positions, either a line string or a polygon ring
+pub type LineString = Vec<Coord>;
+
+/// A polygon: its exterior ring followed by its interior rings (holes)
+pub type Polygon = Vec<LineString>;
+
+/// A two dimensional geometry, following the OGC Simple Features model
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// A point, `None` for `POINT EMPTY`
+    Point(Option<Coord>),
+    LineString(LineString),
+    Polygon(Polygon),
+    MultiPoint(Vec<Coord>),
+    MultiLineString(Vec<LineString>),
+    MultiPolygon(Vec<Polygon>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl Geometry {
+    /// Returns the OGC name of the geometry type, e.g. `POINT`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Geometry::Point(_) => "POINT",
+            Geometry::LineString(_) => "LINESTRING",
+            Geometry::Polygon(_) => "POLYGON",
+            Geometry::MultiPoint(_) => "MULTIPOINT",
+            Geometry::MultiLineString(_) => "MULTILINESTRING",
+            Geometry::MultiPolygon(_) => "MULTIPOLYGON",
+            Geometry::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        }
+    }
+
+    /// Returns true if the geometry has no positions
+    pub fn is_empty(&self) -> bool {
+        let mut empty = true;
+        self.for_each_coord(&mut |_| empty = false);
+        empty
+    }
+
+    /// Returns the smallest axis-aligned box containing the geometry, or
+    /// `None` if the geometry is empty
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bbox: Option<BoundingBox> = None;
+        self.for_each_coord(&mut |coord| match bbox.as_mut() {
+            Some(bbox) => bbox.expand(coord),
+            None => bbox = Some(BoundingBox::from_coord(coord)),
+        });
+        bbox
+    }
+
+    /// Calls `f` for every position of the geometry
+    pub fn for_each_coord(&self, f: &mut impl FnMut(Coord)) {
+        match self {
+            Geometry::Point(coord) => coord.iter().copied().for_each(f),
+            Geometry::LineString(coords) | Geometry::MultiPoint(coords) => {
+                coords.iter().copied().for_each(f)
+            }
+            Geometry::Polygon(rings) | Geometry::MultiLineString(rings) => rings
+                .iter()
+                .flat_map(|ring| ring.iter().copied())
+                .for_each(f),
+            Geometry::MultiPolygon(polygons) => polygons
+                .iter()
+                .flatten()
+                .flat_map(|ring| ring.iter().copied())
+                .for_each(f),
+            Geometry::GeometryCollection(geometries) => geometries
+                .iter()
+                .for_each(|geometry| geometry.for_each_coord(f)),
+        }
+    }
+
+    /// Splits the geometry into its points, line strings and polygons
+    pub(crate) fn components(&self) -> Components<'_> {
+        let mut components = Components::default();
+        self.collect_components(&mut components);
+        components
+    }
+
+    fn collect_components<'a>(&'a self, components: &mut Components<'a>) {
+        match self {
+            Geometry::Point(coord) => components.points.extend(coord),
+            Geometry::MultiPoint(coords) => components.points.extend(coords),
+            Geometry::LineString(coords) => components.lines.push(coords),
+            Geometry::MultiLineString(lines) => {
+                components.lines.extend(lines.iter().map(Vec::as_slice))
+            }
+            Geometry::Polygon(rings) => components.polygons.push(rings),
+            Geometry::MultiPolygon(polygons) => components
+                .polygons
+                .extend(polygons.iter().map(Vec::as_slice)),
+            Geometry::GeometryCollection(geometries) => geometries
+                .iter()
+                .for_each(|geometry| geometry.collect_components(components)),
+        }
+    }
+}
+
+/// The points, line strings and polygons of a geometry
+#[derive(Debug, Default)]
+pub(crate) struct Components<'a> {
+    pub points: Vec<Coord>,
+    pub lines: Vec<&'a [Coord]>,
+    pub polygons: Vec<&'a [LineString]>,
+}
+
+impl Components<'_> {
+    /// Returns every position of the components
+    pub fn vertices(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.points
+            .iter()
+            .copied()
+            .chain(self.lines.iter().flat_map(|line| line.iter().copied()))
+            .chain(
+                self.polygons
+                    .iter()
+                    .flat_map(|rings| rings.iter())
+                    .flat_map(|ring| ring.iter().copied()),
+            )
+    }
+
+    /// Returns the segments of the line strings and of the polygon rings
+    pub fn segments(&self) -> impl Iterator<Item = (Coord, Coord)> + '_ {
+        self.lines
+            .iter()
+            .copied()
+            .chain(
+                self.polygons
+                    .iter()
+                    .flat_map(|rings| rings.iter().map(Vec::as_slice)),
+            )
+            .flat_map(|line| line.windows(2).map(|segment| (segment[0], segment[1])))
+    }
+}
@@ -0,0 +1,152 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/apache/datafusion/19fe44cf2f30cbdd63d4a4f52c74055163c6cc38/docs/logos/standalone_logo/logo_original.svg",
+    html_favicon_url = "https://raw.githubusercontent.com/apache/datafusion/19fe44cf2f30cbdd63d4a4f52c74055163c6cc38/docs/logos/standalone_logo/logo_original.svg"
+)]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+// Make cheap clones clear: https://github.com/apache/datafusion/issues/11143
+#![deny(clippy::clone_on_ref_ptr)]
+
+//! Geospatial types and functions for [DataFusion].
+//!
+//! This crate contains planar geospatial functions following the naming of
+//! PostGIS (`st_point`, `st_intersects`, ...), implemented using the
+//! extension API.
+//!
+//! Geometries are stored as [Well-known binary] (WKB) in binary columns,
+//! annotated with the [GeoArrow] `geoarrow.wkb` extension type, see
+//! [`types::Wkb`].
+//!
+//! [DataFusion]: https://crates.io/crates/datafusion
+//! [Well-known binary]: https://libgeos.org/specifications/wkb/
+//! [GeoArrow]: https://geoarrow.org/extension-types.html
+//!
+//! # Example: using the functions
+//!
+//! You can register all the functions using the [`register_all`] function
+//! as shown below.
+//!
+//! ```
+//! # use datafusion_execution::FunctionRegistry;
+//! # use datafusion_expr::{ScalarUDF, AggregateUDF, WindowUDF};
+//! # use datafusion_expr::planner::ExprPlanner;
+//! # use datafusion_common::Result;
+//! # use std::collections::HashSet;
+//! # use std::sync::Arc;
+//! # // Note: We can't use a real SessionContext here because the
+//! # // `datafusion_functions_geo` crate has no dependence on the DataFusion crate
+//! # // thus use a dummy SessionContext that has enough of the implementation
+//! # struct SessionContext {}
+//! # impl FunctionRegistry for SessionContext {
+//! #    fn register_udf(&mut self, _udf: Arc<ScalarUDF>) -> Result<Option<Arc<ScalarUDF>>> { Ok (None) }
+//! #    fn udfs(&self) -> HashSet<String> { unimplemented!() }
+//! #    fn udf(&self, _name: &str) -> Result<Arc<ScalarUDF>> { unimplemented!() }
+//! #    fn udaf(&self, name: &str) -> Result<Arc<AggregateUDF>> {unimplemented!() }
+//! #    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> { unimplemented!() }
+//! #    fn expr_planners(&self) -> Vec<Arc<dyn ExprPlanner>> { unimplemented!() }
+//! # }
+//! # impl SessionContext {
+//! #   fn new() -> Self { SessionContext {} }
+//! #   async fn sql(&mut self, _query: &str) -> Result<()> { Ok(()) }
+//! #  }
+//! #
+//! # async fn stub() -> Result<()> {
+//! // Create a new session context
+//! let mut ctx = SessionContext::new();
+//! // register all geospatial functions with the context
+//! datafusion_functions_geo::register_all(&mut ctx)?;
+//! // run a query
+//! let df = ctx.sql("SELECT st_distance(st_point(0, 0), st_point(3, 4))").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Example: pruning Parquet row groups
+//!
+//! Parquet statistics of geometry columns cannot be used to skip row groups.
+//! Instead, the table stores the bounding box of each geometry `<geometry>`
+//! in four `Float64` columns named `<geometry>_xmin`, `<geometry>_ymin`,
+//! `<geometry>_xmax` and `<geometry>_ymax`, for instance written with:
+//!
+//! ```sql
+//! COPY (
+//!   SELECT id, geom, st_xmin(geom) AS geom_xmin, st_ymin(geom) AS geom_ymin,
+//!     st_xmax(geom) AS geom_xmax, st_ymax(geom) AS geom_ymax
+//!   FROM geometries
+//! ) TO 'geometries/' STORED AS PARQUET;
+//! ```
+//!
+//! The [`pruning::BoundingBoxPruning`] optimizer rule, that is not enabled by
+//! default and must be registered with `SessionContext::add_optimizer_rule`,
+//! then adds predicates on these columns to the `st_intersects` and
+//! `st_contains` filters against a constant geometry, so that the row groups
+//! whose bounding boxes cannot match are skipped.
+//!
+//! # Example: calling a specific function in Rust
+//!
+//! The [`expr_fn`] module contains functions that create [`Expr`]s invoking
+//! the geospatial functions:
+//!
+//! ```rust
+//! # use datafusion_expr::{col, lit};
+//! use datafusion_functions_geo::expr_fn::{st_intersects, st_point};
+//! // Create the expression `st_intersects(geom, st_point(1, 2))`
+//! let expr = st_intersects(col("geom"), st_point(lit(1.0), lit(2.0)));
+//! ```
+//!
+//! [`Expr`]: datafusion_expr::Expr
+
+pub mod algorithm;
+pub mod bounding_box;
+pub mod function;
+pub mod geometry;
+pub mod pruning;
+pub mod types;
+pub mod wkb;
+pub mod wkt;
+
+use datafusion_common::Result;
+use datafusion_execution::FunctionRegistry;
+use datafusion_expr::ScalarUDF;
+use log::debug;
+use std::sync::Arc;
+
+/// Fluent-style API for creating `Expr`s
+pub mod expr_fn {
+    pub use super::function::expr_fn::*;
+}
+
+/// Returns all default scalar functions
+pub fn all_default_scalar_functions() -> Vec<Arc<ScalarUDF>> {
+    function::functions()
+}
+
+/// Registers all the geospatial functions with a [`FunctionRegistry`]
+pub fn register_all(registry: &mut dyn FunctionRegistry) -> Result<()> {
+    let scalar_functions: Vec<Arc<ScalarUDF>> = all_default_scalar_functions();
+    scalar_functions.into_iter().try_for_each(|udf| {
+        let existing_udf = registry.register_udf(udf)?;
+        if let Some(existing_udf) = existing_udf {
+            debug!("Overwrite existing UDF: {}", existing_udf.name());
+        }
+        Ok(()) as Result<()>
+    })?;
+
+    Ok(())
+}
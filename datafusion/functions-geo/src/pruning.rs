@@ -0,0 +1,407 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`BoundingBoxPruning`] derives bounding box predicates from geospatial
+//! predicates, so that Parquet row groups can be pruned
+
+use crate::bounding_box::BoundingBox;
+use crate::function::st_bounds::Bound;
+use crate::function::st_contains::StContainsFunc;
+use crate::function::st_intersects::StIntersectsFunc;
+use crate::wkb::read_wkb;
+use datafusion_common::tree_node::Transformed;
+use datafusion_common::{internal_err, Column, Result, ScalarValue};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{
+    lit, Cast, Expr, Filter, LogicalPlan, Projection, TableScan, TryCast,
+};
+use datafusion_optimizer::optimizer::ApplyOrder;
+use datafusion_optimizer::{OptimizerConfig, OptimizerRule};
+use std::sync::Arc;
+
+/// Optimizer rule that adds bounding box predicates to filters on geometry
+/// columns.
+///
+/// Geometries are opaque binary values, so Parquet statistics cannot be used
+/// to skip row groups that do not match a geospatial predicate. When the
+/// bounding box of a geometry column `<geometry>` is stored in the
+/// `<geometry>_xmin`, `<geometry>_ymin`, `<geometry>_xmax` and
+/// `<geometry>_ymax` columns of the same table (for instance computed with
+/// `st_xmin` and friends when writing the table), this rule rewrites
+///
+/// ```text
+/// st_intersects(geom, <constant geometry>)
+/// ```
+///
+/// to
+///
+/// ```text
+/// st_intersects(geom, <constant geometry>)
+///   AND geom_xmin <= <xmax> AND geom_xmax >= <xmin>
+///   AND geom_ymin <= <ymax> AND geom_ymax >= <ymin>
+/// ```
+///
+/// where `<xmin>`, ... are the bounds of the constant geometry. The added
+/// predicates only use min/max comparisons, that the Parquet pruning
+/// predicate can evaluate on row group statistics. `st_contains` is handled
+/// the same way, in both directions. If the bounding box columns were
+/// projected away from the table scan below the filter, the scan is widened
+/// to read them and they are projected away again after the filter.
+///
+/// The rule is not enabled by default. It only applies to tables following
+/// the bounding box column convention above, and assumes that the bounding
+/// box columns cover the geometries: the added predicates are then redundant
+/// with the geospatial predicate and the results are unchanged, but rows
+/// whose bounding box columns do not cover their geometry may be missing from
+/// the results.
+#[derive(Default, Debug)]
+pub struct BoundingBoxPruning {}
+
+impl BoundingBoxPruning {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for BoundingBoxPruning {
+    fn name(&self) -> &str {
+        "bounding_box_pruning"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let LogicalPlan::Filter(filter) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let conjuncts = split_conjunction(&filter.predicate);
+        let schema = Arc::clone(filter.input.schema());
+        // Indices of the bounding box columns that were projected away from
+        // the table scan below the filter
+        let mut missing_indices = vec![];
+        let mut new_conjuncts = vec![];
+        for conjunct in &conjuncts {
+            let Some((bound_columns, predicates)) = bounding_box_predicates(conjunct)
+            else {
+                continue;
+            };
+            let Some(indices) = bound_columns
+                .iter()
+                .filter(|column| !schema.has_column(column))
+                .map(|column| unprojected_column_index(&filter.input, column))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            for predicate in predicates {
+                if !conjuncts.contains(&&predicate) && !new_conjuncts.contains(&predicate)
+                {
+                    new_conjuncts.push(predicate);
+                }
+            }
+            for idx in indices {
+                if !missing_indices.contains(&idx) {
+                    missing_indices.push(idx);
+                }
+            }
+        }
+        if new_conjuncts.is_empty() {
+            return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+        }
+
+        let predicate =
+            conjunction(conjuncts.into_iter().cloned().chain(new_conjuncts)).unwrap();
+        if missing_indices.is_empty() {
+            return Filter::try_new(predicate, filter.input)
+                .map(|filter| Transformed::yes(LogicalPlan::Filter(filter)));
+        }
+
+        // Read the bounding box columns from the table as well, and project
+        // them away again after the filter
+        let LogicalPlan::TableScan(scan) = Arc::unwrap_or_clone(filter.input) else {
+            return internal_err!("Expected a table scan below the filter");
+        };
+        let mut projection = scan.projection.unwrap_or_default();
+        projection.extend(missing_indices);
+        let scan = TableScan::try_new(
+            scan.table_name,
+            scan.source,
+            Some(projection),
+            scan.filters,
+            scan.fetch,
+        )?;
+        let filter = Filter::try_new(predicate, Arc::new(LogicalPlan::TableScan(scan)))?;
+        Ok(Transformed::yes(LogicalPlan::Projection(
+            Projection::new_from_schema(Arc::new(LogicalPlan::Filter(filter)), schema),
+        )))
+    }
+}
+
+/// Returns the index in the table schema of `column`, if `plan` is a scan of
+/// the table of `column` that does not project it
+fn unprojected_column_index(plan: &LogicalPlan, column: &Column) -> Option<usize> {
+    let LogicalPlan::TableScan(scan) = plan else {
+        return None;
+    };
+    if scan.projection.is_none()
+        || column
+            .relation
+            .as_ref()
+            .is_some_and(|relation| !scan.table_name.resolved_eq(relation))
+    {
+        return None;
+    }
+    scan.source.schema().index_of(&column.name).ok()
+}
+
+/// How the bounding box of a geometry column must relate to the bounding box
+/// of a constant geometry for the geospatial predicate to possibly be true
+#[derive(Debug, Clone, Copy)]
+enum Relation {
+    Intersects,
+    /// The column is within the constant geometry
+    Within,
+    /// The column contains the constant geometry
+    Contains,
+}
+
+/// Returns the bounding box columns and the bounding box predicates implied
+/// by `expr`
+fn bounding_box_predicates(expr: &Expr) -> Option<([Column; 4], Vec<Expr>)> {
+    let Expr::ScalarFunction(ScalarFunction { func, args }) = expr else {
+        return None;
+    };
+    let [arg1, arg2] = args.as_slice() else {
+        return None;
+    };
+    let inner = func.inner().as_any();
+    let (column, bbox, relation) = if inner.is::<StIntersectsFunc>() {
+        match (as_column(arg1), as_column(arg2)) {
+            (Some(column), None) => (column, constant_bbox(arg2), Relation::Intersects),
+            (None, Some(column)) => (column, constant_bbox(arg1), Relation::Intersects),
+            _ => return None,
+        }
+    } else if inner.is::<StContainsFunc>() {
+        match (as_column(arg1), as_column(arg2)) {
+            (Some(column), None) => (column, constant_bbox(arg2), Relation::Contains),
+            (None, Some(column)) => (column, constant_bbox(arg1), Relation::Within),
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    let bbox = bbox?;
+
+    let bound_columns =
+        [Bound::XMin, Bound::YMin, Bound::XMax, Bound::YMax].map(|bound| {
+            Column::new(
+                column.relation.clone(),
+                format!("{}_{}", column.name, bound.suffix()),
+            )
+        });
+    let [xmin, ymin, xmax, ymax] = bound_columns.clone().map(Expr::Column);
+
+    let predicates = match relation {
+        Relation::Intersects => vec![
+            xmin.lt_eq(lit(bbox.xmax)),
+            xmax.gt_eq(lit(bbox.xmin)),
+            ymin.lt_eq(lit(bbox.ymax)),
+            ymax.gt_eq(lit(bbox.ymin)),
+        ],
+        Relation::Within => vec![
+            xmin.gt_eq(lit(bbox.xmin)),
+            xmax.lt_eq(lit(bbox.xmax)),
+            ymin.gt_eq(lit(bbox.ymin)),
+            ymax.lt_eq(lit(bbox.ymax)),
+        ],
+        Relation::Contains => vec![
+            xmin.lt_eq(lit(bbox.xmin)),
+            xmax.gt_eq(lit(bbox.xmax)),
+            ymin.lt_eq(lit(bbox.ymin)),
+            ymax.gt_eq(lit(bbox.ymax)),
+        ],
+    };
+    Some((bound_columns, predicates))
+}
+
+/// Returns the column of `expr`, ignoring the casts added by type coercion
+fn as_column(expr: &Expr) -> Option<&Column> {
+    match expr {
+        Expr::Column(column) => Some(column),
+        Expr::Cast(Cast { expr, .. }) | Expr::TryCast(TryCast { expr, .. }) => {
+            as_column(expr)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the bounding box of a constant geometry, or `None` if `expr` is
+/// not a constant or is an empty geometry
+fn constant_bbox(expr: &Expr) -> Option<BoundingBox> {
+    match expr {
+        Expr::Literal(
+            ScalarValue::Binary(Some(wkb))
+            | ScalarValue::LargeBinary(Some(wkb))
+            | ScalarValue::BinaryView(Some(wkb))
+            | ScalarValue::FixedSizeBinary(_, Some(wkb)),
+            _,
+        ) => read_wkb(wkb).ok()?.bounding_box(),
+        Expr::Cast(Cast { expr, .. }) | Expr::TryCast(TryCast { expr, .. }) => {
+            constant_bbox(expr)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr_fn::{st_contains, st_intersects};
+    use crate::geometry::{Coord, Geometry};
+    use crate::wkb::write_wkb;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_expr::logical_plan::builder::table_scan;
+    use datafusion_expr::{col, LogicalPlanBuilder};
+    use datafusion_optimizer::OptimizerContext;
+
+    fn scan() -> Result<LogicalPlanBuilder> {
+        let schema = Schema::new(vec![
+            Field::new("geom", DataType::Binary, true),
+            Field::new("geom_xmin", DataType::Float64, true),
+            Field::new("geom_ymin", DataType::Float64, true),
+            Field::new("geom_xmax", DataType::Float64, true),
+            Field::new("geom_ymax", DataType::Float64, true),
+            Field::new("other", DataType::Binary, true),
+        ]);
+        table_scan(Some("t"), &schema, None)
+    }
+
+    fn polygon() -> Expr {
+        let ring = vec![
+            Coord::new(1.0, 2.0),
+            Coord::new(3.0, 2.0),
+            Coord::new(3.0, 4.0),
+            Coord::new(1.0, 2.0),
+        ];
+        lit(write_wkb(&Geometry::Polygon(vec![ring])))
+    }
+
+    fn optimize(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+        BoundingBoxPruning::new().rewrite(plan, &OptimizerContext::new())
+    }
+
+    fn filter_predicate(plan: &LogicalPlan) -> String {
+        match plan {
+            LogicalPlan::Filter(filter) => filter.predicate.to_string(),
+            other => panic!("Expected a filter, got {other}"),
+        }
+    }
+
+    #[test]
+    fn test_intersects() -> Result<()> {
+        let plan = scan()?
+            .filter(st_intersects(col("geom"), polygon()))?
+            .build()?;
+        let optimized = optimize(plan)?;
+        assert!(optimized.transformed);
+        let predicate = filter_predicate(&optimized.data);
+        assert!(
+            predicate.ends_with(
+                "AND t.geom_xmin <= Float64(3) AND t.geom_xmax >= Float64(1) \
+                AND t.geom_ymin <= Float64(4) AND t.geom_ymax >= Float64(2)"
+            ),
+            "{predicate}"
+        );
+
+        // The rule does not add the predicates twice
+        assert!(!optimize(optimized.data)?.transformed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains() -> Result<()> {
+        let plan = scan()?
+            .filter(st_contains(polygon(), col("geom")))?
+            .build()?;
+        let predicate = filter_predicate(&optimize(plan)?.data);
+        assert!(
+            predicate.ends_with(
+                "AND t.geom_xmin >= Float64(1) AND t.geom_xmax <= Float64(3) \
+                AND t.geom_ymin >= Float64(2) AND t.geom_ymax <= Float64(4)"
+            ),
+            "{predicate}"
+        );
+
+        let plan = scan()?
+            .filter(st_contains(col("geom"), polygon()))?
+            .build()?;
+        let predicate = filter_predicate(&optimize(plan)?.data);
+        assert!(
+            predicate.ends_with(
+                "AND t.geom_xmin <= Float64(1) AND t.geom_xmax >= Float64(3) \
+                AND t.geom_ymin <= Float64(2) AND t.geom_ymax >= Float64(4)"
+            ),
+            "{predicate}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_projected_scan() -> Result<()> {
+        let schema = scan()?.schema().as_arrow().clone();
+        let plan = table_scan(Some("t"), &schema, Some(vec![0]))?
+            .filter(st_intersects(col("geom"), polygon()))?
+            .build()?;
+        let optimized = optimize(plan)?;
+        assert!(optimized.transformed);
+        let LogicalPlan::Projection(projection) = &optimized.data else {
+            panic!("Expected a projection, got {}", optimized.data);
+        };
+        assert_eq!(projection.schema.fields().len(), 1);
+        assert!(filter_predicate(&projection.input).ends_with(
+            "AND t.geom_xmin <= Float64(3) AND t.geom_xmax >= Float64(1) \
+                AND t.geom_ymin <= Float64(4) AND t.geom_ymax >= Float64(2)"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_bounding_box_columns() -> Result<()> {
+        let plan = scan()?
+            .filter(st_intersects(polygon(), col("other")))?
+            .build()?;
+        assert!(!optimize(plan)?.transformed);
+
+        let plan = scan()?
+            .filter(st_intersects(col("geom"), col("other")))?
+            .build()?;
+        assert!(!optimize(plan)?.transformed);
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The geometry type
+//!
+//! Geometries are stored as [Well-known binary] in binary arrays, annotated
+//! with the [GeoArrow] `geoarrow.wkb` extension type. The extension type is
+//! also a [`LogicalType`], so that planning can tell geometries apart from
+//! other binary values.
+//!
+//! [Well-known binary]: https://libgeos.org/specifications/wkb/
+//! [GeoArrow]: https://geoarrow.org/extension-types.html
+
+use arrow::datatypes::{DataType, Field};
+use arrow::error::ArrowError;
+use arrow_schema::extension::ExtensionType;
+use datafusion_common::types::{LogicalType, LogicalTypeRef, NativeType, TypeSignature};
+use std::sync::{Arc, LazyLock};
+
+/// The `geoarrow.wkb` extension type: planar geometries encoded as
+/// Well-known binary
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Wkb;
+
+impl ExtensionType for Wkb {
+    const NAME: &'static str = "geoarrow.wkb";
+
+    type Metadata = ();
+
+    fn metadata(&self) -> &Self::Metadata {
+        &()
+    }
+
+    fn serialize_metadata(&self) -> Option<String> {
+        // No coordinate reference system (CRS) nor edge interpolation, that
+        // is planar geometries in an unknown CRS
+        Some("{}".to_string())
+    }
+
+    fn deserialize_metadata(
+        _metadata: Option<&str>,
+    ) -> Result<Self::Metadata, ArrowError> {
+        // The CRS and edges metadata do not change the representation of
+        // geometries and are ignored
+        Ok(())
+    }
+
+    fn supports_data_type(&self, data_type: &DataType) -> Result<(), ArrowError> {
+        match data_type {
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => Ok(()),
+            other => Err(ArrowError::InvalidArgumentError(format!(
+                "{} data type mismatch, expected Binary, LargeBinary or BinaryView, found {other}",
+                Self::NAME
+            ))),
+        }
+    }
+
+    fn try_new(
+        data_type: &DataType,
+        _metadata: Self::Metadata,
+    ) -> Result<Self, ArrowError> {
+        Wkb.supports_data_type(data_type).map(|_| Wkb)
+    }
+}
+
+impl LogicalType for Wkb {
+    fn native(&self) -> &NativeType {
+        &NativeType::Binary
+    }
+
+    fn signature(&self) -> TypeSignature<'_> {
+        TypeSignature::Extension {
+            name: <Self as ExtensionType>::NAME,
+            parameters: &[],
+        }
+    }
+}
+
+static LOGICAL_WKB: LazyLock<LogicalTypeRef> = LazyLock::new(|| Arc::new(Wkb));
+
+/// Getter for singleton instance of the logical geometry type, see [`Wkb`]
+pub fn logical_wkb() -> LogicalTypeRef {
+    Arc::clone(&LOGICAL_WKB)
+}
+
+/// Returns a nullable `Binary` field named `name` annotated with the
+/// geometry extension type
+pub fn geometry_field(name: impl Into<String>) -> Field {
+    Field::new(name, DataType::Binary, true).with_extension_type(Wkb)
+}
+
+/// Returns true if `field` is annotated with the geometry extension type
+pub fn is_geometry_field(field: &Field) -> bool {
+    field.extension_type_name() == Some(Wkb::NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_common::types::logical_binary;
+
+    #[test]
+    fn test_geometry_field() {
+        let field = geometry_field("geom");
+        assert!(is_geometry_field(&field));
+        assert_eq!(
+            field
+                .metadata()
+                .get("ARROW:extension:name")
+                .map(String::as_str),
+            Some("geoarrow.wkb")
+        );
+        assert!(field.try_extension_type::<Wkb>().is_ok());
+
+        let mut field = Field::new("geom", DataType::Utf8, true);
+        assert!(!is_geometry_field(&field));
+        assert!(field.try_with_extension_type(Wkb).is_err());
+    }
+
+    #[test]
+    fn test_logical_type() {
+        assert_eq!(logical_wkb().native(), &NativeType::Binary);
+        assert!(logical_wkb() != logical_binary());
+        assert!(logical_wkb() == logical_wkb());
+    }
+}
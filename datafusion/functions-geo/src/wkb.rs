@@ -0,0 +1,340 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading and writing the [Well-known binary] (WKB) representation of
+//! geometries
+//!
+//! [Well-known binary]: https://libgeos.org/specifications/wkb/
+
+use crate::geometry::{Coord, Geometry, LineString, Polygon};
+use datafusion_common::{exec_datafusion_err, exec_err, not_impl_err, Result};
+
+const WKB_POINT: u32 = 1;
+const WKB_LINESTRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOINT: u32 = 4;
+const WKB_MULTILINESTRING: u32 = 5;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// Extended WKB (PostGIS) flags
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Parses a geometry from its WKB representation.
+///
+/// Both byte orders are supported, as well as the SRID of extended WKB,
+/// which is ignored. Geometries with Z or M dimensions are not supported.
+pub fn read_wkb(buf: &[u8]) -> Result<Geometry> {
+    let mut reader = WkbReader { buf, offset: 0 };
+    let geometry = reader.read_geometry()?;
+    if reader.offset != buf.len() {
+        return exec_err!(
+            "Invalid WKB: {} trailing bytes after the geometry",
+            buf.len() - reader.offset
+        );
+    }
+    Ok(geometry)
+}
+
+/// Returns the little-endian WKB representation of `geometry`
+pub fn write_wkb(geometry: &Geometry) -> Vec<u8> {
+    let mut buf = vec![];
+    write_geometry(&mut buf, geometry);
+    buf
+}
+
+struct WkbReader<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self.buf.get(self.offset..self.offset + N).ok_or_else(|| {
+            exec_datafusion_err!("Invalid WKB: unexpected end of input")
+        })?;
+        self.offset += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32> {
+        let bytes = self.take::<4>()?;
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self, little_endian: bool) -> Result<f64> {
+        let bytes = self.take::<8>()?;
+        Ok(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_coord(&mut self, little_endian: bool) -> Result<Coord> {
+        let x = self.read_f64(little_endian)?;
+        let y = self.read_f64(little_endian)?;
+        Ok(Coord::new(x, y))
+    }
+
+    fn read_coords(&mut self, little_endian: bool) -> Result<LineString> {
+        let len = self.read_len(little_endian, 16)?;
+        (0..len).map(|_| self.read_coord(little_endian)).collect()
+    }
+
+    fn read_polygon(&mut self, little_endian: bool) -> Result<Polygon> {
+        let len = self.read_len(little_endian, 4)?;
+        (0..len).map(|_| self.read_coords(little_endian)).collect()
+    }
+
+    /// Reads a number of elements, checking that the remaining input can hold
+    /// them to avoid huge allocations on corrupted input
+    fn read_len(
+        &mut self,
+        little_endian: bool,
+        min_element_size: usize,
+    ) -> Result<usize> {
+        let len = self.read_u32(little_endian)? as usize;
+        if len.saturating_mul(min_element_size) > self.buf.len() - self.offset {
+            return exec_err!("Invalid WKB: unexpected end of input");
+        }
+        Ok(len)
+    }
+
+    fn read_geometry(&mut self) -> Result<Geometry> {
+        let little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            [other] => return exec_err!("Invalid WKB: unknown byte order {other}"),
+        };
+        let mut geometry_type = self.read_u32(little_endian)?;
+        if geometry_type & (EWKB_Z | EWKB_M) != 0 || geometry_type % 10000 >= 1000 {
+            return not_impl_err!("WKB geometries with Z or M dimensions");
+        }
+        if geometry_type & EWKB_SRID != 0 {
+            self.read_u32(little_endian)?;
+            geometry_type &= !EWKB_SRID;
+        }
+
+        Ok(match geometry_type {
+            WKB_POINT => {
+                let coord = self.read_coord(little_endian)?;
+                // POINT EMPTY is encoded with NaN coordinates
+                Geometry::Point((!coord.x.is_nan() || !coord.y.is_nan()).then_some(coord))
+            }
+            WKB_LINESTRING => Geometry::LineString(self.read_coords(little_endian)?),
+            WKB_POLYGON => Geometry::Polygon(self.read_polygon(little_endian)?),
+            WKB_MULTIPOINT => {
+                let points = self.read_children(little_endian)?;
+                let coords = points
+                    .into_iter()
+                    .filter_map(|point| match point {
+                        Geometry::Point(coord) => Some(Ok(coord?)),
+                        other => Some(exec_err!(
+                            "Invalid WKB: MULTIPOINT cannot contain a {}",
+                            other.type_name()
+                        )),
+                    })
+                    .collect::<Result<_>>()?;
+                Geometry::MultiPoint(coords)
+            }
+            WKB_MULTILINESTRING => {
+                let lines = self.read_children(little_endian)?;
+                let lines = lines
+                    .into_iter()
+                    .map(|line| match line {
+                        Geometry::LineString(coords) => Ok(coords),
+                        other => exec_err!(
+                            "Invalid WKB: MULTILINESTRING cannot contain a {}",
+                            other.type_name()
+                        ),
+                    })
+                    .collect::<Result<_>>()?;
+                Geometry::MultiLineString(lines)
+            }
+            WKB_MULTIPOLYGON => {
+                let polygons = self.read_children(little_endian)?;
+                let polygons = polygons
+                    .into_iter()
+                    .map(|polygon| match polygon {
+                        Geometry::Polygon(rings) => Ok(rings),
+                        other => exec_err!(
+                            "Invalid WKB: MULTIPOLYGON cannot contain a {}",
+                            other.type_name()
+                        ),
+                    })
+                    .collect::<Result<_>>()?;
+                Geometry::MultiPolygon(polygons)
+            }
+            WKB_GEOMETRYCOLLECTION => {
+                Geometry::GeometryCollection(self.read_children(little_endian)?)
+            }
+            other => return exec_err!("Invalid WKB: unknown geometry type {other}"),
+        })
+    }
+
+    fn read_children(&mut self, little_endian: bool) -> Result<Vec<Geometry>> {
+        // The smallest geometry is an empty one: byte order, type and length
+        let len = self.read_len(little_endian, 9)?;
+        (0..len).map(|_| self.read_geometry()).collect()
+    }
+}
+
+fn write_header(buf: &mut Vec<u8>, geometry_type: u32) {
+    buf.push(1);
+    buf.extend_from_slice(&geometry_type.to_le_bytes());
+}
+
+fn write_coord(buf: &mut Vec<u8>, coord: Coord) {
+    buf.extend_from_slice(&coord.x.to_le_bytes());
+    buf.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_len(buf: &mut Vec<u8>, len: usize) {
+    buf.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn write_coords(buf: &mut Vec<u8>, coords: &[Coord]) {
+    write_len(buf, coords.len());
+    coords.iter().for_each(|coord| write_coord(buf, *coord));
+}
+
+fn write_polygon(buf: &mut Vec<u8>, rings: &[LineString]) {
+    write_len(buf, rings.len());
+    rings.iter().for_each(|ring| write_coords(buf, ring));
+}
+
+fn write_geometry(buf: &mut Vec<u8>, geometry: &Geometry) {
+    match geometry {
+        Geometry::Point(coord) => {
+            write_header(buf, WKB_POINT);
+            write_coord(buf, coord.unwrap_or(Coord::new(f64::NAN, f64::NAN)));
+        }
+        Geometry::LineString(coords) => {
+            write_header(buf, WKB_LINESTRING);
+            write_coords(buf, coords);
+        }
+        Geometry::Polygon(rings) => {
+            write_header(buf, WKB_POLYGON);
+            write_polygon(buf, rings);
+        }
+        Geometry::MultiPoint(coords) => {
+            write_header(buf, WKB_MULTIPOINT);
+            write_len(buf, coords.len());
+            for coord in coords {
+                write_header(buf, WKB_POINT);
+                write_coord(buf, *coord);
+            }
+        }
+        Geometry::MultiLineString(lines) => {
+            write_header(buf, WKB_MULTILINESTRING);
+            write_len(buf, lines.len());
+            for line in lines {
+                write_header(buf, WKB_LINESTRING);
+                write_coords(buf, line);
+            }
+        }
+        Geometry::MultiPolygon(polygons) => {
+            write_header(buf, WKB_MULTIPOLYGON);
+            write_len(buf, polygons.len());
+            for rings in polygons {
+                write_header(buf, WKB_POLYGON);
+                write_polygon(buf, rings);
+            }
+        }
+        Geometry::GeometryCollection(geometries) => {
+            write_header(buf, WKB_GEOMETRYCOLLECTION);
+            write_len(buf, geometries.len());
+            geometries
+                .iter()
+                .for_each(|geometry| write_geometry(buf, geometry));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Polygon {
+        vec![vec![
+            Coord::new(0.0, 0.0),
+            Coord::new(1.0, 0.0),
+            Coord::new(1.0, 1.0),
+            Coord::new(0.0, 1.0),
+            Coord::new(0.0, 0.0),
+        ]]
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let geometries = vec![
+            Geometry::Point(Some(Coord::new(1.0, 2.0))),
+            Geometry::Point(None),
+            Geometry::LineString(vec![Coord::new(0.0, 0.0), Coord::new(1.0, 1.0)]),
+            Geometry::Polygon(square()),
+            Geometry::MultiPoint(vec![Coord::new(0.0, 0.0), Coord::new(1.0, 1.0)]),
+            Geometry::MultiLineString(vec![vec![], vec![Coord::new(1.0, 1.0)]]),
+            Geometry::MultiPolygon(vec![square(), square()]),
+            Geometry::GeometryCollection(vec![
+                Geometry::Point(Some(Coord::new(1.0, 2.0))),
+                Geometry::Polygon(square()),
+            ]),
+        ];
+        for geometry in geometries {
+            assert_eq!(read_wkb(&write_wkb(&geometry))?, geometry);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_big_endian_ewkb() -> Result<()> {
+        // POINT(1 2) with SRID 4326
+        let mut buf = vec![0];
+        buf.extend_from_slice(&(WKB_POINT | EWKB_SRID).to_be_bytes());
+        buf.extend_from_slice(&4326u32.to_be_bytes());
+        buf.extend_from_slice(&1.0f64.to_be_bytes());
+        buf.extend_from_slice(&2.0f64.to_be_bytes());
+        assert_eq!(read_wkb(&buf)?, Geometry::Point(Some(Coord::new(1.0, 2.0))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let wkb = write_wkb(&Geometry::Polygon(square()));
+        let err = read_wkb(&wkb[..wkb.len() - 1]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid WKB: unexpected end of input"
+        );
+
+        let err = read_wkb(&[1, 1, 0x10, 0, 0]).unwrap_err();
+        assert!(err.to_string().contains("Z or M dimensions"), "{err}");
+
+        let err = read_wkb(&[2]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid WKB: unknown byte order 2"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading and writing the [Well-known text] (WKT) representation of
+//! geometries
+//!
+//! [Well-known text]: https://libgeos.org/specifications/wkt/
+
+use crate::geometry::{Coord, Geometry, LineString, Polygon};
+use datafusion_common::{exec_err, not_impl_err, Result};
+use std::fmt::Write;
+
+/// Parses a geometry from its WKT representation, e.g. `POINT(1 2)`.
+///
+/// Keywords are case insensitive and an optional `SRID=<srid>;` prefix
+/// (extended WKT) is ignored. Geometries with Z or M dimensions are not
+/// supported.
+pub fn read_wkt(wkt: &str) -> Result<Geometry> {
+    let mut wkt = wkt.trim_start();
+    if wkt.len() >= 5 && wkt[..5].eq_ignore_ascii_case("SRID=") {
+        match wkt.find(';') {
+            Some(end) => wkt = &wkt[end + 1..],
+            None => return exec_err!("Invalid WKT: missing ';' after SRID"),
+        }
+    }
+
+    let mut parser = WktParser { input: wkt, pos: 0 };
+    let geometry = parser.parse_geometry()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.input.len() {
+        return parser.error("end of input");
+    }
+    Ok(geometry)
+}
+
+/// Returns the WKT representation of `geometry`, e.g. `POINT(1 2)`
+pub fn write_wkt(geometry: &Geometry) -> String {
+    let mut wkt = String::new();
+    write_geometry(&mut wkt, geometry);
+    wkt
+}
+
+struct WktParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl WktParser<'_> {
+    fn error<T>(&self, expected: &str) -> Result<T> {
+        let found = match self.input[self.pos..].chars().next() {
+            Some(c) => format!("'{c}'"),
+            None => "end of input".to_string(),
+        };
+        exec_err!(
+            "Invalid WKT: expected {expected} at position {}, found {found}",
+            self.pos
+        )
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.input[self.pos..].chars().next()
+    }
+
+    /// Consumes `c` if it is the next character
+    fn consume(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            self.error(&format!("'{c}'"))
+        }
+    }
+
+    fn parse_word(&mut self) -> &str {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn parse_number(&mut self) -> Result<f64> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| {
+                !(c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+')
+            })
+            .unwrap_or(rest.len());
+        match rest[..len].parse::<f64>() {
+            Ok(number) => {
+                self.pos += len;
+                Ok(number)
+            }
+            Err(_) => self.error("a number"),
+        }
+    }
+
+    fn parse_coord(&mut self) -> Result<Coord> {
+        let x = self.parse_number()?;
+        let y = self.parse_number()?;
+        if matches!(self.peek(), Some(c) if c != ',' && c != ')') {
+            return not_impl_err!("WKT geometries with Z or M dimensions");
+        }
+        Ok(Coord::new(x, y))
+    }
+
+    /// Parses `EMPTY`, returning true, or an opening parenthesis, returning
+    /// false
+    fn parse_empty(&mut self) -> Result<bool> {
+        if self.consume('(') {
+            return Ok(false);
+        }
+        let word = self.parse_word().to_ascii_uppercase();
+        match word.as_str() {
+            "EMPTY" => Ok(true),
+            "Z" | "M" | "ZM" => not_impl_err!("WKT geometries with Z or M dimensions"),
+            _ => self.error("'(' or EMPTY"),
+        }
+    }
+
+    /// Parses a comma separated list of elements that follows `EMPTY` or an
+    /// opening parenthesis
+    fn parse_list<T>(
+        &mut self,
+        mut parse_element: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        if self.parse_empty()? {
+            return Ok(vec![]);
+        }
+        let mut elements = vec![parse_element(self)?];
+        while self.consume(',') {
+            elements.push(parse_element(self)?);
+        }
+        self.expect(')')?;
+        Ok(elements)
+    }
+
+    fn parse_coords(&mut self) -> Result<LineString> {
+        self.parse_list(Self::parse_coord)
+    }
+
+    fn parse_polygon(&mut self) -> Result<Polygon> {
+        self.parse_list(Self::parse_coords)
+    }
+
+    /// Parses a point of a multi point, which may or may not be enclosed
+    /// in parentheses
+    fn parse_multipoint_element(&mut self) -> Result<Coord> {
+        if self.consume('(') {
+            let coord = self.parse_coord()?;
+            self.expect(')')?;
+            Ok(coord)
+        } else {
+            self.parse_coord()
+        }
+    }
+
+    fn parse_geometry(&mut self) -> Result<Geometry> {
+        let start = self.pos;
+        let keyword = self.parse_word().to_ascii_uppercase();
+        Ok(match keyword.as_str() {
+            "POINT" => {
+                if self.parse_empty()? {
+                    Geometry::Point(None)
+                } else {
+                    let coord = self.parse_coord()?;
+                    self.expect(')')?;
+                    Geometry::Point(Some(coord))
+                }
+            }
+            "LINESTRING" => Geometry::LineString(self.parse_coords()?),
+            "POLYGON" => Geometry::Polygon(self.parse_polygon()?),
+            "MULTIPOINT" => {
+                Geometry::MultiPoint(self.parse_list(Self::parse_multipoint_element)?)
+            }
+            "MULTILINESTRING" => {
+                Geometry::MultiLineString(self.parse_list(Self::parse_coords)?)
+            }
+            "MULTIPOLYGON" => {
+                Geometry::MultiPolygon(self.parse_list(Self::parse_polygon)?)
+            }
+            "GEOMETRYCOLLECTION" => {
+                Geometry::GeometryCollection(self.parse_list(Self::parse_geometry)?)
+            }
+            _ => {
+                self.pos = start;
+                self.skip_whitespace();
+                return self.error("a geometry type");
+            }
+        })
+    }
+}
+
+fn write_coord(wkt: &mut String, coord: &Coord) {
+    write!(wkt, "{} {}", coord.x, coord.y).unwrap();
+}
+
+/// Writes `EMPTY` or the comma separated `elements` between parentheses
+fn write_list<T>(
+    wkt: &mut String,
+    elements: &[T],
+    mut write_element: impl FnMut(&mut String, &T),
+) {
+    if elements.is_empty() {
+        wkt.push_str(" EMPTY");
+        return;
+    }
+    wkt.push('(');
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            wkt.push(',');
+        }
+        write_element(wkt, element);
+    }
+    wkt.push(')');
+}
+
+fn write_coords(wkt: &mut String, coords: &LineString) {
+    write_list(wkt, coords, write_coord)
+}
+
+fn write_polygon(wkt: &mut String, rings: &Polygon) {
+    write_list(wkt, rings, write_coords)
+}
+
+fn write_geometry(wkt: &mut String, geometry: &Geometry) {
+    wkt.push_str(geometry.type_name());
+    match geometry {
+        Geometry::Point(coord) => write_list(wkt, coord.as_slice(), write_coord),
+        Geometry::LineString(coords) => write_coords(wkt, coords),
+        Geometry::Polygon(rings) => write_polygon(wkt, rings),
+        Geometry::MultiPoint(coords) => write_list(wkt, coords, |wkt, coord| {
+            wkt.push('(');
+            write_coord(wkt, coord);
+            wkt.push(')');
+        }),
+        Geometry::MultiLineString(lines) => write_list(wkt, lines, write_coords),
+        Geometry::MultiPolygon(polygons) => write_list(wkt, polygons, write_polygon),
+        Geometry::GeometryCollection(geometries) => {
+            write_list(wkt, geometries, write_geometry)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(wkt: &str) -> String {
+        write_wkt(&read_wkt(wkt).unwrap())
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for wkt in [
+            "POINT(1 2)",
+            "POINT EMPTY",
+            "LINESTRING(0 0,1 1.5,-2 3)",
+            "LINESTRING EMPTY",
+            "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1))",
+            "MULTIPOINT((1 2),(3 4))",
+            "MULTILINESTRING((0 0,1 1),(2 2,3 3))",
+            "MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((5 5,6 5,6 6,5 5)))",
+            "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))",
+            "GEOMETRYCOLLECTION EMPTY",
+        ] {
+            assert_eq!(roundtrip(wkt), wkt);
+        }
+    }
+
+    #[test]
+    fn test_read_variants() {
+        assert_eq!(roundtrip("  point ( 1e3   -2 ) "), "POINT(1000 -2)");
+        assert_eq!(roundtrip("MULTIPOINT(1 2, 3 4)"), "MULTIPOINT((1 2),(3 4))");
+        assert_eq!(roundtrip("SRID=4326;POINT(1 2)"), "POINT(1 2)");
+        assert_eq!(roundtrip("Polygon empty"), "POLYGON EMPTY");
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let err = read_wkt("POINT(1)").unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid WKT: expected a number at position 7, found ')'"
+        );
+
+        let err = read_wkt("CIRCLE(1 2)").unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid WKT: expected a geometry type at position 0, found 'C'"
+        );
+
+        let err = read_wkt("POINT(1 2) x").unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid WKT: expected end of input at position 11, found 'x'"
+        );
+
+        let err = read_wkt("POINT Z (1 2 3)").unwrap_err();
+        assert!(err.to_string().contains("Z or M dimensions"), "{err}");
+
+        let err = read_wkt("POINT 1 2").unwrap_err();
+        assert!(err.to_string().contains("expected '(' or EMPTY"), "{err}");
+    }
+}
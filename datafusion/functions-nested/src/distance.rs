@@ -15,15 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! [ScalarUDFImpl] definitions for array_distance function.
+//! [ScalarUDFImpl] definitions for array_distance and the vector distance functions.
 
 use crate::utils::make_scalar_function;
 use arrow::array::{
-    Array, ArrayRef, Float64Array, LargeListArray, ListArray, OffsetSizeTrait,
+    Array, ArrayRef, AsArray, Float64Array, LargeListArray, ListArray, OffsetSizeTrait,
+    PrimitiveArray,
 };
+use arrow::buffer::NullBuffer;
 use arrow::datatypes::{
-    DataType,
+    ArrowPrimitiveType, DataType,
     DataType::{FixedSizeList, LargeList, List, Null},
+    Field, Float32Type, Float64Type,
 };
 use datafusion_common::cast::{
     as_float32_array, as_float64_array, as_generic_list_array, as_int32_array,
@@ -32,6 +35,7 @@ use datafusion_common::cast::{
 use datafusion_common::utils::{coerced_type_with_base_type_only, ListCoercion};
 use datafusion_common::{
     exec_err, internal_datafusion_err, plan_err, utils::take_function_args, Result,
+    ScalarValue,
 };
 use datafusion_expr::{
     ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
@@ -40,6 +44,7 @@ use datafusion_functions::{downcast_arg, downcast_named_arg};
 use datafusion_macros::user_doc;
 use itertools::Itertools;
 use std::any::Any;
+use std::ops::{Add, Mul, Range, Sub};
 use std::sync::Arc;
 
 make_udf_expr_and_func!(
@@ -50,6 +55,30 @@ make_udf_expr_and_func!(
     array_distance_udf
 );
 
+make_udf_expr_and_func!(
+    CosineDistance,
+    cosine_distance,
+    array1 array2,
+    "returns the cosine distance between two numeric vectors.",
+    cosine_distance_udf
+);
+
+make_udf_expr_and_func!(
+    InnerProduct,
+    inner_product,
+    array1 array2,
+    "returns the inner product of two numeric vectors.",
+    inner_product_udf
+);
+
+make_udf_expr_and_func!(
+    L1Distance,
+    l1_distance,
+    array1 array2,
+    "returns the Manhattan distance between two numeric vectors.",
+    l1_distance_udf
+);
+
 #[user_doc(
     doc_section(label = "Array Functions"),
     description = "Returns the Euclidean distance between two input arrays of equal length.",
@@ -267,3 +296,510 @@ fn convert_to_f64_array(array: &ArrayRef) -> Result<Float64Array> {
         _ => exec_err!("Unsupported array type for conversion to Float64Array"),
     }
 }
+
+#[user_doc(
+    doc_section(label = "Array Functions"),
+    description = "Returns the cosine distance between two input vectors of equal length, i.e. `1 - cosine similarity`. Returns `NaN` if one of the vectors only contains zeros.",
+    syntax_example = "cosine_distance(array1, array2)",
+    sql_example = r#"```sql
+> select cosine_distance([1.0, 0.0], [1.0, 1.0]);
++--------------------------------------------------------------------------------------+
+| cosine_distance(make_array(Float64(1),Float64(0)),make_array(Float64(1),Float64(1))) |
++--------------------------------------------------------------------------------------+
+| 0.29289321881345254                                                                  |
++--------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "array1",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    ),
+    argument(
+        name = "array2",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    )
+)]
+#[derive(Debug)]
+pub struct CosineDistance {
+    signature: Signature,
+}
+
+impl Default for CosineDistance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CosineDistance {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for CosineDistance {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "cosine_distance"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_vector_types(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_vector_function(self.name(), VectorKernel::CosineDistance, &args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Array Functions"),
+    description = "Returns the inner product (dot product) of two input vectors of equal length.",
+    syntax_example = "inner_product(array1, array2)",
+    sql_example = r#"```sql
+> select inner_product([1, 2], [3, 4]);
++----------------------------------------------------------------------------+
+| inner_product(make_array(Int64(1),Int64(2)),make_array(Int64(3),Int64(4))) |
++----------------------------------------------------------------------------+
+| 11.0                                                                       |
++----------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "array1",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    ),
+    argument(
+        name = "array2",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    )
+)]
+#[derive(Debug)]
+pub struct InnerProduct {
+    signature: Signature,
+}
+
+impl Default for InnerProduct {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InnerProduct {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for InnerProduct {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "inner_product"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_vector_types(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_vector_function(self.name(), VectorKernel::InnerProduct, &args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Array Functions"),
+    description = "Returns the Manhattan (L1) distance between two input vectors of equal length.",
+    syntax_example = "l1_distance(array1, array2)",
+    sql_example = r#"```sql
+> select l1_distance([1, 2], [3, 5]);
++--------------------------------------------------------------------------+
+| l1_distance(make_array(Int64(1),Int64(2)),make_array(Int64(3),Int64(5))) |
++--------------------------------------------------------------------------+
+| 5.0                                                                      |
++--------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "array1",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    ),
+    argument(
+        name = "array2",
+        description = "Vector expression. Can be a constant, column, or function, and any combination of array operators."
+    )
+)]
+#[derive(Debug)]
+pub struct L1Distance {
+    signature: Signature,
+}
+
+impl Default for L1Distance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl L1Distance {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for L1Distance {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "l1_distance"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_vector_types(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_vector_function(self.name(), VectorKernel::L1Distance, &args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Coerces the arguments of a vector function.
+///
+/// `FixedSizeList<Float32>` vectors, the usual representation of embeddings,
+/// are kept as is and the other argument is cast to the same type, so that
+/// the distance is computed on `f32` values without any copy. The other
+/// vectors are coerced to lists of `Float64`.
+fn coerce_vector_types(name: &str, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+    let [arg_type1, arg_type2] = take_function_args(name, arg_types)?;
+    let mut dimension = None;
+    for arg_type in [arg_type1, arg_type2] {
+        let element_type = match arg_type {
+            Null => continue,
+            List(field) | LargeList(field) => field.data_type(),
+            FixedSizeList(field, size) => {
+                if field.data_type() == &DataType::Float32 {
+                    match dimension {
+                        Some(dimension) if dimension != *size => {
+                            return plan_err!(
+                                "{name} requires vectors of the same dimension, got {dimension} and {size}"
+                            );
+                        }
+                        _ => dimension = Some(*size),
+                    }
+                }
+                field.data_type()
+            }
+            _ => return plan_err!("{name} does not support type {arg_type}"),
+        };
+        if !element_type.is_numeric() && !element_type.is_null() {
+            return plan_err!("{name} does not support type {arg_type}");
+        }
+    }
+
+    let field = Arc::new(Field::new_list_field(
+        if dimension.is_some() {
+            DataType::Float32
+        } else {
+            DataType::Float64
+        },
+        true,
+    ));
+    let coerced_type = match dimension {
+        Some(dimension) => FixedSizeList(field, dimension),
+        None if matches!(arg_type1, LargeList(_))
+            || matches!(arg_type2, LargeList(_)) =>
+        {
+            LargeList(field)
+        }
+        None => List(field),
+    };
+    Ok(vec![coerced_type.clone(), coerced_type])
+}
+
+/// The computation of a vector function
+#[derive(Debug, Clone, Copy)]
+enum VectorKernel {
+    CosineDistance,
+    InnerProduct,
+    L1Distance,
+}
+
+/// Number of independent accumulators of the kernels, so that the compiler
+/// can vectorize the loops
+const LANES: usize = 8;
+
+impl VectorKernel {
+    /// Computes the kernel on two vectors of the same length
+    fn compute<T: VectorElement>(self, left: &[T], right: &[T]) -> f64 {
+        let left_chunks = left.chunks_exact(LANES);
+        let right_chunks = right.chunks_exact(LANES);
+        let remainder = left_chunks.remainder().iter().zip(right_chunks.remainder());
+        match self {
+            VectorKernel::CosineDistance => {
+                let mut dot = [T::default(); LANES];
+                let mut left_norm = [T::default(); LANES];
+                let mut right_norm = [T::default(); LANES];
+                for (l, r) in left_chunks.zip(right_chunks) {
+                    for lane in 0..LANES {
+                        dot[lane] = dot[lane] + l[lane] * r[lane];
+                        left_norm[lane] = left_norm[lane] + l[lane] * l[lane];
+                        right_norm[lane] = right_norm[lane] + r[lane] * r[lane];
+                    }
+                }
+                let (mut dot, mut left_norm, mut right_norm) =
+                    (T::sum(dot), T::sum(left_norm), T::sum(right_norm));
+                for (&l, &r) in remainder {
+                    dot = dot + l * r;
+                    left_norm = left_norm + l * l;
+                    right_norm = right_norm + r * r;
+                }
+                let (dot, left_norm, right_norm): (f64, f64, f64) =
+                    (dot.into(), left_norm.into(), right_norm.into());
+                1.0 - dot / (left_norm.sqrt() * right_norm.sqrt())
+            }
+            VectorKernel::InnerProduct => {
+                let mut dot = [T::default(); LANES];
+                for (l, r) in left_chunks.zip(right_chunks) {
+                    for lane in 0..LANES {
+                        dot[lane] = dot[lane] + l[lane] * r[lane];
+                    }
+                }
+                remainder
+                    .fold(T::sum(dot), |dot, (&l, &r)| dot + l * r)
+                    .into()
+            }
+            VectorKernel::L1Distance => {
+                let mut sum = [T::default(); LANES];
+                for (l, r) in left_chunks.zip(right_chunks) {
+                    for lane in 0..LANES {
+                        sum[lane] = sum[lane] + (l[lane] - r[lane]).abs();
+                    }
+                }
+                remainder
+                    .fold(T::sum(sum), |sum, (&l, &r)| sum + (l - r).abs())
+                    .into()
+            }
+        }
+    }
+}
+
+/// The floating point types the vector kernels are computed on
+trait VectorElement:
+    Copy + Default + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Into<f64>
+{
+    fn abs(self) -> Self;
+
+    fn sum(lanes: [Self; LANES]) -> Self {
+        lanes.into_iter().fold(Self::default(), |sum, v| sum + v)
+    }
+}
+
+impl VectorElement for f32 {
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+}
+
+impl VectorElement for f64 {
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+}
+
+fn invoke_vector_function(
+    name: &str,
+    kernel: VectorKernel,
+    args: &datafusion_expr::ScalarFunctionArgs,
+) -> Result<ColumnarValue> {
+    let [arg1, arg2] = take_function_args(name, &args.args)?;
+    // Scalar vectors, e.g. the query vector of a nearest neighbor search, are
+    // not expanded to the number of rows
+    let array1 = arg1.to_array(1)?;
+    let array2 = arg2.to_array(1)?;
+    let is_scalar = |arg: &ColumnarValue| matches!(arg, ColumnarValue::Scalar(_));
+    let number_rows = if is_scalar(arg1) && is_scalar(arg2) {
+        1
+    } else {
+        args.number_rows
+    };
+
+    let result = match (array1.data_type(), array2.data_type()) {
+        (FixedSizeList(..), FixedSizeList(..)) => compute_vector_function::<Float32Type>(
+            name,
+            kernel,
+            Vectors::try_new(&array1, is_scalar(arg1))?,
+            Vectors::try_new(&array2, is_scalar(arg2))?,
+            number_rows,
+        ),
+        (List(_), List(_)) | (LargeList(_), LargeList(_)) => {
+            compute_vector_function::<Float64Type>(
+                name,
+                kernel,
+                Vectors::try_new(&array1, is_scalar(arg1))?,
+                Vectors::try_new(&array2, is_scalar(arg2))?,
+                number_rows,
+            )
+        }
+        (Null, _) | (_, Null) => Ok(Float64Array::new_null(number_rows)),
+        (arg_type1, arg_type2) => {
+            exec_err!("{name} does not support types {arg_type1} and {arg_type2}")
+        }
+    }?;
+
+    if is_scalar(arg1) && is_scalar(arg2) {
+        ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
+
+fn compute_vector_function<T: ArrowPrimitiveType>(
+    name: &str,
+    kernel: VectorKernel,
+    vectors1: Vectors<'_, T>,
+    vectors2: Vectors<'_, T>,
+    number_rows: usize,
+) -> Result<Float64Array>
+where
+    T::Native: VectorElement,
+{
+    (0..number_rows)
+        .map(|row| match (vectors1.get(row), vectors2.get(row)) {
+            (Some(vector1), Some(vector2)) => {
+                if vector1.len() != vector2.len() {
+                    return exec_err!(
+                        "{name} requires vectors of the same dimension, got {} and {}",
+                        vector1.len(),
+                        vector2.len()
+                    );
+                }
+                Ok(Some(kernel.compute(vector1, vector2)))
+            }
+            _ => Ok(None),
+        })
+        .collect()
+}
+
+/// The vectors of a list argument, as slices of its values
+struct Vectors<'a, T: ArrowPrimitiveType> {
+    nulls: Option<&'a NullBuffer>,
+    values: &'a PrimitiveArray<T>,
+    offsets: VectorOffsets<'a>,
+    /// The argument is a scalar, whose only vector is used for every row
+    is_scalar: bool,
+}
+
+enum VectorOffsets<'a> {
+    Fixed(usize),
+    Small(&'a [i32]),
+    Large(&'a [i64]),
+}
+
+impl<'a, T: ArrowPrimitiveType> Vectors<'a, T> {
+    fn try_new(array: &'a ArrayRef, is_scalar: bool) -> Result<Self> {
+        let (values, offsets) = match array.data_type() {
+            FixedSizeList(_, size) => (
+                array.as_fixed_size_list().values(),
+                VectorOffsets::Fixed(*size as usize),
+            ),
+            List(_) => {
+                let list = array.as_list::<i32>();
+                (list.values(), VectorOffsets::Small(list.value_offsets()))
+            }
+            LargeList(_) => {
+                let list = array.as_list::<i64>();
+                (list.values(), VectorOffsets::Large(list.value_offsets()))
+            }
+            other => return exec_err!("Unsupported vector type {other}"),
+        };
+        let Some(values) = values.as_primitive_opt::<T>() else {
+            return exec_err!("Unsupported vector element type {}", values.data_type());
+        };
+        Ok(Self {
+            nulls: array.nulls(),
+            values,
+            offsets,
+            is_scalar,
+        })
+    }
+
+    /// Returns the vector of `row`, or `None` if it is null or contains nulls
+    fn get(&self, row: usize) -> Option<&'a [T::Native]> {
+        let row = if self.is_scalar { 0 } else { row };
+        if self.nulls.is_some_and(|nulls| nulls.is_null(row)) {
+            return None;
+        }
+        let range = self.range(row);
+        if self.values.null_count() > 0
+            && range.clone().any(|idx| self.values.is_null(idx))
+        {
+            return None;
+        }
+        Some(&self.values.values()[range])
+    }
+
+    fn range(&self, row: usize) -> Range<usize> {
+        match self.offsets {
+            VectorOffsets::Fixed(size) => row * size..(row + 1) * size,
+            VectorOffsets::Small(offsets) => {
+                offsets[row] as usize..offsets[row + 1] as usize
+            }
+            VectorOffsets::Large(offsets) => {
+                offsets[row] as usize..offsets[row + 1] as usize
+            }
+        }
+    }
+}
@@ -86,6 +86,9 @@ pub mod expr_fn {
     pub use super::dimension::array_dims;
     pub use super::dimension::array_ndims;
     pub use super::distance::array_distance;
+    pub use super::distance::cosine_distance;
+    pub use super::distance::inner_product;
+    pub use super::distance::l1_distance;
     pub use super::empty::array_empty;
     pub use super::except::array_except;
     pub use super::extract::array_any_value;
@@ -149,6 +152,9 @@ pub fn all_default_nested_functions() -> Vec<Arc<ScalarUDF>> {
         empty::array_empty_udf(),
         length::array_length_udf(),
         distance::array_distance_udf(),
+        distance::cosine_distance_udf(),
+        distance::inner_product_udf(),
+        distance::l1_distance_udf(),
         flatten::flatten_udf(),
         min_max::array_max_udf(),
         min_max::array_min_udf(),
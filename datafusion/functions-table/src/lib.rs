@@ -25,13 +25,14 @@
 #![cfg_attr(not(test), deny(clippy::clone_on_ref_ptr))]
 
 pub mod generate_series;
+pub mod regexp_split_to_table;
 
 use datafusion_catalog::TableFunction;
 use std::sync::Arc;
 
 /// Returns all default table functions
 pub fn all_default_table_functions() -> Vec<Arc<TableFunction>> {
-    vec![generate_series(), range()]
+    vec![generate_series(), range(), regexp_split_to_table()]
 }
 
 /// Creates a singleton instance of a table function
@@ -59,3 +60,7 @@ macro_rules! create_udtf_function {
 
 create_udtf_function!(generate_series::GenerateSeriesFunc, "generate_series");
 create_udtf_function!(generate_series::RangeFunc, "range");
+create_udtf_function!(
+    regexp_split_to_table::RegexpSplitToTableFunc,
+    "regexp_split_to_table"
+);
@@ -0,0 +1,177 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use datafusion_catalog::Session;
+use datafusion_catalog::TableFunctionImpl;
+use datafusion_catalog::TableProvider;
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::{Expr, TableType};
+use datafusion_functions::regex::compile_regex;
+use datafusion_functions::regex::regexpsplittoarray::regexp_split;
+use datafusion_physical_plan::memory::{LazyBatchGenerator, LazyMemoryExec};
+use datafusion_physical_plan::ExecutionPlan;
+use parking_lot::RwLock;
+use regex::Regex;
+use std::fmt;
+use std::sync::Arc;
+
+/// Table that splits a string using a regular expression as the delimiter,
+/// with one row per part
+#[derive(Debug, Clone)]
+struct RegexpSplitToTable {
+    schema: SchemaRef,
+    /// The string and the regular expression to split it with, `None` if
+    /// one of the arguments was null
+    args: Option<(String, Regex)>,
+}
+
+/// Generates the parts of the string in batches of at most `batch_size` rows
+#[derive(Debug, Clone)]
+struct RegexpSplitState {
+    schema: SchemaRef,
+    parts: Vec<String>,
+    offset: usize,
+    batch_size: usize,
+}
+
+impl LazyBatchGenerator for RegexpSplitState {
+    fn generate_next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        if self.offset >= self.parts.len() {
+            return Ok(None);
+        }
+
+        let end = self.parts.len().min(self.offset + self.batch_size);
+        let array = StringArray::from_iter_values(&self.parts[self.offset..end]);
+        self.offset = end;
+        let batch =
+            RecordBatch::try_new(Arc::clone(&self.schema), vec![Arc::new(array)])?;
+        Ok(Some(batch))
+    }
+}
+
+impl fmt::Display for RegexpSplitState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "regexp_split_to_table: parts={}, batch_size={}",
+            self.parts.len(),
+            self.batch_size
+        )
+    }
+}
+
+#[async_trait]
+impl TableProvider for RegexpSplitToTable {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let batch_size = state.config_options().execution.batch_size;
+        let schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema(),
+        };
+        let parts = match &self.args {
+            Some((value, regex)) => regexp_split(value, regex)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            None => vec![],
+        };
+        let generator = RegexpSplitState {
+            schema: self.schema(),
+            parts,
+            offset: 0,
+            batch_size,
+        };
+
+        Ok(Arc::new(LazyMemoryExec::try_new(
+            schema,
+            vec![Arc::new(RwLock::new(generator))],
+        )?))
+    }
+}
+
+/// `regexp_split_to_table(str, regexp[, flags])` splits `str` using the
+/// regular expression `regexp` as the delimiter and returns one row per part
+#[derive(Debug)]
+pub struct RegexpSplitToTableFunc {}
+
+impl TableFunctionImpl for RegexpSplitToTableFunc {
+    fn call(&self, exprs: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if !(2..=3).contains(&exprs.len()) {
+            return plan_err!("regexp_split_to_table function requires 2 or 3 arguments");
+        }
+
+        let mut args = Vec::with_capacity(exprs.len());
+        for (expr_index, expr) in exprs.iter().enumerate() {
+            match expr {
+                Expr::Literal(
+                    ScalarValue::Utf8(value)
+                    | ScalarValue::LargeUtf8(value)
+                    | ScalarValue::Utf8View(value),
+                    _,
+                ) => args.push(value.as_deref()),
+                Expr::Literal(ScalarValue::Null, _) => args.push(None),
+                other => {
+                    return plan_err!(
+                        "Argument #{} must be a string literal or NULL, got {:?}",
+                        expr_index + 1,
+                        other
+                    )
+                }
+            }
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Utf8,
+            false,
+        )]));
+
+        let (value, regex, flags) = match args[..] {
+            [Some(value), Some(regex)] => (value, regex, None),
+            // A null flags argument is the same as no flags
+            [Some(value), Some(regex), flags] => (value, regex, flags),
+            _ => {
+                return Ok(Arc::new(RegexpSplitToTable { schema, args: None }));
+            }
+        };
+        let args = Some((value.to_string(), compile_regex(regex, flags)?));
+
+        Ok(Arc::new(RegexpSplitToTable { schema, args }))
+    }
+}
@@ -0,0 +1,349 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `conditional_change_event` and `conditional_true_event` window function
+//! implementations
+
+use crate::define_udwf_and_expr;
+use arrow::datatypes::FieldRef;
+use datafusion_common::arrow::array::{Array, ArrayRef, UInt64Array};
+use datafusion_common::arrow::datatypes::{DataType, Field};
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::window_doc_sections::DOC_SECTION_ANALYTICAL;
+use datafusion_expr::{
+    Documentation, PartitionEvaluator, Signature, Volatility, WindowUDFImpl,
+};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+use std::any::Any;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::{Arc, LazyLock};
+
+define_udwf_and_expr!(
+    ConditionalChangeEvent,
+    conditional_change_event,
+    [expr],
+    "Returns the number of times the value of the argument changed \
+    compared to the previous row, starting from 0.",
+    ConditionalEvent::change
+);
+
+define_udwf_and_expr!(
+    ConditionalTrueEvent,
+    conditional_true_event,
+    [expr],
+    "Returns the number of rows up to the current row for which the \
+    boolean argument evaluated to true, starting from 0.",
+    ConditionalEvent::true_event
+);
+
+#[derive(Debug, Copy, Clone)]
+pub enum ConditionalEventKind {
+    /// A new event starts when the argument differs from the previous row
+    Change,
+    /// A new event starts when the argument is `true`
+    True,
+}
+
+/// Assigns an event number to each row of the partition, incrementing it
+/// every time the condition of [`ConditionalEventKind`] is met
+#[derive(Debug)]
+pub struct ConditionalEvent {
+    signature: Signature,
+    kind: ConditionalEventKind,
+}
+
+impl ConditionalEvent {
+    /// Create a new conditional event window function of the given kind
+    pub fn new(kind: ConditionalEventKind) -> Self {
+        let signature = match kind {
+            ConditionalEventKind::Change => Signature::any(1, Volatility::Immutable),
+            ConditionalEventKind::True => {
+                Signature::exact(vec![DataType::Boolean], Volatility::Immutable)
+            }
+        };
+        Self { signature, kind }
+    }
+
+    /// Create a `conditional_change_event` window function
+    pub fn change() -> Self {
+        Self::new(ConditionalEventKind::Change)
+    }
+
+    /// Create a `conditional_true_event` window function
+    pub fn true_event() -> Self {
+        Self::new(ConditionalEventKind::True)
+    }
+}
+
+static CONDITIONAL_CHANGE_EVENT_DOCUMENTATION: LazyLock<Documentation> =
+    LazyLock::new(|| {
+        Documentation::builder(
+            DOC_SECTION_ANALYTICAL,
+            "Assigns an event number to each row, starting from 0 and \
+            incremented by 1 each time the value of expression differs from \
+            its value in the previous row. Two NULL values are considered equal.",
+            "conditional_change_event(expression)",
+        )
+        .with_argument("expression", "Expression to operate on")
+        .with_sql_example(
+            r#"```sql
+    --Example usage of the conditional_change_event window function:
+    SELECT ts,
+           status,
+           conditional_change_event(status) OVER (ORDER BY ts) AS session
+    FROM events;
+```
+
+```sql
++----+--------+---------+
+| ts | status | session |
++----+--------+---------+
+| 1  | up     | 0       |
+| 2  | up     | 0       |
+| 3  | down   | 1       |
+| 4  | up     | 2       |
++----+--------+---------+
+```"#,
+        )
+        .build()
+    });
+
+static CONDITIONAL_TRUE_EVENT_DOCUMENTATION: LazyLock<Documentation> =
+    LazyLock::new(|| {
+        Documentation::builder(
+            DOC_SECTION_ANALYTICAL,
+            "Assigns an event number to each row, starting from 0 and \
+            incremented by 1 for each row where the boolean expression \
+            evaluates to true.",
+            "conditional_true_event(expression)",
+        )
+        .with_argument("expression", "Boolean expression to operate on")
+        .with_sql_example(
+            r#"```sql
+    --Example usage of the conditional_true_event window function:
+    SELECT ts,
+           gap,
+           conditional_true_event(gap > 30) OVER (ORDER BY ts) AS session
+    FROM clicks;
+```
+
+```sql
++----+-----+---------+
+| ts | gap | session |
++----+-----+---------+
+| 1  | 0   | 0       |
+| 2  | 10  | 0       |
+| 3  | 45  | 1       |
+| 4  | 5   | 1       |
++----+-----+---------+
+```"#,
+        )
+        .build()
+    });
+
+impl WindowUDFImpl for ConditionalEvent {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.kind {
+            ConditionalEventKind::Change => "conditional_change_event",
+            ConditionalEventKind::True => "conditional_true_event",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        _partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        Ok(Box::new(ConditionalEventEvaluator {
+            kind: self.kind,
+            n_events: 0,
+        }))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::UInt64, false).into())
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        match self.kind {
+            ConditionalEventKind::Change => Some(&CONDITIONAL_CHANGE_EVENT_DOCUMENTATION),
+            ConditionalEventKind::True => Some(&CONDITIONAL_TRUE_EVENT_DOCUMENTATION),
+        }
+    }
+}
+
+/// State for the conditional event window functions: the number of events
+/// seen so far in the partition.
+#[derive(Debug)]
+struct ConditionalEventEvaluator {
+    kind: ConditionalEventKind,
+    n_events: u64,
+}
+
+impl ConditionalEventEvaluator {
+    /// Returns `true` if a new event starts at row `idx`
+    fn is_event(&self, array: &ArrayRef, idx: usize) -> Result<bool> {
+        match self.kind {
+            ConditionalEventKind::Change => {
+                if idx == 0 {
+                    return Ok(false);
+                }
+                let previous = ScalarValue::try_from_array(array, idx - 1)?;
+                let current = ScalarValue::try_from_array(array, idx)?;
+                Ok(previous != current)
+            }
+            ConditionalEventKind::True => {
+                let array = as_boolean_array(array)?;
+                Ok(array.is_valid(idx) && array.value(idx))
+            }
+        }
+    }
+}
+
+impl PartitionEvaluator for ConditionalEventEvaluator {
+    /// `conditional_change_event` compares the current row with the
+    /// previous one, `conditional_true_event` only needs the current row.
+    fn get_range(&self, idx: usize, _n_rows: usize) -> Result<Range<usize>> {
+        let start = match self.kind {
+            ConditionalEventKind::Change => idx.saturating_sub(1),
+            ConditionalEventKind::True => idx,
+        };
+        Ok(Range {
+            start,
+            end: idx + 1,
+        })
+    }
+
+    fn is_causal(&self) -> bool {
+        // Events only depend on the current and previous rows:
+        true
+    }
+
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        // The current row is the last row of the range. For the first row of
+        // the partition there is no previous row to compare with.
+        let idx = range.end - 1;
+        let is_event = match self.kind {
+            ConditionalEventKind::Change => {
+                range.end - range.start == 2 && self.is_event(&values[0], idx)?
+            }
+            ConditionalEventKind::True => self.is_event(&values[0], idx)?,
+        };
+        if is_event {
+            self.n_events += 1;
+        }
+        Ok(ScalarValue::UInt64(Some(self.n_events)))
+    }
+
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> Result<ArrayRef> {
+        let array = &values[0];
+        let events = (0..num_rows)
+            .map(|idx| {
+                if self.is_event(array, idx)? {
+                    self.n_events += 1;
+                }
+                Ok(self.n_events)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(UInt64Array::from(events)))
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_common::arrow::array::{BooleanArray, Int32Array};
+    use datafusion_common::cast::as_uint64_array;
+
+    fn evaluate_all(
+        udwf: ConditionalEvent,
+        values: ArrayRef,
+    ) -> Result<(UInt64Array, UInt64Array)> {
+        let num_rows = values.len();
+        let values = [values];
+
+        let all = udwf
+            .partition_evaluator(PartitionEvaluatorArgs::default())?
+            .evaluate_all(&values, num_rows)?;
+
+        // Row by row evaluation as done by `BoundedWindowAggExec`
+        let mut evaluator = udwf.partition_evaluator(PartitionEvaluatorArgs::default())?;
+        let row_wise = (0..num_rows)
+            .map(|idx| evaluator.evaluate(&values, &evaluator.get_range(idx, num_rows)?))
+            .collect::<Result<Vec<_>>>()?;
+        let row_wise = ScalarValue::iter_to_array(row_wise)?;
+
+        Ok((
+            as_uint64_array(&all)?.clone(),
+            as_uint64_array(&row_wise)?.clone(),
+        ))
+    }
+
+    #[test]
+    fn conditional_change_event() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(1),
+            Some(2),
+            None,
+            None,
+            Some(2),
+            Some(2),
+            Some(1),
+        ]));
+        let (all, row_wise) = evaluate_all(ConditionalEvent::change(), values)?;
+
+        assert_eq!(vec![0, 0, 1, 2, 2, 3, 3, 4], *all.values());
+        assert_eq!(all, row_wise);
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_true_event() -> Result<()> {
+        let values: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(false),
+            Some(true),
+            Some(true),
+            None,
+            Some(false),
+            Some(true),
+        ]));
+        let (all, row_wise) = evaluate_all(ConditionalEvent::true_event(), values)?;
+
+        assert_eq!(vec![0, 1, 2, 2, 2, 3], *all.values());
+        assert_eq!(all, row_wise);
+        Ok(())
+    }
+}
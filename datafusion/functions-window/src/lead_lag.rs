@@ -25,12 +25,13 @@ use datafusion_common::arrow::datatypes::Field;
 use datafusion_common::{arrow_datafusion_err, DataFusionError, Result, ScalarValue};
 use datafusion_expr::window_doc_sections::DOC_SECTION_ANALYTICAL;
 use datafusion_expr::{
-    Documentation, Literal, PartitionEvaluator, ReversedUDWF, Signature, TypeSignature,
-    Volatility, WindowUDFImpl,
+    ColumnarValue, Documentation, Literal, PartitionEvaluator, ReversedUDWF, Signature,
+    TypeSignature, Volatility, WindowUDFImpl,
 };
 use datafusion_functions_window_common::expr::ExpressionArgs;
 use datafusion_functions_window_common::field::WindowUDFFieldArgs;
 use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+use datafusion_physical_expr::expressions::CastExpr;
 use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
 use std::any::Any;
 use std::cmp::min;
@@ -157,7 +158,8 @@ static LAG_DOCUMENTATION: LazyLock<Documentation> = LazyLock::new(|| {
         .with_argument("offset", "Integer. Specifies how many rows back \
         the value of expression should be retrieved. Defaults to 1.")
         .with_argument("default", "The default value if the offset is \
-        not within the partition. Must be of the same type as expression.")
+        not within the partition. Can be a constant or an expression evaluated \
+        for the current row, and must be coercible to the type of expression.")
         .with_sql_example(r#"```sql
     --Example usage of the lag window function:
     SELECT employee_id,
@@ -193,7 +195,8 @@ static LEAD_DOCUMENTATION: LazyLock<Documentation> = LazyLock::new(|| {
         .with_argument("offset", "Integer. Specifies how many rows \
         forward the value of expression should be retrieved. Defaults to 1.")
         .with_argument("default", "The default value if the offset is \
-        not within the partition. Must be of the same type as expression.")
+        not within the partition. Can be a constant or an expression evaluated \
+        for the current row, and must be coercible to the type of expression.")
         .with_sql_example(r#"```sql
 -- Example usage of lead() :
 SELECT
@@ -240,9 +243,17 @@ impl WindowUDFImpl for WindowShift {
     /// on the default value argument.
     ///
     /// For more details see: <https://github.com/apache/datafusion/issues/12717>
+    ///
+    /// When the default value is not a literal, it is evaluated for each row
+    /// and passed to the evaluator as a second column.
     fn expressions(&self, expr_args: ExpressionArgs) -> Vec<Arc<dyn PhysicalExpr>> {
         parse_expr(expr_args.input_exprs(), expr_args.input_fields())
             .into_iter()
+            .chain(
+                parse_default_expr(expr_args.input_exprs(), expr_args.input_fields())
+                    .ok()
+                    .flatten(),
+            )
             .collect::<Vec<_>>()
     }
 
@@ -272,6 +283,7 @@ impl WindowUDFImpl for WindowShift {
             default_value,
             ignore_nulls: partition_evaluator_args.ignore_nulls(),
             non_null_offsets: VecDeque::new(),
+            trailing_nulls: 0,
         }))
     }
 
@@ -327,15 +339,46 @@ fn parse_expr(
         return Ok(expr);
     }
 
-    let default_value = get_scalar_value_from_args(input_exprs, 2)?;
-    default_value.map_or(Ok(expr), |value| {
-        ScalarValue::try_from(&value.data_type()).map(|v| {
+    // The default value may be an arbitrary expression, so the type is
+    // taken from its field rather than from a literal value.
+    input_fields.get(2).map_or(Ok(expr), |default_field| {
+        ScalarValue::try_from(default_field.data_type()).map(|v| {
             Arc::new(datafusion_physical_expr::expressions::Literal::new(v))
                 as Arc<dyn PhysicalExpr>
         })
     })
 }
 
+/// Returns `true` if the default value argument is missing or a literal.
+fn has_literal_default(input_exprs: &[Arc<dyn PhysicalExpr>]) -> bool {
+    input_exprs.get(2).is_none_or(|expr| {
+        expr.as_any()
+            .is::<datafusion_physical_expr::expressions::Literal>()
+    })
+}
+
+/// Returns the default value argument when it is not a literal, i.e. it
+/// has to be evaluated for every row of the partition.
+///
+/// For e.g. in `lag(a, 1, b)` the value of `b` in the current row is
+/// returned when the row that precedes it does not exist. The expression
+/// is cast to the return type of the window function if necessary.
+fn parse_default_expr(
+    input_exprs: &[Arc<dyn PhysicalExpr>],
+    input_fields: &[FieldRef],
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    if has_literal_default(input_exprs) {
+        return Ok(None);
+    }
+
+    let expr = Arc::clone(&input_exprs[2]);
+    let return_type = parse_expr_field(input_fields)?.data_type().clone();
+    match input_fields.get(2) {
+        Some(field) if field.data_type() == &return_type => Ok(Some(expr)),
+        _ => Ok(Some(Arc::new(CastExpr::new(expr, return_type, None)))),
+    }
+}
+
 static NULL_FIELD: LazyLock<FieldRef> =
     LazyLock::new(|| Field::new("value", DataType::Null, true).into());
 
@@ -367,6 +410,10 @@ fn parse_default_value(
     input_types: &[FieldRef],
 ) -> Result<ScalarValue> {
     let expr_field = parse_expr_field(input_types)?;
+    if !has_literal_default(input_exprs) {
+        // Evaluated per row, see `parse_default_expr`
+        return ScalarValue::try_from(expr_field.data_type());
+    }
     let unparsed = get_scalar_value_from_args(input_exprs, 2)?;
 
     unparsed
@@ -382,6 +429,9 @@ struct WindowShiftEvaluator {
     ignore_nulls: bool,
     // VecDeque contains offset values that between non-null entries
     non_null_offsets: VecDeque<usize>,
+    // Number of null entries seen after the last non-null entry tracked by
+    // `non_null_offsets`, used by LEAD when nulls are ignored
+    trailing_nulls: usize,
 }
 
 impl WindowShiftEvaluator {
@@ -389,25 +439,48 @@ impl WindowShiftEvaluator {
         // Mode is LAG, when shift_offset is positive
         self.shift_offset > 0
     }
+
+    /// Returns the default value for the row at `idx`.
+    ///
+    /// A default value that is not a literal is evaluated for every row
+    /// and passed as the second column of `values` (see
+    /// [`WindowUDFImpl::expressions`] for [`WindowShift`]).
+    fn default_value_at(&self, values: &[ArrayRef], idx: usize) -> Result<ScalarValue> {
+        match values.get(1) {
+            Some(defaults) => ScalarValue::try_from_array(defaults, idx),
+            None => Ok(self.default_value.clone()),
+        }
+    }
+
+    /// Returns the default values for all the rows in `values`.
+    fn default_values(&self, values: &[ArrayRef]) -> ColumnarValue {
+        match values.get(1) {
+            Some(defaults) => ColumnarValue::Array(Arc::clone(defaults)),
+            None => ColumnarValue::Scalar(self.default_value.clone()),
+        }
+    }
 }
 
 // implement ignore null for evaluate_all
 fn evaluate_all_with_ignore_null(
     array: &ArrayRef,
     offset: i64,
-    default_value: &ScalarValue,
+    default_value: &ColumnarValue,
     is_lag: bool,
 ) -> Result<ArrayRef, DataFusionError> {
-    let valid_indices: Vec<usize> =
-        array.nulls().unwrap().valid_indices().collect::<Vec<_>>();
+    let valid_indices: Vec<usize> = match array.nulls() {
+        Some(nulls) => nulls.valid_indices().collect(),
+        None => (0..array.len()).collect(),
+    };
     let direction = !is_lag;
+    let offset = offset.unsigned_abs() as usize;
     let new_array_results: Result<Vec<_>, DataFusionError> = (0..array.len())
         .map(|id| {
             let result_index = match valid_indices.binary_search(&id) {
                 Ok(pos) => if direction {
-                    pos.checked_add(offset as usize)
+                    pos.checked_add(offset)
                 } else {
-                    pos.checked_sub(offset.unsigned_abs() as usize)
+                    pos.checked_sub(offset)
                 }
                 .and_then(|new_pos| {
                     if new_pos < valid_indices.len() {
@@ -416,10 +489,14 @@ fn evaluate_all_with_ignore_null(
                         None
                     }
                 }),
+                // `pos` is the index of the first non-null entry after a null
+                // entry, which is the first row following it
                 Err(pos) => if direction {
-                    pos.checked_add(offset as usize)
+                    offset
+                        .checked_sub(1)
+                        .and_then(|offset| pos.checked_add(offset))
                 } else if pos > 0 {
-                    pos.checked_sub(offset.unsigned_abs() as usize)
+                    pos.checked_sub(offset)
                 } else {
                     None
                 }
@@ -432,9 +509,12 @@ fn evaluate_all_with_ignore_null(
                 }),
             };
 
-            match result_index {
-                Some(index) => ScalarValue::try_from_array(array, index),
-                None => Ok(default_value.clone()),
+            match (result_index, default_value) {
+                (Some(index), _) => ScalarValue::try_from_array(array, index),
+                (None, ColumnarValue::Scalar(value)) => Ok(value.clone()),
+                (None, ColumnarValue::Array(values)) => {
+                    ScalarValue::try_from_array(values, id)
+                }
             }
         })
         .collect();
@@ -446,7 +526,7 @@ fn evaluate_all_with_ignore_null(
 fn shift_with_default_value(
     array: &ArrayRef,
     offset: i64,
-    default_value: &ScalarValue,
+    default_value: &ColumnarValue,
 ) -> Result<ArrayRef> {
     use datafusion_common::arrow::compute::concat;
 
@@ -454,15 +534,20 @@ fn shift_with_default_value(
     if offset == 0 {
         Ok(Arc::clone(array))
     } else if offset == i64::MIN || offset.abs() >= value_len {
-        default_value.to_array_of_size(value_len as usize)
+        default_value.to_array(value_len as usize)
     } else {
         let slice_offset = (-offset).clamp(0, value_len) as usize;
         let length = array.len() - offset.unsigned_abs() as usize;
         let slice = array.slice(slice_offset, length);
 
-        // Generate array with remaining `null` items
+        // Generate array with remaining `null` items, taking the default
+        // values of the rows whose shifted row is outside the partition
         let nulls = offset.unsigned_abs() as usize;
-        let default_values = default_value.to_array_of_size(nulls)?;
+        let default_values = match default_value {
+            ColumnarValue::Scalar(value) => value.to_array_of_size(nulls)?,
+            ColumnarValue::Array(values) if offset > 0 => values.slice(0, nulls),
+            ColumnarValue::Array(values) => values.slice(length, nulls),
+        };
 
         // Concatenate both arrays, add nulls after if shift > 0 else before
         if offset > 0 {
@@ -491,9 +576,13 @@ impl PartitionEvaluator for WindowShiftEvaluator {
             let end = idx + 1;
             Ok(Range { start, end })
         } else {
-            let end = if self.non_null_offsets.len() == (-self.shift_offset) as usize {
+            let non_null_row_count = (-self.shift_offset) as usize;
+            let end = if self.ignore_nulls
+                && self.non_null_offsets.len() >= non_null_row_count
+            {
                 // How many rows needed further than the current row to get necessary lead result
-                let offset: usize = self.non_null_offsets.iter().sum();
+                let offset: usize =
+                    self.non_null_offsets.iter().take(non_null_row_count).sum();
                 min(idx + offset + 1, n_rows)
             } else if !self.ignore_nulls {
                 let offset = (-self.shift_offset) as usize;
@@ -558,31 +647,17 @@ impl PartitionEvaluator for WindowShiftEvaluator {
             // Stores the necessary non-null entry number further than the current row.
             let non_null_row_count = (-self.shift_offset) as usize;
 
-            if self.non_null_offsets.is_empty() {
-                // When empty, fill non_null offsets with the data further than the current row.
-                let mut offset_val = 1;
-                for idx in range.start + 1..range.end {
-                    if array.is_valid(idx) {
-                        self.non_null_offsets.push_back(offset_val);
-                        offset_val = 1;
-                    } else {
-                        offset_val += 1;
-                    }
-                    // It is enough to keep track of `non_null_row_count + 1` non-null offset.
-                    // further data is unnecessary for the result.
-                    if self.non_null_offsets.len() == non_null_row_count + 1 {
-                        break;
-                    }
-                }
-            } else if range.end < len && array.is_valid(range.end) {
-                // Update `non_null_offsets` with the new end data.
-                if array.is_valid(range.end) {
-                    // When non-null, append a new offset.
-                    self.non_null_offsets.push_back(1);
+            // Scan the rows of the window that have not been seen yet. Offsets
+            // are relative to the current row, followed by the distances
+            // between consecutive non-null entries.
+            let seen: usize =
+                self.non_null_offsets.iter().sum::<usize>() + self.trailing_nulls;
+            for idx in range.start + 1 + seen..range.end {
+                if array.is_valid(idx) {
+                    self.non_null_offsets.push_back(self.trailing_nulls + 1);
+                    self.trailing_nulls = 0;
                 } else {
-                    // When null, increment offset count of the last entry
-                    let last_idx = self.non_null_offsets.len() - 1;
-                    self.non_null_offsets[last_idx] += 1;
+                    self.trailing_nulls += 1;
                 }
             }
 
@@ -596,12 +671,14 @@ impl PartitionEvaluator for WindowShiftEvaluator {
             };
             // Prune `self.non_null_offsets` from the start. so that at next iteration
             // start of the `self.non_null_offsets` matches with current row.
-            if !self.non_null_offsets.is_empty() {
-                self.non_null_offsets[0] -= 1;
-                if self.non_null_offsets[0] == 0 {
+            if let Some(first) = self.non_null_offsets.front_mut() {
+                *first -= 1;
+                if *first == 0 {
                     // When offset is 0. Remove it.
                     self.non_null_offsets.pop_front();
                 }
+            } else {
+                self.trailing_nulls = self.trailing_nulls.saturating_sub(1);
             }
         }
 
@@ -614,7 +691,12 @@ impl PartitionEvaluator for WindowShiftEvaluator {
         if !(idx.is_none() || (self.ignore_nulls && array.is_null(idx.unwrap()))) {
             ScalarValue::try_from_array(array, idx.unwrap())
         } else {
-            Ok(self.default_value.clone())
+            let current_row = if self.is_lag() {
+                range.end - 1
+            } else {
+                range.start
+            };
+            self.default_value_at(values, current_row)
         }
     }
 
@@ -623,15 +705,17 @@ impl PartitionEvaluator for WindowShiftEvaluator {
         values: &[ArrayRef],
         _num_rows: usize,
     ) -> Result<ArrayRef> {
-        // LEAD, LAG window functions take single column, values will have size 1
+        // LEAD, LAG window functions take a single column, followed by the
+        // default values when they are not a literal
         let value = &values[0];
+        let default_value = self.default_values(values);
         if !self.ignore_nulls {
-            shift_with_default_value(value, self.shift_offset, &self.default_value)
+            shift_with_default_value(value, self.shift_offset, &default_value)
         } else {
             evaluate_all_with_ignore_null(
                 value,
                 self.shift_offset,
-                &self.default_value,
+                &default_value,
                 self.is_lag(),
             )
         }
@@ -674,6 +758,7 @@ mod tests {
             default_value: ScalarValue::Null,
             ignore_nulls: false,
             non_null_offsets: Default::default(),
+            trailing_nulls: 0,
         };
         assert_eq!(lag_fn.get_range(6, 10)?, Range { start: 4, end: 7 });
         assert_eq!(lag_fn.get_range(0, 10)?, Range { start: 0, end: 1 });
@@ -685,6 +770,7 @@ mod tests {
             ignore_nulls: true,
             // models data received [<Some>, <Some>, <Some>, NULL, <Some>, NULL, <current row>, ...]
             non_null_offsets: vec![2, 2].into(), // [1, 1, 2, 2] actually, just last 2 is used
+            trailing_nulls: 0,
         };
         assert_eq!(lag_fn.get_range(6, 10)?, Range { start: 2, end: 7 });
 
@@ -694,6 +780,7 @@ mod tests {
             default_value: ScalarValue::Null,
             ignore_nulls: false,
             non_null_offsets: Default::default(),
+            trailing_nulls: 0,
         };
         assert_eq!(lead_fn.get_range(6, 10)?, Range { start: 6, end: 8 });
         assert_eq!(lead_fn.get_range(9, 10)?, Range { start: 9, end: 10 });
@@ -705,6 +792,7 @@ mod tests {
             ignore_nulls: true,
             // models data received [..., <current row>, NULL, <Some>, NULL, <Some>, ..]
             non_null_offsets: vec![2, 2].into(),
+            trailing_nulls: 0,
         };
         assert_eq!(lead_fn.get_range(4, 10)?, Range { start: 4, end: 9 });
 
@@ -797,4 +885,155 @@ mod tests {
             .collect::<Int32Array>(),
         )
     }
+
+    /// Evaluates `expr` row by row, as done by `BoundedWindowAggExec`, and
+    /// checks that the results match `evaluate_all`.
+    fn test_row_wise_matches_evaluate_all(
+        expr: WindowShift,
+        input_exprs: &[Arc<dyn PhysicalExpr>],
+        values: &[ArrayRef],
+        ignore_nulls: bool,
+        expected: Int32Array,
+    ) -> Result<()> {
+        let input_fields = input_exprs
+            .iter()
+            .map(|_| Arc::new(Field::new("f", DataType::Int32, true)))
+            .collect::<Vec<_>>();
+        let args = || {
+            PartitionEvaluatorArgs::new(input_exprs, &input_fields, false, ignore_nulls)
+        };
+        let num_rows = values[0].len();
+
+        let result = expr
+            .partition_evaluator(args())?
+            .evaluate_all(values, num_rows)?;
+        assert_eq!(expected, *as_int32_array(&result)?);
+
+        let mut evaluator = expr.partition_evaluator(args())?;
+        let result = (0..num_rows)
+            .map(|idx| evaluator.evaluate(values, &evaluator.get_range(idx, num_rows)?))
+            .collect::<Result<Vec<_>>>()?;
+        let result = ScalarValue::iter_to_array(result)?;
+        assert_eq!(expected, *as_int32_array(&result)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lead_lag_ignore_nulls() -> Result<()> {
+        let expr = Arc::new(Column::new("c3", 0)) as Arc<dyn PhysicalExpr>;
+        let offset =
+            Arc::new(Literal::new(ScalarValue::Int32(Some(2)))) as Arc<dyn PhysicalExpr>;
+        let input_exprs = [expr, offset];
+        let values: Vec<ArrayRef> = vec![Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            None,
+            None,
+            Some(6),
+            None,
+            Some(8),
+            None,
+        ]))];
+
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lag(),
+            &input_exprs,
+            &values,
+            true,
+            Int32Array::from(vec![
+                None,
+                None,
+                None,
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(3),
+                Some(3),
+                Some(6),
+            ]),
+        )?;
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lead(),
+            &input_exprs,
+            &values,
+            true,
+            Int32Array::from(vec![
+                Some(6),
+                Some(6),
+                Some(8),
+                Some(8),
+                Some(8),
+                None,
+                None,
+                None,
+                None,
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_lead_ignore_nulls_without_nulls() -> Result<()> {
+        let expr = Arc::new(Column::new("c3", 0)) as Arc<dyn PhysicalExpr>;
+        let values: Vec<ArrayRef> = vec![Arc::new(Int32Array::from(vec![1, 2, 3]))];
+
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lead(),
+            &[expr],
+            &values,
+            true,
+            Int32Array::from(vec![Some(2), Some(3), None]),
+        )
+    }
+
+    #[test]
+    fn test_lead_lag_with_default_column() -> Result<()> {
+        let expr = Arc::new(Column::new("c3", 0)) as Arc<dyn PhysicalExpr>;
+        let offset =
+            Arc::new(Literal::new(ScalarValue::Int32(Some(1)))) as Arc<dyn PhysicalExpr>;
+        let default = Arc::new(Column::new("c4", 1)) as Arc<dyn PhysicalExpr>;
+        let input_exprs = [expr, offset, default];
+        let input_fields = [DataType::Int32, DataType::Int32, DataType::Int32]
+            .into_iter()
+            .map(|d| Arc::new(Field::new("f", d, true)))
+            .collect::<Vec<_>>();
+
+        // The default value is evaluated as an additional column
+        let exprs = WindowShift::lag()
+            .expressions(ExpressionArgs::new(&input_exprs, &input_fields));
+        assert_eq!(exprs.len(), 2);
+
+        let values: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3), None])),
+            Arc::new(Int32Array::from(vec![10, 20, 30, 40])),
+        ];
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lag(),
+            &input_exprs,
+            &values,
+            false,
+            Int32Array::from(vec![Some(10), Some(1), None, Some(3)]),
+        )?;
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lead(),
+            &input_exprs,
+            &values,
+            false,
+            Int32Array::from(vec![None, Some(3), None, Some(40)]),
+        )?;
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lag(),
+            &input_exprs,
+            &values,
+            true,
+            Int32Array::from(vec![Some(10), Some(1), Some(1), Some(3)]),
+        )?;
+        test_row_wise_matches_evaluate_all(
+            WindowShift::lead(),
+            &input_exprs,
+            &values,
+            true,
+            Int32Array::from(vec![Some(3), Some(3), Some(30), Some(40)]),
+        )
+    }
 }
@@ -42,11 +42,13 @@ use datafusion_expr::WindowUDF;
 #[macro_use]
 pub mod macros;
 
+pub mod conditional_event;
 pub mod cume_dist;
 pub mod lead_lag;
 pub mod nth_value;
 pub mod ntile;
 pub mod rank;
+pub mod ratio_to_report;
 pub mod row_number;
 
 pub mod planner;
@@ -55,12 +57,16 @@ mod utils;
 
 /// Fluent-style API for creating `Expr`s
 pub mod expr_fn {
+    pub use super::conditional_event::{
+        conditional_change_event, conditional_true_event,
+    };
     pub use super::cume_dist::cume_dist;
     pub use super::lead_lag::lag;
     pub use super::lead_lag::lead;
     pub use super::nth_value::{first_value, last_value, nth_value};
     pub use super::ntile::ntile;
     pub use super::rank::{dense_rank, percent_rank, rank};
+    pub use super::ratio_to_report::ratio_to_report;
     pub use super::row_number::row_number;
 }
 
@@ -78,6 +84,9 @@ pub fn all_default_window_functions() -> Vec<Arc<WindowUDF>> {
         nth_value::first_value_udwf(),
         nth_value::last_value_udwf(),
         nth_value::nth_value_udwf(),
+        conditional_event::conditional_change_event_udwf(),
+        conditional_event::conditional_true_event_udwf(),
+        ratio_to_report::ratio_to_report_udwf(),
     ]
 }
 /// Registers all enabled packages with a [`FunctionRegistry`]
@@ -20,7 +20,7 @@
 use crate::utils::{get_scalar_value_from_args, get_signed_integer};
 
 use arrow::datatypes::FieldRef;
-use datafusion_common::arrow::array::ArrayRef;
+use datafusion_common::arrow::array::{Array, ArrayRef};
 use datafusion_common::arrow::datatypes::{DataType, Field};
 use datafusion_common::{exec_datafusion_err, exec_err, Result, ScalarValue};
 use datafusion_expr::window_doc_sections::DOC_SECTION_ANALYTICAL;
@@ -33,7 +33,7 @@ use datafusion_functions_window_common::field;
 use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
 use field::WindowUDFFieldArgs;
 use std::any::Any;
-use std::cmp::Ordering;
+use std::cmp::{min, Ordering};
 use std::fmt::Debug;
 use std::ops::Range;
 use std::sync::LazyLock;
@@ -277,6 +277,7 @@ impl WindowUDFImpl for NthValue {
                 state,
                 ignore_nulls: partition_evaluator_args.ignore_nulls(),
                 n: 0,
+                retained_rows: 0,
             }));
         }
 
@@ -306,6 +307,7 @@ impl WindowUDFImpl for NthValue {
             state,
             ignore_nulls: partition_evaluator_args.ignore_nulls(),
             n,
+            retained_rows: 0,
         }))
     }
 
@@ -356,6 +358,50 @@ pub(crate) struct NthValueEvaluator {
     state: NthValueState,
     ignore_nulls: bool,
     n: i64,
+    // When nulls are ignored and the result is taken from the end of the
+    // window frame (LAST_VALUE, or NTH_VALUE with a negative index), the
+    // number of rows at the end of the last window frame that contain the
+    // non-null values that later frames may still refer to.
+    retained_rows: usize,
+}
+
+impl NthValueEvaluator {
+    /// Returns `true` if the result is counted from the end of the window frame.
+    fn is_reverse_direction(&self) -> bool {
+        match self.state.kind {
+            NthValueKind::First => false,
+            NthValueKind::Last => true,
+            NthValueKind::Nth => self.n < 0,
+        }
+    }
+
+    /// Number of non-null entries, counted from the end of the window frame,
+    /// that are needed to compute the result.
+    fn reverse_index(&self) -> usize {
+        match self.state.kind {
+            NthValueKind::Nth => self.n.unsigned_abs() as usize,
+            _ => 1,
+        }
+    }
+
+    /// Updates `retained_rows` using the non-null indices of the window frame
+    /// `range`, so that the rows before them can be pruned when the window
+    /// frame start is unbounded.
+    fn update_retained_rows(
+        &mut self,
+        valid_indices: Option<&[usize]>,
+        range: &Range<usize>,
+    ) {
+        let reverse_index = self.reverse_index();
+        self.retained_rows = match valid_indices {
+            Some([]) => 0,
+            Some(valid_indices) => {
+                let n_valid = min(reverse_index, valid_indices.len());
+                range.end - valid_indices[valid_indices.len() - n_valid]
+            }
+            None => min(reverse_index, range.end - range.start),
+        };
+    }
 }
 
 impl PartitionEvaluator for NthValueEvaluator {
@@ -394,8 +440,24 @@ impl PartitionEvaluator for NthValueEvaluator {
                 }
             }
         };
-        // Do not memoize results when nulls are ignored.
-        if is_prunable && !self.ignore_nulls {
+        if self.ignore_nulls {
+            // When nulls are ignored, results counted from the start of the
+            // window frame are final once they are non-null. Results counted
+            // from the end only depend on the last non-null entries seen.
+            if self.is_reverse_direction() {
+                state.window_frame_range.start = state
+                    .window_frame_range
+                    .end
+                    .saturating_sub(self.retained_rows);
+            } else if size > 0 && out.is_valid(size - 1) {
+                if self.state.finalized_result.is_none() {
+                    let result = ScalarValue::try_from_array(out, size - 1)?;
+                    self.state.finalized_result = Some(result);
+                }
+                state.window_frame_range.start =
+                    state.window_frame_range.end.saturating_sub(buffer_size);
+            }
+        } else if is_prunable {
             if self.state.finalized_result.is_none() && !is_reverse_direction {
                 let result = ScalarValue::try_from_array(out, size - 1)?;
                 self.state.finalized_result = Some(result);
@@ -419,6 +481,7 @@ impl PartitionEvaluator for NthValueEvaluator {
             let n_range = range.end - range.start;
             if n_range == 0 {
                 // We produce None if the window is empty.
+                self.retained_rows = 0;
                 return ScalarValue::try_from(arr.data_type());
             }
 
@@ -437,6 +500,7 @@ impl PartitionEvaluator for NthValueEvaluator {
                             .collect::<Vec<_>>();
                         if valid_indices.is_empty() {
                             // If all values are null, return directly.
+                            self.retained_rows = 0;
                             return ScalarValue::try_from(arr.data_type());
                         }
                         Some(valid_indices)
@@ -446,6 +510,9 @@ impl PartitionEvaluator for NthValueEvaluator {
             } else {
                 None
             };
+            if self.ignore_nulls && self.is_reverse_direction() {
+                self.update_retained_rows(valid_indices.as_deref(), range);
+            }
             match self.state.kind {
                 NthValueKind::First => {
                     if let Some(valid_indices) = &valid_indices {
@@ -0,0 +1,231 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `ratio_to_report` window function implementation
+
+use crate::define_udwf_and_expr;
+use arrow::datatypes::FieldRef;
+use datafusion_common::arrow::array::{Array, ArrayRef, Float64Array};
+use datafusion_common::arrow::compute::sum;
+use datafusion_common::arrow::datatypes::{DataType, Field};
+use datafusion_common::cast::as_float64_array;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::{
+    Documentation, PartitionEvaluator, Signature, Volatility, WindowUDFImpl,
+};
+use datafusion_functions_window_common::field::WindowUDFFieldArgs;
+use datafusion_functions_window_common::partition::PartitionEvaluatorArgs;
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
+
+define_udwf_and_expr!(
+    RatioToReport,
+    ratio_to_report,
+    [expr],
+    "Returns the ratio of a value to the sum of the values in the partition."
+);
+
+/// ratio_to_report expression
+#[user_doc(
+    doc_section(label = "Analytical Functions"),
+    description = "Returns the ratio of the value of expression for the current row to \
+    the sum of expression over all the rows of the partition. NULL values are ignored \
+    when computing the sum; the result is NULL if the sum is NULL or zero. The ORDER BY \
+    clause and window frame of the window have no effect on the result.",
+    syntax_example = "ratio_to_report(expression)",
+    argument(name = "expression", description = "Numeric expression to operate on"),
+    sql_example = r#"```sql
+    --Example usage of the ratio_to_report window function:
+    SELECT department,
+           salary,
+           ratio_to_report(salary) OVER (PARTITION BY department) AS ratio
+    FROM employees;
+```
+
+```sql
++-------------+--------+-------+
+| department  | salary | ratio |
++-------------+--------+-------+
+| Sales       | 30000  | 0.3   |
+| Sales       | 70000  | 0.7   |
+| Engineering | 50000  | 0.5   |
+| Engineering | 50000  | 0.5   |
++-------------+--------+-------+
+```"#
+)]
+#[derive(Debug)]
+pub struct RatioToReport {
+    signature: Signature,
+}
+
+impl RatioToReport {
+    /// Create a new `ratio_to_report` function
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(
+                1,
+                vec![DataType::Float64],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl Default for RatioToReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowUDFImpl for RatioToReport {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ratio_to_report"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        _partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        Ok(Box::<RatioToReportEvaluator>::default())
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::Float64, true).into())
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// State for the `ratio_to_report` window function.
+#[derive(Debug, Default)]
+struct RatioToReportEvaluator {
+    /// Sum of the values in the partition, computed once the whole
+    /// partition is available
+    total: Option<Option<f64>>,
+    /// Number of rows evaluated so far
+    n_rows: usize,
+}
+
+impl RatioToReportEvaluator {
+    fn ratio(value: Option<f64>, total: Option<f64>) -> Option<f64> {
+        match (value, total) {
+            (Some(value), Some(total)) if total != 0.0 => Some(value / total),
+            _ => None,
+        }
+    }
+}
+
+impl PartitionEvaluator for RatioToReportEvaluator {
+    /// Every row needs the whole partition to compute the sum of the values
+    fn get_range(&self, _idx: usize, n_rows: usize) -> Result<Range<usize>> {
+        Ok(Range {
+            start: 0,
+            end: n_rows,
+        })
+    }
+
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        let array = as_float64_array(&values[0])?;
+        let total = *self.total.get_or_insert_with(|| {
+            sum(&array.slice(range.start, range.end - range.start))
+        });
+        let idx = range.start + self.n_rows;
+        self.n_rows += 1;
+        let value = array.is_valid(idx).then(|| array.value(idx));
+        Ok(ScalarValue::Float64(Self::ratio(value, total)))
+    }
+
+    fn evaluate_all(
+        &mut self,
+        values: &[ArrayRef],
+        _num_rows: usize,
+    ) -> Result<ArrayRef> {
+        let array = as_float64_array(&values[0])?;
+        let total = sum(array);
+        Ok(Arc::new(
+            array
+                .iter()
+                .map(|value| Self::ratio(value, total))
+                .collect::<Float64Array>(),
+        ))
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_to_report() -> Result<()> {
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(1.0),
+            None,
+            Some(3.0),
+            Some(4.0),
+        ]));
+        let num_rows = values.len();
+        let values = [values];
+        let expected =
+            Float64Array::from(vec![Some(0.125), None, Some(0.375), Some(0.5)]);
+
+        let all = RatioToReport::new()
+            .partition_evaluator(PartitionEvaluatorArgs::default())?
+            .evaluate_all(&values, num_rows)?;
+        assert_eq!(&expected, as_float64_array(&all)?);
+
+        let mut evaluator =
+            RatioToReport::new().partition_evaluator(PartitionEvaluatorArgs::default())?;
+        let row_wise = (0..num_rows)
+            .map(|idx| evaluator.evaluate(&values, &evaluator.get_range(idx, num_rows)?))
+            .collect::<Result<Vec<_>>>()?;
+        let row_wise = ScalarValue::iter_to_array(row_wise)?;
+        assert_eq!(&expected, as_float64_array(&row_wise)?);
+        Ok(())
+    }
+
+    #[test]
+    fn ratio_to_report_zero_sum() -> Result<()> {
+        let values: ArrayRef =
+            Arc::new(Float64Array::from(vec![Some(1.0), Some(-1.0), None]));
+        let all = RatioToReport::new()
+            .partition_evaluator(PartitionEvaluatorArgs::default())?
+            .evaluate_all(&[values], 3)?;
+        assert_eq!(as_float64_array(&all)?.null_count(), 3);
+        Ok(())
+    }
+}
@@ -17,23 +17,41 @@
 
 //! "regex" DataFusion functions
 
+use arrow::array::{
+    Array, ArrayRef, GenericListBuilder, LargeStringArray, LargeStringBuilder,
+    ListBuilder, StringArray, StringArrayType, StringBuilder, StringViewArray,
+    StringViewBuilder,
+};
+use arrow::datatypes::DataType;
 use arrow::error::ArrowError;
+use datafusion_common::{internal_err, Result, ScalarValue};
+use datafusion_expr::ColumnarValue;
 use regex::Regex;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::sync::Arc;
 pub mod regexpcount;
+pub mod regexpextract;
 pub mod regexpinstr;
 pub mod regexplike;
 pub mod regexpmatch;
 pub mod regexpreplace;
+pub mod regexpsplittoarray;
+pub mod regexpsubstr;
 
 // create UDFs
 make_udf_function!(regexpcount::RegexpCountFunc, regexp_count);
+make_udf_function!(regexpextract::RegexpExtractFunc, regexp_extract);
+make_udf_function!(regexpextract::RegexpExtractAllFunc, regexp_extract_all);
 make_udf_function!(regexpinstr::RegexpInstrFunc, regexp_instr);
 make_udf_function!(regexpmatch::RegexpMatchFunc, regexp_match);
 make_udf_function!(regexplike::RegexpLikeFunc, regexp_like);
 make_udf_function!(regexpreplace::RegexpReplaceFunc, regexp_replace);
+make_udf_function!(
+    regexpsplittoarray::RegexpSplitToArrayFunc,
+    regexp_split_to_array
+);
+make_udf_function!(regexpsubstr::RegexpSubstrFunc, regexp_substr);
 
 pub mod expr_fn {
     use datafusion_expr::Expr;
@@ -115,6 +133,78 @@ pub mod expr_fn {
         };
         super::regexp_replace().call(args)
     }
+
+    /// Splits a string using a regular expression as the delimiter.
+    pub fn regexp_split_to_array(
+        string: Expr,
+        pattern: Expr,
+        flags: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![string, pattern];
+        if let Some(flags) = flags {
+            args.push(flags);
+        };
+        super::regexp_split_to_array().call(args)
+    }
+
+    /// Returns the substring that matches a regular expression.
+    pub fn regexp_substr(
+        values: Expr,
+        regex: Expr,
+        start: Option<Expr>,
+        n: Option<Expr>,
+        flags: Option<Expr>,
+        subexpr: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(start) = start {
+            args.push(start);
+        };
+        if let Some(n) = n {
+            args.push(n);
+        };
+        if let Some(flags) = flags {
+            args.push(flags);
+        };
+        if let Some(subexpr) = subexpr {
+            args.push(subexpr);
+        };
+        super::regexp_substr().call(args)
+    }
+
+    /// Extracts the capture group of the first match of a regular expression.
+    pub fn regexp_extract(
+        values: Expr,
+        regex: Expr,
+        group: Option<Expr>,
+        flags: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(group) = group {
+            args.push(group);
+        };
+        if let Some(flags) = flags {
+            args.push(flags);
+        };
+        super::regexp_extract().call(args)
+    }
+
+    /// Extracts the capture group of all the matches of a regular expression.
+    pub fn regexp_extract_all(
+        values: Expr,
+        regex: Expr,
+        group: Option<Expr>,
+        flags: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(group) = group {
+            args.push(group);
+        };
+        if let Some(flags) = flags {
+            args.push(flags);
+        };
+        super::regexp_extract_all().call(args)
+    }
 }
 
 /// Returns all DataFusion functions defined in this package
@@ -125,6 +215,10 @@ pub fn functions() -> Vec<Arc<datafusion_expr::ScalarUDF>> {
         regexp_instr(),
         regexp_like(),
         regexp_replace(),
+        regexp_split_to_array(),
+        regexp_substr(),
+        regexp_extract(),
+        regexp_extract_all(),
     ]
 }
 
@@ -164,3 +258,140 @@ pub fn compile_regex(regex: &str, flags: Option<&str>) -> Result<Regex, ArrowErr
         ArrowError::ComputeError(format!("Regular expression did not compile: {pattern}"))
     })
 }
+
+/// Invokes a regular expression function with `args` converted to arrays.
+///
+/// The first argument is expanded to the number of rows of the batch, while
+/// the other scalar arguments are passed as single element arrays so that a
+/// scalar pattern is only compiled once per batch (see [`for_each_regex`]).
+/// If all the arguments are scalars, the result is a scalar.
+pub(crate) fn invoke_regex_function(
+    args: &[ColumnarValue],
+    func: impl FnOnce(&[ArrayRef]) -> Result<ArrayRef>,
+) -> Result<ColumnarValue> {
+    let len = args
+        .iter()
+        .fold(Option::<usize>::None, |acc, arg| match arg {
+            ColumnarValue::Scalar(_) => acc,
+            ColumnarValue::Array(a) => Some(a.len()),
+        });
+
+    let is_scalar = len.is_none();
+    let inferred_length = len.unwrap_or(1);
+    let args = args
+        .iter()
+        .enumerate()
+        .map(|(idx, arg)| match arg {
+            ColumnarValue::Scalar(scalar) if idx > 0 => scalar.to_array(),
+            arg => arg.to_array(inferred_length),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let result = func(&args)?;
+    if is_scalar {
+        // If all inputs are scalar, keeps output as scalar
+        ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+/// Returns the index in `array` of the argument for row `idx`: single
+/// element arrays hold a scalar argument shared by all the rows.
+pub(crate) fn arg_index(array: &dyn Array, idx: usize) -> usize {
+    if array.len() == 1 {
+        0
+    } else {
+        idx
+    }
+}
+
+/// Calls `f` with the index, the string and the compiled regular expression
+/// of every row of `values`. The string or the regular expression is `None`
+/// when the corresponding input is null.
+///
+/// When both `regex_array` and `flags_array` hold a single value, the regular
+/// expression is compiled once. Otherwise each distinct pattern and flags pair
+/// is compiled once using [`compile_and_cache_regex`].
+pub(crate) fn for_each_regex<'a, S>(
+    values: S,
+    regex_array: S,
+    flags_array: Option<S>,
+    mut f: impl FnMut(usize, Option<&'a str>, Option<&Regex>) -> Result<()>,
+) -> Result<()>
+where
+    S: StringArrayType<'a> + Copy,
+{
+    let value_at = |array: S, idx: usize| {
+        let idx = arg_index(&array, idx);
+        array.is_valid(idx).then(|| array.value(idx))
+    };
+
+    if regex_array.len() == 1 && flags_array.is_none_or(|flags| flags.len() == 1) {
+        let flags = flags_array.and_then(|flags| value_at(flags, 0));
+        let regex = value_at(regex_array, 0)
+            .map(|regex| compile_regex(regex, flags))
+            .transpose()?;
+        for (idx, value) in values.iter().enumerate() {
+            f(idx, value, regex.as_ref())?;
+        }
+    } else {
+        let mut regex_cache = HashMap::new();
+        for (idx, value) in values.iter().enumerate() {
+            let flags = flags_array.and_then(|flags| value_at(flags, idx));
+            let regex = match value_at(regex_array, idx) {
+                Some(regex) => {
+                    Some(compile_and_cache_regex(regex, flags, &mut regex_cache)?)
+                }
+                None => None,
+            };
+            f(idx, value, regex)?;
+        }
+    }
+    Ok(())
+}
+
+/// Creates a string array of type `data_type` from `values`
+pub(crate) fn to_string_array(
+    data_type: &DataType,
+    values: Vec<Option<&str>>,
+) -> Result<ArrayRef> {
+    Ok(match data_type {
+        DataType::Utf8 => Arc::new(StringArray::from(values)),
+        DataType::LargeUtf8 => Arc::new(LargeStringArray::from(values)),
+        DataType::Utf8View => Arc::new(StringViewArray::from(values)),
+        other => return internal_err!("Unsupported string type {other:?}"),
+    })
+}
+
+/// Creates a list array of strings of type `data_type` from `values`
+pub(crate) fn to_string_list_array(
+    data_type: &DataType,
+    values: Vec<Option<Vec<&str>>>,
+) -> Result<ArrayRef> {
+    fn build<B>(
+        mut builder: GenericListBuilder<i32, B>,
+        values: Vec<Option<Vec<&str>>>,
+    ) -> ArrayRef
+    where
+        B: arrow::array::ArrayBuilder + for<'s> Extend<Option<&'s str>>,
+    {
+        for value in values {
+            match value {
+                Some(value) => {
+                    builder.values().extend(value.into_iter().map(Some));
+                    builder.append(true);
+                }
+                None => builder.append(false),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+
+    Ok(match data_type {
+        DataType::Utf8 => build(ListBuilder::new(StringBuilder::new()), values),
+        DataType::LargeUtf8 => build(ListBuilder::new(LargeStringBuilder::new()), values),
+        DataType::Utf8View => build(ListBuilder::new(StringViewBuilder::new()), values),
+        other => return internal_err!("Unsupported string type {other:?}"),
+    })
+}
@@ -0,0 +1,484 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `regexp_extract` and `regexp_extract_all` implementations
+
+use crate::regex::{
+    arg_index, for_each_regex, invoke_regex_function, to_string_array,
+    to_string_list_array,
+};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringArrayType};
+use arrow::datatypes::{
+    DataType, DataType::Int64, DataType::LargeUtf8, DataType::Utf8, DataType::Utf8View,
+    Field, Int64Type,
+};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature::Exact,
+    TypeSignature::Uniform, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts a capture group from the first match of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) in a string. Returns an empty string if the regular expression or the group does not match.",
+    syntax_example = "regexp_extract(str, regexp[, group[, flags]])",
+    sql_example = r#"```sql
+> select regexp_extract('2024-05-17', '(?<year>\d+)-(?<month>\d+)', 'month');
++-------------------------------------------------------------------------------------+
+| regexp_extract(Utf8("2024-05-17"),Utf8("(?<year>\d+)-(?<month>\d+)"),Utf8("month")) |
++-------------------------------------------------------------------------------------+
+| 05                                                                                  |
++-------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    standard_argument(name = "regexp", prefix = "Regular"),
+    argument(
+        name = "group",
+        description = "Optional index or name of the capture group to extract. Defaults to 0, which extracts the entire match."
+    ),
+    argument(
+        name = "flags",
+        description = r#"Optional regular expression flags that control the behavior of the regular expression. The following flags are supported:
+  - **i**: case-insensitive: letters match both upper and lower case
+  - **m**: multi-line mode: ^ and $ match begin/end of line
+  - **s**: allow . to match \n
+  - **R**: enables CRLF mode: when multi-line mode is enabled, \r\n is used
+  - **U**: swap the meaning of x* and x*?"#
+    )
+)]
+#[derive(Debug)]
+pub struct RegexpExtractFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: extract_signature(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_regex_function(&args.args, |args| regexp_extract_func(args, false))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts a capture group from all the matches of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) in a string and returns them as an array.",
+    syntax_example = "regexp_extract_all(str, regexp[, group[, flags]])",
+    sql_example = r#"```sql
+> select regexp_extract_all('a=1, b=22', '(\w)=(\d+)', 2);
++-------------------------------------------------------------------+
+| regexp_extract_all(Utf8("a=1, b=22"),Utf8("(\w)=(\d+)"),Int64(2)) |
++-------------------------------------------------------------------+
+| [1, 22]                                                           |
++-------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    standard_argument(name = "regexp", prefix = "Regular"),
+    argument(
+        name = "group",
+        description = "Optional index or name of the capture group to extract. Defaults to 0, which extracts the entire matches."
+    ),
+    argument(
+        name = "flags",
+        description = r#"Optional regular expression flags that control the behavior of the regular expression. The following flags are supported:
+  - **i**: case-insensitive: letters match both upper and lower case
+  - **m**: multi-line mode: ^ and $ match begin/end of line
+  - **s**: allow . to match \n
+  - **R**: enables CRLF mode: when multi-line mode is enabled, \r\n is used
+  - **U**: swap the meaning of x* and x*?"#
+    )
+)]
+#[derive(Debug)]
+pub struct RegexpExtractAllFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractAllFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractAllFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: extract_signature(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractAllFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_all"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::List(Arc::new(Field::new_list_field(
+            arg_types[0].clone(),
+            true,
+        ))))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_regex_function(&args.args, |args| regexp_extract_func(args, true))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The group can either be given by its index or by its name
+fn extract_signature() -> Signature {
+    let mut signatures = vec![Uniform(2, vec![Utf8View, LargeUtf8, Utf8])];
+    for string_type in [Utf8View, LargeUtf8, Utf8] {
+        for group_type in [Int64, string_type.clone()] {
+            signatures.push(Exact(vec![
+                string_type.clone(),
+                string_type.clone(),
+                group_type.clone(),
+            ]));
+            signatures.push(Exact(vec![
+                string_type.clone(),
+                string_type.clone(),
+                group_type,
+                string_type.clone(),
+            ]));
+        }
+    }
+    Signature::one_of(signatures, Volatility::Immutable)
+}
+
+/// Extracts the capture group of the first match of each row, or of all the
+/// matches when `all` is true.
+pub fn regexp_extract_func(args: &[ArrayRef], all: bool) -> Result<ArrayRef> {
+    let name = if all {
+        "regexp_extract_all"
+    } else {
+        "regexp_extract"
+    };
+    let args_len = args.len();
+    if !(2..=4).contains(&args_len) {
+        return exec_err!("{name} was called with {args_len} arguments. It requires at least 2 and at most 4.");
+    }
+
+    let values = &args[0];
+    let regex_array = &args[1];
+    let group_array = args.get(2);
+    let flags_array = args.get(3);
+    match values.data_type() {
+        Utf8 => regexp_extract_inner(
+            values.as_string::<i32>(),
+            regex_array.as_string::<i32>(),
+            group_array.map(|group| GroupArg::new(group, |g| g.as_string::<i32>())),
+            flags_array.map(|flags| flags.as_string::<i32>()),
+            all,
+        ),
+        LargeUtf8 => regexp_extract_inner(
+            values.as_string::<i64>(),
+            regex_array.as_string::<i64>(),
+            group_array.map(|group| GroupArg::new(group, |g| g.as_string::<i64>())),
+            flags_array.map(|flags| flags.as_string::<i64>()),
+            all,
+        ),
+        Utf8View => regexp_extract_inner(
+            values.as_string_view(),
+            regex_array.as_string_view(),
+            group_array.map(|group| GroupArg::new(group, |g| g.as_string_view())),
+            flags_array.map(|flags| flags.as_string_view()),
+            all,
+        ),
+        other => exec_err!("Unsupported data type {other:?} for function {name}"),
+    }
+}
+
+/// The `group` argument of `regexp_extract`
+enum GroupArg<'a, S> {
+    Index(&'a Int64Array),
+    Name(S),
+}
+
+impl<'a, S: StringArrayType<'a> + Copy> GroupArg<'a, S> {
+    fn new(array: &'a ArrayRef, as_string: impl Fn(&'a ArrayRef) -> S) -> Self {
+        match array.data_type() {
+            Int64 => Self::Index(array.as_primitive::<Int64Type>()),
+            _ => Self::Name(as_string(array)),
+        }
+    }
+
+    /// Returns the group for row `idx`, or `None` if it is null
+    fn value(&self, idx: usize) -> Option<CaptureGroup<'a>> {
+        match self {
+            Self::Index(array) => {
+                let idx = arg_index(*array, idx);
+                array
+                    .is_valid(idx)
+                    .then(|| CaptureGroup::Index(array.value(idx)))
+            }
+            Self::Name(array) => {
+                let idx = arg_index(array, idx);
+                array
+                    .is_valid(idx)
+                    .then(|| CaptureGroup::Name(array.value(idx)))
+            }
+        }
+    }
+}
+
+enum CaptureGroup<'a> {
+    Index(i64),
+    Name(&'a str),
+}
+
+impl CaptureGroup<'_> {
+    /// Returns the index of the group in `regex`
+    fn resolve(&self, regex: &Regex, name: &str) -> Result<usize> {
+        match self {
+            Self::Index(index) => match usize::try_from(*index) {
+                Ok(index) if index < regex.captures_len() => Ok(index),
+                _ => exec_err!(
+                    "{name}() group index {index} is out of range, the regular expression has {} groups",
+                    regex.captures_len() - 1
+                ),
+            },
+            Self::Name(group) => {
+                match regex
+                    .capture_names()
+                    .position(|capture_name| capture_name == Some(group))
+                {
+                    Some(index) => Ok(index),
+                    None => exec_err!(
+                        "{name}() the regular expression has no group named '{group}'"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+fn regexp_extract_inner<'a, S>(
+    values: S,
+    regex_array: S,
+    group_array: Option<GroupArg<'a, S>>,
+    flags_array: Option<S>,
+    all: bool,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a> + Copy,
+{
+    let name = if all {
+        "regexp_extract_all"
+    } else {
+        "regexp_extract"
+    };
+    let mut first_matches = vec![];
+    let mut all_matches = vec![];
+    for_each_regex(values, regex_array, flags_array, |idx, value, regex| {
+        let group = match &group_array {
+            Some(group_array) => group_array.value(idx),
+            None => Some(CaptureGroup::Index(0)),
+        };
+        let (Some(value), Some(regex), Some(group)) = (value, regex, group) else {
+            if all {
+                all_matches.push(None);
+            } else {
+                first_matches.push(None);
+            }
+            return Ok(());
+        };
+
+        let group = group.resolve(regex, name)?;
+        if all {
+            let matches = if group == 0 {
+                regex.find_iter(value).map(|m| m.as_str()).collect()
+            } else {
+                regex
+                    .captures_iter(value)
+                    .map(|captures| captures.get(group).map_or("", |m| m.as_str()))
+                    .collect()
+            };
+            all_matches.push(Some(matches));
+        } else {
+            let matched = if group == 0 {
+                regex.find(value)
+            } else {
+                regex
+                    .captures(value)
+                    .and_then(|captures| captures.get(group))
+            };
+            first_matches.push(Some(matched.map_or("", |m| m.as_str())));
+        }
+        Ok(())
+    })?;
+
+    if all {
+        to_string_list_array(values.data_type(), all_matches)
+    } else {
+        to_string_array(values.data_type(), first_matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, ListArray, StringArray, StringViewArray};
+
+    fn extract(args: &[ArrayRef]) -> Result<Vec<Option<String>>> {
+        let result = regexp_extract_func(args, false)?;
+        Ok(result
+            .as_string_view()
+            .iter()
+            .map(|value| value.map(str::to_string))
+            .collect())
+    }
+
+    #[test]
+    fn test_regexp_extract() -> Result<()> {
+        let values: ArrayRef = Arc::new(StringViewArray::from(vec![
+            Some("2024-05-17"),
+            Some("no date"),
+            Some("1999-12"),
+            None,
+        ]));
+        let regex: ArrayRef = Arc::new(StringViewArray::from(vec![
+            r"(?<year>\d+)-(?<month>\d+)(-(?<day>\d+))?",
+        ]));
+
+        let result = extract(&[Arc::clone(&values), Arc::clone(&regex)])?;
+        assert_eq!(
+            result,
+            vec![
+                Some("2024-05-17".to_string()),
+                Some("".to_string()),
+                Some("1999-12".to_string()),
+                None
+            ]
+        );
+
+        let group: ArrayRef = Arc::new(Int64Array::from(vec![1]));
+        let result = extract(&[Arc::clone(&values), Arc::clone(&regex), group])?;
+        assert_eq!(result[0].as_deref(), Some("2024"));
+
+        let group: ArrayRef = Arc::new(StringViewArray::from(vec!["day"]));
+        let result = extract(&[values, regex, group])?;
+        assert_eq!(
+            result,
+            vec![
+                Some("17".to_string()),
+                Some("".to_string()),
+                Some("".to_string()),
+                None
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_extract_invalid_group() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["abc"]));
+        let regex: ArrayRef = Arc::new(StringArray::from(vec!["(b)"]));
+
+        let group: ArrayRef = Arc::new(Int64Array::from(vec![2]));
+        let err =
+            regexp_extract_func(&[Arc::clone(&values), Arc::clone(&regex), group], false)
+                .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_extract() group index 2 is out of range, the regular expression has 1 groups"
+        );
+
+        let group: ArrayRef = Arc::new(StringArray::from(vec!["name"]));
+        let err = regexp_extract_func(&[values, regex, group], false).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_extract() the regular expression has no group named 'name'"
+        );
+    }
+
+    #[test]
+    fn test_regexp_extract_all() -> Result<()> {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("a=1, B=22, c=333"),
+            Some("none"),
+            None,
+        ]));
+        let regex: ArrayRef = Arc::new(StringArray::from(vec![r"([a-z])=(\d+)"]));
+        let group: ArrayRef = Arc::new(Int64Array::from(vec![2]));
+        let flags: ArrayRef = Arc::new(StringArray::from(vec!["i"]));
+
+        let result = regexp_extract_func(&[values, regex, group, flags], true)?;
+        let list = result.as_any().downcast_ref::<ListArray>().unwrap();
+        let matches = list.value(0);
+        let matches = matches.as_string::<i32>();
+        assert_eq!(
+            matches.iter().collect::<Vec<_>>(),
+            vec![Some("1"), Some("22"), Some("333")]
+        );
+        assert_eq!(list.value(1).len(), 0);
+        assert!(list.is_null(2));
+        Ok(())
+    }
+}
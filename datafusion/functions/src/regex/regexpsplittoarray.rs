@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::regex::{for_each_regex, invoke_regex_function, to_string_list_array};
+use arrow::array::{Array, ArrayRef, AsArray, StringArrayType};
+use arrow::datatypes::{
+    DataType, DataType::LargeUtf8, DataType::Utf8, DataType::Utf8View, Field,
+};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature::Exact,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Splits a string using a [regular expression](https://docs.rs/regex/latest/regex/#syntax) as the delimiter and returns the parts as an array. Empty matches at the beginning or the end of the string, or immediately after a previous match are ignored.",
+    syntax_example = "regexp_split_to_array(str, regexp[, flags])",
+    sql_example = r#"```sql
+> select regexp_split_to_array('hello world  foo', '\s+');
++-------------------------------------------------------------+
+| regexp_split_to_array(Utf8("hello world  foo"),Utf8("\s+")) |
++-------------------------------------------------------------+
+| [hello, world, foo]                                         |
++-------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    standard_argument(name = "regexp", prefix = "Regular"),
+    argument(
+        name = "flags",
+        description = r#"Optional regular expression flags that control the behavior of the regular expression. The following flags are supported:
+  - **i**: case-insensitive: letters match both upper and lower case
+  - **m**: multi-line mode: ^ and $ match begin/end of line
+  - **s**: allow . to match \n
+  - **R**: enables CRLF mode: when multi-line mode is enabled, \r\n is used
+  - **U**: swap the meaning of x* and x*?"#
+    )
+)]
+#[derive(Debug)]
+pub struct RegexpSplitToArrayFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpSplitToArrayFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpSplitToArrayFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    Exact(vec![Utf8View, Utf8View]),
+                    Exact(vec![Utf8, Utf8]),
+                    Exact(vec![LargeUtf8, LargeUtf8]),
+                    Exact(vec![Utf8View, Utf8View, Utf8View]),
+                    Exact(vec![Utf8, Utf8, Utf8]),
+                    Exact(vec![LargeUtf8, LargeUtf8, LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpSplitToArrayFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_split_to_array"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::List(Arc::new(Field::new_list_field(
+            arg_types[0].clone(),
+            true,
+        ))))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_regex_function(&args.args, regexp_split_to_array_func)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+pub fn regexp_split_to_array_func(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=3).contains(&args_len) {
+        return exec_err!("regexp_split_to_array was called with {args_len} arguments. It requires at least 2 and at most 3.");
+    }
+
+    let values = &args[0];
+    let regex_array = &args[1];
+    let flags_array = args.get(2);
+    match values.data_type() {
+        Utf8 => regexp_split_to_array_inner(
+            values.as_string::<i32>(),
+            regex_array.as_string::<i32>(),
+            flags_array.map(|flags| flags.as_string::<i32>()),
+        ),
+        LargeUtf8 => regexp_split_to_array_inner(
+            values.as_string::<i64>(),
+            regex_array.as_string::<i64>(),
+            flags_array.map(|flags| flags.as_string::<i64>()),
+        ),
+        Utf8View => regexp_split_to_array_inner(
+            values.as_string_view(),
+            regex_array.as_string_view(),
+            flags_array.map(|flags| flags.as_string_view()),
+        ),
+        other => {
+            exec_err!(
+                "Unsupported data type {other:?} for function regexp_split_to_array"
+            )
+        }
+    }
+}
+
+fn regexp_split_to_array_inner<'a, S>(
+    values: S,
+    regex_array: S,
+    flags_array: Option<S>,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a> + Copy,
+{
+    let mut result = Vec::with_capacity(values.len());
+    for_each_regex(values, regex_array, flags_array, |_, value, regex| {
+        result.push(match (value, regex) {
+            (Some(value), Some(regex)) => Some(regexp_split(value, regex)),
+            _ => None,
+        });
+        Ok(())
+    })?;
+    to_string_list_array(values.data_type(), result)
+}
+
+/// Splits `value` around the matches of `regex`.
+///
+/// As in PostgreSQL, empty matches at the beginning or the end of `value`, or
+/// immediately after a previous match are ignored, so that an empty pattern
+/// splits a string into its characters.
+pub fn regexp_split<'h>(value: &'h str, regex: &Regex) -> Vec<&'h str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    for m in regex.find_iter(value) {
+        if m.is_empty() && (m.start() == start || m.start() == value.len()) {
+            continue;
+        }
+        parts.push(&value[start..m.start()]);
+        start = m.end();
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListArray, StringArray, StringViewArray};
+
+    fn split(value: &str, pattern: &str) -> Vec<String> {
+        let regex = Regex::new(pattern).unwrap();
+        regexp_split(value, &regex)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_regexp_split() {
+        assert_eq!(split("hello world", r"\s+"), vec!["hello", "world"]);
+        assert_eq!(split("a,b,,c", ","), vec!["a", "b", "", "c"]);
+        assert_eq!(split(",a,", ","), vec!["", "a", ""]);
+        assert_eq!(split("abc", ""), vec!["a", "b", "c"]);
+        assert_eq!(split("abc", "x*"), vec!["a", "b", "c"]);
+        assert_eq!(split("", ","), vec![""]);
+        assert_eq!(split("Düsseldorf", "s+"), vec!["Dü", "eldorf"]);
+    }
+
+    #[test]
+    fn test_regexp_split_to_array() -> Result<()> {
+        let values: ArrayRef = Arc::new(StringViewArray::from(vec![
+            Some("a1b22c"),
+            None,
+            Some("A1b"),
+        ]));
+        let regex: ArrayRef = Arc::new(StringViewArray::from(vec!["[0-9]+"]));
+        let result = regexp_split_to_array_func(&[values, regex])?;
+
+        let list = result.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(list.value_type(), Utf8View);
+        assert!(list.is_null(1));
+        let parts = |idx: usize| {
+            list.value(idx)
+                .as_string_view()
+                .iter()
+                .map(|part| part.unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parts(0), vec!["a", "b", "c"]);
+        assert_eq!(parts(2), vec!["A", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_split_to_array_per_row_pattern_and_flags() -> Result<()> {
+        let values: ArrayRef =
+            Arc::new(StringArray::from(vec!["aXbxc", "aXbxc", "aXbxc"]));
+        let regex: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("x"), Some("x"), None]));
+        let flags: ArrayRef = Arc::new(StringArray::from(vec![None, Some("i"), None]));
+        let result = regexp_split_to_array_func(&[values, regex, flags])?;
+
+        let list = result.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(list.value(0).len(), 2);
+        assert_eq!(list.value(1).len(), 3);
+        assert!(list.is_null(2));
+        Ok(())
+    }
+}
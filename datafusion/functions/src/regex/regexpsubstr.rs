@@ -0,0 +1,321 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::regex::{arg_index, for_each_regex, invoke_regex_function, to_string_array};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringArrayType};
+use arrow::datatypes::{
+    DataType, DataType::Int64, DataType::LargeUtf8, DataType::Utf8, DataType::Utf8View,
+    Int64Type,
+};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature::Exact,
+    TypeSignature::Uniform, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the substring that matches the specified occurrence of a [regular expression](https://docs.rs/regex/latest/regex/#syntax), or NULL if there is no such match.",
+    syntax_example = "regexp_substr(str, regexp[, start[, N[, flags[, subexpr]]]])",
+    sql_example = r#"```sql
+> select regexp_substr('number 123 and 4567', '[0-9]+', 1, 2);
++-----------------------------------------------------------------------------+
+| regexp_substr(Utf8("number 123 and 4567"),Utf8("[0-9]+"),Int64(1),Int64(2)) |
++-----------------------------------------------------------------------------+
+| 4567                                                                        |
++-----------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    standard_argument(name = "regexp", prefix = "Regular"),
+    argument(
+        name = "start",
+        description = "- **start**: Optional start position (the first position is 1) to search for the regular expression. Can be a constant, column, or function. Defaults to 1"
+    ),
+    argument(
+        name = "N",
+        description = "- **N**: Optional The N-th occurrence of pattern to find. Defaults to 1 (first match). Can be a constant, column, or function."
+    ),
+    argument(
+        name = "flags",
+        description = r#"Optional regular expression flags that control the behavior of the regular expression. The following flags are supported:
+  - **i**: case-insensitive: letters match both upper and lower case
+  - **m**: multi-line mode: ^ and $ match begin/end of line
+  - **s**: allow . to match \n
+  - **R**: enables CRLF mode: when multi-line mode is enabled, \r\n is used
+  - **U**: swap the meaning of x* and x*?"#
+    ),
+    argument(
+        name = "subexpr",
+        description = "Optional Specifies which capture group (subexpression) of the match to return. Defaults to 0, which returns the entire match."
+    )
+)]
+#[derive(Debug)]
+pub struct RegexpSubstrFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpSubstrFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpSubstrFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    Uniform(2, vec![Utf8View, LargeUtf8, Utf8]),
+                    Exact(vec![Utf8View, Utf8View, Int64]),
+                    Exact(vec![LargeUtf8, LargeUtf8, Int64]),
+                    Exact(vec![Utf8, Utf8, Int64]),
+                    Exact(vec![Utf8View, Utf8View, Int64, Int64]),
+                    Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64]),
+                    Exact(vec![Utf8, Utf8, Int64, Int64]),
+                    Exact(vec![Utf8View, Utf8View, Int64, Int64, Utf8View]),
+                    Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64, LargeUtf8]),
+                    Exact(vec![Utf8, Utf8, Int64, Int64, Utf8]),
+                    Exact(vec![Utf8View, Utf8View, Int64, Int64, Utf8View, Int64]),
+                    Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64, LargeUtf8, Int64]),
+                    Exact(vec![Utf8, Utf8, Int64, Int64, Utf8, Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpSubstrFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_substr"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_regex_function(&args.args, regexp_substr_func)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+pub fn regexp_substr_func(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=6).contains(&args_len) {
+        return exec_err!("regexp_substr was called with {args_len} arguments. It requires at least 2 and at most 6.");
+    }
+
+    let values = &args[0];
+    let regex_array = &args[1];
+    let start_array = args.get(2).map(|start| start.as_primitive::<Int64Type>());
+    let nth_array = args.get(3).map(|nth| nth.as_primitive::<Int64Type>());
+    let flags_array = args.get(4);
+    let subexpr_array = args
+        .get(5)
+        .map(|subexpr| subexpr.as_primitive::<Int64Type>());
+    match values.data_type() {
+        Utf8 => regexp_substr_inner(
+            values.as_string::<i32>(),
+            regex_array.as_string::<i32>(),
+            start_array,
+            nth_array,
+            flags_array.map(|flags| flags.as_string::<i32>()),
+            subexpr_array,
+        ),
+        LargeUtf8 => regexp_substr_inner(
+            values.as_string::<i64>(),
+            regex_array.as_string::<i64>(),
+            start_array,
+            nth_array,
+            flags_array.map(|flags| flags.as_string::<i64>()),
+            subexpr_array,
+        ),
+        Utf8View => regexp_substr_inner(
+            values.as_string_view(),
+            regex_array.as_string_view(),
+            start_array,
+            nth_array,
+            flags_array.map(|flags| flags.as_string_view()),
+            subexpr_array,
+        ),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_substr")
+        }
+    }
+}
+
+fn regexp_substr_inner<'a, S>(
+    values: S,
+    regex_array: S,
+    start_array: Option<&Int64Array>,
+    nth_array: Option<&Int64Array>,
+    flags_array: Option<S>,
+    subexpr_array: Option<&Int64Array>,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a> + Copy,
+{
+    // Returns the value of an optional integer argument for row `idx`
+    let int_arg = |array: Option<&Int64Array>, idx: usize, default: i64| match array {
+        Some(array) => {
+            let idx = arg_index(array, idx);
+            array.is_valid(idx).then(|| array.value(idx))
+        }
+        None => Some(default),
+    };
+
+    let mut result = Vec::with_capacity(values.len());
+    for_each_regex(values, regex_array, flags_array, |idx, value, regex| {
+        let args = (
+            value,
+            regex,
+            int_arg(start_array, idx, 1),
+            int_arg(nth_array, idx, 1),
+            int_arg(subexpr_array, idx, 0),
+        );
+        result.push(match args {
+            (Some(value), Some(regex), Some(start), Some(n), Some(subexpr)) => {
+                substr(value, regex, start, n, subexpr)?
+            }
+            _ => None,
+        });
+        Ok(())
+    })?;
+    to_string_array(values.data_type(), result)
+}
+
+/// Returns the capture group `subexpr` of the `n`-th match of `regex` in
+/// `value`, starting the search at the 1-based character position `start`.
+fn substr<'h>(
+    value: &'h str,
+    regex: &Regex,
+    start: i64,
+    n: i64,
+    subexpr: i64,
+) -> Result<Option<&'h str>> {
+    if start < 1 {
+        return exec_err!("regexp_substr() requires start to be 1-based");
+    }
+    if n < 1 {
+        return exec_err!("regexp_substr() requires N to be 1 or greater");
+    }
+    if subexpr < 0 {
+        return exec_err!("regexp_substr() requires subexpr to be 0 or greater");
+    }
+
+    let Some((byte_start_offset, _)) = value.char_indices().nth((start - 1) as usize)
+    else {
+        return Ok(None);
+    };
+    let search_slice = &value[byte_start_offset..];
+    Ok(regex
+        .captures_iter(search_slice)
+        .nth((n - 1) as usize)
+        .and_then(|captures| captures.get(subexpr as usize))
+        .map(|matched| matched.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{LargeStringArray, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_regexp_substr() -> Result<()> {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("abc123def45"),
+            Some("abc123def45"),
+            Some("abc123def45"),
+            Some("abc"),
+            None,
+        ]));
+        let regex: ArrayRef = Arc::new(StringArray::from(vec![r"[a-z]+(\d+)"]));
+        let start: ArrayRef = Arc::new(Int64Array::from(vec![1, 5, 2, 1, 1]));
+        let nth: ArrayRef = Arc::new(Int64Array::from(vec![2, 1, 1, 1, 1]));
+        let flags: ArrayRef = Arc::new(StringArray::from(vec![""]));
+        let subexpr: ArrayRef = Arc::new(Int64Array::from(vec![1]));
+
+        let result = regexp_substr_func(&[
+            Arc::clone(&values),
+            Arc::clone(&regex),
+            start,
+            nth,
+            flags,
+            subexpr,
+        ])?;
+        let expected =
+            StringArray::from(vec![Some("45"), Some("45"), Some("123"), None, None]);
+        assert_eq!(result.as_string::<i32>(), &expected);
+
+        let result = regexp_substr_func(&[values, regex])?;
+        let expected = StringArray::from(vec![
+            Some("abc123"),
+            Some("abc123"),
+            Some("abc123"),
+            None,
+            None,
+        ]);
+        assert_eq!(result.as_string::<i32>(), &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_substr_start_beyond_string() -> Result<()> {
+        let values: ArrayRef = Arc::new(LargeStringArray::from(vec!["Köln"]));
+        let regex: ArrayRef = Arc::new(LargeStringArray::from(vec!["."]));
+        let start: ArrayRef = Arc::new(Int64Array::from(vec![2]));
+        let result =
+            regexp_substr_func(&[Arc::clone(&values), Arc::clone(&regex), start])?;
+        assert_eq!(result.as_string::<i64>().value(0), "ö");
+
+        let start: ArrayRef = Arc::new(Int64Array::from(vec![5]));
+        let result = regexp_substr_func(&[values, regex, start])?;
+        assert!(result.is_null(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_substr_invalid_arguments() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["abc"]));
+        let regex: ArrayRef = Arc::new(StringArray::from(vec!["b"]));
+        let start: ArrayRef = Arc::new(Int64Array::from(vec![0]));
+        let err = regexp_substr_func(&[values, regex, start]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_substr() requires start to be 1-based"
+        );
+    }
+}
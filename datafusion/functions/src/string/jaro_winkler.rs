@@ -0,0 +1,149 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+
+use crate::strings::compare_strings;
+use crate::utils::make_scalar_function;
+use datafusion_common::types::logical_string;
+use datafusion_common::utils::datafusion_strsim;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::Result;
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Returns the [`Jaro-Winkler similarity`](https://en.wikipedia.org/wiki/Jaro%E2%80%93Winkler_distance) between the two given strings, from 0 (no similarity) to 1 (identical strings).",
+    syntax_example = "jaro_winkler(str1, str2)",
+    sql_example = r#"```sql
+> select jaro_winkler('martha', 'marhta');
++---------------------------------------------+
+| jaro_winkler(Utf8("martha"),Utf8("marhta")) |
++---------------------------------------------+
+| 0.9611111111111111                          |
++---------------------------------------------+
+```"#,
+    argument(
+        name = "str1",
+        description = "String expression to compute the Jaro-Winkler similarity with str2."
+    ),
+    argument(
+        name = "str2",
+        description = "String expression to compute the Jaro-Winkler similarity with str1."
+    ),
+    related_udf(name = "levenshtein"),
+    related_udf(name = "similarity")
+)]
+#[derive(Debug)]
+pub struct JaroWinklerFunc {
+    signature: Signature,
+}
+
+impl Default for JaroWinklerFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JaroWinklerFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![
+                    Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                    Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JaroWinklerFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "jaro_winkler"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(jaro_winkler, vec![])(&args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the Jaro-Winkler similarity between the two given strings.
+/// JARO_WINKLER('dixon', 'dicksonx') = 0.8133333333333332
+fn jaro_winkler(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let [str1, str2] = take_function_args("jaro_winkler", args)?;
+    compare_strings(str1, str2, "jaro_winkler", datafusion_strsim::jaro_winkler)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Array, Float64Array, LargeStringArray, StringViewArray};
+
+    use super::*;
+
+    #[test]
+    fn mixed_string_types() -> Result<()> {
+        let str1: ArrayRef = Arc::new(StringViewArray::from(vec![
+            Some("martha"),
+            Some("dixon"),
+            Some(""),
+            None,
+        ]));
+        let str2: ArrayRef = Arc::new(LargeStringArray::from(vec![
+            Some("marhta"),
+            Some("dicksonx"),
+            Some(""),
+            Some("jones"),
+        ]));
+
+        let result = jaro_winkler(&[str1, str2])?;
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+
+        assert!((result.value(0) - 0.96111).abs() < 0.00001);
+        assert!((result.value(1) - 0.81333).abs() < 0.00001);
+        assert_eq!(result.value(2), 1.0);
+        assert!(result.is_null(3));
+
+        Ok(())
+    }
+}
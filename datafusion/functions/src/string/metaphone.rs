@@ -0,0 +1,314 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+
+use crate::strings::map_strings;
+use crate::utils::{make_scalar_function, utf8_to_str_type};
+use datafusion_common::types::logical_string;
+use datafusion_common::Result;
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Returns the [`Metaphone`](https://en.wikipedia.org/wiki/Metaphone) code of a string, \
+    which is the same for strings that are pronounced similarly in English. \
+    Characters other than ASCII letters are ignored and `0` stands for the `th` sound.",
+    syntax_example = "metaphone(str)",
+    sql_example = r#"```sql
+> select metaphone('Knight'), metaphone('Night');
++---------------------------+--------------------------+
+| metaphone(Utf8("Knight")) | metaphone(Utf8("Night")) |
++---------------------------+--------------------------+
+| NT                        | NT                       |
++---------------------------+--------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    related_udf(name = "soundex")
+)]
+#[derive(Debug)]
+pub struct MetaphoneFunc {
+    signature: Signature,
+}
+
+impl Default for MetaphoneFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaphoneFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Native(
+                    logical_string(),
+                ))],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MetaphoneFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "metaphone"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0] == DataType::Utf8View {
+            Ok(DataType::Utf8View)
+        } else {
+            utf8_to_str_type(&arg_types[0], "metaphone")
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(metaphone, vec![])(&args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the Metaphone code of the string.
+/// METAPHONE('Thompson') = '0MPSN'
+fn metaphone(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let mut word = vec![];
+    map_strings(&args[0], "metaphone", |string, code| {
+        word.clear();
+        word.extend(
+            string
+                .bytes()
+                .filter(u8::is_ascii_alphabetic)
+                .map(|b| b.to_ascii_uppercase()),
+        );
+        metaphone_word(&word, code)
+    })
+}
+
+fn is_vowel(letter: u8) -> bool {
+    matches!(letter, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+/// Returns true for the vowels that soften a preceding C or G
+fn is_front_vowel(letter: u8) -> bool {
+    matches!(letter, b'E' | b'I' | b'Y')
+}
+
+/// Appends the Metaphone code of `word`, made of uppercase ASCII letters,
+/// to `code`. Follows the rules of Lawrence Philips' original algorithm.
+fn metaphone_word(word: &[u8], code: &mut String) {
+    let at = |i: usize| word.get(i).copied().unwrap_or_default();
+    let matches_at = |i: usize, pattern: &[u8]| word[i..].starts_with(pattern);
+
+    // Initial letters with special rules
+    let mut i = match word {
+        [b'A', b'E', ..] => {
+            code.push('E');
+            2
+        }
+        [b'G' | b'K' | b'P', b'N', ..] => {
+            code.push('N');
+            2
+        }
+        [b'W', b'R', ..] => {
+            code.push('R');
+            2
+        }
+        [b'W', b'H', ..] => {
+            code.push('W');
+            2
+        }
+        [b'X', ..] => {
+            code.push('S');
+            1
+        }
+        [first, ..] if is_vowel(*first) => {
+            code.push(*first as char);
+            1
+        }
+        _ => 0,
+    };
+
+    while i < word.len() {
+        let letter = word[i];
+        let previous = if i > 0 { word[i - 1] } else { 0 };
+        let next = at(i + 1);
+        let is_last = i + 1 == word.len();
+
+        // Double letters are coded once, except for C
+        if letter != b'C' && letter == previous {
+            i += 1;
+            continue;
+        }
+
+        match letter {
+            // Vowels are only coded at the start of the word
+            b'A' | b'E' | b'I' | b'O' | b'U' => {}
+            // Silent in a trailing MB
+            b'B' => {
+                if !(previous == b'M' && is_last) {
+                    code.push('B');
+                }
+            }
+            b'C' => {
+                if previous == b'S' && is_front_vowel(next) {
+                    // Silent in SCE, SCI and SCY
+                } else if matches_at(i, b"CIA") {
+                    code.push('X');
+                } else if is_front_vowel(next) {
+                    code.push('S');
+                } else if previous == b'S' && next == b'H' {
+                    code.push('K');
+                } else if next == b'H' {
+                    if i == 0 && word.len() >= 3 && !is_vowel(at(2)) {
+                        code.push('K');
+                    } else {
+                        code.push('X');
+                    }
+                } else {
+                    code.push('K');
+                }
+            }
+            b'D' => {
+                if next == b'G' && is_front_vowel(at(i + 2)) {
+                    code.push('J');
+                    i += 2;
+                } else {
+                    code.push('T');
+                }
+            }
+            b'G' => {
+                let silent =
+                    (next == b'H' && !is_vowel(at(i + 2))) || (i > 0 && next == b'N');
+                if silent {
+                    // Silent in GH not followed by a vowel and in GN
+                } else if is_front_vowel(next) && previous != b'G' {
+                    code.push('J');
+                } else {
+                    code.push('K');
+                }
+            }
+            // Silent after CSPTG and when not followed by a vowel
+            b'H' => {
+                if !is_last
+                    && !matches!(previous, b'C' | b'S' | b'P' | b'T' | b'G')
+                    && is_vowel(next)
+                {
+                    code.push('H');
+                }
+            }
+            // Silent after C
+            b'K' => {
+                if previous != b'C' {
+                    code.push('K');
+                }
+            }
+            b'P' => code.push(if next == b'H' { 'F' } else { 'P' }),
+            b'Q' => code.push('K'),
+            b'S' => {
+                if matches_at(i, b"SH") || matches_at(i, b"SIO") || matches_at(i, b"SIA")
+                {
+                    code.push('X');
+                } else {
+                    code.push('S');
+                }
+            }
+            b'T' => {
+                if matches_at(i, b"TIA") || matches_at(i, b"TIO") {
+                    code.push('X');
+                } else if next == b'H' {
+                    code.push('0');
+                } else if !matches_at(i, b"TCH") {
+                    code.push('T');
+                }
+            }
+            b'V' => code.push('F'),
+            b'W' | b'Y' => {
+                if is_vowel(next) {
+                    code.push(letter as char);
+                }
+            }
+            b'X' => code.push_str("KS"),
+            b'Z' => code.push('S'),
+            _ => code.push(letter as char),
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_metaphone(string: &str) -> String {
+        let mut code = String::new();
+        metaphone_word(string.to_ascii_uppercase().as_bytes(), &mut code);
+        code
+    }
+
+    #[test]
+    fn test_metaphone() {
+        let cases = [
+            ("the", "0"),
+            ("quick", "KK"),
+            ("brown", "BRN"),
+            ("fox", "FKS"),
+            ("jumped", "JMPT"),
+            ("over", "OFR"),
+            ("lazy", "LS"),
+            ("dogs", "TKS"),
+            ("howl", "HL"),
+            ("testing", "TSTNK"),
+            ("Thompson", "0MPSN"),
+            ("knight", "NT"),
+            ("aeon", "EN"),
+            ("wright", "RT"),
+            ("white", "WT"),
+            ("Xavier", "SFR"),
+            ("thumb", "0M"),
+            ("science", "SNS"),
+            ("christ", "KRST"),
+            ("church", "XRX"),
+            ("school", "SKL"),
+            ("edge", "EJ"),
+            ("nation", "NXN"),
+            ("witch", "WX"),
+            ("", ""),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(to_metaphone(word), expected, "metaphone({word})");
+        }
+    }
+}
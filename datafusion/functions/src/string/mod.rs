@@ -30,14 +30,18 @@ pub mod concat;
 pub mod concat_ws;
 pub mod contains;
 pub mod ends_with;
+pub mod jaro_winkler;
 pub mod levenshtein;
 pub mod lower;
 pub mod ltrim;
+pub mod metaphone;
 pub mod octet_length;
 pub mod overlay;
 pub mod repeat;
 pub mod replace;
 pub mod rtrim;
+pub mod similarity;
+pub mod soundex;
 pub mod split_part;
 pub mod starts_with;
 pub mod to_hex;
@@ -51,13 +55,17 @@ make_udf_function!(chr::ChrFunc, chr);
 make_udf_function!(concat::ConcatFunc, concat);
 make_udf_function!(concat_ws::ConcatWsFunc, concat_ws);
 make_udf_function!(ends_with::EndsWithFunc, ends_with);
+make_udf_function!(jaro_winkler::JaroWinklerFunc, jaro_winkler);
 make_udf_function!(levenshtein::LevenshteinFunc, levenshtein);
 make_udf_function!(ltrim::LtrimFunc, ltrim);
 make_udf_function!(lower::LowerFunc, lower);
+make_udf_function!(metaphone::MetaphoneFunc, metaphone);
 make_udf_function!(octet_length::OctetLengthFunc, octet_length);
 make_udf_function!(repeat::RepeatFunc, repeat);
 make_udf_function!(replace::ReplaceFunc, replace);
 make_udf_function!(rtrim::RtrimFunc, rtrim);
+make_udf_function!(similarity::SimilarityFunc, similarity);
+make_udf_function!(soundex::SoundexFunc, soundex);
 make_udf_function!(starts_with::StartsWithFunc, starts_with);
 make_udf_function!(split_part::SplitPartFunc, split_part);
 make_udf_function!(to_hex::ToHexFunc, to_hex);
@@ -91,6 +99,10 @@ pub mod expr_fn {
         ends_with,
         "Returns true if the `string` ends with the `suffix`, false otherwise.",
         string suffix
+    ),(
+        jaro_winkler,
+        "Returns the Jaro-Winkler similarity between the two given strings",
+        arg1 arg2
     ),(
         levenshtein,
         "Returns the Levenshtein distance between the two given strings",
@@ -103,6 +115,10 @@ pub mod expr_fn {
         ltrim,
         "Removes all characters, spaces by default, from the beginning of a string",
         args,
+    ),(
+        metaphone,
+        "Returns the Metaphone code of a string",
+        arg1
     ),(
         octet_length,
         "returns the number of bytes of a string",
@@ -119,6 +135,14 @@ pub mod expr_fn {
         rtrim,
         "Removes all characters, spaces by default, from the end of a string",
         args,
+    ),(
+        similarity,
+        "Returns the trigram similarity between the two given strings",
+        arg1 arg2
+    ),(
+        soundex,
+        "Returns the Soundex code of a string",
+        arg1
     ),(
         split_part,
         "Splits a string based on a delimiter and picks out the desired field based on the index.",
@@ -167,13 +191,17 @@ pub fn functions() -> Vec<Arc<ScalarUDF>> {
         concat(),
         concat_ws(),
         ends_with(),
+        jaro_winkler(),
         levenshtein(),
         lower(),
         ltrim(),
+        metaphone(),
         octet_length(),
         repeat(),
         replace(),
         rtrim(),
+        similarity(),
+        soundex(),
         split_part(),
         starts_with(),
         to_hex(),
@@ -0,0 +1,197 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+use std::cmp::Ordering;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+
+use crate::strings::compare_strings;
+use crate::utils::make_scalar_function;
+use datafusion_common::types::logical_string;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::Result;
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Returns the trigram similarity between the two given strings, from 0 (no trigram in common) to 1 (same trigrams). \
+    The trigrams of a string are the lowercased three character sequences of each of its words, where words are delimited by non-alphanumeric characters \
+    and are padded with two spaces at the start and one space at the end. \
+    The similarity is the number of trigrams shared by both strings divided by the number of distinct trigrams of the two strings.",
+    syntax_example = "similarity(str1, str2)",
+    sql_example = r#"```sql
+> select similarity('word', 'two words');
++--------------------------------------------+
+| similarity(Utf8("word"),Utf8("two words")) |
++--------------------------------------------+
+| 0.36363636363636365                        |
++--------------------------------------------+
+```"#,
+    argument(
+        name = "str1",
+        description = "String expression to compute the trigram similarity with str2."
+    ),
+    argument(
+        name = "str2",
+        description = "String expression to compute the trigram similarity with str1."
+    ),
+    related_udf(name = "jaro_winkler"),
+    related_udf(name = "levenshtein")
+)]
+#[derive(Debug)]
+pub struct SimilarityFunc {
+    signature: Signature,
+}
+
+impl Default for SimilarityFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimilarityFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![
+                    Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                    Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SimilarityFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "similarity"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(similarity, vec![])(&args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the trigram similarity between the two given strings.
+/// SIMILARITY('word', 'two words') = 0.36363636363636365
+fn similarity(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let [str1, str2] = take_function_args("similarity", args)?;
+    compare_strings(str1, str2, "similarity", trigram_similarity)
+}
+
+type Trigram = [char; 3];
+
+fn trigram_similarity(str1: &str, str2: &str) -> f64 {
+    let trigrams1 = trigrams(str1);
+    let trigrams2 = trigrams(str2);
+    if trigrams1.is_empty() || trigrams2.is_empty() {
+        return 0.0;
+    }
+
+    // Both sets are sorted, so they can be intersected with a merge
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < trigrams1.len() && j < trigrams2.len() {
+        match trigrams1[i].cmp(&trigrams2[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    let distinct = trigrams1.len() + trigrams2.len() - shared;
+    shared as f64 / distinct as f64
+}
+
+/// Returns the sorted, distinct trigrams of the words of `string`
+fn trigrams(string: &str) -> Vec<Trigram> {
+    let mut trigrams = vec![];
+    let mut word = vec![' ', ' '];
+    for c in string.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if word.len() > 2 {
+            word.push(' ');
+            trigrams.extend(word.windows(3).map(|w| [w[0], w[1], w[2]]));
+            word.truncate(2);
+        }
+    }
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigrams() {
+        let to_strings = |trigrams: Vec<Trigram>| {
+            trigrams
+                .into_iter()
+                .map(|t| t.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            to_strings(trigrams("Cat")),
+            vec!["  c", " ca", "at ", "cat"]
+        );
+        assert_eq!(
+            to_strings(trigrams("a-b a")),
+            vec!["  a", "  b", " a ", " b "]
+        );
+        assert!(trigrams("").is_empty());
+        assert!(trigrams(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_trigram_similarity() {
+        assert_eq!(trigram_similarity("word", "word"), 1.0);
+        assert_eq!(trigram_similarity("WORD", "word"), 1.0);
+        assert_eq!(trigram_similarity("word", "two words"), 4.0 / 11.0);
+        assert_eq!(trigram_similarity("abc", "xyz"), 0.0);
+        assert_eq!(trigram_similarity("", ""), 0.0);
+    }
+}
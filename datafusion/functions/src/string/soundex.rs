@@ -0,0 +1,185 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+
+use crate::strings::map_strings;
+use crate::utils::{make_scalar_function, utf8_to_str_type};
+use datafusion_common::types::logical_string;
+use datafusion_common::Result;
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+/// Length of a Soundex code
+const SOUNDEX_LENGTH: usize = 4;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Returns the [`Soundex`](https://en.wikipedia.org/wiki/Soundex) code of a string, \
+    which is the same for strings that are pronounced similarly in English. \
+    The code is the first letter of the string followed by three digits. \
+    Characters other than ASCII letters are ignored and an empty string is returned if there are none.",
+    syntax_example = "soundex(str)",
+    sql_example = r#"```sql
+> select soundex('Robert'), soundex('Rupert');
++-------------------------+-------------------------+
+| soundex(Utf8("Robert")) | soundex(Utf8("Rupert")) |
++-------------------------+-------------------------+
+| R163                    | R163                    |
++-------------------------+-------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    related_udf(name = "metaphone")
+)]
+#[derive(Debug)]
+pub struct SoundexFunc {
+    signature: Signature,
+}
+
+impl Default for SoundexFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoundexFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Native(
+                    logical_string(),
+                ))],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SoundexFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "soundex"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0] == DataType::Utf8View {
+            Ok(DataType::Utf8View)
+        } else {
+            utf8_to_str_type(&arg_types[0], "soundex")
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(soundex, vec![])(&args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the American Soundex code of the string.
+/// SOUNDEX('Tymczak') = 'T522'
+fn soundex(args: &[ArrayRef]) -> Result<ArrayRef> {
+    map_strings(&args[0], "soundex", soundex_string)
+}
+
+fn soundex_string(string: &str, code: &mut String) {
+    let mut letters = string
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return;
+    };
+    code.push(first as char);
+
+    let mut previous = soundex_digit(first);
+    for letter in letters {
+        let digit = soundex_digit(letter);
+        match digit {
+            // H and W do not separate letters with the same digit
+            None if matches!(letter, b'H' | b'W') => continue,
+            Some(digit) if previous != Some(digit) => {
+                code.push(digit);
+                if code.len() == SOUNDEX_LENGTH {
+                    return;
+                }
+            }
+            _ => {}
+        }
+        previous = digit;
+    }
+
+    while code.len() < SOUNDEX_LENGTH {
+        code.push('0');
+    }
+}
+
+/// Returns the Soundex digit of an uppercase ASCII letter, or `None` for
+/// vowels, H, W and Y
+fn soundex_digit(letter: u8) -> Option<char> {
+    match letter {
+        b'B' | b'F' | b'P' | b'V' => Some('1'),
+        b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => Some('2'),
+        b'D' | b'T' => Some('3'),
+        b'L' => Some('4'),
+        b'M' | b'N' => Some('5'),
+        b'R' => Some('6'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_soundex(string: &str) -> String {
+        let mut code = String::new();
+        soundex_string(string, &mut code);
+        code
+    }
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(to_soundex("Robert"), "R163");
+        assert_eq!(to_soundex("Rupert"), "R163");
+        assert_eq!(to_soundex("Rubin"), "R150");
+        assert_eq!(to_soundex("Ashcraft"), "A261");
+        assert_eq!(to_soundex("Ashcroft"), "A261");
+        assert_eq!(to_soundex("Tymczak"), "T522");
+        assert_eq!(to_soundex("Pfister"), "P236");
+        assert_eq!(to_soundex("Honeyman"), "H555");
+        assert_eq!(to_soundex("  o'hara"), "O600");
+        assert_eq!(to_soundex("A"), "A000");
+        assert_eq!(to_soundex(""), "");
+        assert_eq!(to_soundex("123"), "");
+    }
+}
@@ -16,14 +16,17 @@
 // under the License.
 
 use std::mem::size_of;
+use std::sync::Arc;
 
 use arrow::array::{
-    make_view, Array, ArrayAccessor, ArrayDataBuilder, ArrayIter, ByteView,
-    GenericStringArray, LargeStringArray, NullBufferBuilder, OffsetSizeTrait,
-    StringArray, StringViewArray, StringViewBuilder,
+    make_view, Array, ArrayAccessor, ArrayDataBuilder, ArrayIter, ArrayRef, AsArray,
+    ByteView, Float64Array, GenericStringArray, LargeStringArray, LargeStringBuilder,
+    NullBufferBuilder, OffsetSizeTrait, StringArray, StringBuilder, StringViewArray,
+    StringViewBuilder,
 };
 use arrow::buffer::{MutableBuffer, NullBuffer};
 use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result};
 
 /// Abstracts iteration over different types of string arrays.
 #[deprecated(since = "45.0.0", note = "Use arrow::array::StringArrayType instead")]
@@ -406,6 +409,114 @@ impl ColumnarValueRef<'_> {
     }
 }
 
+/// Applies `op` to every string of `array` and returns an array of the same
+/// string type, so that `Utf8View` input produces `Utf8View` output.
+///
+/// `op` appends the transformed string to the buffer it is given, which is
+/// reused across rows to avoid an allocation per value. Nulls are preserved.
+pub fn map_strings<F>(array: &ArrayRef, name: &str, op: F) -> Result<ArrayRef>
+where
+    F: FnMut(&str, &mut String),
+{
+    match array.data_type() {
+        DataType::Utf8 => {
+            let array = array.as_string::<i32>();
+            let mut builder =
+                StringBuilder::with_capacity(array.len(), array.value_data().len());
+            map_string_values(array, op, |value| builder.append_option(value));
+            Ok(Arc::new(builder.finish()))
+        }
+        DataType::LargeUtf8 => {
+            let array = array.as_string::<i64>();
+            let mut builder =
+                LargeStringBuilder::with_capacity(array.len(), array.value_data().len());
+            map_string_values(array, op, |value| builder.append_option(value));
+            Ok(Arc::new(builder.finish()))
+        }
+        DataType::Utf8View => {
+            let array = array.as_string_view();
+            let mut builder = StringViewBuilder::with_capacity(array.len());
+            map_string_values(array, op, |value| builder.append_option(value));
+            Ok(Arc::new(builder.finish()))
+        }
+        other => exec_err!("Unsupported data type {other:?} for function {name}"),
+    }
+}
+
+fn map_string_values<'a, V, F, A>(values: V, mut op: F, mut append: A)
+where
+    V: arrow::array::StringArrayType<'a>,
+    F: FnMut(&str, &mut String),
+    A: FnMut(Option<&str>),
+{
+    let mut buffer = String::new();
+    for value in values.iter() {
+        match value {
+            Some(value) => {
+                buffer.clear();
+                op(value, &mut buffer);
+                append(Some(&buffer));
+            }
+            None => append(None),
+        }
+    }
+}
+
+/// Applies `op` to every pair of strings of `left` and `right` and returns the
+/// results as a [`Float64Array`]. The arrays may be of different string types
+/// and are read without casting. The result is null if either string is null.
+pub fn compare_strings<F>(
+    left: &ArrayRef,
+    right: &ArrayRef,
+    name: &str,
+    op: F,
+) -> Result<ArrayRef>
+where
+    F: Fn(&str, &str) -> f64,
+{
+    match left.data_type() {
+        DataType::Utf8 => compare_strings_with(left.as_string::<i32>(), right, name, op),
+        DataType::LargeUtf8 => {
+            compare_strings_with(left.as_string::<i64>(), right, name, op)
+        }
+        DataType::Utf8View => {
+            compare_strings_with(left.as_string_view(), right, name, op)
+        }
+        other => exec_err!("Unsupported data type {other:?} for function {name}"),
+    }
+}
+
+fn compare_strings_with<'a, L, F>(
+    left: L,
+    right: &ArrayRef,
+    name: &str,
+    op: F,
+) -> Result<ArrayRef>
+where
+    L: arrow::array::StringArrayType<'a>,
+    F: Fn(&str, &str) -> f64,
+{
+    let result = match right.data_type() {
+        DataType::Utf8 => compare_string_values(left, right.as_string::<i32>(), op),
+        DataType::LargeUtf8 => compare_string_values(left, right.as_string::<i64>(), op),
+        DataType::Utf8View => compare_string_values(left, right.as_string_view(), op),
+        other => return exec_err!("Unsupported data type {other:?} for function {name}"),
+    };
+    Ok(Arc::new(result))
+}
+
+fn compare_string_values<'a, 'b, L, R, F>(left: L, right: R, op: F) -> Float64Array
+where
+    L: arrow::array::StringArrayType<'a>,
+    R: arrow::array::StringArrayType<'b>,
+    F: Fn(&str, &str) -> f64,
+{
+    left.iter()
+        .zip(right.iter())
+        .map(|(left, right)| Some(op(left?, right?)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,6 +26,7 @@ pub mod find_in_set;
 pub mod initcap;
 pub mod left;
 pub mod lpad;
+pub mod normalize;
 pub mod reverse;
 pub mod right;
 pub mod rpad;
@@ -33,6 +34,7 @@ pub mod strpos;
 pub mod substr;
 pub mod substrindex;
 pub mod translate;
+pub mod unaccent;
 
 // create UDFs
 make_udf_function!(character_length::CharacterLengthFunc, character_length);
@@ -40,6 +42,7 @@ make_udf_function!(find_in_set::FindInSetFunc, find_in_set);
 make_udf_function!(initcap::InitcapFunc, initcap);
 make_udf_function!(left::LeftFunc, left);
 make_udf_function!(lpad::LPadFunc, lpad);
+make_udf_function!(normalize::NormalizeFunc, normalize);
 make_udf_function!(right::RightFunc, right);
 make_udf_function!(reverse::ReverseFunc, reverse);
 make_udf_function!(rpad::RPadFunc, rpad);
@@ -48,6 +51,7 @@ make_udf_function!(substr::SubstrFunc, substr);
 make_udf_function!(substr::SubstrFunc, substring);
 make_udf_function!(substrindex::SubstrIndexFunc, substr_index);
 make_udf_function!(translate::TranslateFunc, translate);
+make_udf_function!(unaccent::UnaccentFunc, unaccent);
 
 pub mod expr_fn {
     use datafusion_expr::Expr;
@@ -104,6 +108,14 @@ pub mod expr_fn {
         find_in_set,
         "Returns a value in the range of 1 to N if the string `str` is in the string list `strlist` consisting of N substrings",
         string strlist
+    ),(
+        normalize,
+        "converts the `string` to a Unicode normalization form, NFC by default",
+        args,
+    ),(
+        unaccent,
+        "removes accents and other diacritical marks from the `string`",
+        string
     ));
 
     #[doc = "the number of characters in the `string`"]
@@ -135,6 +147,7 @@ pub fn functions() -> Vec<Arc<ScalarUDF>> {
         initcap(),
         left(),
         lpad(),
+        normalize(),
         reverse(),
         right(),
         rpad(),
@@ -142,5 +155,6 @@ pub fn functions() -> Vec<Arc<ScalarUDF>> {
         substr(),
         substr_index(),
         translate(),
+        unaccent(),
     ]
 }
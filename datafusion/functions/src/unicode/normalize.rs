@@ -0,0 +1,283 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+use std::str::FromStr;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+use unicode_normalization::{
+    is_nfc_quick, is_nfd_quick, is_nfkc_quick, is_nfkd_quick, IsNormalized,
+    UnicodeNormalization,
+};
+
+use crate::strings::map_strings;
+use crate::utils::{make_scalar_function, utf8_to_str_type};
+use datafusion_common::types::logical_string;
+use datafusion_common::{exec_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Converts a string to the specified [Unicode normalization form](https://unicode.org/reports/tr15/), \
+    so that strings made of equivalent characters compare equal.",
+    syntax_example = "normalize(str[, form])",
+    sql_example = r#"```sql
+> select normalize('ﬁ', 'NFKC'), length(normalize('Å', 'NFD'));
++-----------------------------------+----------------------------------------------------+
+| normalize(Utf8("ﬁ"),Utf8("NFKC")) | character_length(normalize(Utf8("Å"),Utf8("NFD"))) |
++-----------------------------------+----------------------------------------------------+
+| fi                                | 2                                                  |
++-----------------------------------+----------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "form",
+        description = "The normalization form, one of `NFC`, `NFD`, `NFKC` or `NFKD`. Must be a constant. _Default is `NFC`._"
+    ),
+    related_udf(name = "unaccent")
+)]
+#[derive(Debug)]
+pub struct NormalizeFunc {
+    signature: Signature,
+}
+
+impl Default for NormalizeFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NormalizeFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Coercible(vec![Coercion::new_exact(
+                        TypeSignatureClass::Native(logical_string()),
+                    )]),
+                    TypeSignature::Coercible(vec![
+                        Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                        Coercion::new_exact(TypeSignatureClass::Native(logical_string())),
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for NormalizeFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "normalize"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0] == DataType::Utf8View {
+            Ok(DataType::Utf8View)
+        } else {
+            utf8_to_str_type(&arg_types[0], "normalize")
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let form = match args.args.get(1) {
+            None => NormalizationForm::Nfc,
+            Some(ColumnarValue::Scalar(form)) => match form.try_as_str() {
+                Some(Some(form)) => form.parse()?,
+                Some(None) => {
+                    return Ok(ColumnarValue::Scalar(ScalarValue::try_from(
+                        args.return_type(),
+                    )?))
+                }
+                None => {
+                    return exec_err!(
+                        "Unsupported data type {:?} for the form of function normalize",
+                        form.data_type()
+                    )
+                }
+            },
+            Some(ColumnarValue::Array(_)) => {
+                return exec_err!("normalize requires the form to be a constant")
+            }
+        };
+
+        make_scalar_function(
+            move |args: &[ArrayRef]| {
+                map_strings(&args[0], "normalize", |string, normalized| {
+                    form.normalize(string, normalized)
+                })
+            },
+            vec![],
+        )(&args.args[..1])
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// A Unicode normalization form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NormalizationForm {
+    /// Canonical decomposition followed by canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility decomposition followed by canonical composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl FromStr for NormalizationForm {
+    type Err = DataFusionError;
+
+    fn from_str(form: &str) -> Result<Self> {
+        match form.to_ascii_uppercase().as_str() {
+            "NFC" => Ok(Self::Nfc),
+            "NFD" => Ok(Self::Nfd),
+            "NFKC" => Ok(Self::Nfkc),
+            "NFKD" => Ok(Self::Nfkd),
+            _ => exec_err!(
+                "Invalid normalization form '{form}' for function normalize, expected one of NFC, NFD, NFKC or NFKD"
+            ),
+        }
+    }
+}
+
+impl NormalizationForm {
+    /// Appends `string` converted to this normalization form to `normalized`
+    fn normalize(self, string: &str, normalized: &mut String) {
+        // ASCII strings, and strings the quick check proves to already be in
+        // this form, are copied as is
+        let is_normalized = string.is_ascii()
+            || match self {
+                Self::Nfc => is_nfc_quick(string.chars()),
+                Self::Nfd => is_nfd_quick(string.chars()),
+                Self::Nfkc => is_nfkc_quick(string.chars()),
+                Self::Nfkd => is_nfkd_quick(string.chars()),
+            } == IsNormalized::Yes;
+        if is_normalized {
+            normalized.push_str(string);
+            return;
+        }
+
+        match self {
+            Self::Nfc => normalized.extend(string.nfc()),
+            Self::Nfd => normalized.extend(string.nfd()),
+            Self::Nfkc => normalized.extend(string.nfkc()),
+            Self::Nfkd => normalized.extend(string.nfkd()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, StringArray, StringViewArray};
+    use arrow::datatypes::DataType::{Utf8, Utf8View};
+    use datafusion_common::{exec_err, Result, ScalarValue};
+    use datafusion_expr::{ColumnarValue, ScalarUDFImpl};
+
+    use crate::unicode::normalize::NormalizeFunc;
+    use crate::utils::test::test_function;
+
+    #[test]
+    fn test_functions() -> Result<()> {
+        test_function!(
+            NormalizeFunc::new(),
+            vec![ColumnarValue::Scalar(ScalarValue::from("A\u{30a}"))],
+            Ok(Some("\u{c5}")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        test_function!(
+            NormalizeFunc::new(),
+            vec![
+                ColumnarValue::Scalar(ScalarValue::from("\u{c5}")),
+                ColumnarValue::Scalar(ScalarValue::from("nfd")),
+            ],
+            Ok(Some("A\u{30a}")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        test_function!(
+            NormalizeFunc::new(),
+            vec![
+                ColumnarValue::Scalar(ScalarValue::Utf8View(Some(
+                    "\u{fb01}nance \u{2460}".to_string()
+                ))),
+                ColumnarValue::Scalar(ScalarValue::from("NFKC")),
+            ],
+            Ok(Some("finance 1")),
+            &str,
+            Utf8View,
+            StringViewArray
+        );
+        test_function!(
+            NormalizeFunc::new(),
+            vec![
+                ColumnarValue::Scalar(ScalarValue::Utf8View(Some(
+                    "\u{1e9b}\u{323}".to_string()
+                ))),
+                ColumnarValue::Scalar(ScalarValue::from("NFKD")),
+            ],
+            Ok(Some("s\u{323}\u{307}")),
+            &str,
+            Utf8View,
+            StringViewArray
+        );
+        test_function!(
+            NormalizeFunc::new(),
+            vec![
+                ColumnarValue::Scalar(ScalarValue::from("abc")),
+                ColumnarValue::Scalar(ScalarValue::Utf8(None)),
+            ],
+            Ok(None),
+            &str,
+            Utf8,
+            StringArray
+        );
+        test_function!(
+            NormalizeFunc::new(),
+            vec![
+                ColumnarValue::Scalar(ScalarValue::from("abc")),
+                ColumnarValue::Scalar(ScalarValue::from("NFX")),
+            ],
+            exec_err!("Invalid normalization form 'NFX' for function normalize, expected one of NFC, NFD, NFKC or NFKD"),
+            &str,
+            Utf8,
+            StringArray
+        );
+
+        Ok(())
+    }
+}
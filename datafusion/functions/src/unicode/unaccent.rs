@@ -0,0 +1,218 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+use crate::strings::map_strings;
+use crate::utils::{make_scalar_function, utf8_to_str_type};
+use datafusion_common::types::logical_string;
+use datafusion_common::Result;
+use datafusion_expr::{
+    Coercion, ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "String Functions"),
+    description = "Removes accents and other diacritical marks from a string. \
+    Letters that do not decompose into a base letter and marks, such as `ø` or `ß`, are replaced by their closest ASCII spelling.",
+    syntax_example = "unaccent(str)",
+    sql_example = r#"```sql
+> select unaccent('Crème Brûlée');
++--------------------------------+
+| unaccent(Utf8("Crème Brûlée")) |
++--------------------------------+
+| Creme Brulee                   |
++--------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    related_udf(name = "normalize")
+)]
+#[derive(Debug)]
+pub struct UnaccentFunc {
+    signature: Signature,
+}
+
+impl Default for UnaccentFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnaccentFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Native(
+                    logical_string(),
+                ))],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UnaccentFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "unaccent"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0] == DataType::Utf8View {
+            Ok(DataType::Utf8View)
+        } else {
+            utf8_to_str_type(&arg_types[0], "unaccent")
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        make_scalar_function(unaccent, vec![])(&args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Removes the diacritical marks of the string.
+/// UNACCENT('Ångström') = 'Angstrom'
+fn unaccent(args: &[ArrayRef]) -> Result<ArrayRef> {
+    map_strings(&args[0], "unaccent", unaccent_string)
+}
+
+fn unaccent_string(string: &str, unaccented: &mut String) {
+    if string.is_ascii() {
+        unaccented.push_str(string);
+        return;
+    }
+
+    for c in string.chars() {
+        if let Some(replacement) = ascii_spelling(c) {
+            unaccented.push_str(replacement);
+            continue;
+        }
+
+        // Keep the base characters of the canonical decomposition. Characters
+        // without marks in their decomposition, e.g. Hangul syllables, are
+        // kept as is.
+        let start = unaccented.len();
+        let mut has_marks = false;
+        decompose_canonical(c, |d| {
+            if is_combining_mark(d) {
+                has_marks = true;
+            } else {
+                unaccented.push(d);
+            }
+        });
+        if !has_marks {
+            unaccented.truncate(start);
+            unaccented.push(c);
+        }
+    }
+}
+
+/// Returns the ASCII spelling of letters that have no canonical decomposition
+fn ascii_spelling(c: char) -> Option<&'static str> {
+    let spelling = match c {
+        'Æ' => "AE",
+        'Ð' | 'Đ' => "D",
+        'Ħ' => "H",
+        'Ł' => "L",
+        'Ø' => "O",
+        'Œ' => "OE",
+        'Þ' => "TH",
+        'Ŧ' => "T",
+        'ß' => "ss",
+        'æ' => "ae",
+        'ð' | 'đ' => "d",
+        'ħ' => "h",
+        'ı' => "i",
+        'ł' => "l",
+        'ø' => "o",
+        'œ' => "oe",
+        'þ' => "th",
+        'ŧ' => "t",
+        _ => return None,
+    };
+    Some(spelling)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, LargeStringArray, StringArray, StringViewArray};
+    use arrow::datatypes::DataType::{LargeUtf8, Utf8, Utf8View};
+    use datafusion_common::{Result, ScalarValue};
+    use datafusion_expr::{ColumnarValue, ScalarUDFImpl};
+
+    use crate::unicode::unaccent::UnaccentFunc;
+    use crate::utils::test::test_function;
+
+    #[test]
+    fn test_functions() -> Result<()> {
+        test_function!(
+            UnaccentFunc::new(),
+            vec![ColumnarValue::Scalar(ScalarValue::from("Ångström"))],
+            Ok(Some("Angstrom")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        test_function!(
+            UnaccentFunc::new(),
+            vec![ColumnarValue::Scalar(ScalarValue::LargeUtf8(Some(
+                "Ærøskøbing Straße Łódź".to_string()
+            )))],
+            Ok(Some("AEroskobing Strasse Lodz")),
+            &str,
+            LargeUtf8,
+            LargeStringArray
+        );
+        test_function!(
+            UnaccentFunc::new(),
+            vec![ColumnarValue::Scalar(ScalarValue::Utf8View(Some(
+                "Nguye\u{302}\u{303}n Đức 한국어 ΆΈΉ".to_string()
+            )))],
+            Ok(Some("Nguyen Duc 한국어 ΑΕΗ")),
+            &str,
+            Utf8View,
+            StringViewArray
+        );
+        test_function!(
+            UnaccentFunc::new(),
+            vec![ColumnarValue::Scalar(ScalarValue::Utf8View(None))],
+            Ok(None),
+            &str,
+            Utf8View,
+            StringViewArray
+        );
+
+        Ok(())
+    }
+}
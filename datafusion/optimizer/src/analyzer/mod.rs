@@ -29,6 +29,7 @@ use datafusion_expr::expr_rewriter::FunctionRewrite;
 use datafusion_expr::{InvariantLevel, LogicalPlan};
 
 use crate::analyzer::resolve_grouping_function::ResolveGroupingFunction;
+use crate::analyzer::share_subplans::ShareSubplans;
 use crate::analyzer::type_coercion::TypeCoercion;
 use crate::utils::log_plan;
 
@@ -36,6 +37,7 @@ use self::function_rewrite::ApplyFunctionRewrites;
 
 pub mod function_rewrite;
 pub mod resolve_grouping_function;
+pub mod share_subplans;
 pub mod type_coercion;
 
 pub mod subquery {
@@ -96,6 +98,7 @@ impl Analyzer {
         let rules: Vec<Arc<dyn AnalyzerRule + Send + Sync>> = vec![
             Arc::new(ResolveGroupingFunction::new()),
             Arc::new(TypeCoercion::new()),
+            Arc::new(ShareSubplans::new()),
         ];
         Self::with_rules(rules)
     }
@@ -136,6 +139,9 @@ impl Analyzer {
         plan.check_invariants(InvariantLevel::Always)
             .map_err(|e| e.context("Invalid input plan passed to Analyzer"))?;
 
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("analyze").entered();
+
         let start_time = Instant::now();
         let mut new_plan = plan;
 
@@ -157,6 +163,10 @@ impl Analyzer {
 
         // TODO add common rule executor for Analyzer and Optimizer
         for rule in rules {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::info_span!("analyzer_rule", rule = rule.name()).entered();
+
             new_plan = rule
                 .analyze(new_plan, config)
                 .map_err(|e| e.context(rule.name()))?;
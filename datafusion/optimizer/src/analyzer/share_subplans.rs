@@ -0,0 +1,374 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ShareSubplans`] evaluates repeated subplans once, sharing their result
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::analyzer::AnalyzerRule;
+use crate::plan_signature::LogicalPlanSignature;
+
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNodeRecursion};
+use datafusion_common::Result;
+use datafusion_expr::logical_plan::{LogicalPlan, SharedSubplan};
+
+/// Finds subplans that occur several times in a plan and wraps them in
+/// [`SharedSubplan`]s, so that they are evaluated once for all their
+/// occurrences.
+///
+/// For example, the common table expression `t` below is inlined into both
+/// of its references, and is aggregated twice unless shared:
+///
+/// ```sql
+/// WITH t AS (SELECT a, sum(b) AS s FROM x GROUP BY a)
+/// SELECT * FROM t t1 JOIN t t2 ON t1.a = t2.a + 1
+/// ```
+///
+/// When `datafusion.optimizer.enable_subplan_reuse` is set, identical
+/// subplans, including those of subqueries, are shared if they contain at
+/// least `datafusion.optimizer.subplan_reuse_threshold` joins, aggregates,
+/// sorts and window functions. The largest repeated subplans are shared
+/// first. Subplans with volatile expressions or outer references are never
+/// shared, since their occurrences may compute different results.
+///
+/// Independently of that option, this rule also resolves the hints of
+/// common table expressions: the `SharedSubplan`s of `MATERIALIZED` ones
+/// are kept, and those of `NOT MATERIALIZED` ones are removed. Finally,
+/// the `SharedSubplan`s are numbered in the order they appear in the plan,
+/// one identifier per distinct shared input.
+#[derive(Default, Debug)]
+pub struct ShareSubplans {}
+
+impl ShareSubplans {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AnalyzerRule for ShareSubplans {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let options = &config.optimizer;
+        let mut detected_id = None;
+        let plan = if options.enable_subplan_reuse {
+            let id = next_shared_subplan_id(&plan)?;
+            let shared =
+                share_repeated_subplans(plan, id, options.subplan_reuse_threshold)?;
+            if shared.transformed {
+                detected_id = Some(id);
+            }
+            shared.data
+        } else {
+            plan
+        };
+        number_shared_subplans(plan, detected_id)
+    }
+
+    fn name(&self) -> &str {
+        "share_subplans"
+    }
+}
+
+/// Returns an identifier greater than those of the `SharedSubplan`s of `plan`
+fn next_shared_subplan_id(plan: &LogicalPlan) -> Result<usize> {
+    let mut next_id = 0;
+    plan.apply_with_subqueries(|node| {
+        if let LogicalPlan::SharedSubplan(shared) = node {
+            next_id = next_id.max(shared.id + 1);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(next_id)
+}
+
+/// Wraps the repeated subplans of `plan` in `SharedSubplan`s numbered `id`
+fn share_repeated_subplans(
+    plan: LogicalPlan,
+    id: usize,
+    threshold: usize,
+) -> Result<Transformed<LogicalPlan>> {
+    let mut occurrences = HashMap::<LogicalPlanSignature, usize>::new();
+    plan.apply_with_subqueries(|node| {
+        if is_shareable(node, threshold)? {
+            *occurrences
+                .entry(LogicalPlanSignature::new(node))
+                .or_default() += 1;
+        }
+        Ok(skip_inputs(node))
+    })?;
+    if occurrences.values().all(|count| *count < 2) {
+        return Ok(Transformed::no(plan));
+    }
+
+    plan.transform_down_with_subqueries(|node| {
+        if is_shareable(&node, threshold)?
+            && occurrences[&LogicalPlanSignature::new(&node)] > 1
+        {
+            let shared = SharedSubplan::new(id, Arc::new(node), true);
+            Ok(Transformed::new(
+                LogicalPlan::SharedSubplan(shared),
+                true,
+                TreeNodeRecursion::Jump,
+            ))
+        } else {
+            let recursion = skip_inputs(&node);
+            Ok(Transformed::new(node, false, recursion))
+        }
+    })
+}
+
+/// Returns whether the inputs of `node` are not searched for repeated
+/// subplans: those of `SharedSubplan`s, which are already shared or must not
+/// be, and the terms of recursive queries, whose results change with each
+/// iteration.
+fn skip_inputs(node: &LogicalPlan) -> TreeNodeRecursion {
+    match node {
+        LogicalPlan::SharedSubplan(_) | LogicalPlan::RecursiveQuery(_) => {
+            TreeNodeRecursion::Jump
+        }
+        _ => TreeNodeRecursion::Continue,
+    }
+}
+
+/// Returns whether the subplan `plan` may be shared between its occurrences,
+/// and is expensive enough for it
+fn is_shareable(plan: &LogicalPlan, threshold: usize) -> Result<bool> {
+    if matches!(
+        plan,
+        LogicalPlan::SharedSubplan(_)
+            | LogicalPlan::Subquery(_)
+            | LogicalPlan::Explain(_)
+            | LogicalPlan::Analyze(_)
+            | LogicalPlan::Dml(_)
+            | LogicalPlan::Ddl(_)
+            | LogicalPlan::Copy(_)
+            | LogicalPlan::Statement(_)
+            | LogicalPlan::DescribeTable(_)
+    ) {
+        return Ok(false);
+    }
+
+    let mut cost = 0;
+    let mut deterministic = true;
+    plan.apply_with_subqueries(|node| {
+        if matches!(
+            node,
+            LogicalPlan::Join(_)
+                | LogicalPlan::Aggregate(_)
+                | LogicalPlan::Sort(_)
+                | LogicalPlan::Window(_)
+        ) {
+            cost += 1;
+        }
+        node.apply_expressions(|expr| {
+            if expr.is_volatile() || expr.contains_outer() {
+                deterministic = false;
+                Ok(TreeNodeRecursion::Stop)
+            } else {
+                Ok(TreeNodeRecursion::Continue)
+            }
+        })
+    })?;
+    Ok(deterministic && cost >= threshold)
+}
+
+/// Removes the `SharedSubplan`s of `NOT MATERIALIZED` common table
+/// expressions, and those numbered `detected_id` that were not found more
+/// than once, and numbers the others by their distinct inputs
+fn number_shared_subplans(
+    plan: LogicalPlan,
+    detected_id: Option<usize>,
+) -> Result<LogicalPlan> {
+    let mut occurrences = HashMap::<(usize, Arc<LogicalPlan>), (usize, usize)>::new();
+    plan.apply_with_subqueries(|node| {
+        if let LogicalPlan::SharedSubplan(shared) = node {
+            if shared.materialized {
+                let next_id = occurrences.len();
+                occurrences
+                    .entry((shared.id, Arc::clone(&shared.input)))
+                    .or_insert((next_id, 0))
+                    .1 += 1;
+            }
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+
+    plan.transform_down_with_subqueries(|node| {
+        let LogicalPlan::SharedSubplan(shared) = node else {
+            return Ok(Transformed::no(node));
+        };
+        if !shared.materialized {
+            return Ok(Transformed::yes(Arc::unwrap_or_clone(shared.input)));
+        }
+        let (id, count) = occurrences[&(shared.id, Arc::clone(&shared.input))];
+        if count < 2 && detected_id == Some(shared.id) {
+            Ok(Transformed::yes(Arc::unwrap_or_clone(shared.input)))
+        } else {
+            Ok(Transformed::new_transformed(
+                LogicalPlan::SharedSubplan(SharedSubplan { id, ..shared }),
+                id != shared.id,
+            ))
+        }
+    })
+    .data()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::assert_analyzed_plan_with_config_eq_snapshot;
+    use crate::test::test_table_scan_with_name;
+
+    use datafusion_expr::{col, logical_plan::builder::LogicalPlanBuilder, JoinType};
+    use datafusion_functions_aggregate::expr_fn::sum;
+
+    macro_rules! assert_analyzed_plan_eq {
+        (
+            $enable_subplan_reuse: expr,
+            $plan: expr,
+            @ $expected: literal $(,)?
+        ) => {{
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_subplan_reuse = $enable_subplan_reuse;
+            let rule = Arc::new(ShareSubplans::new());
+            assert_analyzed_plan_with_config_eq_snapshot!(
+                options,
+                rule,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    /// `SELECT a, sum(b) AS s FROM t GROUP BY a`, aliased as `agg`
+    fn aggregate() -> Result<LogicalPlan> {
+        LogicalPlanBuilder::from(test_table_scan_with_name("t")?)
+            .aggregate(vec![col("a")], vec![sum(col("b")).alias("s")])?
+            .alias("agg")?
+            .build()
+    }
+
+    /// `SELECT * FROM t1 JOIN t2 ON t1.a = t2.a`
+    fn self_join(t1: LogicalPlan, t2: LogicalPlan) -> Result<LogicalPlan> {
+        LogicalPlanBuilder::from(t1)
+            .alias("t1")?
+            .join(
+                LogicalPlanBuilder::from(t2).alias("t2")?.build()?,
+                JoinType::Inner,
+                (vec!["t1.a"], vec!["t2.a"]),
+                None,
+            )?
+            .build()
+    }
+
+    #[test]
+    fn share_repeated_aggregate() -> Result<()> {
+        let plan = self_join(aggregate()?, aggregate()?)?;
+
+        assert_analyzed_plan_eq!(
+            true,
+            plan,
+            @r"
+        Inner Join: t1.a = t2.a
+          SubqueryAlias: t1
+            SharedSubplan: id=0
+              SubqueryAlias: agg
+                Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                  TableScan: t
+          SubqueryAlias: t2
+            SharedSubplan: id=0
+              SubqueryAlias: agg
+                Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                  TableScan: t
+        "
+        )
+    }
+
+    #[test]
+    fn share_disabled() -> Result<()> {
+        let plan = self_join(aggregate()?, aggregate()?)?;
+
+        assert_analyzed_plan_eq!(
+            false,
+            plan,
+            @r"
+        Inner Join: t1.a = t2.a
+          SubqueryAlias: t1
+            SubqueryAlias: agg
+              Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                TableScan: t
+          SubqueryAlias: t2
+            SubqueryAlias: agg
+              Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                TableScan: t
+        "
+        )
+    }
+
+    #[test]
+    fn share_below_threshold() -> Result<()> {
+        // the scans contain no joins, aggregates, sorts or windows
+        let plan = self_join(
+            test_table_scan_with_name("t")?,
+            test_table_scan_with_name("t")?,
+        )?;
+
+        assert_analyzed_plan_eq!(
+            true,
+            plan,
+            @r"
+        Inner Join: t1.a = t2.a
+          SubqueryAlias: t1
+            TableScan: t
+          SubqueryAlias: t2
+            TableScan: t
+        "
+        )
+    }
+
+    #[test]
+    fn materialized_hints() -> Result<()> {
+        // `t1` is a `MATERIALIZED` reference, numbered 4 by the SQL planner,
+        // and `t2` a `NOT MATERIALIZED` one
+        let t1 = SharedSubplan::new(4, Arc::new(aggregate()?), true);
+        let t2 = SharedSubplan::new(5, Arc::new(aggregate()?), false);
+        let plan = self_join(
+            LogicalPlan::SharedSubplan(t1),
+            LogicalPlan::SharedSubplan(t2),
+        )?;
+
+        assert_analyzed_plan_eq!(
+            true,
+            plan,
+            @r"
+        Inner Join: t1.a = t2.a
+          SubqueryAlias: t1
+            SharedSubplan: id=0
+              SubqueryAlias: agg
+                Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                  TableScan: t
+          SubqueryAlias: t2
+            SubqueryAlias: agg
+              Aggregate: groupBy=[[t.a]], aggr=[[sum(t.b) AS s]]
+                TableScan: t
+        "
+        )
+    }
+}
@@ -0,0 +1,347 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CardinalityEstimator`] estimates the number of rows of logical plans
+
+use std::fmt::{self, Display, Formatter};
+
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{TreeNodeRecursion, TreeNodeVisitor};
+use datafusion_common::Column;
+use datafusion_expr::logical_plan::{FetchType, JoinType, LogicalPlan};
+use datafusion_expr::utils::split_conjunction;
+use datafusion_expr::Expr;
+
+/// Estimates the number of rows of [`LogicalPlan`]s from the [`Statistics`]
+/// of the scanned tables (see [`TableSource::statistics`]).
+///
+/// The estimates assume the predicates are independent: the selectivity of
+/// an equijoin predicate is `1 / max(distinct values of each side)`, and
+/// `datafusion.optimizer.default_filter_selectivity` is used for the other
+/// predicates.
+///
+/// [`Statistics`]: datafusion_common::Statistics
+/// [`TableSource::statistics`]: datafusion_expr::TableSource::statistics
+#[derive(Debug, Clone)]
+pub struct CardinalityEstimator {
+    /// The selectivity of predicates that can't be estimated
+    default_selectivity: f64,
+}
+
+impl CardinalityEstimator {
+    /// Create a new estimator using the selectivity configured in `options`
+    pub fn new(options: &ConfigOptions) -> Self {
+        Self {
+            default_selectivity: options.optimizer.default_filter_selectivity as f64
+                / 100.0,
+        }
+    }
+
+    /// Returns the selectivity of predicates that can't be estimated
+    pub fn default_selectivity(&self) -> f64 {
+        self.default_selectivity
+    }
+
+    /// Returns the estimated number of rows of a plan, if known
+    pub fn num_rows(&self, plan: &LogicalPlan) -> Option<f64> {
+        match plan {
+            LogicalPlan::TableScan(scan) => {
+                let statistics = scan.source.statistics()?;
+                let num_rows = *statistics.num_rows.get_value()? as f64
+                    * self.default_selectivity.powi(scan.filters.len() as i32);
+                Some(
+                    scan.fetch
+                        .map_or(num_rows, |fetch| num_rows.min(fetch as f64)),
+                )
+            }
+            LogicalPlan::Filter(filter) => {
+                let predicates = split_conjunction(&filter.predicate).len();
+                Some(
+                    self.num_rows(&filter.input)?
+                        * self.default_selectivity.powi(predicates as i32),
+                )
+            }
+            LogicalPlan::Join(join) if join.join_type == JoinType::Inner => {
+                let left_rows = self.num_rows(&join.left)?;
+                let right_rows = self.num_rows(&join.right)?;
+                let num_rows = join.on.iter().fold(
+                    left_rows * right_rows,
+                    |num_rows, (left, right)| {
+                        let left_distinct =
+                            self.expr_distinct_count(&join.left, left, left_rows);
+                        let right_distinct =
+                            self.expr_distinct_count(&join.right, right, right_rows);
+                        num_rows / left_distinct.max(right_distinct).max(1.0)
+                    },
+                );
+                let filters = join
+                    .filter
+                    .as_ref()
+                    .map_or(0, |filter| split_conjunction(filter).len());
+                Some(num_rows * self.default_selectivity.powi(filters as i32))
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                if aggregate.group_expr.is_empty() {
+                    return Some(1.0);
+                }
+                let input_rows = self.num_rows(&aggregate.input)?;
+                // Assume the grouping expressions are independent
+                let groups =
+                    aggregate.group_expr.iter().try_fold(1.0, |groups, expr| {
+                        let column = expr.try_as_col()?;
+                        Some(groups * self.distinct_count(&aggregate.input, column)?)
+                    })?;
+                Some(groups.min(input_rows))
+            }
+            LogicalPlan::Union(union) => {
+                union.inputs.iter().map(|input| self.num_rows(input)).sum()
+            }
+            LogicalPlan::Projection(projection) => self.num_rows(&projection.input),
+            LogicalPlan::SubqueryAlias(alias) => self.num_rows(&alias.input),
+            LogicalPlan::Sort(sort) => {
+                let num_rows = self.num_rows(&sort.input)?;
+                Some(
+                    sort.fetch
+                        .map_or(num_rows, |fetch| num_rows.min(fetch as f64)),
+                )
+            }
+            LogicalPlan::Limit(limit) => match limit.get_fetch_type().ok()? {
+                FetchType::Literal(Some(fetch)) => Some(
+                    self.num_rows(&limit.input)
+                        .map_or(fetch as f64, |num_rows| num_rows.min(fetch as f64)),
+                ),
+                _ => self.num_rows(&limit.input),
+            },
+            LogicalPlan::Values(values) => Some(values.values.len() as f64),
+            LogicalPlan::EmptyRelation(empty) => {
+                Some(if empty.produce_one_row { 1.0 } else { 0.0 })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number of distinct values of a column of a plan, if known
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn distinct_count(&self, plan: &LogicalPlan, column: &Column) -> Option<f64> {
+        match plan {
+            LogicalPlan::TableScan(scan) => {
+                let index = scan.projected_schema.maybe_index_of_column(column)?;
+                let index = match &scan.projection {
+                    Some(projection) => projection[index],
+                    None => index,
+                };
+                let statistics = scan.source.statistics()?;
+                let distinct_count = statistics
+                    .column_statistics
+                    .get(index)?
+                    .distinct_count
+                    .get_value()?;
+                Some(*distinct_count as f64)
+            }
+            LogicalPlan::Projection(projection) => {
+                let index = projection.schema.maybe_index_of_column(column)?;
+                let expr = match &projection.expr[index] {
+                    Expr::Alias(alias) => alias.expr.as_ref(),
+                    expr => expr,
+                };
+                self.distinct_count(&projection.input, expr.try_as_col()?)
+            }
+            LogicalPlan::SubqueryAlias(alias) => {
+                let index = alias.schema.maybe_index_of_column(column)?;
+                let input_column =
+                    Column::from(alias.input.schema().qualified_field(index));
+                self.distinct_count(&alias.input, &input_column)
+            }
+            LogicalPlan::Join(join) if join.join_type == JoinType::Inner => {
+                if join.left.schema().has_column(column) {
+                    self.distinct_count(&join.left, column)
+                } else {
+                    self.distinct_count(&join.right, column)
+                }
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                let index = aggregate.schema.maybe_index_of_column(column)?;
+                let expr = aggregate.group_expr.get(index)?;
+                self.distinct_count(&aggregate.input, expr.try_as_col()?)
+            }
+            LogicalPlan::Filter(filter) => self.distinct_count(&filter.input, column),
+            LogicalPlan::Sort(sort) => self.distinct_count(&sort.input, column),
+            LogicalPlan::Limit(limit) => self.distinct_count(&limit.input, column),
+            _ => None,
+        }
+    }
+
+    /// Returns the estimated number of distinct values of an expression of a
+    /// plan with `num_rows` rows, which is at most `num_rows`
+    pub fn expr_distinct_count(
+        &self,
+        plan: &LogicalPlan,
+        expr: &Expr,
+        num_rows: f64,
+    ) -> f64 {
+        expr.try_as_col()
+            .and_then(|column| self.distinct_count(plan, column))
+            .map_or(num_rows, |distinct_count| distinct_count.min(num_rows))
+    }
+
+    /// Return a `format`able structure that produces a single line per node,
+    /// like [`LogicalPlan::display_indent`], followed by the estimated number
+    /// of rows of the node if known. For example:
+    ///
+    /// ```text
+    /// Inner Join: t1.a = t2.a [estimated_rows=100]
+    ///   TableScan: t1 projection=[a] [estimated_rows=100]
+    ///   TableScan: t2 projection=[a] [estimated_rows=1000]
+    /// ```
+    pub fn display_indent<'a>(&'a self, plan: &'a LogicalPlan) -> impl Display + 'a {
+        struct Wrapper<'a>(&'a CardinalityEstimator, &'a LogicalPlan);
+        impl Display for Wrapper<'_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                let mut visitor = EstimatedRowsVisitor {
+                    f,
+                    estimator: self.0,
+                    indent: 0,
+                };
+                match self.1.visit_with_subqueries(&mut visitor) {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(fmt::Error),
+                }
+            }
+        }
+        Wrapper(self, plan)
+    }
+}
+
+/// Formats a plan like `IndentVisitor`, with the estimated number of rows
+struct EstimatedRowsVisitor<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    estimator: &'a CardinalityEstimator,
+    /// The current indent
+    indent: usize,
+}
+
+impl<'n> TreeNodeVisitor<'n> for EstimatedRowsVisitor<'_, '_> {
+    type Node = LogicalPlan;
+
+    fn f_down(
+        &mut self,
+        plan: &'n LogicalPlan,
+    ) -> datafusion_common::Result<TreeNodeRecursion> {
+        if self.indent > 0 {
+            writeln!(self.f)?;
+        }
+        write!(self.f, "{:indent$}", "", indent = self.indent * 2)?;
+        write!(self.f, "{}", plan.display())?;
+        if let Some(num_rows) = self.estimator.num_rows(plan) {
+            write!(self.f, " [estimated_rows={}]", num_rows.ceil() as u64)?;
+        }
+
+        self.indent += 1;
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn f_up(
+        &mut self,
+        _plan: &'n LogicalPlan,
+    ) -> datafusion_common::Result<TreeNodeRecursion> {
+        self.indent -= 1;
+        Ok(TreeNodeRecursion::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::any::Any;
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::stats::Precision;
+    use datafusion_common::{ColumnStatistics, Result, Statistics};
+    use datafusion_expr::{col, lit, LogicalPlanBuilder, TableSource};
+    use insta::assert_snapshot;
+
+    struct StatisticsTable {
+        schema: SchemaRef,
+        statistics: Statistics,
+    }
+
+    impl TableSource for StatisticsTable {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            Arc::clone(&self.schema)
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.statistics.clone())
+        }
+    }
+
+    /// Scans a table with `num_rows` rows whose column `a` has
+    /// `distinct_count` distinct values
+    fn scan(name: &str, num_rows: usize, distinct_count: usize) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+        let statistics = Statistics {
+            num_rows: Precision::Exact(num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics: vec![
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(distinct_count)),
+                ColumnStatistics::new_unknown(),
+            ],
+        };
+        let source = Arc::new(StatisticsTable { schema, statistics });
+        LogicalPlanBuilder::scan(name, source, None)?.build()
+    }
+
+    #[test]
+    fn display_estimated_rows() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(scan("t1", 100, 10)?)
+            .join(
+                LogicalPlanBuilder::from(scan("t2", 1000, 50)?)
+                    .filter(col("t2.b").gt(lit(5i64)))?
+                    .alias("s")?
+                    .build()?,
+                JoinType::Inner,
+                (vec!["t1.a"], vec!["s.a"]),
+                None,
+            )?
+            .limit(0, Some(10))?
+            .build()?;
+
+        let estimator = CardinalityEstimator::new(&ConfigOptions::default());
+        assert_snapshot!(
+            estimator.display_indent(&plan),
+            @r"
+        Limit: skip=0, fetch=10 [estimated_rows=10]
+          Inner Join: t1.a = s.a [estimated_rows=400]
+            TableScan: t1 [estimated_rows=100]
+            SubqueryAlias: s [estimated_rows=200]
+              Filter: t2.b > Int64(5) [estimated_rows=200]
+                TableScan: t2 [estimated_rows=1000]
+        "
+        );
+        Ok(())
+    }
+}
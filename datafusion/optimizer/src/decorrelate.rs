@@ -26,7 +26,9 @@ use crate::simplify_expressions::ExprSimplifier;
 use datafusion_common::tree_node::{
     Transformed, TransformedResult, TreeNode, TreeNodeRecursion, TreeNodeRewriter,
 };
-use datafusion_common::{plan_err, Column, DFSchemaRef, HashMap, Result, ScalarValue};
+use datafusion_common::{
+    plan_err, Column, DFSchema, DFSchemaRef, HashMap, Result, ScalarValue,
+};
 use datafusion_expr::expr::Alias;
 use datafusion_expr::simplify::SimplifyContext;
 use datafusion_expr::utils::{
@@ -418,6 +420,96 @@ impl PullUpCorrelatedExpr {
     }
 }
 
+/// The columns of the enclosing query that are referenced by a subquery
+///
+/// The outer references of the subqueries nested within the subquery are
+/// resolved against the scopes in between, so that only the columns that
+/// are provided by the enclosing query are collected.
+#[derive(Debug, Default)]
+pub struct OuterReferences {
+    /// The columns referenced by the plan of the subquery
+    pub direct: BTreeSet<Column>,
+    /// The columns referenced by the subqueries nested within the subquery
+    pub nested: BTreeSet<Column>,
+}
+
+impl OuterReferences {
+    /// Collects the outer references of the subquery `plan`
+    pub fn try_new(plan: &LogicalPlan) -> Result<Self> {
+        let mut references = Self::default();
+        references.collect(plan, &[], false)?;
+        Ok(references)
+    }
+
+    /// Returns true if the subquery does not reference the enclosing query
+    pub fn is_empty(&self) -> bool {
+        self.direct.is_empty() && self.nested.is_empty()
+    }
+
+    /// Returns all the referenced columns
+    pub fn columns(&self) -> BTreeSet<Column> {
+        self.direct.union(&self.nested).cloned().collect()
+    }
+
+    /// Returns true if the correlated expressions of the subquery can be
+    /// pulled up into a join with an input of schema `outer`
+    ///
+    /// The correlated expressions of the nested subqueries can not be pulled
+    /// up, as they are only evaluated when the nested subqueries are
+    /// decorrelated.
+    pub fn can_pull_up_into(&self, outer: &DFSchema) -> bool {
+        self.nested.is_empty()
+            && self.direct.iter().all(|column| outer.has_column(column))
+    }
+
+    fn collect(
+        &mut self,
+        plan: &LogicalPlan,
+        scopes: &[DFSchemaRef],
+        nested: bool,
+    ) -> Result<()> {
+        // The subqueries of the plan are resolved against its inputs first
+        let input_scopes = plan
+            .inputs()
+            .into_iter()
+            .map(|input| Arc::clone(input.schema()))
+            .chain(scopes.iter().cloned())
+            .collect::<Vec<_>>();
+        plan.apply_expressions(|expr| {
+            expr.apply(|expr| {
+                match expr {
+                    Expr::OuterReferenceColumn(_, column)
+                        if !scopes.iter().any(|scope| scope.has_column(column)) =>
+                    {
+                        if nested {
+                            self.nested.insert(column.clone());
+                        } else {
+                            self.direct.insert(column.clone());
+                        }
+                    }
+                    Expr::ScalarSubquery(subquery)
+                    | Expr::Exists(expr::Exists { subquery, .. })
+                    | Expr::InSubquery(expr::InSubquery { subquery, .. }) => {
+                        self.collect(&subquery.subquery, &input_scopes, true)?;
+                    }
+                    _ => {}
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })
+        })?;
+        for input in plan.inputs() {
+            match input {
+                // The outer references of lateral subqueries refer to their siblings
+                LogicalPlan::Subquery(subquery) => {
+                    self.collect(&subquery.subquery, &input_scopes, true)?
+                }
+                input => self.collect(input, scopes, nested)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 fn can_pullup_over_aggregation(expr: &Expr) -> bool {
     if let Expr::BinaryExpr(BinaryExpr {
         left,
@@ -0,0 +1,1317 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DecorrelateGeneralSubquery`] unnests arbitrary correlated subqueries
+
+use std::sync::Arc;
+
+use crate::decorrelate::{OuterReferences, UN_MATCHED_ROW_INDICATOR};
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{
+    Column, DFSchema, DFSchemaRef, NullEquality, Result, TableReference,
+};
+use datafusion_expr::expr::{Exists, InSubquery, WindowFunction};
+use datafusion_expr::expr_rewriter::NamePreserver;
+use datafusion_expr::logical_plan::{
+    Aggregate, Distinct, FetchType, Filter, Join, JoinConstraint, JoinType, Limit,
+    Projection, SkipType, Sort, Subquery, SubqueryAlias, Union, Window,
+};
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{
+    binary_expr, lit, when, Expr, ExprFunctionExt, LogicalPlan, LogicalPlanBuilder,
+    Operator, SortExpr,
+};
+
+/// The name of the row number used to evaluate correlated limits
+const ROW_NUMBER: &str = "__row_number";
+
+/// Optimizer rule for unnesting the correlated subqueries that can not be
+/// decorrelated by pulling up their correlated predicates
+///
+/// This implements the dependent join elimination of [Unnesting Arbitrary
+/// Queries]: the distinct values of the outer columns referenced by the
+/// subquery (its *domain*) are pushed down into the subquery, replacing the
+/// outer references with the columns of the domain. The subquery is then
+/// evaluated once for every value of the domain, and joined back to the outer
+/// query on the domain columns.
+///
+/// For example, given a query like:
+///
+/// ```text
+/// select t1.a, (select count(*) from t2 where t2.b < t1.b) from t1
+/// ```
+///
+/// The optimized plan will be:
+///
+/// ```text
+/// Projection: t1.a, __scalar_sq_1.count(*) AS count(*)
+///   Left Join: t1.b = __scalar_sq_1.b
+///     TableScan: t1
+///     SubqueryAlias: __scalar_sq_1
+///       Projection: CASE WHEN __aggregate_3.__always_true IS NULL THEN Int64(0) ELSE __aggregate_3.count(*) END AS count(*), __domain_2.b
+///         Left Join: __domain_2.b = __aggregate_3.b
+///           SubqueryAlias: __domain_2
+///             Distinct:
+///               Projection: t1.b AS b
+///                 TableScan: t1
+///           SubqueryAlias: __aggregate_3
+///             Aggregate: groupBy=[[__domain_2.b, Boolean(true) AS __always_true]], aggr=[[count(Int64(1)) AS count(*)]]
+///               Filter: t2.b < __domain_2.b
+///                 Cross Join:
+///                   TableScan: t2
+///                   SubqueryAlias: __domain_2
+///                     Distinct:
+///                       Projection: t1.b AS b
+///                         TableScan: t1
+/// ```
+///
+/// The joins on nullable domain columns compare nulls as equal, so that the
+/// rows of the outer query with null outer columns are joined with the
+/// results of the subquery for the null values.
+///
+/// [Unnesting Arbitrary Queries]: https://dl.gi.de/items/b9df4765-d1b0-4267-a77c-4ce4ab0ee62d
+#[derive(Default, Debug)]
+pub struct DecorrelateGeneralSubquery {}
+
+impl DecorrelateGeneralSubquery {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OptimizerRule for DecorrelateGeneralSubquery {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let unnested = match &plan {
+            LogicalPlan::Filter(filter) if has_subquery(&filter.predicate) => {
+                unnest_filter(filter, config)?
+            }
+            LogicalPlan::Projection(projection)
+                if projection.expr.iter().any(has_subquery) =>
+            {
+                unnest_projection(projection, config)?
+            }
+            LogicalPlan::Join(join) => unnest_lateral_join(join, config)?,
+            _ => None,
+        };
+        // The subqueries nested within the unnested subqueries are now
+        // correlated with the joined plans, and are unnested in turn
+        Ok(match unnested {
+            Some(plan) => Transformed::yes(plan),
+            None => Transformed::no(plan),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "decorrelate_general_subquery"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+/// Returns true if the expression has a subquery somewhere in it
+fn has_subquery(expr: &Expr) -> bool {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_)
+        ))
+    })
+    .expect("Inner is always Ok")
+}
+
+/// Unnests the subqueries of a filter, turning the `EXISTS`/`IN` conjuncts
+/// into semi/anti joins
+fn unnest_filter(
+    filter: &Filter,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let alias_generator = config.alias_generator();
+    let mut cur_input = filter.input.as_ref().clone();
+    let mut predicates = vec![];
+    for predicate in split_conjunction(&filter.predicate) {
+        let (subquery, in_expr, negated) = match predicate {
+            Expr::Exists(Exists { subquery, negated }) => (subquery, None, *negated),
+            Expr::InSubquery(InSubquery {
+                expr,
+                subquery,
+                negated,
+            }) if !has_subquery(expr) => (subquery, Some(expr.as_ref()), *negated),
+            predicate if has_subquery(predicate) => {
+                let Some((plan, predicate)) =
+                    unnest_subquery_exprs(cur_input, predicate.clone(), config)?
+                else {
+                    return Ok(None);
+                };
+                cur_input = plan;
+                predicates.push(predicate);
+                continue;
+            }
+            predicate => {
+                predicates.push(predicate.clone());
+                continue;
+            }
+        };
+        let join_type = if negated {
+            JoinType::LeftAnti
+        } else {
+            JoinType::LeftSemi
+        };
+        let alias = alias_generator.next("__correlated_sq");
+        let Some(plan) =
+            join_subquery(cur_input, subquery, join_type, in_expr, &alias, config)?
+        else {
+            return Ok(None);
+        };
+        cur_input = plan;
+    }
+
+    let mut builder = LogicalPlanBuilder::from(cur_input);
+    if let Some(predicate) = conjunction(predicates) {
+        builder = builder.filter(predicate)?;
+    }
+    // Remove the columns added by the joins
+    if builder.schema().fields().len() != filter.input.schema().fields().len() {
+        builder = builder.project(
+            filter
+                .input
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column),
+        )?;
+    }
+    builder.build().map(Some)
+}
+
+/// Unnests the subqueries of the expressions of a projection
+fn unnest_projection(
+    projection: &Projection,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let name_preserver = NamePreserver::new_for_projection();
+    let mut cur_input = projection.input.as_ref().clone();
+    let mut exprs = vec![];
+    for expr in &projection.expr {
+        if !has_subquery(expr) {
+            exprs.push(expr.clone());
+            continue;
+        }
+        let saved_name = name_preserver.save(expr);
+        let Some((plan, expr)) = unnest_subquery_exprs(cur_input, expr.clone(), config)?
+        else {
+            return Ok(None);
+        };
+        cur_input = plan;
+        exprs.push(saved_name.restore(expr));
+    }
+    Projection::try_new(exprs, Arc::new(cur_input))
+        .map(|projection| Some(LogicalPlan::Projection(projection)))
+}
+
+/// Replaces the subqueries of `expr` with the columns of joins to `input`
+fn unnest_subquery_exprs(
+    input: LogicalPlan,
+    expr: Expr,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<(LogicalPlan, Expr)>> {
+    let alias_generator = config.alias_generator();
+    let mut cur_input = Some(input);
+    let expr = expr
+        .transform_down(|expr| {
+            let (subquery, in_expr, alias, result) = match &expr {
+                Expr::ScalarSubquery(subquery) => {
+                    let alias = alias_generator.next("__scalar_sq");
+                    let name = subquery.subquery.schema().field(0).name();
+                    let result = Expr::Column(Column::new(Some(alias.clone()), name));
+                    (subquery, None, alias, result)
+                }
+                Expr::Exists(Exists { subquery, negated }) => {
+                    let alias = alias_generator.next("__correlated_sq");
+                    let mark = Expr::Column(Column::new(Some(alias.clone()), "mark"));
+                    let result = if *negated { !mark } else { mark };
+                    (subquery, None, alias, result)
+                }
+                Expr::InSubquery(InSubquery {
+                    expr: in_expr,
+                    subquery,
+                    negated,
+                }) if !has_subquery(in_expr) => {
+                    let alias = alias_generator.next("__correlated_sq");
+                    let mark = Expr::Column(Column::new(Some(alias.clone()), "mark"));
+                    let result = if *negated { !mark } else { mark };
+                    (subquery, Some(in_expr.as_ref()), alias, result)
+                }
+                _ => return Ok(Transformed::no(expr)),
+            };
+            let join_type = match expr {
+                Expr::ScalarSubquery(_) => JoinType::Left,
+                _ => JoinType::LeftMark,
+            };
+            let unnested = match cur_input.take() {
+                Some(input) => {
+                    join_subquery(input, subquery, join_type, in_expr, &alias, config)?
+                }
+                None => None,
+            };
+            match unnested {
+                Some(plan) => {
+                    cur_input = Some(plan);
+                    Ok(Transformed::new(result, true, TreeNodeRecursion::Jump))
+                }
+                None => Ok(Transformed::new(expr, false, TreeNodeRecursion::Stop)),
+            }
+        })
+        .data()?;
+    Ok(cur_input.map(|plan| (plan, expr)))
+}
+
+/// Unnests a lateral join with a correlated subquery
+fn unnest_lateral_join(
+    join: &Join,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let Some(subquery) = lateral_subquery(&join.right)? else {
+        return Ok(None);
+    };
+    if !matches!(join.join_type, JoinType::Inner | JoinType::Left) || !join.on.is_empty()
+    {
+        return Ok(None);
+    }
+    let references = OuterReferences::try_new(&subquery)?;
+    if references.is_empty() {
+        return Ok(None);
+    }
+    let Some(domain) = Domain::try_new(&join.left, references.columns(), config)? else {
+        return Ok(None);
+    };
+    let Some(unnested) = domain.unnest(&subquery)? else {
+        return Ok(None);
+    };
+    let on = domain.join_keys(None, &domain.alias);
+    let plan = LogicalPlan::Join(Join::try_new(
+        Arc::clone(&join.left),
+        Arc::new(unnested),
+        on,
+        join.filter.clone(),
+        join.join_type,
+        JoinConstraint::On,
+        domain.null_equality(),
+    )?);
+    // Remove the domain columns
+    LogicalPlanBuilder::from(plan)
+        .project(join.schema.columns().into_iter().map(Expr::Column))?
+        .build()
+        .map(Some)
+}
+
+/// Returns the plan of the subquery on the right side of a lateral join,
+/// keeping its alias
+fn lateral_subquery(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Subquery(subquery) => Ok(Some(subquery.subquery.as_ref().clone())),
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
+            let LogicalPlan::Subquery(subquery) = input.as_ref() else {
+                return Ok(None);
+            };
+            SubqueryAlias::try_new(Arc::clone(&subquery.subquery), alias.clone())
+                .map(|plan| Some(LogicalPlan::SubqueryAlias(plan)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Unnests `subquery` and joins it to `outer` on its domain, aliasing the
+/// unnested subquery as `alias`
+///
+/// `in_expr` is the expression compared with the output of an `IN` subquery.
+fn join_subquery(
+    outer: LogicalPlan,
+    subquery: &Subquery,
+    join_type: JoinType,
+    in_expr: Option<&Expr>,
+    alias: &str,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    // Only the existence of the rows of the subquery matters for the semi,
+    // anti and mark joins, not their order
+    let plan = match join_type {
+        JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
+            remove_sorts(&subquery.subquery)?
+        }
+        _ => subquery.subquery.as_ref().clone(),
+    };
+    let references = OuterReferences::try_new(&plan)?;
+    let Some(domain) = Domain::try_new(&outer, references.columns(), config)? else {
+        return Ok(None);
+    };
+    let Some(unnested) = domain.unnest(&plan)? else {
+        return Ok(None);
+    };
+    let subquery_alias = SubqueryAlias::try_new(Arc::new(unnested), alias)?;
+
+    let filter = in_expr.map(|in_expr| {
+        let name = subquery.subquery.schema().field(0).name();
+        in_expr
+            .clone()
+            .eq(Expr::Column(Column::new(Some(alias), name)))
+    });
+    let on = domain.join_keys(None, alias);
+    // Join the uncorrelated subqueries on a constant predicate, like
+    // `ScalarSubqueryToJoin` does
+    let filter = match filter {
+        None if on.is_empty() => Some(lit(true)),
+        filter => filter,
+    };
+    Join::try_new(
+        Arc::new(outer),
+        Arc::new(LogicalPlan::SubqueryAlias(subquery_alias)),
+        on,
+        filter,
+        join_type,
+        JoinConstraint::On,
+        domain.null_equality(),
+    )
+    .map(|join| Some(LogicalPlan::Join(join)))
+}
+
+/// Removes the sorts without a fetch from the top of `plan`, down to the
+/// first node that may depend on the order of its input
+fn remove_sorts(plan: &LogicalPlan) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::Sort(Sort {
+            input, fetch: None, ..
+        }) => remove_sorts(input),
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::SubqueryAlias(_) => plan
+            .clone()
+            .map_children(|input| remove_sorts(&input).map(Transformed::yes))
+            .data(),
+        _ => Ok(plan.clone()),
+    }
+}
+
+/// The distinct values of the outer columns referenced by a subquery
+struct Domain<'a> {
+    /// The distinct outer columns, with their domain column names
+    input: Arc<LogicalPlan>,
+    /// The alias of the domain
+    alias: String,
+    /// The outer columns, with the names of the corresponding domain columns
+    columns: Vec<(Column, String)>,
+    /// Whether any of the outer columns is nullable
+    nullable: bool,
+    config: &'a dyn OptimizerConfig,
+}
+
+impl<'a> Domain<'a> {
+    /// Creates the domain of the `columns` of `outer`, or returns `None`
+    /// if any of them is not provided by `outer`
+    fn try_new(
+        outer: &LogicalPlan,
+        columns: impl IntoIterator<Item = Column>,
+        config: &'a dyn OptimizerConfig,
+    ) -> Result<Option<Self>> {
+        let schema = outer.schema();
+        let mut domain_columns: Vec<(Column, String)> = vec![];
+        let mut nullable = false;
+        for column in columns {
+            if !schema.has_column(&column) {
+                return Ok(None);
+            }
+            nullable |= schema.qualified_field_from_column(&column)?.1.is_nullable();
+            let mut name = column.name.clone();
+            if domain_columns.iter().any(|(_, other)| *other == name) {
+                name = format!("{name}_{}", domain_columns.len());
+            }
+            domain_columns.push((column, name));
+        }
+        let input = if domain_columns.is_empty() {
+            // The domain is never used by the uncorrelated subqueries
+            outer.clone()
+        } else {
+            LogicalPlanBuilder::from(outer.clone())
+                .project(
+                    domain_columns
+                        .iter()
+                        .map(|(column, name)| Expr::Column(column.clone()).alias(name)),
+                )?
+                .distinct()?
+                .build()?
+        };
+        Ok(Some(Self {
+            input: Arc::new(input),
+            alias: config.alias_generator().next("__domain"),
+            columns: domain_columns,
+            nullable,
+            config,
+        }))
+    }
+
+    /// Returns the same domain, under another alias
+    fn realias(&self) -> Self {
+        Self {
+            input: Arc::clone(&self.input),
+            alias: self.config.alias_generator().next("__domain"),
+            columns: self.columns.clone(),
+            nullable: self.nullable,
+            config: self.config,
+        }
+    }
+
+    fn plan(&self) -> Result<LogicalPlan> {
+        SubqueryAlias::try_new(Arc::clone(&self.input), self.alias.as_str())
+            .map(LogicalPlan::SubqueryAlias)
+    }
+
+    fn null_equality(&self) -> NullEquality {
+        if self.nullable {
+            NullEquality::NullEqualsNull
+        } else {
+            NullEquality::NullEqualsNothing
+        }
+    }
+
+    /// Returns the domain columns
+    fn column_exprs(&self) -> Vec<Expr> {
+        self.columns
+            .iter()
+            .map(|(_, name)| Expr::Column(Column::new(Some(self.alias.clone()), name)))
+            .collect()
+    }
+
+    /// Returns the keys joining the domain columns of `left`, or the outer
+    /// columns if `None`, with the domain columns of `right`
+    fn join_keys(&self, left: Option<&str>, right: &str) -> Vec<(Expr, Expr)> {
+        self.columns
+            .iter()
+            .map(|(column, name)| {
+                let left = match left {
+                    Some(left) => Column::new(Some(left), name),
+                    None => column.clone(),
+                };
+                let right = Column::new(Some(right), name);
+                (Expr::Column(left), Expr::Column(right))
+            })
+            .collect()
+    }
+
+    /// Returns the domain column of the outer column `column`
+    fn domain_column(&self, column: &Column) -> Option<Column> {
+        self.columns
+            .iter()
+            .find(|(outer, _)| outer == column)
+            .map(|(_, name)| Column::new(Some(self.alias.clone()), name))
+    }
+
+    /// Returns a plan evaluating `plan` for every value of the domain, with
+    /// the domain columns added to its output, or `None` if the plan can not
+    /// be unnested
+    fn unnest(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        if self.columns.is_empty() {
+            return Ok(Some(plan.clone()));
+        }
+        if OuterReferences::try_new(plan)?.is_empty() {
+            return LogicalPlanBuilder::from(plan.clone())
+                .cross_join(self.plan()?)?
+                .build()
+                .map(Some);
+        }
+
+        let unnested = match plan {
+            LogicalPlan::Filter(filter) => {
+                let Some(input) = self.unnest(&filter.input)? else {
+                    return Ok(None);
+                };
+                let predicate = self.rewrite_expr(filter.predicate.clone(), &[&input])?;
+                LogicalPlan::Filter(Filter::try_new(predicate, Arc::new(input))?)
+            }
+            LogicalPlan::Projection(projection) => {
+                let Some(input) = self.unnest(&projection.input)? else {
+                    return Ok(None);
+                };
+                let exprs = self
+                    .rewrite_exprs(plan, projection.expr.clone(), &[&input])?
+                    .into_iter()
+                    .chain(self.column_exprs())
+                    .collect();
+                LogicalPlan::Projection(Projection::try_new(exprs, Arc::new(input))?)
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                if aggregate
+                    .group_expr
+                    .iter()
+                    .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+                {
+                    return Ok(None);
+                }
+                let Some(input) = self.unnest(&aggregate.input)? else {
+                    return Ok(None);
+                };
+                let aggr_expr =
+                    self.rewrite_exprs(plan, aggregate.aggr_expr.clone(), &[&input])?;
+                if aggregate.group_expr.is_empty() {
+                    return self.unnest_scalar_aggregate(plan.schema(), input, aggr_expr);
+                }
+                let group_expr = self
+                    .rewrite_exprs(plan, aggregate.group_expr.clone(), &[&input])?
+                    .into_iter()
+                    .chain(self.column_exprs())
+                    .collect();
+                LogicalPlan::Aggregate(Aggregate::try_new(
+                    Arc::new(input),
+                    group_expr,
+                    aggr_expr,
+                )?)
+            }
+            LogicalPlan::Window(window) => {
+                let Some(input) = self.unnest(&window.input)? else {
+                    return Ok(None);
+                };
+                // Evaluate the window functions for every value of the domain
+                let name_preserver = NamePreserver::new(plan);
+                let mut window_expr = vec![];
+                for expr in &window.window_expr {
+                    let saved_name = name_preserver.save(expr);
+                    let expr = self.rewrite_expr(expr.clone(), &[&input])?;
+                    let Some(expr) = self.partition_by_domain(expr)? else {
+                        return Ok(None);
+                    };
+                    window_expr.push(saved_name.restore(expr));
+                }
+                LogicalPlan::Window(Window::try_new(window_expr, Arc::new(input))?)
+            }
+            LogicalPlan::Sort(sort) => {
+                if sort.fetch.is_some() {
+                    return self.unnest_limit(&sort.input, &sort.expr, 0, sort.fetch);
+                }
+                let Some(input) = self.unnest(&sort.input)? else {
+                    return Ok(None);
+                };
+                LogicalPlan::Sort(Sort {
+                    expr: self.rewrite_sort_exprs(&sort.expr, &input)?,
+                    input: Arc::new(input),
+                    fetch: None,
+                })
+            }
+            LogicalPlan::Limit(limit) => return self.unnest_limit_plan(limit),
+            LogicalPlan::Distinct(Distinct::All(input)) => {
+                let Some(input) = self.unnest(input)? else {
+                    return Ok(None);
+                };
+                LogicalPlan::Distinct(Distinct::All(Arc::new(input)))
+            }
+            LogicalPlan::SubqueryAlias(subquery_alias) => {
+                let Some(input) = self.unnest(&subquery_alias.input)? else {
+                    return Ok(None);
+                };
+                let alias = &subquery_alias.alias;
+                let plan = SubqueryAlias::try_new(Arc::new(input), alias.clone())?;
+                // Restore the qualifier of the domain columns
+                let exprs = subquery_alias
+                    .schema
+                    .columns()
+                    .into_iter()
+                    .map(Expr::Column)
+                    .chain(self.columns.iter().map(|(_, name)| {
+                        Expr::Column(Column::new(Some(alias.clone()), name))
+                            .alias_qualified(Some(self.alias.as_str()), name)
+                    }))
+                    .collect();
+                LogicalPlan::Projection(Projection::try_new(
+                    exprs,
+                    Arc::new(LogicalPlan::SubqueryAlias(plan)),
+                )?)
+            }
+            LogicalPlan::Union(union) => {
+                let mut inputs = vec![];
+                for input in &union.inputs {
+                    let Some(unnested) = self.unnest(input)? else {
+                        return Ok(None);
+                    };
+                    // Align the columns of the inputs
+                    let exprs = input
+                        .schema()
+                        .columns()
+                        .into_iter()
+                        .map(Expr::Column)
+                        .chain(self.column_exprs())
+                        .collect();
+                    inputs.push(Arc::new(LogicalPlan::Projection(Projection::try_new(
+                        exprs,
+                        Arc::new(unnested),
+                    )?)));
+                }
+                let unioned = Union::try_new_with_loose_types(inputs)?;
+                let names = union
+                    .schema
+                    .iter()
+                    .map(|(qualifier, field)| (qualifier.cloned(), field.name().clone()))
+                    .chain(self.columns.iter().map(|(_, name)| {
+                        (Some(self.alias.as_str().into()), name.clone())
+                    }));
+                let exprs = unioned
+                    .schema
+                    .columns()
+                    .into_iter()
+                    .zip(names)
+                    .map(|(column, (qualifier, name))| {
+                        Expr::Column(column).alias_qualified(qualifier, name)
+                    })
+                    .collect();
+                LogicalPlan::Projection(Projection::try_new(
+                    exprs,
+                    Arc::new(LogicalPlan::Union(unioned)),
+                )?)
+            }
+            LogicalPlan::Join(join) => return self.unnest_join(join),
+            _ => return Ok(None),
+        };
+        Ok(Some(unnested))
+    }
+
+    /// Unnests a scalar aggregate, which produces a row for every value of
+    /// the domain, even when its input is empty
+    fn unnest_scalar_aggregate(
+        &self,
+        schema: &DFSchema,
+        input: LogicalPlan,
+        aggr_expr: Vec<Expr>,
+    ) -> Result<Option<LogicalPlan>> {
+        // The results of the aggregates on empty inputs, like 0 for `count`
+        let mut defaults = vec![];
+        for (expr, field) in aggr_expr.iter().zip(schema.fields()) {
+            let Expr::AggregateFunction(aggregate) = expr.clone().unalias_nested().data
+            else {
+                return Ok(None);
+            };
+            defaults.push(aggregate.func.default_value(field.data_type())?);
+        }
+        let mut group_expr = self.column_exprs();
+        if defaults.iter().any(|value| !value.is_null()) {
+            group_expr.push(lit(true).alias(UN_MATCHED_ROW_INDICATOR));
+        }
+        let num_group_expr = group_expr.len();
+        let aggregate = Aggregate::try_new(Arc::new(input), group_expr, aggr_expr)?;
+
+        let alias = self.config.alias_generator().next("__aggregate");
+        let aggregate = SubqueryAlias::try_new(
+            Arc::new(LogicalPlan::Aggregate(aggregate)),
+            alias.as_str(),
+        )?;
+        let aggregate_schema = Arc::clone(&aggregate.schema);
+        let join = Join::try_new(
+            Arc::new(self.plan()?),
+            Arc::new(LogicalPlan::SubqueryAlias(aggregate)),
+            self.join_keys(Some(&self.alias), &alias),
+            None,
+            JoinType::Left,
+            JoinConstraint::On,
+            self.null_equality(),
+        )?;
+
+        let unmatched =
+            Expr::Column(Column::new(Some(alias.as_str()), UN_MATCHED_ROW_INDICATOR))
+                .is_null();
+        let mut exprs = vec![];
+        for (i, ((qualifier, field), default)) in schema.iter().zip(defaults).enumerate()
+        {
+            let result = Expr::Column(Column::new(
+                Some(alias.as_str()),
+                aggregate_schema.field(num_group_expr + i).name(),
+            ));
+            let result = if default.is_null() {
+                result
+            } else {
+                when(unmatched.clone(), lit(default)).otherwise(result)?
+            };
+            exprs.push(result.alias_qualified(qualifier.cloned(), field.name()));
+        }
+        exprs.extend(self.column_exprs());
+        Projection::try_new(exprs, Arc::new(LogicalPlan::Join(join)))
+            .map(|projection| Some(LogicalPlan::Projection(projection)))
+    }
+
+    fn unnest_limit_plan(&self, limit: &Limit) -> Result<Option<LogicalPlan>> {
+        let (SkipType::Literal(skip), FetchType::Literal(fetch)) =
+            (limit.get_skip_type()?, limit.get_fetch_type()?)
+        else {
+            return Ok(None);
+        };
+        match limit.input.as_ref() {
+            LogicalPlan::Sort(sort) => {
+                let fetch = match (fetch, sort.fetch) {
+                    (Some(fetch), Some(sort_fetch)) => {
+                        Some(fetch.min(sort_fetch.saturating_sub(skip)))
+                    }
+                    (Some(fetch), None) => Some(fetch),
+                    (None, sort_fetch) => {
+                        sort_fetch.map(|sort_fetch| sort_fetch.saturating_sub(skip))
+                    }
+                };
+                self.unnest_limit(&sort.input, &sort.expr, skip, fetch)
+            }
+            input => self.unnest_limit(input, &[], skip, fetch),
+        }
+    }
+
+    /// Unnests a limit over the rows of `input` ordered by `sort_exprs`, by
+    /// numbering the rows of every value of the domain
+    fn unnest_limit(
+        &self,
+        input: &LogicalPlan,
+        sort_exprs: &[SortExpr],
+        skip: usize,
+        fetch: Option<usize>,
+    ) -> Result<Option<LogicalPlan>> {
+        let Some(row_number) = self
+            .config
+            .function_registry()
+            .and_then(|registry| registry.udwf("row_number").ok())
+        else {
+            return Ok(None);
+        };
+        let Some(input) = self.unnest(input)? else {
+            return Ok(None);
+        };
+        let columns = input.schema().columns();
+        let row_number = Expr::from(WindowFunction::new(row_number, vec![]))
+            .partition_by(self.column_exprs())
+            .order_by(self.rewrite_sort_exprs(sort_exprs, &input)?)
+            .build()?
+            .alias(ROW_NUMBER);
+
+        let row_number_col = Expr::Column(Column::new_unqualified(ROW_NUMBER));
+        let mut predicate = row_number_col.clone().gt(lit(skip as u64));
+        if let Some(fetch) = fetch {
+            predicate = predicate.and(row_number_col.lt_eq(lit((skip + fetch) as u64)));
+        }
+        LogicalPlanBuilder::from(input)
+            .window(vec![row_number])?
+            .filter(predicate)?
+            .project(columns.into_iter().map(Expr::Column))?
+            .build()
+            .map(Some)
+    }
+
+    fn unnest_join(&self, join: &Join) -> Result<Option<LogicalPlan>> {
+        let left_correlated = !OuterReferences::try_new(&join.left)?.is_empty();
+        let right_correlated = !OuterReferences::try_new(&join.right)?.is_empty();
+        // Whether the rows of the join are produced by the rows of the left
+        // (right) input, so that the domain can only be pushed down into it
+        let left_preserving = matches!(
+            join.join_type,
+            JoinType::Inner
+                | JoinType::Left
+                | JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+        );
+        let right_preserving = matches!(
+            join.join_type,
+            JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+        );
+
+        let mut other_alias = None;
+        let (left, right, keys) = if !right_correlated && left_preserving {
+            let Some(left) = self.unnest(&join.left)? else {
+                return Ok(None);
+            };
+            (left, join.right.as_ref().clone(), vec![])
+        } else if !left_correlated && right_preserving {
+            let Some(right) = self.unnest(&join.right)? else {
+                return Ok(None);
+            };
+            (join.left.as_ref().clone(), right, vec![])
+        } else if left_preserving || join.join_type == JoinType::Right {
+            // Push the domain down into both inputs, joining them on it
+            let other = self.realias();
+            let (left, right, keys) = if left_preserving {
+                let keys = self.join_keys(Some(&self.alias), &other.alias);
+                (self.unnest(&join.left)?, other.unnest(&join.right)?, keys)
+            } else {
+                let keys = other.join_keys(Some(&other.alias), &self.alias);
+                (other.unnest(&join.left)?, self.unnest(&join.right)?, keys)
+            };
+            let (Some(left), Some(right)) = (left, right) else {
+                return Ok(None);
+            };
+            other_alias = Some(TableReference::bare(other.alias));
+            (left, right, keys)
+        } else {
+            return Ok(None);
+        };
+
+        let inputs = [&left, &right];
+        let mut on = join
+            .on
+            .iter()
+            .map(|(l, r)| {
+                Ok((
+                    self.rewrite_expr(l.clone(), &inputs)?,
+                    self.rewrite_expr(r.clone(), &inputs)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut filter = join
+            .filter
+            .clone()
+            .map(|filter| self.rewrite_expr(filter, &inputs))
+            .transpose()?;
+        let mut null_equality = join.null_equality;
+        if on.is_empty() && !keys.is_empty() {
+            on = keys;
+            null_equality = self.null_equality();
+        } else if null_equality == self.null_equality() || !self.nullable {
+            on.extend(keys);
+        } else {
+            let keys = keys
+                .into_iter()
+                .map(|(l, r)| binary_expr(l, Operator::IsNotDistinctFrom, r));
+            filter = conjunction(filter.into_iter().chain(keys));
+        }
+
+        let plan = LogicalPlan::Join(Join::try_new(
+            Arc::new(left),
+            Arc::new(right),
+            on,
+            filter,
+            join.join_type,
+            join.join_constraint,
+            null_equality,
+        )?);
+        let Some(other_alias) = other_alias else {
+            return Ok(Some(plan));
+        };
+        // Remove the columns of the other domain
+        let columns = plan
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| column.relation.as_ref() != Some(&other_alias))
+            .map(Expr::Column);
+        LogicalPlanBuilder::from(plan)
+            .project(columns)?
+            .build()
+            .map(Some)
+    }
+
+    /// Adds the domain columns to the partitions of a window function
+    fn partition_by_domain(&self, expr: Expr) -> Result<Option<Expr>> {
+        match expr {
+            Expr::Alias(alias) => Ok(self
+                .partition_by_domain(*alias.expr)?
+                .map(|expr| expr.alias_qualified(alias.relation, alias.name))),
+            Expr::WindowFunction(mut window_function) => {
+                window_function
+                    .params
+                    .partition_by
+                    .extend(self.column_exprs());
+                Ok(Some(Expr::WindowFunction(window_function)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn rewrite_sort_exprs(
+        &self,
+        sort_exprs: &[SortExpr],
+        input: &LogicalPlan,
+    ) -> Result<Vec<SortExpr>> {
+        sort_exprs
+            .iter()
+            .map(|sort| {
+                let expr = self.rewrite_expr(sort.expr.clone(), &[input])?;
+                Ok(sort.with_expr(expr))
+            })
+            .collect()
+    }
+
+    /// Rewrites the output expressions of `plan`, preserving their names
+    fn rewrite_exprs(
+        &self,
+        plan: &LogicalPlan,
+        exprs: Vec<Expr>,
+        inputs: &[&LogicalPlan],
+    ) -> Result<Vec<Expr>> {
+        let name_preserver = NamePreserver::new(plan);
+        exprs
+            .into_iter()
+            .map(|expr| {
+                let saved_name = name_preserver.save(&expr);
+                Ok(saved_name.restore(self.rewrite_expr(expr, inputs)?))
+            })
+            .collect()
+    }
+
+    /// Replaces the outer references of an expression of the subquery with
+    /// the domain columns of the unnested `inputs`
+    fn rewrite_expr(&self, expr: Expr, inputs: &[&LogicalPlan]) -> Result<Expr> {
+        let input_scopes = inputs
+            .iter()
+            .map(|input| Arc::clone(input.schema()))
+            .collect::<Vec<_>>();
+        self.rewrite_outer_refs(expr, &[], &input_scopes, false)
+    }
+
+    /// Replaces the outer references of `expr` not resolved by `scopes`,
+    /// which are references to the domain columns when `nested` within
+    /// subqueries of the subquery
+    fn rewrite_outer_refs(
+        &self,
+        expr: Expr,
+        scopes: &[DFSchemaRef],
+        input_scopes: &[DFSchemaRef],
+        nested: bool,
+    ) -> Result<Expr> {
+        expr.transform_up(|expr| {
+            Ok(match expr {
+                Expr::OuterReferenceColumn(data_type, column)
+                    if !scopes.iter().any(|scope| scope.has_column(&column)) =>
+                {
+                    match self.domain_column(&column) {
+                        Some(domain) if nested => Transformed::yes(
+                            Expr::OuterReferenceColumn(data_type, domain),
+                        ),
+                        Some(domain) => Transformed::yes(Expr::Column(domain)),
+                        None => {
+                            Transformed::no(Expr::OuterReferenceColumn(data_type, column))
+                        }
+                    }
+                }
+                Expr::ScalarSubquery(subquery) => Transformed::yes(Expr::ScalarSubquery(
+                    self.rewrite_subquery(subquery, input_scopes)?,
+                )),
+                Expr::Exists(Exists { subquery, negated }) => {
+                    Transformed::yes(Expr::Exists(Exists {
+                        subquery: self.rewrite_subquery(subquery, input_scopes)?,
+                        negated,
+                    }))
+                }
+                Expr::InSubquery(InSubquery {
+                    expr,
+                    subquery,
+                    negated,
+                }) => Transformed::yes(Expr::InSubquery(InSubquery {
+                    expr,
+                    subquery: self.rewrite_subquery(subquery, input_scopes)?,
+                    negated,
+                })),
+                expr => Transformed::no(expr),
+            })
+        })
+        .data()
+    }
+
+    fn rewrite_subquery(
+        &self,
+        subquery: Subquery,
+        scopes: &[DFSchemaRef],
+    ) -> Result<Subquery> {
+        let plan =
+            self.rewrite_nested_plan(subquery.subquery.as_ref().clone(), scopes)?;
+        let outer_ref_columns = subquery
+            .outer_ref_columns
+            .into_iter()
+            .map(|expr| self.rewrite_outer_refs(expr, scopes, scopes, true))
+            .collect::<Result<_>>()?;
+        Ok(Subquery {
+            subquery: Arc::new(plan),
+            outer_ref_columns,
+            spans: subquery.spans,
+        })
+    }
+
+    /// Replaces the outer references to the domain of a subquery nested within
+    /// the subquery, whose outer references are first resolved by `scopes`
+    fn rewrite_nested_plan(
+        &self,
+        plan: LogicalPlan,
+        scopes: &[DFSchemaRef],
+    ) -> Result<LogicalPlan> {
+        let input_scopes = plan
+            .inputs()
+            .into_iter()
+            .map(|input| Arc::clone(input.schema()))
+            .chain(scopes.iter().cloned())
+            .collect::<Vec<_>>();
+        let plan = match plan {
+            // The outer references of lateral subqueries refer to their siblings
+            LogicalPlan::Subquery(subquery) => {
+                LogicalPlan::Subquery(self.rewrite_subquery(subquery, scopes)?)
+            }
+            plan => {
+                plan.map_children(|input| {
+                    let scopes = match input {
+                        LogicalPlan::Subquery(_) => input_scopes.as_slice(),
+                        _ => scopes,
+                    };
+                    self.rewrite_nested_plan(input, scopes)
+                        .map(Transformed::yes)
+                })?
+                .data
+            }
+        };
+        let name_preserver = NamePreserver::new(&plan);
+        plan.map_expressions(|expr| {
+            let saved_name = name_preserver.save(&expr);
+            let expr = self.rewrite_outer_refs(expr, scopes, &input_scopes, true)?;
+            Ok(Transformed::yes(saved_name.restore(expr)))
+        })?
+        .data
+        .recompute_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+
+    use crate::assert_optimized_plan_eq_display_indent_snapshot;
+    use arrow::datatypes::DataType;
+    use datafusion_expr::{col, exists, out_ref_col, scalar_subquery};
+    use datafusion_functions_aggregate::count::count_all;
+    use datafusion_functions_aggregate::min_max::max;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let rule: Arc<dyn crate::OptimizerRule + Send + Sync> = Arc::new(DecorrelateGeneralSubquery::new());
+            assert_optimized_plan_eq_display_indent_snapshot!(
+                rule,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    /// Test a scalar subquery correlated with a non-equality predicate
+    #[test]
+    fn non_equi_scalar_subquery() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .filter(col("t2.b").lt(out_ref_col(DataType::UInt32, "t1.b")))?
+                .aggregate(Vec::<Expr>::new(), vec![count_all()])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .project(vec![col("t1.a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: t1.a, __scalar_sq_1.count(*) AS count(*) [a:UInt32, count(*):Int64;N]
+          Left Join: t1.b = __scalar_sq_1.b [a:UInt32, b:UInt32, c:UInt32, count(*):Int64;N, b:UInt32;N]
+            TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __scalar_sq_1 [count(*):Int64;N, b:UInt32]
+              Projection: CASE WHEN __aggregate_3.__always_true IS NULL THEN Int64(0) ELSE __aggregate_3.count(*) END AS count(*), __domain_2.b [count(*):Int64;N, b:UInt32]
+                Left Join: __domain_2.b = __aggregate_3.b [b:UInt32, b:UInt32;N, __always_true:Boolean;N, count(*):Int64;N]
+                  SubqueryAlias: __domain_2 [b:UInt32]
+                    Distinct: [b:UInt32]
+                      Projection: t1.b AS b [b:UInt32]
+                        TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+                  SubqueryAlias: __aggregate_3 [b:UInt32, __always_true:Boolean, count(*):Int64]
+                    Aggregate: groupBy=[[__domain_2.b, Boolean(true) AS __always_true]], aggr=[[count(Int64(1)) AS count(*)]] [b:UInt32, __always_true:Boolean, count(*):Int64]
+                      Filter: t2.b < __domain_2.b [a:UInt32, b:UInt32, c:UInt32, b:UInt32]
+                        Cross Join:  [a:UInt32, b:UInt32, c:UInt32, b:UInt32]
+                          TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+                          SubqueryAlias: __domain_2 [b:UInt32]
+                            Distinct: [b:UInt32]
+                              Projection: t1.b AS b [b:UInt32]
+                                TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test that the correlated limits are not unnested without a
+    /// `row_number` function to evaluate them
+    #[test]
+    fn correlated_limit_without_row_number() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .filter(col("t2.a").eq(out_ref_col(DataType::UInt32, "t1.a")))?
+                .sort(vec![col("t2.b").sort(true, false)])?
+                .limit(0, Some(1))?
+                .project(vec![col("t2.c")])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(exists(subquery).and(col("t1.c").gt(lit(1u32))))?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Filter: EXISTS (<subquery>) AND t1.c > UInt32(1) [a:UInt32, b:UInt32, c:UInt32]
+          Subquery: [c:UInt32]
+            Projection: t2.c [c:UInt32]
+              Limit: skip=0, fetch=1 [a:UInt32, b:UInt32, c:UInt32]
+                Sort: t2.b ASC NULLS LAST [a:UInt32, b:UInt32, c:UInt32]
+                  Filter: t2.a = outer_ref(t1.a) [a:UInt32, b:UInt32, c:UInt32]
+                    TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+          TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test that the sorts without a fetch are removed from `EXISTS`
+    /// subqueries
+    #[test]
+    fn exists_subquery_with_sort() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .filter(col("t2.a").gt(out_ref_col(DataType::UInt32, "t1.a")))?
+                .project(vec![col("t2.c"), out_ref_col(DataType::UInt32, "t1.b")])?
+                .sort(vec![out_ref_col(DataType::UInt32, "t1.b").sort(true, false)])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(exists(subquery))?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        LeftSemi Join: t1.a = __correlated_sq_1.a, t1.b = __correlated_sq_1.b [a:UInt32, b:UInt32, c:UInt32]
+          TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+          SubqueryAlias: __correlated_sq_1 [c:UInt32, outer_ref(t1.b):UInt32, a:UInt32, b:UInt32]
+            Projection: t2.c, __domain_2.b AS outer_ref(t1.b), __domain_2.a, __domain_2.b [c:UInt32, outer_ref(t1.b):UInt32, a:UInt32, b:UInt32]
+              Filter: t2.a > __domain_2.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                Cross Join:  [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                  TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+                  SubqueryAlias: __domain_2 [a:UInt32, b:UInt32]
+                    Distinct: [a:UInt32, b:UInt32]
+                      Projection: t1.a AS a, t1.b AS b [a:UInt32, b:UInt32]
+                        TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test a subquery correlated with the query two levels up
+    #[test]
+    fn nested_correlated_subquery() -> Result<()> {
+        let inner = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t3")?)
+                .filter(
+                    col("t3.a")
+                        .eq(out_ref_col(DataType::UInt32, "t2.a"))
+                        .and(col("t3.b").lt(out_ref_col(DataType::UInt32, "t1.b"))),
+                )?
+                .project(vec![col("t3.c")])?
+                .build()?,
+        );
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .filter(
+                    col("t2.a")
+                        .eq(out_ref_col(DataType::UInt32, "t1.a"))
+                        .and(exists(inner)),
+                )?
+                .aggregate(Vec::<Expr>::new(), vec![max(col("t2.c"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .project(vec![col("t1.a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: t1.a, __scalar_sq_1.max(t2.c) AS max(t2.c) [a:UInt32, max(t2.c):UInt32;N]
+          Left Join: t1.a = __scalar_sq_1.a, t1.b = __scalar_sq_1.b [a:UInt32, b:UInt32, c:UInt32, max(t2.c):UInt32;N, a:UInt32;N, b:UInt32;N]
+            TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __scalar_sq_1 [max(t2.c):UInt32;N, a:UInt32, b:UInt32]
+              Projection: __aggregate_3.max(t2.c) AS max(t2.c), __domain_2.a, __domain_2.b [max(t2.c):UInt32;N, a:UInt32, b:UInt32]
+                Left Join: __domain_2.a = __aggregate_3.a, __domain_2.b = __aggregate_3.b [a:UInt32, b:UInt32, a:UInt32;N, b:UInt32;N, max(t2.c):UInt32;N]
+                  SubqueryAlias: __domain_2 [a:UInt32, b:UInt32]
+                    Distinct: [a:UInt32, b:UInt32]
+                      Projection: t1.a AS a, t1.b AS b [a:UInt32, b:UInt32]
+                        TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+                  SubqueryAlias: __aggregate_3 [a:UInt32, b:UInt32, max(t2.c):UInt32;N]
+                    Aggregate: groupBy=[[__domain_2.a, __domain_2.b]], aggr=[[max(t2.c)]] [a:UInt32, b:UInt32, max(t2.c):UInt32;N]
+                      Filter: t2.a = __domain_2.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                        LeftSemi Join: __domain_2.b = __correlated_sq_4.b, t2.a = __correlated_sq_4.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                          Cross Join:  [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                            TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+                            SubqueryAlias: __domain_2 [a:UInt32, b:UInt32]
+                              Distinct: [a:UInt32, b:UInt32]
+                                Projection: t1.a AS a, t1.b AS b [a:UInt32, b:UInt32]
+                                  TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+                          SubqueryAlias: __correlated_sq_4 [c:UInt32, b:UInt32, a:UInt32]
+                            Projection: t3.c, __domain_5.b, __domain_5.a [c:UInt32, b:UInt32, a:UInt32]
+                              Filter: t3.a = __domain_5.a AND t3.b < __domain_5.b [a:UInt32, b:UInt32, c:UInt32, b:UInt32, a:UInt32]
+                                Cross Join:  [a:UInt32, b:UInt32, c:UInt32, b:UInt32, a:UInt32]
+                                  TableScan: t3 [a:UInt32, b:UInt32, c:UInt32]
+                                  SubqueryAlias: __domain_5 [b:UInt32, a:UInt32]
+                                    Distinct: [b:UInt32, a:UInt32]
+                                      Projection: __domain_2.b AS b, t2.a AS a [b:UInt32, a:UInt32]
+                                        Cross Join:  [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32]
+                                          TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+                                          SubqueryAlias: __domain_2 [a:UInt32, b:UInt32]
+                                            Distinct: [a:UInt32, b:UInt32]
+                                              Projection: t1.a AS a, t1.b AS b [a:UInt32, b:UInt32]
+                                                TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test an uncorrelated subquery, which is joined on a constant predicate
+    #[test]
+    fn uncorrelated_scalar_subquery() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .aggregate(Vec::<Expr>::new(), vec![max(col("t2.c"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(col("t1.c").lt(scalar_subquery(subquery)))?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: t1.a, t1.b, t1.c [a:UInt32, b:UInt32, c:UInt32]
+          Filter: t1.c < __scalar_sq_1.max(t2.c) [a:UInt32, b:UInt32, c:UInt32, max(t2.c):UInt32;N]
+            Left Join:  Filter: Boolean(true) [a:UInt32, b:UInt32, c:UInt32, max(t2.c):UInt32;N]
+              TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+              SubqueryAlias: __scalar_sq_1 [max(t2.c):UInt32;N]
+                Aggregate: groupBy=[[]], aggr=[[max(t2.c)]] [max(t2.c):UInt32;N]
+                  TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+}
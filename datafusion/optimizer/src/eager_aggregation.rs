@@ -0,0 +1,815 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EagerAggregation`] pushes partial aggregates below joins and unions
+
+use std::sync::Arc;
+
+use crate::cardinality::CardinalityEstimator;
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use arrow::datatypes::DataType;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{Column, DFSchema, Result};
+use datafusion_expr::expr::{AggregateFunction, AggregateFunctionParams};
+use datafusion_expr::logical_plan::{
+    Aggregate, Join, JoinType, LogicalPlan, Projection, Union,
+};
+use datafusion_expr::registry::FunctionRegistry;
+use datafusion_expr::{cast, AggregateUDF, Expr, ExprSchemable};
+
+/// A partial aggregate is only pushed down if it is estimated to reduce the
+/// number of rows of its input by at least this factor
+const MAX_PARTIAL_GROUPS_RATIO: f64 = 0.5;
+
+/// Pushes partial aggregates below inner joins and into the inputs of
+/// unions, so that fewer rows are joined or unioned before being aggregated.
+///
+/// # Joins
+///
+/// When all the arguments of the aggregate functions come from one input of
+/// an inner equijoin, that input is aggregated first, grouped by its join
+/// keys and the grouping columns of the aggregate it contains. The aggregate
+/// above the join then combines the partial aggregates. For example:
+///
+/// ```text
+/// Aggregate: groupBy=[[d.region]], aggr=[[sum(f.amount)]]
+///   Inner Join: f.k = d.k
+///     TableScan: f
+///     TableScan: d
+/// ```
+///
+/// Is rewritten to:
+///
+/// ```text
+/// Projection: d.region, sum(__eager_agg_1) AS sum(f.amount)
+///   Aggregate: groupBy=[[d.region]], aggr=[[sum(__eager_agg_1)]]
+///     Inner Join: f.k = d.k
+///       Aggregate: groupBy=[[f.k]], aggr=[[sum(f.amount) AS __eager_agg_1]]
+///         TableScan: f
+///       TableScan: d
+/// ```
+///
+/// # Unions
+///
+/// Each input of a union is aggregated by the grouping columns of the
+/// aggregate above the union, which combines the partial aggregates.
+///
+/// # Restrictions
+///
+/// Only `sum`, `count`, `min`, `max` and `avg` (of `Float64` values, computed
+/// from a `sum` and a `count`) without `DISTINCT`, `FILTER` or `ORDER BY`
+/// are pushed down, for aggregates grouped by columns (a global aggregate
+/// would return `NULL` instead of `0` for the `count` of no rows).
+///
+/// The rewrite is guided by the [`Statistics`] of the scanned tables: the
+/// partial aggregates must be estimated to at least halve the number of rows
+/// of their inputs, which requires the number of rows and the distinct count
+/// of the grouping columns of the inputs to be known.
+///
+/// [`Statistics`]: datafusion_common::Statistics
+#[derive(Default, Debug)]
+pub struct EagerAggregation;
+
+impl EagerAggregation {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EagerAggregation {
+    fn name(&self) -> &str {
+        "eager_aggregation"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config.options().optimizer.enable_eager_aggregation {
+            return Ok(Transformed::no(plan));
+        }
+        let LogicalPlan::Aggregate(aggregate) = &plan else {
+            return Ok(Transformed::no(plan));
+        };
+        if !matches!(
+            aggregate.input.as_ref(),
+            LogicalPlan::Join(_) | LogicalPlan::Union(_)
+        ) {
+            return Ok(Transformed::no(plan));
+        }
+        let Some(registry) = config.function_registry() else {
+            return Ok(Transformed::no(plan));
+        };
+        if aggregate.group_expr.is_empty()
+            || !aggregate
+                .group_expr
+                .iter()
+                .all(|expr| matches!(expr, Expr::Column(_)))
+        {
+            return Ok(Transformed::no(plan));
+        }
+        let Some(split) = SplitAggregates::try_new(aggregate, registry, config)? else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let estimator = CardinalityEstimator::new(config.options());
+        let rewritten = match aggregate.input.as_ref() {
+            LogicalPlan::Join(join) => {
+                push_below_join(aggregate, join, &split, &estimator)?
+            }
+            LogicalPlan::Union(union) => {
+                push_into_union(aggregate, union, &split, &estimator)?
+            }
+            _ => None,
+        };
+        Ok(match rewritten {
+            Some(rewritten) => Transformed::yes(rewritten),
+            None => Transformed::no(plan),
+        })
+    }
+}
+
+/// The aggregate expressions of an [`Aggregate`] split into partial
+/// aggregates, final aggregates that combine them, and the expressions that
+/// compute the original aggregates from the final aggregates
+struct SplitAggregates {
+    /// The partial aggregates, aliased
+    partial: Vec<Expr>,
+    /// The final aggregates of the partial aggregates
+    final_aggregates: Vec<Expr>,
+    /// The values of the original aggregates
+    outputs: Vec<Expr>,
+}
+
+impl SplitAggregates {
+    /// Splits the aggregate expressions, or returns `None` if one of them
+    /// can't be computed from partial aggregates
+    fn try_new(
+        aggregate: &Aggregate,
+        registry: &dyn FunctionRegistry,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<Self>> {
+        let (Ok(sum), Ok(count)) = (registry.udaf("sum"), registry.udaf("count")) else {
+            return Ok(None);
+        };
+        let alias_generator = config.alias_generator();
+        // The partial aggregates are aliased, so that they can be referenced
+        // by the final aggregates
+        let partial_alias = |expr: Expr| {
+            let alias = alias_generator.next("__eager_agg");
+            (expr.alias(&alias), Expr::Column(Column::from_name(alias)))
+        };
+        let final_column =
+            |expr: &Expr| Expr::Column(Column::from_name(expr.schema_name().to_string()));
+
+        let mut split = Self {
+            partial: vec![],
+            final_aggregates: vec![],
+            outputs: vec![],
+        };
+        for (index, expr) in aggregate.aggr_expr.iter().enumerate() {
+            let Some((func, args)) = decomposable_aggregate(expr) else {
+                return Ok(None);
+            };
+            let output_type = aggregate
+                .schema
+                .field(aggregate.group_expr.len() + index)
+                .data_type();
+            match func.name() {
+                "sum" | "min" | "max" => {
+                    let (partial, partial_column) =
+                        partial_alias(call(Arc::clone(func), args.to_vec()));
+                    let final_aggregate = call(Arc::clone(func), vec![partial_column]);
+                    split.outputs.push(final_column(&final_aggregate));
+                    split.partial.push(partial);
+                    split.final_aggregates.push(final_aggregate);
+                }
+                "count" => {
+                    let (partial, partial_column) =
+                        partial_alias(call(Arc::clone(func), args.to_vec()));
+                    let final_aggregate = call(Arc::clone(&sum), vec![partial_column]);
+                    split.outputs.push(final_column(&final_aggregate));
+                    split.partial.push(partial);
+                    split.final_aggregates.push(final_aggregate);
+                }
+                "avg" if output_type == &DataType::Float64 => {
+                    let (partial_sum, partial_sum_column) =
+                        partial_alias(call(Arc::clone(&sum), args.to_vec()));
+                    let (partial_count, partial_count_column) =
+                        partial_alias(call(Arc::clone(&count), args.to_vec()));
+                    let final_sum = call(Arc::clone(&sum), vec![partial_sum_column]);
+                    let final_count = call(Arc::clone(&sum), vec![partial_count_column]);
+                    split.outputs.push(
+                        cast(final_column(&final_sum), DataType::Float64)
+                            / cast(final_column(&final_count), DataType::Float64),
+                    );
+                    split.partial.extend([partial_sum, partial_count]);
+                    split.final_aggregates.extend([final_sum, final_count]);
+                }
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(split))
+    }
+
+    /// Returns the columns referenced by the arguments of the partial
+    /// aggregates
+    fn column_refs(&self) -> Vec<&Column> {
+        self.partial
+            .iter()
+            .flat_map(|expr| expr.column_refs())
+            .collect()
+    }
+}
+
+/// Returns the function and arguments of an aggregate expression that can be
+/// computed from partial aggregates
+fn decomposable_aggregate(expr: &Expr) -> Option<(&Arc<AggregateUDF>, &[Expr])> {
+    let expr = match expr {
+        Expr::Alias(alias) => alias.expr.as_ref(),
+        expr => expr,
+    };
+    match expr {
+        Expr::AggregateFunction(AggregateFunction {
+            func,
+            params:
+                AggregateFunctionParams {
+                    args,
+                    distinct: false,
+                    filter: None,
+                    order_by,
+                    null_treatment: None,
+                },
+        }) if order_by.is_empty() => Some((func, args)),
+        _ => None,
+    }
+}
+
+/// Returns a call of an aggregate function
+fn call(func: Arc<AggregateUDF>, args: Vec<Expr>) -> Expr {
+    Expr::AggregateFunction(AggregateFunction::new_udf(
+        func,
+        args,
+        false,
+        None,
+        vec![],
+        None,
+    ))
+}
+
+/// Pushes the partial aggregates below an inner join into the input that
+/// contains all their arguments
+fn push_below_join(
+    aggregate: &Aggregate,
+    join: &Join,
+    split: &SplitAggregates,
+    estimator: &CardinalityEstimator,
+) -> Result<Option<LogicalPlan>> {
+    if join.join_type != JoinType::Inner
+        || join.filter.is_some()
+        || !join.on.iter().all(|(left, right)| {
+            left.try_as_col().is_some() && right.try_as_col().is_some()
+        })
+    {
+        return Ok(None);
+    }
+
+    // The input that contains the arguments of the aggregates, or the largest
+    // input if they have no arguments (e.g. `count(*)`)
+    let columns = split.column_refs();
+    let push_left = if columns.is_empty() {
+        match (
+            estimator.num_rows(&join.left),
+            estimator.num_rows(&join.right),
+        ) {
+            (Some(left_rows), Some(right_rows)) => left_rows >= right_rows,
+            _ => return Ok(None),
+        }
+    } else if columns
+        .iter()
+        .all(|column| join.left.schema().has_column(column))
+    {
+        true
+    } else if columns
+        .iter()
+        .all(|column| join.right.schema().has_column(column))
+    {
+        false
+    } else {
+        return Ok(None);
+    };
+    let input = if push_left { &join.left } else { &join.right };
+    // The input was already aggregated
+    if matches!(input.as_ref(), LogicalPlan::Aggregate(_)) {
+        return Ok(None);
+    }
+
+    // Group by the join keys and the grouping columns of the input
+    let mut group_expr: Vec<Expr> = vec![];
+    let keys = join
+        .on
+        .iter()
+        .map(|(left, right)| if push_left { left } else { right });
+    let grouping_columns = aggregate.group_expr.iter().filter(|expr| {
+        expr.column_refs()
+            .iter()
+            .all(|c| input.schema().has_column(c))
+    });
+    for expr in keys.chain(grouping_columns) {
+        if !group_expr.contains(expr) {
+            group_expr.push(expr.clone());
+        }
+    }
+
+    let Some(partial) = partial_aggregate(
+        Arc::clone(input),
+        group_expr,
+        split.partial.clone(),
+        estimator,
+    )?
+    else {
+        return Ok(None);
+    };
+    let (left, right) = if push_left {
+        (partial, Arc::clone(&join.right))
+    } else {
+        (Arc::clone(&join.left), partial)
+    };
+    let join = LogicalPlan::Join(Join::try_new(
+        left,
+        right,
+        join.on.clone(),
+        None,
+        join.join_type,
+        join.join_constraint,
+        join.null_equality,
+    )?);
+
+    final_aggregate(aggregate, join, aggregate.group_expr.clone(), split).map(Some)
+}
+
+/// Pushes the partial aggregates into the inputs of a union
+fn push_into_union(
+    aggregate: &Aggregate,
+    union: &Union,
+    split: &SplitAggregates,
+    estimator: &CardinalityEstimator,
+) -> Result<Option<LogicalPlan>> {
+    let Some(group_indices) = aggregate
+        .group_expr
+        .iter()
+        .map(|expr| {
+            expr.try_as_col()
+                .and_then(|column| union.schema.maybe_index_of_column(column))
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+
+    let mut inputs = Vec::with_capacity(union.inputs.len());
+    let (mut input_rows, mut partial_rows) = (0.0, 0.0);
+    for input in &union.inputs {
+        if matches!(input.as_ref(), LogicalPlan::Aggregate(_)) {
+            return Ok(None);
+        }
+        // Replace the columns of the union by the columns of the input
+        let input_schema = input.schema();
+        let to_input = |expr: Expr| {
+            expr.transform(|expr| match expr {
+                Expr::Column(column) => {
+                    let index = union.schema.index_of_column(&column)?;
+                    Ok(Transformed::yes(Expr::Column(Column::from(
+                        input_schema.qualified_field(index),
+                    ))))
+                }
+                _ => Ok(Transformed::no(expr)),
+            })
+            .data()
+        };
+        let group_expr = group_indices
+            .iter()
+            .map(|index| Expr::Column(Column::from(input_schema.qualified_field(*index))))
+            .collect();
+        let aggr_expr = split
+            .partial
+            .iter()
+            .cloned()
+            .map(to_input)
+            .collect::<Result<Vec<_>>>()?;
+        let partial = LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::clone(input),
+            group_expr,
+            aggr_expr,
+        )?);
+        let (Some(rows), Some(groups)) =
+            (estimator.num_rows(input), estimator.num_rows(&partial))
+        else {
+            return Ok(None);
+        };
+        input_rows += rows;
+        partial_rows += groups;
+        inputs.push(Arc::new(partial));
+    }
+    if partial_rows > input_rows * MAX_PARTIAL_GROUPS_RATIO {
+        return Ok(None);
+    }
+
+    let union = LogicalPlan::Union(Union::try_new_with_loose_types(inputs)?);
+    let group_expr = (0..group_indices.len())
+        .map(|index| Expr::Column(Column::from(union.schema().qualified_field(index))))
+        .collect();
+    final_aggregate(aggregate, union, group_expr, split).map(Some)
+}
+
+/// Returns the aggregate of the partial aggregates of an input, or `None` if
+/// it isn't estimated to reduce the number of rows of the input enough
+fn partial_aggregate(
+    input: Arc<LogicalPlan>,
+    group_expr: Vec<Expr>,
+    aggr_expr: Vec<Expr>,
+    estimator: &CardinalityEstimator,
+) -> Result<Option<Arc<LogicalPlan>>> {
+    let Some(input_rows) = estimator.num_rows(&input) else {
+        return Ok(None);
+    };
+    let partial =
+        LogicalPlan::Aggregate(Aggregate::try_new(input, group_expr, aggr_expr)?);
+    match estimator.num_rows(&partial) {
+        Some(rows) if rows <= input_rows * MAX_PARTIAL_GROUPS_RATIO => {
+            Ok(Some(Arc::new(partial)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns the aggregate of the partial aggregates of `input`, with a
+/// projection that restores the schema of the original `aggregate`
+fn final_aggregate(
+    aggregate: &Aggregate,
+    input: LogicalPlan,
+    group_expr: Vec<Expr>,
+    split: &SplitAggregates,
+) -> Result<LogicalPlan> {
+    let final_aggregate = LogicalPlan::Aggregate(Aggregate::try_new(
+        Arc::new(input),
+        group_expr,
+        split.final_aggregates.clone(),
+    )?);
+    let final_schema = final_aggregate.schema();
+    let exprs = (0..aggregate.group_expr.len())
+        .map(|index| Expr::Column(Column::from(final_schema.qualified_field(index))))
+        .chain(split.outputs.iter().cloned())
+        .enumerate()
+        .map(|(index, expr)| restore_field(expr, &aggregate.schema, index, final_schema))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(LogicalPlan::Projection(Projection::try_new(
+        exprs,
+        Arc::new(final_aggregate),
+    )?))
+}
+
+/// Casts and aliases `expr` to the type and name of the `index`th field of
+/// `schema`
+fn restore_field(
+    expr: Expr,
+    schema: &DFSchema,
+    index: usize,
+    input_schema: &DFSchema,
+) -> Result<Expr> {
+    let (qualifier, field) = schema.qualified_field(index);
+    let expr = if &expr.get_type(input_schema)? == field.data_type() {
+        expr
+    } else {
+        cast(expr, field.data_type().clone())
+    };
+    Ok(match &expr {
+        Expr::Column(column)
+            if column.relation.as_ref() == qualifier && &column.name == field.name() =>
+        {
+            expr
+        }
+        _ => expr.alias_qualified(qualifier.cloned(), field.name()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::datatypes::{Field, Schema, SchemaRef};
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::stats::Precision;
+    use datafusion_common::{ColumnStatistics, Statistics};
+    use datafusion_expr::registry::MemoryFunctionRegistry;
+    use datafusion_expr::{col, lit, LogicalPlanBuilder, TableSource};
+    use datafusion_functions_aggregate::average::{avg, avg_udaf};
+    use datafusion_functions_aggregate::count::{count, count_distinct, count_udaf};
+    use datafusion_functions_aggregate::min_max::{max, max_udaf, min, min_udaf};
+    use datafusion_functions_aggregate::sum::{sum, sum_udaf};
+    use insta::assert_snapshot;
+    use std::any::Any;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let starting_schema = Arc::clone($plan.schema());
+            let optimized_plan = optimize($plan)?;
+            assert!(starting_schema
+                .logically_equivalent_names_and_types(optimized_plan.schema()));
+            assert_snapshot!(
+                optimized_plan.display_indent(),
+                @ $expected,
+            );
+
+            Ok(())
+        }};
+    }
+
+    struct TestConfig {
+        alias_generator: Arc<AliasGenerator>,
+        options: ConfigOptions,
+        registry: MemoryFunctionRegistry,
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> DateTime<Utc> {
+            Utc::now()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            &self.alias_generator
+        }
+
+        fn options(&self) -> &ConfigOptions {
+            &self.options
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+    }
+
+    fn optimize(plan: LogicalPlan) -> Result<LogicalPlan> {
+        let mut options = ConfigOptions::default();
+        options.optimizer.enable_eager_aggregation = true;
+        let mut registry = MemoryFunctionRegistry::new();
+        for udaf in [sum_udaf(), count_udaf(), avg_udaf(), min_udaf(), max_udaf()] {
+            registry.register_udaf(udaf)?;
+        }
+        let config = TestConfig {
+            alias_generator: Arc::new(AliasGenerator::new()),
+            options,
+            registry,
+        };
+        plan.transform_down(|plan| EagerAggregation::new().rewrite(plan, &config))
+            .data()
+    }
+
+    /// A table with known statistics
+    struct StatisticsTable {
+        schema: SchemaRef,
+        statistics: Option<Statistics>,
+    }
+
+    impl TableSource for StatisticsTable {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            Arc::clone(&self.schema)
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            self.statistics.clone()
+        }
+    }
+
+    /// Scans a table with `num_rows` rows and two columns: a `k` with
+    /// `distinct_keys` distinct values and a `v` with unknown statistics
+    fn scan(name: &str, num_rows: usize, distinct_keys: usize) -> Result<LogicalPlan> {
+        scan_with_statistics(
+            name,
+            Some(Statistics {
+                num_rows: Precision::Exact(num_rows),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![
+                    ColumnStatistics::new_unknown()
+                        .with_distinct_count(Precision::Exact(distinct_keys)),
+                    ColumnStatistics::new_unknown(),
+                ],
+            }),
+        )
+    }
+
+    fn scan_with_statistics(
+        name: &str,
+        statistics: Option<Statistics>,
+    ) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("v", DataType::Float64, true),
+        ]));
+        let source = Arc::new(StatisticsTable { schema, statistics });
+        LogicalPlanBuilder::scan(name, source, None)?.build()
+    }
+
+    /// Joins `fact` and `dim` on `k`, then aggregates by `dim.v`
+    fn aggregate_join(
+        fact: LogicalPlan,
+        dim: LogicalPlan,
+        aggr_expr: Vec<Expr>,
+    ) -> Result<LogicalPlan> {
+        LogicalPlanBuilder::from(fact)
+            .join(dim, JoinType::Inner, (vec!["fact.k"], vec!["dim.k"]), None)?
+            .aggregate(vec![col("dim.v")], aggr_expr)?
+            .build()
+    }
+
+    #[test]
+    fn push_sum_below_join() -> Result<()> {
+        let plan = aggregate_join(
+            scan("fact", 1000, 10)?,
+            scan("dim", 10, 10)?,
+            vec![sum(col("fact.v"))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.v, sum(__eager_agg_1) AS sum(fact.v)
+          Aggregate: groupBy=[[dim.v]], aggr=[[sum(__eager_agg_1)]]
+            Inner Join: fact.k = dim.k
+              Aggregate: groupBy=[[fact.k]], aggr=[[sum(fact.v) AS __eager_agg_1]]
+                TableScan: fact
+              TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn push_decomposable_aggregates_below_join() -> Result<()> {
+        let plan = aggregate_join(
+            scan("fact", 1000, 10)?,
+            scan("dim", 10, 10)?,
+            vec![
+                count(col("fact.v")),
+                min(col("fact.v")),
+                max(col("fact.v")),
+                avg(col("fact.v")),
+            ],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.v, sum(__eager_agg_1) AS count(fact.v), min(__eager_agg_2) AS min(fact.v), max(__eager_agg_3) AS max(fact.v), CAST(sum(__eager_agg_4) AS Float64) / CAST(sum(__eager_agg_5) AS Float64) AS avg(fact.v)
+          Aggregate: groupBy=[[dim.v]], aggr=[[sum(__eager_agg_1), min(__eager_agg_2), max(__eager_agg_3), sum(__eager_agg_4), sum(__eager_agg_5)]]
+            Inner Join: fact.k = dim.k
+              Aggregate: groupBy=[[fact.k]], aggr=[[count(fact.v) AS __eager_agg_1, min(fact.v) AS __eager_agg_2, max(fact.v) AS __eager_agg_3, sum(fact.v) AS __eager_agg_4, count(fact.v) AS __eager_agg_5]]
+                TableScan: fact
+              TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn push_below_right_input() -> Result<()> {
+        // The aggregated column and the grouping column are in the right input
+        let plan = LogicalPlanBuilder::from(scan("dim", 10, 10)?)
+            .join(
+                scan("fact", 1000, 10)?,
+                JoinType::Inner,
+                (vec!["dim.k"], vec!["fact.k"]),
+                None,
+            )?
+            .aggregate(vec![col("dim.v"), col("fact.k")], vec![sum(col("fact.v"))])?
+            .build()?;
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.v, fact.k, sum(__eager_agg_1) AS sum(fact.v)
+          Aggregate: groupBy=[[dim.v, fact.k]], aggr=[[sum(__eager_agg_1)]]
+            Inner Join: dim.k = fact.k
+              TableScan: dim
+              Aggregate: groupBy=[[fact.k]], aggr=[[sum(fact.v) AS __eager_agg_1]]
+                TableScan: fact
+        ")
+    }
+
+    #[test]
+    fn push_count_star_below_largest_input() -> Result<()> {
+        let plan = aggregate_join(
+            scan("fact", 1000, 10)?,
+            scan("dim", 10, 10)?,
+            vec![count(lit(1i64))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: dim.v, sum(__eager_agg_1) AS count(Int64(1))
+          Aggregate: groupBy=[[dim.v]], aggr=[[sum(__eager_agg_1)]]
+            Inner Join: fact.k = dim.k
+              Aggregate: groupBy=[[fact.k]], aggr=[[count(Int64(1)) AS __eager_agg_1]]
+                TableScan: fact
+              TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn keep_aggregates_that_do_not_reduce_rows() -> Result<()> {
+        // The join keys of the fact table are unique
+        let plan = aggregate_join(
+            scan("fact", 1000, 1000)?,
+            scan("dim", 10, 10)?,
+            vec![sum(col("fact.v"))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.v]], aggr=[[sum(fact.v)]]
+          Inner Join: fact.k = dim.k
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn keep_aggregates_without_statistics() -> Result<()> {
+        let plan = aggregate_join(
+            scan_with_statistics("fact", None)?,
+            scan("dim", 10, 10)?,
+            vec![sum(col("fact.v"))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.v]], aggr=[[sum(fact.v)]]
+          Inner Join: fact.k = dim.k
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn keep_non_decomposable_aggregates() -> Result<()> {
+        let plan = aggregate_join(
+            scan("fact", 1000, 10)?,
+            scan("dim", 10, 10)?,
+            vec![sum(col("fact.v")), count_distinct(col("fact.v"))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.v]], aggr=[[sum(fact.v), count(DISTINCT fact.v)]]
+          Inner Join: fact.k = dim.k
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn keep_aggregates_of_both_inputs() -> Result<()> {
+        let plan = aggregate_join(
+            scan("fact", 1000, 10)?,
+            scan("dim", 10, 10)?,
+            vec![sum(col("fact.v") + col("dim.v"))],
+        )?;
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[dim.v]], aggr=[[sum(fact.v + dim.v)]]
+          Inner Join: fact.k = dim.k
+            TableScan: fact
+            TableScan: dim
+        ")
+    }
+
+    #[test]
+    fn push_into_union_inputs() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(scan("t1", 1000, 10)?)
+            .union(scan("t2", 500, 5)?)?
+            .aggregate(vec![col("k")], vec![sum(col("v")), count(col("v"))])?
+            .build()?;
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: k, sum(__eager_agg_1) AS sum(v), sum(__eager_agg_2) AS count(v)
+          Aggregate: groupBy=[[k]], aggr=[[sum(__eager_agg_1), sum(__eager_agg_2)]]
+            Union
+              Aggregate: groupBy=[[t1.k]], aggr=[[sum(t1.v) AS __eager_agg_1, count(t1.v) AS __eager_agg_2]]
+                TableScan: t1
+              Aggregate: groupBy=[[t2.k]], aggr=[[sum(t2.v) AS __eager_agg_1, count(t2.v) AS __eager_agg_2]]
+                TableScan: t2
+        ")
+    }
+}
@@ -0,0 +1,326 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EliminateDependentGroupBy`] removes the `GROUP BY` columns that are
+//! functionally dependent on the other grouping columns
+
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::Transformed;
+use datafusion_common::{Column, Dependency, HashSet, Result};
+use datafusion_expr::{Aggregate, Expr, LogicalPlan, LogicalPlanBuilder};
+
+/// Optimizer rule that simplifies the `GROUP BY` clause using the functional
+/// dependencies of the aggregate input, such as the ones derived from the
+/// primary keys of the scanned tables.
+///
+/// The grouping columns that are determined by the other grouping columns do
+/// not change the groups, so they are removed from the `GROUP BY` clause and
+/// computed with `first_value` instead. A projection on top of the aggregate
+/// preserves the original schema. For example, if `id` is the primary key of
+/// `t`:
+///
+/// ```text
+/// SELECT id, name, count(*) FROM t JOIN u ON t.id = u.t_id GROUP BY id, name
+/// ```
+///
+/// Is rewritten into
+///
+/// ```text
+/// SELECT id, first_value(name) AS name, count(*) FROM t JOIN u ON t.id = u.t_id GROUP BY id
+/// ```
+///
+/// An aggregate without aggregate expressions that groups by a key of its
+/// input does not combine any rows, and is replaced by a projection.
+///
+/// Only the dependencies of non-nullable keys are used, as the rows with null
+/// values in a unique key are not determined by the key.
+#[derive(Default, Debug)]
+pub struct EliminateDependentGroupBy {}
+
+impl EliminateDependentGroupBy {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EliminateDependentGroupBy {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Aggregate(aggregate)
+                if !aggregate.group_expr.is_empty()
+                    && !aggregate
+                        .group_expr
+                        .iter()
+                        .any(|expr| matches!(expr, Expr::GroupingSet(_))) =>
+            {
+                simplify_group_by(aggregate, config)
+            }
+            _ => Ok(Transformed::no(plan)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_dependent_group_by"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+fn simplify_group_by(
+    aggregate: Aggregate,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    let input_schema = aggregate.input.schema();
+    // The input field indices of the grouping columns
+    let group_indices = aggregate
+        .group_expr
+        .iter()
+        .map(|expr| match expr {
+            Expr::Column(column) => input_schema.maybe_index_of_column(column),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut required = group_indices
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+    let mut is_grouped_by_key = false;
+    for dependency in input_schema.functional_dependencies().iter() {
+        if dependency.nullable
+            || !dependency
+                .source_indices
+                .iter()
+                .all(|idx| required.contains(idx))
+        {
+            continue;
+        }
+        is_grouped_by_key |= dependency.mode == Dependency::Single;
+        // The key determines the dependent columns, so only the key is
+        // required to form the groups
+        for idx in &dependency.target_indices {
+            if !dependency.source_indices.contains(idx) {
+                required.remove(idx);
+            }
+        }
+    }
+
+    // Every group has a single row, so there is nothing to aggregate
+    if is_grouped_by_key && aggregate.aggr_expr.is_empty() {
+        return LogicalPlanBuilder::from(Arc::unwrap_or_clone(aggregate.input))
+            .project(aggregate.group_expr)?
+            .build()
+            .map(Transformed::yes);
+    }
+
+    let (group_expr, dependent_expr): (Vec<_>, Vec<_>) = aggregate
+        .group_expr
+        .iter()
+        .zip(&group_indices)
+        .partition(|(_, idx)| idx.is_none_or(|idx| required.contains(&idx)));
+    if dependent_expr.is_empty() {
+        return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
+    }
+    let Some(first_value) = config
+        .function_registry()
+        .and_then(|registry| registry.udaf("first_value").ok())
+    else {
+        return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
+    };
+
+    let group_expr = group_expr
+        .into_iter()
+        .map(|(expr, _)| expr.clone())
+        .collect::<Vec<_>>();
+    let dependent_expr = dependent_expr
+        .into_iter()
+        .map(|(expr, _)| expr)
+        .collect::<Vec<_>>();
+    let aggr_expr = dependent_expr
+        .iter()
+        .map(|&expr| first_value.call(vec![expr.clone()]))
+        .chain(aggregate.aggr_expr.iter().cloned())
+        .collect();
+    let simplified =
+        Aggregate::try_new(Arc::clone(&aggregate.input), group_expr.clone(), aggr_expr)?;
+
+    // Restore the original schema, replacing the dependent columns with their
+    // first values
+    let schema = Arc::clone(&simplified.schema);
+    let column = |idx: usize| Expr::Column(Column::from(schema.qualified_field(idx)));
+    let mut dependent_idx = group_expr.len();
+    let mut group_idx = 0;
+    let mut projection = Vec::with_capacity(aggregate.schema.fields().len());
+    for (expr, (qualifier, field)) in
+        aggregate.group_expr.iter().zip(aggregate.schema.iter())
+    {
+        if dependent_expr.contains(&expr) {
+            projection.push(
+                column(dependent_idx).alias_qualified(qualifier.cloned(), field.name()),
+            );
+            dependent_idx += 1;
+        } else {
+            projection.push(column(group_idx));
+            group_idx += 1;
+        }
+    }
+    projection.extend((dependent_idx..schema.fields().len()).map(column));
+
+    LogicalPlanBuilder::from(LogicalPlan::Aggregate(simplified))
+        .project(projection)?
+        .build()
+        .map(Transformed::yes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use crate::Optimizer;
+
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::registry::{FunctionRegistry, MemoryFunctionRegistry};
+    use datafusion_expr::{col, JoinType};
+    use datafusion_functions_aggregate::count::count;
+    use datafusion_functions_aggregate::first_last::first_value_udaf;
+    use insta::assert_snapshot;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let optimized_plan = optimize($plan)?;
+            assert_snapshot!(optimized_plan, @ $expected);
+
+            Ok(())
+        }};
+    }
+
+    struct TestConfig {
+        alias_generator: Arc<AliasGenerator>,
+        options: ConfigOptions,
+        registry: MemoryFunctionRegistry,
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> DateTime<Utc> {
+            Utc::now()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            &self.alias_generator
+        }
+
+        fn options(&self) -> &ConfigOptions {
+            &self.options
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+    }
+
+    fn optimize(plan: LogicalPlan) -> Result<LogicalPlan> {
+        let mut registry = MemoryFunctionRegistry::new();
+        registry.register_udaf(first_value_udaf())?;
+        let config = TestConfig {
+            alias_generator: Arc::new(AliasGenerator::new()),
+            options: ConfigOptions::default(),
+            registry,
+        };
+        Optimizer::with_rules(vec![Arc::new(EliminateDependentGroupBy::new())]).optimize(
+            plan,
+            &config,
+            |_, _| {},
+        )
+    }
+
+    #[test]
+    fn group_by_primary_key_and_dependent_columns() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_primary_key("t1")?)
+            .join_on(
+                test_table_scan_with_name("t2")?,
+                JoinType::Inner,
+                vec![col("t1.a").eq(col("t2.a"))],
+            )?
+            .aggregate(
+                vec![col("t1.b"), col("t1.a"), col("t2.c")],
+                vec![count(col("t2.b"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: first_value(t1.b) AS b, t1.a, t2.c, count(t2.b)
+          Aggregate: groupBy=[[t1.a, t2.c]], aggr=[[first_value(t1.b), count(t2.b)]]
+            Inner Join:  Filter: t1.a = t2.a
+              TableScan: t1
+              TableScan: t2
+        "
+        )
+    }
+
+    #[test]
+    fn group_by_primary_key_without_aggregates() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_primary_key("t1")?)
+            .aggregate(vec![col("a"), col("c")], Vec::<Expr>::new())?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+            Projection: t1.a, t1.c
+              TableScan: t1
+            "
+        )
+    }
+
+    #[test]
+    fn group_by_without_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_primary_key("t1")?)
+            .aggregate(vec![col("b"), col("c")], vec![count(col("a"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+            Aggregate: groupBy=[[t1.b, t1.c]], aggr=[[count(t1.a)]]
+              TableScan: t1
+            "
+        )
+    }
+}
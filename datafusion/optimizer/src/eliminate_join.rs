@@ -15,19 +15,28 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! [`EliminateJoin`] rewrites `INNER JOIN` with `true`/`null`
+//! [`EliminateJoin`] rewrites `INNER JOIN` with `true`/`null`, and removes the
+//! outer joins whose non-preserved side is unused
+use std::sync::Arc;
+
 use crate::optimizer::ApplyOrder;
 use crate::{OptimizerConfig, OptimizerRule};
 use datafusion_common::tree_node::Transformed;
-use datafusion_common::{Result, ScalarValue};
+use datafusion_common::{Dependency, HashSet, NullEquality, Result, ScalarValue};
 use datafusion_expr::JoinType::Inner;
 use datafusion_expr::{
-    logical_plan::{EmptyRelation, LogicalPlan},
+    logical_plan::{EmptyRelation, Join, JoinType, LogicalPlan},
     Expr,
 };
 
 /// Eliminates joins when join condition is false.
 /// Replaces joins when inner join condition is true with a cross join.
+///
+/// Also eliminates the left (right) joins below a projection or aggregate
+/// that only uses the columns of the left (right) side, if the right (left)
+/// side has a unique key among its join keys, such as a primary key. Each
+/// row of the preserved side then matches at most one row, so the join
+/// returns exactly the rows of the preserved side.
 #[derive(Default, Debug)]
 pub struct EliminateJoin;
 
@@ -63,6 +72,9 @@ impl OptimizerRule for EliminateJoin {
                     _ => Ok(Transformed::no(LogicalPlan::Join(join))),
                 }
             }
+            LogicalPlan::Projection(_) | LogicalPlan::Aggregate(_) => {
+                eliminate_unused_outer_join(plan)
+            }
             _ => Ok(Transformed::no(plan)),
         }
     }
@@ -72,14 +84,75 @@ impl OptimizerRule for EliminateJoin {
     }
 }
 
+/// Replaces the outer join input of `plan` with its preserved side, if
+/// `plan` does not use the other side and each row of the preserved side
+/// matches at most one row of it
+fn eliminate_unused_outer_join(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    let [LogicalPlan::Join(join)] = plan.inputs()[..] else {
+        return Ok(Transformed::no(plan));
+    };
+    let (preserved, other, other_keys) = match join.join_type {
+        JoinType::Left => (
+            &join.left,
+            &join.right,
+            join.on.iter().map(|(_, r)| r).collect(),
+        ),
+        JoinType::Right => (
+            &join.right,
+            &join.left,
+            join.on.iter().map(|(l, _)| l).collect(),
+        ),
+        _ => return Ok(Transformed::no(plan)),
+    };
+    if !is_unique(other, other_keys, join) {
+        return Ok(Transformed::no(plan));
+    }
+    let exprs = plan.expressions();
+    if !exprs
+        .iter()
+        .flat_map(|expr| expr.column_refs())
+        .all(|column| preserved.schema().has_column(column))
+    {
+        return Ok(Transformed::no(plan));
+    }
+    let preserved = Arc::unwrap_or_clone(Arc::clone(preserved));
+    plan.with_new_exprs(exprs, vec![preserved])
+        .map(Transformed::yes)
+}
+
+/// Returns true if `plan` has at most one row matching the join `keys` of
+/// `join` for any values of the keys
+fn is_unique(plan: &LogicalPlan, keys: Vec<&Expr>, join: &Join) -> bool {
+    let schema = plan.schema();
+    let key_indices = keys
+        .into_iter()
+        .filter_map(|key| key.try_as_col())
+        .filter_map(|column| schema.maybe_index_of_column(column))
+        .collect::<HashSet<_>>();
+    schema.functional_dependencies().iter().any(|dependency| {
+        dependency.mode == Dependency::Single
+            // Null keys only match when nulls compare equal
+            && (!dependency.nullable
+                || join.null_equality == NullEquality::NullEqualsNothing)
+            && dependency
+                .source_indices
+                .iter()
+                .all(|idx| key_indices.contains(idx))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::assert_optimized_plan_eq_snapshot;
     use crate::eliminate_join::EliminateJoin;
+    use crate::test::*;
     use crate::OptimizerContext;
     use datafusion_common::Result;
     use datafusion_expr::JoinType::Inner;
-    use datafusion_expr::{lit, logical_plan::builder::LogicalPlanBuilder};
+    use datafusion_expr::{
+        col, lit, logical_plan::builder::LogicalPlanBuilder, JoinType,
+    };
+    use datafusion_functions_aggregate::count::count;
     use std::sync::Arc;
 
     macro_rules! assert_optimized_plan_equal {
@@ -110,4 +183,80 @@ mod tests {
 
         assert_optimized_plan_equal!(plan, @"EmptyRelation")
     }
+
+    #[test]
+    fn left_join_to_primary_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                test_table_scan_with_primary_key("t2")?,
+                JoinType::Left,
+                (vec!["t1.b"], vec!["t2.a"]),
+                None,
+            )?
+            .project(vec![col("t1.a"), col("t1.b")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, t1.b
+          TableScan: t1
+        ")
+    }
+
+    #[test]
+    fn right_join_to_primary_key_under_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_primary_key("t1")?)
+            .join(
+                test_table_scan_with_name("t2")?,
+                JoinType::Right,
+                (vec!["t1.a"], vec!["t2.b"]),
+                Some(col("t1.c").gt(col("t2.c"))),
+            )?
+            .aggregate(vec![col("t2.a")], vec![count(col("t2.b"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[t2.a]], aggr=[[count(t2.b)]]
+          TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn left_join_using_right_columns() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                test_table_scan_with_primary_key("t2")?,
+                JoinType::Left,
+                (vec!["t1.b"], vec!["t2.a"]),
+                None,
+            )?
+            .project(vec![col("t1.a"), col("t2.b")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, t2.b
+          Left Join: t1.b = t2.a
+            TableScan: t1
+            TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn left_join_to_non_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                test_table_scan_with_primary_key("t2")?,
+                JoinType::Left,
+                (vec!["t1.b"], vec!["t2.b"]),
+                None,
+            )?
+            .project(vec![col("t1.a")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a
+          Left Join: t1.b = t2.b
+            TableScan: t1
+            TableScan: t2
+        ")
+    }
 }
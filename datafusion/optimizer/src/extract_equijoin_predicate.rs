@@ -19,8 +19,8 @@
 use crate::optimizer::ApplyOrder;
 use crate::{OptimizerConfig, OptimizerRule};
 use datafusion_common::tree_node::Transformed;
-use datafusion_common::DFSchema;
 use datafusion_common::Result;
+use datafusion_common::{DFSchema, NullEquality};
 use datafusion_expr::utils::split_conjunction_owned;
 use datafusion_expr::utils::{can_hash, find_valid_equijoin_key_pair};
 use datafusion_expr::{BinaryExpr, Expr, ExprSchemable, Join, LogicalPlan, Operator};
@@ -67,6 +67,11 @@ impl OptimizerRule for ExtractEquijoinPredicate {
         _config: &dyn OptimizerConfig,
     ) -> Result<Transformed<LogicalPlan>> {
         match plan {
+            // The equijoin keys of the join compare nulls as equal, unlike `=`
+            LogicalPlan::Join(Join {
+                null_equality: NullEquality::NullEqualsNull,
+                ..
+            }) => Ok(Transformed::no(plan)),
             LogicalPlan::Join(Join {
                 left,
                 right,
@@ -158,6 +163,7 @@ mod tests {
     use crate::assert_optimized_plan_eq_display_indent_snapshot;
     use crate::test::*;
     use arrow::datatypes::DataType;
+    use datafusion_common::Column;
     use datafusion_expr::{
         col, lit, logical_plan::builder::LogicalPlanBuilder, JoinType,
     };
@@ -219,6 +225,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn join_with_null_equals_null_keys() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        let plan = LogicalPlanBuilder::from(t1)
+            .join_detailed(
+                t2,
+                JoinType::Left,
+                (Vec::<Column>::new(), Vec::<Column>::new()),
+                Some(col("t1.a").eq(col("t2.a"))),
+                NullEquality::NullEqualsNull,
+            )?
+            .build()?;
+
+        // `=` does not match nulls, so it can not be turned into a join key
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Left Join:  Filter: t1.a = t2.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32;N, b:UInt32;N, c:UInt32;N]
+          TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]
+          TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
     #[test]
     fn join_with_only_none_equi_predicate() -> Result<()> {
         let t1 = test_table_scan_with_name("t1")?;
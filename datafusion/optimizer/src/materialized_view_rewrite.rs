@@ -0,0 +1,935 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MaterializedViewRewrite`] answers queries from registered
+//! [`RewriteCandidate`]s, such as materialized views and summary tables
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{
+    Transformed, TreeNode, TreeNodeRecursion, TreeNodeRewriter,
+};
+use datafusion_common::{plan_err, DFSchema, Result, ScalarValue, TableReference};
+use datafusion_expr::expr::{AggregateFunction, AggregateFunctionParams};
+use datafusion_expr::logical_plan::{Aggregate, Filter, LogicalPlan, Projection};
+use datafusion_expr::registry::FunctionRegistry;
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{
+    cast, AggregateUDF, BinaryExpr, Expr, ExprSchemable, LogicalPlanBuilder, Operator,
+    TableSource,
+};
+use log::debug;
+
+/// A table storing the result of a plan, that the optimizer may scan instead
+/// of computing the parts of other plans that the plan contains.
+///
+/// The definition is analyzed but not optimized, and is made of an optional
+/// projection, an optional aggregate, filters and a source plan (usually a
+/// table scan or joins of table scans). Each column of the table stores the
+/// corresponding output column of the definition.
+///
+/// Keeping the table up to date with its definition is the responsibility of
+/// its owner: queries are answered from the table as if it always was.
+#[derive(Clone)]
+pub struct RewriteCandidate {
+    name: TableReference,
+    definition: LogicalPlan,
+    source: Arc<dyn TableSource>,
+}
+
+impl RewriteCandidate {
+    /// Creates a candidate storing the result of `definition` in the table
+    /// `source` named `name`, which must have the same number of columns
+    /// and types as the output of the definition
+    pub fn try_new(
+        name: impl Into<TableReference>,
+        definition: LogicalPlan,
+        source: Arc<dyn TableSource>,
+    ) -> Result<Self> {
+        let name = name.into();
+        let schema = source.schema();
+        let definition_schema = definition.schema();
+        if schema.fields().len() != definition_schema.fields().len()
+            || schema
+                .fields()
+                .iter()
+                .zip(definition_schema.fields())
+                .any(|(field, expected)| field.data_type() != expected.data_type())
+        {
+            return plan_err!(
+                "The schema of the rewrite candidate {name} does not match its definition: \
+                 expected {definition_schema}, got {schema:?}"
+            );
+        }
+        Ok(Self {
+            name,
+            definition,
+            source,
+        })
+    }
+
+    /// The name of the table
+    pub fn name(&self) -> &TableReference {
+        &self.name
+    }
+
+    /// The plan whose result is stored in the table
+    pub fn definition(&self) -> &LogicalPlan {
+        &self.definition
+    }
+
+    /// The table storing the result of the definition
+    pub fn source(&self) -> &Arc<dyn TableSource> {
+        &self.source
+    }
+}
+
+impl Debug for RewriteCandidate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RewriteCandidate")
+            .field("name", &self.name)
+            .field("definition", &self.definition)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replaces parts of plans by scans of the [`RewriteCandidate`]s returned by
+/// [`OptimizerConfig::rewrite_candidates`] that store their results.
+///
+/// Projections and aggregates of filtered source plans are matched against
+/// the candidates with the same source plan, for example:
+///
+/// ```text
+/// Projection: sales.region, sum(sales.amount)
+///   Aggregate: groupBy=[[sales.region]], aggr=[[sum(sales.amount)]]
+///     Filter: sales.day >= Date32("2024-01-01")
+///       TableScan: sales
+/// ```
+///
+/// Is answered from a candidate `sales_by_day` defined as `SELECT day, region,
+/// sum(amount) AS total FROM sales GROUP BY day, region` by:
+///
+/// ```text
+/// Projection: sales.region, sum(sales.amount)
+///   Projection: sales_by_day.region AS region, sum(sales_by_day.total) AS sum(sales.amount)
+///     Aggregate: groupBy=[[sales_by_day.region]], aggr=[[sum(sales_by_day.total)]]
+///       Filter: sales_by_day.day >= Date32("2024-01-01")
+///         TableScan: sales_by_day
+/// ```
+///
+/// A candidate is used when:
+///
+/// * Its filters are implied by the filters of the plan (filter
+///   subsumption). Each filter of the candidate must be a filter of the plan,
+///   or a comparison to a literal implied by a comparison of the plan, such
+///   as `x > 10` by `x >= 20`. The other filters of the plan are applied to
+///   the table, and must only reference its columns (and its grouping
+///   columns, for an aggregate candidate).
+/// * The expressions of the plan can be computed from the columns of the
+///   table (projection subsets).
+/// * For an aggregate candidate, the plan is an aggregate whose grouping
+///   expressions can be computed from the grouping columns of the candidate.
+///   When they group the rows of the candidate further, the `sum`, `count`,
+///   `min` and `max` aggregates of the candidate (without `DISTINCT`,
+///   `FILTER` or `ORDER BY`) are combined (group-by rollup). Otherwise, any
+///   aggregate of the candidate can be used.
+///
+/// The first candidate that can be used is chosen, and the scan of its table
+/// shows in `EXPLAIN`.
+#[derive(Default, Debug)]
+pub struct MaterializedViewRewrite;
+
+impl MaterializedViewRewrite {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for MaterializedViewRewrite {
+    fn name(&self) -> &str {
+        "materialized_view_rewrite"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let candidates = config.rewrite_candidates();
+        if !config.options().optimizer.enable_materialized_view_rewrite
+            || candidates.is_empty()
+        {
+            return Ok(Transformed::no(plan));
+        }
+        let Some(query) = QueryShape::try_new(&plan) else {
+            return Ok(Transformed::no(plan));
+        };
+        for candidate in candidates {
+            let Some(shape) = CandidateShape::try_new(&candidate.definition) else {
+                continue;
+            };
+            if let Some(rewritten) =
+                rewrite_with_candidate(&plan, &query, candidate, &shape, config)?
+            {
+                debug!("Answered plan from rewrite candidate {}", candidate.name);
+                return Ok(Transformed::new(rewritten, true, TreeNodeRecursion::Jump));
+            }
+        }
+        Ok(Transformed::no(plan))
+    }
+}
+
+/// The filters of `plan`, and the first node below them
+fn split_filters(mut plan: &LogicalPlan) -> (Vec<&Expr>, &LogicalPlan) {
+    let mut filters = vec![];
+    while let LogicalPlan::Filter(filter) = plan {
+        filters.extend(split_conjunction(&filter.predicate));
+        plan = filter.input.as_ref();
+    }
+    (filters, plan)
+}
+
+/// A projection or an aggregate of a filtered source plan, that may be
+/// answered from a candidate
+struct QueryShape<'a> {
+    source: &'a LogicalPlan,
+    filters: Vec<&'a Expr>,
+    /// The projection or aggregate of the source
+    output: QueryOutput<'a>,
+}
+
+enum QueryOutput<'a> {
+    Projection(&'a Projection),
+    Aggregate(&'a Aggregate),
+}
+
+impl<'a> QueryShape<'a> {
+    fn try_new(plan: &'a LogicalPlan) -> Option<Self> {
+        let (output, input) = match plan {
+            LogicalPlan::Projection(projection) => (
+                QueryOutput::Projection(projection),
+                projection.input.as_ref(),
+            ),
+            LogicalPlan::Aggregate(aggregate)
+                if !aggregate
+                    .group_expr
+                    .iter()
+                    .any(|expr| matches!(expr, Expr::GroupingSet(_))) =>
+            {
+                (QueryOutput::Aggregate(aggregate), aggregate.input.as_ref())
+            }
+            _ => return None,
+        };
+        let (filters, source) = split_filters(input);
+        Some(Self {
+            source,
+            filters,
+            output,
+        })
+    }
+}
+
+/// The definition of a [`RewriteCandidate`] decomposed into its parts
+struct CandidateShape<'a> {
+    source: &'a LogicalPlan,
+    filters: Vec<&'a Expr>,
+    /// The grouping expressions of an aggregate candidate
+    group_by: Option<&'a [Expr]>,
+    /// The expression computed by each column of the table, in terms of the
+    /// columns of the source (and of aggregate functions, for an aggregate
+    /// candidate)
+    columns: Vec<Expr>,
+}
+
+impl<'a> CandidateShape<'a> {
+    fn try_new(definition: &'a LogicalPlan) -> Option<Self> {
+        let (projection, below_projection) = match definition {
+            LogicalPlan::Projection(projection) => {
+                (Some(&projection.expr), projection.input.as_ref())
+            }
+            plan => (None, plan),
+        };
+        let (aggregate, below_aggregate) = match below_projection {
+            LogicalPlan::Aggregate(aggregate) => {
+                if aggregate
+                    .group_expr
+                    .iter()
+                    .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+                {
+                    return None;
+                }
+                (Some(aggregate), aggregate.input.as_ref())
+            }
+            plan => (None, plan),
+        };
+        let (filters, source) = split_filters(below_aggregate);
+
+        // The expressions computed by the columns of the plan below the
+        // projection
+        let input_columns: Vec<Expr> = match aggregate {
+            Some(aggregate) => aggregate
+                .group_expr
+                .iter()
+                .chain(&aggregate.aggr_expr)
+                .map(|expr| expr.clone().unalias_nested().data)
+                .collect(),
+            None => below_projection
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .collect(),
+        };
+        let columns = match projection {
+            Some(exprs) => {
+                let input_schema = below_projection.schema();
+                exprs
+                    .iter()
+                    .map(|expr| {
+                        expr.clone()
+                            .unalias_nested()
+                            .data
+                            .transform(|expr| {
+                                if let Expr::Column(column) = &expr {
+                                    if let Some(index) =
+                                        input_schema.maybe_index_of_column(column)
+                                    {
+                                        let input = input_columns[index].clone();
+                                        return Ok(Transformed::yes(input));
+                                    }
+                                }
+                                Ok(Transformed::no(expr))
+                            })
+                            .map(|transformed| transformed.data)
+                            .ok()
+                    })
+                    .collect::<Option<_>>()?
+            }
+            None => input_columns,
+        };
+        Some(Self {
+            source,
+            filters,
+            group_by: aggregate.map(|aggregate| aggregate.group_expr.as_slice()),
+            columns,
+        })
+    }
+
+    /// Returns whether the column `index` of the table has the same value for
+    /// all the rows of a group of an aggregate candidate
+    fn is_grouping_column(&self, index: usize) -> bool {
+        !self.columns[index]
+            .exists(|expr| Ok(matches!(expr, Expr::AggregateFunction(_))))
+            .unwrap_or(true)
+    }
+}
+
+/// Replaces the sub-expressions of an expression equal to the expression
+/// computed by a column of the table by that column, and fails if other
+/// columns of the source remain
+struct ColumnReplacer<'a> {
+    /// The expressions computed by the columns of the table that can be
+    /// used, and the columns
+    targets: Vec<(&'a Expr, Expr)>,
+    complete: bool,
+}
+
+impl<'a> ColumnReplacer<'a> {
+    fn new(
+        shape: &'a CandidateShape,
+        table_schema: &DFSchema,
+        usable: impl Fn(usize) -> bool,
+    ) -> Self {
+        let targets = shape
+            .columns
+            .iter()
+            .zip(table_schema.columns())
+            .enumerate()
+            .filter(|(index, _)| usable(*index))
+            .map(|(_, (expr, column))| (expr, Expr::Column(column)))
+            .collect();
+        Self {
+            targets,
+            complete: true,
+        }
+    }
+
+    /// Returns the expression in terms of the columns of the table, or
+    /// `None` if it can't be computed from them
+    fn replace(&mut self, expr: &Expr) -> Result<Option<Expr>> {
+        self.complete = true;
+        let replaced = expr.clone().rewrite(self)?.data;
+        Ok(self.complete.then_some(replaced))
+    }
+}
+
+impl TreeNodeRewriter for ColumnReplacer<'_> {
+    type Node = Expr;
+
+    fn f_down(&mut self, expr: Expr) -> Result<Transformed<Expr>> {
+        if let Some((_, column)) =
+            self.targets.iter().find(|(target, _)| **target == expr)
+        {
+            return Ok(Transformed::new(
+                column.clone(),
+                true,
+                TreeNodeRecursion::Jump,
+            ));
+        }
+        match expr {
+            Expr::Column(_)
+            | Expr::OuterReferenceColumn(_, _)
+            | Expr::ScalarSubquery(_)
+            | Expr::Exists(_)
+            | Expr::InSubquery(_) => {
+                self.complete = false;
+                Ok(Transformed::new(expr, false, TreeNodeRecursion::Stop))
+            }
+            expr => Ok(Transformed::no(expr)),
+        }
+    }
+}
+
+/// Returns a comparison of an expression to a non null literal as the
+/// expression, the operator and the literal
+fn comparison_to_literal(expr: &Expr) -> Option<(&Expr, Operator, &ScalarValue)> {
+    let Expr::BinaryExpr(BinaryExpr { left, op, right }) = expr else {
+        return None;
+    };
+    if !matches!(
+        op,
+        Operator::Eq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+    ) {
+        return None;
+    }
+    match (left.as_ref(), right.as_ref()) {
+        (expr, Expr::Literal(value, _)) if !value.is_null() => Some((expr, *op, value)),
+        (Expr::Literal(value, _), expr) if !value.is_null() => {
+            Some((expr, op.swap()?, value))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether the filter `query` implies the filter `candidate`
+fn implies(query: &Expr, candidate: &Expr) -> bool {
+    if query == candidate {
+        return true;
+    }
+    let (
+        Some((query_expr, query_op, query_value)),
+        Some((candidate_expr, candidate_op, candidate_value)),
+    ) = (
+        comparison_to_literal(query),
+        comparison_to_literal(candidate),
+    )
+    else {
+        return false;
+    };
+    if query_expr != candidate_expr {
+        return false;
+    }
+    let Some(ordering) = query_value.partial_cmp(candidate_value) else {
+        return false;
+    };
+    match (query_op, candidate_op) {
+        (Operator::Eq, Operator::Eq) => ordering.is_eq(),
+        (Operator::Gt, Operator::Gt | Operator::GtEq) => ordering.is_ge(),
+        (Operator::Eq | Operator::GtEq, Operator::Gt) => ordering.is_gt(),
+        (Operator::Eq | Operator::GtEq, Operator::GtEq) => ordering.is_ge(),
+        (Operator::Lt, Operator::Lt | Operator::LtEq) => ordering.is_le(),
+        (Operator::Eq | Operator::LtEq, Operator::Lt) => ordering.is_lt(),
+        (Operator::Eq | Operator::LtEq, Operator::LtEq) => ordering.is_le(),
+        _ => false,
+    }
+}
+
+/// Returns `plan` computed from the table of `candidate`, or `None` if the
+/// candidate can't be used
+fn rewrite_with_candidate(
+    plan: &LogicalPlan,
+    query: &QueryShape,
+    candidate: &RewriteCandidate,
+    shape: &CandidateShape,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    if query.source != shape.source {
+        return Ok(None);
+    }
+    if !shape
+        .filters
+        .iter()
+        .all(|filter| query.filters.iter().any(|query| implies(query, filter)))
+    {
+        return Ok(None);
+    }
+    let residual_filters = query
+        .filters
+        .iter()
+        .filter(|filter| !shape.filters.contains(filter));
+
+    let scan = LogicalPlanBuilder::scan(
+        candidate.name.clone(),
+        Arc::clone(&candidate.source),
+        None,
+    )?
+    .build()?;
+    let table_schema = Arc::clone(scan.schema());
+    // The rows of an aggregate candidate can only be filtered and grouped
+    // by its grouping columns
+    let mut grouping = ColumnReplacer::new(shape, &table_schema, |index| {
+        shape.group_by.is_none() || shape.is_grouping_column(index)
+    });
+    let mut filters = vec![];
+    for filter in residual_filters {
+        let Some(filter) = grouping.replace(filter)? else {
+            return Ok(None);
+        };
+        filters.push(filter);
+    }
+    let has_residual_filters = !filters.is_empty();
+    let mut input = scan;
+    if let Some(predicate) = conjunction(filters) {
+        input = LogicalPlan::Filter(Filter::try_new(predicate, Arc::new(input))?);
+    }
+
+    let outputs = match (&query.output, shape.group_by) {
+        (QueryOutput::Projection(projection), None) => {
+            let mut outputs = vec![];
+            for expr in &projection.expr {
+                let Some(expr) = grouping.replace(expr)? else {
+                    return Ok(None);
+                };
+                outputs.push(expr);
+            }
+            outputs
+        }
+        (QueryOutput::Projection(_), Some(_)) => return Ok(None),
+        (QueryOutput::Aggregate(aggregate), None) => {
+            let mut group_expr = vec![];
+            for expr in &aggregate.group_expr {
+                let Some(expr) = grouping.replace(expr)? else {
+                    return Ok(None);
+                };
+                group_expr.push(expr);
+            }
+            let mut aggr_expr = vec![];
+            for expr in &aggregate.aggr_expr {
+                let Some(expr) = grouping.replace(expr)? else {
+                    return Ok(None);
+                };
+                aggr_expr.push(expr);
+            }
+            let aggregate = Aggregate::try_new(Arc::new(input), group_expr, aggr_expr)?;
+            input = LogicalPlan::Aggregate(aggregate);
+            input
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .collect()
+        }
+        (QueryOutput::Aggregate(aggregate), Some(candidate_group_by)) => {
+            let mut group_expr = vec![];
+            for expr in &aggregate.group_expr {
+                let Some(expr) = grouping.replace(expr)? else {
+                    return Ok(None);
+                };
+                group_expr.push(expr);
+            }
+            let query_group_by: Vec<_> = aggregate
+                .group_expr
+                .iter()
+                .map(|e| e.clone().unalias())
+                .collect();
+            let candidate_group_by: Vec<_> = candidate_group_by
+                .iter()
+                .map(|e| e.clone().unalias())
+                .collect();
+            let same_groups = query_group_by
+                .iter()
+                .all(|expr| candidate_group_by.contains(expr))
+                && candidate_group_by
+                    .iter()
+                    .all(|expr| query_group_by.contains(expr));
+            // A global aggregate returns a row even when its input has none,
+            // which the filtered row of a global candidate would not
+            if same_groups && query_group_by.is_empty() && has_residual_filters {
+                return Ok(None);
+            }
+            let aggregates = ColumnReplacer::new(shape, &table_schema, |index| {
+                !shape.is_grouping_column(index)
+            });
+            if same_groups {
+                let mut outputs = group_expr;
+                for expr in &aggregate.aggr_expr {
+                    let expr = expr.clone().unalias_nested().data;
+                    let Some((_, column)) = aggregates
+                        .targets
+                        .iter()
+                        .find(|(target, _)| **target == expr)
+                    else {
+                        return Ok(None);
+                    };
+                    outputs.push(column.clone());
+                }
+                outputs
+            } else {
+                let Some(registry) = config.function_registry() else {
+                    return Ok(None);
+                };
+                let mut aggr_expr = vec![];
+                for expr in &aggregate.aggr_expr {
+                    let expr = expr.clone().unalias_nested().data;
+                    let Some((_, column)) = aggregates
+                        .targets
+                        .iter()
+                        .find(|(target, _)| **target == expr)
+                    else {
+                        return Ok(None);
+                    };
+                    let Some(rollup) = rollup(
+                        &expr,
+                        column.clone(),
+                        registry,
+                        aggregate.group_expr.is_empty(),
+                    ) else {
+                        return Ok(None);
+                    };
+                    aggr_expr.push(rollup);
+                }
+                let aggregate =
+                    Aggregate::try_new(Arc::new(input), group_expr, aggr_expr)?;
+                input = LogicalPlan::Aggregate(aggregate);
+                input
+                    .schema()
+                    .columns()
+                    .into_iter()
+                    .map(Expr::Column)
+                    .collect()
+            }
+        }
+    };
+
+    // Gives the outputs the names and types of the rewritten plan
+    let input_schema = Arc::clone(input.schema());
+    let exprs = outputs
+        .into_iter()
+        .zip(plan.schema().iter())
+        .map(|(expr, (qualifier, field))| {
+            let expr = if expr.get_type(&input_schema)? == *field.data_type() {
+                expr
+            } else {
+                cast(expr, field.data_type().clone())
+            };
+            Ok(expr.alias_qualified(qualifier.cloned(), field.name()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let projection = Projection::try_new(exprs, Arc::new(input))?;
+    Ok(Some(LogicalPlan::Projection(projection)))
+}
+
+/// Returns the aggregate combining the values of an aggregate for groups of
+/// rows, stored in `column`
+fn rollup(
+    expr: &Expr,
+    column: Expr,
+    registry: &dyn FunctionRegistry,
+    global: bool,
+) -> Option<Expr> {
+    let Expr::AggregateFunction(AggregateFunction {
+        func,
+        params:
+            AggregateFunctionParams {
+                distinct: false,
+                filter: None,
+                order_by,
+                null_treatment: None,
+                ..
+            },
+    }) = expr
+    else {
+        return None;
+    };
+    if !order_by.is_empty() {
+        return None;
+    }
+    let func: Arc<AggregateUDF> = match func.name() {
+        "sum" | "min" | "max" => Arc::clone(func),
+        // A global `sum` of counts returns `NULL` instead of `0` for no rows
+        "count" if !global => registry.udaf("sum").ok()?,
+        _ => return None,
+    };
+    Some(Expr::AggregateFunction(AggregateFunction::new_udf(
+        func,
+        vec![column],
+        false,
+        None,
+        vec![],
+        None,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test::test_table_scan;
+
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::logical_plan::builder::LogicalTableSource;
+    use datafusion_expr::registry::MemoryFunctionRegistry;
+    use datafusion_expr::{col, lit};
+    use datafusion_functions_aggregate::average::avg;
+    use datafusion_functions_aggregate::count::{count, count_udaf};
+    use datafusion_functions_aggregate::sum::{sum, sum_udaf};
+    use insta::assert_snapshot;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            $candidates:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let starting_schema = Arc::clone($plan.schema());
+            let optimized_plan = optimize($plan, $candidates)?;
+            assert!(starting_schema
+                .logically_equivalent_names_and_types(optimized_plan.schema()));
+            assert_snapshot!(
+                optimized_plan.display_indent(),
+                @ $expected,
+            );
+
+            Ok(())
+        }};
+    }
+
+    struct TestConfig {
+        alias_generator: Arc<AliasGenerator>,
+        options: ConfigOptions,
+        registry: MemoryFunctionRegistry,
+        candidates: Vec<Arc<RewriteCandidate>>,
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> DateTime<Utc> {
+            Utc::now()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            &self.alias_generator
+        }
+
+        fn options(&self) -> &ConfigOptions {
+            &self.options
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+
+        fn rewrite_candidates(&self) -> &[Arc<RewriteCandidate>] {
+            &self.candidates
+        }
+    }
+
+    fn optimize(
+        plan: LogicalPlan,
+        candidates: Vec<RewriteCandidate>,
+    ) -> Result<LogicalPlan> {
+        let mut registry = MemoryFunctionRegistry::new();
+        registry.register_udaf(sum_udaf())?;
+        registry.register_udaf(count_udaf())?;
+        let config = TestConfig {
+            alias_generator: Arc::new(AliasGenerator::new()),
+            options: ConfigOptions::default(),
+            registry,
+            candidates: candidates.into_iter().map(Arc::new).collect(),
+        };
+        plan.transform_down(|plan| MaterializedViewRewrite::new().rewrite(plan, &config))
+            .map(|transformed| transformed.data)
+    }
+
+    /// A candidate named `name` storing the result of `definition`
+    fn candidate(name: &str, definition: LogicalPlan) -> Result<RewriteCandidate> {
+        let schema = Arc::new(definition.schema().as_arrow().clone());
+        let source = Arc::new(LogicalTableSource::new(schema));
+        RewriteCandidate::try_new(name, definition, source)
+    }
+
+    /// The totals of `c` by `a` and `b`
+    fn totals_by_a_and_b() -> Result<RewriteCandidate> {
+        let definition = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(
+                vec![col("a"), col("b")],
+                vec![sum(col("c")), count(col("c"))],
+            )?
+            .project(vec![
+                col("a"),
+                col("b"),
+                col("sum(test.c)").alias("total"),
+                col("count(test.c)").alias("n"),
+            ])?
+            .build()?;
+        candidate("totals", definition)
+    }
+
+    #[test]
+    fn rollup_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("b").eq(lit(1u32)))?
+            .aggregate(vec![col("a")], vec![sum(col("c")), count(col("c"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![totals_by_a_and_b()?],
+            @r"
+        Projection: totals.a AS a, sum(totals.total) AS sum(test.c), sum(totals.n) AS count(test.c)
+          Aggregate: groupBy=[[totals.a]], aggr=[[sum(totals.total), sum(totals.n)]]
+            Filter: totals.b = UInt32(1)
+              TableScan: totals
+        "
+        )
+    }
+
+    #[test]
+    fn same_groups() -> Result<()> {
+        let definition = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("c"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("c"))])?
+            .project(vec![col("avg(test.c)"), col("a")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![candidate("averages", definition)?],
+            @r"
+        Projection: avg(test.c), test.a
+          Projection: averages.a AS a, averages.avg(test.c) AS avg(test.c)
+            TableScan: averages
+        "
+        )
+    }
+
+    #[test]
+    fn filter_subsumption() -> Result<()> {
+        let definition = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("c").gt(lit(10u32)))?
+            .project(vec![col("a"), col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("c").gt_eq(lit(20u32)).and(col("a").eq(lit(1u32))))?
+            .project(vec![col("a"), col("c") + lit(1u32)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![candidate("large_c", definition)?],
+            @r"
+        Projection: large_c.a AS a, large_c.c + UInt32(1) AS test.c + UInt32(1)
+          Filter: large_c.c >= UInt32(20) AND large_c.a = UInt32(1)
+            TableScan: large_c
+        "
+        )
+    }
+
+    #[test]
+    fn filter_not_subsumed() -> Result<()> {
+        let definition = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("c").gt(lit(10u32)))?
+            .project(vec![col("a"), col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("c").gt(lit(5u32)))?
+            .project(vec![col("a")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![candidate("large_c", definition)?],
+            @r"
+        Projection: test.a
+          Filter: test.c > UInt32(5)
+            TableScan: test
+        "
+        )
+    }
+
+    #[test]
+    fn missing_columns() -> Result<()> {
+        // `c` is aggregated by the candidate, so can't be filtered
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("c").gt(lit(5u32)))?
+            .aggregate(vec![col("a")], vec![sum(col("c"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![totals_by_a_and_b()?],
+            @r"
+        Aggregate: groupBy=[[test.a]], aggr=[[sum(test.c)]]
+          Filter: test.c > UInt32(5)
+            TableScan: test
+        "
+        )
+    }
+
+    #[test]
+    fn global_count() -> Result<()> {
+        // The `sum` of the counts of no groups would be `NULL`
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(Vec::<Expr>::new(), vec![count(col("c"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            vec![totals_by_a_and_b()?],
+            @r"
+        Aggregate: groupBy=[[]], aggr=[[count(test.c)]]
+          TableScan: test
+        "
+        )
+    }
+
+    #[test]
+    fn invalid_candidate() -> Result<()> {
+        let definition = test_table_scan()?;
+        let schema = Arc::new(arrow::datatypes::Schema::empty());
+        let source = Arc::new(LogicalTableSource::new(schema));
+        let err = RewriteCandidate::try_new("empty", definition, source).unwrap_err();
+        assert!(err.to_string().contains("does not match its definition"));
+        Ok(())
+    }
+}
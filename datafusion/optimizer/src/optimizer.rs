@@ -33,9 +33,12 @@ use datafusion_common::{internal_err, DFSchema, DataFusionError, HashSet, Result
 use datafusion_expr::logical_plan::LogicalPlan;
 
 use crate::common_subexpr_eliminate::CommonSubexprEliminate;
+use crate::decorrelate_general::DecorrelateGeneralSubquery;
 use crate::decorrelate_lateral_join::DecorrelateLateralJoin;
 use crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery;
+use crate::eager_aggregation::EagerAggregation;
 use crate::eliminate_cross_join::EliminateCrossJoin;
+use crate::eliminate_dependent_group_by::EliminateDependentGroupBy;
 use crate::eliminate_duplicated_expr::EliminateDuplicatedExpr;
 use crate::eliminate_filter::EliminateFilter;
 use crate::eliminate_group_by_constant::EliminateGroupByConstant;
@@ -46,11 +49,13 @@ use crate::eliminate_one_union::EliminateOneUnion;
 use crate::eliminate_outer_join::EliminateOuterJoin;
 use crate::extract_equijoin_predicate::ExtractEquijoinPredicate;
 use crate::filter_null_join_keys::FilterNullJoinKeys;
+use crate::materialized_view_rewrite::{MaterializedViewRewrite, RewriteCandidate};
 use crate::optimize_projections::OptimizeProjections;
 use crate::plan_signature::LogicalPlanSignature;
 use crate::propagate_empty_relation::PropagateEmptyRelation;
 use crate::push_down_filter::PushDownFilter;
 use crate::push_down_limit::PushDownLimit;
+use crate::reorder_joins::ReorderJoins;
 use crate::replace_distinct_aggregate::ReplaceDistinctWithAggregate;
 use crate::scalar_subquery_to_join::ScalarSubqueryToJoin;
 use crate::simplify_expressions::SimplifyExpressions;
@@ -112,6 +117,12 @@ pub trait OptimizerConfig {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         None
     }
+
+    /// Return the tables storing the results of plans, that may be scanned
+    /// instead of computing the parts of the optimized plans they contain
+    fn rewrite_candidates(&self) -> &[Arc<RewriteCandidate>] {
+        &[]
+    }
 }
 
 /// A standalone [`OptimizerConfig`] that can be used independently
@@ -221,6 +232,9 @@ impl Optimizer {
     /// Create a new optimizer using the recommended list of rules
     pub fn new() -> Self {
         let rules: Vec<Arc<dyn OptimizerRule + Sync + Send>> = vec![
+            // Must be first, so that the plans are matched against the
+            // definitions of the candidates before being rewritten
+            Arc::new(MaterializedViewRewrite::new()),
             Arc::new(EliminateNestedUnion::new()),
             Arc::new(SimplifyExpressions::new()),
             Arc::new(ReplaceDistinctWithAggregate::new()),
@@ -228,6 +242,7 @@ impl Optimizer {
             Arc::new(DecorrelatePredicateSubquery::new()),
             Arc::new(ScalarSubqueryToJoin::new()),
             Arc::new(DecorrelateLateralJoin::new()),
+            Arc::new(DecorrelateGeneralSubquery::new()),
             Arc::new(ExtractEquijoinPredicate::new()),
             Arc::new(EliminateDuplicatedExpr::new()),
             Arc::new(EliminateFilter::new()),
@@ -241,10 +256,17 @@ impl Optimizer {
             // Filters can't be pushed down past Limits, we should do PushDownFilter after PushDownLimit
             Arc::new(PushDownLimit::new()),
             Arc::new(PushDownFilter::new()),
+            // Must be after PushDownFilter, so that the estimated sizes of the
+            // join inputs account for their filters
+            Arc::new(ReorderJoins::new()),
+            // Must be after ReorderJoins, so that the partial aggregates are
+            // pushed below the reordered joins
+            Arc::new(EagerAggregation::new()),
             Arc::new(SingleDistinctToGroupBy::new()),
             // The previous optimizations added expressions and projections,
             // that might benefit from the following rules
             Arc::new(EliminateGroupByConstant::new()),
+            Arc::new(EliminateDependentGroupBy::new()),
             Arc::new(CommonSubexprEliminate::new()),
             Arc::new(OptimizeProjections::new()),
         ];
@@ -319,6 +341,9 @@ impl Optimizer {
         plan.check_invariants(InvariantLevel::Executable)
             .map_err(|e| e.context("Invalid input plan before LP Optimizers"))?;
 
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("optimize").entered();
+
         let start_time = Instant::now();
         let options = config.options();
         let mut new_plan = plan;
@@ -333,6 +358,11 @@ impl Optimizer {
             log_plan(&format!("Optimizer input (pass {i})"), &new_plan);
 
             for rule in &self.rules {
+                #[cfg(feature = "tracing")]
+                let _span =
+                    tracing::info_span!("optimizer_rule", rule = rule.name(), pass = i)
+                        .entered();
+
                 // If skipping failed rules, copy plan before attempting to rewrite
                 // as rewriting is destructive
                 let prev_plan = options
@@ -567,35 +567,244 @@ fn infer_join_predicates(
     predicates: &[Expr],
     on_filters: &[Expr],
 ) -> Result<Vec<Expr>> {
+    let join_type = join.join_type;
+
     // Only allow both side key is column.
-    let join_col_keys = join
-        .on
-        .iter()
-        .filter_map(|(l, r)| {
-            let left_col = l.try_as_col()?;
-            let right_col = r.try_as_col()?;
-            Some((left_col, right_col))
-        })
-        .collect::<Vec<_>>();
+    let mut equivalences = JoinEquivalences::default();
+    for (l, r) in &join.on {
+        if let (Some(l), Some(r)) = (l.try_as_col(), r.try_as_col()) {
+            equivalences.add(l, r);
+        }
+    }
+    if join_type == JoinType::Inner {
+        predicates
+            .iter()
+            .chain(on_filters)
+            .for_each(|predicate| equivalences.add_equality(predicate));
+    }
 
-    let join_type = join.join_type;
+    // The predicates already known to hold for the rows of the inputs
+    let mut input_constraints = vec![];
+    collect_input_constraints(&join.left, &mut equivalences, &mut input_constraints);
+    collect_input_constraints(&join.right, &mut equivalences, &mut input_constraints);
 
+    let join_schemas = (join.left.schema().as_ref(), join.right.schema().as_ref());
     let mut inferred_predicates = InferredPredicates::new(join_type);
 
     infer_join_predicates_from_predicates(
-        &join_col_keys,
+        join_schemas,
+        &equivalences,
         predicates,
         &mut inferred_predicates,
     )?;
 
+    // The constraints of the inputs are inferred like the join filter, as
+    // they do not filter the rows of the preserved side of an outer join
+    let on_filters = on_filters
+        .iter()
+        .chain(&input_constraints)
+        .cloned()
+        .collect::<Vec<_>>();
     infer_join_predicates_from_on_filters(
-        &join_col_keys,
+        join_schemas,
+        &equivalences,
         join_type,
-        on_filters,
+        &on_filters,
         &mut inferred_predicates,
     )?;
 
-    Ok(inferred_predicates.predicates)
+    // Skip the predicates already applied to the inputs
+    let mut predicates = inferred_predicates.predicates;
+    predicates.retain(|predicate| !input_constraints.contains(predicate));
+    Ok(predicates.into_iter().unique().collect())
+}
+
+/// Sets of columns that are equal in every row of a join, built from the
+/// equi-join keys and the column equalities of the join and of its inputs.
+/// e.g. for
+/// ```text
+/// SELECT * FROM a JOIN b ON a.k = b.k JOIN c ON b.k = c.k WHERE a.k > 10
+/// ```
+/// `a.k`, `b.k` and `c.k` are in the same set, so `c.k > 10` can be inferred
+/// for the scan of `c`.
+#[derive(Debug, Default)]
+struct JoinEquivalences {
+    sets: Vec<IndexSet<Column>>,
+}
+
+impl JoinEquivalences {
+    /// Records that `left` and `right` are equal
+    fn add(&mut self, left: &Column, right: &Column) {
+        let left_set = self.sets.iter().position(|set| set.contains(left));
+        let right_set = self.sets.iter().position(|set| set.contains(right));
+        match (left_set, right_set) {
+            (Some(l), Some(r)) if l == r => {}
+            (Some(l), Some(r)) => {
+                let removed = self.sets.swap_remove(l.max(r));
+                self.sets[l.min(r)].extend(removed);
+            }
+            (Some(l), None) => {
+                self.sets[l].insert(right.clone());
+            }
+            (None, Some(r)) => {
+                self.sets[r].insert(left.clone());
+            }
+            (None, None) => {
+                self.sets
+                    .push(IndexSet::from([left.clone(), right.clone()]));
+            }
+        }
+    }
+
+    /// Records the columns compared by `predicate`, if it is `col1 = col2`
+    fn add_equality(&mut self, predicate: &Expr) {
+        if let Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) = predicate
+        {
+            if let (Expr::Column(left), Expr::Column(right)) =
+                (left.as_ref(), right.as_ref())
+            {
+                self.add(left, right);
+            }
+        }
+    }
+
+    /// Returns a column of `schema` equal to `column`, if any
+    fn find_in(&self, column: &Column, schema: &DFSchema) -> Option<&Column> {
+        self.sets
+            .iter()
+            .find(|set| set.contains(column))?
+            .iter()
+            .find(|equivalent| schema.has_column(equivalent))
+    }
+}
+
+/// Collects the predicates that hold for every row of `plan` into
+/// `constraints`, and its column equalities into `equivalences`, looking
+/// through filters, inner joins, projections and aliases
+fn collect_input_constraints(
+    plan: &LogicalPlan,
+    equivalences: &mut JoinEquivalences,
+    constraints: &mut Vec<Expr>,
+) {
+    let mut add_predicate = |predicate: &Expr| {
+        equivalences.add_equality(predicate);
+        if !predicate.is_volatile() && !contains_subquery(predicate) {
+            constraints.push(predicate.clone());
+        }
+    };
+    match plan {
+        LogicalPlan::Filter(filter) => {
+            split_conjunction(&filter.predicate)
+                .into_iter()
+                .for_each(&mut add_predicate);
+            collect_input_constraints(&filter.input, equivalences, constraints);
+        }
+        LogicalPlan::TableScan(scan) => {
+            scan.filters
+                .iter()
+                .flat_map(split_conjunction)
+                .for_each(&mut add_predicate);
+        }
+        LogicalPlan::Join(join) if join.join_type == JoinType::Inner => {
+            if let Some(filter) = &join.filter {
+                split_conjunction(filter)
+                    .into_iter()
+                    .for_each(&mut add_predicate);
+            }
+            for (l, r) in &join.on {
+                if let (Some(l), Some(r)) = (l.try_as_col(), r.try_as_col()) {
+                    equivalences.add(l, r);
+                }
+            }
+            collect_input_constraints(&join.left, equivalences, constraints);
+            collect_input_constraints(&join.right, equivalences, constraints);
+        }
+        LogicalPlan::Projection(projection) => {
+            // Only the columns passed through unchanged are known
+            let columns = projection
+                .expr
+                .iter()
+                .zip(projection.schema.columns())
+                .filter_map(|(expr, output)| {
+                    let input = match expr {
+                        Expr::Column(input) => input,
+                        Expr::Alias(alias) => alias.expr.try_as_col()?,
+                        _ => return None,
+                    };
+                    Some((input.clone(), output))
+                })
+                .collect();
+            collect_renamed_input_constraints(
+                &projection.input,
+                &columns,
+                equivalences,
+                constraints,
+            );
+        }
+        LogicalPlan::SubqueryAlias(alias) => {
+            let columns = alias
+                .input
+                .schema()
+                .columns()
+                .into_iter()
+                .zip(alias.schema.columns())
+                .collect();
+            collect_renamed_input_constraints(
+                &alias.input,
+                &columns,
+                equivalences,
+                constraints,
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Collects the constraints of `input` like [`collect_input_constraints`],
+/// renaming its columns by `columns`. The constraints on the other columns
+/// are ignored.
+fn collect_renamed_input_constraints(
+    input: &LogicalPlan,
+    columns: &HashMap<Column, Column>,
+    equivalences: &mut JoinEquivalences,
+    constraints: &mut Vec<Expr>,
+) {
+    let mut input_equivalences = JoinEquivalences::default();
+    let mut input_constraints = vec![];
+    collect_input_constraints(input, &mut input_equivalences, &mut input_constraints);
+    for set in input_equivalences.sets {
+        let mut set = set.iter().filter_map(|col| columns.get(col));
+        if let Some(first) = set.next() {
+            set.for_each(|col| equivalences.add(first, col));
+        }
+    }
+    for predicate in input_constraints {
+        let column_refs = predicate.column_refs();
+        if column_refs.iter().all(|col| columns.contains_key(col)) {
+            let replace_map = column_refs
+                .into_iter()
+                .map(|col| (col, &columns[col]))
+                .collect::<HashMap<_, _>>();
+            if let Ok(predicate) = replace_col(predicate.clone(), &replace_map) {
+                constraints.push(predicate);
+            }
+        }
+    }
+}
+
+/// Returns true if `expr` contains a subquery
+fn contains_subquery(expr: &Expr) -> bool {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::Exists(_) | Expr::InSubquery(_) | Expr::ScalarSubquery(_)
+        ))
+    })
+    .unwrap_or(true)
 }
 
 /// Inferred predicates collector.
@@ -643,19 +852,23 @@ impl InferredPredicates {
 /// Infer predicates from the pushed down predicates.
 ///
 /// Parameters
-/// * `join_col_keys` column pairs from the join ON clause
+/// * `join_schemas` the schemas of the left and right join inputs
+///
+/// * `equivalences` the columns equal in the rows of the join
 ///
 /// * `predicates` the pushed down predicates
 ///
 /// * `inferred_predicates` the inferred results
 ///
 fn infer_join_predicates_from_predicates(
-    join_col_keys: &[(&Column, &Column)],
+    join_schemas: (&DFSchema, &DFSchema),
+    equivalences: &JoinEquivalences,
     predicates: &[Expr],
     inferred_predicates: &mut InferredPredicates,
 ) -> Result<()> {
     infer_join_predicates_impl::<true, true>(
-        join_col_keys,
+        join_schemas,
+        equivalences,
         predicates,
         inferred_predicates,
     )
@@ -664,7 +877,9 @@ fn infer_join_predicates_from_predicates(
 /// Infer predicates from the join filter.
 ///
 /// Parameters
-/// * `join_col_keys` column pairs from the join ON clause
+/// * `join_schemas` the schemas of the left and right join inputs
+///
+/// * `equivalences` the columns equal in the rows of the join
 ///
 /// * `join_type` the JoinType of Join
 ///
@@ -674,7 +889,8 @@ fn infer_join_predicates_from_predicates(
 /// * `inferred_predicates` the inferred results
 ///
 fn infer_join_predicates_from_on_filters(
-    join_col_keys: &[(&Column, &Column)],
+    join_schemas: (&DFSchema, &DFSchema),
+    equivalences: &JoinEquivalences,
     join_type: JoinType,
     on_filters: &[Expr],
     inferred_predicates: &mut InferredPredicates,
@@ -682,20 +898,23 @@ fn infer_join_predicates_from_on_filters(
     match join_type {
         JoinType::Full | JoinType::LeftAnti | JoinType::RightAnti => Ok(()),
         JoinType::Inner => infer_join_predicates_impl::<true, true>(
-            join_col_keys,
+            join_schemas,
+            equivalences,
             on_filters,
             inferred_predicates,
         ),
         JoinType::Left | JoinType::LeftSemi | JoinType::LeftMark => {
             infer_join_predicates_impl::<true, false>(
-                join_col_keys,
+                join_schemas,
+                equivalences,
                 on_filters,
                 inferred_predicates,
             )
         }
         JoinType::Right | JoinType::RightSemi | JoinType::RightMark => {
             infer_join_predicates_impl::<false, true>(
-                join_col_keys,
+                join_schemas,
+                equivalences,
                 on_filters,
                 inferred_predicates,
             )
@@ -706,7 +925,9 @@ fn infer_join_predicates_from_on_filters(
 /// Infer predicates from the given predicates.
 ///
 /// Parameters
-/// * `join_col_keys` column pairs from the join ON clause
+/// * `join_schemas` the schemas of the left and right join inputs
+///
+/// * `equivalences` the columns equal in the rows of the join
 ///
 /// * `input_predicates` the given predicates. It can be the pushed down predicates,
 ///   or it can be the filters of the Join
@@ -723,23 +944,27 @@ fn infer_join_predicates_impl<
     const ENABLE_LEFT_TO_RIGHT: bool,
     const ENABLE_RIGHT_TO_LEFT: bool,
 >(
-    join_col_keys: &[(&Column, &Column)],
+    (left_schema, right_schema): (&DFSchema, &DFSchema),
+    equivalences: &JoinEquivalences,
     input_predicates: &[Expr],
     inferred_predicates: &mut InferredPredicates,
 ) -> Result<()> {
     for predicate in input_predicates {
+        if predicate.is_volatile() {
+            continue;
+        }
         let mut join_cols_to_replace = HashMap::new();
 
-        for &col in &predicate.column_refs() {
-            for (l, r) in join_col_keys.iter() {
-                if ENABLE_LEFT_TO_RIGHT && col == *l {
-                    join_cols_to_replace.insert(col, *r);
-                    break;
-                }
-                if ENABLE_RIGHT_TO_LEFT && col == *r {
-                    join_cols_to_replace.insert(col, *l);
-                    break;
-                }
+        for col in predicate.column_refs() {
+            let equivalent = if ENABLE_LEFT_TO_RIGHT && left_schema.has_column(col) {
+                equivalences.find_in(col, right_schema)
+            } else if ENABLE_RIGHT_TO_LEFT && right_schema.has_column(col) {
+                equivalences.find_in(col, left_schema)
+            } else {
+                None
+            };
+            if let Some(equivalent) = equivalent {
+                join_cols_to_replace.insert(col, equivalent);
             }
         }
         if join_cols_to_replace.is_empty() {
@@ -2591,6 +2816,127 @@ mod tests {
         )
     }
 
+    /// predicate on one side of the join is inferred for the other side
+    /// from an equality of the filter
+    #[test]
+    fn infer_predicate_from_filter_equality() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan_with_name("test2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .cross_join(right)?
+            .filter(
+                col("test.a")
+                    .eq(col("test2.a"))
+                    .and(col("test.a").gt(lit(10i64))),
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Inner Join:  Filter: test.a = test2.a
+          TableScan: test, full_filters=[test.a > Int64(10)]
+          TableScan: test2, full_filters=[test2.a > Int64(10)]
+        "
+        )
+    }
+
+    /// predicate is inferred through the join keys of the join inputs
+    #[test]
+    fn infer_predicate_through_join_chain() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                test_table_scan_with_name("test2")?,
+                JoinType::Inner,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .join(
+                test_table_scan_with_name("test3")?,
+                JoinType::Inner,
+                (vec![Column::from("test2.a")], vec![Column::from("test3.a")]),
+                None,
+            )?
+            .filter(col("test.a").gt(lit(10i64)))?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Inner Join: test2.a = test3.a
+          Inner Join: test.a = test2.a
+            TableScan: test, full_filters=[test.a > Int64(10)]
+            TableScan: test2, full_filters=[test2.a > Int64(10)]
+          TableScan: test3, full_filters=[test3.a > Int64(10)]
+        "
+        )
+    }
+
+    /// IN list and IS NOT NULL predicates already filtering a join input
+    /// are inferred for the other input
+    #[test]
+    fn infer_predicate_from_join_input() -> Result<()> {
+        let left = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(
+                col("a")
+                    .in_list(vec![lit(1i64), lit(2i64)], false)
+                    .and(col("b").is_not_null()),
+            )?
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let right = LogicalPlanBuilder::from(test_table_scan_with_name("test2")?)
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join(
+                right,
+                JoinType::Inner,
+                (
+                    vec![Column::from_name("a"), Column::from_name("b")],
+                    vec![Column::from_name("a"), Column::from_name("b")],
+                ),
+                None,
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Inner Join: test.a = test2.a, test.b = test2.b
+          Projection: test.a, test.b
+            TableScan: test, full_filters=[test.a IN ([Int64(1), Int64(2)]), test.b IS NOT NULL]
+          Projection: test2.a, test2.b
+            TableScan: test2, full_filters=[test2.a IN ([Int64(1), Int64(2)]), test2.b IS NOT NULL]
+        "
+        )
+    }
+
+    /// predicates filtering the preserved side of an outer join are not
+    /// inferred for the other side
+    #[test]
+    fn infer_predicate_from_left_join_input() -> Result<()> {
+        let right = LogicalPlanBuilder::from(test_table_scan_with_name("test2")?)
+            .filter(col("a").gt(lit(10i64)))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                right,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Left Join: test.a = test2.a
+          TableScan: test
+          TableScan: test2, full_filters=[test2.a > Int64(10)]
+        "
+        )
+    }
+
     /// post-join predicates on the left side of a right join are not duplicated
     #[test]
     fn filter_using_right_join() -> Result<()> {
@@ -0,0 +1,995 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ReorderJoins`] reorders trees of inner joins based on their estimated cost
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::cardinality::CardinalityEstimator;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{DFSchemaRef, NullEquality, Result};
+use datafusion_expr::logical_plan::{
+    Join, JoinConstraint, JoinType, LogicalPlan, Projection,
+};
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::Expr;
+
+/// The maximum number of inputs of a tree of joins that can be reordered
+const MAX_JOIN_INPUTS: usize = u64::BITS as usize;
+
+/// The relative decrease of the estimated cost of a tree of joins required
+/// to reorder it, so that rounding errors don't cause plans of equal cost to
+/// be reordered back and forth
+const MIN_COST_DECREASE: f64 = 1e-6;
+
+/// Reorders the inputs of trees of inner joins to minimize the estimated
+/// size of the intermediate join results.
+///
+/// The inputs of a tree of inner joins are the vertices of a join graph, whose
+/// edges are the equijoin predicates between two inputs. The number of rows
+/// of the join of a set of inputs is estimated from the number of rows of the
+/// inputs and the selectivity of the predicates between them, using the
+/// [`Statistics`] of the scanned tables: the selectivity of an equijoin
+/// predicate is `1 / max(distinct values of each side)`, and the default
+/// filter selectivity is used for the other predicates. The cost of a join
+/// order is the sum of the estimated number of rows of all its joins.
+///
+/// The join order with the smallest cost is found using the DPccp algorithm
+/// from [Analysis of Two Existing and One New Dynamic Programming Algorithm
+/// for the Generation of Optimal Bushy Join Trees without Cross Products]
+/// when the number of inputs is at most
+/// `datafusion.optimizer.join_reordering_dp_threshold`. For larger trees of
+/// joins, the inputs are joined greedily, starting with the pair of inputs
+/// with the smallest join result.
+///
+/// The joins are only reordered if the number of rows of all their inputs
+/// can be estimated, the join graph is connected (i.e. has no cross joins),
+/// and the new join order has a smaller estimated cost than the original one.
+/// A projection restores the original order of the columns.
+///
+/// # Example
+///
+/// With a large `fact` table, and a `d2` table smaller than `d1`:
+/// ```text
+/// Inner Join: fact.d2 = d2.id
+///   Inner Join: fact.d1 = d1.id
+///     TableScan: fact
+///     TableScan: d1
+///   TableScan: d2
+/// ```
+///
+/// Is rewritten to join `fact` with `d2` first:
+/// ```text
+/// Projection: fact.d1, fact.d2, d1.id, d2.id
+///   Inner Join: fact.d1 = d1.id
+///     Inner Join: d2.id = fact.d2
+///       TableScan: d2
+///       TableScan: fact
+///     TableScan: d1
+/// ```
+///
+/// [`Statistics`]: datafusion_common::Statistics
+/// [Analysis of Two Existing and One New Dynamic Programming Algorithm for the Generation of Optimal Bushy Join Trees without Cross Products]: https://www.vldb.org/conf/2006/p930-moerkotte.pdf
+#[derive(Default, Debug)]
+pub struct ReorderJoins;
+
+impl ReorderJoins {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for ReorderJoins {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    #[cfg_attr(feature = "recursive_protection", recursive::recursive)]
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let options = &config.options().optimizer;
+        if !options.enable_join_reordering {
+            return Ok(Transformed::no(plan));
+        }
+
+        if !is_reorderable_join(&plan) {
+            return plan.map_children(|input| self.rewrite(input, config));
+        }
+
+        // Reorder the joins of the inputs first
+        let estimator = CardinalityEstimator::new(config.options());
+        rewrite_join_inputs(self, plan, config)?.transform_data(|plan| {
+            let Some(graph) = JoinGraph::try_new(&plan, &estimator)? else {
+                return Ok(Transformed::no(plan));
+            };
+            match graph.reorder(options.join_reordering_dp_threshold)? {
+                Some(reordered) => Ok(Transformed::yes(reordered)),
+                None => Ok(Transformed::no(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "reorder_joins"
+    }
+}
+
+/// Returns true if the plan is a join that can be reordered with its inputs
+fn is_reorderable_join(plan: &LogicalPlan) -> bool {
+    matches!(
+        plan,
+        LogicalPlan::Join(Join {
+            join_type: JoinType::Inner,
+            join_constraint: JoinConstraint::On,
+            null_equality: NullEquality::NullEqualsNothing,
+            ..
+        })
+    )
+}
+
+/// Rewrites the inputs of a tree of reorderable joins
+fn rewrite_join_inputs(
+    optimizer: &ReorderJoins,
+    plan: LogicalPlan,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    plan.map_children(|input| {
+        if is_reorderable_join(&input) {
+            rewrite_join_inputs(optimizer, input, config)
+        } else {
+            optimizer.rewrite(input, config)
+        }
+    })
+}
+
+/// A join predicate of the form `left = right`
+struct Equijoin {
+    left: Expr,
+    right: Expr,
+    /// The inputs referenced by `left`
+    left_inputs: u64,
+    /// The inputs referenced by `right`
+    right_inputs: u64,
+}
+
+/// A join predicate with its estimated selectivity
+struct Predicate {
+    /// The inputs referenced by the predicate
+    inputs: u64,
+    selectivity: f64,
+}
+
+/// The best join order found for a set of inputs
+struct JoinOrder {
+    /// The estimated cost of the joins of the set
+    cost: f64,
+    /// The estimated number of rows of the join of the set
+    num_rows: f64,
+    /// The two subsets joined last, or `None` if the set is a single input
+    children: Option<(u64, u64)>,
+}
+
+/// The inputs and predicates of a tree of inner joins. Sets of inputs are
+/// represented as bitmaps of their indices.
+struct JoinGraph {
+    /// The schema of the original tree of joins
+    schema: DFSchemaRef,
+    /// The inputs of the joins, in the order of the original plan
+    inputs: Vec<Arc<LogicalPlan>>,
+    /// The estimated number of rows of each input
+    input_rows: Vec<f64>,
+    /// The equijoin predicates of the joins
+    equijoins: Vec<Equijoin>,
+    /// The other predicates of the joins
+    filters: Vec<(Expr, u64)>,
+    /// The selectivity of all predicates
+    predicates: Vec<Predicate>,
+    /// For each input, the inputs it shares an equijoin predicate with
+    neighbors: Vec<u64>,
+    /// The sets of inputs joined by each join of the original plan
+    original_joins: Vec<u64>,
+}
+
+impl JoinGraph {
+    /// Builds the join graph of a tree of joins, or returns `None` if the
+    /// joins can't be reordered
+    fn try_new(
+        plan: &LogicalPlan,
+        estimator: &CardinalityEstimator,
+    ) -> Result<Option<Self>> {
+        let LogicalPlan::Join(join) = plan else {
+            return Ok(None);
+        };
+        let mut inputs = vec![];
+        let mut on = vec![];
+        let mut filters = vec![];
+        let mut original_joins = vec![];
+        flatten_joins(
+            join,
+            &mut inputs,
+            &mut on,
+            &mut filters,
+            &mut original_joins,
+        );
+
+        // Two inputs are ordered when planning the physical join
+        if inputs.len() <= 2 || inputs.len() > MAX_JOIN_INPUTS {
+            return Ok(None);
+        }
+        let Some(input_rows) = inputs
+            .iter()
+            .map(|input| estimator.num_rows(input))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(None);
+        };
+
+        let mut graph = Self {
+            schema: Arc::clone(plan.schema()),
+            inputs,
+            input_rows,
+            equijoins: vec![],
+            filters: vec![],
+            predicates: vec![],
+            neighbors: vec![],
+            original_joins: original_joins
+                .into_iter()
+                .map(|(start, end)| range_set(start, end))
+                .collect(),
+        };
+        graph.neighbors = vec![0; graph.inputs.len()];
+
+        for (left, right) in on {
+            let (Some(left_inputs), Some(right_inputs)) =
+                (graph.inputs_of(&left), graph.inputs_of(&right))
+            else {
+                return Ok(None);
+            };
+            let inputs = left_inputs | right_inputs;
+            if inputs.count_ones() < 2 {
+                return Ok(None);
+            }
+
+            let selectivity = if left_inputs.count_ones() == 1
+                && right_inputs.count_ones() == 1
+            {
+                let (left_index, right_index) = (
+                    left_inputs.trailing_zeros() as usize,
+                    right_inputs.trailing_zeros() as usize,
+                );
+                graph.neighbors[left_index] |= right_inputs;
+                graph.neighbors[right_index] |= left_inputs;
+                let left_distinct = graph.distinct_count(estimator, left_index, &left);
+                let right_distinct = graph.distinct_count(estimator, right_index, &right);
+                1.0 / left_distinct.max(right_distinct).max(1.0)
+            } else {
+                estimator.default_selectivity()
+            };
+            graph.predicates.push(Predicate {
+                inputs,
+                selectivity,
+            });
+            graph.equijoins.push(Equijoin {
+                left,
+                right,
+                left_inputs,
+                right_inputs,
+            });
+        }
+
+        for filter in filters {
+            if filter.is_volatile() {
+                return Ok(None);
+            }
+            let Some(inputs) = graph.inputs_of(&filter) else {
+                return Ok(None);
+            };
+            if inputs.count_ones() < 2 {
+                return Ok(None);
+            }
+            graph.predicates.push(Predicate {
+                inputs,
+                selectivity: estimator.default_selectivity(),
+            });
+            graph.filters.push((filter, inputs));
+        }
+
+        if !graph.is_connected() {
+            return Ok(None);
+        }
+        Ok(Some(graph))
+    }
+
+    /// Returns the set of inputs referenced by an expression, or `None` if
+    /// one of its columns isn't found in exactly one input
+    fn inputs_of(&self, expr: &Expr) -> Option<u64> {
+        let mut inputs = 0;
+        for column in expr.column_refs() {
+            let mut matches = self
+                .inputs
+                .iter()
+                .enumerate()
+                .filter(|(_, input)| input.schema().has_column(column));
+            let (index, _) = matches.next()?;
+            if matches.next().is_some() {
+                return None;
+            }
+            inputs |= 1 << index;
+        }
+        (inputs != 0).then_some(inputs)
+    }
+
+    /// Returns the estimated number of distinct values of a join key of an
+    /// input, which is at most its number of rows
+    fn distinct_count(
+        &self,
+        estimator: &CardinalityEstimator,
+        index: usize,
+        expr: &Expr,
+    ) -> f64 {
+        estimator.expr_distinct_count(&self.inputs[index], expr, self.input_rows[index])
+    }
+
+    fn all_inputs(&self) -> u64 {
+        range_set(0, self.inputs.len())
+    }
+
+    /// Returns the inputs sharing an equijoin predicate with a set of inputs
+    /// but not in the set
+    fn neighbors_of(&self, set: u64) -> u64 {
+        inputs_in(set).fold(0, |neighbors, index| neighbors | self.neighbors[index])
+            & !set
+    }
+
+    fn is_connected(&self) -> bool {
+        let mut connected = 1;
+        loop {
+            let neighbors = self.neighbors_of(connected);
+            if neighbors == 0 {
+                return connected == self.all_inputs();
+            }
+            connected |= neighbors;
+        }
+    }
+
+    /// Returns the estimated number of rows of the join of a set of inputs
+    fn num_rows(&self, set: u64) -> f64 {
+        let input_rows = inputs_in(set)
+            .map(|index| self.input_rows[index])
+            .product::<f64>();
+        self.predicates
+            .iter()
+            .filter(|predicate| is_subset(predicate.inputs, set))
+            .fold(input_rows, |num_rows, predicate| {
+                num_rows * predicate.selectivity
+            })
+    }
+
+    /// Returns the reordered joins, or `None` if their estimated cost isn't
+    /// smaller than the cost of the original join order
+    fn reorder(&self, dp_threshold: usize) -> Result<Option<LogicalPlan>> {
+        let join_orders = if self.inputs.len() <= dp_threshold {
+            self.enumerate_join_orders()
+        } else {
+            self.greedy_join_order()
+        };
+        let Some(best) = join_orders.get(&self.all_inputs()) else {
+            return Ok(None);
+        };
+
+        let original_cost = self
+            .original_joins
+            .iter()
+            .map(|set| self.num_rows(*set))
+            .sum::<f64>();
+        if best.cost >= original_cost * (1.0 - MIN_COST_DECREASE) {
+            return Ok(None);
+        }
+
+        let plan = Arc::unwrap_or_clone(self.build(&join_orders, self.all_inputs())?);
+        if plan.schema() == &self.schema {
+            return Ok(Some(plan));
+        }
+        Ok(Some(LogicalPlan::Projection(Projection::new_from_schema(
+            Arc::new(plan),
+            Arc::clone(&self.schema),
+        ))))
+    }
+
+    /// Returns the join orders of the single inputs
+    fn input_join_orders(&self) -> HashMap<u64, JoinOrder> {
+        self.input_rows
+            .iter()
+            .enumerate()
+            .map(|(index, num_rows)| {
+                let join_order = JoinOrder {
+                    cost: 0.0,
+                    num_rows: *num_rows,
+                    children: None,
+                };
+                (1 << index, join_order)
+            })
+            .collect()
+    }
+
+    /// Updates the best join order of the union of two sets of inputs with
+    /// the join of their best join orders, if it has a smaller cost
+    fn join(&self, join_orders: &mut HashMap<u64, JoinOrder>, left: u64, right: u64) {
+        let set = left | right;
+        let num_rows = join_orders
+            .get(&set)
+            .map_or_else(|| self.num_rows(set), |join_order| join_order.num_rows);
+        let cost = join_orders[&left].cost + join_orders[&right].cost + num_rows;
+        if join_orders
+            .get(&set)
+            .is_none_or(|join_order| cost < join_order.cost)
+        {
+            let join_order = JoinOrder {
+                cost,
+                num_rows,
+                children: Some((left, right)),
+            };
+            join_orders.insert(set, join_order);
+        }
+    }
+
+    /// Finds the best join order of every connected set of inputs with the
+    /// DPccp algorithm, which enumerates the pairs of connected sets of
+    /// inputs that are connected to each other
+    fn enumerate_join_orders(&self) -> HashMap<u64, JoinOrder> {
+        let mut pairs = vec![];
+        for index in (0..self.inputs.len()).rev() {
+            let start = 1 << index;
+            let mut connected_sets = vec![start];
+            self.enumerate_connected_sets(
+                start,
+                range_set(0, index + 1),
+                &mut connected_sets,
+            );
+            for set in connected_sets {
+                self.enumerate_complements(set, &mut pairs);
+            }
+        }
+
+        // The best join orders of both sets of a pair must be known before
+        // the pair is joined
+        pairs.sort_by_key(|(left, right)| (left | right).count_ones());
+        let mut join_orders = self.input_join_orders();
+        for (left, right) in pairs {
+            self.join(&mut join_orders, left, right);
+        }
+        join_orders
+    }
+
+    /// Enumerates the connected sets of inputs that extend `set` with
+    /// inputs not in `excluded`
+    fn enumerate_connected_sets(&self, set: u64, excluded: u64, sets: &mut Vec<u64>) {
+        let neighbors = self.neighbors_of(set) & !excluded;
+        for subset in subsets_of(neighbors) {
+            sets.push(set | subset);
+        }
+        for subset in subsets_of(neighbors) {
+            self.enumerate_connected_sets(set | subset, excluded | neighbors, sets);
+        }
+    }
+
+    /// Enumerates the connected sets of inputs that are connected to `set`,
+    /// and don't contain inputs with a smaller index than the smallest input
+    /// of `set`, so that every pair is only enumerated once
+    fn enumerate_complements(&self, set: u64, pairs: &mut Vec<(u64, u64)>) {
+        let excluded = range_set(0, set.trailing_zeros() as usize + 1) | set;
+        let neighbors = self.neighbors_of(set) & !excluded;
+        for index in inputs_in(neighbors).rev() {
+            let start = 1 << index;
+            let mut complements = vec![start];
+            self.enumerate_connected_sets(
+                start,
+                excluded | (range_set(0, index + 1) & neighbors),
+                &mut complements,
+            );
+            pairs.extend(complements.into_iter().map(|complement| (set, complement)));
+        }
+    }
+
+    /// Finds a join order by repeatedly joining the two connected sets of
+    /// inputs with the smallest estimated join result
+    fn greedy_join_order(&self) -> HashMap<u64, JoinOrder> {
+        let mut join_orders = self.input_join_orders();
+        let mut sets = (0..self.inputs.len())
+            .map(|index| 1 << index)
+            .collect::<Vec<u64>>();
+        while sets.len() > 1 {
+            let mut smallest: Option<(usize, usize, f64)> = None;
+            for i in 0..sets.len() {
+                let neighbors = self.neighbors_of(sets[i]);
+                for j in i + 1..sets.len() {
+                    if neighbors & sets[j] == 0 {
+                        continue;
+                    }
+                    let num_rows = self.num_rows(sets[i] | sets[j]);
+                    if smallest.is_none_or(|(_, _, smallest)| num_rows < smallest) {
+                        smallest = Some((i, j, num_rows));
+                    }
+                }
+            }
+            let Some((i, j, _)) = smallest else {
+                break;
+            };
+            self.join(&mut join_orders, sets[i], sets[j]);
+            sets[i] |= sets[j];
+            sets.swap_remove(j);
+        }
+        join_orders
+    }
+
+    /// Builds the plan of the best join order of a set of inputs
+    fn build(
+        &self,
+        join_orders: &HashMap<u64, JoinOrder>,
+        set: u64,
+    ) -> Result<Arc<LogicalPlan>> {
+        let Some((left, right)) = join_orders[&set].children else {
+            return Ok(Arc::clone(&self.inputs[set.trailing_zeros() as usize]));
+        };
+        // The smaller input is the build side of the hash join
+        let (left, right) = if join_orders[&right].num_rows < join_orders[&left].num_rows
+        {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        // Each predicate is evaluated by the first join of all its inputs
+        let mut on = vec![];
+        let mut filters = vec![];
+        for equijoin in &self.equijoins {
+            if is_subset(equijoin.left_inputs, left)
+                && is_subset(equijoin.right_inputs, right)
+            {
+                on.push((equijoin.left.clone(), equijoin.right.clone()));
+            } else if is_subset(equijoin.left_inputs, right)
+                && is_subset(equijoin.right_inputs, left)
+            {
+                on.push((equijoin.right.clone(), equijoin.left.clone()));
+            } else if joins(equijoin.left_inputs | equijoin.right_inputs, left, right) {
+                filters.push(equijoin.left.clone().eq(equijoin.right.clone()));
+            }
+        }
+        for (filter, inputs) in &self.filters {
+            if joins(*inputs, left, right) {
+                filters.push(filter.clone());
+            }
+        }
+
+        let join = Join::try_new(
+            self.build(join_orders, left)?,
+            self.build(join_orders, right)?,
+            on,
+            conjunction(filters),
+            JoinType::Inner,
+            JoinConstraint::On,
+            NullEquality::NullEqualsNothing,
+        )?;
+        Ok(Arc::new(LogicalPlan::Join(join)))
+    }
+}
+
+/// Collects the inputs and predicates of a tree of reorderable joins, and
+/// the range of inputs of each join
+fn flatten_joins(
+    join: &Join,
+    inputs: &mut Vec<Arc<LogicalPlan>>,
+    on: &mut Vec<(Expr, Expr)>,
+    filters: &mut Vec<Expr>,
+    joins: &mut Vec<(usize, usize)>,
+) {
+    let start = inputs.len();
+    for input in [&join.left, &join.right] {
+        match input.as_ref() {
+            LogicalPlan::Join(input_join) if is_reorderable_join(input) => {
+                flatten_joins(input_join, inputs, on, filters, joins)
+            }
+            _ => inputs.push(Arc::clone(input)),
+        }
+    }
+    on.extend(join.on.iter().cloned());
+    if let Some(filter) = &join.filter {
+        filters.extend(split_conjunction(filter).into_iter().cloned());
+    }
+    joins.push((start, inputs.len()));
+}
+
+/// Returns the set of the inputs from `start` (inclusive) to `end` (exclusive)
+fn range_set(start: usize, end: usize) -> u64 {
+    let below = |index: usize| {
+        if index >= MAX_JOIN_INPUTS {
+            u64::MAX
+        } else {
+            (1 << index) - 1
+        }
+    };
+    below(end) & !below(start)
+}
+
+/// Returns the indices of the inputs of a set, in increasing order
+fn inputs_in(set: u64) -> impl DoubleEndedIterator<Item = usize> {
+    (0..MAX_JOIN_INPUTS).filter(move |index| set & (1 << index) != 0)
+}
+
+/// Returns the non empty subsets of a set
+fn subsets_of(set: u64) -> impl Iterator<Item = u64> {
+    let mut subset = set;
+    std::iter::from_fn(move || {
+        if subset == 0 {
+            return None;
+        }
+        let current = subset;
+        subset = (subset - 1) & set;
+        Some(current)
+    })
+}
+
+fn is_subset(subset: u64, set: u64) -> bool {
+    subset & !set == 0
+}
+
+/// Returns true if a predicate on `inputs` is first evaluated by the join of
+/// `left` and `right`
+fn joins(inputs: u64, left: u64, right: u64) -> bool {
+    is_subset(inputs, left | right)
+        && !is_subset(inputs, left)
+        && !is_subset(inputs, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptimizerContext;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use chrono::{DateTime, Utc};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::stats::Precision;
+    use datafusion_common::{ColumnStatistics, Statistics};
+    use datafusion_expr::{col, lit, LogicalPlanBuilder, TableSource};
+    use insta::assert_snapshot;
+    use std::any::Any;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let starting_schema = Arc::clone($plan.schema());
+            let optimized_plan = reorder($plan, 12)?;
+            // Verify the schema remains unchanged
+            assert_eq!(&starting_schema, optimized_plan.schema());
+            assert_snapshot!(
+                optimized_plan.display_indent(),
+                @ $expected,
+            );
+
+            Ok(())
+        }};
+    }
+
+    struct TestConfig {
+        alias_generator: Arc<AliasGenerator>,
+        options: ConfigOptions,
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> DateTime<Utc> {
+            Utc::now()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            &self.alias_generator
+        }
+
+        fn options(&self) -> &ConfigOptions {
+            &self.options
+        }
+    }
+
+    fn reorder(plan: LogicalPlan, dp_threshold: usize) -> Result<LogicalPlan> {
+        let mut options = ConfigOptions::default();
+        options.optimizer.enable_join_reordering = true;
+        options.optimizer.join_reordering_dp_threshold = dp_threshold;
+        let config = TestConfig {
+            alias_generator: Arc::new(AliasGenerator::new()),
+            options,
+        };
+        Ok(ReorderJoins::new().rewrite(plan, &config)?.data)
+    }
+
+    /// A table with known statistics
+    struct StatisticsTable {
+        schema: SchemaRef,
+        statistics: Option<Statistics>,
+    }
+
+    impl TableSource for StatisticsTable {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            Arc::clone(&self.schema)
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            self.statistics.clone()
+        }
+    }
+
+    /// Scans a table with `num_rows` rows and two columns: a unique `id`
+    /// and a `key` with `distinct_keys` distinct values
+    fn scan(name: &str, num_rows: usize, distinct_keys: usize) -> Result<LogicalPlan> {
+        scan_with_statistics(
+            name,
+            Some(Statistics {
+                num_rows: Precision::Exact(num_rows),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![
+                    ColumnStatistics::new_unknown()
+                        .with_distinct_count(Precision::Exact(num_rows)),
+                    ColumnStatistics::new_unknown()
+                        .with_distinct_count(Precision::Inexact(distinct_keys)),
+                ],
+            }),
+        )
+    }
+
+    fn scan_with_statistics(
+        name: &str,
+        statistics: Option<Statistics>,
+    ) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("key", DataType::Int64, false),
+        ]));
+        let source = Arc::new(StatisticsTable { schema, statistics });
+        LogicalPlanBuilder::scan(name, source, None)?.build()
+    }
+
+    fn join(
+        left: LogicalPlan,
+        right: LogicalPlan,
+        on: (&str, &str),
+        filter: Option<Expr>,
+    ) -> Result<LogicalPlan> {
+        LogicalPlanBuilder::from(left)
+            .join(right, JoinType::Inner, (vec![on.0], vec![on.1]), filter)?
+            .build()
+    }
+
+    #[test]
+    fn join_smallest_result_first() -> Result<()> {
+        // d2 is the smallest table joined with fact, so it is joined first
+        let plan = join(
+            join(
+                scan("fact", 1000, 1000)?,
+                scan("d1", 100, 100)?,
+                ("fact.id", "d1.id"),
+                None,
+            )?,
+            scan("d2", 10, 10)?,
+            ("fact.key", "d2.key"),
+            None,
+        )?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: fact.id, fact.key, d1.id, d1.key, d2.id, d2.key
+          Inner Join: fact.id = d1.id
+            Inner Join: d2.key = fact.key
+              TableScan: d2
+              TableScan: fact
+            TableScan: d1
+        "
+        )
+    }
+
+    #[test]
+    fn keep_cheapest_order() -> Result<()> {
+        let plan = join(
+            join(
+                scan("fact", 1000, 1000)?,
+                scan("d2", 10, 10)?,
+                ("fact.key", "d2.key"),
+                None,
+            )?,
+            scan("d1", 100, 100)?,
+            ("fact.id", "d1.id"),
+            None,
+        )?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Inner Join: fact.id = d1.id
+          Inner Join: fact.key = d2.key
+            TableScan: fact
+            TableScan: d2
+          TableScan: d1
+        "
+        )
+    }
+
+    #[test]
+    fn place_filters_on_first_join_of_their_inputs() -> Result<()> {
+        let plan = join(
+            join(
+                scan("a", 1000, 1000)?,
+                scan("b", 1000, 1000)?,
+                ("a.id", "b.id"),
+                Some(col("a.key").lt(col("b.key"))),
+            )?,
+            scan("c", 10, 10)?,
+            ("b.key", "c.key"),
+            Some((col("a.key") + col("c.key")).gt(lit(1i64))),
+        )?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: a.id, a.key, b.id, b.key, c.id, c.key
+          Inner Join: b.id = a.id Filter: a.key < b.key AND a.key + c.key > Int64(1)
+            Inner Join: c.key = b.key
+              TableScan: c
+              TableScan: b
+            TableScan: a
+        "
+        )
+    }
+
+    #[test]
+    fn unknown_statistics() -> Result<()> {
+        let plan = join(
+            join(
+                scan("fact", 1000, 1000)?,
+                scan_with_statistics("d1", None)?,
+                ("fact.id", "d1.id"),
+                None,
+            )?,
+            scan("d2", 10, 10)?,
+            ("fact.key", "d2.key"),
+            None,
+        )?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Inner Join: fact.key = d2.key
+          Inner Join: fact.id = d1.id
+            TableScan: fact
+            TableScan: d1
+          TableScan: d2
+        "
+        )
+    }
+
+    #[test]
+    fn cross_join_not_reordered() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(join(
+            scan("fact", 1000, 1000)?,
+            scan("d1", 100, 100)?,
+            ("fact.id", "d1.id"),
+            None,
+        )?)
+        .cross_join(scan("d2", 10, 10)?)?
+        .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Cross Join: 
+          Inner Join: fact.id = d1.id
+            TableScan: fact
+            TableScan: d1
+          TableScan: d2
+        "
+        )
+    }
+
+    /// A star schema where the fact table is joined with the dimension tables
+    /// in decreasing order of their number of rows
+    fn star_schema(dimensions: usize) -> Result<LogicalPlan> {
+        let mut plan = scan("fact", 100_000, 100_000)?;
+        for i in 0..dimensions {
+            let name = format!("d{i}");
+            let num_rows = 10 * (dimensions - i);
+            plan = join(
+                plan,
+                scan(&name, num_rows, num_rows)?,
+                ("fact.key", &format!("{name}.key")),
+                None,
+            )?;
+        }
+        Ok(plan)
+    }
+
+    #[test]
+    fn star_schema_join_order() -> Result<()> {
+        let plan = star_schema(3)?;
+        // The greedy join order is the same as the best one
+        assert_eq!(reorder(plan.clone(), 0)?, reorder(plan.clone(), 12)?);
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: fact.id, fact.key, d0.id, d0.key, d1.id, d1.key, d2.id, d2.key
+          Inner Join: fact.key = d0.key
+            Inner Join: fact.key = d1.key
+              Inner Join: d2.key = fact.key
+                TableScan: d2
+                TableScan: fact
+              TableScan: d1
+            TableScan: d0
+        "
+        )
+    }
+
+    #[test]
+    fn large_star_schema_join_order() -> Result<()> {
+        let plan = star_schema(20)?;
+        let schema = Arc::clone(plan.schema());
+        let optimized_plan = reorder(plan, 12)?;
+        assert_eq!(optimized_plan.schema(), &schema);
+
+        // The dimension tables are joined from the smallest to the largest
+        let formatted_plan = optimized_plan.display_indent().to_string();
+        let scans = formatted_plan
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("TableScan: "))
+            .collect::<Vec<_>>();
+        let mut expected = vec!["d19".to_string(), "fact".to_string()];
+        expected.extend((0..19).rev().map(|i| format!("d{i}")));
+        assert_eq!(scans, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn reordering_is_idempotent() -> Result<()> {
+        let plan = reorder(star_schema(5)?, 12)?;
+        let LogicalPlan::Projection(projection) = &plan else {
+            panic!("expected a projection, got {plan}");
+        };
+        let joins = Arc::unwrap_or_clone(Arc::clone(&projection.input));
+        assert_eq!(reorder(joins.clone(), 12)?, joins);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_by_default() -> Result<()> {
+        let plan = star_schema(3)?;
+        let optimized_plan =
+            ReorderJoins::new().rewrite(plan.clone(), &OptimizerContext::new())?;
+        assert!(!optimized_plan.transformed);
+        assert_eq!(optimized_plan.data, plan);
+        Ok(())
+    }
+}
@@ -22,7 +22,7 @@ use crate::{OptimizerConfig, OptimizerRule};
 use std::sync::Arc;
 
 use datafusion_common::tree_node::Transformed;
-use datafusion_common::{Column, Result};
+use datafusion_common::{Column, Dependency, Result};
 use datafusion_expr::expr_rewriter::normalize_cols;
 use datafusion_expr::utils::expand_wildcard;
 use datafusion_expr::{col, ExprFunctionExt, LogicalPlanBuilder};
@@ -39,6 +39,9 @@ use datafusion_expr::{Aggregate, Distinct, DistinctOn, Expr, LogicalPlan};
 /// SELECT a, b FROM tab GROUP BY a, b
 /// ```
 ///
+/// The `DISTINCT` is removed instead if its input already has a non-nullable
+/// key, for example the primary key of `tab`.
+///
 /// On the other hand, for a `DISTINCT ON` query the replacement is
 /// a bit more involved and effectively converts
 /// ```text
@@ -90,6 +93,11 @@ impl OptimizerRule for ReplaceDistinctWithAggregate {
                     {
                         return Ok(Transformed::yes(input.as_ref().clone()));
                     }
+                    // If the input has a key that can not be null, its rows are
+                    // already distinct:
+                    if dep.mode == Dependency::Single && !dep.nullable {
+                        return Ok(Transformed::yes(input.as_ref().clone()));
+                    }
                 }
 
                 // Replace with aggregation:
@@ -243,6 +251,35 @@ mod tests {
         ")
     }
 
+    #[test]
+    fn eliminate_distinct_over_primary_key() -> Result<()> {
+        let table_scan = test_table_scan_with_primary_key("test")?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .project(vec![col("a"), col("b")])?
+            .distinct()?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: test.a, test.b
+          TableScan: test
+        ")
+    }
+
+    #[test]
+    fn do_not_eliminate_distinct_without_primary_key() -> Result<()> {
+        let table_scan = test_table_scan_with_primary_key("test")?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .project(vec![col("b"), col("c")])?
+            .distinct()?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[test.b, test.c]], aggr=[[]]
+          Projection: test.b, test.c
+            TableScan: test
+        ")
+    }
+
     #[test]
     fn do_not_eliminate_distinct() -> Result<()> {
         let table_scan = test_table_scan().unwrap();
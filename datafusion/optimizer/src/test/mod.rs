@@ -20,7 +20,8 @@ use crate::optimizer::Optimizer;
 use crate::{OptimizerContext, OptimizerRule};
 use arrow::datatypes::{DataType, Field, Schema};
 use datafusion_common::config::ConfigOptions;
-use datafusion_common::{assert_contains, Result};
+use datafusion_common::{assert_contains, Constraint, Constraints, Result};
+use datafusion_expr::logical_plan::builder::table_source_with_constraints;
 use datafusion_expr::{logical_plan::table_scan, LogicalPlan, LogicalPlanBuilder};
 use std::sync::Arc;
 
@@ -45,6 +46,14 @@ pub fn test_table_scan() -> Result<LogicalPlan> {
     test_table_scan_with_name("test")
 }
 
+/// some tests share a common table with a primary key on `a`
+pub fn test_table_scan_with_primary_key(name: &str) -> Result<LogicalPlan> {
+    let schema = Schema::new(test_table_scan_fields());
+    let constraints = Constraints::new_unverified(vec![Constraint::PrimaryKey(vec![0])]);
+    let source = table_source_with_constraints(&schema, constraints);
+    LogicalPlanBuilder::scan(name, source, None)?.build()
+}
+
 /// Scan an empty data source, mainly used in tests
 pub fn scan_empty(
     name: Option<&str>,
@@ -26,7 +26,7 @@ use crate::utils::collect_columns;
 use crate::PhysicalExpr;
 
 use arrow::datatypes::Schema;
-use datafusion_common::stats::Precision;
+use datafusion_common::stats::{Histogram, MostCommonValues, Precision};
 use datafusion_common::{
     internal_datafusion_err, internal_err, ColumnStatistics, Result, ScalarValue,
 };
@@ -89,6 +89,12 @@ pub struct ExprBoundaries {
     pub interval: Option<Interval>,
     /// Maximum number of distinct values this expression can produce, if known.
     pub distinct_count: Precision<usize>,
+    /// Equi-depth histogram of the values of the column, if known. When
+    /// present, it is used instead of assuming uniformly distributed values
+    /// to calculate the selectivity.
+    pub histogram: Option<Arc<Histogram>>,
+    /// The most common values of the column, if known. See `histogram`.
+    pub most_common_values: Option<Arc<MostCommonValues>>,
 }
 
 impl ExprBoundaries {
@@ -124,6 +130,8 @@ impl ExprBoundaries {
             column,
             interval: Some(interval),
             distinct_count: col_stats.distinct_count,
+            histogram: col_stats.histogram.clone(),
+            most_common_values: col_stats.most_common_values.clone(),
         })
     }
 
@@ -139,6 +147,8 @@ impl ExprBoundaries {
                     column: Column::new(field.name(), i),
                     interval: Some(Interval::make_unbounded(field.data_type())?),
                     distinct_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 })
             })
             .collect()
@@ -150,7 +160,8 @@ impl ExprBoundaries {
 /// The function accepts boundaries of the input columns in the `context` parameter.
 /// It then tries to tighten these boundaries based on the provided `expr`.
 /// The resulting selectivity value is calculated by comparing the initial and final boundaries.
-/// The computation assumes that the data within the column is uniformly distributed and not sorted.
+/// The computation assumes that the data within the column is uniformly distributed and not sorted,
+/// unless the histogram or the most common values of the column are known.
 ///
 /// # Arguments
 ///
@@ -271,9 +282,8 @@ fn calculate_selectivity(
     target_boundaries: &[ExprBoundaries],
     initial_boundaries: &[ExprBoundaries],
 ) -> Result<f64> {
-    // Since the intervals are assumed uniform and the values
-    // are not correlated, we need to multiply the selectivities
-    // of multiple columns to get the overall selectivity.
+    // Since the values are not correlated, we need to multiply the
+    // selectivities of multiple columns to get the overall selectivity.
     if target_boundaries.len() != initial_boundaries.len() {
         return Err(internal_datafusion_err!(
             "The number of columns in the initial and target boundaries should be the same"
@@ -282,8 +292,16 @@ fn calculate_selectivity(
     let mut acc: f64 = 1.0;
     for (initial, target) in initial_boundaries.iter().zip(target_boundaries) {
         match (initial.interval.as_ref(), target.interval.as_ref()) {
-            (Some(initial), Some(target)) => {
-                acc *= cardinality_ratio(initial, target);
+            (Some(initial_interval), Some(target_interval)) => {
+                acc *= if initial_interval == target_interval {
+                    1.0
+                } else {
+                    distribution_selectivity(initial, target_interval)
+                        // Assume the values are uniformly distributed
+                        .unwrap_or_else(|| {
+                            cardinality_ratio(initial_interval, target_interval)
+                        })
+                };
             }
             (None, Some(_)) => {
                 return internal_err!(
@@ -297,12 +315,22 @@ fn calculate_selectivity(
     Ok(acc)
 }
 
+/// Estimates the fraction of the rows of a column with a value in `target`
+/// using the histogram and the most common values of the column, if known
+fn distribution_selectivity(initial: &ExprBoundaries, target: &Interval) -> Option<f64> {
+    let column_statistics = ColumnStatistics::new_unknown()
+        .with_histogram(initial.histogram.clone())
+        .with_most_common_values(initial.most_common_values.clone());
+    column_statistics.range_selectivity(target.lower(), target.upper())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::{assert_contains, DFSchema};
+    use datafusion_common::stats::{Histogram, MostCommonValues, Precision};
+    use datafusion_common::{assert_contains, ColumnStatistics, DFSchema, ScalarValue};
     use datafusion_expr::{
         col, execution_props::ExecutionProps, interval_arithmetic::Interval, lit, Expr,
     };
@@ -438,4 +466,52 @@ mod tests {
         .unwrap_err();
         assert_contains!(analysis_error.to_string(), expected_error);
     }
+
+    #[test]
+    fn test_analyze_selectivity_with_histogram() {
+        let schema = Arc::new(Schema::new(vec![make_field("a", DataType::Int32)]));
+        let df_schema = DFSchema::try_from(Arc::clone(&schema)).unwrap();
+        let int = |v| ScalarValue::Int32(Some(v));
+        // 900 rows with the value 0 and a row for each value between 901
+        // and 1000
+        let values = (901..=1000).map(|v| (int(v), 1)).collect::<Vec<_>>();
+        let statistics = ColumnStatistics::new_unknown()
+            .with_min_value(Precision::Exact(int(0)))
+            .with_max_value(Precision::Exact(int(1000)))
+            .with_histogram(
+                Histogram::try_new_equi_depth(&values, 10, 1000).map(Arc::new),
+            )
+            .with_most_common_values(Some(Arc::new(MostCommonValues::new(
+                vec![(int(0), 900)],
+                1000,
+            ))));
+
+        let test_cases = vec![
+            // The most common value
+            (col("a").eq(lit(0)), 0.9),
+            // A value of the histogram
+            (col("a").eq(lit(950)), 0.001),
+            // A range of the histogram
+            (col("a").gt(lit(950)), 0.05),
+            // A range including the most common value
+            (col("a").lt_eq(lit(910)), 0.91),
+        ];
+        for (expr, expected) in test_cases {
+            let boundaries =
+                vec![ExprBoundaries::try_from_column(&schema, &statistics, 0).unwrap()];
+            let physical_expr =
+                create_physical_expr(&expr, &df_schema, &ExecutionProps::new()).unwrap();
+            let analysis_result = analyze(
+                &physical_expr,
+                AnalysisContext::new(boundaries),
+                df_schema.as_ref(),
+            )
+            .unwrap();
+            let selectivity = analysis_result.selectivity.unwrap();
+            assert!(
+                (selectivity - expected).abs() < 1e-9,
+                "expected selectivity {expected} but got {selectivity} for {expr:?}"
+            );
+        }
+    }
 }
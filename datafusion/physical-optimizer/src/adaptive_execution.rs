@@ -0,0 +1,93 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An optimizer rule that wraps the plans with partitioned hash joins in an
+//! [`AdaptiveExec`], to re-optimize them during execution
+
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_common::Result;
+use datafusion_physical_plan::adaptive::AdaptiveExec;
+use datafusion_physical_plan::execution_plan::ExecutionPlanProperties;
+use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion_physical_plan::recursive_query::RecursiveQueryExec;
+use datafusion_physical_plan::work_table::WorkTableExec;
+use datafusion_physical_plan::ExecutionPlan;
+
+/// An optimizer rule that wraps the plan in an [`AdaptiveExec`] when
+/// `datafusion.optimizer.enable_adaptive_execution` is set, so that its
+/// partitioned hash joins are re-optimized during execution using the actual
+/// sizes of their inputs.
+///
+/// Only bounded plans with at least one [`PartitionMode::Partitioned`]
+/// [`HashJoinExec`] are wrapped. Recursive queries are not, as their work
+/// tables are executed several times.
+#[derive(Default, Debug)]
+pub struct AdaptiveExecution {}
+
+impl AdaptiveExecution {
+    /// Create a new `AdaptiveExecution`
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for AdaptiveExecution {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_adaptive_execution
+            || plan.boundedness().is_unbounded()
+            || plan.as_any().is::<AdaptiveExec>()
+        {
+            return Ok(plan);
+        }
+
+        let mut partitioned_join = false;
+        let mut recursive = false;
+        plan.apply(|node| {
+            let node = node.as_any();
+            if node.is::<RecursiveQueryExec>() || node.is::<WorkTableExec>() {
+                recursive = true;
+                return Ok(TreeNodeRecursion::Stop);
+            }
+            if let Some(join) = node.downcast_ref::<HashJoinExec>() {
+                partitioned_join |= join.mode == PartitionMode::Partitioned;
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })?;
+
+        if partitioned_join && !recursive {
+            Ok(Arc::new(AdaptiveExec::new(plan)))
+        } else {
+            Ok(plan)
+        }
+    }
+
+    fn name(&self) -> &str {
+        "AdaptiveExecution"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
@@ -24,6 +24,7 @@
 // https://github.com/apache/datafusion/issues/11143
 #![deny(clippy::clone_on_ref_ptr)]
 
+pub mod adaptive_execution;
 pub mod aggregate_statistics;
 pub mod coalesce_async_exec_input;
 pub mod coalesce_batches;
@@ -37,9 +38,12 @@ pub mod limit_pushdown;
 pub mod limited_distinct_aggregation;
 pub mod optimizer;
 pub mod output_requirements;
+pub mod partitioned_topk;
 pub mod projection_pushdown;
+pub mod result_caching;
 pub use datafusion_pruning as pruning;
 pub mod sanity_checker;
+pub mod skew_handling;
 pub mod topk_aggregation;
 pub mod update_aggr_exprs;
 pub mod utils;
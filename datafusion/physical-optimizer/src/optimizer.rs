@@ -20,6 +20,7 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use crate::adaptive_execution::AdaptiveExecution;
 use crate::aggregate_statistics::AggregateStatistics;
 use crate::coalesce_batches::CoalesceBatches;
 use crate::combine_partial_final_agg::CombinePartialFinalAggregate;
@@ -31,8 +32,11 @@ use crate::join_selection::JoinSelection;
 use crate::limit_pushdown::LimitPushdown;
 use crate::limited_distinct_aggregation::LimitedDistinctAggregation;
 use crate::output_requirements::OutputRequirements;
+use crate::partitioned_topk::PartitionedTopK;
 use crate::projection_pushdown::ProjectionPushdown;
+use crate::result_caching::ResultCaching;
 use crate::sanity_checker::SanityCheckPlan;
+use crate::skew_handling::SkewHandling;
 use crate::topk_aggregation::TopKAggregation;
 use crate::update_aggr_exprs::OptimizeAggregateOrder;
 
@@ -117,6 +121,11 @@ impl PhysicalOptimizer {
             Arc::new(EnforceSorting::new()),
             // Run once after the local sorting requirement is changed
             Arc::new(OptimizeAggregateOrder::new()),
+            // The PartitionedTopK rule replaces the sorts added by the EnforceSorting rule
+            // below `ROW_NUMBER` / `RANK` windows filtered on their result, so it should
+            // run after the EnforceSorting rule, and before the CoalesceBatches rule adds
+            // operators between the filters and the windows.
+            Arc::new(PartitionedTopK::new()),
             // TODO: `try_embed_to_hash_join` in the ProjectionPushdown rule would be block by the CoalesceBatches, so add it before CoalesceBatches. Maybe optimize it in the future.
             Arc::new(ProjectionPushdown::new()),
             // The CoalesceBatches rule will not influence the distribution and ordering of the
@@ -143,10 +152,21 @@ impl PhysicalOptimizer {
             // reduced by narrowing their input tables.
             Arc::new(ProjectionPushdown::new()),
             Arc::new(EnsureCooperative::new()),
+            // The SkewHandling rule spreads the rows of the hot keys of the
+            // partitioned aggregates and hash joins across all the partitions.
+            Arc::new(SkewHandling::new()),
+            // The ResultCaching rule caches the results of the subplans which
+            // only read files. It must run after the other rules, which may
+            // change these subplans, but before the dynamic filters are pushed
+            // down, which are not pushed through the cached results.
+            Arc::new(ResultCaching::new()),
             // This FilterPushdown handles dynamic filters that may have references to the source ExecutionPlan.
             // Therefore it should be run at the end of the optimization process since any changes to the plan may break the dynamic filter's references.
             // See `FilterPushdownPhase` for more details.
             Arc::new(FilterPushdown::new_post_optimization()),
+            // The AdaptiveExecution rule wraps the final plan so that its
+            // partitioned hash joins are re-optimized during execution.
+            Arc::new(AdaptiveExecution::new()),
             // The SanityCheckPlan rule checks whether the order and
             // distribution requirements of each node in the plan
             // is satisfied. It will also reject non-runnable query
@@ -0,0 +1,282 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An optimizer rule that replaces `ROW_NUMBER` / `RANK` windows filtered on
+//! their result by a [`PartitionedTopKExec`]
+
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use arrow::datatypes::DataType;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column, Literal};
+use datafusion_physical_expr::physical_exprs_bag_equal;
+use datafusion_physical_expr::{conjunction_opt, split_conjunction, PhysicalExpr};
+use datafusion_physical_expr_common::sort_expr::LexOrdering;
+use datafusion_physical_plan::execution_plan::ExecutionPlanProperties;
+use datafusion_physical_plan::filter::FilterExec;
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::sorts::sort::SortExec;
+use datafusion_physical_plan::windows::{
+    BoundedWindowAggExec, PartitionedTopKExec, PartitionedTopKFunction,
+    StandardWindowExpr, WindowAggExec, WindowUDFExpr,
+};
+use datafusion_physical_plan::{ExecutionPlan, WindowExpr};
+
+/// An optimizer rule that replaces a `ROW_NUMBER` or `RANK` window and a
+/// filter keeping its first rows by a [`PartitionedTopKExec`], for queries
+/// such as:
+///
+/// ```sql
+/// SELECT * FROM (
+///   SELECT *, row_number() OVER (PARTITION BY k ORDER BY ts DESC) rn FROM t
+/// ) WHERE rn <= 3
+/// ```
+///
+/// The `FilterExec` on `rn <= 3` (or `rn < 4`, `rn = 1`), the window and
+/// the `SortExec` of its input are replaced by a `PartitionedTopKExec`
+/// keeping at most 3 rows for each partition. A projection of the window
+/// columns between the filter and the window is kept. The other conjuncts of the
+/// filter are kept in a `FilterExec` above the `PartitionedTopKExec`.
+#[derive(Default, Debug)]
+pub struct PartitionedTopK {}
+
+impl PartitionedTopK {
+    /// Create a new `PartitionedTopK`
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn transform_filter(
+        plan: &Arc<dyn ExecutionPlan>,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() else {
+            return Ok(None);
+        };
+        // The filter may be on a projection of the window columns
+        let (projection, window) =
+            match filter.input().as_any().downcast_ref::<ProjectionExec>() {
+                Some(projection) => (Some(projection), projection.input()),
+                None => (None, filter.input()),
+            };
+        let (window_exprs, sort) = if let Some(window) =
+            window.as_any().downcast_ref::<BoundedWindowAggExec>()
+        {
+            (window.window_expr(), window.input())
+        } else if let Some(window) = window.as_any().downcast_ref::<WindowAggExec>() {
+            (window.window_expr(), window.input())
+        } else {
+            return Ok(None);
+        };
+        let [window_expr] = window_exprs else {
+            return Ok(None);
+        };
+        let Some(function) = rank_function(window_expr) else {
+            return Ok(None);
+        };
+        if window_expr.partition_by().is_empty() || window.boundedness().is_unbounded() {
+            return Ok(None);
+        }
+        let Some(order_by) = LexOrdering::new(window_expr.order_by().to_vec()) else {
+            return Ok(None);
+        };
+
+        // The bound on the row number / rank in the filter
+        let window_column = window.schema().fields().len() - 1;
+        let window_column = match projection {
+            Some(projection) => {
+                let Some(index) = projection_column(projection, window_column) else {
+                    return Ok(None);
+                };
+                index
+            }
+            None => window_column,
+        };
+        let mut fetch = None;
+        let mut residual = vec![];
+        for conjunct in split_conjunction(filter.predicate()) {
+            match rank_bound(conjunct, window_column) {
+                Some((bound, exact)) => {
+                    fetch = Some(fetch.map_or(bound, |fetch: usize| fetch.min(bound)));
+                    if !exact {
+                        residual.push(Arc::clone(conjunct));
+                    }
+                }
+                None => residual.push(Arc::clone(conjunct)),
+            }
+        }
+        let Some(fetch) = fetch.filter(|fetch| *fetch > 0) else {
+            return Ok(None);
+        };
+
+        // The input of the window is sorted by the partition keys and the
+        // order keys, the PartitionedTopKExec replaces the sort
+        let Some(sort) = sort.as_any().downcast_ref::<SortExec>() else {
+            return Ok(None);
+        };
+        let partition_len = window_expr.partition_by().len();
+        if sort.fetch().is_some()
+            || sort.expr().len() != partition_len + order_by.len()
+            || sort.expr()[partition_len..] != order_by[..]
+            || !(sort.preserve_partitioning()
+                || sort.input().output_partitioning().partition_count() == 1)
+        {
+            return Ok(None);
+        }
+        let partition_sort_exprs = sort.expr()[..partition_len].to_vec();
+        let sort_partition_exprs = partition_sort_exprs
+            .iter()
+            .map(|sort_expr| Arc::clone(&sort_expr.expr))
+            .collect::<Vec<_>>();
+        if !physical_exprs_bag_equal(&sort_partition_exprs, window_expr.partition_by()) {
+            return Ok(None);
+        }
+        let Some(partition_by) = LexOrdering::new(partition_sort_exprs) else {
+            return Ok(None);
+        };
+
+        let mut topk: Arc<dyn ExecutionPlan> = Arc::new(PartitionedTopKExec::try_new(
+            Arc::clone(sort.input()),
+            partition_by,
+            order_by,
+            function,
+            fetch,
+            window_expr.field()?,
+        )?);
+        if let Some(projection) = projection {
+            topk = Arc::new(ProjectionExec::try_new(projection.expr().to_vec(), topk)?);
+        }
+        let plan: Arc<dyn ExecutionPlan> = match conjunction_opt(residual) {
+            Some(predicate) => Arc::new(
+                FilterExec::try_new(predicate, topk)?
+                    .with_projection(filter.projection().cloned())?,
+            ),
+            None => match filter.projection() {
+                Some(projection) => {
+                    let schema = topk.schema();
+                    let exprs = projection
+                        .iter()
+                        .map(|index| {
+                            let name = schema.field(*index).name();
+                            (
+                                Arc::new(Column::new(name, *index))
+                                    as Arc<dyn PhysicalExpr>,
+                                name.to_string(),
+                            )
+                        })
+                        .collect();
+                    Arc::new(ProjectionExec::try_new(exprs, topk)?)
+                }
+                None => topk,
+            },
+        };
+        Ok(Some(plan))
+    }
+}
+
+/// Returns the function of `window_expr` if it is `ROW_NUMBER` or `RANK`
+fn rank_function(window_expr: &Arc<dyn WindowExpr>) -> Option<PartitionedTopKFunction> {
+    let window_expr = window_expr.as_any().downcast_ref::<StandardWindowExpr>()?;
+    let udwf = window_expr
+        .get_standard_func_expr()
+        .as_any()
+        .downcast_ref::<WindowUDFExpr>()?;
+    match udwf.fun().name() {
+        "row_number" => Some(PartitionedTopKFunction::RowNumber),
+        "rank" => Some(PartitionedTopKFunction::Rank),
+        _ => None,
+    }
+}
+
+/// Returns the output index of the column `index` in `projection`, if every
+/// expression of `projection` is a column
+fn projection_column(projection: &ProjectionExec, index: usize) -> Option<usize> {
+    let mut output = None;
+    for (i, (expr, _)) in projection.expr().iter().enumerate() {
+        let column = expr.as_any().downcast_ref::<Column>()?;
+        if column.index() == index {
+            output = Some(i);
+        }
+    }
+    output
+}
+
+/// Returns the maximum row number / rank of the rows satisfying `expr`, if
+/// it bounds the column `window_column`, and whether `expr` is equivalent
+/// to the bound
+fn rank_bound(
+    expr: &Arc<dyn PhysicalExpr>,
+    window_column: usize,
+) -> Option<(usize, bool)> {
+    let binary = expr.as_any().downcast_ref::<BinaryExpr>()?;
+    let is_window_column = |expr: &Arc<dyn PhysicalExpr>| {
+        expr.as_any()
+            .downcast_ref::<Column>()
+            .is_some_and(|column| column.index() == window_column)
+    };
+    let (op, literal) = if is_window_column(binary.left()) {
+        (*binary.op(), binary.right())
+    } else if is_window_column(binary.right()) {
+        (binary.op().swap()?, binary.left())
+    } else {
+        return None;
+    };
+    let value = literal.as_any().downcast_ref::<Literal>()?.value();
+    let ScalarValue::UInt64(Some(value)) = value.cast_to(&DataType::UInt64).ok()? else {
+        return None;
+    };
+    let value = usize::try_from(value).ok()?;
+    match op {
+        Operator::LtEq => Some((value, true)),
+        Operator::Lt => Some((value.saturating_sub(1), true)),
+        // The row number / rank is at least 1
+        Operator::Eq => Some((value, value == 1)),
+        _ => None,
+    }
+}
+
+impl PhysicalOptimizerRule for PartitionedTopK {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_partitioned_topk {
+            return Ok(plan);
+        }
+        plan.transform_down(|plan| {
+            Ok(match Self::transform_filter(&plan)? {
+                Some(plan) => Transformed::yes(plan),
+                None => Transformed::no(plan),
+            })
+        })
+        .data()
+    }
+
+    fn name(&self) -> &str {
+        "PartitionedTopK"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+// see `partitioned_topk.slt` for tests
@@ -0,0 +1,209 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An optimizer rule that caches the results of the plans and subplans which
+//! only read files, in the query result cache of the runtime
+
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::TreeNode;
+use datafusion_common::Result;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_expr::Volatility;
+use datafusion_physical_expr::{PhysicalExpr, ScalarFunctionExpr};
+use datafusion_physical_plan::aggregates::AggregateExec;
+use datafusion_physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion_physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion_physical_plan::coop::CooperativeExec;
+use datafusion_physical_plan::execution_plan::ExecutionPlanProperties;
+use datafusion_physical_plan::filter::FilterExec;
+use datafusion_physical_plan::joins::utils::JoinFilter;
+use datafusion_physical_plan::joins::{
+    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, SortMergeJoinExec,
+};
+use datafusion_physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::repartition::RepartitionExec;
+use datafusion_physical_plan::result_cache::CachedResultExec;
+use datafusion_physical_plan::sorts::sort::SortExec;
+use datafusion_physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion_physical_plan::union::UnionExec;
+use datafusion_physical_plan::windows::{BoundedWindowAggExec, WindowAggExec};
+use datafusion_physical_plan::{with_new_children_if_necessary, ExecutionPlan};
+use object_store::ObjectMeta;
+
+/// An optimizer rule that caches the results of the plans which only read
+/// files, when `datafusion.execution.enable_result_cache` is set.
+///
+/// The whole plan is wrapped in a [`CachedResultExec`] if it can be cached,
+/// and otherwise its largest subplans which can be cached. A plan can be cached
+/// if its leaves are file scans, it only contains the common relational
+/// operators, and it does not evaluate volatile functions such as `random()`.
+///
+/// The results are cached in the query result cache of the runtime, see
+/// `datafusion.runtime.query_result_cache_limit`, and are only returned while
+/// the files read by the plan are unchanged.
+#[derive(Default, Debug)]
+pub struct ResultCaching {}
+
+impl ResultCaching {
+    /// Create a new `ResultCaching`
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for ResultCaching {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.execution.enable_result_cache || plan.boundedness().is_unbounded() {
+            return Ok(plan);
+        }
+        cache_results(plan)
+    }
+
+    fn name(&self) -> &str {
+        "ResultCaching"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Wraps `plan` in a [`CachedResultExec`] if it can be cached, and otherwise
+/// the largest subplans of `plan` which can be cached
+fn cache_results(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    if plan.as_any().is::<CachedResultExec>() {
+        return Ok(plan);
+    }
+    let mut files = vec![];
+    if is_cacheable(&plan, &mut files) {
+        return Ok(Arc::new(CachedResultExec::new(plan, files)));
+    }
+    let children = plan
+        .children()
+        .into_iter()
+        .map(|child| cache_results(Arc::clone(child)))
+        .collect::<Result<Vec<_>>>()?;
+    with_new_children_if_necessary(plan, children)
+}
+
+/// Returns true if the result of `plan` can be cached, collecting the files it
+/// reads in `files`
+fn is_cacheable(plan: &Arc<dyn ExecutionPlan>, files: &mut Vec<ObjectMeta>) -> bool {
+    if let Some(source) = plan.as_any().downcast_ref::<DataSourceExec>() {
+        let Some(config) = source
+            .data_source()
+            .as_any()
+            .downcast_ref::<FileScanConfig>()
+        else {
+            return false;
+        };
+        files.extend(
+            config
+                .file_groups
+                .iter()
+                .flat_map(|group| group.iter())
+                .map(|file| file.object_meta.clone()),
+        );
+        return true;
+    }
+    is_deterministic(plan)
+        && plan
+            .children()
+            .into_iter()
+            .all(|child| is_cacheable(child, files))
+}
+
+/// Returns true if `plan` is a known operator which returns the same result
+/// for the same input
+fn is_deterministic(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let any = plan.as_any();
+    if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        !projection.expr().iter().any(|(expr, _)| is_volatile(expr))
+    } else if let Some(filter) = any.downcast_ref::<FilterExec>() {
+        !is_volatile(filter.predicate())
+    } else if let Some(aggregate) = any.downcast_ref::<AggregateExec>() {
+        let group_exprs = aggregate.group_expr().expr().iter().map(|(expr, _)| expr);
+        let aggr_exprs = aggregate
+            .aggr_expr()
+            .iter()
+            .flat_map(|aggr| aggr.expressions())
+            .collect::<Vec<_>>();
+        let filter_exprs = aggregate.filter_expr().iter().flatten();
+        !group_exprs
+            .chain(aggr_exprs.iter())
+            .chain(filter_exprs)
+            .any(is_volatile)
+    } else if let Some(sort) = any.downcast_ref::<SortExec>() {
+        !sort
+            .expr()
+            .iter()
+            .any(|sort_expr| is_volatile(&sort_expr.expr))
+    } else if let Some(join) = any.downcast_ref::<HashJoinExec>() {
+        !join.filter().is_some_and(is_volatile_filter)
+    } else if let Some(join) = any.downcast_ref::<NestedLoopJoinExec>() {
+        !join.filter().is_some_and(is_volatile_filter)
+    } else if let Some(join) = any.downcast_ref::<SortMergeJoinExec>() {
+        !join.filter().as_ref().is_some_and(is_volatile_filter)
+    } else if let Some(window) = any.downcast_ref::<WindowAggExec>() {
+        !window
+            .window_expr()
+            .iter()
+            .any(|expr| expr.expressions().iter().any(is_volatile))
+    } else if let Some(window) = any.downcast_ref::<BoundedWindowAggExec>() {
+        !window
+            .window_expr()
+            .iter()
+            .any(|expr| expr.expressions().iter().any(is_volatile))
+    } else {
+        any.is::<SortPreservingMergeExec>()
+            || any.is::<CoalesceBatchesExec>()
+            || any.is::<CoalescePartitionsExec>()
+            || any.is::<RepartitionExec>()
+            || any.is::<GlobalLimitExec>()
+            || any.is::<LocalLimitExec>()
+            || any.is::<UnionExec>()
+            || any.is::<CrossJoinExec>()
+            || any.is::<CooperativeExec>()
+    }
+}
+
+fn is_volatile_filter(filter: &JoinFilter) -> bool {
+    is_volatile(filter.expression())
+}
+
+/// Returns true if `expr` contains a volatile function, whose result would
+/// change if the expression were evaluated more than once
+fn is_volatile(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.exists(|expr| {
+        Ok(expr
+            .as_any()
+            .downcast_ref::<ScalarFunctionExpr>()
+            .is_some_and(|func| {
+                func.fun().signature().volatility == Volatility::Volatile
+            }))
+    })
+    .unwrap_or(true)
+}
@@ -0,0 +1,305 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An optimizer rule that detects the hot keys of the hash repartitions, and
+//! spreads the rows of the hot keys of the partitioned aggregates and hash
+//! joins across all the partitions
+
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use datafusion_common::config::{ConfigOptions, OptimizerOptions};
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{JoinType, Result};
+use datafusion_physical_expr::Distribution;
+use datafusion_physical_plan::aggregates::{AggregateExec, AggregateMode};
+use datafusion_physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion_physical_plan::execution_plan::ExecutionPlanProperties;
+use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion_physical_plan::repartition::{
+    HotKeyDetector, HotKeyRouting, RepartitionExec,
+};
+use datafusion_physical_plan::{
+    with_new_children_if_necessary, ExecutionPlan, InputOrderMode, Partitioning,
+};
+
+/// An optimizer rule that handles the skew of the hash partitioned operators
+/// when `datafusion.optimizer.enable_skew_handling` is set.
+///
+/// The hot keys of a hash [`RepartitionExec`] are detected during execution by
+/// sampling the keys of the first rows of each input partition:
+///
+/// * The input of the partial aggregate of a partitioned [`AggregateExec`] is
+///   hash repartitioned on the grouping keys, spreading the rows of the hot
+///   keys across all the partitions. Each partition then partially aggregates
+///   the groups of the hot keys, which are merged by the final aggregation.
+/// * The probe side of a partitioned [`HashJoinExec`] spreads the rows of its
+///   hot keys across all the partitions, and its build side replicates the
+///   rows of these keys to all the partitions. Only the joins which do not
+///   output the unmatched build rows are handled, and only when the operators
+///   above the join do not require its hash partitioning. The joins are not
+///   handled with adaptive execution, which may collect their build side.
+/// * The other hash repartitions only detect their hot keys, and report them
+///   in their metrics.
+#[derive(Default, Debug)]
+pub struct SkewHandling {}
+
+impl SkewHandling {
+    /// Create a new `SkewHandling`
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for SkewHandling {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let options = &config.optimizer;
+        if !options.enable_skew_handling || plan.boundedness().is_unbounded() {
+            return Ok(plan);
+        }
+
+        let plan = handle_skew(plan, false, options)?;
+
+        // The other hash repartitions detect their hot keys for the metrics
+        plan.transform_up(|plan| {
+            let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>()
+            else {
+                return Ok(Transformed::no(plan));
+            };
+            let detector = new_detector(options);
+            match with_detector(repartition, HotKeyRouting::Detect, detector)? {
+                Some(repartition) => Ok(Transformed::yes(repartition)),
+                None => Ok(Transformed::no(plan)),
+            }
+        })
+        .data()
+    }
+
+    fn name(&self) -> &str {
+        "SkewHandling"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Handles the skew of the aggregates and joins of `plan`. `required` is true
+/// if the operators above `plan` require its hash partitioning.
+fn handle_skew(
+    plan: Arc<dyn ExecutionPlan>,
+    required: bool,
+    options: &OptimizerOptions,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(plan);
+    }
+    let partition_count = plan.output_partitioning().partition_count();
+    let new_children = children
+        .into_iter()
+        .zip(plan.required_input_distribution())
+        .map(|(child, distribution)| {
+            let child_required = match distribution {
+                Distribution::HashPartitioned(_) => true,
+                Distribution::SinglePartition => false,
+                // The partitioning of the child may be the output
+                // partitioning of `plan`
+                Distribution::UnspecifiedDistribution => {
+                    required
+                        && child.output_partitioning().partition_count()
+                            == partition_count
+                }
+            };
+            handle_skew(Arc::clone(child), child_required, options)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let plan = with_new_children_if_necessary(plan, new_children)?;
+
+    if let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+        if !required && !options.enable_adaptive_execution {
+            if let Some(new_plan) = spread_join_hot_keys(join, &plan, options)? {
+                return Ok(new_plan);
+            }
+        }
+    } else if let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() {
+        if let Some(new_plan) = spread_aggregate_hot_keys(aggregate, &plan, options)? {
+            return Ok(new_plan);
+        }
+    }
+    Ok(plan)
+}
+
+/// Spreads the rows of the hot keys of the probe side of a partitioned `join`
+/// across all the partitions, and replicates the matching build rows
+fn spread_join_hot_keys(
+    join: &HashJoinExec,
+    plan: &Arc<dyn ExecutionPlan>,
+    options: &OptimizerOptions,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    // The unmatched build rows would be output by each partition
+    if join.mode != PartitionMode::Partitioned
+        || !matches!(
+            join.join_type(),
+            JoinType::Inner
+                | JoinType::Right
+                | JoinType::RightSemi
+                | JoinType::RightAnti
+                | JoinType::RightMark
+        )
+    {
+        return Ok(None);
+    }
+
+    let detector = new_detector(options);
+    let left = with_input_hot_keys(join.left(), HotKeyRouting::Replicate, &detector)?;
+    let right = with_input_hot_keys(join.right(), HotKeyRouting::Spread, &detector)?;
+    match (left, right) {
+        (Some(left), Some(right)) => {
+            Ok(Some(Arc::clone(plan).with_new_children(vec![left, right])?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Repartitions the input of the partial aggregate of a partitioned
+/// `aggregate` on its grouping keys, spreading the rows of the hot keys
+/// across all the partitions. The plan is expected to be:
+///
+/// ```text
+/// AggregateExec: mode=FinalPartitioned
+///   (CoalesceBatchesExec)
+///     RepartitionExec: partitioning=Hash
+///       AggregateExec: mode=Partial
+///         (RepartitionExec: partitioning=RoundRobinBatch)
+/// ```
+fn spread_aggregate_hot_keys(
+    aggregate: &AggregateExec,
+    plan: &Arc<dyn ExecutionPlan>,
+    options: &OptimizerOptions,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if aggregate.mode() != &AggregateMode::FinalPartitioned {
+        return Ok(None);
+    }
+    let coalesce = aggregate
+        .input()
+        .as_any()
+        .is::<CoalesceBatchesExec>()
+        .then_some(aggregate.input());
+    let hash = match coalesce {
+        Some(coalesce) => coalesce.children()[0],
+        None => aggregate.input(),
+    };
+    let Some(repartition) = hash.as_any().downcast_ref::<RepartitionExec>() else {
+        return Ok(None);
+    };
+    let Partitioning::Hash(_, partition_count) = repartition.partitioning() else {
+        return Ok(None);
+    };
+    let partial_plan = repartition.input();
+    let Some(partial) = partial_plan.as_any().downcast_ref::<AggregateExec>() else {
+        return Ok(None);
+    };
+    if partial.mode() != &AggregateMode::Partial
+        || !partial.group_expr().is_single()
+        || partial.group_expr().expr().is_empty()
+        || partial.input_order_mode() != &InputOrderMode::Linear
+        || partial.required_input_ordering()[0].is_some()
+    {
+        return Ok(None);
+    }
+
+    // The round robin repartition is replaced
+    let input = match partial.input().as_any().downcast_ref::<RepartitionExec>() {
+        Some(round_robin)
+            if matches!(round_robin.partitioning(), Partitioning::RoundRobinBatch(_))
+                && !round_robin.preserve_order() =>
+        {
+            round_robin.input()
+        }
+        _ => partial.input(),
+    };
+    let detector = new_detector(options);
+    let input = RepartitionExec::try_new(
+        Arc::clone(input),
+        Partitioning::Hash(partial.group_expr().input_exprs(), *partition_count),
+    )?
+    .with_hot_keys(HotKeyRouting::Spread, detector);
+
+    let partial = Arc::clone(partial_plan).with_new_children(vec![Arc::new(input)])?;
+    let mut input = Arc::clone(hash).with_new_children(vec![partial])?;
+    if let Some(coalesce) = coalesce {
+        input = Arc::clone(coalesce).with_new_children(vec![input])?;
+    }
+    Ok(Some(Arc::clone(plan).with_new_children(vec![input])?))
+}
+
+/// Sets the hot key `routing` of the hash repartition below the input of a
+/// join, if any
+fn with_input_hot_keys(
+    plan: &Arc<dyn ExecutionPlan>,
+    routing: HotKeyRouting,
+    detector: &Arc<HotKeyDetector>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if let Some(coalesce) = plan.as_any().downcast_ref::<CoalesceBatchesExec>() {
+        return with_input_hot_keys(coalesce.input(), routing, detector)?
+            .map(|input| Arc::clone(plan).with_new_children(vec![input]))
+            .transpose();
+    }
+    let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>() else {
+        return Ok(None);
+    };
+    if repartition.preserve_order() {
+        return Ok(None);
+    }
+    with_detector(repartition, routing, Arc::clone(detector))
+}
+
+fn new_detector(options: &OptimizerOptions) -> Arc<HotKeyDetector> {
+    Arc::new(HotKeyDetector::new(
+        options.skew_sample_rows,
+        options.skew_hot_key_ratio,
+    ))
+}
+
+/// Returns `repartition` detecting its hot keys with `detector`, if it is a
+/// hash repartition which does not detect them yet
+fn with_detector(
+    repartition: &RepartitionExec,
+    routing: HotKeyRouting,
+    detector: Arc<HotKeyDetector>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if !matches!(repartition.partitioning(), Partitioning::Hash(_, _))
+        || repartition.hot_key_routing().is_some()
+    {
+        return Ok(None);
+    }
+    let mut new_repartition = RepartitionExec::try_new(
+        Arc::clone(repartition.input()),
+        repartition.partitioning().clone(),
+    )?;
+    if repartition.preserve_order() {
+        new_repartition = new_repartition.with_preserve_order();
+    }
+    Ok(Some(Arc::new(
+        new_repartition.with_hot_keys(routing, detector),
+    )))
+}
@@ -0,0 +1,1000 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AdaptiveExec`]: re-optimizes the hash joins of a plan during execution,
+//! using the actual sizes of their inputs
+
+use std::any::Any;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::task::Poll;
+
+use crate::coalesce_batches::CoalesceBatchesExec;
+use crate::coop::cooperative;
+use crate::execution_plan::{
+    with_new_children_if_necessary, Boundedness, CardinalityEffect, EmissionType,
+    SchedulingType,
+};
+use crate::joins::utils::OnceAsync;
+use crate::joins::{HashJoinExec, PartitionMode};
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::repartition::RepartitionExec;
+use crate::spill::get_record_batch_memory_size;
+use crate::stream::{ObservedStream, RecordBatchStreamAdapter};
+use crate::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+    SendableRecordBatchStream, Statistics,
+};
+
+use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{Distribution, EquivalenceProperties, Partitioning};
+use futures::future::BoxFuture;
+use futures::{future, stream, FutureExt, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+
+/// Re-optimizes the partitioned [`HashJoinExec`]s of its input during
+/// execution, using the actual sizes of their inputs
+///
+/// When it is executed, `AdaptiveExec` materializes the build side of each
+/// partitioned hash join of its input into a [`MaterializedStageExec`], from
+/// the deepest joins to the root, before the join executes. Knowing the actual
+/// rows and bytes of its build side, the join is:
+///
+/// * switched to [`PartitionMode::CollectLeft`] if the build side is within
+///   `datafusion.optimizer.hash_join_single_partition_threshold` and
+///   `hash_join_single_partition_threshold_rows`, without repartitioning its
+///   probe side when possible.
+/// * otherwise, its probe side is read until it has more rows than the build
+///   side. If it ends before, its batches are materialized too, and the inputs
+///   are swapped if the probe side is the smaller one. The small partitions of
+///   both sides are then coalesced into partitions of about
+///   `datafusion.optimizer.adaptive_target_partition_size` bytes.
+///
+/// A re-optimized join is only used if it keeps the schema, partition count
+/// and ordering of the original join, and its hash partitioning if the
+/// operators above it require it.
+///
+/// The decisions are displayed by `EXPLAIN ANALYZE`: once executed, the child
+/// of an `AdaptiveExec` is the re-optimized plan, with the metrics of its
+/// operators.
+///
+/// The build sides are materialized once for all the partitions. As the probe
+/// sides are only partially materialized, an `AdaptiveExec` can only be
+/// executed once.
+#[derive(Debug)]
+pub struct AdaptiveExec {
+    /// The plan to re-optimize
+    input: Arc<dyn ExecutionPlan>,
+    /// Re-optimizes the input once for all the output partitions
+    adapted: OnceAsync<Arc<dyn ExecutionPlan>>,
+    /// The re-optimized plan, once computed
+    adapted_plan: Arc<OnceLock<Arc<dyn ExecutionPlan>>>,
+    /// The decisions taken for the hash joins of the input
+    decisions: Arc<Mutex<Vec<String>>>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl AdaptiveExec {
+    /// Create a new `AdaptiveExec` re-optimizing `input`
+    pub fn new(input: Arc<dyn ExecutionPlan>) -> Self {
+        let cache = Self::compute_properties(&input);
+        Self {
+            input,
+            adapted: OnceAsync::default(),
+            adapted_plan: Arc::new(OnceLock::new()),
+            decisions: Arc::new(Mutex::new(vec![])),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        }
+    }
+
+    /// The plan to re-optimize
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The re-optimized plan, once `AdaptiveExec` was executed
+    pub fn adapted_plan(&self) -> Option<&Arc<dyn ExecutionPlan>> {
+        self.adapted_plan.get()
+    }
+
+    /// The decisions taken for the hash joins of the input, once
+    /// `AdaptiveExec` was executed
+    pub fn decisions(&self) -> Vec<String> {
+        self.decisions.lock().clone()
+    }
+
+    /// The partitioning of the re-optimized plan is not known in advance, only
+    /// its partition count is kept
+    fn compute_properties(input: &Arc<dyn ExecutionPlan>) -> PlanProperties {
+        PlanProperties::new(
+            input.equivalence_properties().clone(),
+            Partitioning::UnknownPartitioning(
+                input.output_partitioning().partition_count(),
+            ),
+            input.pipeline_behavior(),
+            input.boundedness(),
+        )
+    }
+}
+
+impl DisplayAs for AdaptiveExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let decisions = self.decisions.lock();
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AdaptiveExec")?;
+                if !decisions.is_empty() {
+                    write!(f, ": decisions=[{}]", decisions.join(", "))?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                for decision in decisions.iter() {
+                    writeln!(f, "decision={decision}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AdaptiveExec {
+    fn name(&self) -> &'static str {
+        "AdaptiveExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    /// The re-optimized plan once executed, the input otherwise
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![self.adapted_plan.get().unwrap_or(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return internal_err!("AdaptiveExec wrong number of children");
+        }
+        Ok(Arc::new(AdaptiveExec::new(children.swap_remove(0))))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut adapted = self.adapted.try_once(|| {
+            let adapter = Arc::new(Adapter {
+                context: Arc::clone(&context),
+                decisions: Arc::clone(&self.decisions),
+            });
+            let input = Arc::clone(&self.input);
+            let adapted_plan = Arc::clone(&self.adapted_plan);
+            Ok(async move {
+                let plan = adapter.adapt(input, false).await?;
+                let _ = adapted_plan.set(Arc::clone(&plan));
+                Ok(plan)
+            })
+        })?;
+
+        let stream = stream::once(async move {
+            let plan = future::poll_fn(|cx| adapted.get_shared(cx)).await?;
+            plan.execute(partition, context)
+        })
+        .try_flatten();
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema(), stream));
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(ObservedStream::new(
+            stream,
+            baseline_metrics,
+            None,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        match partition {
+            Some(_) => Ok(Statistics::new_unknown(&self.schema())),
+            None => self.input.partition_statistics(None),
+        }
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::Equal
+    }
+}
+
+/// Re-optimizes the partitioned hash joins of a plan, from the deepest ones
+struct Adapter {
+    context: Arc<TaskContext>,
+    decisions: Arc<Mutex<Vec<String>>>,
+}
+
+impl Adapter {
+    /// Re-optimizes the joins of `plan`. `required` is true if the operators
+    /// above `plan` require its hash partitioning.
+    fn adapt(
+        self: Arc<Self>,
+        plan: Arc<dyn ExecutionPlan>,
+        required: bool,
+    ) -> BoxFuture<'static, Result<Arc<dyn ExecutionPlan>>> {
+        async move {
+            let partition_count = plan.output_partitioning().partition_count();
+            let children = plan.children().into_iter().cloned().collect::<Vec<_>>();
+            if children.is_empty() {
+                return Ok(plan);
+            }
+            let mut new_children = Vec::with_capacity(children.len());
+            for (child, distribution) in
+                children.into_iter().zip(plan.required_input_distribution())
+            {
+                let child_required = match distribution {
+                    Distribution::HashPartitioned(_) => true,
+                    Distribution::SinglePartition => false,
+                    // The partitioning of the child may be the output
+                    // partitioning of `plan`
+                    Distribution::UnspecifiedDistribution => {
+                        required
+                            && child.output_partitioning().partition_count()
+                                == partition_count
+                    }
+                };
+                new_children.push(Arc::clone(&self).adapt(child, child_required).await?);
+            }
+            let plan = with_new_children_if_necessary(plan, new_children)?;
+
+            let operator = uninstrumented(&plan);
+            match operator.as_any().downcast_ref::<HashJoinExec>() {
+                Some(join) if join.mode == PartitionMode::Partitioned => {
+                    let new_plan = self.adapt_join(join, operator, required).await?;
+                    Ok(instrumented_like(&plan, new_plan))
+                }
+                _ => Ok(plan),
+            }
+        }
+        .boxed()
+    }
+
+    /// Re-optimizes the partitioned hash join `join`, which is `plan`
+    async fn adapt_join(
+        &self,
+        join: &HashJoinExec,
+        plan: &Arc<dyn ExecutionPlan>,
+        required: bool,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let options = &self.context.session_config().options().optimizer;
+        let fits_in_single_partition = |stage: &StageData| {
+            stage.rows <= options.hash_join_single_partition_threshold_rows
+                && stage.bytes <= options.hash_join_single_partition_threshold
+        };
+        let partition_count = join.left().output_partitioning().partition_count();
+
+        let build = StageData::materialize(join.left(), &self.context, None).await?;
+        let build_stage = MaterializedStageExec::new(Arc::clone(&build), join.left());
+
+        // Broadcast a small build side to the partitions of the probe side
+        if fits_in_single_partition(&build) {
+            let left: Arc<dyn ExecutionPlan> = Arc::new(build_stage.collected());
+            for (right, repartitioned) in probe_inputs(join.right()) {
+                let new_plan: Arc<dyn ExecutionPlan> = Arc::new(join_with_inputs(
+                    join,
+                    Arc::clone(&left),
+                    right,
+                    PartitionMode::CollectLeft,
+                )?);
+                if can_replace(plan, &new_plan, required)? {
+                    let suffix = if repartitioned {
+                        ""
+                    } else {
+                        " without probe side repartitioning"
+                    };
+                    self.decide(format!(
+                        "HashJoinExec: build_rows={} -> mode=CollectLeft{suffix}",
+                        build.rows
+                    ));
+                    return Ok(new_plan);
+                }
+            }
+        }
+
+        // Read the probe side until it has more rows than the build side
+        let probe =
+            StageData::materialize(join.right(), &self.context, Some(build.rows)).await?;
+        let probe_stage = MaterializedStageExec::new(Arc::clone(&probe), join.right());
+        if probe.complete {
+            let target_size = options.adaptive_target_partition_size.max(1);
+            let coalesced_count = (build.bytes + probe.bytes)
+                .div_ceil(target_size)
+                .clamp(1, partition_count);
+            let rows = format!("build_rows={}, probe_rows={}", build.rows, probe.rows);
+
+            // The probe side is the smaller one: swap the inputs
+            if probe.rows < build.rows && join.join_type().supports_swap() {
+                if fits_in_single_partition(&probe) {
+                    let new_plan = join_with_inputs(
+                        join,
+                        Arc::new(build_stage.clone()),
+                        Arc::new(probe_stage.collected()),
+                        PartitionMode::Partitioned,
+                    )?
+                    .swap_inputs(PartitionMode::CollectLeft)?;
+                    if can_replace(plan, &new_plan, required)? {
+                        self.decide(format!(
+                            "HashJoinExec: {rows} -> swapped inputs, mode=CollectLeft"
+                        ));
+                        return Ok(new_plan);
+                    }
+                }
+                let new_plan = join_with_inputs(
+                    join,
+                    Arc::new(build_stage.coalesced(coalesced_count)),
+                    Arc::new(probe_stage.coalesced(coalesced_count)),
+                    PartitionMode::Partitioned,
+                )?
+                .swap_inputs(PartitionMode::Partitioned)?;
+                if can_replace(plan, &new_plan, required)? {
+                    self.decide(format!(
+                        "HashJoinExec: {rows} -> swapped inputs, partitions={coalesced_count}"
+                    ));
+                    return Ok(new_plan);
+                }
+            }
+
+            // Coalesce the small partitions
+            if coalesced_count < partition_count {
+                let new_plan: Arc<dyn ExecutionPlan> = Arc::new(join_with_inputs(
+                    join,
+                    Arc::new(build_stage.coalesced(coalesced_count)),
+                    Arc::new(probe_stage.coalesced(coalesced_count)),
+                    PartitionMode::Partitioned,
+                )?);
+                if can_replace(plan, &new_plan, required)? {
+                    self.decide(format!(
+                        "HashJoinExec: {rows} -> partitions={coalesced_count}"
+                    ));
+                    return Ok(new_plan);
+                }
+            }
+        }
+
+        self.decide(format!(
+            "HashJoinExec: build_rows={} -> unchanged",
+            build.rows
+        ));
+        Ok(Arc::new(join_with_inputs(
+            join,
+            Arc::new(build_stage),
+            Arc::new(probe_stage),
+            PartitionMode::Partitioned,
+        )?))
+    }
+
+    fn decide(&self, decision: String) {
+        self.decisions.lock().push(decision);
+    }
+}
+
+/// Returns the inputs a `CollectLeft` join can probe instead of `right`, and
+/// whether they are hash repartitioned: the input of the hash repartitioning
+/// of `right` if any, then `right` itself
+fn probe_inputs(right: &Arc<dyn ExecutionPlan>) -> Vec<(Arc<dyn ExecutionPlan>, bool)> {
+    let repartition = match uninstrumented(right)
+        .as_any()
+        .downcast_ref::<CoalesceBatchesExec>()
+    {
+        Some(coalesce) => uninstrumented(coalesce.input()),
+        None => uninstrumented(right),
+    };
+    let mut inputs = vec![];
+    if let Some(repartition) = repartition.as_any().downcast_ref::<RepartitionExec>() {
+        if matches!(repartition.partitioning(), Partitioning::Hash(_, _)) {
+            inputs.push((Arc::clone(repartition.input()), false));
+        }
+    }
+    inputs.push((Arc::clone(right), true));
+    inputs
+}
+
+/// Returns the operator of `plan`, unwrapping its tracing instrumentation if
+/// any
+fn uninstrumented(plan: &Arc<dyn ExecutionPlan>) -> &Arc<dyn ExecutionPlan> {
+    #[cfg(feature = "tracing")]
+    return crate::instrumented::uninstrumented(plan);
+    #[cfg(not(feature = "tracing"))]
+    plan
+}
+
+/// Returns `new_plan`, which replaces the operator of `plan`, with the tracing
+/// instrumentation of `plan` if any
+fn instrumented_like(
+    plan: &Arc<dyn ExecutionPlan>,
+    new_plan: Arc<dyn ExecutionPlan>,
+) -> Arc<dyn ExecutionPlan> {
+    #[cfg(feature = "tracing")]
+    if plan.as_any().is::<crate::instrumented::InstrumentedExec>() {
+        if Arc::ptr_eq(uninstrumented(plan), &new_plan) {
+            return Arc::clone(plan);
+        }
+        return Arc::new(crate::instrumented::InstrumentedExec::new(new_plan));
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = plan;
+    new_plan
+}
+
+/// Returns a copy of `join` with new inputs and partition mode
+fn join_with_inputs(
+    join: &HashJoinExec,
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    mode: PartitionMode,
+) -> Result<HashJoinExec> {
+    HashJoinExec::try_new(
+        left,
+        right,
+        join.on().to_vec(),
+        join.filter().cloned(),
+        join.join_type(),
+        join.projection.clone(),
+        mode,
+        join.null_equality(),
+    )
+}
+
+/// Returns true if `new_plan` can replace `plan`: it has the same schema,
+/// partition count and ordering, and the same hash partitioning if it is
+/// `required` by the operators above
+fn can_replace(
+    plan: &Arc<dyn ExecutionPlan>,
+    new_plan: &Arc<dyn ExecutionPlan>,
+    required: bool,
+) -> Result<bool> {
+    let partitioning = plan.output_partitioning();
+    let new_partitioning = new_plan.output_partitioning();
+    if plan.schema() != new_plan.schema()
+        || partitioning.partition_count() != new_partitioning.partition_count()
+    {
+        return Ok(false);
+    }
+    if let (true, Partitioning::Hash(exprs, _)) = (required, partitioning) {
+        let distribution = Distribution::HashPartitioned(exprs.clone());
+        if !new_partitioning.satisfy(&distribution, new_plan.equivalence_properties()) {
+            return Ok(false);
+        }
+    }
+    match plan.output_ordering() {
+        Some(ordering) => new_plan
+            .equivalence_properties()
+            .ordering_satisfy(ordering.clone()),
+        None => Ok(true),
+    }
+}
+
+/// The batches of the partitions of a plan, materialized during execution
+struct StageData {
+    partitions: Vec<Vec<RecordBatch>>,
+    /// The streams of the partitions that were not read until their end
+    remaining: Vec<Mutex<Option<SendableRecordBatchStream>>>,
+    /// True for the partitions that were read until their end
+    finished: Vec<bool>,
+    /// True if all the partitions were read until their end
+    complete: bool,
+    rows: usize,
+    bytes: usize,
+    _reservation: MemoryReservation,
+}
+
+impl fmt::Debug for StageData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StageData")
+            .field("complete", &self.complete)
+            .field("rows", &self.rows)
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+impl StageData {
+    /// Reads the partitions of `plan` concurrently, until their end or until
+    /// more than `max_rows` rows were read
+    async fn materialize(
+        plan: &Arc<dyn ExecutionPlan>,
+        context: &Arc<TaskContext>,
+        max_rows: Option<usize>,
+    ) -> Result<Arc<Self>> {
+        let mut reservation =
+            MemoryConsumer::new(format!("MaterializedStageExec[{}]", plan.name()))
+                .register(context.memory_pool());
+        let partition_count = plan.output_partitioning().partition_count();
+        let mut streams = (0..partition_count)
+            .map(|partition| plan.execute(partition, Arc::clone(context)).map(Some))
+            .collect::<Result<Vec<_>>>()?;
+        let mut partitions = vec![vec![]; partition_count];
+        let (mut rows, mut bytes) = (0, 0);
+
+        future::poll_fn(|cx| loop {
+            let mut progress = false;
+            for (partition, stream) in streams.iter_mut().enumerate() {
+                if max_rows.is_some_and(|max_rows| rows > max_rows) {
+                    return Poll::Ready(Ok(()));
+                }
+                let Some(input) = stream else {
+                    continue;
+                };
+                match input.poll_next_unpin(cx) {
+                    Poll::Ready(Some(Ok(batch))) => {
+                        let size = get_record_batch_memory_size(&batch);
+                        reservation.try_grow(size)?;
+                        rows += batch.num_rows();
+                        bytes += size;
+                        partitions[partition].push(batch);
+                        progress = true;
+                    }
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+                    Poll::Ready(None) => {
+                        *stream = None;
+                        progress = true;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+            if streams.iter().all(Option::is_none) {
+                return Poll::Ready(Ok(()));
+            }
+            if !progress {
+                return Poll::Pending;
+            }
+        })
+        .await?;
+
+        let finished = streams.iter().map(Option::is_none).collect::<Vec<_>>();
+        Ok(Arc::new(Self {
+            partitions,
+            complete: finished.iter().all(|finished| *finished),
+            finished,
+            remaining: streams.into_iter().map(Mutex::new).collect(),
+            rows,
+            bytes,
+            _reservation: reservation,
+        }))
+    }
+
+    /// Returns the stream of the batches of `partition` that were not read
+    /// during the materialization
+    fn take_remaining(
+        &self,
+        partition: usize,
+    ) -> Result<Option<SendableRecordBatchStream>> {
+        if self.finished[partition] {
+            return Ok(None);
+        }
+        match self.remaining[partition].lock().take() {
+            Some(stream) => Ok(Some(stream)),
+            None => internal_err!(
+                "Partition {partition} of MaterializedStageExec was already executed"
+            ),
+        }
+    }
+}
+
+/// The output of a plan, materialized during execution by an [`AdaptiveExec`]
+///
+/// The materialized plan is displayed as its child, with its metrics, but is
+/// not executed again. Each output partition reads the batches of a group of
+/// partitions of the materialized plan, followed by the rest of their streams
+/// if they were not read until their end.
+#[derive(Debug, Clone)]
+pub struct MaterializedStageExec {
+    /// The materialized plan
+    input: Arc<dyn ExecutionPlan>,
+    data: Arc<StageData>,
+    /// The partitions of `data` read by each output partition
+    groups: Vec<Vec<usize>>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl MaterializedStageExec {
+    /// A stage with the partitions of `input`, materialized into `data`
+    fn new(data: Arc<StageData>, input: &Arc<dyn ExecutionPlan>) -> Self {
+        let cache = PlanProperties::new(
+            input.equivalence_properties().clone(),
+            input.output_partitioning().clone(),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        )
+        .with_scheduling_type(SchedulingType::Cooperative);
+        Self {
+            input: Arc::clone(input),
+            groups: (0..data.partitions.len()).map(|p| vec![p]).collect(),
+            data,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        }
+    }
+
+    /// Returns a stage reading the partitions of this stage in `count` groups
+    ///
+    /// The partitions `p` with the same `p % count` are read by the same
+    /// output partition, so that two stages hash partitioned the same way are
+    /// still co-partitioned. The stage keeps its partition count, the output
+    /// partitions after the first `count` ones are empty.
+    fn coalesced(&self, count: usize) -> Self {
+        let partition_count = self.data.partitions.len();
+        let mut groups = vec![vec![]; partition_count];
+        for partition in 0..partition_count {
+            groups[partition % count].push(partition);
+        }
+        self.with_groups(groups)
+    }
+
+    /// Returns a stage reading all the partitions of this stage in a single
+    /// output partition
+    fn collected(&self) -> Self {
+        self.with_groups(vec![(0..self.data.partitions.len()).collect()])
+    }
+
+    fn with_groups(&self, groups: Vec<Vec<usize>>) -> Self {
+        let cache = PlanProperties::new(
+            EquivalenceProperties::new(self.schema()),
+            Partitioning::UnknownPartitioning(groups.len()),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        )
+        .with_scheduling_type(SchedulingType::Cooperative);
+        Self {
+            groups,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+            ..self.clone()
+        }
+    }
+
+    /// The rows of the partitions of the materialized plan
+    pub fn rows(&self) -> usize {
+        self.data.rows
+    }
+
+    /// The size in bytes of the batches of the materialized plan
+    pub fn bytes(&self) -> usize {
+        self.data.bytes
+    }
+}
+
+impl DisplayAs for MaterializedStageExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let (rows, bytes) = if self.data.complete {
+            ("rows", "bytes")
+        } else {
+            ("buffered_rows", "buffered_bytes")
+        };
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "MaterializedStageExec: {rows}={}, {bytes}={}",
+                    self.data.rows, self.data.bytes
+                )?;
+                if self.groups.iter().any(|group| group.len() != 1) {
+                    write!(f, ", input_partitions={}", self.data.partitions.len())?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "{rows}={}", self.data.rows)?;
+                writeln!(f, "{bytes}={}", self.data.bytes)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MaterializedStageExec {
+    fn name(&self) -> &'static str {
+        "MaterializedStageExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    /// The new child is only displayed, the stage keeps its materialized
+    /// batches
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return internal_err!("MaterializedStageExec wrong number of children");
+        }
+        Ok(Arc::new(Self {
+            input: children.swap_remove(0),
+            ..Self::clone(&self)
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let Some(group) = self.groups.get(partition) else {
+            return internal_err!(
+                "Invalid partition {partition} for MaterializedStageExec with {} partitions",
+                self.groups.len()
+            );
+        };
+        let mut output = stream::empty().boxed();
+        for &input_partition in group {
+            let batches = self.data.partitions[input_partition].clone();
+            let remaining = self.data.take_remaining(input_partition)?;
+            output = output
+                .chain(stream::iter(batches.into_iter().map(Ok)))
+                .chain(stream::iter(remaining).flatten())
+                .boxed();
+        }
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema(), output));
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(
+            cooperative(ObservedStream::new(stream, baseline_metrics, None))
+                .with_cancellation_token(context.cancellation_token().clone()),
+        ))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        let mut statistics = Statistics::new_unknown(&self.schema());
+        if !self.data.complete {
+            return Ok(statistics);
+        }
+        let partitions = match partition {
+            Some(partition) => self.groups.get(partition).cloned().unwrap_or_default(),
+            None => (0..self.data.partitions.len()).collect(),
+        };
+        let batches = || {
+            partitions
+                .iter()
+                .flat_map(|partition| &self.data.partitions[*partition])
+        };
+        statistics.num_rows = Precision::Exact(batches().map(|b| b.num_rows()).sum());
+        statistics.total_byte_size =
+            Precision::Exact(batches().map(get_record_batch_memory_size).sum());
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::joins::utils::JoinOn;
+    use crate::test::{build_table_i32, TestMemoryExec};
+
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion_common::{JoinType, NullEquality};
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_physical_expr::expressions::Column;
+    use datafusion_physical_expr::PhysicalExpr;
+
+    /// A table `(a, b, c)` with `b` in `values`, split in 4 partitions
+    fn table(prefix: &str, values: &[i32]) -> Arc<dyn ExecutionPlan> {
+        let partitions = (0..4)
+            .map(|partition| {
+                let b: Vec<i32> =
+                    values.iter().skip(partition).step_by(4).copied().collect();
+                let a: Vec<i32> = (0..b.len() as i32).collect();
+                vec![build_table_i32(
+                    (&format!("{prefix}a"), &a),
+                    (&format!("{prefix}b"), &b),
+                    (&format!("{prefix}c"), &b),
+                )]
+            })
+            .collect::<Vec<_>>();
+        let schema = partitions[0][0].schema();
+        TestMemoryExec::try_new_exec(&partitions, schema, None).unwrap()
+    }
+
+    /// A partitioned inner hash join of `left` and `right` on `b`, with both
+    /// inputs hash repartitioned into 4 partitions
+    fn join_plan(left: &[i32], right: &[i32]) -> Result<Arc<dyn ExecutionPlan>> {
+        let repartitioned = |prefix: &str, values: &[i32]| {
+            let input = table(prefix, values);
+            let column = Arc::new(Column::new_with_schema(
+                &format!("{prefix}b"),
+                &input.schema(),
+            )?) as Arc<dyn PhysicalExpr>;
+            let repartition = RepartitionExec::try_new(
+                input,
+                Partitioning::Hash(vec![Arc::clone(&column)], 4),
+            )?;
+            Ok::<_, datafusion_common::DataFusionError>((Arc::new(repartition), column))
+        };
+        let (left, left_column) = repartitioned("l", left)?;
+        let (right, right_column) = repartitioned("r", right)?;
+        let on: JoinOn = vec![(left_column, right_column)];
+        Ok(Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+        )?))
+    }
+
+    /// A task context where hash joins fit in a single partition up to
+    /// `single_partition_rows`
+    fn task_context(single_partition_rows: usize) -> Arc<TaskContext> {
+        let mut config = SessionConfig::new();
+        config
+            .options_mut()
+            .optimizer
+            .hash_join_single_partition_threshold_rows = single_partition_rows;
+        Arc::new(TaskContext::default().with_session_config(config))
+    }
+
+    fn sorted_lines(batches: &[RecordBatch]) -> Vec<String> {
+        let formatted = pretty_format_batches(batches).unwrap().to_string();
+        let mut lines = formatted.lines().map(str::to_string).collect::<Vec<_>>();
+        lines.sort();
+        lines
+    }
+
+    /// Executes the join of `left` and `right` with an `AdaptiveExec`, checks
+    /// its results and returns its decisions and re-optimized plan
+    async fn run_adaptive(
+        left: &[i32],
+        right: &[i32],
+        context: Arc<TaskContext>,
+    ) -> Result<(Vec<String>, Arc<dyn ExecutionPlan>)> {
+        let expected = collect(join_plan(left, right)?, Arc::clone(&context)).await?;
+        let adaptive = Arc::new(AdaptiveExec::new(join_plan(left, right)?));
+        let batches = collect(Arc::clone(&adaptive) as _, context).await?;
+        assert_eq!(sorted_lines(&batches), sorted_lines(&expected));
+        let adapted_plan = Arc::clone(adaptive.adapted_plan().unwrap());
+        Ok((adaptive.decisions(), adapted_plan))
+    }
+
+    fn join_of(plan: &Arc<dyn ExecutionPlan>) -> &HashJoinExec {
+        plan.as_any()
+            .downcast_ref::<HashJoinExec>()
+            .or_else(|| plan.children()[0].as_any().downcast_ref::<HashJoinExec>())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn collect_left() -> Result<()> {
+        let (decisions, plan) =
+            run_adaptive(&[1, 2, 3], &[1, 2, 3, 3, 4, 5, 6, 7], task_context(10)).await?;
+        assert_eq!(
+            decisions,
+            vec!["HashJoinExec: build_rows=3 -> mode=CollectLeft without probe side repartitioning"]
+        );
+        let join = join_of(&plan);
+        assert_eq!(join.mode, PartitionMode::CollectLeft);
+        assert_eq!(join.right().name(), "DataSourceExec");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn swap_inputs() -> Result<()> {
+        let (decisions, plan) =
+            run_adaptive(&[1, 2, 3, 3, 4, 5, 6, 7], &[1, 2, 3], task_context(4)).await?;
+        assert_eq!(
+            decisions,
+            vec!["HashJoinExec: build_rows=8, probe_rows=3 -> swapped inputs, mode=CollectLeft"]
+        );
+        let join = join_of(&plan);
+        assert_eq!(join.mode, PartitionMode::CollectLeft);
+        assert_eq!(join.left().name(), "MaterializedStageExec");
+        assert_eq!(join.left().schema().field(0).name(), "ra");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn swap_and_coalesce_partitions() -> Result<()> {
+        let (decisions, plan) =
+            run_adaptive(&[1, 2, 3, 3, 4, 5, 6, 7], &[1, 2, 3], task_context(0)).await?;
+        assert_eq!(
+            decisions,
+            vec!["HashJoinExec: build_rows=8, probe_rows=3 -> swapped inputs, partitions=1"]
+        );
+        let join = join_of(&plan);
+        assert_eq!(join.mode, PartitionMode::Partitioned);
+        assert_eq!(join.left().schema().field(0).name(), "ra");
+        assert_eq!(plan.output_partitioning().partition_count(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn coalesce_partitions() -> Result<()> {
+        let (decisions, plan) =
+            run_adaptive(&[1, 2, 3, 4], &[1, 2, 3, 4], task_context(0)).await?;
+        assert_eq!(
+            decisions,
+            vec!["HashJoinExec: build_rows=4, probe_rows=4 -> partitions=1"]
+        );
+        let join = join_of(&plan);
+        assert_eq!(join.mode, PartitionMode::Partitioned);
+        assert_eq!(join.left().schema().field(0).name(), "la");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unchanged() -> Result<()> {
+        let (decisions, plan) =
+            run_adaptive(&[1, 2, 3], &[1, 2, 3, 3, 4, 5, 6, 7], task_context(0)).await?;
+        assert_eq!(decisions, vec!["HashJoinExec: build_rows=3 -> unchanged"]);
+        let join = join_of(&plan);
+        assert_eq!(join.mode, PartitionMode::Partitioned);
+        let probe = join
+            .right()
+            .as_any()
+            .downcast_ref::<MaterializedStageExec>();
+        assert_eq!(probe.unwrap().rows(), 4);
+        Ok(())
+    }
+}
@@ -23,6 +23,8 @@ use arrow::compute::concat_batches;
 use arrow::datatypes::SchemaRef;
 use std::sync::Arc;
 
+use crate::spill::get_record_batch_memory_size;
+
 /// Concatenate multiple [`RecordBatch`]es
 ///
 /// `BatchCoalescer` concatenates multiple small [`RecordBatch`]es, produced by
@@ -80,6 +82,8 @@ pub struct BatchCoalescer {
     buffer: Vec<RecordBatch>,
     /// Buffered row count
     buffered_rows: usize,
+    /// Memory used by the buffered batches
+    buffered_memory_size: usize,
     /// Limit: maximum number of rows to fetch, `None` means fetch all rows
     fetch: Option<usize>,
 }
@@ -103,6 +107,7 @@ impl BatchCoalescer {
             total_rows: 0,
             buffer: vec![],
             buffered_rows: 0,
+            buffered_memory_size: 0,
             fetch,
         }
     }
@@ -130,6 +135,22 @@ impl BatchCoalescer {
         self.buffer.is_empty()
     }
 
+    /// Return the memory used by the buffered batches
+    pub fn buffered_memory_size(&self) -> usize {
+        self.buffered_memory_size
+    }
+
+    /// Return the memory needed to concatenate the buffered batches, in
+    /// addition to the memory used by the buffered batches
+    pub fn concat_memory_size(&self) -> usize {
+        // A single batch is returned without copying it
+        if self.buffer.len() > 1 {
+            self.buffered_memory_size
+        } else {
+            0
+        }
+    }
+
     /// Checks if the buffer will reach the specified limit after getting
     /// `batch`.
     ///
@@ -146,6 +167,7 @@ impl BatchCoalescer {
 
                 let batch = batch.slice(0, remaining_rows);
                 self.buffered_rows += batch.num_rows();
+                self.buffered_memory_size += get_record_batch_memory_size(&batch);
                 self.total_rows = fetch;
                 self.buffer.push(batch);
                 true
@@ -164,6 +186,7 @@ impl BatchCoalescer {
         } else {
             self.total_rows += batch.num_rows();
             self.buffered_rows += batch.num_rows();
+            self.buffered_memory_size += get_record_batch_memory_size(&batch);
             self.buffer.push(batch);
             self.buffered_rows >= self.target_batch_size
         }
@@ -174,6 +197,7 @@ impl BatchCoalescer {
         let batch = concat_batches(&self.schema, &self.buffer)?;
         self.buffer.clear();
         self.buffered_rows = 0;
+        self.buffered_memory_size = 0;
         Ok(batch)
     }
 }
@@ -31,6 +31,7 @@ use crate::{
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::Result;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::PhysicalExpr;
 
@@ -180,6 +181,9 @@ impl ExecutionPlan for CoalesceBatchesExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        let reservation =
+            MemoryConsumer::new(format!("CoalesceBatchesExec[{partition}]"))
+                .register(context.memory_pool());
         Ok(Box::pin(CoalesceBatchesStream {
             input: self.input.execute(partition, context)?,
             coalescer: BatchCoalescer::new(
@@ -188,6 +192,7 @@ impl ExecutionPlan for CoalesceBatchesExec {
                 self.fetch,
             ),
             baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+            reservation,
             // Start by pulling data
             inner_state: CoalesceBatchesStreamState::Pull,
         }))
@@ -257,6 +262,8 @@ struct CoalesceBatchesStream {
     coalescer: BatchCoalescer,
     /// Execution metrics
     baseline_metrics: BaselineMetrics,
+    /// Memory reservation for the buffered batches
+    reservation: MemoryReservation,
     /// The current inner state of the stream. This state dictates the current
     /// action or operation to be performed in the streaming process.
     inner_state: CoalesceBatchesStreamState,
@@ -324,6 +331,20 @@ enum CoalesceBatchesStreamState {
 }
 
 impl CoalesceBatchesStream {
+    /// Concatenates the buffered batches, reserving the memory of the copy
+    fn finish_batch(&mut self) -> Result<RecordBatch> {
+        let concat_size = self.coalescer.concat_memory_size();
+        if concat_size > 0 {
+            // The copy is needed to make progress, so it is tracked without
+            // failing like the buffered batches
+            let size = self.coalescer.buffered_memory_size() + concat_size;
+            self.reservation.resize(size);
+        }
+        let batch = self.coalescer.finish_batch();
+        self.reservation.free();
+        batch
+    }
+
     fn poll_next_inner(
         self: &mut Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -339,7 +360,14 @@ impl CoalesceBatchesStream {
 
                     match input_batch {
                         Some(Ok(batch)) => match self.coalescer.push_batch(batch) {
-                            CoalescerState::Continue => {}
+                            CoalescerState::Continue => {
+                                // The buffered batches are held until the
+                                // target is reached. They are already
+                                // allocated, so they are tracked without
+                                // failing, to make the other consumers spill.
+                                let size = self.coalescer.buffered_memory_size();
+                                self.reservation.resize(size);
+                            }
                             CoalescerState::LimitReached => {
                                 self.inner_state = CoalesceBatchesStreamState::Exhausted;
                             }
@@ -358,7 +386,7 @@ impl CoalesceBatchesStream {
                 CoalesceBatchesStreamState::ReturnBuffer => {
                     let _timer = cloned_time.timer();
                     // Combine buffered batches into one batch and return it.
-                    let batch = self.coalescer.finish_batch()?;
+                    let batch = self.finish_batch()?;
                     // Set to pull state for the next iteration.
                     self.inner_state = CoalesceBatchesStreamState::Pull;
                     return Poll::Ready(Some(Ok(batch)));
@@ -371,7 +399,7 @@ impl CoalesceBatchesStream {
                     } else {
                         let _timer = cloned_time.timer();
                         // If the buffer still contains batches, prepare to return them.
-                        let batch = self.coalescer.finish_batch()?;
+                        let batch = self.finish_batch()?;
                         Poll::Ready(Some(Ok(batch)))
                     };
                 }
@@ -22,7 +22,8 @@ use std::fs::metadata;
 use std::sync::Arc;
 
 use super::SendableRecordBatchStream;
-use crate::stream::RecordBatchReceiverStream;
+use crate::spill::get_record_batch_memory_size;
+use crate::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
 use crate::{ColumnStatistics, Statistics};
 
 use arrow::array::Array;
@@ -92,8 +93,26 @@ fn build_file_list_recurse(
 /// If running in a tokio context spawns the execution of `stream` to a separate task
 /// allowing it to execute in parallel with an intermediate buffer of size `buffer`
 pub(crate) fn spawn_buffered(
+    input: SendableRecordBatchStream,
+    buffer: usize,
+) -> SendableRecordBatchStream {
+    spawn_buffered_inner(input, buffer, None)
+}
+
+/// Like [`spawn_buffered`], accounting for the batches held in the intermediate
+/// buffer in `reservation`
+pub(crate) fn spawn_buffered_with_reservation(
+    input: SendableRecordBatchStream,
+    buffer: usize,
+    reservation: SharedMemoryReservation,
+) -> SendableRecordBatchStream {
+    spawn_buffered_inner(input, buffer, Some(reservation))
+}
+
+fn spawn_buffered_inner(
     mut input: SendableRecordBatchStream,
     buffer: usize,
+    reservation: Option<SharedMemoryReservation>,
 ) -> SendableRecordBatchStream {
     // Use tokio only if running from a multi-thread tokio context
     match tokio::runtime::Handle::try_current() {
@@ -104,8 +123,16 @@ pub(crate) fn spawn_buffered(
 
             let sender = builder.tx();
 
+            let sender_reservation = reservation.clone();
             builder.spawn(async move {
                 while let Some(item) = input.next().await {
+                    let item = match (&item, &sender_reservation) {
+                        (Ok(batch), Some(reservation)) => reservation
+                            .lock()
+                            .try_grow(get_record_batch_memory_size(batch))
+                            .and(item),
+                        _ => item,
+                    };
                     if sender.send(item).await.is_err() {
                         // Receiver dropped when query is shutdown early (e.g., limit) or error,
                         // no need to return propagate the send error.
@@ -116,7 +143,19 @@ pub(crate) fn spawn_buffered(
                 Ok(())
             });
 
-            builder.build()
+            let stream = builder.build();
+            match reservation {
+                Some(reservation) => {
+                    let schema = stream.schema();
+                    let stream = stream.inspect_ok(move |batch| {
+                        reservation
+                            .lock()
+                            .shrink(get_record_batch_memory_size(batch));
+                    });
+                    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+                }
+                None => stream,
+            }
         }
         _ => input,
     }
@@ -256,20 +295,8 @@ mod tests {
             num_rows: Precision::Exact(3),
             total_byte_size: Precision::Exact(byte_size),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Absent,
-                    min_value: Precision::Absent,
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Absent,
-                    min_value: Precision::Absent,
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(0),
-                },
+                ColumnStatistics::new_unknown().with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown().with_null_count(Precision::Exact(0)),
             ],
         };
 
@@ -296,13 +323,9 @@ mod tests {
         let expected = Statistics {
             num_rows: Precision::Exact(6),
             total_byte_size: Precision::Exact(byte_size),
-            column_statistics: vec![ColumnStatistics {
-                distinct_count: Precision::Absent,
-                max_value: Precision::Absent,
-                min_value: Precision::Absent,
-                sum_value: Precision::Absent,
-                null_count: Precision::Exact(3),
-            }],
+            column_statistics: vec![
+                ColumnStatistics::new_unknown().with_null_count(Precision::Exact(3))
+            ],
         };
 
         assert_eq!(actual, expected);
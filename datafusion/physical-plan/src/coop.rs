@@ -64,6 +64,14 @@
 //!
 //! The optimizer rule currently checks the plan for exchange-like operators and leave operators
 //! that report [`SchedulingType::NonCooperative`] in their [plan properties](ExecutionPlan::properties).
+//!
+//! ## Cancellation
+//!
+//! Yield points are also where the execution of a query is cancelled. A [`CooperativeStream`]
+//! created with a [`CancellationToken`] checks the token before polling its input, and ends with
+//! the error of the token once it is cancelled. The built-in operators use the token of their
+//! [`TaskContext::cancellation_token`], which is cancelled when the query is cancelled, times out
+//! or exceeds its resource limits.
 
 #[cfg(any(
     datafusion_coop = "tokio_fallback",
@@ -83,6 +91,7 @@ use crate::{
 use arrow::record_batch::RecordBatch;
 use arrow_schema::Schema;
 use datafusion_common::{internal_err, Result, Statistics};
+use datafusion_execution::cancellation::CancellationToken;
 use datafusion_execution::TaskContext;
 
 use crate::execution_plan::SchedulingType;
@@ -101,6 +110,10 @@ where
     inner: T,
     #[cfg(datafusion_coop = "per_stream")]
     budget: u8,
+    /// Checked before polling `inner`
+    cancellation_token: Option<CancellationToken>,
+    /// Set once the cancellation error was returned
+    cancelled: bool,
 }
 
 #[cfg(datafusion_coop = "per_stream")]
@@ -119,8 +132,20 @@ where
             inner,
             #[cfg(datafusion_coop = "per_stream")]
             budget: YIELD_FREQUENCY,
+            cancellation_token: None,
+            cancelled: false,
         }
     }
+
+    /// Ends the stream with the error of `cancellation_token` once it is
+    /// cancelled. The token is checked every time the stream is polled.
+    pub fn with_cancellation_token(
+        mut self,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
 }
 
 impl<T> Stream for CooperativeStream<T>
@@ -133,6 +158,16 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if self.cancelled {
+            return Poll::Ready(None);
+        }
+        if let Some(token) = &self.cancellation_token {
+            if let Err(e) = token.check() {
+                self.cancelled = true;
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+
         #[cfg(datafusion_coop = "tokio")]
         {
             // TODO this should be the default implementation
@@ -276,8 +311,12 @@ impl ExecutionPlan for CooperativeExec {
         partition: usize,
         task_ctx: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        let cancellation_token = task_ctx.cancellation_token().clone();
         let child_stream = self.input.execute(partition, task_ctx)?;
-        Ok(make_cooperative(child_stream))
+        Ok(make_cooperative_with_cancellation(
+            child_stream,
+            cancellation_token,
+        ))
     }
 
     fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
@@ -316,6 +355,20 @@ pub fn make_cooperative(stream: SendableRecordBatchStream) -> SendableRecordBatc
     )))
 }
 
+/// Wraps a `SendableRecordBatchStream` inside a [`CooperativeStream`] that also ends with an
+/// error once `cancellation_token` is cancelled.
+///
+/// See [`make_cooperative`] and [`CooperativeStream::with_cancellation_token`].
+pub fn make_cooperative_with_cancellation(
+    stream: SendableRecordBatchStream,
+    cancellation_token: CancellationToken,
+) -> SendableRecordBatchStream {
+    Box::pin(
+        cooperative(RecordBatchStreamAdapter::new(stream.schema(), stream))
+            .with_cancellation_token(cancellation_token),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,4 +420,22 @@ mod tests {
         assert_eq!(out.len(), count);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn cancelled_stream() -> Result<()> {
+        let token = CancellationToken::new();
+        let mut stream =
+            make_cooperative_with_cancellation(make_empty_batches(10), token.clone());
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+
+        token.cancel();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: The query was cancelled"
+        );
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
 }
@@ -42,7 +42,9 @@ use std::sync::Arc;
 use crate::coalesce_partitions::CoalescePartitionsExec;
 use crate::display::DisplayableExecutionPlan;
 use crate::metrics::MetricsSet;
+use crate::progress::{PlanProgress, ProgressStream};
 use crate::projection::ProjectionExec;
+use crate::query_limits::QueryLimits;
 use crate::stream::RecordBatchStreamAdapter;
 
 use arrow::array::{Array, RecordBatch};
@@ -1103,21 +1105,43 @@ pub async fn collect(
 ///
 /// Dropping the stream will abort the execution of the query, and free up
 /// any allocated resources
+///
+/// # Progress
+///
+/// The progress of the query is reported by [`TaskContext::progress`] until
+/// the stream is dropped.
+///
+/// # Limits
+///
+/// The plan is executed with a [`TaskContext::for_execution`] of `context`,
+/// so that the limits apply to this execution only. The stream fails once
+/// `datafusion.execution.query_timeout` elapsed, or once
+/// `datafusion.execution.max_result_rows` rows were returned. The
+/// cancellation token of the execution is then cancelled to stop the rest of
+/// the query. The timeout requires a Tokio runtime with the time driver
+/// enabled. The stream also fails once the token is cancelled from elsewhere,
+/// e.g. by the memory pool of the query, or once the
+/// [`TaskContext::cancellation_token`] of `context` is cancelled.
 pub fn execute_stream(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<SendableRecordBatchStream> {
-    match plan.output_partitioning().partition_count() {
-        0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
-        1 => plan.execute(0, context),
+    let context = Arc::new(context.for_execution());
+    let progress = PlanProgress::register(&plan, &context);
+    let limits = QueryLimits::new(&context);
+    let stream = match plan.output_partitioning().partition_count() {
+        0 => Box::pin(EmptyRecordBatchStream::new(plan.schema())),
+        1 => plan.execute(0, context)?,
         2.. => {
             // merge into a single partition
             let plan = CoalescePartitionsExec::new(Arc::clone(&plan));
             // CoalescePartitionsExec must produce a single partition
             assert_eq!(1, plan.properties().output_partitioning().partition_count());
-            plan.execute(0, context)
+            plan.execute(0, context)?
         }
-    }
+    };
+    let stream = limits.wrap(stream);
+    Ok(Box::pin(ProgressStream::new(stream, progress)))
 }
 
 /// Execute the [ExecutionPlan] and collect the results in memory
@@ -1167,14 +1191,35 @@ pub async fn collect_partitioned(
 ///
 /// Dropping the stream will abort the execution of the query, and free up
 /// any allocated resources
+///
+/// # Progress
+///
+/// The progress of the query is reported by [`TaskContext::progress`] until
+/// all the streams are dropped.
+///
+/// # Limits
+///
+/// The plan is executed with a [`TaskContext::for_execution`] of `context`,
+/// so that the limits apply to this execution only. The stream fails once
+/// `datafusion.execution.query_timeout` elapsed, or once
+/// `datafusion.execution.max_result_rows` rows were returned. The
+/// cancellation token of the execution is then cancelled to stop the rest of
+/// the query. The timeout requires a Tokio runtime with the time driver
+/// enabled. The stream also fails once the token is cancelled from elsewhere,
+/// e.g. by the memory pool of the query, or once the
+/// [`TaskContext::cancellation_token`] of `context` is cancelled.
 pub fn execute_stream_partitioned(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<Vec<SendableRecordBatchStream>> {
+    let context = Arc::new(context.for_execution());
+    let progress = PlanProgress::register(&plan, &context);
+    let limits = QueryLimits::new(&context);
     let num_partitions = plan.output_partitioning().partition_count();
     let mut streams = Vec::with_capacity(num_partitions);
     for i in 0..num_partitions {
-        streams.push(plan.execute(i, Arc::clone(&context))?);
+        let stream = limits.wrap(plan.execute(i, Arc::clone(&context))?);
+        streams.push(Box::pin(ProgressStream::new(stream, Arc::clone(&progress))) as _);
     }
     Ok(streams)
 }
@@ -597,13 +597,12 @@ fn collect_new_statistics(
             )| {
                 let Some(interval) = interval else {
                     // If the interval is `None`, we can say that there are no rows:
-                    return ColumnStatistics {
-                        null_count: Precision::Exact(0),
-                        max_value: Precision::Exact(ScalarValue::Null),
-                        min_value: Precision::Exact(ScalarValue::Null),
-                        sum_value: Precision::Exact(ScalarValue::Null),
-                        distinct_count: Precision::Exact(0),
-                    };
+                    return ColumnStatistics::new_unknown()
+                        .with_null_count(Precision::Exact(0))
+                        .with_max_value(Precision::Exact(ScalarValue::Null))
+                        .with_min_value(Precision::Exact(ScalarValue::Null))
+                        .with_sum_value(Precision::Exact(ScalarValue::Null))
+                        .with_distinct_count(Precision::Exact(0));
                 };
                 let (lower, upper) = interval.into_bounds();
                 let (min_value, max_value) = if lower.eq(&upper) {
@@ -611,13 +610,11 @@ fn collect_new_statistics(
                 } else {
                     (Precision::Inexact(lower), Precision::Inexact(upper))
                 };
-                ColumnStatistics {
-                    null_count: input_column_stats[idx].null_count.to_inexact(),
-                    max_value,
-                    min_value,
-                    sum_value: Precision::Absent,
-                    distinct_count: distinct_count.to_inexact(),
-                }
+                ColumnStatistics::new_unknown()
+                    .with_null_count(input_column_stats[idx].null_count.to_inexact())
+                    .with_max_value(max_value)
+                    .with_min_value(min_value)
+                    .with_distinct_count(distinct_count.to_inexact())
             },
         )
         .collect()
@@ -822,11 +819,9 @@ mod tests {
             Statistics {
                 num_rows: Precision::Inexact(100),
                 total_byte_size: Precision::Inexact(100 * bytes_per_row),
-                column_statistics: vec![ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                    ..Default::default()
-                }],
+                column_statistics: vec![ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(100))))],
             },
             schema.clone(),
         ));
@@ -847,11 +842,9 @@ mod tests {
         );
         assert_eq!(
             statistics.column_statistics,
-            vec![ColumnStatistics {
-                min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
-                ..Default::default()
-            }]
+            vec![ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(25))))]
         );
 
         Ok(())
@@ -865,11 +858,9 @@ mod tests {
         let input = Arc::new(StatisticsExec::new(
             Statistics {
                 num_rows: Precision::Inexact(100),
-                column_statistics: vec![ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                    ..Default::default()
-                }],
+                column_statistics: vec![ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(100))))],
                 total_byte_size: Precision::Absent,
             },
             schema.clone(),
@@ -893,11 +884,9 @@ mod tests {
         assert_eq!(statistics.num_rows, Precision::Inexact(16));
         assert_eq!(
             statistics.column_statistics,
-            vec![ColumnStatistics {
-                min_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
-                max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
-                ..Default::default()
-            }]
+            vec![ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(10))))
+                .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(25))))]
         );
 
         Ok(())
@@ -916,16 +905,14 @@ mod tests {
             Statistics {
                 num_rows: Precision::Inexact(100),
                 column_statistics: vec![
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(50))),
-                        ..Default::default()
-                    },
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(50)))),
                 ],
                 total_byte_size: Precision::Absent,
             },
@@ -960,16 +947,12 @@ mod tests {
         assert_eq!(
             statistics.column_statistics,
             vec![
-                ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
-                    ..Default::default()
-                },
-                ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(46))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(50))),
-                    ..Default::default()
-                }
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(10))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(25)))),
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(46))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(50))))
             ]
         );
 
@@ -1016,21 +999,21 @@ mod tests {
                 num_rows: Precision::Inexact(1000),
                 total_byte_size: Precision::Inexact(4000),
                 column_statistics: vec![
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Float32(Some(1000.0))),
-                        max_value: Precision::Inexact(ScalarValue::Float32(Some(1100.0))),
-                        ..Default::default()
-                    },
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(3)))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Float32(Some(
+                            1000.0,
+                        ))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Float32(Some(
+                            1100.0,
+                        )))),
                 ],
             },
             schema,
@@ -1074,21 +1057,15 @@ mod tests {
         assert_eq!(statistics.num_rows, Precision::Inexact(134));
         assert_eq!(statistics.total_byte_size, Precision::Inexact(533));
         let exp_col_stats = vec![
-            ColumnStatistics {
-                min_value: Precision::Inexact(ScalarValue::Int32(Some(4))),
-                max_value: Precision::Inexact(ScalarValue::Int32(Some(53))),
-                ..Default::default()
-            },
-            ColumnStatistics {
-                min_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
-                max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
-                ..Default::default()
-            },
-            ColumnStatistics {
-                min_value: Precision::Inexact(ScalarValue::Float32(Some(1000.0))),
-                max_value: Precision::Inexact(ScalarValue::Float32(Some(1075.0))),
-                ..Default::default()
-            },
+            ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(4))))
+                .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(53)))),
+            ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(3))))
+                .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(3)))),
+            ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Float32(Some(1000.0))))
+                .with_max_value(Precision::Inexact(ScalarValue::Float32(Some(1075.0)))),
         ];
         let _ = exp_col_stats
             .into_iter()
@@ -1134,16 +1111,14 @@ mod tests {
                 num_rows: Precision::Inexact(1000),
                 total_byte_size: Precision::Inexact(4000),
                 column_statistics: vec![
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
-                        ..Default::default()
-                    },
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(3)))),
                 ],
             },
             schema,
@@ -1189,16 +1164,14 @@ mod tests {
                 num_rows: Precision::Inexact(1000),
                 total_byte_size: Precision::Inexact(4000),
                 column_statistics: vec![
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
-                        ..Default::default()
-                    },
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(3)))),
                 ],
             },
             schema,
@@ -1226,20 +1199,18 @@ mod tests {
         assert_eq!(
             statistics.column_statistics,
             vec![
-                ColumnStatistics {
-                    min_value: Precision::Exact(ScalarValue::Null),
-                    max_value: Precision::Exact(ScalarValue::Null),
-                    sum_value: Precision::Exact(ScalarValue::Null),
-                    distinct_count: Precision::Exact(0),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    min_value: Precision::Exact(ScalarValue::Null),
-                    max_value: Precision::Exact(ScalarValue::Null),
-                    sum_value: Precision::Exact(ScalarValue::Null),
-                    distinct_count: Precision::Exact(0),
-                    null_count: Precision::Exact(0),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Exact(ScalarValue::Null))
+                    .with_max_value(Precision::Exact(ScalarValue::Null))
+                    .with_sum_value(Precision::Exact(ScalarValue::Null))
+                    .with_distinct_count(Precision::Exact(0))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Exact(ScalarValue::Null))
+                    .with_max_value(Precision::Exact(ScalarValue::Null))
+                    .with_sum_value(Precision::Exact(ScalarValue::Null))
+                    .with_distinct_count(Precision::Exact(0))
+                    .with_null_count(Precision::Exact(0)),
             ]
         );
 
@@ -1257,16 +1228,16 @@ mod tests {
                 num_rows: Precision::Inexact(1000),
                 total_byte_size: Precision::Inexact(4000),
                 column_statistics: vec![
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
-                    ColumnStatistics {
-                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                        ..Default::default()
-                    },
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
+                    ColumnStatistics::new_unknown()
+                        .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                        .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(
+                            100,
+                        )))),
                 ],
             },
             schema,
@@ -1286,16 +1257,12 @@ mod tests {
         assert_eq!(
             statistics.column_statistics,
             vec![
-                ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(49))),
-                    ..Default::default()
-                },
-                ColumnStatistics {
-                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
-                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
-                    ..Default::default()
-                },
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(49)))),
+                ColumnStatistics::new_unknown()
+                    .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(1))))
+                    .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(100)))),
             ]
         );
 
@@ -1334,13 +1301,9 @@ mod tests {
         let expected_filter_statistics = Statistics {
             num_rows: Precision::Absent,
             total_byte_size: Precision::Absent,
-            column_statistics: vec![ColumnStatistics {
-                null_count: Precision::Absent,
-                min_value: Precision::Inexact(ScalarValue::Int32(Some(5))),
-                max_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
-                sum_value: Precision::Absent,
-                distinct_count: Precision::Absent,
-            }],
+            column_statistics: vec![ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Inexact(ScalarValue::Int32(Some(5))))
+                .with_max_value(Precision::Inexact(ScalarValue::Int32(Some(10))))],
         };
 
         assert_eq!(filter_statistics, expected_filter_statistics);
@@ -1397,9 +1360,7 @@ mod tests {
             Statistics {
                 num_rows: Precision::Inexact(1000),
                 total_byte_size: Precision::Inexact(4000),
-                column_statistics: vec![ColumnStatistics {
-                    ..Default::default()
-                }],
+                column_statistics: vec![ColumnStatistics::new_unknown()],
             },
             schema,
         ));
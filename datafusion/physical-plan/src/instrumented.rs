@@ -0,0 +1,557 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`InstrumentedExec`]: records a [`tracing`] span for each partition stream
+//! of an [`ExecutionPlan`]
+//!
+//! The spans are named `execute` and carry the following fields:
+//!
+//! * `operator`: the [`ExecutionPlan::name`] of the operator
+//! * `partition`: the executed partition
+//! * `output_rows`: the rows produced by the partition, if the operator
+//!   reports them with its [`BaselineMetrics`]
+//! * `elapsed_compute`: the CPU time spent by the partition, in nanoseconds,
+//!   if the operator reports it with its [`BaselineMetrics`]
+//!
+//! The `output_rows` and `elapsed_compute` fields are recorded when the stream
+//! ends or is dropped. The span of an operator is the parent of the spans of
+//! the inputs executed by it, so any [`tracing`] subscriber, such as an
+//! OpenTelemetry exporter, can rebuild the tree of the executed operators.
+//!
+//! Streams executed on spawned tasks, e.g. by `RepartitionExec`, are only
+//! attached to the span of their parent if the spawned tasks propagate the
+//! current span, which is done by registering a [`CurrentSpanTracer`] with
+//! [`set_join_set_tracer`].
+//!
+//! This module is only available with the `tracing` feature.
+//!
+//! [`BaselineMetrics`]: crate::metrics::BaselineMetrics
+//! [`set_join_set_tracer`]: datafusion_common_runtime::set_join_set_tracer
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::execution_plan::CardinalityEffect;
+use crate::filter_pushdown::{
+    ChildPushdownResult, FilterDescription, FilterPushdownPhase,
+    FilterPushdownPropagation,
+};
+use crate::metrics::{MetricValue, MetricsSet};
+use crate::projection::ProjectionExec;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, PlanProperties,
+    RecordBatchStream, SendableRecordBatchStream,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Result, Statistics};
+use datafusion_common_runtime::JoinSetTracer;
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr_common::sort_expr::OrderingRequirements;
+use futures::future::BoxFuture;
+use futures::{FutureExt, Stream, StreamExt};
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+/// Wraps an [`ExecutionPlan`] to record a [`tracing`] span for each of its
+/// executed partitions.
+///
+/// Apart from [`ExecutionPlan::execute`] and [`ExecutionPlan::as_any`], all
+/// the methods are delegated to the wrapped plan. The instrumented plan is
+/// downcast as an `InstrumentedExec`, so that code rebuilding a downcast
+/// operator does not silently drop the instrumentation: use
+/// [`InstrumentedExec::inner`] or [`uninstrumented`] to reach the wrapped
+/// operator.
+///
+/// See the [module level documentation](crate::instrumented) for the
+/// recorded fields.
+#[derive(Debug)]
+pub struct InstrumentedExec {
+    inner: Arc<dyn ExecutionPlan>,
+}
+
+impl InstrumentedExec {
+    /// Create a new `InstrumentedExec` wrapping `inner`
+    pub fn new(inner: Arc<dyn ExecutionPlan>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped plan
+    pub fn inner(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.inner
+    }
+}
+
+/// Wraps every node of `plan` with an [`InstrumentedExec`]
+///
+/// This should be called once, on a plan that is not instrumented yet.
+pub fn instrument_plan(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_up(|plan| {
+        Ok(Transformed::yes(
+            Arc::new(InstrumentedExec::new(plan)) as Arc<dyn ExecutionPlan>
+        ))
+    })
+    .map(|transformed| transformed.data)
+}
+
+/// Returns the plan wrapped by `plan` if it is an [`InstrumentedExec`], and
+/// `plan` otherwise
+pub fn uninstrumented(plan: &Arc<dyn ExecutionPlan>) -> &Arc<dyn ExecutionPlan> {
+    match plan.as_any().downcast_ref::<InstrumentedExec>() {
+        Some(instrumented) => instrumented.inner(),
+        None => plan,
+    }
+}
+
+/// A [`JoinSetTracer`] running the spawned tasks in the span that was current
+/// when they were spawned
+///
+/// ```
+/// # use datafusion_common_runtime::set_join_set_tracer;
+/// # use datafusion_physical_plan::instrumented::CurrentSpanTracer;
+/// set_join_set_tracer(&CurrentSpanTracer).expect("tracer is set once");
+/// ```
+#[derive(Debug)]
+pub struct CurrentSpanTracer;
+
+impl JoinSetTracer for CurrentSpanTracer {
+    fn trace_future(
+        &self,
+        fut: BoxFuture<'static, Box<dyn Any + Send>>,
+    ) -> BoxFuture<'static, Box<dyn Any + Send>> {
+        fut.in_current_span().boxed()
+    }
+
+    fn trace_block(
+        &self,
+        f: Box<dyn FnOnce() -> Box<dyn Any + Send> + Send>,
+    ) -> Box<dyn FnOnce() -> Box<dyn Any + Send> + Send> {
+        let span = Span::current();
+        Box::new(move || span.in_scope(f))
+    }
+}
+
+impl DisplayAs for InstrumentedExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        self.inner.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for InstrumentedExec {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        self.inner.properties()
+    }
+
+    fn check_invariants(
+        &self,
+        check: crate::execution_plan::InvariantLevel,
+    ) -> Result<()> {
+        self.inner.check_invariants(check)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.inner.required_input_distribution()
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        self.inner.required_input_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.inner.maintains_input_order()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        self.inner.benefits_from_input_partitioning()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        self.inner.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let inner = Arc::clone(&self.inner).with_new_children(children)?;
+        Ok(Arc::new(InstrumentedExec::new(inner)))
+    }
+
+    fn repartitioned(
+        &self,
+        target_partitions: usize,
+        config: &ConfigOptions,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        Ok(self
+            .inner
+            .repartitioned(target_partitions, config)?
+            .map(|plan| Arc::new(InstrumentedExec::new(plan)) as _))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let span = tracing::info_span!(
+            "execute",
+            operator = self.inner.name(),
+            partition,
+            output_rows = Empty,
+            elapsed_compute = Empty,
+        );
+        // the inputs executed by the operator are children of its span
+        let stream = span.in_scope(|| self.inner.execute(partition, context))?;
+        Ok(Box::pin(InstrumentedStream {
+            inner: stream,
+            plan: Arc::clone(&self.inner),
+            partition,
+            span,
+            finished: false,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.inner.metrics()
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        #[allow(deprecated)]
+        self.inner.statistics()
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        self.inner.partition_statistics(partition)
+    }
+
+    fn supports_limit_pushdown(&self) -> bool {
+        self.inner.supports_limit_pushdown()
+    }
+
+    fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn ExecutionPlan>> {
+        self.inner
+            .with_fetch(limit)
+            .map(|plan| Arc::new(InstrumentedExec::new(plan)) as _)
+    }
+
+    fn fetch(&self) -> Option<usize> {
+        self.inner.fetch()
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        self.inner.cardinality_effect()
+    }
+
+    fn try_swapping_with_projection(
+        &self,
+        projection: &ProjectionExec,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        self.inner.try_swapping_with_projection(projection)
+    }
+
+    fn gather_filters_for_pushdown(
+        &self,
+        phase: FilterPushdownPhase,
+        parent_filters: Vec<Arc<dyn PhysicalExpr>>,
+        config: &ConfigOptions,
+    ) -> Result<FilterDescription> {
+        self.inner
+            .gather_filters_for_pushdown(phase, parent_filters, config)
+    }
+
+    fn handle_child_pushdown_result(
+        &self,
+        phase: FilterPushdownPhase,
+        child_pushdown_result: ChildPushdownResult,
+        config: &ConfigOptions,
+    ) -> Result<FilterPushdownPropagation<Arc<dyn ExecutionPlan>>> {
+        let mut propagation = self.inner.handle_child_pushdown_result(
+            phase,
+            child_pushdown_result,
+            config,
+        )?;
+        propagation.updated_node = propagation
+            .updated_node
+            .map(|plan| Arc::new(InstrumentedExec::new(plan)) as _);
+        Ok(propagation)
+    }
+
+    fn with_new_state(
+        &self,
+        state: Arc<dyn Any + Send + Sync>,
+    ) -> Option<Arc<dyn ExecutionPlan>> {
+        self.inner
+            .with_new_state(state)
+            .map(|plan| Arc::new(InstrumentedExec::new(plan)) as _)
+    }
+}
+
+/// The stream of an [`InstrumentedExec`] partition, polling the stream of the
+/// wrapped plan inside the span of the partition
+struct InstrumentedStream {
+    inner: SendableRecordBatchStream,
+    plan: Arc<dyn ExecutionPlan>,
+    partition: usize,
+    span: Span,
+    /// Whether the metrics were recorded in the span
+    finished: bool,
+}
+
+impl InstrumentedStream {
+    /// Records the baseline metrics of the partition in the span
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        let Some(metrics) = self.plan.metrics() else {
+            return;
+        };
+        let partition_metric = |f: fn(&MetricValue) -> bool| {
+            metrics
+                .iter()
+                .filter(|metric| {
+                    metric.partition() == Some(self.partition) && f(metric.value())
+                })
+                .map(|metric| metric.value().as_usize() as u64)
+                .reduce(|acc, value| acc + value)
+        };
+        if let Some(output_rows) =
+            partition_metric(|value| matches!(value, MetricValue::OutputRows(_)))
+        {
+            self.span.record("output_rows", output_rows);
+        }
+        if let Some(elapsed_compute) =
+            partition_metric(|value| matches!(value, MetricValue::ElapsedCompute(_)))
+        {
+            self.span.record("elapsed_compute", elapsed_compute);
+        }
+    }
+}
+
+impl Stream for InstrumentedStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let poll = this.span.in_scope(|| this.inner.poll_next_unpin(cx));
+        if matches!(poll, Poll::Ready(None)) {
+            this.finish();
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for InstrumentedStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Drop for InstrumentedStream {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::coalesce_partitions::CoalescePartitionsExec;
+    use crate::placeholder_row::PlaceholderRowExec;
+    use crate::{collect, displayable};
+
+    use arrow::datatypes::Schema;
+    use datafusion_common_runtime::set_join_set_tracer;
+
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::Mutex;
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    /// The fields of a span, with the operator of its parent span
+    #[derive(Default)]
+    struct SpanFields(HashMap<String, String>);
+
+    /// A [`Layer`] collecting the fields of the closed spans
+    #[derive(Default, Clone)]
+    struct CollectingLayer {
+        open: Arc<Mutex<HashMap<Id, SpanFields>>>,
+        closed: Arc<Mutex<Vec<SpanFields>>>,
+    }
+
+    impl Visit for SpanFields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CollectingLayer {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+            let mut fields = SpanFields::default();
+            attrs.record(&mut fields);
+            if let Some(parent) = ctx.span(id).and_then(|span| span.parent()) {
+                let open = self.open.lock().unwrap();
+                if let Some(operator) = open[&parent.id()].0.get("operator") {
+                    fields.0.insert("parent".to_string(), operator.clone());
+                }
+            }
+            self.open.lock().unwrap().insert(id.clone(), fields);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: LayerContext<'_, S>) {
+            if let Some(fields) = self.open.lock().unwrap().get_mut(id) {
+                values.record(fields);
+            }
+        }
+
+        fn on_close(&self, id: Id, _ctx: LayerContext<'_, S>) {
+            if let Some(fields) = self.open.lock().unwrap().remove(&id) {
+                self.closed.lock().unwrap().push(fields);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_spans() -> Result<()> {
+        let plan = Arc::new(CoalesceBatchesExec::new(
+            Arc::new(CoalescePartitionsExec::new(Arc::new(
+                PlaceholderRowExec::new(Arc::new(Schema::empty())).with_partitions(2),
+            ))),
+            1024,
+        ));
+        let plan = instrument_plan(plan)?;
+
+        // the instrumented plan is displayed as the wrapped plan
+        assert_eq!(
+            displayable(plan.as_ref()).indent(true).to_string(),
+            "CoalesceBatchesExec: target_batch_size=1024\
+            \n  CoalescePartitionsExec\
+            \n    PlaceholderRowExec\n"
+        );
+        assert!(plan.as_any().is::<InstrumentedExec>());
+        assert!(uninstrumented(&plan).as_any().is::<CoalesceBatchesExec>());
+
+        // the input partitions are executed on spawned tasks
+        let _ = set_join_set_tracer(&CurrentSpanTracer);
+        let layer = CollectingLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let batches = collect(plan, Arc::new(TaskContext::default())).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+        let mut spans = layer
+            .closed
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|fields| {
+                let field = |name: &str| fields.0.get(name).cloned().unwrap_or_default();
+                (
+                    field("operator"),
+                    field("partition"),
+                    field("parent"),
+                    field("output_rows"),
+                    !field("elapsed_compute").is_empty(),
+                )
+            })
+            .collect::<Vec<_>>();
+        spans.sort();
+
+        let expected = vec![
+            ("CoalesceBatchesExec", "0", "", "2", true),
+            (
+                "CoalescePartitionsExec",
+                "0",
+                "CoalesceBatchesExec",
+                "2",
+                true,
+            ),
+            (
+                "PlaceholderRowExec",
+                "0",
+                "CoalescePartitionsExec",
+                "",
+                false,
+            ),
+            (
+                "PlaceholderRowExec",
+                "1",
+                "CoalescePartitionsExec",
+                "",
+                false,
+            ),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(operator, partition, parent, output_rows, elapsed)| {
+                (
+                    operator.to_string(),
+                    partition.to_string(),
+                    parent.to_string(),
+                    output_rows.to_string(),
+                    elapsed,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(spans, expected);
+        Ok(())
+    }
+}
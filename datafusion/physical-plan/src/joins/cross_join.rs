@@ -413,12 +413,8 @@ fn stats_cartesian_product(
     // Min, max and distinct_count on the other hand are invariants.
     let cross_join_stats = left_col_stats
         .into_iter()
-        .map(|s| ColumnStatistics {
-            null_count: s.null_count.multiply(&right_row_count),
-            distinct_count: s.distinct_count,
-            min_value: s.min_value,
-            max_value: s.max_value,
-            sum_value: s
+        .map(|s| {
+            let sum_value = s
                 .sum_value
                 .get_value()
                 // Cast the row count into the same type as any existing sum value
@@ -428,26 +424,32 @@ fn stats_cartesian_product(
                         .ok()
                 })
                 .map(|row_count| s.sum_value.multiply(&row_count))
-                .unwrap_or(Precision::Absent),
+                .unwrap_or(Precision::Absent);
+            ColumnStatistics::new_unknown()
+                .with_null_count(s.null_count.multiply(&right_row_count))
+                .with_distinct_count(s.distinct_count)
+                .with_min_value(s.min_value)
+                .with_max_value(s.max_value)
+                .with_sum_value(sum_value)
         })
         .chain(right_col_stats.into_iter().map(|s| {
-            ColumnStatistics {
-                null_count: s.null_count.multiply(&left_row_count),
-                distinct_count: s.distinct_count,
-                min_value: s.min_value,
-                max_value: s.max_value,
-                sum_value: s
-                    .sum_value
-                    .get_value()
-                    // Cast the row count into the same type as any existing sum value
-                    .and_then(|v| {
-                        Precision::<ScalarValue>::from(left_row_count)
-                            .cast_to(&v.data_type())
-                            .ok()
-                    })
-                    .map(|row_count| s.sum_value.multiply(&row_count))
-                    .unwrap_or(Precision::Absent),
-            }
+            let sum_value = s
+                .sum_value
+                .get_value()
+                // Cast the row count into the same type as any existing sum value
+                .and_then(|v| {
+                    Precision::<ScalarValue>::from(left_row_count)
+                        .cast_to(&v.data_type())
+                        .ok()
+                })
+                .map(|row_count| s.sum_value.multiply(&row_count))
+                .unwrap_or(Precision::Absent);
+            ColumnStatistics::new_unknown()
+                .with_null_count(s.null_count.multiply(&left_row_count))
+                .with_distinct_count(s.distinct_count)
+                .with_min_value(s.min_value)
+                .with_max_value(s.max_value)
+                .with_sum_value(sum_value)
         }))
         .collect();
 
@@ -679,33 +681,29 @@ mod tests {
             num_rows: Precision::Exact(left_row_count),
             total_byte_size: Precision::Exact(left_bytes),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(42))),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(3),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(42))))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a")))
+                    .with_null_count(Precision::Exact(3)),
             ],
         };
 
         let right = Statistics {
             num_rows: Precision::Exact(right_row_count),
             total_byte_size: Precision::Exact(right_bytes),
-            column_statistics: vec![ColumnStatistics {
-                distinct_count: Precision::Exact(3),
-                max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
-                min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
-                sum_value: Precision::Exact(ScalarValue::Int64(Some(20))),
-                null_count: Precision::Exact(2),
-            }],
+            column_statistics: vec![ColumnStatistics::new_unknown()
+                .with_distinct_count(Precision::Exact(3))
+                .with_max_value(Precision::Exact(ScalarValue::Int64(Some(12))))
+                .with_min_value(Precision::Exact(ScalarValue::Int64(Some(0))))
+                .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(20))))
+                .with_null_count(Precision::Exact(2))],
         };
 
         let result = stats_cartesian_product(left, right);
@@ -714,31 +712,27 @@ mod tests {
             num_rows: Precision::Exact(left_row_count * right_row_count),
             total_byte_size: Precision::Exact(2 * left_bytes * right_bytes),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(
                         42 * right_row_count as i64,
-                    ))),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(3 * right_row_count),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(3),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(
+                    ))))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a")))
+                    .with_null_count(Precision::Exact(3 * right_row_count)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(3))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(12))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(0))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(
                         20 * left_row_count as i64,
-                    ))),
-                    null_count: Precision::Exact(2 * left_row_count),
-                },
+                    ))))
+                    .with_null_count(Precision::Exact(2 * left_row_count)),
             ],
         };
 
@@ -753,33 +747,29 @@ mod tests {
             num_rows: Precision::Exact(left_row_count),
             total_byte_size: Precision::Exact(23),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(42))),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(3),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(42))))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a")))
+                    .with_null_count(Precision::Exact(3)),
             ],
         };
 
         let right = Statistics {
             num_rows: Precision::Absent,
             total_byte_size: Precision::Absent,
-            column_statistics: vec![ColumnStatistics {
-                distinct_count: Precision::Exact(3),
-                max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
-                min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
-                sum_value: Precision::Exact(ScalarValue::Int64(Some(20))),
-                null_count: Precision::Exact(2),
-            }],
+            column_statistics: vec![ColumnStatistics::new_unknown()
+                .with_distinct_count(Precision::Exact(3))
+                .with_max_value(Precision::Exact(ScalarValue::Int64(Some(12))))
+                .with_min_value(Precision::Exact(ScalarValue::Int64(Some(0))))
+                .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(20))))
+                .with_null_count(Precision::Exact(2))],
         };
 
         let result = stats_cartesian_product(left, right);
@@ -788,29 +778,26 @@ mod tests {
             num_rows: Precision::Absent,
             total_byte_size: Precision::Absent,
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Absent, // we don't know the row count on the right
-                    null_count: Precision::Absent, // we don't know the row count on the right
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Absent, // we don't know the row count on the right
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(3),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(
+                // we don't know the row count on the right, so the sum
+                // value and null count are absent
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4)))),
+                // we don't know the row count on the right, so the null count
+                // is absent
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a"))),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(3))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(12))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(0))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(
                         20 * left_row_count as i64,
-                    ))),
-                    null_count: Precision::Exact(2 * left_row_count),
-                },
+                    ))))
+                    .with_null_count(Precision::Exact(2 * left_row_count)),
             ],
         };
 
@@ -20,10 +20,11 @@
 use std::fmt;
 use std::mem::size_of;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::task::Poll;
 use std::{any::Any, vec};
 
+use super::shared_bounds::SharedBoundsAccumulator;
 use super::utils::{
     asymmetric_join_output_partitioning, get_final_indices_from_shared_bitmap,
     reorder_output_after_swap, swap_join_projection,
@@ -34,6 +35,9 @@ use super::{
 };
 use super::{JoinOn, JoinOnRef};
 use crate::execution_plan::{boundedness_from_children, EmissionType};
+use crate::filter_pushdown::{
+    ChildFilterDescription, FilterDescription, FilterPushdownPhase, PredicateSupport,
+};
 use crate::joins::join_hash_map::{JoinHashMapU32, JoinHashMapU64};
 use crate::projection::{
     try_embed_projection, try_pushdown_through_join, EmbeddedProjection, JoinData,
@@ -68,6 +72,7 @@ use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use arrow::util::bit_util;
+use datafusion_common::config::ConfigOptions;
 use datafusion_common::utils::memory::estimate_memory_size;
 use datafusion_common::{
     internal_datafusion_err, internal_err, plan_err, project_schema, DataFusionError,
@@ -79,7 +84,8 @@ use datafusion_expr::Operator;
 use datafusion_physical_expr::equivalence::{
     join_equivalence_properties, ProjectionMapping,
 };
-use datafusion_physical_expr::PhysicalExprRef;
+use datafusion_physical_expr::expressions::{lit, DynamicFilterPhysicalExpr};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
 use datafusion_physical_expr_common::datum::compare_op_for_nested;
 
 use ahash::RandomState;
@@ -358,6 +364,12 @@ pub struct HashJoinExec {
     pub null_equality: NullEquality,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
+    /// Filter on the probe side join keys, updated with the bounds of the build
+    /// side join keys for dynamic filter pushdown. Only present for the join
+    /// types whose output does not depend on the unmatched probe side rows.
+    dynamic_filter: Option<Arc<DynamicFilterPhysicalExpr>>,
+    /// Bounds of the build side join keys, shared by all output streams
+    bounds_accumulator: OnceLock<Arc<SharedBoundsAccumulator>>,
 }
 
 impl HashJoinExec {
@@ -404,6 +416,11 @@ impl HashJoinExec {
             projection.as_ref(),
         )?;
 
+        let dynamic_filter = Self::can_filter_probe_side(*join_type).then(|| {
+            let on_right = on.iter().map(|(_, r)| Arc::clone(r)).collect();
+            Arc::new(DynamicFilterPhysicalExpr::new(on_right, lit(true)))
+        });
+
         Ok(HashJoinExec {
             left,
             right,
@@ -419,6 +436,8 @@ impl HashJoinExec {
             column_indices,
             null_equality,
             cache,
+            dynamic_filter,
+            bounds_accumulator: OnceLock::new(),
         })
     }
 
@@ -478,6 +497,20 @@ impl HashJoinExec {
         ]
     }
 
+    /// Returns true if the probe side rows that match no build side row can be
+    /// filtered out before the join without changing its output.
+    fn can_filter_probe_side(join_type: JoinType) -> bool {
+        matches!(
+            join_type,
+            JoinType::Inner
+                | JoinType::Left
+                | JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::RightSemi
+        )
+    }
+
     /// Get probe side information for the hash join.
     pub fn probe_side() -> JoinSide {
         // In current implementation right side is always probe side.
@@ -757,7 +790,7 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
+        let mut new_join = HashJoinExec::try_new(
             Arc::clone(&children[0]),
             Arc::clone(&children[1]),
             self.on.clone(),
@@ -766,7 +799,10 @@ impl ExecutionPlan for HashJoinExec {
             self.projection.clone(),
             self.mode,
             self.null_equality,
-        )?))
+        )?;
+        // Keep the dynamic filter that may have been pushed down into the probe side
+        new_join.dynamic_filter = self.dynamic_filter.clone();
+        Ok(Arc::new(new_join))
     }
 
     fn execute(
@@ -802,6 +838,38 @@ impl ExecutionPlan for HashJoinExec {
             );
         }
 
+        let bounds_accumulator = self
+            .dynamic_filter
+            .as_ref()
+            .filter(|_| {
+                context
+                    .session_config()
+                    .options()
+                    .optimizer
+                    .enable_dynamic_filter_pushdown
+            })
+            .map(|filter| {
+                let accumulator = self.bounds_accumulator.get_or_init(|| {
+                    let build_partitions = match self.mode {
+                        PartitionMode::Partitioned => left_partitions,
+                        _ => 1,
+                    };
+                    Arc::new(SharedBoundsAccumulator::new(
+                        Arc::clone(filter),
+                        on_right.clone(),
+                        self.right.schema(),
+                        self.null_equality,
+                        context
+                            .session_config()
+                            .options()
+                            .optimizer
+                            .hash_join_inlist_pushdown_max_rows,
+                        build_partitions,
+                    ))
+                });
+                Arc::clone(accumulator)
+            });
+
         let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
         let left_fut = match self.mode {
             PartitionMode::CollectLeft => self.left_fut.try_once(|| {
@@ -818,6 +886,7 @@ impl ExecutionPlan for HashJoinExec {
                     reservation,
                     need_produce_result_in_final(self.join_type),
                     self.right().output_partitioning().partition_count(),
+                    bounds_accumulator,
                 ))
             })?,
             PartitionMode::Partitioned => {
@@ -835,6 +904,7 @@ impl ExecutionPlan for HashJoinExec {
                     reservation,
                     need_produce_result_in_final(self.join_type),
                     1,
+                    bounds_accumulator,
                 ))
             }
             PartitionMode::Auto => {
@@ -944,10 +1014,45 @@ impl ExecutionPlan for HashJoinExec {
             try_embed_projection(projection, self)
         }
     }
+
+    fn gather_filters_for_pushdown(
+        &self,
+        phase: FilterPushdownPhase,
+        parent_filters: Vec<Arc<dyn PhysicalExpr>>,
+        config: &ConfigOptions,
+    ) -> Result<FilterDescription> {
+        // Parent filters are not pushed through the join
+        let unsupported = parent_filters
+            .iter()
+            .map(|f| PredicateSupport::Unsupported(Arc::clone(f)))
+            .collect::<Vec<_>>();
+        let left = ChildFilterDescription {
+            parent_filters: unsupported.clone(),
+            self_filters: vec![],
+        };
+        let mut right = ChildFilterDescription {
+            parent_filters: unsupported,
+            self_filters: vec![],
+        };
+
+        // The dynamic filter can only be pushed down once the plan is final,
+        // as it references the probe side join keys
+        if let Some(filter) = &self.dynamic_filter {
+            if matches!(phase, FilterPushdownPhase::Post)
+                && config.optimizer.enable_dynamic_filter_pushdown
+            {
+                right =
+                    right.with_self_filter(Arc::clone(filter) as Arc<dyn PhysicalExpr>);
+            }
+        }
+
+        Ok(FilterDescription::new().with_child(left).with_child(right))
+    }
 }
 
 /// Reads the left (build) side of the input, buffering it in memory, to build a
 /// hash table (`LeftJoinData`)
+#[allow(clippy::too_many_arguments)]
 async fn collect_left_input(
     random_state: RandomState,
     left_stream: SendableRecordBatchStream,
@@ -956,6 +1061,7 @@ async fn collect_left_input(
     reservation: MemoryReservation,
     with_visited_indices_bitmap: bool,
     probe_threads_count: usize,
+    bounds_accumulator: Option<Arc<SharedBoundsAccumulator>>,
 ) -> Result<JoinLeftData> {
     let schema = left_stream.schema();
 
@@ -1045,6 +1151,11 @@ async fn collect_left_input(
         })
         .collect::<Result<Vec<_>>>()?;
 
+    // Publish the bounds of the join keys to the probe side before probing
+    if let Some(bounds_accumulator) = bounds_accumulator {
+        bounds_accumulator.report_partition(&left_values)?;
+    }
+
     let data = JoinLeftData::new(
         hashmap,
         single_batch,
@@ -36,6 +36,7 @@ pub mod utils;
 
 mod join_filter;
 mod join_hash_map;
+mod shared_bounds;
 
 #[cfg(test)]
 pub mod test_utils;
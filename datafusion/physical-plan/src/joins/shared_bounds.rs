@@ -0,0 +1,357 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Build side join key bounds shared by the partitions of a [`HashJoinExec`],
+//! used to update the dynamic filter pushed down into its probe side.
+//!
+//! [`HashJoinExec`]: super::HashJoinExec
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef};
+use arrow::datatypes::SchemaRef;
+use datafusion_common::{NullEquality, Result, ScalarValue};
+use datafusion_expr::Operator;
+use datafusion_functions_aggregate_common::min_max::{max_batch, min_batch};
+use datafusion_physical_expr::expressions::{
+    in_list, is_null, lit, BinaryExpr, DynamicFilterPhysicalExpr,
+};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
+use parking_lot::Mutex;
+
+/// Collects the bounds of the join keys of every build side partition of a
+/// hash join and, once all partitions have reported, updates the dynamic
+/// filter of the probe side with them.
+///
+/// The filter is only updated once all the build side partitions are known,
+/// because each of them is joined with a different partition of the probe
+/// side while the filter is applied to the whole probe side scan.
+pub(crate) struct SharedBoundsAccumulator {
+    /// The dynamic filter pushed down into the probe side
+    filter: Arc<DynamicFilterPhysicalExpr>,
+    /// The probe side join key expressions
+    on_right: Vec<PhysicalExprRef>,
+    /// The schema of the probe side
+    probe_schema: SchemaRef,
+    null_equality: NullEquality,
+    /// The maximum number of build side rows for which the key values are listed
+    max_in_list_rows: usize,
+    state: Mutex<BoundsState>,
+}
+
+struct BoundsState {
+    /// Number of build side partitions that have not reported yet
+    remaining_partitions: usize,
+    /// Number of build side rows reported so far
+    num_rows: usize,
+    /// The bounds of each join key
+    keys: Vec<KeyBounds>,
+}
+
+/// The values seen for one join key on the build side
+#[derive(Default)]
+struct KeyBounds {
+    /// The minimum and maximum non null values, if any
+    min_max: Option<(ScalarValue, ScalarValue)>,
+    has_nulls: bool,
+    /// The distinct non null values, or `None` once there are too many
+    /// build side rows to list them
+    distinct: Option<HashSet<ScalarValue>>,
+    /// Whether the values of this key can't be used to filter the probe side,
+    /// e.g. because their min/max can't be compared
+    unsupported: bool,
+}
+
+impl fmt::Debug for SharedBoundsAccumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedBoundsAccumulator")
+    }
+}
+
+impl SharedBoundsAccumulator {
+    /// Create a new accumulator expecting `num_partitions` build side partitions
+    pub(crate) fn new(
+        filter: Arc<DynamicFilterPhysicalExpr>,
+        on_right: Vec<PhysicalExprRef>,
+        probe_schema: SchemaRef,
+        null_equality: NullEquality,
+        max_in_list_rows: usize,
+        num_partitions: usize,
+    ) -> Self {
+        let keys = on_right
+            .iter()
+            .map(|_| KeyBounds {
+                distinct: (max_in_list_rows > 0).then(HashSet::new),
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            filter,
+            on_right,
+            probe_schema,
+            null_equality,
+            max_in_list_rows,
+            state: Mutex::new(BoundsState {
+                remaining_partitions: num_partitions,
+                num_rows: 0,
+                keys,
+            }),
+        }
+    }
+
+    /// Report the join key values of a build side partition, updating the
+    /// dynamic filter if it is the last partition to report.
+    pub(crate) fn report_partition(&self, left_values: &[ArrayRef]) -> Result<()> {
+        let mut state = self.state.lock();
+        let num_rows = left_values.first().map_or(0, |values| values.len());
+        state.num_rows += num_rows;
+        let list_values = state.num_rows <= self.max_in_list_rows;
+
+        for ((bounds, values), on_right) in
+            state.keys.iter_mut().zip(left_values).zip(&self.on_right)
+        {
+            if !bounds.unsupported {
+                let data_type = on_right.data_type(&self.probe_schema)?;
+                if values.data_type() == &data_type {
+                    bounds.update(values, list_values)?;
+                } else {
+                    bounds.unsupported = true;
+                }
+            }
+        }
+
+        state.remaining_partitions = state.remaining_partitions.saturating_sub(1);
+        if state.remaining_partitions == 0 {
+            if let Some(predicate) = self.build_predicate(&state.keys)? {
+                self.filter.update(predicate)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the predicate filtering out the probe side rows that can't
+    /// match any build side row, or `None` if no such predicate can be built
+    fn build_predicate(
+        &self,
+        keys: &[KeyBounds],
+    ) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+        let mut predicates = vec![];
+        for (bounds, on_right) in keys.iter().zip(&self.on_right) {
+            if bounds.unsupported {
+                continue;
+            }
+            let nulls_match =
+                bounds.has_nulls && self.null_equality == NullEquality::NullEqualsNull;
+
+            let Some((min, max)) = &bounds.min_max else {
+                if !nulls_match {
+                    // No probe side row can match an empty build side
+                    return Ok(Some(lit(false)));
+                }
+                predicates.push(is_null(Arc::clone(on_right))?);
+                continue;
+            };
+
+            let mut predicate: Arc<dyn PhysicalExpr> = if min == max {
+                Arc::new(BinaryExpr::new(
+                    Arc::clone(on_right),
+                    Operator::Eq,
+                    lit(min.clone()),
+                ))
+            } else {
+                let range = Arc::new(BinaryExpr::new(
+                    Arc::new(BinaryExpr::new(
+                        Arc::clone(on_right),
+                        Operator::GtEq,
+                        lit(min.clone()),
+                    )),
+                    Operator::And,
+                    Arc::new(BinaryExpr::new(
+                        Arc::clone(on_right),
+                        Operator::LtEq,
+                        lit(max.clone()),
+                    )),
+                ));
+                match &bounds.distinct {
+                    Some(distinct) => {
+                        let mut values = distinct.iter().cloned().collect::<Vec<_>>();
+                        values
+                            .sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                        let list = values.into_iter().map(lit).collect();
+                        Arc::new(BinaryExpr::new(
+                            range,
+                            Operator::And,
+                            in_list(
+                                Arc::clone(on_right),
+                                list,
+                                &false,
+                                &self.probe_schema,
+                            )?,
+                        ))
+                    }
+                    _ => range,
+                }
+            };
+            if nulls_match {
+                predicate = Arc::new(BinaryExpr::new(
+                    is_null(Arc::clone(on_right))?,
+                    Operator::Or,
+                    predicate,
+                ));
+            }
+            predicates.push(predicate);
+        }
+
+        Ok(predicates
+            .into_iter()
+            .reduce(|a, b| Arc::new(BinaryExpr::new(a, Operator::And, b))))
+    }
+}
+
+impl KeyBounds {
+    /// Update the bounds with the join key values of a build side partition
+    fn update(&mut self, values: &ArrayRef, list_values: bool) -> Result<()> {
+        self.has_nulls |= values.null_count() > 0;
+        if values.null_count() == values.len() {
+            return Ok(());
+        }
+
+        let (min, max) = (min_batch(values)?, max_batch(values)?);
+        self.min_max = match self.min_max.take() {
+            None => Some((min, max)),
+            Some((current_min, current_max)) => {
+                match (min.partial_cmp(&current_min), max.partial_cmp(&current_max)) {
+                    (Some(min_ordering), Some(max_ordering)) => Some((
+                        if min_ordering.is_lt() {
+                            min
+                        } else {
+                            current_min
+                        },
+                        if max_ordering.is_gt() {
+                            max
+                        } else {
+                            current_max
+                        },
+                    )),
+                    _ => {
+                        self.unsupported = true;
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        if !list_values {
+            self.distinct = None;
+        }
+        if let Some(distinct) = &mut self.distinct {
+            for index in 0..values.len() {
+                if values.is_valid(index) {
+                    distinct.insert(ScalarValue::try_from_array(values, index)?);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_physical_expr::expressions::col;
+    use datafusion_physical_expr_common::physical_expr::fmt_sql;
+
+    fn accumulator(
+        null_equality: NullEquality,
+        max_in_list_rows: usize,
+        num_partitions: usize,
+    ) -> (SharedBoundsAccumulator, Arc<DynamicFilterPhysicalExpr>) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let on_right = vec![col("a", &schema).unwrap(), col("b", &schema).unwrap()];
+        let filter =
+            Arc::new(DynamicFilterPhysicalExpr::new(on_right.clone(), lit(true)));
+        let accumulator = SharedBoundsAccumulator::new(
+            Arc::clone(&filter),
+            on_right,
+            schema,
+            null_equality,
+            max_in_list_rows,
+            num_partitions,
+        );
+        (accumulator, filter)
+    }
+
+    fn values(a: Vec<Option<i32>>, b: Vec<Option<&str>>) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(Int32Array::from(a)),
+            Arc::new(StringArray::from(b)),
+        ]
+    }
+
+    #[test]
+    fn bounds_of_all_partitions() -> Result<()> {
+        let (accumulator, filter) = accumulator(NullEquality::NullEqualsNothing, 0, 2);
+
+        accumulator.report_partition(&values(
+            vec![Some(3), None, Some(5)],
+            vec![Some("b"), Some("b"), None],
+        ))?;
+        // The filter is only updated once all partitions reported
+        assert_eq!(filter.current()?.to_string(), "true");
+
+        accumulator.report_partition(&values(vec![Some(1)], vec![Some("b")]))?;
+        assert_eq!(
+            filter.current()?.to_string(),
+            "a@0 >= 1 AND a@0 <= 5 AND b@1 = b"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn in_list_of_small_build_side() -> Result<()> {
+        let (accumulator, filter) = accumulator(NullEquality::NullEqualsNull, 4, 1);
+
+        accumulator.report_partition(&values(
+            vec![Some(30), Some(10), Some(20), Some(10)],
+            vec![Some("x"), None, Some("y"), Some("x")],
+        ))?;
+        assert_eq!(
+            fmt_sql(filter.current()?.as_ref()).to_string(),
+            "a >= 10 AND a <= 30 AND a IN (10, 20, 30) AND (b IS NULL OR b >= x AND b <= y AND b IN (x, y))"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_build_side() -> Result<()> {
+        let (accumulator, filter) = accumulator(NullEquality::NullEqualsNothing, 0, 2);
+
+        accumulator.report_partition(&values(vec![], vec![]))?;
+        accumulator.report_partition(&values(vec![None], vec![None]))?;
+        assert_eq!(filter.current()?.to_string(), "false");
+        Ok(())
+    }
+}
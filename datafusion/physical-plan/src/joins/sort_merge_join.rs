@@ -23,7 +23,6 @@ use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::{HashMap, VecDeque};
 use std::fmt::Formatter;
-use std::fs::File;
 use std::io::BufReader;
 use std::mem::size_of;
 use std::ops::Range;
@@ -107,10 +106,13 @@ use futures::{Stream, StreamExt};
 /// Buffered input is buffered for all record batches having the same value of join key.
 /// If the memory limit increases beyond the specified value and spilling is enabled,
 /// buffered batches could be spilled to disk. If spilling is disabled, the execution
-/// will fail under the same conditions. Multiple record batches of buffered could currently reside
-/// in memory/disk during the execution. The number of buffered batches residing in
-/// memory/disk depends on the number of rows of buffered input having the same value
-/// of join key as that of streamed input rows currently present in memory. Due to pre-sorted inputs,
+/// will fail under the same conditions. The spilled batches are read back on the task of
+/// the join, so they are only spilled to local files, and never to a configured
+/// [`SpillStorage`] whose readers may block to fetch the data. Multiple record batches
+/// of buffered could currently reside in memory/disk during the execution. The number
+/// of buffered batches residing in memory/disk depends on the number of rows of buffered
+/// input having the same value of join key as that of streamed input rows currently
+/// present in memory. Due to pre-sorted inputs,
 /// the algorithm understands when it is not needed anymore, and releases the buffered batches
 /// from memory/disk. The buffered input is managed through the states in `BufferedState`
 /// and buffered input batches are represented by `BufferedBatch`.
@@ -125,6 +127,8 @@ use futures::{Stream, StreamExt};
 ///
 /// Helpful short video demonstration:
 /// <https://www.youtube.com/watch?v=jiWCPJtDE2c>.
+///
+/// [`SpillStorage`]: datafusion_execution::spill_storage::SpillStorage
 #[derive(Debug, Clone)]
 pub struct SortMergeJoinExec {
     /// Left sorted joining execution plan
@@ -1351,7 +1355,9 @@ impl SortMergeJoinStream {
             join_metrics.spill_metrics.clone(),
             Arc::clone(&buffered_schema),
         )
-        .with_compression_type(spill_compression);
+        .with_compression_type(spill_compression)
+        // The spill files are read on the task of the join
+        .with_local_files(true);
         Ok(Self {
             state: SortMergeJoinState::Init,
             sort_options,
@@ -1453,7 +1459,7 @@ impl SortMergeJoinStream {
                     .set_max(self.reservation.size());
                 Ok(())
             }
-            Err(_) if self.runtime_env.disk_manager.tmp_files_enabled() => {
+            Err(_) if self.runtime_env.disk_manager.local_tmp_files_enabled() => {
                 // Spill buffered batch to disk
                 if let Some(batch) = buffered_batch.batch {
                     let spill_file = self
@@ -2342,7 +2348,7 @@ fn fetch_right_columns_from_batch_by_idxs(
             let mut buffered_cols: Vec<ArrayRef> =
                 Vec::with_capacity(buffered_indices.len());
 
-            let file = BufReader::new(File::open(spill_file.path())?);
+            let file = BufReader::new(spill_file.reader()?);
             let reader = StreamReader::try_new(file, None)?;
 
             for batch in reader {
@@ -2616,10 +2622,14 @@ mod tests {
     use datafusion_execution::config::SessionConfig;
     use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+    use datafusion_execution::spill_storage::ObjectStoreSpillStorage;
     use datafusion_execution::TaskContext;
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::BinaryExpr;
+    use futures::StreamExt;
     use insta::{allow_duplicates, assert_snapshot};
+    use object_store::memory::InMemory;
+    use object_store::ObjectStore;
 
     use crate::expressions::Column;
     use crate::joins::sort_merge_join::{get_corrected_filter_mask, JoinedRecordBatches};
@@ -4286,6 +4296,60 @@ mod tests {
         Ok(())
     }
 
+    /// The buffered batches are not spilled to a [`SpillStorage`], as they are
+    /// read on the task of the join, here of a current thread runtime
+    ///
+    /// [`SpillStorage`]: datafusion_execution::spill_storage::SpillStorage
+    #[tokio::test]
+    async fn overallocation_single_batch_no_spill_to_object_store() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![0, 1, 2, 3, 4, 5]),
+            ("b1", &vec![1, 2, 3, 4, 5, 6]),
+            ("c1", &vec![4, 5, 6, 7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![0, 10, 20, 30, 40]),
+            ("b2", &vec![1, 3, 4, 6, 8]),
+            ("c2", &vec![50, 60, 70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let sort_options = vec![SortOptions::default(); on.len()];
+
+        let store = Arc::new(InMemory::new());
+        let spill_storage =
+            ObjectStoreSpillStorage::new(Arc::clone(&store) as _, "spill".into());
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(100, 1.0)
+            .with_disk_manager_builder(
+                DiskManagerBuilder::default()
+                    .with_mode(DiskManagerMode::SpillStorage(Arc::new(spill_storage))),
+            )
+            .build_arc()?;
+        let task_ctx = TaskContext::default()
+            .with_session_config(SessionConfig::default().with_batch_size(50))
+            .with_runtime(runtime);
+
+        let join = join_with_options(
+            left,
+            right,
+            on,
+            Inner,
+            sort_options,
+            NullEquality::NullEqualsNothing,
+        )?;
+        let stream = join.execute(0, Arc::new(task_ctx))?;
+        let err = common::collect(stream).await.unwrap_err();
+
+        assert_contains!(err.to_string(), "Disk spilling disabled");
+        assert_eq!(join.metrics().unwrap().spill_count(), Some(0));
+        assert_eq!(store.list(None).count().await, 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn overallocation_single_batch_spill() -> Result<()> {
         let left = build_table(
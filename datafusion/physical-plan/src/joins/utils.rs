@@ -1737,13 +1737,12 @@ mod tests {
         distinct_count: Precision<usize>,
         null_count: Precision<usize>,
     ) -> ColumnStatistics {
-        ColumnStatistics {
-            distinct_count,
-            min_value: min.map(ScalarValue::from),
-            max_value: max.map(ScalarValue::from),
-            sum_value: Absent,
-            null_count,
-        }
+        ColumnStatistics::new_unknown()
+            .with_distinct_count(distinct_count)
+            .with_min_value(min.map(ScalarValue::from))
+            .with_max_value(max.map(ScalarValue::from))
+            .with_sum_value(Absent)
+            .with_null_count(null_count)
     }
 
     type PartialStats = (
@@ -1991,19 +1990,15 @@ mod tests {
 
     #[test]
     fn test_inner_join_cardinality_decimal_range() -> Result<()> {
-        let left_col_stats = vec![ColumnStatistics {
-            distinct_count: Absent,
-            min_value: Inexact(ScalarValue::Decimal128(Some(32500), 14, 4)),
-            max_value: Inexact(ScalarValue::Decimal128(Some(35000), 14, 4)),
-            ..Default::default()
-        }];
-
-        let right_col_stats = vec![ColumnStatistics {
-            distinct_count: Absent,
-            min_value: Inexact(ScalarValue::Decimal128(Some(33500), 14, 4)),
-            max_value: Inexact(ScalarValue::Decimal128(Some(34000), 14, 4)),
-            ..Default::default()
-        }];
+        let left_col_stats = vec![ColumnStatistics::new_unknown()
+            .with_distinct_count(Absent)
+            .with_min_value(Inexact(ScalarValue::Decimal128(Some(32500), 14, 4)))
+            .with_max_value(Inexact(ScalarValue::Decimal128(Some(35000), 14, 4)))];
+
+        let right_col_stats = vec![ColumnStatistics::new_unknown()
+            .with_distinct_count(Absent)
+            .with_min_value(Inexact(ScalarValue::Decimal128(Some(33500), 14, 4)))
+            .with_max_value(Inexact(ScalarValue::Decimal128(Some(34000), 14, 4)))];
 
         assert_eq!(
             estimate_inner_join_cardinality(
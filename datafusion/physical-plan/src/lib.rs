@@ -54,10 +54,12 @@ pub use crate::work_table::WorkTable;
 pub use spill::spill_manager::SpillManager;
 
 mod ordering;
+mod query_limits;
 mod render_tree;
 mod topk;
 mod visitor;
 
+pub mod adaptive;
 pub mod aggregates;
 pub mod analyze;
 pub mod async_func;
@@ -72,14 +74,19 @@ pub mod execution_plan;
 pub mod explain;
 pub mod filter;
 pub mod filter_pushdown;
+#[cfg(feature = "tracing")]
+pub mod instrumented;
 pub mod joins;
 pub mod limit;
 pub mod memory;
 pub mod metrics;
 pub mod placeholder_row;
+pub mod progress;
 pub mod projection;
 pub mod recursive_query;
 pub mod repartition;
+pub mod result_cache;
+pub mod shared_subplan;
 pub mod sorts;
 pub mod spill;
 pub mod stream;
@@ -156,6 +156,16 @@ pub struct SpillMetrics {
 
     /// total spilled rows during the execution of the operator
     pub spilled_rows: Count,
+
+    /// total time spent writing the spill files, to compute the write
+    /// throughput with `spilled_bytes`
+    pub spill_write_time: Time,
+
+    /// total bytes read from the spill files
+    pub spill_read_bytes: Count,
+
+    /// total time spent reading the spill files
+    pub spill_read_time: Time,
 }
 
 impl SpillMetrics {
@@ -165,6 +175,12 @@ impl SpillMetrics {
             spill_file_count: MetricBuilder::new(metrics).spill_count(partition),
             spilled_bytes: MetricBuilder::new(metrics).spilled_bytes(partition),
             spilled_rows: MetricBuilder::new(metrics).spilled_rows(partition),
+            spill_write_time: MetricBuilder::new(metrics)
+                .subset_time("spill_write_time", partition),
+            spill_read_bytes: MetricBuilder::new(metrics)
+                .counter("spill_read_bytes", partition),
+            spill_read_time: MetricBuilder::new(metrics)
+                .subset_time("spill_read_time", partition),
         }
     }
 }
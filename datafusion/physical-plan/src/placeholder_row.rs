@@ -163,7 +163,9 @@ impl ExecutionPlan for PlaceholderRowExec {
         }
 
         let ms = MemoryStream::try_new(self.data()?, Arc::clone(&self.schema), None)?;
-        Ok(Box::pin(cooperative(ms)))
+        Ok(Box::pin(cooperative(ms).with_cancellation_token(
+            context.cancellation_token().clone(),
+        )))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -0,0 +1,342 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PlanProgress`]: reports the progress of an executing [`ExecutionPlan`]
+
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use crate::metrics::MetricValue;
+use crate::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::instant::Instant;
+use datafusion_common::Result;
+use datafusion_execution::memory_pool::MemoryPool;
+use datafusion_execution::progress::{OperatorProgress, ProgressSource, QueryProgress};
+use datafusion_execution::TaskContext;
+use futures::{Stream, StreamExt};
+
+/// Reports the progress of an [`ExecutionPlan`] while it executes
+///
+/// The progress of each operator is read from its live [metrics], which are
+/// updated as the partitions of the operator produce their output. The total
+/// rows of each operator are estimated from its [`Statistics`] the first
+/// time the progress is reported.
+///
+/// The completed percentage of the query is estimated from the leaf-most
+/// operators reporting both their output rows and estimated rows, which are
+/// usually the scans of the query.
+///
+/// [metrics]: ExecutionPlan::metrics
+/// [`Statistics`]: datafusion_common::Statistics
+#[derive(Debug)]
+pub struct PlanProgress {
+    plan: Arc<dyn ExecutionPlan>,
+    memory_pool: Arc<dyn MemoryPool>,
+    start: Instant,
+    /// The estimated rows of the operators, in depth-first order
+    estimated_rows: OnceLock<Vec<Option<usize>>>,
+}
+
+impl PlanProgress {
+    /// Create a new `PlanProgress` for `plan`, executed with `context`
+    pub fn new(plan: Arc<dyn ExecutionPlan>, context: &TaskContext) -> Self {
+        Self {
+            plan,
+            memory_pool: Arc::clone(context.memory_pool()),
+            start: Instant::now(),
+            estimated_rows: OnceLock::new(),
+        }
+    }
+
+    /// Registers the progress of `plan` in the [`ProgressRegistry`] of
+    /// `context`, until the returned source is dropped
+    ///
+    /// [`ProgressRegistry`]: datafusion_execution::progress::ProgressRegistry
+    pub fn register(
+        plan: &Arc<dyn ExecutionPlan>,
+        context: &TaskContext,
+    ) -> Arc<dyn ProgressSource> {
+        let source: Arc<dyn ProgressSource> =
+            Arc::new(Self::new(Arc::clone(plan), context));
+        context.progress_registry().register(&source);
+        source
+    }
+}
+
+impl ProgressSource for PlanProgress {
+    fn progress(&self) -> QueryProgress {
+        let estimated_rows = self.estimated_rows.get_or_init(|| {
+            let mut estimated_rows = vec![];
+            visit(&self.plan, 0, &mut |plan, _| {
+                estimated_rows.push(
+                    plan.partition_statistics(None)
+                        .ok()
+                        .and_then(|stats| stats.num_rows.get_value().copied()),
+                )
+            });
+            estimated_rows
+        });
+
+        let mut operators = vec![];
+        visit(&self.plan, 0, &mut |plan, depth| {
+            let estimated_rows = estimated_rows.get(operators.len()).copied().flatten();
+            operators.push(operator_progress(plan, depth, estimated_rows));
+        });
+        let percent_complete = completed_fraction(&operators, 0)
+            .map(|(completed, total)| completed / total.max(1.0) * 100.0);
+
+        QueryProgress {
+            operators,
+            memory_used: self.memory_pool.reserved(),
+            elapsed: self.start.elapsed(),
+            percent_complete,
+        }
+    }
+}
+
+/// Visits `plan` and its inputs in depth-first order
+fn visit(
+    plan: &Arc<dyn ExecutionPlan>,
+    depth: usize,
+    f: &mut impl FnMut(&Arc<dyn ExecutionPlan>, usize),
+) {
+    f(plan, depth);
+    for child in plan.children() {
+        visit(child, depth + 1, f);
+    }
+}
+
+fn operator_progress(
+    plan: &Arc<dyn ExecutionPlan>,
+    depth: usize,
+    estimated_rows: Option<usize>,
+) -> OperatorProgress {
+    let metrics = plan.metrics();
+    let sum = |f: fn(&MetricValue) -> bool| {
+        metrics
+            .as_ref()
+            .and_then(|metrics| metrics.sum(|metric| f(metric.value())))
+            .map(|value| value.as_usize())
+    };
+    OperatorProgress {
+        name: plan.name().to_string(),
+        depth,
+        output_rows: sum(|value| matches!(value, MetricValue::OutputRows(_))),
+        bytes_scanned: metrics
+            .as_ref()
+            .and_then(|metrics| metrics.sum_by_name("bytes_scanned"))
+            .map(|value| value.as_usize()),
+        estimated_rows,
+        spill_count: sum(|value| matches!(value, MetricValue::SpillCount(_))),
+        memory_used: sum(|value| matches!(value, MetricValue::CurrentMemoryUsage(_))),
+    }
+}
+
+/// Returns the completed and total estimated rows of the leaf-most operators
+/// under `operators[index]` that report both
+///
+/// The inputs are used if they can all be estimated, the operator itself
+/// otherwise.
+fn completed_fraction(
+    operators: &[OperatorProgress],
+    index: usize,
+) -> Option<(f64, f64)> {
+    let depth = operators[index].depth;
+    let mut inputs = Some((0.0, 0.0));
+    let mut has_inputs = false;
+    let mut child = index + 1;
+    while child < operators.len() && operators[child].depth > depth {
+        if operators[child].depth == depth + 1 {
+            has_inputs = true;
+            inputs = match (inputs, completed_fraction(operators, child)) {
+                (Some((completed, total)), Some((input_completed, input_total))) => {
+                    Some((completed + input_completed, total + input_total))
+                }
+                _ => None,
+            };
+        }
+        child += 1;
+    }
+
+    match inputs {
+        Some(inputs) if has_inputs => Some(inputs),
+        _ => {
+            let operator = &operators[index];
+            operator.estimated_rows.zip(operator.output_rows).map(
+                |(estimated, output)| (output.min(estimated) as f64, estimated as f64),
+            )
+        }
+    }
+}
+
+/// A stream keeping the progress of its query registered until it is dropped
+pub(crate) struct ProgressStream {
+    inner: SendableRecordBatchStream,
+    _progress: Arc<dyn ProgressSource>,
+}
+
+impl ProgressStream {
+    pub(crate) fn new(
+        inner: SendableRecordBatchStream,
+        progress: Arc<dyn ProgressSource>,
+    ) -> Self {
+        Self {
+            inner,
+            _progress: progress,
+        }
+    }
+}
+
+impl Stream for ProgressStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for ProgressStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::execute_stream;
+    use crate::memory::{LazyBatchGenerator, LazyMemoryExec};
+    use crate::test::make_partition;
+
+    use parking_lot::RwLock;
+
+    /// Generates `num_batches` batches of 100 rows
+    #[derive(Debug)]
+    struct TestGenerator {
+        num_batches: usize,
+    }
+
+    impl std::fmt::Display for TestGenerator {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "TestGenerator: num_batches={}", self.num_batches)
+        }
+    }
+
+    impl LazyBatchGenerator for TestGenerator {
+        fn generate_next_batch(&mut self) -> Result<Option<RecordBatch>> {
+            if self.num_batches == 0 {
+                return Ok(None);
+            }
+            self.num_batches -= 1;
+            Ok(Some(make_partition(100)))
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_progress() -> Result<()> {
+        let generator = TestGenerator { num_batches: 4 };
+        let input = LazyMemoryExec::try_new(
+            make_partition(0).schema(),
+            vec![Arc::new(RwLock::new(generator))],
+        )?;
+        let plan = Arc::new(CoalesceBatchesExec::new(Arc::new(input), 1000));
+        let context = Arc::new(TaskContext::default());
+        assert!(context.progress().is_empty());
+
+        let mut stream = execute_stream(plan, Arc::clone(&context))?;
+        let progress = context.progress();
+        assert_eq!(progress.len(), 1);
+        let operators = progress[0]
+            .operators
+            .iter()
+            .map(|op| (op.name.as_str(), op.depth, op.output_rows))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operators,
+            vec![
+                ("CoalesceBatchesExec", 0, Some(0)),
+                ("LazyMemoryExec", 1, Some(0))
+            ]
+        );
+        // the rows of a `LazyMemoryExec` can not be estimated
+        assert_eq!(progress[0].percent_complete, None);
+
+        while let Some(batch) = stream.next().await {
+            batch?;
+        }
+        let progress = context.progress();
+        assert_eq!(progress[0].output_rows(), Some(400));
+        assert_eq!(progress[0].operators[1].output_rows, Some(400));
+        assert_eq!(progress[0].spill_count(), 0);
+
+        drop(stream);
+        assert!(context.progress().is_empty());
+        Ok(())
+    }
+
+    fn operator(
+        depth: usize,
+        output_rows: Option<usize>,
+        estimated_rows: Option<usize>,
+    ) -> OperatorProgress {
+        OperatorProgress {
+            name: "TestExec".to_string(),
+            depth,
+            output_rows,
+            bytes_scanned: None,
+            estimated_rows,
+            spill_count: None,
+            memory_used: None,
+        }
+    }
+
+    #[test]
+    fn completed_fraction_of_inputs() {
+        // a join of two estimated scans
+        let operators = vec![
+            operator(0, Some(10), None),
+            operator(1, Some(50), Some(100)),
+            operator(2, Some(50), Some(100)),
+            operator(1, Some(300), Some(200)),
+        ];
+        assert_eq!(completed_fraction(&operators, 0), Some((250.0, 300.0)));
+
+        // an input can not be estimated, but its parent can
+        let operators = vec![
+            operator(0, Some(10), Some(40)),
+            operator(1, Some(50), Some(100)),
+            operator(1, Some(300), None),
+        ];
+        assert_eq!(completed_fraction(&operators, 0), Some((10.0, 40.0)));
+
+        // nothing can be estimated
+        let operators = vec![operator(0, Some(10), None), operator(1, None, None)];
+        assert_eq!(completed_fraction(&operators, 0), None);
+    }
+}
@@ -33,19 +33,27 @@ use super::{
     SendableRecordBatchStream, Statistics,
 };
 use crate::execution_plan::CardinalityEffect;
+use crate::filter_pushdown::{
+    ChildFilterDescription, ChildPushdownResult, FilterDescription, FilterPushdownPhase,
+    FilterPushdownPropagation, PredicateSupport,
+};
 use crate::joins::utils::{ColumnIndex, JoinFilter, JoinOn, JoinOnRef};
 use crate::{ColumnStatistics, DisplayFormatType, ExecutionPlan, PhysicalExpr};
 
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::config::ConfigOptions;
 use datafusion_common::stats::Precision;
 use datafusion_common::tree_node::{
     Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
 };
 use datafusion_common::{internal_err, JoinSide, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
+use datafusion_expr::Volatility;
 use datafusion_physical_expr::equivalence::ProjectionMapping;
 use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::ScalarFunctionExpr;
 use datafusion_physical_expr_common::physical_expr::{fmt_sql, PhysicalExprRef};
 use datafusion_physical_expr_common::sort_expr::{LexOrdering, LexRequirement};
 
@@ -228,11 +236,22 @@ impl ExecutionPlan for ProjectionExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         trace!("Start ProjectionExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        // Only the computed arrays allocate memory, the columns are shared
+        // with the input batches
+        let reservation = self
+            .expr
+            .iter()
+            .any(|(expr, _)| !expr.as_any().is::<Column>())
+            .then(|| {
+                MemoryConsumer::new(format!("ProjectionExec[{partition}]"))
+                    .register(context.memory_pool())
+            });
         Ok(Box::pin(ProjectionStream {
             schema: Arc::clone(&self.schema),
             expr: self.expr.iter().map(|x| Arc::clone(&x.0)).collect(),
             input: self.input.execute(partition, context)?,
             baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+            reservation,
         }))
     }
 
@@ -273,6 +292,63 @@ impl ExecutionPlan for ProjectionExec {
             Ok(Some(Arc::new(projection.clone())))
         }
     }
+
+    fn gather_filters_for_pushdown(
+        &self,
+        _phase: FilterPushdownPhase,
+        parent_filters: Vec<Arc<dyn PhysicalExpr>>,
+        _config: &ConfigOptions,
+    ) -> Result<FilterDescription> {
+        // Filters are rewritten in terms of the input of the projection. For
+        // instance, the dynamic filter `d@1 < 0.5` of a `TopK` on a computed
+        // distance `cosine_distance(emb@0, [...]) AS d` becomes
+        // `cosine_distance(emb@0, [...]) < 0.5`, that the scan can evaluate.
+        let parent_filters = parent_filters
+            .into_iter()
+            .map(|filter| {
+                let references_volatile = collect_columns(&filter)
+                    .iter()
+                    .any(|column| is_volatile(&self.expr[column.index()].0));
+                if references_volatile {
+                    return Ok(PredicateSupport::Unsupported(filter));
+                }
+                Ok(match update_expr(&filter, &self.expr, true)? {
+                    Some(rewritten) => PredicateSupport::Supported(rewritten),
+                    // The filter does not reference any column
+                    None => PredicateSupport::Supported(filter),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(FilterDescription::new().with_child(ChildFilterDescription {
+            parent_filters,
+            self_filters: vec![],
+        }))
+    }
+
+    fn handle_child_pushdown_result(
+        &self,
+        _phase: FilterPushdownPhase,
+        child_pushdown_result: ChildPushdownResult,
+        _config: &ConfigOptions,
+    ) -> Result<FilterPushdownPropagation<Arc<dyn ExecutionPlan>>> {
+        Ok(FilterPushdownPropagation::transparent(
+            child_pushdown_result,
+        ))
+    }
+}
+
+/// Returns true if `expr` contains a volatile function, whose result would
+/// change if the expression were evaluated more than once
+fn is_volatile(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.exists(|expr| {
+        Ok(expr
+            .as_any()
+            .downcast_ref::<ScalarFunctionExpr>()
+            .is_some_and(|func| {
+                func.fun().signature().volatility == Volatility::Volatile
+            }))
+    })
+    .unwrap_or(true)
 }
 
 fn stats_projection(
@@ -310,7 +386,7 @@ fn stats_projection(
 }
 
 impl ProjectionStream {
-    fn batch_project(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+    fn batch_project(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
         // Records time on drop
         let _timer = self.baseline_metrics.elapsed_compute().timer();
         let arrays = self
@@ -322,6 +398,17 @@ impl ProjectionStream {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        if let Some(reservation) = &mut self.reservation {
+            let computed_size = self
+                .expr
+                .iter()
+                .zip(&arrays)
+                .filter(|(expr, _)| !expr.as_any().is::<Column>())
+                .map(|(_, array)| array.get_array_memory_size())
+                .sum();
+            reservation.try_grow(computed_size)?;
+        }
+
         if arrays.is_empty() {
             let options =
                 RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
@@ -339,6 +426,11 @@ struct ProjectionStream {
     expr: Vec<Arc<dyn PhysicalExpr>>,
     input: SendableRecordBatchStream,
     baseline_metrics: BaselineMetrics,
+    /// Memory reservation for the arrays computed for the last output batch,
+    /// if any expression is not a column. It is held until the consumer polls
+    /// the next batch, as the batch is then accounted for by the consumer if
+    /// it keeps it
+    reservation: Option<MemoryReservation>,
 }
 
 impl Stream for ProjectionStream {
@@ -348,6 +440,9 @@ impl Stream for ProjectionStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if let Some(reservation) = &mut self.reservation {
+            reservation.free();
+        }
         let poll = self.input.poll_next_unpin(cx).map(|x| match x {
             Some(Ok(batch)) => Some(self.batch_project(&batch)),
             other => other,
@@ -1128,27 +1223,21 @@ mod tests {
             num_rows: Precision::Exact(5),
             total_byte_size: Precision::Exact(23),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(42))),
-                    null_count: Precision::Exact(0),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(3),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
-                    min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
-                    sum_value: Precision::Exact(ScalarValue::Float32(Some(5.5))),
-                    null_count: Precision::Absent,
-                },
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(42))))
+                    .with_null_count(Precision::Exact(0)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a")))
+                    .with_null_count(Precision::Exact(3)),
+                ColumnStatistics::new_unknown()
+                    .with_max_value(Precision::Exact(ScalarValue::Float32(Some(1.1))))
+                    .with_min_value(Precision::Exact(ScalarValue::Float32(Some(0.1))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Float32(Some(5.5)))),
             ],
         }
     }
@@ -1175,20 +1264,17 @@ mod tests {
             num_rows: Precision::Exact(5),
             total_byte_size: Precision::Exact(23),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(1),
-                    max_value: Precision::Exact(ScalarValue::from("x")),
-                    min_value: Precision::Exact(ScalarValue::from("a")),
-                    sum_value: Precision::Absent,
-                    null_count: Precision::Exact(3),
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(42))),
-                    null_count: Precision::Exact(0),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(1))
+                    .with_max_value(Precision::Exact(ScalarValue::from("x")))
+                    .with_min_value(Precision::Exact(ScalarValue::from("a")))
+                    .with_null_count(Precision::Exact(3)),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(42))))
+                    .with_null_count(Precision::Exact(0)),
             ],
         };
 
@@ -1211,20 +1297,16 @@ mod tests {
             num_rows: Precision::Exact(5),
             total_byte_size: Precision::Exact(60),
             column_statistics: vec![
-                ColumnStatistics {
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
-                    min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
-                    sum_value: Precision::Exact(ScalarValue::Float32(Some(5.5))),
-                    null_count: Precision::Absent,
-                },
-                ColumnStatistics {
-                    distinct_count: Precision::Exact(5),
-                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
-                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
-                    sum_value: Precision::Exact(ScalarValue::Int64(Some(42))),
-                    null_count: Precision::Exact(0),
-                },
+                ColumnStatistics::new_unknown()
+                    .with_max_value(Precision::Exact(ScalarValue::Float32(Some(1.1))))
+                    .with_min_value(Precision::Exact(ScalarValue::Float32(Some(0.1))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Float32(Some(5.5)))),
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Exact(5))
+                    .with_max_value(Precision::Exact(ScalarValue::Int64(Some(21))))
+                    .with_min_value(Precision::Exact(ScalarValue::Int64(Some(-4))))
+                    .with_sum_value(Precision::Exact(ScalarValue::Int64(Some(42))))
+                    .with_null_count(Precision::Exact(0)),
             ],
         };
 
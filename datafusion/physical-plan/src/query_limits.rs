@@ -0,0 +1,301 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`QueryLimits`]: enforces the timeout and the result rows limit of a query,
+//! and its cancellation
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::{RecordBatchStream, SendableRecordBatchStream};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::Result;
+use datafusion_execution::cancellation::{CancellationReason, CancellationToken};
+use datafusion_execution::TaskContext;
+use futures::{Stream, StreamExt};
+use tokio::time::{Instant, Sleep};
+
+/// The limits of a query, enforced on its output streams
+///
+/// * `datafusion.execution.query_timeout`: the query fails once the timeout
+///   elapsed since it started to execute. The timeout is measured with the
+///   Tokio timer, so the query must be executed on a runtime with the time
+///   driver enabled.
+/// * `datafusion.execution.max_result_rows`: the query fails once its output
+///   streams returned more rows in total.
+///
+/// When a limit is exceeded, the [`CancellationToken`] of the query is
+/// cancelled, which also stops the operators executing on other tasks.
+///
+/// The output streams also fail once the token is cancelled from elsewhere,
+/// e.g. by the memory pool of the query, even if the operators of the query
+/// no longer check it, such as a sort returning its sorted batches.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryLimits {
+    cancellation_token: CancellationToken,
+    timeout: Option<(Instant, Duration)>,
+    max_result_rows: Option<usize>,
+    /// Rows returned by all the output streams of the query
+    result_rows: Arc<AtomicUsize>,
+}
+
+impl QueryLimits {
+    /// Returns the limits configured in `context`. The timeout starts when
+    /// this is called.
+    pub(crate) fn new(context: &TaskContext) -> Self {
+        let options = &context.session_config().options().execution;
+        let timeout = options.query_timeout.map(|millis| {
+            let timeout = Duration::from_millis(millis);
+            (Instant::now() + timeout, timeout)
+        });
+        Self {
+            cancellation_token: context.cancellation_token().clone(),
+            timeout,
+            max_result_rows: options.max_result_rows,
+            result_rows: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Wraps an output stream of the query to enforce the limits
+    pub(crate) fn wrap(
+        &self,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(QueryLimitStream {
+            inner: stream,
+            limits: self.clone(),
+            sleep: self
+                .timeout
+                .map(|(deadline, _)| Box::pin(tokio::time::sleep_until(deadline))),
+            done: false,
+        })
+    }
+}
+
+struct QueryLimitStream {
+    inner: SendableRecordBatchStream,
+    limits: QueryLimits,
+    /// Completes when the query times out
+    sleep: Option<Pin<Box<Sleep>>>,
+    /// Set once the stream returned an error for the exceeded limits
+    done: bool,
+}
+
+impl QueryLimitStream {
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        let limits = &self.limits;
+        if let (Some(sleep), Some((_, timeout))) = (&mut self.sleep, limits.timeout) {
+            if sleep.as_mut().poll(cx).is_ready() {
+                limits
+                    .cancellation_token
+                    .cancel_with_reason(CancellationReason::Timeout(timeout));
+            }
+        }
+        limits.cancellation_token.check()?;
+
+        let batch = match std::task::ready!(self.inner.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => batch,
+            other => return Poll::Ready(other),
+        };
+        if let Some(max_result_rows) = limits.max_result_rows {
+            let rows = limits
+                .result_rows
+                .fetch_add(batch.num_rows(), Ordering::Relaxed)
+                + batch.num_rows();
+            if rows > max_result_rows {
+                limits.cancellation_token.cancel_with_reason(
+                    CancellationReason::ResourcesExhausted(format!(
+                        "The query returned more than {max_result_rows} rows. Try increasing `datafusion.execution.max_result_rows`."
+                    )),
+                );
+                limits.cancellation_token.check()?;
+            }
+        }
+        Poll::Ready(Some(Ok(batch)))
+    }
+}
+
+impl Stream for QueryLimitStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let poll = self.poll_next_inner(cx);
+        if matches!(poll, Poll::Ready(Some(Err(_))))
+            && self.limits.cancellation_token.is_cancelled()
+        {
+            self.done = true;
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for QueryLimitStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::stream::RecordBatchStreamAdapter;
+    use crate::test::{make_partition, TestMemoryExec};
+    use crate::{collect, execute_stream, ExecutionPlan};
+
+    use arrow::datatypes::Schema;
+    use datafusion_common::assert_contains;
+    use datafusion_execution::config::SessionConfig;
+
+    fn task_context(f: impl FnOnce(&mut SessionConfig)) -> Arc<TaskContext> {
+        let mut config = SessionConfig::new();
+        f(&mut config);
+        Arc::new(TaskContext::default().with_session_config(config))
+    }
+
+    #[tokio::test]
+    async fn query_timeout() -> Result<()> {
+        let context = task_context(|config| {
+            config.options_mut().execution.query_timeout = Some(10);
+        });
+        let limits = QueryLimits::new(&context);
+        let schema = Arc::new(Schema::empty());
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::pending(),
+        ));
+
+        let mut stream = limits.wrap(stream);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: The query timed out after 10 ms. Try increasing `datafusion.execution.query_timeout`."
+        );
+        assert!(stream.next().await.is_none());
+        assert!(context.cancellation_token().is_cancelled());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn max_result_rows() -> Result<()> {
+        let plan = || -> Result<Arc<dyn ExecutionPlan>> {
+            let batch = make_partition(100);
+            let input = TestMemoryExec::try_new_exec(
+                &[vec![
+                    batch.clone(),
+                    batch.clone(),
+                    batch.clone(),
+                    batch.clone(),
+                ]],
+                batch.schema(),
+                None,
+            )?;
+            Ok(Arc::new(CoalesceBatchesExec::new(input, 100)))
+        };
+        let context = task_context(|config| {
+            config.options_mut().execution.max_result_rows = Some(400);
+        });
+        assert_eq!(collect(plan()?, context).await?.len(), 4);
+
+        let context = task_context(|config| {
+            config.options_mut().execution.max_result_rows = Some(250);
+        });
+        let mut stream = execute_stream(plan()?, Arc::clone(&context))?;
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Resources exhausted: The query returned more than 250 rows. Try increasing `datafusion.execution.max_result_rows`."
+        );
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn limits_of_each_execution() -> Result<()> {
+        let plan = |batches: usize| -> Result<Arc<dyn ExecutionPlan>> {
+            let batch = make_partition(100);
+            TestMemoryExec::try_new_exec(
+                &[vec![batch.clone(); batches]],
+                batch.schema(),
+                None,
+            )
+            .map(|exec| exec as _)
+        };
+        let context = task_context(|config| {
+            config.options_mut().execution.max_result_rows = Some(250);
+        });
+        let err = collect(plan(3)?, Arc::clone(&context)).await.unwrap_err();
+        assert_contains!(err.to_string(), "The query returned more than 250 rows");
+
+        // the next query is neither cancelled nor limited by the rows
+        // returned by the first one
+        assert!(!context.cancellation_token().is_cancelled());
+        assert_eq!(collect(plan(2)?, Arc::clone(&context)).await?.len(), 2);
+        assert_eq!(collect(plan(2)?, Arc::clone(&context)).await?.len(), 2);
+
+        // cancelling the context cancels the next queries
+        context.cancellation_token().cancel();
+        let err = collect(plan(2)?, context).await.unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: The query was cancelled"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancelled_query() -> Result<()> {
+        let context = TaskContext::default();
+        let limits = QueryLimits::new(&context);
+        let schema = Arc::new(Schema::empty());
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::pending(),
+        ));
+
+        let mut stream = limits.wrap(stream);
+        context.cancellation_token().cancel();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: The query was cancelled"
+        );
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
+}
@@ -19,6 +19,7 @@
 //! partitions to M output partitions based on a partitioning scheme, optionally
 //! maintaining the order of the input rows in the output.
 
+use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
 use std::pin::Pin;
 use std::sync::Arc;
@@ -26,7 +27,9 @@ use std::task::{Context, Poll};
 use std::{any::Any, vec};
 
 use super::common::SharedMemoryReservation;
-use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
+use super::metrics::{
+    self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet, SpillMetrics,
+};
 use super::{
     DisplayAs, ExecutionPlanProperties, RecordBatchStream, SendableRecordBatchStream,
 };
@@ -38,6 +41,8 @@ use crate::repartition::distributor_channels::{
     channels, partition_aware_channels, DistributionReceiver, DistributionSender,
 };
 use crate::sorts::streaming_merge::StreamingMergeBuilder;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
 use crate::stream::RecordBatchStreamAdapter;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, Statistics};
 
@@ -59,28 +64,76 @@ use crate::filter_pushdown::{
     FilterPushdownPropagation,
 };
 use futures::stream::Stream;
-use futures::{FutureExt, StreamExt, TryStreamExt};
-use log::trace;
+use futures::{ready, FutureExt, StreamExt, TryStreamExt};
+use log::{debug, trace};
 use parking_lot::Mutex;
 
 mod distributor_channels;
+mod skew;
+
+pub use skew::{HotKeyDetector, HotKeyRouting};
+use skew::{HotKeyRouter, HotKeys, KeySampler, SkewMetrics};
+
+/// A batch sent from an input partition to an output partition
+enum RepartitionBatch {
+    /// A batch held in memory, accounted for in the reservation of the output
+    /// partition
+    Memory(RecordBatch),
+    /// Batches written to disk because the reservation of the output partition
+    /// could not grow
+    Spilled(SharedSpillFile),
+}
+
+/// A spill file shared by an input partition and an output partition
+///
+/// The input partition appends its consecutive spilled batches to the same
+/// file, until the output partition takes the file to read it back. The
+/// next spilled batch then starts a new file, so that an output partition
+/// that is consumed slowly does not keep a file open per spilled batch.
+type SharedSpillFile = Arc<Mutex<Option<InProgressSpillFile>>>;
 
-type MaybeBatch = Option<Result<RecordBatch>>;
+impl Debug for RepartitionBatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Memory(batch) => f.debug_tuple("Memory").field(batch).finish(),
+            Self::Spilled(_) => f.debug_tuple("Spilled").finish_non_exhaustive(),
+        }
+    }
+}
+
+type MaybeBatch = Option<Result<RepartitionBatch>>;
 type InputPartitionsToCurrentPartitionSender = Vec<DistributionSender<MaybeBatch>>;
 type InputPartitionsToCurrentPartitionReceiver = Vec<DistributionReceiver<MaybeBatch>>;
 
+/// The channels of one output partition
+#[derive(Debug)]
+struct PartitionChannels {
+    /// Senders used by each input partition
+    tx: InputPartitionsToCurrentPartitionSender,
+    /// Receivers used by the output partition
+    rx: InputPartitionsToCurrentPartitionReceiver,
+    /// Memory reservation for the batches buffered in the channels
+    reservation: SharedMemoryReservation,
+    /// Writes the batches that do not fit in the reservation to disk, if the
+    /// disk manager allows it
+    spill_manager: Option<SpillManager>,
+}
+
+/// The sending side of an output partition, used by one input partition
+#[derive(Clone)]
+struct OutputChannel {
+    sender: DistributionSender<MaybeBatch>,
+    reservation: SharedMemoryReservation,
+    spill_manager: Option<SpillManager>,
+    /// The spill file of the last batch sent, if it was spilled
+    spill_file: Option<SharedSpillFile>,
+}
+
 #[derive(Debug)]
 struct ConsumingInputStreamsState {
     /// Channels for sending batches from input partitions to output partitions.
     /// Key is the partition number.
-    channels: HashMap<
-        usize,
-        (
-            InputPartitionsToCurrentPartitionSender,
-            InputPartitionsToCurrentPartitionReceiver,
-            SharedMemoryReservation,
-        ),
-    >,
+    channels: HashMap<usize, PartitionChannels>,
 
     /// Helper that ensures that that background job is killed once it is no longer needed.
     abort_helper: Arc<Vec<SpawnedTask<()>>>,
@@ -148,20 +201,22 @@ impl RepartitionExecState {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn consume_input_streams(
         &mut self,
         input: Arc<dyn ExecutionPlan>,
         metrics: ExecutionPlanMetricsSet,
         partitioning: Partitioning,
         preserve_order: bool,
+        hot_keys: Option<HotKeys>,
         name: String,
         context: Arc<TaskContext>,
     ) -> Result<&mut ConsumingInputStreamsState> {
         let streams_and_metrics = match self {
             RepartitionExecState::NotInitialized => {
                 self.ensure_input_streams_initialized(
-                    input,
-                    metrics,
+                    Arc::clone(&input),
+                    metrics.clone(),
                     partitioning.partition_count(),
                     Arc::clone(&context),
                 )?;
@@ -200,32 +255,77 @@ impl RepartitionExecState {
             (txs, rxs)
         };
 
+        let can_spill = context.runtime_env().disk_manager.tmp_files_enabled();
         let mut channels = HashMap::with_capacity(txs.len());
         for (partition, (tx, rx)) in txs.into_iter().zip(rxs).enumerate() {
             let reservation = Arc::new(Mutex::new(
                 MemoryConsumer::new(format!("{name}[{partition}]"))
+                    .with_can_spill(can_spill)
                     .register(context.memory_pool()),
             ));
-            channels.insert(partition, (tx, rx, reservation));
+            let spill_manager = can_spill.then(|| {
+                SpillManager::new(
+                    context.runtime_env(),
+                    SpillMetrics::new(&metrics, partition),
+                    input.schema(),
+                )
+                .with_compression_type(context.session_config().spill_compression())
+            });
+            channels.insert(
+                partition,
+                PartitionChannels {
+                    tx,
+                    rx,
+                    reservation,
+                    spill_manager,
+                },
+            );
         }
 
+        // the input partitions sample their keys to detect the hot keys
+        let skew_metrics = hot_keys.as_ref().map(|hot_keys| {
+            let skew_metrics = SkewMetrics::new(num_output_partitions, &metrics);
+            if hot_keys.routing != HotKeyRouting::Replicate {
+                hot_keys.detector.register(
+                    num_input_partitions,
+                    num_output_partitions,
+                    skew_metrics.hot_keys.clone(),
+                );
+            }
+            skew_metrics
+        });
+
         // launch one async task per *input* partition
         let mut spawned_tasks = Vec::with_capacity(num_input_partitions);
-        for (i, (stream, metrics)) in
+        for (i, (stream, input_metrics)) in
             std::mem::take(streams_and_metrics).into_iter().enumerate()
         {
             let txs: HashMap<_, _> = channels
                 .iter()
-                .map(|(partition, (tx, _rx, reservation))| {
-                    (*partition, (tx[i].clone(), Arc::clone(reservation)))
+                .map(|(partition, channels)| {
+                    let channel = OutputChannel {
+                        sender: channels.tx[i].clone(),
+                        reservation: Arc::clone(&channels.reservation),
+                        spill_manager: channels.spill_manager.clone(),
+                        spill_file: None,
+                    };
+                    (*partition, channel)
                 })
                 .collect();
 
+            let input_hot_keys = hot_keys.clone().zip(skew_metrics.clone()).map(
+                |(hot_keys, skew_metrics)| {
+                    let hot_key_rows =
+                        MetricBuilder::new(&metrics).counter("hot_key_rows", i);
+                    (hot_keys, skew_metrics, hot_key_rows)
+                },
+            );
             let input_task = SpawnedTask::spawn(RepartitionExec::pull_from_input(
                 stream,
                 txs.clone(),
                 partitioning.clone(),
-                metrics,
+                input_metrics,
+                input_hot_keys,
             ));
 
             // In a separate task, wait for each input to be done
@@ -233,7 +333,7 @@ impl RepartitionExecState {
             let wait_for_task = SpawnedTask::spawn(RepartitionExec::wait_for_task(
                 input_task,
                 txs.into_iter()
-                    .map(|(partition, (tx, _reservation))| (partition, tx))
+                    .map(|(partition, channel)| (partition, channel.sender))
                     .collect(),
             ));
             spawned_tasks.push(wait_for_task);
@@ -261,6 +361,8 @@ enum BatchPartitionerState {
         exprs: Vec<Arc<dyn PhysicalExpr>>,
         num_partitions: usize,
         hash_buffer: Vec<u64>,
+        /// Routes the rows of the hot keys, if they are handled
+        hot_key_router: Option<HotKeyRouter>,
     },
     RoundRobin {
         num_partitions: usize,
@@ -286,6 +388,7 @@ impl BatchPartitioner {
                 // Use fixed random hash
                 random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
                 hash_buffer: vec![],
+                hot_key_router: None,
             },
             other => return not_impl_err!("Unsupported repartitioning scheme {other:?}"),
         };
@@ -336,26 +439,25 @@ impl BatchPartitioner {
                     exprs,
                     num_partitions: partitions,
                     hash_buffer,
+                    hot_key_router,
                 } => {
                     // Tracking time required for distributing indexes across output partitions
                     let timer = self.timer.timer();
 
-                    let arrays = exprs
-                        .iter()
-                        .map(|expr| expr.evaluate(&batch)?.into_array(batch.num_rows()))
-                        .collect::<Result<Vec<_>>>()?;
-
-                    hash_buffer.clear();
-                    hash_buffer.resize(batch.num_rows(), 0);
-
-                    create_hashes(&arrays, random_state, hash_buffer)?;
+                    hash_batch(&batch, exprs, random_state, hash_buffer)?;
 
                     let mut indices: Vec<_> = (0..*partitions)
                         .map(|_| Vec::with_capacity(batch.num_rows()))
                         .collect();
 
                     for (index, hash) in hash_buffer.iter().enumerate() {
-                        indices[(*hash % *partitions as u64) as usize].push(index as u32);
+                        let index = index as u32;
+                        if let Some(router) = hot_key_router.as_mut() {
+                            if router.route(index, *hash, &mut indices) {
+                                continue;
+                            }
+                        }
+                        indices[(*hash % *partitions as u64) as usize].push(index);
                     }
 
                     // Finished building index-arrays for output partitions
@@ -396,6 +498,31 @@ impl BatchPartitioner {
         Ok(it)
     }
 
+    /// Returns the hashes of the keys of the rows of `batch`, if the
+    /// partitioning is a hash partitioning
+    fn hashes(&mut self, batch: &RecordBatch) -> Result<Option<&[u64]>> {
+        match &mut self.state {
+            BatchPartitionerState::Hash {
+                random_state,
+                exprs,
+                hash_buffer,
+                ..
+            } => {
+                let _timer = self.timer.timer();
+                hash_batch(batch, exprs, random_state, hash_buffer)?;
+                Ok(Some(hash_buffer))
+            }
+            BatchPartitionerState::RoundRobin { .. } => Ok(None),
+        }
+    }
+
+    /// Routes the rows of the hot keys with `router`
+    fn set_hot_key_router(&mut self, router: HotKeyRouter) {
+        if let BatchPartitionerState::Hash { hot_key_router, .. } = &mut self.state {
+            *hot_key_router = Some(router);
+        }
+    }
+
     // return the number of output partitions
     fn num_partitions(&self) -> usize {
         match self.state {
@@ -405,6 +532,25 @@ impl BatchPartitioner {
     }
 }
 
+/// Computes the hashes of the `exprs` of the rows of `batch` into `hash_buffer`
+fn hash_batch(
+    batch: &RecordBatch,
+    exprs: &[Arc<dyn PhysicalExpr>],
+    random_state: &ahash::RandomState,
+    hash_buffer: &mut Vec<u64>,
+) -> Result<()> {
+    let arrays = exprs
+        .iter()
+        .map(|expr| expr.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+
+    hash_buffer.clear();
+    hash_buffer.resize(batch.num_rows(), 0);
+
+    create_hashes(&arrays, random_state, hash_buffer)?;
+    Ok(())
+}
+
 /// Maps `N` input partitions to `M` output partitions based on a
 /// [`Partitioning`] scheme.
 ///
@@ -464,6 +610,15 @@ impl BatchPartitioner {
 /// arbitrary interleaving (and thus unordered) unless
 /// [`Self::with_preserve_order`] specifies otherwise.
 ///
+/// # Spilling
+///
+/// The batches waiting to be consumed by an output partition are accounted for
+/// in a memory reservation of that partition. When the reservation can not
+/// grow, for example because one output partition of a skewed hash
+/// repartitioning is consumed slowly, the batches are spilled to disk and read
+/// back in the order they were sent. Without a disk manager, the
+/// `ResourcesExhausted` error is returned instead.
+///
 /// # Footnote
 ///
 /// The "Exchange Operator" was first described in the 1989 paper
@@ -485,6 +640,9 @@ pub struct RepartitionExec {
     preserve_order: bool,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
+    /// How the hot keys of a hash partitioning are detected and routed, if
+    /// they are
+    hot_keys: Option<HotKeys>,
 }
 
 #[derive(Debug, Clone)]
@@ -553,6 +711,16 @@ impl RepartitionExec {
     pub fn name(&self) -> &str {
         "RepartitionExec"
     }
+
+    /// How the rows of the hot keys are routed, if they are detected
+    pub fn hot_key_routing(&self) -> Option<HotKeyRouting> {
+        self.hot_keys.as_ref().map(|hot_keys| hot_keys.routing)
+    }
+
+    /// The detector of the hot keys, if they are detected
+    pub fn hot_key_detector(&self) -> Option<&Arc<HotKeyDetector>> {
+        self.hot_keys.as_ref().map(|hot_keys| &hot_keys.detector)
+    }
 }
 
 impl DisplayAs for RepartitionExec {
@@ -574,6 +742,10 @@ impl DisplayAs for RepartitionExec {
                 if let Some(sort_exprs) = self.sort_exprs() {
                     write!(f, ", sort_exprs={}", sort_exprs.clone())?;
                 }
+
+                if let Some(routing) = self.hot_key_routing() {
+                    write!(f, ", hot_keys={routing}")?;
+                }
                 Ok(())
             }
             DisplayFormatType::TreeRender => {
@@ -592,6 +764,10 @@ impl DisplayAs for RepartitionExec {
                 if self.preserve_order {
                     writeln!(f, "preserve_order={}", self.preserve_order)?;
                 }
+
+                if let Some(routing) = self.hot_key_routing() {
+                    writeln!(f, "hot_keys={routing}")?;
+                }
                 Ok(())
             }
         }
@@ -627,6 +803,7 @@ impl ExecutionPlan for RepartitionExec {
         if self.preserve_order {
             repartition = repartition.with_preserve_order();
         }
+        repartition.hot_keys = self.hot_keys.clone();
         Ok(Arc::new(repartition))
     }
 
@@ -660,6 +837,8 @@ impl ExecutionPlan for RepartitionExec {
         // Get existing ordering to use for merging
         let sort_exprs = self.sort_exprs().cloned();
 
+        let hot_keys = self.hot_keys.clone();
+
         let state = Arc::clone(&self.state);
         if let Some(mut state) = state.try_lock() {
             state.ensure_input_streams_initialized(
@@ -668,13 +847,28 @@ impl ExecutionPlan for RepartitionExec {
                 partitioning.partition_count(),
                 Arc::clone(&context),
             )?;
+
+            // The input partitions spreading the hot keys start to sample
+            // their keys right away, as the other side of the join waits for
+            // the hot keys before it is consumed
+            if self.hot_key_routing() == Some(HotKeyRouting::Spread) {
+                state.consume_input_streams(
+                    Arc::clone(&input),
+                    metrics.clone(),
+                    partitioning.clone(),
+                    preserve_order,
+                    hot_keys.clone(),
+                    name.clone(),
+                    Arc::clone(&context),
+                )?;
+            }
         }
 
         let stream = futures::stream::once(async move {
             let num_input_partitions = input.output_partitioning().partition_count();
 
             // lock scope
-            let (mut rx, reservation, abort_helper) = {
+            let (rx, reservation, spill_manager, abort_helper) = {
                 // lock mutexes
                 let mut state = state.lock();
                 let state = state.consume_input_streams(
@@ -682,19 +876,39 @@ impl ExecutionPlan for RepartitionExec {
                     metrics.clone(),
                     partitioning,
                     preserve_order,
+                    hot_keys,
                     name.clone(),
                     Arc::clone(&context),
                 )?;
 
                 // now return stream for the specified *output* partition which will
                 // read from the channel
-                let (_tx, rx, reservation) = state
+                let PartitionChannels {
+                    rx,
+                    reservation,
+                    spill_manager,
+                    ..
+                } = state
                     .channels
                     .remove(&partition)
                     .expect("partition not used yet");
 
-                (rx, reservation, Arc::clone(&state.abort_helper))
+                (
+                    rx,
+                    reservation,
+                    spill_manager,
+                    Arc::clone(&state.abort_helper),
+                )
             };
+            let mut rx = rx
+                .into_iter()
+                .map(|receiver| SpillingReceiver {
+                    receiver,
+                    reservation: Arc::clone(&reservation),
+                    spill_manager: spill_manager.clone(),
+                    spill_stream: None,
+                })
+                .collect::<Vec<_>>();
 
             trace!(
                 "Before returning stream in {name}::execute for partition: {partition}"
@@ -709,7 +923,6 @@ impl ExecutionPlan for RepartitionExec {
                             schema: Arc::clone(&schema_captured),
                             receiver,
                             _drop_helper: Arc::clone(&abort_helper),
-                            reservation: Arc::clone(&reservation),
                         }) as SendableRecordBatchStream
                     })
                     .collect::<Vec<_>>();
@@ -737,7 +950,6 @@ impl ExecutionPlan for RepartitionExec {
                     schema: input.schema(),
                     input: rx.swap_remove(0),
                     _drop_helper: abort_helper,
-                    reservation,
                 }) as SendableRecordBatchStream)
             }
         })
@@ -800,10 +1012,9 @@ impl ExecutionPlan for RepartitionExec {
             others => others.clone(),
         };
 
-        Ok(Some(Arc::new(RepartitionExec::try_new(
-            new_projection,
-            new_partitioning,
-        )?)))
+        let mut repartition = RepartitionExec::try_new(new_projection, new_partitioning)?;
+        repartition.hot_keys = self.hot_keys.clone();
+        Ok(Some(Arc::new(repartition)))
     }
 
     fn gather_filters_for_pushdown(
@@ -844,6 +1055,7 @@ impl RepartitionExec {
             metrics: ExecutionPlanMetricsSet::new(),
             preserve_order,
             cache,
+            hot_keys: None,
         })
     }
 
@@ -908,6 +1120,22 @@ impl RepartitionExec {
         self
     }
 
+    /// Detect the hot keys of the hash partitioning with `detector`, and route
+    /// their rows according to `routing`. See [`HotKeyRouting`].
+    ///
+    /// The output partitioning is still reported as [`Partitioning::Hash`]:
+    /// with [`HotKeyRouting::Spread`] and [`HotKeyRouting::Replicate`], the
+    /// rows of a hot key are not all in one output partition anymore, so this
+    /// should only be set when the consumers of the output handle it.
+    pub fn with_hot_keys(
+        mut self,
+        routing: HotKeyRouting,
+        detector: Arc<HotKeyDetector>,
+    ) -> Self {
+        self.hot_keys = Some(HotKeys { routing, detector });
+        self
+    }
+
     /// Return the sort expressions that are used to merge
     fn sort_exprs(&self) -> Option<&LexOrdering> {
         if self.preserve_order {
@@ -920,26 +1148,73 @@ impl RepartitionExec {
     /// Pulls data from the specified input plan, feeding it to the
     /// output partitions based on the desired partitioning
     ///
-    /// txs hold the output sending channels for each output partition
+    /// txs hold the output sending channels for each output partition. The
+    /// batches that do not fit in the memory reservation of their output
+    /// partition are spilled to disk, and read back by the output partition
+    /// in the order they were sent.
+    ///
+    /// If the hot keys are detected, the keys of the first rows are sampled
+    /// before they are sent, and the rows of the hot keys are routed once all
+    /// the input partitions sampled their keys.
     async fn pull_from_input(
         mut stream: SendableRecordBatchStream,
-        mut output_channels: HashMap<
-            usize,
-            (DistributionSender<MaybeBatch>, SharedMemoryReservation),
-        >,
+        mut output_channels: HashMap<usize, OutputChannel>,
         partitioning: Partitioning,
         metrics: RepartitionMetrics,
+        hot_keys: Option<(HotKeys, SkewMetrics, metrics::Count)>,
     ) -> Result<()> {
         let mut partitioner =
             BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
 
+        // The sampled batches, sent before the rest of the input
+        let mut sampled = VecDeque::new();
+        let mut input_done = false;
+        let skew_metrics = match hot_keys {
+            Some((hot_keys, skew_metrics, hot_key_rows)) => {
+                if hot_keys.routing != HotKeyRouting::Replicate {
+                    let mut sampler = KeySampler::new(Arc::clone(&hot_keys.detector));
+                    while !sampler.is_complete() {
+                        let timer = metrics.fetch_time.timer();
+                        let result = stream.next().await;
+                        timer.done();
+                        let Some(batch) = result.transpose()? else {
+                            input_done = true;
+                            break;
+                        };
+                        if let Some(hashes) = partitioner.hashes(&batch)? {
+                            sampler.add(hashes);
+                        }
+                        sampled.push_back(batch);
+                    }
+                }
+                if hot_keys.routing != HotKeyRouting::Detect {
+                    let detected = hot_keys.detector.hot_keys().await;
+                    skew_metrics.hot_keys.set(detected.len());
+                    partitioner.set_hot_key_router(HotKeyRouter::new(
+                        hot_keys.routing,
+                        detected,
+                        hot_key_rows,
+                    ));
+                }
+                Some(skew_metrics)
+            }
+            None => None,
+        };
+
         // While there are still outputs to send to, keep pulling inputs
         let mut batches_until_yield = partitioner.num_partitions();
         while !output_channels.is_empty() {
             // fetch the next batch
-            let timer = metrics.fetch_time.timer();
-            let result = stream.next().await;
-            timer.done();
+            let result = if let Some(batch) = sampled.pop_front() {
+                Some(Ok(batch))
+            } else if input_done {
+                None
+            } else {
+                let timer = metrics.fetch_time.timer();
+                let result = stream.next().await;
+                timer.done();
+                result
+            };
 
             // Input is done
             let batch = match result {
@@ -950,15 +1225,48 @@ impl RepartitionExec {
             for res in partitioner.partition_iter(batch)? {
                 let (partition, batch) = res?;
                 let size = batch.get_array_memory_size();
+                if let Some(skew_metrics) = &skew_metrics {
+                    skew_metrics.record(partition, batch.num_rows());
+                }
 
                 let timer = metrics.send_time[partition].timer();
                 // if there is still a receiver, send to it
-                if let Some((tx, reservation)) = output_channels.get_mut(&partition) {
-                    reservation.lock().try_grow(size)?;
+                if let Some(channel) = output_channels.get_mut(&partition) {
+                    let grown = channel.reservation.lock().try_grow(size);
+                    let batch = match (grown, &channel.spill_manager) {
+                        (Ok(()), _) => {
+                            channel.spill_file = None;
+                            RepartitionBatch::Memory(batch)
+                        }
+                        (Err(_), Some(spill_manager)) => {
+                            if batch.num_rows() == 0 {
+                                continue;
+                            }
+                            // Append to the spill file of the previous batch,
+                            // unless the output partition is already reading it
+                            if let Some(spill_file) = &channel.spill_file {
+                                if let Some(file) = spill_file.lock().as_mut() {
+                                    file.append_batch(&batch)?;
+                                    continue;
+                                }
+                            }
+                            debug!("Spilling batches of RepartitionExec partition {partition} to disk");
+                            let mut file = spill_manager
+                                .create_in_progress_file("RepartitionExec")?;
+                            file.append_batch(&batch)?;
+                            let spill_file = Arc::new(Mutex::new(Some(file)));
+                            channel.spill_file = Some(Arc::clone(&spill_file));
+                            RepartitionBatch::Spilled(spill_file)
+                        }
+                        (Err(e), None) => return Err(e),
+                    };
+                    let is_memory = matches!(batch, RepartitionBatch::Memory(_));
 
-                    if tx.send(Some(Ok(batch))).await.is_err() {
+                    if channel.sender.send(Some(Ok(batch))).await.is_err() {
                         // If the other end has hung up, it was an early shutdown (e.g. LIMIT)
-                        reservation.lock().shrink(size);
+                        if is_memory {
+                            channel.reservation.lock().shrink(size);
+                        }
                         output_channels.remove(&partition);
                     }
                 }
@@ -1039,6 +1347,81 @@ impl RepartitionExec {
     }
 }
 
+/// Receives the batches sent by one input partition to an output partition,
+/// reading back the spilled batches in the order they were sent.
+struct SpillingReceiver {
+    /// channel containing the repartitioned batches
+    receiver: DistributionReceiver<MaybeBatch>,
+
+    /// Memory reservation.
+    reservation: SharedMemoryReservation,
+
+    /// Reads the spilled batches
+    spill_manager: Option<SpillManager>,
+
+    /// Stream of the spilled batch that is being read
+    spill_stream: Option<SendableRecordBatchStream>,
+}
+
+impl SpillingReceiver {
+    /// Polls for the next batch. Returns `Some(None)` when the input partition
+    /// has finished sending batches, and `None` when the channel is closed.
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Option<Result<RecordBatch>>>> {
+        loop {
+            if let Some(spill_stream) = self.spill_stream.as_mut() {
+                match ready!(spill_stream.poll_next_unpin(cx)) {
+                    Some(v) => return Poll::Ready(Some(Some(v))),
+                    None => self.spill_stream = None,
+                }
+            }
+
+            let v = match ready!(self.receiver.recv().poll_unpin(cx)) {
+                Some(Some(v)) => v,
+                Some(None) => return Poll::Ready(Some(None)),
+                None => return Poll::Ready(None),
+            };
+            match v {
+                Ok(RepartitionBatch::Memory(batch)) => {
+                    self.reservation
+                        .lock()
+                        .shrink(batch.get_array_memory_size());
+                    return Poll::Ready(Some(Some(Ok(batch))));
+                }
+                Ok(RepartitionBatch::Spilled(spill_file)) => {
+                    let Some(spill_manager) = &self.spill_manager else {
+                        let err = internal_err!(
+                            "RepartitionExec received a spilled batch without a SpillManager"
+                        );
+                        return Poll::Ready(Some(Some(err)));
+                    };
+                    // Taking the file makes the input partition write its next
+                    // spilled batches to a new file
+                    let Some(mut file) = spill_file.lock().take() else {
+                        let err = internal_err!(
+                            "RepartitionExec received a spill file that was already read"
+                        );
+                        return Poll::Ready(Some(Some(err)));
+                    };
+                    let stream = file.finish().and_then(|file| match file {
+                        Some(file) => spill_manager.read_spill_as_stream(file),
+                        None => {
+                            internal_err!("RepartitionExec received an empty spill file")
+                        }
+                    });
+                    match stream {
+                        Ok(stream) => self.spill_stream = Some(stream),
+                        Err(e) => return Poll::Ready(Some(Some(Err(e)))),
+                    }
+                }
+                Err(e) => return Poll::Ready(Some(Some(Err(e)))),
+            }
+        }
+    }
+}
+
 struct RepartitionStream {
     /// Number of input partitions that will be sending batches to this output channel
     num_input_partitions: usize,
@@ -1050,13 +1433,10 @@ struct RepartitionStream {
     schema: SchemaRef,
 
     /// channel containing the repartitioned batches
-    input: DistributionReceiver<MaybeBatch>,
+    input: SpillingReceiver,
 
     /// Handle to ensure background tasks are killed when no longer needed.
     _drop_helper: Arc<Vec<SpawnedTask<()>>>,
-
-    /// Memory reservation.
-    reservation: SharedMemoryReservation,
 }
 
 impl Stream for RepartitionStream {
@@ -1067,14 +1447,8 @@ impl Stream for RepartitionStream {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         loop {
-            match self.input.recv().poll_unpin(cx) {
+            match self.input.poll_recv(cx) {
                 Poll::Ready(Some(Some(v))) => {
-                    if let Ok(batch) = &v {
-                        self.reservation
-                            .lock()
-                            .shrink(batch.get_array_memory_size());
-                    }
-
                     return Poll::Ready(Some(v));
                 }
                 Poll::Ready(Some(None)) => {
@@ -1113,13 +1487,10 @@ struct PerPartitionStream {
     schema: SchemaRef,
 
     /// channel containing the repartitioned batches
-    receiver: DistributionReceiver<MaybeBatch>,
+    receiver: SpillingReceiver,
 
     /// Handle to ensure background tasks are killed when no longer needed.
     _drop_helper: Arc<Vec<SpawnedTask<()>>>,
-
-    /// Memory reservation.
-    reservation: SharedMemoryReservation,
 }
 
 impl Stream for PerPartitionStream {
@@ -1129,15 +1500,8 @@ impl Stream for PerPartitionStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        match self.receiver.recv().poll_unpin(cx) {
-            Poll::Ready(Some(Some(v))) => {
-                if let Ok(batch) = &v {
-                    self.reservation
-                        .lock()
-                        .shrink(batch.get_array_memory_size());
-                }
-                Poll::Ready(Some(v))
-            }
+        match self.receiver.poll_recv(cx) {
+            Poll::Ready(Some(Some(v))) => Poll::Ready(Some(v)),
             Poll::Ready(Some(None)) => {
                 // Input partition has finished sending batches
                 Poll::Ready(None)
@@ -1172,12 +1536,14 @@ mod tests {
         {collect, expressions::col},
     };
 
-    use arrow::array::{ArrayRef, StringArray, UInt32Array};
+    use arrow::array::{ArrayRef, AsArray, StringArray, UInt32Array};
     use arrow::datatypes::{DataType, Field, Schema};
     use datafusion_common::cast::as_string_array;
     use datafusion_common::test_util::batches_to_sort_string;
     use datafusion_common::{arrow_datafusion_err, exec_err};
     use datafusion_common_runtime::JoinSet;
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::memory_pool::FairSpillPool;
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use insta::assert_snapshot;
     use itertools::Itertools;
@@ -1665,9 +2031,12 @@ mod tests {
         let input_partitions = vec![partition];
         let partitioning = Partitioning::RoundRobinBatch(4);
 
-        // setup up context
+        // setup up context, without a disk manager to spill to
         let runtime = RuntimeEnvBuilder::default()
             .with_memory_limit(1, 1.0)
+            .with_disk_manager_builder(
+                DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+            )
             .build_arc()?;
 
         let task_ctx = TaskContext::default().with_runtime(runtime);
@@ -1693,6 +2062,200 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn spill_skewed_hash_partition() -> Result<()> {
+        // every row is sent to the same output partition
+        let schema = test_schema();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(UInt32Array::from(vec![1; 8]))],
+        )?;
+        let input_partitions = vec![vec![batch; 50], vec![]];
+        let partitioning = Partitioning::Hash(vec![col("c0", &schema)?], 4);
+
+        // setup up context, with room for a few batches
+        let runtime = RuntimeEnvBuilder::default()
+            .with_memory_pool(Arc::new(FairSpillPool::new(1024)))
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+
+        // create physical plan
+        let exec =
+            TestMemoryExec::try_new_exec(&input_partitions, Arc::clone(&schema), None)?;
+        let exec = RepartitionExec::try_new(exec, partitioning)?;
+
+        // the skewed partition is not consumed until the other partitions are done
+        let mut output_partitions = vec![];
+        for i in 0..exec.partitioning().partition_count() {
+            let stream = exec.execute(i, Arc::clone(&task_ctx))?;
+            output_partitions.push(crate::common::collect(stream).await?);
+        }
+
+        let num_rows = output_partitions
+            .iter()
+            .map(|batches| batches.iter().map(|b| b.num_rows()).sum::<usize>())
+            .collect::<Vec<_>>();
+        assert_eq!(num_rows.iter().sum::<usize>(), 50 * 8);
+        assert_eq!(num_rows.iter().max(), Some(&(50 * 8)));
+
+        let metrics = exec.metrics().unwrap();
+        // the consecutive spilled batches are written to the same file
+        assert_eq!(metrics.spill_count(), Some(1));
+        assert!(metrics.spilled_rows().unwrap() > 0);
+        assert_eq!(task_ctx.runtime_env().memory_pool.reserved(), 0);
+
+        Ok(())
+    }
+
+    /// Input partitions where the key 1 has most of the rows
+    fn skewed_partitions(schema: &SchemaRef) -> Result<Vec<Vec<RecordBatch>>> {
+        let hot = RecordBatch::try_new(
+            Arc::clone(schema),
+            vec![Arc::new(UInt32Array::from(vec![1; 8]))],
+        )?;
+        let partition = vec![hot.clone(), create_batch(), hot];
+        Ok(vec![partition.clone(), partition])
+    }
+
+    async fn collect_partitions(
+        exec: &RepartitionExec,
+        task_ctx: &Arc<TaskContext>,
+    ) -> Result<Vec<Vec<RecordBatch>>> {
+        let mut streams = vec![];
+        for i in 0..exec.partitioning().partition_count() {
+            streams.push(exec.execute(i, Arc::clone(task_ctx))?);
+        }
+        let mut output_partitions = vec![];
+        for stream in streams {
+            output_partitions.push(crate::common::collect(stream).await?);
+        }
+        Ok(output_partitions)
+    }
+
+    fn partition_rows(output_partitions: &[Vec<RecordBatch>]) -> Vec<usize> {
+        output_partitions
+            .iter()
+            .map(|batches| batches.iter().map(|b| b.num_rows()).sum())
+            .collect()
+    }
+
+    fn metric(exec: &RepartitionExec, name: &str) -> usize {
+        exec.metrics()
+            .unwrap()
+            .sum_by_name(name)
+            .map(|value| value.as_usize())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn detect_hot_keys() -> Result<()> {
+        let schema = test_schema();
+        let input_partitions = skewed_partitions(&schema)?;
+        let exec =
+            TestMemoryExec::try_new_exec(&input_partitions, Arc::clone(&schema), None)?;
+        let detector = Arc::new(HotKeyDetector::new(100, 0.5));
+        let exec = RepartitionExec::try_new(
+            exec,
+            Partitioning::Hash(vec![col("c0", &schema)?], 4),
+        )?
+        .with_hot_keys(HotKeyRouting::Detect, Arc::clone(&detector));
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let num_rows = partition_rows(&collect_partitions(&exec, &task_ctx).await?);
+
+        // the rows of the hot key are still sent to a single partition
+        assert_eq!(num_rows.iter().sum::<usize>(), 48);
+        assert!(num_rows.iter().max().unwrap() >= &34);
+        assert_eq!(detector.detected_hot_keys().unwrap().len(), 1);
+        assert_eq!(metric(&exec, "hot_keys"), 1);
+        assert_eq!(metric(&exec, "hot_key_rows"), 0);
+        assert_eq!(metric(&exec, "partition_rows"), 48);
+        assert_eq!(
+            metric(&exec, "max_partition_rows"),
+            *num_rows.iter().max().unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn spread_and_replicate_hot_keys() -> Result<()> {
+        let schema = test_schema();
+        let partitioning = Partitioning::Hash(vec![col("c0", &schema)?], 4);
+        let detector = Arc::new(HotKeyDetector::new(100, 0.5));
+
+        let input_partitions = skewed_partitions(&schema)?;
+        let exec =
+            TestMemoryExec::try_new_exec(&input_partitions, Arc::clone(&schema), None)?;
+        let spread = RepartitionExec::try_new(exec, partitioning.clone())?
+            .with_hot_keys(HotKeyRouting::Spread, Arc::clone(&detector));
+
+        let exec = TestMemoryExec::try_new_exec(
+            &[vec![create_batch()]],
+            Arc::clone(&schema),
+            None,
+        )?;
+        let replicate = RepartitionExec::try_new(exec, partitioning)?
+            .with_hot_keys(HotKeyRouting::Replicate, Arc::clone(&detector));
+
+        // the spreading side samples its keys as soon as it is executed
+        let task_ctx = Arc::new(TaskContext::default());
+        let mut spread_streams = vec![];
+        for i in 0..4 {
+            spread_streams.push(spread.execute(i, Arc::clone(&task_ctx))?);
+        }
+        let replicated = collect_partitions(&replicate, &task_ctx).await?;
+        let mut spread_partitions = vec![];
+        for stream in spread_streams {
+            spread_partitions.push(crate::common::collect(stream).await?);
+        }
+
+        // the 34 rows of the hot key are sent round robin
+        let num_rows = partition_rows(&spread_partitions);
+        assert_eq!(num_rows.iter().sum::<usize>(), 48);
+        assert!(num_rows.iter().all(|rows| *rows >= 8), "{num_rows:?}");
+        assert_eq!(metric(&spread, "hot_keys"), 1);
+        assert_eq!(metric(&spread, "hot_key_rows"), 34);
+
+        // the row of the hot key is sent to every partition
+        for batches in &replicated {
+            assert!(batches.iter().any(|batch| {
+                let keys = batch.column(0).as_primitive::<UInt32Type>();
+                keys.iter().any(|key| key == Some(1))
+            }));
+        }
+        assert_eq!(partition_rows(&replicated).iter().sum::<usize>(), 8 + 3);
+        assert_eq!(metric(&replicate, "hot_keys"), 1);
+        assert_eq!(metric(&replicate, "hot_key_rows"), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replicate_without_sampling() -> Result<()> {
+        // no key is hot if the spreading side is not executed
+        let schema = test_schema();
+        let detector = Arc::new(HotKeyDetector::new(100, 0.5));
+        let exec = TestMemoryExec::try_new_exec(
+            &skewed_partitions(&schema)?,
+            Arc::clone(&schema),
+            None,
+        )?;
+        let replicate = RepartitionExec::try_new(
+            exec,
+            Partitioning::Hash(vec![col("c0", &schema)?], 4),
+        )?
+        .with_hot_keys(HotKeyRouting::Replicate, Arc::clone(&detector));
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let num_rows = partition_rows(&collect_partitions(&replicate, &task_ctx).await?);
+        assert_eq!(num_rows.iter().sum::<usize>(), 48);
+        assert!(detector.detected_hot_keys().unwrap().is_empty());
+        assert_eq!(metric(&replicate, "hot_key_rows"), 0);
+
+        Ok(())
+    }
+
     /// Create vector batches
     fn create_vec_batches(n: usize) -> Vec<RecordBatch> {
         let batch = create_batch();
@@ -0,0 +1,345 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Detection and routing of the hot keys of a hash [`RepartitionExec`]
+//!
+//! [`RepartitionExec`]: super::RepartitionExec
+
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use crate::metrics::{Count, ExecutionPlanMetricsSet, Gauge, MetricBuilder};
+
+use datafusion_common::{HashMap, HashSet};
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+/// How a hash [`RepartitionExec`] routes the rows of its hot keys
+///
+/// The rows of the other keys are always sent to the output partition of the
+/// hash of their key.
+///
+/// [`RepartitionExec`]: super::RepartitionExec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotKeyRouting {
+    /// The rows of the hot keys are sent to the output partition of the hash
+    /// of their key: the hot keys are only detected and reported in the
+    /// metrics
+    Detect,
+    /// The rows of the hot keys are sent round robin to all the output
+    /// partitions
+    ///
+    /// The output is not hash partitioned on the hot keys anymore: it can be
+    /// partially aggregated and merged again, or joined with a side that
+    /// [`Replicate`](Self::Replicate)s the same hot keys.
+    Spread,
+    /// The rows of the hot keys are sent to all the output partitions
+    ///
+    /// This side does not sample its keys: it waits for the hot keys detected
+    /// by the other side of the join, which [`Spread`](Self::Spread)s them.
+    Replicate,
+}
+
+impl Display for HotKeyRouting {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Detect => write!(f, "detect"),
+            Self::Spread => write!(f, "spread"),
+            Self::Replicate => write!(f, "replicate"),
+        }
+    }
+}
+
+/// Detects the hot keys of a hash [`RepartitionExec`] by sampling the hashes
+/// of the keys of the first rows of each of its input partitions
+///
+/// A key is hot when it has more than `hot_key_ratio` times the sampled rows
+/// an output partition receives on average.
+///
+/// A detector is shared by the two repartitions of the inputs of a join: the
+/// hot keys sampled on the probe side are the ones the build side replicates.
+/// If the build side needs the hot keys before the probe side started to
+/// sample its keys, no key is hot.
+///
+/// [`RepartitionExec`]: super::RepartitionExec
+#[derive(Debug)]
+pub struct HotKeyDetector {
+    sample_rows: usize,
+    hot_key_ratio: f64,
+    state: Mutex<DetectorState>,
+    /// The hot keys, once detected
+    hot_keys: watch::Sender<Option<Arc<HashSet<u64>>>>,
+}
+
+#[derive(Debug, Default)]
+struct DetectorState {
+    /// Input partitions that did not add their sample yet, `None` until the
+    /// sampling repartition is executed
+    pending: Option<usize>,
+    output_partitions: usize,
+    /// Sampled rows for each key hash
+    counts: HashMap<u64, usize>,
+    rows: usize,
+    hot_keys_metric: Option<Gauge>,
+}
+
+impl HotKeyDetector {
+    /// Create a new detector sampling `sample_rows` rows of each input
+    /// partition
+    pub fn new(sample_rows: usize, hot_key_ratio: f64) -> Self {
+        Self {
+            sample_rows,
+            hot_key_ratio,
+            state: Mutex::new(DetectorState::default()),
+            hot_keys: watch::Sender::new(None),
+        }
+    }
+
+    /// The rows sampled from each input partition
+    pub fn sample_rows(&self) -> usize {
+        self.sample_rows
+    }
+
+    /// The ratio of the average rows of an output partition above which a
+    /// key is hot
+    pub fn hot_key_ratio(&self) -> f64 {
+        self.hot_key_ratio
+    }
+
+    /// The hashes of the hot keys, once detected
+    pub fn detected_hot_keys(&self) -> Option<Arc<HashSet<u64>>> {
+        self.hot_keys.borrow().clone()
+    }
+
+    /// Registers the input partitions of the repartition sampling its keys
+    pub(super) fn register(
+        &self,
+        input_partitions: usize,
+        output_partitions: usize,
+        hot_keys_metric: Gauge,
+    ) {
+        let mut state = self.state.lock();
+        if state.pending.is_some() || self.detected_hot_keys().is_some() {
+            return;
+        }
+        state.pending = Some(input_partitions);
+        state.output_partitions = output_partitions;
+        state.hot_keys_metric = Some(hot_keys_metric);
+        if input_partitions == 0 {
+            self.finish(&mut state);
+        }
+    }
+
+    /// Adds the sample of an input partition
+    fn add_sample(&self, counts: HashMap<u64, usize>, rows: usize) {
+        let mut state = self.state.lock();
+        let Some(pending) = state.pending.as_mut() else {
+            return;
+        };
+        *pending = pending.saturating_sub(1);
+        let done = *pending == 0;
+        for (hash, count) in counts {
+            *state.counts.entry(hash).or_default() += count;
+        }
+        state.rows += rows;
+        if done {
+            self.finish(&mut state);
+        }
+    }
+
+    fn finish(&self, state: &mut DetectorState) {
+        if self.detected_hot_keys().is_some() {
+            return;
+        }
+        let hot_keys = if state.output_partitions > 1 {
+            let threshold =
+                self.hot_key_ratio * state.rows as f64 / state.output_partitions as f64;
+            state
+                .counts
+                .drain()
+                .filter(|(_, count)| *count > 1 && *count as f64 > threshold)
+                .map(|(hash, _)| hash)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        if let Some(metric) = &state.hot_keys_metric {
+            metric.set(hot_keys.len());
+        }
+        state.counts = HashMap::new();
+        self.hot_keys.send_replace(Some(Arc::new(hot_keys)));
+    }
+
+    /// Returns the hashes of the hot keys, once all the input partitions of
+    /// the sampling repartition added their sample
+    pub(super) async fn hot_keys(&self) -> Arc<HashSet<u64>> {
+        {
+            let mut state = self.state.lock();
+            if state.pending.is_none() {
+                // The keys are not sampled: no key is hot
+                self.finish(&mut state);
+            }
+        }
+        let mut receiver = self.hot_keys.subscribe();
+        let hot_keys = match receiver.wait_for(Option::is_some).await {
+            Ok(hot_keys) => hot_keys.clone().unwrap_or_default(),
+            // The sender is owned by `self`
+            Err(_) => Arc::default(),
+        };
+        hot_keys
+    }
+}
+
+/// The hot key handling of a [`RepartitionExec`]
+///
+/// [`RepartitionExec`]: super::RepartitionExec
+#[derive(Debug, Clone)]
+pub(super) struct HotKeys {
+    pub(super) routing: HotKeyRouting,
+    pub(super) detector: Arc<HotKeyDetector>,
+}
+
+/// Samples the key hashes of an input partition, and adds its sample to the
+/// detector when dropped, so that the other partitions do not wait for it if
+/// the input fails
+pub(super) struct KeySampler {
+    detector: Arc<HotKeyDetector>,
+    counts: HashMap<u64, usize>,
+    rows: usize,
+}
+
+impl KeySampler {
+    pub(super) fn new(detector: Arc<HotKeyDetector>) -> Self {
+        Self {
+            detector,
+            counts: HashMap::new(),
+            rows: 0,
+        }
+    }
+
+    /// Returns true once enough rows were sampled
+    pub(super) fn is_complete(&self) -> bool {
+        self.rows >= self.detector.sample_rows
+    }
+
+    /// Samples the key hashes of a batch
+    pub(super) fn add(&mut self, hashes: &[u64]) {
+        let remaining = self.detector.sample_rows.saturating_sub(self.rows);
+        for hash in hashes.iter().take(remaining) {
+            *self.counts.entry(*hash).or_default() += 1;
+        }
+        self.rows += hashes.len().min(remaining);
+    }
+}
+
+impl Drop for KeySampler {
+    fn drop(&mut self) {
+        self.detector
+            .add_sample(std::mem::take(&mut self.counts), self.rows);
+    }
+}
+
+/// Routes the rows of the hot keys of an input partition
+pub(super) struct HotKeyRouter {
+    routing: HotKeyRouting,
+    hot_keys: Arc<HashSet<u64>>,
+    next_partition: usize,
+    /// Rows of the hot keys spread or replicated
+    hot_key_rows: Count,
+}
+
+impl HotKeyRouter {
+    pub(super) fn new(
+        routing: HotKeyRouting,
+        hot_keys: Arc<HashSet<u64>>,
+        hot_key_rows: Count,
+    ) -> Self {
+        Self {
+            routing,
+            hot_keys,
+            next_partition: 0,
+            hot_key_rows,
+        }
+    }
+
+    /// Adds the row `index` with the key `hash` to the `indices` of the output
+    /// partitions it is sent to. Returns false if the key is not hot.
+    pub(super) fn route(
+        &mut self,
+        index: u32,
+        hash: u64,
+        indices: &mut [Vec<u32>],
+    ) -> bool {
+        if self.routing == HotKeyRouting::Detect
+            || self.hot_keys.is_empty()
+            || !self.hot_keys.contains(&hash)
+        {
+            return false;
+        }
+        match self.routing {
+            HotKeyRouting::Spread => {
+                indices[self.next_partition].push(index);
+                self.next_partition = (self.next_partition + 1) % indices.len();
+            }
+            HotKeyRouting::Replicate => {
+                indices.iter_mut().for_each(|indices| indices.push(index))
+            }
+            HotKeyRouting::Detect => {}
+        }
+        self.hot_key_rows.add(1);
+        true
+    }
+}
+
+/// The skew metrics of a [`RepartitionExec`] detecting its hot keys, shared by
+/// its input partitions
+///
+/// [`RepartitionExec`]: super::RepartitionExec
+#[derive(Debug, Clone)]
+pub(super) struct SkewMetrics {
+    /// Rows sent to each output partition
+    partition_rows: Vec<Count>,
+    /// Rows sent to the largest output partition
+    max_partition_rows: Gauge,
+    /// Number of hot keys detected
+    pub(super) hot_keys: Gauge,
+}
+
+impl SkewMetrics {
+    pub(super) fn new(
+        output_partitions: usize,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Self {
+        Self {
+            partition_rows: (0..output_partitions)
+                .map(|partition| {
+                    MetricBuilder::new(metrics).counter("partition_rows", partition)
+                })
+                .collect(),
+            max_partition_rows: MetricBuilder::new(metrics)
+                .global_gauge("max_partition_rows"),
+            hot_keys: MetricBuilder::new(metrics).global_gauge("hot_keys"),
+        }
+    }
+
+    /// Records `rows` sent to the output partition `partition`
+    pub(super) fn record(&self, partition: usize, rows: usize) {
+        let partition_rows = &self.partition_rows[partition];
+        partition_rows.add(rows);
+        self.max_partition_rows.set_max(partition_rows.value());
+    }
+}
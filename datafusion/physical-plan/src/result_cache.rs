@@ -0,0 +1,517 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the cached result execution plan, which returns the result of its
+//! input from the query result cache of the runtime
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::coop::cooperative;
+use crate::execution_plan::{CardinalityEffect, ExecutionPlanProperties, SchedulingType};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use crate::stream::{ObservedStream, RecordBatchStreamAdapter};
+use crate::{
+    displayable, DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
+    RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::cache::cache_manager::QueryResultCache;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use futures::{stream, Stream, StreamExt};
+use log::debug;
+use object_store::ObjectMeta;
+use parking_lot::Mutex;
+
+/// Returns the result of its input from the query result cache of the
+/// runtime, see [`CacheManager::get_query_result_cache`].
+///
+/// The result is cached under the normalized plan of the input, along with the
+/// versions of the files the input reads: it is only returned while none of
+/// these files has changed. On a miss, the input is executed, and its batches
+/// are cached once all its partitions are read until their end, unless they
+/// exceed `datafusion.execution.result_cache_max_entry_size` or do not fit in
+/// the memory pool.
+///
+/// The input is executed as usual if the runtime has no query result cache.
+///
+/// [`CacheManager::get_query_result_cache`]: datafusion_execution::cache::cache_manager::CacheManager::get_query_result_cache
+#[derive(Debug)]
+pub struct CachedResultExec {
+    /// The cached plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The normalized plan of the input, the key of its result in the cache
+    key: String,
+    /// The files read by the input
+    files: Vec<ObjectMeta>,
+    /// The batches of the current execution of the input
+    collector: Mutex<Option<Arc<Mutex<ResultCollector>>>>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl CachedResultExec {
+    /// Create a new CachedResultExec caching the result of `input`, which
+    /// reads `files`
+    pub fn new(input: Arc<dyn ExecutionPlan>, files: Vec<ObjectMeta>) -> Self {
+        let key = displayable(input.as_ref())
+            .set_show_schema(true)
+            .indent(true)
+            .to_string();
+        let cache = Self::compute_properties(&input);
+        Self {
+            input,
+            key,
+            files,
+            collector: Mutex::new(None),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        }
+    }
+
+    /// The cached plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The key of the result of the input in the cache
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The files read by the input
+    pub fn files(&self) -> &[ObjectMeta] {
+        &self.files
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(input: &Arc<dyn ExecutionPlan>) -> PlanProperties {
+        PlanProperties::new(
+            input.equivalence_properties().clone(),
+            input.output_partitioning().clone(),
+            input.pipeline_behavior(),
+            input.boundedness(),
+        )
+        .with_scheduling_type(SchedulingType::Cooperative)
+    }
+
+    /// Returns the collector of the execution of `partition`, starting a new
+    /// execution if the partition was already executed
+    fn collector(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+    ) -> Arc<Mutex<ResultCollector>> {
+        let mut current = self.collector.lock();
+        if let Some(collector) = current.as_ref() {
+            let mut state = collector.lock();
+            if !state.started[partition] {
+                state.started[partition] = true;
+                return Arc::clone(collector);
+            }
+        }
+        let partition_count = self.properties().partitioning.partition_count();
+        let mut collector = ResultCollector {
+            started: vec![false; partition_count],
+            partitions: vec![None; partition_count],
+            remaining: partition_count,
+            size: 0,
+            failed: false,
+            reservation: MemoryConsumer::new("CachedResultExec")
+                .register(context.memory_pool()),
+        };
+        collector.started[partition] = true;
+        let collector = Arc::new(Mutex::new(collector));
+        *current = Some(Arc::clone(&collector));
+        collector
+    }
+}
+
+impl DisplayAs for CachedResultExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "CachedResultExec: files={}", self.files.len())
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "files={}", self.files.len())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for CachedResultExec {
+    fn name(&self) -> &'static str {
+        "CachedResultExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return internal_err!("CachedResultExec wrong number of children");
+        }
+        Ok(Arc::new(CachedResultExec::new(
+            children.swap_remove(0),
+            self.files.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let partition_count = self.properties().partitioning.partition_count();
+        if partition >= partition_count {
+            return internal_err!(
+                "Invalid partition {partition} for CachedResultExec with {partition_count} partitions"
+            );
+        }
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let hits = MetricBuilder::new(&self.metrics).counter("cache_hits", partition);
+        let misses = MetricBuilder::new(&self.metrics).counter("cache_misses", partition);
+        let cancellation_token = context.cancellation_token().clone();
+
+        let stream: SendableRecordBatchStream =
+            match context.runtime_env().cache_manager.get_query_result_cache() {
+                None => self.input.execute(partition, context)?,
+                Some(cache) => match cache.get_with_extra(&self.key, &self.files) {
+                    Some(result) if result.len() == partition_count => {
+                        hits.add(1);
+                        let batches = result[partition].clone();
+                        Box::pin(RecordBatchStreamAdapter::new(
+                            self.schema(),
+                            stream::iter(batches.into_iter().map(Ok)),
+                        ))
+                    }
+                    _ => {
+                        misses.add(1);
+                        let max_size = context
+                            .session_config()
+                            .options()
+                            .execution
+                            .result_cache_max_entry_size;
+                        Box::pin(CachingStream {
+                            input: self.input.execute(partition, Arc::clone(&context))?,
+                            partition,
+                            batches: vec![],
+                            collector: self.collector(partition, &context),
+                            cache,
+                            key: self.key.clone(),
+                            files: self.files.clone(),
+                            max_size,
+                            done: false,
+                        })
+                    }
+                },
+            };
+        Ok(Box::pin(
+            cooperative(ObservedStream::new(stream, baseline_metrics, None))
+                .with_cancellation_token(cancellation_token),
+        ))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        self.input.partition_statistics(partition)
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::Equal
+    }
+}
+
+/// The batches of the partitions of an execution of the input of a
+/// [`CachedResultExec`], cached once all the partitions are complete
+#[derive(Debug)]
+struct ResultCollector {
+    /// True for the partitions that were executed
+    started: Vec<bool>,
+    /// The batches of the partitions read until their end
+    partitions: Vec<Option<Vec<RecordBatch>>>,
+    /// The number of partitions not read until their end yet
+    remaining: usize,
+    /// The total size of the collected batches
+    size: usize,
+    /// True if the result can not be cached
+    failed: bool,
+    reservation: MemoryReservation,
+}
+
+impl ResultCollector {
+    /// Gives up caching the result, and releases the collected batches
+    fn fail(&mut self) {
+        self.failed = true;
+        self.partitions
+            .iter_mut()
+            .for_each(|batches| *batches = None);
+        self.reservation.free();
+    }
+}
+
+/// Returns the batches of a partition of the input of a [`CachedResultExec`],
+/// collecting them to cache the result of the input
+struct CachingStream {
+    input: SendableRecordBatchStream,
+    partition: usize,
+    batches: Vec<RecordBatch>,
+    collector: Arc<Mutex<ResultCollector>>,
+    cache: QueryResultCache,
+    key: String,
+    files: Vec<ObjectMeta>,
+    max_size: usize,
+    /// True once the input is read until its end, or failed
+    done: bool,
+}
+
+impl CachingStream {
+    /// Collects `batch`, unless the result does not fit in the cache
+    fn collect_batch(&mut self, batch: &RecordBatch) {
+        let mut collector = self.collector.lock();
+        if collector.failed {
+            self.batches.clear();
+            return;
+        }
+        let size = batch.get_array_memory_size();
+        if collector.size + size > self.max_size
+            || collector.reservation.try_grow(size).is_err()
+        {
+            debug!("The result of a CachedResultExec is too large to be cached");
+            collector.fail();
+            self.batches.clear();
+            return;
+        }
+        collector.size += size;
+        self.batches.push(batch.clone());
+    }
+
+    /// Adds the batches of the partition to the result, and caches the result
+    /// once all the partitions are complete
+    fn finish(&mut self) {
+        self.done = true;
+        let mut collector = self.collector.lock();
+        if collector.failed {
+            return;
+        }
+        collector.partitions[self.partition] = Some(std::mem::take(&mut self.batches));
+        collector.remaining -= 1;
+        if collector.remaining == 0 {
+            let result = collector
+                .partitions
+                .iter_mut()
+                .map(|batches| batches.take().unwrap_or_default())
+                .collect();
+            self.cache
+                .put_with_extra(&self.key, Arc::new(result), &self.files);
+            collector.reservation.free();
+        }
+    }
+
+    /// Gives up caching the result
+    fn fail(&mut self) {
+        self.done = true;
+        self.batches.clear();
+        self.collector.lock().fail();
+    }
+}
+
+impl Stream for CachingStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => self.collect_batch(batch),
+            Poll::Ready(Some(Err(_))) => self.fail(),
+            Poll::Ready(None) if !self.done => self.finish(),
+            _ => {}
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+impl Drop for CachingStream {
+    fn drop(&mut self) {
+        // The partition was not read until its end
+        if !self.done {
+            self.fail();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::collect;
+    use crate::test::exec::MockExec;
+
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::exec_err;
+    use datafusion_execution::cache::cache_manager::CacheManagerConfig;
+    use datafusion_execution::cache::cache_unit::DefaultQueryResultCache;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+
+    fn batch(values: Vec<i32>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let array: ArrayRef = Arc::new(Int32Array::from(values));
+        RecordBatch::try_new(schema, vec![array]).unwrap()
+    }
+
+    fn cached_result(values: Vec<i32>) -> CachedResultExec {
+        let input = MockExec::new(vec![Ok(batch(values))], batch(vec![]).schema());
+        CachedResultExec::new(Arc::new(input), vec![])
+    }
+
+    fn task_context(max_entry_size: usize) -> Result<Arc<TaskContext>> {
+        let cache = Arc::new(DefaultQueryResultCache::new(1024 * 1024));
+        let runtime = RuntimeEnvBuilder::new()
+            .with_cache_manager(
+                CacheManagerConfig::default().with_query_result_cache(Some(cache)),
+            )
+            .build_arc()?;
+        let mut options = ConfigOptions::new();
+        options.execution.result_cache_max_entry_size = max_entry_size;
+        Ok(Arc::new(
+            TaskContext::default()
+                .with_session_config(SessionConfig::from(options))
+                .with_runtime(runtime),
+        ))
+    }
+
+    fn metric(plan: &CachedResultExec, name: &str) -> usize {
+        plan.metrics()
+            .unwrap()
+            .sum_by_name(name)
+            .map(|value| value.as_usize())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn cache_result() -> Result<()> {
+        let context = task_context(1024 * 1024)?;
+        let plan = Arc::new(cached_result(vec![1, 2, 3]));
+        let batches = collect(Arc::clone(&plan) as _, Arc::clone(&context)).await?;
+        assert_eq!(batches, vec![batch(vec![1, 2, 3])]);
+        assert_eq!(metric(&plan, "cache_misses"), 1);
+
+        // An identical plan returns the cached batches, without executing its
+        // input
+        let plan = Arc::new(CachedResultExec::new(
+            Arc::new(MockExec::new(
+                vec![exec_err!("not executed")],
+                batch(vec![]).schema(),
+            )),
+            vec![],
+        ));
+        let batches = collect(Arc::clone(&plan) as _, Arc::clone(&context)).await?;
+        assert_eq!(batches, vec![batch(vec![1, 2, 3])]);
+        assert_eq!(metric(&plan, "cache_hits"), 1);
+        assert_eq!(plan.metrics().unwrap().output_rows(), Some(3));
+        assert_eq!(context.memory_pool().reserved(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn result_too_large() -> Result<()> {
+        let context = task_context(16)?;
+        let plan = Arc::new(cached_result(vec![1, 2, 3]));
+        collect(Arc::clone(&plan) as _, Arc::clone(&context)).await?;
+
+        let plan = Arc::new(cached_result(vec![1, 2, 3]));
+        collect(Arc::clone(&plan) as _, Arc::clone(&context)).await?;
+        assert_eq!(metric(&plan, "cache_misses"), 1);
+        assert_eq!(context.memory_pool().reserved(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_input_not_cached() -> Result<()> {
+        let context = task_context(1024 * 1024)?;
+        let input = MockExec::new(
+            vec![Ok(batch(vec![1])), exec_err!("failed")],
+            batch(vec![]).schema(),
+        );
+        let plan = Arc::new(CachedResultExec::new(Arc::new(input), vec![]));
+        assert!(collect(Arc::clone(&plan) as _, Arc::clone(&context))
+            .await
+            .is_err());
+        let cache = context
+            .runtime_env()
+            .cache_manager
+            .get_query_result_cache()
+            .unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(context.memory_pool().reserved(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,690 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the shared subplan execution plan, which evaluates its input once
+//! for all the references to a subplan
+
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+use crate::coop::cooperative;
+use crate::execution_plan::{CardinalityEffect, EmissionType, SchedulingType};
+use crate::joins::utils::OnceFut;
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::{ObservedStream, RecordBatchStreamAdapter};
+use crate::{
+    displayable, DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, SendableRecordBatchStream, Statistics,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use futures::{future, stream, Future, StreamExt, TryStreamExt};
+use log::debug;
+use parking_lot::Mutex;
+
+/// The result of a shared subplan, computed once for each partition and
+/// execution of the plan, and read by all the [`SharedSubplanExec`]s linked to
+/// it
+///
+/// The partitions are kept separately for each [`TaskContext`] executing the
+/// plan, so that executing the plan again computes the shared subplan again.
+/// A partition is removed from the buffer once every linked
+/// [`SharedSubplanExec`] has executed it, and its batches are released when
+/// their streams are dropped. The partitions of an execution that did not
+/// execute all of its consumers, e.g. because it failed, are released when the
+/// next execution starts or when the plan is dropped.
+pub struct SharedSubplanBuffer {
+    partition_count: usize,
+    /// The number of [`SharedSubplanExec`]s linked to the buffer
+    consumers: AtomicUsize,
+    /// The partitions of the executions in progress
+    executions: Mutex<Vec<SharedExecution>>,
+}
+
+impl SharedSubplanBuffer {
+    /// Create an empty buffer for the given number of partitions
+    pub fn new(partition_count: usize) -> Self {
+        Self {
+            partition_count,
+            consumers: AtomicUsize::new(0),
+            executions: Mutex::new(vec![]),
+        }
+    }
+
+    /// Returns the number of partitions of the buffer
+    pub fn partition_count(&self) -> usize {
+        self.partition_count
+    }
+
+    /// Returns the number of [`SharedSubplanExec`]s linked to the buffer
+    pub fn consumer_count(&self) -> usize {
+        self.consumers.load(Ordering::Relaxed)
+    }
+
+    /// Returns the partition `partition` of the execution of `context`,
+    /// computed with `f` by its first consumer
+    fn partition<F, Fut>(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+        f: F,
+    ) -> Result<OnceFut<SharedPartition>>
+    where
+        F: FnOnce() -> Result<Fut>,
+        Fut: Future<Output = Result<SharedPartition>> + Send + 'static,
+    {
+        let mut executions = self.executions.lock();
+        // the executions whose context was dropped will not read their partitions
+        executions.retain(|execution| execution.context.strong_count() > 0);
+
+        let index = executions
+            .iter()
+            .position(|execution| {
+                std::ptr::eq(execution.context.as_ptr(), Arc::as_ptr(context))
+            })
+            .unwrap_or_else(|| {
+                executions.push(SharedExecution {
+                    context: Arc::downgrade(context),
+                    partitions: (0..self.partition_count).map(|_| None).collect(),
+                    remaining_readers: vec![self.consumer_count(); self.partition_count],
+                });
+                executions.len() - 1
+            });
+        let execution = &mut executions[index];
+
+        let shared_partition = match &execution.partitions[partition] {
+            Some(shared_partition) => shared_partition.clone(),
+            None => {
+                let shared_partition = OnceFut::new(f()?);
+                execution.partitions[partition] = Some(shared_partition.clone());
+                shared_partition
+            }
+        };
+
+        let remaining_readers = &mut execution.remaining_readers[partition];
+        *remaining_readers = remaining_readers.saturating_sub(1);
+        if *remaining_readers == 0 {
+            // the last reader holds the partition until its stream is created
+            execution.partitions[partition] = None;
+            if execution
+                .remaining_readers
+                .iter()
+                .all(|readers| *readers == 0)
+            {
+                executions.swap_remove(index);
+            }
+        }
+        Ok(shared_partition)
+    }
+}
+
+impl std::fmt::Debug for SharedSubplanBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSubplanBuffer")
+            .field("partition_count", &self.partition_count)
+            .field("consumers", &self.consumer_count())
+            .field("executions", &self.executions.lock().len())
+            .finish()
+    }
+}
+
+/// The partitions of a [`SharedSubplanBuffer`] for an execution of the plan
+struct SharedExecution {
+    /// The context of the execution
+    context: Weak<TaskContext>,
+    /// The partitions being computed or read
+    partitions: Vec<Option<OnceFut<SharedPartition>>>,
+    /// The number of consumers that did not execute each partition yet
+    remaining_readers: Vec<usize>,
+}
+
+/// Links a [`SharedSubplanExec`] to a [`SharedSubplanBuffer`], counting it as
+/// one of the consumers of the buffer while it exists
+#[derive(Debug)]
+struct SharedSubplanLink {
+    buffer: Arc<SharedSubplanBuffer>,
+}
+
+impl SharedSubplanLink {
+    fn new(buffer: Arc<SharedSubplanBuffer>) -> Self {
+        buffer.consumers.fetch_add(1, Ordering::Relaxed);
+        Self { buffer }
+    }
+}
+
+impl Drop for SharedSubplanLink {
+    fn drop(&mut self) {
+        self.buffer.consumers.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A partition of a [`SharedSubplanBuffer`]: the batches of the input
+/// partition, either kept in memory or spilled to disk
+#[derive(Debug)]
+struct SharedPartition {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    spill_file: Option<Arc<RefCountedTempFile>>,
+    spill_manager: SpillManager,
+    _reservation: MemoryReservation,
+}
+
+impl SharedPartition {
+    /// Reads the input partition into memory, spilling it to disk when it
+    /// does not fit
+    async fn try_new(
+        mut input: SendableRecordBatchStream,
+        spill_manager: SpillManager,
+        mut reservation: MemoryReservation,
+        context: Arc<TaskContext>,
+    ) -> Result<Self> {
+        let mut batches = vec![];
+        let mut spill_file: Option<InProgressSpillFile> = None;
+        while let Some(batch) = input.next().await.transpose()? {
+            if let Some(spill_file) = spill_file.as_mut() {
+                spill_file.append_batch(&batch)?;
+                continue;
+            }
+            if let Err(e) = reservation.try_grow(batch.get_array_memory_size()) {
+                if !context.runtime_env().disk_manager.tmp_files_enabled() {
+                    return Err(e);
+                }
+                debug!("Spilling the result of a SharedSubplanExec to disk");
+                let mut file = spill_manager.create_in_progress_file("SharedSubplan")?;
+                batches.push(batch);
+                for batch in batches.drain(..) {
+                    file.append_batch(&batch)?;
+                }
+                reservation.free();
+                spill_file = Some(file);
+            } else {
+                batches.push(batch);
+            }
+        }
+
+        let spill_file = match spill_file {
+            Some(mut file) => file.finish()?.map(Arc::new),
+            None => None,
+        };
+        Ok(Self {
+            schema: input.schema(),
+            batches,
+            spill_file,
+            spill_manager,
+            _reservation: reservation,
+        })
+    }
+
+    /// Returns a stream of the batches of the partition
+    fn stream(&self) -> Result<SendableRecordBatchStream> {
+        match &self.spill_file {
+            Some(spill_file) => self
+                .spill_manager
+                .read_shared_spill_as_stream(Arc::clone(spill_file)),
+            None => {
+                let batches = self.batches.clone();
+                Ok(Box::pin(RecordBatchStreamAdapter::new(
+                    Arc::clone(&self.schema),
+                    stream::iter(batches.into_iter().map(Ok)),
+                )))
+            }
+        }
+    }
+}
+
+/// A reference to a subplan whose result is shared by several parts of a
+/// query, such as a `MATERIALIZED` common table expression.
+///
+/// All the `SharedSubplanExec`s of a plan with the same `id` and identical
+/// inputs are linked to the same [`SharedSubplanBuffer`] by
+/// [`link_shared_subplans`]. The first of them to execute a partition
+/// executes the corresponding partition of its input and keeps its batches in
+/// the buffer, spilling them to disk if they do not fit in memory, and all of
+/// them read their output from the buffer. The inputs of the others are not
+/// executed. The buffer is scoped to each execution of the plan, see
+/// [`SharedSubplanBuffer`].
+#[derive(Debug)]
+pub struct SharedSubplanExec {
+    /// Identifier of the shared result
+    id: usize,
+    /// The shared subplan
+    input: Arc<dyn ExecutionPlan>,
+    /// The result of the shared subplan
+    link: SharedSubplanLink,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl SharedSubplanExec {
+    /// Create a new SharedSubplanExec, with a buffer of its own
+    pub fn new(id: usize, input: Arc<dyn ExecutionPlan>) -> Self {
+        let buffer = Arc::new(SharedSubplanBuffer::new(
+            input.output_partitioning().partition_count(),
+        ));
+        Self::new_linked(id, input, buffer)
+    }
+
+    /// Create a new SharedSubplanExec reading `buffer`
+    fn new_linked(
+        id: usize,
+        input: Arc<dyn ExecutionPlan>,
+        buffer: Arc<SharedSubplanBuffer>,
+    ) -> Self {
+        let cache = Self::compute_properties(&input);
+        Self {
+            id,
+            input,
+            link: SharedSubplanLink::new(buffer),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        }
+    }
+
+    /// Identifier of the shared result
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The shared subplan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The buffer holding the result of the shared subplan
+    pub fn buffer(&self) -> &Arc<SharedSubplanBuffer> {
+        &self.link.buffer
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(input: &Arc<dyn ExecutionPlan>) -> PlanProperties {
+        PlanProperties::new(
+            input.equivalence_properties().clone(),
+            input.output_partitioning().clone(),
+            EmissionType::Final,
+            input.boundedness(),
+        )
+        .with_scheduling_type(SchedulingType::Cooperative)
+    }
+}
+
+impl DisplayAs for SharedSubplanExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "SharedSubplanExec: id={}", self.id)
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "id={}", self.id)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for SharedSubplanExec {
+    fn name(&self) -> &'static str {
+        "SharedSubplanExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    /// The returned node stays linked to the buffer of this node if its input
+    /// is identical to the input of this node, as in [`link_shared_subplans`].
+    /// Otherwise its result differs from the result of the other linked nodes,
+    /// and it has a buffer of its own.
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return internal_err!("SharedSubplanExec wrong number of children");
+        }
+        let input = children.swap_remove(0);
+        let linked = input.output_partitioning().partition_count()
+            == self.buffer().partition_count()
+            && (Arc::ptr_eq(&input, &self.input)
+                || displayable(input.as_ref()).indent(true).to_string()
+                    == displayable(self.input.as_ref()).indent(true).to_string());
+        Ok(Arc::new(if linked {
+            SharedSubplanExec::new_linked(self.id, input, Arc::clone(self.buffer()))
+        } else {
+            SharedSubplanExec::new(self.id, input)
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition >= self.buffer().partition_count() {
+            return internal_err!(
+                "Invalid partition {partition} for SharedSubplanExec with {} partitions",
+                self.buffer().partition_count()
+            );
+        }
+        let cancellation_token = context.cancellation_token().clone();
+        let mut shared_partition =
+            self.buffer().partition(partition, &context, || {
+                let input = self.input.execute(partition, Arc::clone(&context))?;
+                let spill_manager = SpillManager::new(
+                    context.runtime_env(),
+                    SpillMetrics::new(&self.metrics, partition),
+                    self.input.schema(),
+                )
+                .with_compression_type(context.session_config().spill_compression());
+                let reservation =
+                    MemoryConsumer::new(format!("SharedSubplanExec[{partition}]"))
+                        .with_can_spill(true)
+                        .register(context.memory_pool());
+                Ok(SharedPartition::try_new(
+                    input,
+                    spill_manager,
+                    reservation,
+                    Arc::clone(&context),
+                ))
+            })?;
+
+        let stream = stream::once(async move {
+            future::poll_fn(|cx| shared_partition.get_shared(cx))
+                .await?
+                .stream()
+        })
+        .try_flatten();
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema(), stream));
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(
+            cooperative(ObservedStream::new(stream, baseline_metrics, None))
+                .with_cancellation_token(cancellation_token),
+        ))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        self.input.partition_statistics(partition)
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::Equal
+    }
+
+    /// Links this node to a [`SharedSubplanBuffer`] with the same number of
+    /// partitions, see [`link_shared_subplans`]
+    fn with_new_state(
+        &self,
+        state: Arc<dyn Any + Send + Sync>,
+    ) -> Option<Arc<dyn ExecutionPlan>> {
+        let buffer = state.downcast::<SharedSubplanBuffer>().ok()?;
+        if buffer.partition_count() != self.buffer().partition_count() {
+            return None;
+        }
+
+        Some(Arc::new(Self::new_linked(
+            self.id,
+            Arc::clone(&self.input),
+            buffer,
+        )))
+    }
+}
+
+/// Links the [`SharedSubplanExec`]s of `plan` that have the same `id` and
+/// identical inputs to the buffer of the first of them, so that the shared
+/// subplan is executed once.
+///
+/// This is done once the plan is optimized: its `SharedSubplanExec`s are not
+/// linked before, and [`ExecutionPlan::with_new_children`] only keeps them
+/// linked while their input is unchanged.
+pub fn link_shared_subplans(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let mut buffers = HashMap::<(usize, String), Arc<SharedSubplanBuffer>>::new();
+    plan.transform_up(|plan| {
+        let Some(shared) = plan.as_any().downcast_ref::<SharedSubplanExec>() else {
+            return Ok(Transformed::no(plan));
+        };
+        let input = displayable(shared.input.as_ref()).indent(true).to_string();
+        match buffers.entry((shared.id, input)) {
+            Entry::Occupied(buffer) => {
+                let buffer = Arc::clone(buffer.get()) as Arc<dyn Any + Send + Sync>;
+                Ok(match plan.with_new_state(buffer) {
+                    Some(linked) => Transformed::yes(linked),
+                    None => Transformed::no(plan),
+                })
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Arc::clone(shared.buffer()));
+                Ok(Transformed::no(plan))
+            }
+        }
+    })
+    .data()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::collect;
+    use crate::test::exec::MockExec;
+    use crate::union::UnionExec;
+
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::memory_pool::FairSpillPool;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+
+    fn batch(values: Vec<i32>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let array: ArrayRef = Arc::new(Int32Array::from(values));
+        RecordBatch::try_new(schema, vec![array]).unwrap()
+    }
+
+    /// A union of two references to a shared subplan, and the shared subplan
+    fn shared_union() -> (Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>) {
+        let batches = (0..10).map(|i| Ok(batch(vec![i; 1000]))).collect();
+        let input = MockExec::new(batches, batch(vec![]).schema());
+        // counts the rows of all the executions of the input
+        let shared: Arc<dyn ExecutionPlan> =
+            Arc::new(CoalesceBatchesExec::new(Arc::new(input), 1000));
+        let union = Arc::new(UnionExec::new(vec![
+            Arc::new(SharedSubplanExec::new(0, Arc::clone(&shared))),
+            Arc::new(SharedSubplanExec::new(0, Arc::clone(&shared))),
+        ]));
+        (union, shared)
+    }
+
+    /// The sorted values of `batches`, as the partitions are collected concurrently
+    fn values(batches: &[RecordBatch]) -> Vec<i32> {
+        let mut values: Vec<i32> = batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch.column(0).as_any().downcast_ref::<Int32Array>();
+                array.unwrap().values().to_vec()
+            })
+            .collect();
+        values.sort_unstable();
+        values
+    }
+
+    fn expected_values() -> Vec<i32> {
+        (0..10).flat_map(|i| vec![i; 2000]).collect()
+    }
+
+    #[tokio::test]
+    async fn shared_subplan_is_executed_once() -> Result<()> {
+        let (plan, shared) = shared_union();
+        let plan = link_shared_subplans(plan)?;
+
+        let batches = collect(plan, Arc::new(TaskContext::default())).await?;
+        assert_eq!(values(&batches), expected_values());
+        assert_eq!(shared.metrics().unwrap().output_rows(), Some(10000));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unlinked_subplans_are_executed_separately() -> Result<()> {
+        let (plan, shared) = shared_union();
+
+        let batches = collect(plan, Arc::new(TaskContext::default())).await?;
+        assert_eq!(values(&batches), expected_values());
+        assert_eq!(shared.metrics().unwrap().output_rows(), Some(20000));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shared_subplan_is_executed_once_per_execution() -> Result<()> {
+        let (plan, shared) = shared_union();
+        let plan = link_shared_subplans(plan)?;
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(10_000_000, 1.0)
+            .build_arc()?;
+
+        for executions in 1..=2 {
+            let context =
+                Arc::new(TaskContext::default().with_runtime(Arc::clone(&runtime)));
+            let batches = collect(Arc::clone(&plan), context).await?;
+            assert_eq!(values(&batches), expected_values());
+            assert_eq!(
+                shared.metrics().unwrap().output_rows(),
+                Some(10000 * executions)
+            );
+            // the buffer does not keep the result once it was read
+            assert_eq!(runtime.memory_pool.reserved(), 0);
+        }
+        Ok(())
+    }
+
+    fn linked_buffer(plan: &Arc<dyn ExecutionPlan>) -> &Arc<SharedSubplanBuffer> {
+        plan.as_any()
+            .downcast_ref::<SharedSubplanExec>()
+            .unwrap()
+            .buffer()
+    }
+
+    #[test]
+    fn with_new_children_keeps_the_link_of_identical_inputs() -> Result<()> {
+        let (plan, shared) = shared_union();
+        let plan = link_shared_subplans(plan)?;
+        let children = plan.children();
+        let buffer = linked_buffer(children[0]);
+        assert!(Arc::ptr_eq(buffer, linked_buffer(children[1])));
+        assert_eq!(buffer.consumer_count(), 2);
+
+        let identical: Arc<dyn ExecutionPlan> = Arc::new(CoalesceBatchesExec::new(
+            Arc::clone(shared.children()[0]),
+            1000,
+        ));
+        let relinked = Arc::clone(children[1]).with_new_children(vec![identical])?;
+        assert!(Arc::ptr_eq(buffer, linked_buffer(&relinked)));
+        assert_eq!(buffer.consumer_count(), 3);
+        drop(relinked);
+        assert_eq!(buffer.consumer_count(), 2);
+
+        let different: Arc<dyn ExecutionPlan> = Arc::new(CoalesceBatchesExec::new(
+            Arc::clone(shared.children()[0]),
+            10,
+        ));
+        let unlinked = Arc::clone(children[1]).with_new_children(vec![different])?;
+        assert!(!Arc::ptr_eq(buffer, linked_buffer(&unlinked)));
+        assert_eq!(linked_buffer(&unlinked).consumer_count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shared_subplan_spills() -> Result<()> {
+        let (plan, shared) = shared_union();
+        let plan = link_shared_subplans(plan)?;
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(10_000)))
+            .build_arc()?;
+        let context = Arc::new(TaskContext::default().with_runtime(runtime));
+
+        let batches = collect(Arc::clone(&plan), context).await?;
+        assert_eq!(values(&batches), expected_values());
+        assert_eq!(shared.metrics().unwrap().output_rows(), Some(10000));
+
+        let spilled_rows = plan
+            .children()
+            .iter()
+            .map(|shared| shared.metrics().unwrap().spilled_rows().unwrap_or(0))
+            .sum::<usize>();
+        assert_eq!(spilled_rows, 10000);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shared_subplan_without_spilling() -> Result<()> {
+        let (plan, _) = shared_union();
+        let plan = link_shared_subplans(plan)?;
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(10_000, 1.0)
+            .with_disk_manager_builder(
+                DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+            )
+            .build_arc()?;
+        let context = Arc::new(TaskContext::default().with_runtime(runtime));
+
+        let err = collect(plan, context).await.unwrap_err();
+        assert!(err.to_string().contains("Resources exhausted"), "{err}");
+        Ok(())
+    }
+}
@@ -49,6 +49,10 @@ pub struct BatchBuilder {
     /// The accumulated stream indexes from which to pull rows
     /// Consists of a tuple of `(batch_idx, row_idx)`
     indices: Vec<(usize, usize)>,
+
+    /// The memory of the last output batch, accounted for in `reservation`
+    /// until [`Self::release_output`] is called
+    output_size: usize,
 }
 
 impl BatchBuilder {
@@ -65,6 +69,7 @@ impl BatchBuilder {
             cursors: vec![BatchCursor::default(); stream_count],
             indices: Vec::with_capacity(batch_size),
             reservation,
+            output_size: 0,
         }
     }
 
@@ -99,6 +104,13 @@ impl BatchBuilder {
         self.indices.is_empty()
     }
 
+    /// Releases the memory of the last output batch, once the consumer asks
+    /// for the next batch and so accounts for the output batch if it keeps it
+    pub fn release_output(&mut self) {
+        self.reservation.shrink(self.output_size);
+        self.output_size = 0;
+    }
+
     /// Returns the schema of this [`BatchBuilder`]
     pub fn schema(&self) -> &SchemaRef {
         &self.schema
@@ -125,6 +137,15 @@ impl BatchBuilder {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        // The output batch is held until it is released by the consumer. It
+        // is already allocated, so this does not fail.
+        self.release_output();
+        self.output_size = columns
+            .iter()
+            .map(|array| array.get_array_memory_size())
+            .sum();
+        self.reservation.grow(self.output_size);
+
         self.indices.clear();
 
         // New cursors are only created once the previous cursor for the stream
@@ -206,6 +206,9 @@ impl<C: CursorValues> SortPreservingMergeStream<C> {
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
+        // The consumer asks for the next batch, so the last one is not held
+        // by this stream anymore
+        self.in_progress.release_output();
         if self.aborted {
             return Poll::Ready(None);
         }
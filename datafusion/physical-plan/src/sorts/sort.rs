@@ -345,9 +345,6 @@ impl ExternalSorter {
             }
 
             for spill in self.finished_spill_files.drain(..) {
-                if !spill.path().exists() {
-                    return internal_err!("Spill file {:?} does not exist", spill.path());
-                }
                 let stream = self.spill_manager.read_spill_as_stream(spill)?;
                 streams.push(stream);
             }
@@ -20,7 +20,7 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use crate::common::spawn_buffered;
+use crate::common::spawn_buffered_with_reservation;
 use crate::limit::LimitStream;
 use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
 use crate::projection::{make_with_child, update_ordering, ProjectionExec};
@@ -37,6 +37,7 @@ use datafusion_physical_expr_common::sort_expr::{LexOrdering, OrderingRequiremen
 
 use crate::execution_plan::{EvaluationType, SchedulingType};
 use log::{debug, trace};
+use parking_lot::Mutex;
 
 /// Sort preserving merge execution plan
 ///
@@ -318,11 +319,18 @@ impl ExecutionPlan for SortPreservingMergeExec {
                 }
             },
             _ => {
+                // The batches buffered for each input partition are accounted
+                // for in the reservation of the merge
+                let buffer_reservation = Arc::new(Mutex::new(reservation.new_empty()));
                 let receivers = (0..input_partitions)
                     .map(|partition| {
                         let stream =
                             self.input.execute(partition, Arc::clone(&context))?;
-                        Ok(spawn_buffered(stream, 1))
+                        Ok(spawn_buffered_with_reservation(
+                            stream,
+                            1,
+                            Arc::clone(&buffer_reservation),
+                        ))
                     })
                     .collect::<Result<_>>()?;
 
@@ -65,7 +65,7 @@ impl InProgressSpillFile {
             let schema = batch.schema();
             if let Some(ref in_progress_file) = self.in_progress_file {
                 self.writer = Some(IPCStreamWriter::new(
-                    in_progress_file.path(),
+                    in_progress_file.writer()?,
                     schema.as_ref(),
                     self.spill_writer.compression,
                 )?);
@@ -75,6 +75,7 @@ impl InProgressSpillFile {
             }
         }
         if let Some(writer) = &mut self.writer {
+            let _timer = self.spill_writer.metrics.spill_write_time.timer();
             let (spilled_rows, _) = writer.write(batch)?;
             if let Some(in_progress_file) = &mut self.in_progress_file {
                 in_progress_file.update_disk_usage()?;
@@ -92,6 +93,7 @@ impl InProgressSpillFile {
     /// If there are no batches spilled before, it returns `None`.
     pub fn finish(&mut self) -> Result<Option<RefCountedTempFile>> {
         if let Some(writer) = &mut self.writer {
+            let _timer = self.spill_writer.metrics.spill_write_time.timer();
             writer.finish()?;
         } else {
             return Ok(None);
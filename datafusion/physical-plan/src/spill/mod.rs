@@ -21,8 +21,8 @@ pub(crate) mod in_progress_spill_file;
 pub(crate) mod spill_manager;
 
 use std::fs::File;
-use std::io::BufReader;
-use std::path::{Path, PathBuf};
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::ptr::NonNull;
 use std::sync::Arc;
@@ -41,9 +41,12 @@ use datafusion_common::config::SpillCompression;
 use datafusion_common::{exec_datafusion_err, DataFusionError, HashSet, Result};
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::spill_storage::SpillFileWriter;
 use datafusion_execution::RecordBatchStream;
 use futures::{FutureExt as _, Stream};
 
+use crate::metrics::{Count, SpillMetrics};
+
 /// Stream that reads spill files from disk where each batch is read in a spawned blocking task
 /// It will read one batch at a time and will not do any buffering, to buffer data use [`crate::common::spawn_buffered`]
 ///
@@ -54,32 +57,41 @@ use futures::{FutureExt as _, Stream};
 struct SpillReaderStream {
     schema: SchemaRef,
     state: SpillReaderStreamState,
+    metrics: SpillMetrics,
 }
 
+/// The reader of a spill file
+type SpillFileReader = StreamReader<BufReader<CountingReader>>;
+
 /// When we poll for the next batch, we will get back both the batch and the reader,
 /// so we can call `next` again.
-type NextRecordBatchResult = Result<(StreamReader<BufReader<File>>, Option<RecordBatch>)>;
+type NextRecordBatchResult = Result<(Box<SpillFileReader>, Option<RecordBatch>)>;
 
 enum SpillReaderStreamState {
     /// Initial state: the stream was not initialized yet
     /// and the file was not opened
-    Uninitialized(RefCountedTempFile),
+    Uninitialized(Arc<RefCountedTempFile>),
 
     /// A read is in progress in a spawned blocking task for which we hold the handle.
     ReadInProgress(SpawnedTask<NextRecordBatchResult>),
 
     /// A read has finished and we wait for being polled again in order to start reading the next batch.
-    Waiting(StreamReader<BufReader<File>>),
+    Waiting(Box<SpillFileReader>),
 
     /// The stream has finished, successfully or not.
     Done,
 }
 
 impl SpillReaderStream {
-    fn new(schema: SchemaRef, spill_file: RefCountedTempFile) -> Self {
+    fn new(
+        schema: SchemaRef,
+        spill_file: Arc<RefCountedTempFile>,
+        metrics: SpillMetrics,
+    ) -> Self {
         Self {
             schema,
             state: SpillReaderStreamState::Uninitialized(spill_file),
+            metrics,
         }
     }
 
@@ -96,8 +108,14 @@ impl SpillReaderStream {
                     unreachable!()
                 };
 
+                let metrics = self.metrics.clone();
                 let task = SpawnedTask::spawn_blocking(move || {
-                    let file = BufReader::new(File::open(spill_file.path())?);
+                    let _timer = metrics.spill_read_time.timer();
+                    let file = BufReader::new(CountingReader {
+                        inner: spill_file.reader()?,
+                        count: metrics.spill_read_bytes,
+                        _spill_file: spill_file,
+                    });
                     // SAFETY: DataFusion's spill writer strictly follows Arrow IPC specifications
                     // with validated schemas and buffers. Skip redundant validation during read
                     // to speedup read operation. This is safe for DataFusion as input guaranteed to be correct when written.
@@ -107,7 +125,7 @@ impl SpillReaderStream {
 
                     let next_batch = reader.next().transpose()?;
 
-                    Ok((reader, next_batch))
+                    Ok((Box::new(reader), next_batch))
                 });
 
                 self.state = SpillReaderStreamState::ReadInProgress(task);
@@ -153,7 +171,9 @@ impl SpillReaderStream {
                     unreachable!()
                 };
 
+                let read_time = self.metrics.spill_read_time.clone();
                 let task = SpawnedTask::spawn_blocking(move || {
+                    let _timer = read_time.timer();
                     let next_batch = reader.next().transpose()?;
 
                     Ok((reader, next_batch))
@@ -185,6 +205,22 @@ impl RecordBatchStream for SpillReaderStream {
     }
 }
 
+/// Counts the bytes read from a spill file
+struct CountingReader {
+    inner: Box<dyn Read + Send>,
+    count: Count,
+    /// The file is deleted when dropped, so it is kept until it is read
+    _spill_file: Arc<RefCountedTempFile>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.count.add(len);
+        Ok(len)
+    }
+}
+
 /// Spill the `RecordBatch` to disk as smaller batches
 /// split by `batch_size_rows`
 #[deprecated(
@@ -199,8 +235,14 @@ pub fn spill_record_batch_by_size(
 ) -> Result<()> {
     let mut offset = 0;
     let total_rows = batch.num_rows();
-    let mut writer =
-        IPCStreamWriter::new(&path, schema.as_ref(), SpillCompression::Uncompressed)?;
+    let file = File::create(&path).map_err(|e| {
+        exec_datafusion_err!("Failed to create partition file at {path:?}: {e:?}")
+    })?;
+    let mut writer = IPCStreamWriter::new(
+        Box::new(file),
+        schema.as_ref(),
+        SpillCompression::Uncompressed,
+    )?;
 
     while offset < total_rows {
         let length = std::cmp::min(total_rows - offset, batch_size_rows);
@@ -281,13 +323,13 @@ fn count_array_data_memory_size(
     }
 }
 
-/// Write in Arrow IPC Stream format to a file.
+/// Write in Arrow IPC Stream format to a spill file.
 ///
 /// Stream format is used for spill because it supports dictionary replacement, and the random
 /// access of IPC File format is not needed (IPC File format doesn't support dictionary replacement).
 struct IPCStreamWriter {
     /// Inner writer
-    pub writer: StreamWriter<File>,
+    pub writer: StreamWriter<Box<dyn SpillFileWriter>>,
     /// Batches written
     pub num_batches: usize,
     /// Rows written
@@ -299,14 +341,10 @@ struct IPCStreamWriter {
 impl IPCStreamWriter {
     /// Create new writer
     pub fn new(
-        path: &Path,
+        file: Box<dyn SpillFileWriter>,
         schema: &Schema,
         compression_type: SpillCompression,
     ) -> Result<Self> {
-        let file = File::create(path).map_err(|e| {
-            exec_datafusion_err!("Failed to create partition file at {path:?}: {e:?}")
-        })?;
-
         let metadata_version = MetadataVersion::V5;
         let alignment = 8;
         let mut write_options =
@@ -337,7 +375,8 @@ impl IPCStreamWriter {
 
     /// Finish the writer
     pub fn finish(&mut self) -> Result<()> {
-        self.writer.finish().map_err(Into::into)
+        self.writer.finish()?;
+        self.writer.get_mut().finish()
     }
 }
 
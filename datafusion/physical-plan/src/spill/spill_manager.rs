@@ -46,6 +46,9 @@ pub struct SpillManager {
     batch_read_buffer_capacity: usize,
     /// general-purpose compression options
     pub(crate) compression: SpillCompression,
+    /// Whether the spill files are created in local directories, see
+    /// [`Self::with_local_files`]
+    local_files: bool,
 }
 
 impl SpillManager {
@@ -56,6 +59,7 @@ impl SpillManager {
             schema,
             batch_read_buffer_capacity: 2,
             compression: SpillCompression::default(),
+            local_files: false,
         }
     }
 
@@ -64,6 +68,19 @@ impl SpillManager {
         self
     }
 
+    /// Creates the spill files in the local directories of the disk manager,
+    /// even if a [`SpillStorage`] is configured
+    ///
+    /// This is needed by the operators reading the spill files on their async
+    /// tasks instead of with [`Self::read_spill_as_stream`], as the readers of
+    /// the other storages may block to fetch the data.
+    ///
+    /// [`SpillStorage`]: datafusion_execution::spill_storage::SpillStorage
+    pub fn with_local_files(mut self, local_files: bool) -> Self {
+        self.local_files = local_files;
+        self
+    }
+
     /// Creates a temporary file for in-progress operations, returning an error
     /// message if file creation fails. The file can be used to append batches
     /// incrementally and then finish the file when done.
@@ -71,7 +88,11 @@ impl SpillManager {
         &self,
         request_msg: &str,
     ) -> Result<InProgressSpillFile> {
-        let temp_file = self.env.disk_manager.create_tmp_file(request_msg)?;
+        let temp_file = if self.local_files {
+            self.env.disk_manager.create_local_tmp_file(request_msg)?
+        } else {
+            self.env.disk_manager.create_tmp_file(request_msg)?
+        };
         Ok(InProgressSpillFile::new(Arc::new(self.clone()), temp_file))
     }
 
@@ -131,10 +152,21 @@ impl SpillManager {
     pub fn read_spill_as_stream(
         &self,
         spill_file_path: RefCountedTempFile,
+    ) -> Result<SendableRecordBatchStream> {
+        self.read_shared_spill_as_stream(Arc::new(spill_file_path))
+    }
+
+    /// Reads a spill file as a stream, like [`Self::read_spill_as_stream`],
+    /// for a file that is read several times. The file is kept until the
+    /// last reference to it is dropped.
+    pub fn read_shared_spill_as_stream(
+        &self,
+        spill_file_path: Arc<RefCountedTempFile>,
     ) -> Result<SendableRecordBatchStream> {
         let stream = Box::pin(cooperative(SpillReaderStream::new(
             Arc::clone(&self.schema),
             spill_file_path,
+            self.metrics.clone(),
         )));
 
         Ok(spawn_buffered(stream, self.batch_read_buffer_capacity))
@@ -22,7 +22,7 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use super::{DisplayAs, DisplayFormatType, PlanProperties};
-use crate::coop::make_cooperative;
+use crate::coop::make_cooperative_with_cancellation;
 use crate::display::{display_orderings, ProjectSchemaDisplay};
 use crate::execution_plan::{Boundedness, EmissionType, SchedulingType};
 use crate::limit::LimitStream;
@@ -274,7 +274,10 @@ impl ExecutionPlan for StreamingTableExec {
             )),
             None => stream,
         };
-        let stream = make_cooperative(projected_stream);
+        let stream = make_cooperative_with_cancellation(
+            projected_stream,
+            ctx.cancellation_token().clone(),
+        );
 
         Ok(match self.limit {
             None => stream,
@@ -45,6 +45,10 @@ use datafusion_physical_expr::{
 };
 use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 
+mod partitioned;
+
+pub(crate) use partitioned::PartitionedTopK;
+
 /// Global TopK
 ///
 /// # Background
@@ -0,0 +1,612 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Partitioned TopK: the first rows of each partition of a `ROW_NUMBER` or
+//! `RANK` window
+
+use std::collections::BinaryHeap;
+use std::mem::size_of;
+use std::sync::Arc;
+
+use super::{build_sort_fields, RecordBatchEntry, RecordBatchStore, TopKRow};
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, SpillMetrics};
+use crate::sorts::streaming_merge::StreamingMergeBuilder;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::windows::PartitionedTopKFunction;
+use crate::SendableRecordBatchStream;
+
+use arrow::array::{ArrayRef, RecordBatch, UInt32Array, UInt64Array};
+use arrow::compute::{interleave_record_batch, take_record_batch};
+use arrow::datatypes::SchemaRef;
+use arrow::row::{OwnedRow, RowConverter, Rows};
+use datafusion_common::config::SpillCompression;
+use datafusion_common::{HashMap, Result};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::runtime_env::RuntimeEnv;
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
+use futures::{stream, StreamExt};
+use log::debug;
+
+/// Partitioned TopK
+///
+/// Keeps the rows of each partition of the input (as defined by the
+/// `PARTITION BY` expressions of a window) whose `ROW_NUMBER` or `RANK`
+/// (as defined by the `ORDER BY` expressions of the window) is at most `k`,
+/// for queries such as:
+///
+/// ```sql
+/// SELECT * FROM (
+///   SELECT *, row_number() OVER (PARTITION BY k ORDER BY ts DESC) rn FROM t
+/// ) WHERE rn <= 3
+/// ```
+///
+/// Instead of sorting the whole input, a heap of at most `k` rows (more for
+/// `RANK` if the order keys have ties) is kept for each partition. If the
+/// memory reservation can not grow, the heaps are sorted and spilled to
+/// disk, and the spilled runs are merged when the input is exhausted.
+///
+/// The output is sorted by the partition keys and the order keys, and has
+/// an additional `UInt64` column with the `ROW_NUMBER` or `RANK` of the rows.
+pub(crate) struct PartitionedTopK {
+    /// Schema of the input
+    schema: SchemaRef,
+    /// Schema of the output: the input and the row number / rank
+    output_schema: SchemaRef,
+    /// The `PARTITION BY` expressions and the order of the partitions in the output
+    partition_by: LexOrdering,
+    /// The `ORDER BY` expressions
+    order_by: LexOrdering,
+    /// The window function numbering the rows
+    function: PartitionedTopKFunction,
+    /// The maximum row number / rank to emit
+    k: usize,
+    /// The target number of rows for output batches
+    batch_size: usize,
+    /// Row converter for the partition keys
+    partition_converter: RowConverter,
+    /// Row converter for the order keys
+    order_converter: RowConverter,
+    /// The heaps of the partitions
+    heap: PartitionedTopKHeap,
+    /// Runtime metrics
+    metrics: PartitionedTopKMetrics,
+    /// Reservation for the heaps
+    reservation: MemoryReservation,
+    /// A handle to the runtime to get spill files
+    runtime: Arc<RuntimeEnv>,
+    spill_manager: SpillManager,
+    /// The sorted runs of the heaps spilled to disk
+    spills: Vec<RefCountedTempFile>,
+}
+
+impl PartitionedTopK {
+    /// Create a new [`PartitionedTopK`] that stores the rows of each
+    /// partition of the input which are numbered at most `k` by `function`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        partition_id: usize,
+        schema: SchemaRef,
+        output_schema: SchemaRef,
+        partition_by: LexOrdering,
+        order_by: LexOrdering,
+        function: PartitionedTopKFunction,
+        k: usize,
+        batch_size: usize,
+        spill_compression: SpillCompression,
+        runtime: Arc<RuntimeEnv>,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Result<Self> {
+        let reservation = MemoryConsumer::new(format!("PartitionedTopK[{partition_id}]"))
+            .with_can_spill(true)
+            .register(&runtime.memory_pool);
+        let partition_converter =
+            RowConverter::new(build_sort_fields(&partition_by, &schema)?)?;
+        let order_converter = RowConverter::new(build_sort_fields(&order_by, &schema)?)?;
+        let metrics = PartitionedTopKMetrics::new(metrics, partition_id);
+        let spill_manager = SpillManager::new(
+            Arc::clone(&runtime),
+            metrics.spill_metrics.clone(),
+            Arc::clone(&schema),
+        )
+        .with_compression_type(spill_compression);
+
+        Ok(Self {
+            schema,
+            output_schema,
+            partition_by,
+            order_by,
+            function,
+            k,
+            batch_size,
+            partition_converter,
+            order_converter,
+            heap: PartitionedTopKHeap::new(k, function, batch_size),
+            metrics,
+            reservation,
+            runtime,
+            spill_manager,
+            spills: vec![],
+        })
+    }
+
+    /// Insert `batch`, remembering the rows that are among the first k rows
+    /// of their partitions seen so far
+    pub fn insert_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        // Updates on drop
+        let baseline = self.metrics.baseline.clone();
+        let _timer = baseline.elapsed_compute().timer();
+
+        let partition_rows =
+            convert_rows(&self.partition_converter, &self.partition_by, &batch)?;
+        let order_rows = convert_rows(&self.order_converter, &self.order_by, &batch)?;
+
+        let mut batch_entry = self.heap.register_batch(batch);
+        for (index, (partition, order)) in
+            partition_rows.iter().zip(order_rows.iter()).enumerate()
+        {
+            self.heap
+                .add(&mut batch_entry, partition.as_ref(), order.as_ref(), index);
+        }
+        self.heap.insert_batch_entry(batch_entry);
+
+        // conserve memory
+        self.heap.maybe_compact()?;
+
+        if self.reservation.try_resize(self.size()).is_err() {
+            // release the unused rows of the stored batches, and spill if
+            // the used rows do not fit either
+            self.heap.compact()?;
+            if let Err(e) = self.reservation.try_resize(self.size()) {
+                if !self.runtime.disk_manager.tmp_files_enabled() {
+                    return Err(e);
+                }
+                self.spill()?;
+                self.reservation.try_resize(self.size())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the sorted contents of the heaps to a spill file, and clears them
+    fn spill(&mut self) -> Result<()> {
+        let Some(batch) = self.heap.emit()? else {
+            return Ok(());
+        };
+        debug!("Spilling the heaps of PartitionedTopK to disk");
+        if let Some(spill) = self.spill_manager.spill_record_batch_by_size(
+            &batch,
+            "PartitionedTopK",
+            self.batch_size,
+        )? {
+            self.spills.push(spill);
+        }
+        Ok(())
+    }
+
+    /// Returns the rows numbered at most k in each partition, sorted by the
+    /// partition keys and the order keys, with their row number / rank
+    pub fn emit(mut self) -> Result<SendableRecordBatchStream> {
+        let sorted = if self.spills.is_empty() {
+            let _timer = self.metrics.baseline.elapsed_compute().timer();
+            let batches = match self.heap.emit()? {
+                Some(batch) => split_batch(batch, self.batch_size),
+                None => vec![],
+            };
+            Box::pin(RecordBatchStreamAdapter::new(
+                Arc::clone(&self.schema),
+                stream::iter(batches.into_iter().map(Ok)),
+            ))
+        } else {
+            self.spill()?;
+            self.reservation.free();
+            let streams = std::mem::take(&mut self.spills)
+                .into_iter()
+                .map(|spill| self.spill_manager.read_spill_as_stream(spill))
+                .collect::<Result<Vec<_>>>()?;
+            let mut expressions = self.partition_by.clone();
+            expressions.extend(self.order_by.iter().cloned());
+            StreamingMergeBuilder::new()
+                .with_streams(streams)
+                .with_schema(Arc::clone(&self.schema))
+                .with_expressions(&expressions)
+                .with_metrics(self.metrics.baseline.intermediate())
+                .with_batch_size(self.batch_size)
+                .with_fetch(None)
+                .with_reservation(self.reservation.new_empty())
+                .build()?
+        };
+
+        let mut numbering = RowNumbering {
+            schema: Arc::clone(&self.output_schema),
+            partition_converter: RowConverter::new(build_sort_fields(
+                &self.partition_by,
+                &self.schema,
+            )?)?,
+            order_converter: RowConverter::new(build_sort_fields(
+                &self.order_by,
+                &self.schema,
+            )?)?,
+            partition_by: self.partition_by,
+            order_by: self.order_by,
+            function: self.function,
+            k: self.k as u64,
+            last_keys: None,
+            row_number: 0,
+            rank: 0,
+            baseline: self.metrics.baseline,
+        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.output_schema),
+            sorted.map(move |batch| numbering.number(batch?)),
+        )))
+    }
+
+    /// return the size of memory used by this operator, in bytes
+    fn size(&self) -> usize {
+        size_of::<Self>()
+            + self.partition_converter.size()
+            + self.order_converter.size()
+            + self.heap.size()
+    }
+}
+
+struct PartitionedTopKMetrics {
+    /// metrics
+    pub baseline: BaselineMetrics,
+
+    pub spill_metrics: SpillMetrics,
+}
+
+impl PartitionedTopKMetrics {
+    fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            baseline: BaselineMetrics::new(metrics, partition),
+            spill_metrics: SpillMetrics::new(metrics, partition),
+        }
+    }
+}
+
+/// Evaluates `exprs` on `batch` and converts the results to [`Rows`]
+fn convert_rows(
+    converter: &RowConverter,
+    exprs: &[PhysicalSortExpr],
+    batch: &RecordBatch,
+) -> Result<Rows> {
+    let columns = exprs
+        .iter()
+        .map(|expr| expr.expr.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(converter.convert_columns(&columns)?)
+}
+
+/// Breaks `batch` into `batch_size` batches
+fn split_batch(batch: RecordBatch, batch_size: usize) -> Vec<RecordBatch> {
+    (0..batch.num_rows())
+        .step_by(batch_size)
+        .map(|offset| batch.slice(offset, batch_size.min(batch.num_rows() - offset)))
+        .collect()
+}
+
+/// The heaps of the partitions of a [`PartitionedTopK`].
+///
+/// Like `TopKHeap`, the heaps keep the *smallest* rows according to the
+/// [arrow::row] format of the order keys, and the row values are stored in
+/// a [`RecordBatchStore`] shared by all the partitions.
+struct PartitionedTopKHeap {
+    /// The maximum row number / rank to keep
+    k: usize,
+    /// The window function numbering the rows
+    function: PartitionedTopKFunction,
+    /// The target number of rows for output batches
+    batch_size: usize,
+    /// The heap of the order keys of each partition, by partition key
+    groups: HashMap<Vec<u8>, BinaryHeap<TopKRow>>,
+    /// Storage the original row values (TopKRow only has the order key)
+    store: RecordBatchStore,
+    /// The number of rows in all the heaps
+    num_rows: usize,
+    /// The size of all owned data held by the heaps
+    owned_bytes: usize,
+}
+
+impl PartitionedTopKHeap {
+    fn new(k: usize, function: PartitionedTopKFunction, batch_size: usize) -> Self {
+        assert!(k > 0);
+        Self {
+            k,
+            function,
+            batch_size,
+            groups: HashMap::new(),
+            store: RecordBatchStore::new(),
+            num_rows: 0,
+            owned_bytes: 0,
+        }
+    }
+
+    /// Register a [`RecordBatch`] with the heaps, returning the
+    /// appropriate entry
+    fn register_batch(&mut self, batch: RecordBatch) -> RecordBatchEntry {
+        self.store.register(batch)
+    }
+
+    /// Insert a [`RecordBatchEntry`] created by a previous call to
+    /// [`Self::register_batch`] into storage.
+    fn insert_batch_entry(&mut self, entry: RecordBatchEntry) {
+        self.store.insert(entry)
+    }
+
+    /// Adds the row with order key `row` to the heap of partition `partition`
+    /// if it is among the first k rows of the partition, removing the rows
+    /// that are no longer among them.
+    fn add(
+        &mut self,
+        batch_entry: &mut RecordBatchEntry,
+        partition: &[u8],
+        row: &[u8],
+        index: usize,
+    ) {
+        if !self.groups.contains_key(partition) {
+            self.owned_bytes += partition.len();
+            self.groups.insert(partition.to_vec(), BinaryHeap::new());
+        }
+        let Self {
+            k,
+            function,
+            groups,
+            store,
+            num_rows,
+            owned_bytes,
+            ..
+        } = self;
+        let k = *k;
+        let heap = groups.get_mut(partition).unwrap();
+
+        match function {
+            PartitionedTopKFunction::RowNumber => {
+                let new_row = if heap.len() < k {
+                    TopKRow::new(row, batch_entry.id, index)
+                } else if row < heap.peek().unwrap().row() {
+                    // Reuse the storage of the evicted row
+                    let prev_max = heap.pop().unwrap();
+                    release(store, batch_entry, &prev_max);
+                    *num_rows -= 1;
+                    *owned_bytes -= prev_max.owned_size();
+                    prev_max.with_new_row(row, batch_entry.id, index)
+                } else {
+                    return;
+                };
+                batch_entry.uses += 1;
+                *num_rows += 1;
+                *owned_bytes += new_row.owned_size();
+                heap.push(new_row);
+            }
+            PartitionedTopKFunction::Rank => {
+                // Rows tied with the current max have the same rank and are kept
+                if heap.len() >= k && row > heap.peek().unwrap().row() {
+                    return;
+                }
+                let new_row = TopKRow::new(row, batch_entry.id, index);
+                batch_entry.uses += 1;
+                *num_rows += 1;
+                *owned_bytes += new_row.owned_size();
+                heap.push(new_row);
+
+                // The rows tied with the max have a rank greater than k if
+                // there are at least k smaller rows
+                while heap.len() > k {
+                    let mut ties = vec![heap.pop().unwrap()];
+                    while heap.peek().is_some_and(|r| r.row() == ties[0].row()) {
+                        ties.push(heap.pop().unwrap());
+                    }
+                    if heap.len() < k {
+                        heap.extend(ties);
+                        break;
+                    }
+                    for row in &ties {
+                        release(store, batch_entry, row);
+                        *num_rows -= 1;
+                        *owned_bytes -= row.owned_size();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the rows stored in the heaps, sorted by partition key and
+    /// order key, as a single [`RecordBatch`], clearing the heaps
+    fn emit(&mut self) -> Result<Option<RecordBatch>> {
+        let mut groups = std::mem::take(&mut self.groups)
+            .into_iter()
+            .collect::<Vec<_>>();
+        groups.sort_unstable_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        let rows = groups
+            .into_iter()
+            .flat_map(|(_, heap)| heap.into_sorted_vec())
+            .collect::<Vec<_>>();
+        let batch = self.interleave(&rows.iter().collect::<Vec<_>>())?;
+
+        self.store.clear();
+        self.num_rows = 0;
+        self.owned_bytes = 0;
+        Ok(batch)
+    }
+
+    /// Compact the heaps, rewriting all stored batches into a single
+    /// batch, if the stored batches hold too many unused rows
+    fn maybe_compact(&mut self) -> Result<()> {
+        // Target holding up to around 20 batches of unused rows, but handle
+        // cases of many partitions where most batches are partially used
+        let max_unused_rows = (20 * self.batch_size) + self.num_rows;
+        if self.store.len() <= 2 || self.store.unused_rows() < max_unused_rows {
+            return Ok(());
+        }
+        self.compact()
+    }
+
+    /// Compact the heaps, rewriting all stored batches into a single batch
+    fn compact(&mut self) -> Result<()> {
+        let mut heaps = self
+            .groups
+            .values_mut()
+            .map(|heap| std::mem::take(heap).into_vec())
+            .collect::<Vec<_>>();
+        let rows = heaps.iter().flatten().collect::<Vec<_>>();
+        let Some(new_batch) = self.interleave(&rows)? else {
+            return Ok(());
+        };
+
+        // clear all old entries in store (this invalidates all
+        // store_ids in the heaps)
+        self.store.clear();
+        let mut batch_entry = self.register_batch(new_batch);
+        batch_entry.uses = self.num_rows;
+
+        // rewrite all existing entries to use the new batch, in the order
+        // they were interleaved
+        let mut index = 0;
+        for (heap, rows) in self.groups.values_mut().zip(heaps.iter_mut()) {
+            for row in rows.iter_mut() {
+                row.batch_id = batch_entry.id;
+                row.index = index;
+                index += 1;
+            }
+            *heap = BinaryHeap::from(std::mem::take(rows));
+        }
+        self.insert_batch_entry(batch_entry);
+        Ok(())
+    }
+
+    /// Returns the values of `rows` as a single [`RecordBatch`]
+    fn interleave(&self, rows: &[&TopKRow]) -> Result<Option<RecordBatch>> {
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let indices = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| (i, row.index))
+            .collect::<Vec<_>>();
+        let batches = rows
+            .iter()
+            .map(|row| {
+                let entry = self
+                    .store
+                    .get(row.batch_id)
+                    .expect("invalid stored batch id");
+                &entry.batch
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(interleave_record_batch(&batches, &indices)?))
+    }
+
+    /// return the size of memory used by the heaps, in bytes
+    fn size(&self) -> usize {
+        size_of::<Self>()
+            + self.groups.capacity()
+                * (size_of::<Vec<u8>>() + size_of::<BinaryHeap<TopKRow>>())
+            + self.num_rows * size_of::<TopKRow>()
+            + self.store.size()
+            + self.owned_bytes
+    }
+}
+
+/// Removes a use of the batch of `row`, which is either `batch_entry` or a
+/// batch of `store`
+fn release(
+    store: &mut RecordBatchStore,
+    batch_entry: &mut RecordBatchEntry,
+    row: &TopKRow,
+) {
+    if row.batch_id == batch_entry.id {
+        batch_entry.uses -= 1;
+    } else {
+        store.unuse(row.batch_id);
+    }
+}
+
+/// Numbers the rows of a stream sorted by the partition keys and the order
+/// keys, keeping the rows numbered at most k
+struct RowNumbering {
+    /// Schema of the output: the input and the row number / rank
+    schema: SchemaRef,
+    partition_converter: RowConverter,
+    order_converter: RowConverter,
+    partition_by: LexOrdering,
+    order_by: LexOrdering,
+    function: PartitionedTopKFunction,
+    k: u64,
+    /// The partition key and order key of the last row
+    last_keys: Option<(OwnedRow, OwnedRow)>,
+    /// The row number of the last row in its partition
+    row_number: u64,
+    /// The rank of the last row in its partition
+    rank: u64,
+    baseline: BaselineMetrics,
+}
+
+impl RowNumbering {
+    fn number(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let _timer = self.baseline.elapsed_compute().timer();
+
+        let partition_rows =
+            convert_rows(&self.partition_converter, &self.partition_by, &batch)?;
+        let order_rows = convert_rows(&self.order_converter, &self.order_by, &batch)?;
+
+        let mut indices = vec![];
+        let mut numbers = vec![];
+        for (index, (partition, order)) in
+            partition_rows.iter().zip(order_rows.iter()).enumerate()
+        {
+            match &mut self.last_keys {
+                Some((last_partition, last_order))
+                    if last_partition.row() == partition =>
+                {
+                    self.row_number += 1;
+                    if last_order.row() != order {
+                        self.rank = self.row_number;
+                        *last_order = order.owned();
+                    }
+                }
+                _ => {
+                    self.row_number = 1;
+                    self.rank = 1;
+                    self.last_keys = Some((partition.owned(), order.owned()));
+                }
+            }
+            let number = match self.function {
+                PartitionedTopKFunction::RowNumber => self.row_number,
+                PartitionedTopKFunction::Rank => self.rank,
+            };
+            if number <= self.k {
+                indices.push(index as u32);
+                numbers.push(number);
+            }
+        }
+
+        let batch = take_record_batch(&batch, &UInt32Array::from(indices))?;
+        let mut columns = batch.columns().to_vec();
+        columns.push(Arc::new(UInt64Array::from(numbers)));
+        let batch = RecordBatch::try_new(Arc::clone(&self.schema), columns)?;
+        self.baseline.record_output(batch.num_rows());
+        Ok(batch)
+    }
+}
@@ -26,6 +26,7 @@ use super::metrics::{
     RecordOutput,
 };
 use super::{DisplayAs, ExecutionPlanProperties, PlanProperties};
+use crate::spill::get_record_batch_memory_size;
 use crate::{
     DisplayFormatType, Distribution, ExecutionPlan, RecordBatchStream,
     SendableRecordBatchStream,
@@ -45,6 +46,7 @@ use async_trait::async_trait;
 use datafusion_common::{
     exec_datafusion_err, exec_err, internal_err, HashMap, HashSet, Result, UnnestOptions,
 };
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::EquivalenceProperties;
 use futures::{Stream, StreamExt};
@@ -185,8 +187,10 @@ impl ExecutionPlan for UnnestExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
         let metrics = UnnestMetrics::new(partition, &self.metrics);
+        let reservation = MemoryConsumer::new(format!("UnnestExec[{partition}]"))
+            .register(context.memory_pool());
 
         Ok(Box::pin(UnnestStream {
             input,
@@ -195,6 +199,7 @@ impl ExecutionPlan for UnnestExec {
             struct_column_indices: self.struct_column_indices.iter().copied().collect(),
             options: self.options.clone(),
             metrics,
+            reservation,
         }))
     }
 
@@ -249,6 +254,10 @@ struct UnnestStream {
     options: UnnestOptions,
     /// Metrics
     metrics: UnnestMetrics,
+    /// Memory reservation for the last output batch, whose columns are all
+    /// new allocations. It is held until the consumer polls the next batch, as
+    /// the batch is then accounted for by the consumer if it keeps it
+    reservation: MemoryReservation,
 }
 
 impl RecordBatchStream for UnnestStream {
@@ -276,6 +285,7 @@ impl UnnestStream {
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
+        self.reservation.free();
         loop {
             return Poll::Ready(match ready!(self.input.poll_next_unpin(cx)) {
                 Some(Ok(batch)) => {
@@ -295,6 +305,8 @@ impl UnnestStream {
                     let Some(result_batch) = result else {
                         continue;
                     };
+                    self.reservation
+                        .try_grow(get_record_batch_memory_size(&result_batch))?;
                     self.metrics.output_batches.add(1);
                     (&result_batch).record_output(&self.metrics.baseline_metrics);
 
@@ -315,13 +315,9 @@ mod tests {
             Statistics {
                 num_rows: Precision::Exact(rows),
                 total_byte_size: Precision::Exact(8), // not important
-                column_statistics: vec![ColumnStatistics {
-                    null_count: Precision::Exact(rows), // there are only nulls
-                    distinct_count: Precision::Absent,
-                    max_value: Precision::Absent,
-                    min_value: Precision::Absent,
-                    sum_value: Precision::Absent,
-                },],
+                column_statistics: vec![ColumnStatistics::new_unknown()
+                    // there are only nulls
+                    .with_null_count(Precision::Exact(rows))],
             }
         );
 
@@ -18,6 +18,7 @@
 //! Physical expressions for window functions
 
 mod bounded_window_agg_exec;
+mod partitioned_topk;
 mod utils;
 mod window_agg_exec;
 
@@ -56,6 +57,7 @@ pub use bounded_window_agg_exec::BoundedWindowAggExec;
 pub use datafusion_physical_expr::window::{
     PlainAggregateWindowExpr, StandardWindowExpr, WindowExpr,
 };
+pub use partitioned_topk::{PartitionedTopKExec, PartitionedTopKFunction};
 pub use window_agg_exec::WindowAggExec;
 
 /// Build field from window function and add it into schema
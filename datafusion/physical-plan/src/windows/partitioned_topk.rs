@@ -0,0 +1,478 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the [`PartitionedTopKExec`] operator, computing the first rows of
+//! each partition of a `ROW_NUMBER` or `RANK` window.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::execution_plan::{Boundedness, CardinalityEffect, EmissionType};
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::stream::RecordBatchStreamAdapter;
+use crate::topk::PartitionedTopK;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, SendableRecordBatchStream,
+};
+
+use arrow::datatypes::{FieldRef, Schema, SchemaRef};
+use datafusion_common::{plan_err, Result};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::EquivalenceProperties;
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
+
+use futures::{StreamExt, TryStreamExt};
+
+/// The window functions numbering the rows of a [`PartitionedTopKExec`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTopKFunction {
+    /// `ROW_NUMBER()`: the position of the row in its partition
+    RowNumber,
+    /// `RANK()`: the position of the first row of the partition with the
+    /// same order keys as the row
+    Rank,
+}
+
+impl fmt::Display for PartitionedTopKFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RowNumber => write!(f, "row_number"),
+            Self::Rank => write!(f, "rank"),
+        }
+    }
+}
+
+/// Computes the rows of each partition of a `ROW_NUMBER` or `RANK` window
+/// whose row number / rank is at most `fetch`, with a bounded number of rows
+/// per partition.
+///
+/// This replaces a window and the filter on its result for queries such as:
+///
+/// ```sql
+/// SELECT * FROM (
+///   SELECT *, row_number() OVER (PARTITION BY k ORDER BY ts DESC) rn FROM t
+/// ) WHERE rn <= 3
+/// ```
+///
+/// which would otherwise sort the whole input, and compute the window for
+/// all the rows before filtering them.
+///
+/// The output has the columns of the input followed by the `UInt64` row
+/// number / rank, and is sorted by the partition keys and the order keys
+/// within each output partition. Like the windows, the input must be hash
+/// partitioned by the partition keys.
+#[derive(Debug, Clone)]
+pub struct PartitionedTopKExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The `PARTITION BY` expressions, and the order of the partitions in the output
+    partition_by: LexOrdering,
+    /// The `ORDER BY` expressions
+    order_by: LexOrdering,
+    /// The window function numbering the rows
+    function: PartitionedTopKFunction,
+    /// The maximum row number / rank of the output rows
+    fetch: usize,
+    /// Schema of the output: the input and the row number / rank
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl PartitionedTopKExec {
+    /// Create a new [`PartitionedTopKExec`], returning the rows of the
+    /// partitions of `input` numbered at most `fetch` by `function`, with
+    /// the row number / rank in `field`.
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        partition_by: LexOrdering,
+        order_by: LexOrdering,
+        function: PartitionedTopKFunction,
+        fetch: usize,
+        field: FieldRef,
+    ) -> Result<Self> {
+        if fetch == 0 {
+            return plan_err!("PartitionedTopKExec requires a positive fetch");
+        }
+        let input_schema = input.schema();
+        let mut fields = input_schema.fields().to_vec();
+        fields.push(field);
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        let cache = Self::compute_properties(
+            Arc::clone(&schema),
+            &input,
+            &partition_by,
+            &order_by,
+        )?;
+        Ok(Self {
+            input,
+            partition_by,
+            order_by,
+            function,
+            fetch,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The `PARTITION BY` expressions
+    pub fn partition_by(&self) -> &LexOrdering {
+        &self.partition_by
+    }
+
+    /// The `ORDER BY` expressions
+    pub fn order_by(&self) -> &LexOrdering {
+        &self.order_by
+    }
+
+    /// The window function numbering the rows
+    pub fn function(&self) -> PartitionedTopKFunction {
+        self.function
+    }
+
+    /// The maximum row number / rank of the output rows
+    pub fn fetch(&self) -> usize {
+        self.fetch
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        schema: SchemaRef,
+        input: &Arc<dyn ExecutionPlan>,
+        partition_by: &LexOrdering,
+        order_by: &LexOrdering,
+    ) -> Result<PlanProperties> {
+        // The output is sorted by the partition keys and the order keys, and
+        // by the partition keys and the row number / rank
+        let number_index = schema.fields().len() - 1;
+        let number = Column::new(schema.field(number_index).name(), number_index);
+        let mut eq_properties = EquivalenceProperties::new(schema)
+            .extend(input.equivalence_properties().clone())?;
+        let mut ordering = partition_by.clone();
+        ordering.extend(order_by.iter().cloned());
+        eq_properties.reorder(ordering)?;
+        let mut ordering = partition_by.clone();
+        ordering.push(PhysicalSortExpr::new_default(Arc::new(number)));
+        eq_properties.add_ordering(ordering);
+
+        let boundedness = match input.boundedness() {
+            Boundedness::Unbounded { .. } => Boundedness::Unbounded {
+                requires_infinite_memory: true,
+            },
+            bounded => bounded,
+        };
+
+        Ok(PlanProperties::new(
+            eq_properties,
+            input.output_partitioning().clone(),
+            EmissionType::Final,
+            boundedness,
+        ))
+    }
+}
+
+impl DisplayAs for PartitionedTopKExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "PartitionedTopKExec: fun={}, fetch={}, partition_by=[{}], order_by=[{}]",
+                    self.function, self.fetch, self.partition_by, self.order_by
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "fun={}", self.function)?;
+                writeln!(f, "fetch={}", self.fetch)?;
+                writeln!(f, "partition_by={}", self.partition_by)?;
+                writeln!(f, "order_by={}", self.order_by)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for PartitionedTopKExec {
+    fn name(&self) -> &'static str {
+        "PartitionedTopKExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::HashPartitioned(
+            self.partition_by
+                .iter()
+                .map(|sort_expr| Arc::clone(&sort_expr.expr))
+                .collect(),
+        )]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let field = Arc::clone(self.schema.fields().last().unwrap());
+        Ok(Arc::new(PartitionedTopKExec::try_new(
+            Arc::clone(&children[0]),
+            self.partition_by.clone(),
+            self.order_by.clone(),
+            self.function,
+            self.fetch,
+            field,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut input = self.input.execute(partition, Arc::clone(&context))?;
+        let mut topk = PartitionedTopK::try_new(
+            partition,
+            input.schema(),
+            Arc::clone(&self.schema),
+            self.partition_by.clone(),
+            self.order_by.clone(),
+            self.function,
+            self.fetch,
+            context.session_config().batch_size(),
+            context.session_config().spill_compression(),
+            context.runtime_env(),
+            &self.metrics,
+        )?;
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            futures::stream::once(async move {
+                while let Some(batch) = input.next().await {
+                    topk.insert_batch(batch?)?;
+                }
+                topk.emit()
+            })
+            .try_flatten(),
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::LowerEqual
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::expressions::{col, PhysicalSortExpr};
+    use crate::test::TestMemoryExec;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field};
+    use arrow_schema::SortOptions;
+    use datafusion_common::assert_batches_eq;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int32, true),
+            Field::new("ts", DataType::Int32, false),
+        ]))
+    }
+
+    /// Builds batches of `num_rows` rows each, with `k` cycling through
+    /// `num_partitions` values (and a null) and `ts` the row position
+    fn test_batches(
+        num_batches: usize,
+        num_rows: usize,
+        num_partitions: i32,
+    ) -> Result<Vec<RecordBatch>> {
+        (0..num_batches)
+            .map(|batch| {
+                let start = (batch * num_rows) as i32;
+                let ts = (start..start + num_rows as i32).collect::<Vec<_>>();
+                let k = ts
+                    .iter()
+                    .map(|ts| {
+                        (ts % (num_partitions + 1) != 0).then_some(ts % num_partitions)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(RecordBatch::try_new(
+                    test_schema(),
+                    vec![
+                        Arc::new(Int32Array::from(k)),
+                        Arc::new(Int32Array::from(ts)),
+                    ],
+                )?)
+            })
+            .collect()
+    }
+
+    fn partitioned_topk(
+        batches: Vec<RecordBatch>,
+        function: PartitionedTopKFunction,
+        order_by: PhysicalSortExpr,
+        fetch: usize,
+    ) -> Result<Arc<PartitionedTopKExec>> {
+        let schema = test_schema();
+        let input = TestMemoryExec::try_new_exec(&[batches], Arc::clone(&schema), None)?;
+        Ok(Arc::new(PartitionedTopKExec::try_new(
+            input,
+            [PhysicalSortExpr::new_default(col("k", &schema)?)].into(),
+            [order_by].into(),
+            function,
+            fetch,
+            Arc::new(Field::new("rn", DataType::UInt64, false)),
+        )?))
+    }
+
+    #[tokio::test]
+    async fn test_row_number() -> Result<()> {
+        let batches = test_batches(3, 5, 3)?;
+        let order_by = PhysicalSortExpr::new(
+            col("ts", &test_schema())?,
+            SortOptions::new(true, false),
+        );
+        let exec =
+            partitioned_topk(batches, PartitionedTopKFunction::RowNumber, order_by, 2)?;
+        let result = collect(exec, Arc::new(TaskContext::default())).await?;
+        assert_batches_eq!(
+            [
+                "+---+----+----+",
+                "| k | ts | rn |",
+                "+---+----+----+",
+                "|   | 12 | 1  |",
+                "|   | 8  | 2  |",
+                "| 0 | 9  | 1  |",
+                "| 0 | 6  | 2  |",
+                "| 1 | 13 | 1  |",
+                "| 1 | 10 | 2  |",
+                "| 2 | 14 | 1  |",
+                "| 2 | 11 | 2  |",
+                "+---+----+----+",
+            ],
+            &result
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rank_with_ties() -> Result<()> {
+        let schema = test_schema();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 1, 1, 1, 2, 2, 2])),
+                Arc::new(Int32Array::from(vec![5, 3, 3, 4, 1, 7, 7, 7])),
+            ],
+        )?;
+        let order_by = PhysicalSortExpr::new_default(col("ts", &schema)?);
+        let exec = partitioned_topk(
+            vec![batch.slice(0, 3), batch.slice(3, 5)],
+            PartitionedTopKFunction::Rank,
+            order_by,
+            2,
+        )?;
+        let result = collect(exec, Arc::new(TaskContext::default())).await?;
+        assert_batches_eq!(
+            [
+                "+---+----+----+",
+                "| k | ts | rn |",
+                "+---+----+----+",
+                "| 1 | 1  | 1  |",
+                "| 1 | 3  | 2  |",
+                "| 1 | 3  | 2  |",
+                "| 2 | 7  | 1  |",
+                "| 2 | 7  | 1  |",
+                "| 2 | 7  | 1  |",
+                "+---+----+----+",
+            ],
+            &result
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spill() -> Result<()> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(192 * 1024, 1.0)
+            .build_arc()?;
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(SessionConfig::new().with_batch_size(100))
+                .with_runtime(runtime),
+        );
+
+        // 1000 partitions of 10 rows
+        let batches = test_batches(100, 100, 1000)?;
+        let order_by = PhysicalSortExpr::new_default(col("ts", &test_schema())?);
+        let exec =
+            partitioned_topk(batches, PartitionedTopKFunction::RowNumber, order_by, 3)?;
+        let result = collect(Arc::clone(&exec) as _, task_ctx).await?;
+
+        let metrics = exec.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert_eq!(metrics.output_rows().unwrap(), 3 * 1001);
+
+        // The first 3 rows of each partition
+        let rows = result.iter().map(|batch| batch.num_rows()).sum::<usize>();
+        assert_eq!(rows, 3 * 1001);
+        let expected = test_batches(100, 100, 1000)?;
+        let expected = collect(
+            partitioned_topk(
+                expected,
+                PartitionedTopKFunction::RowNumber,
+                PhysicalSortExpr::new_default(col("ts", &test_schema())?),
+                3,
+            )?,
+            Arc::new(TaskContext::default()),
+        )
+        .await?;
+        assert_eq!(
+            arrow::compute::concat_batches(&exec.schema(), &result)?,
+            arrow::compute::concat_batches(&exec.schema(), &expected)?
+        );
+        Ok(())
+    }
+}
@@ -197,7 +197,7 @@ impl ExecutionPlan for WorkTableExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         // WorkTable streams must be the plan base.
         if partition != 0 {
@@ -210,7 +210,9 @@ impl ExecutionPlan for WorkTableExec {
         let stream =
             MemoryStream::try_new(batch.batches, Arc::clone(&self.schema), None)?
                 .with_reservation(batch.reservation);
-        Ok(Box::pin(cooperative(stream)))
+        Ok(Box::pin(cooperative(stream).with_cancellation_token(
+            context.cancellation_token().clone(),
+        )))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -654,33 +654,32 @@ impl From<protobuf::Constraint> for Constraint {
 
 impl From<&protobuf::ColumnStats> for ColumnStatistics {
     fn from(cs: &protobuf::ColumnStats) -> ColumnStatistics {
-        ColumnStatistics {
-            null_count: if let Some(nc) = &cs.null_count {
+        ColumnStatistics::new_unknown()
+            .with_null_count(if let Some(nc) = &cs.null_count {
                 nc.clone().into()
             } else {
                 Precision::Absent
-            },
-            max_value: if let Some(max) = &cs.max_value {
+            })
+            .with_max_value(if let Some(max) = &cs.max_value {
                 max.clone().into()
             } else {
                 Precision::Absent
-            },
-            min_value: if let Some(min) = &cs.min_value {
+            })
+            .with_min_value(if let Some(min) = &cs.min_value {
                 min.clone().into()
             } else {
                 Precision::Absent
-            },
-            sum_value: if let Some(sum) = &cs.sum_value {
+            })
+            .with_sum_value(if let Some(sum) = &cs.sum_value {
                 sum.clone().into()
             } else {
                 Precision::Absent
-            },
-            distinct_count: if let Some(dc) = &cs.distinct_count {
+            })
+            .with_distinct_count(if let Some(dc) = &cs.distinct_count {
                 dc.clone().into()
             } else {
                 Precision::Absent
-            },
-        }
+            })
     }
 }
 
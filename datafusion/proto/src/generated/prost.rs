@@ -5,7 +5,7 @@
 pub struct LogicalPlanNode {
     #[prost(
         oneof = "logical_plan_node::LogicalPlanType",
-        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33"
+        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34"
     )]
     pub logical_plan_type: ::core::option::Option<logical_plan_node::LogicalPlanType>,
 }
@@ -77,6 +77,8 @@ pub mod logical_plan_node {
         CteWorkTableScan(super::CteWorkTableScanNode),
         #[prost(message, tag = "33")]
         Dml(::prost::alloc::boxed::Box<super::DmlNode>),
+        #[prost(message, tag = "34")]
+        SharedSubplan(::prost::alloc::boxed::Box<super::SharedSubplanNode>),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1935,6 +1937,15 @@ pub struct RecursiveQueryNode {
     pub is_distinct: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SharedSubplanNode {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<LogicalPlanNode>>,
+    #[prost(bool, tag = "3")]
+    pub materialized: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CteWorkTableScanNode {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
@@ -71,7 +71,8 @@ use datafusion_expr::{
     Statement, WindowUDF,
 };
 use datafusion_expr::{
-    AggregateUDF, DmlStatement, FetchType, RecursiveQuery, SkipType, TableSource, Unnest,
+    AggregateUDF, DmlStatement, FetchType, RecursiveQuery, SharedSubplan, SkipType,
+    TableSource, Unnest,
 };
 
 use self::to_proto::{serialize_expr, serialize_exprs};
@@ -966,6 +967,13 @@ impl AsLogicalPlan for LogicalPlanNode {
                     Arc::new(into_logical_plan!(dml_node.input, ctx, extension_codec)?),
                 ),
             )),
+            LogicalPlanType::SharedSubplan(shared) => {
+                Ok(LogicalPlan::SharedSubplan(SharedSubplan::new(
+                    shared.id as usize,
+                    Arc::new(into_logical_plan!(shared.input, ctx, extension_codec)?),
+                    shared.materialized,
+                )))
+            }
         }
     }
 
@@ -1778,6 +1786,21 @@ impl AsLogicalPlan for LogicalPlanNode {
                     ))),
                 })
             }
+            LogicalPlan::SharedSubplan(shared) => {
+                let input = LogicalPlanNode::try_from_logical_plan(
+                    shared.input.as_ref(),
+                    extension_codec,
+                )?;
+                Ok(LogicalPlanNode {
+                    logical_plan_type: Some(LogicalPlanType::SharedSubplan(Box::new(
+                        protobuf::SharedSubplanNode {
+                            id: shared.id as u64,
+                            input: Some(Box::new(input)),
+                            materialized: shared.materialized,
+                        },
+                    ))),
+                })
+            }
         }
     }
 }
@@ -2595,6 +2595,23 @@ async fn roundtrip_recursive_query() {
     );
 }
 
+#[tokio::test]
+async fn roundtrip_materialized_cte() -> Result<()> {
+    let query = "WITH cte AS MATERIALIZED (SELECT 1 as n)
+        SELECT * FROM cte UNION ALL SELECT n + 1 FROM cte";
+
+    // `MATERIALIZED` is only parsed by the PostgreSQL dialect
+    let config =
+        SessionConfig::new().set_str("datafusion.sql_parser.dialect", "PostgreSQL");
+    let ctx = SessionContext::new_with_config(config);
+    let plan = ctx.sql(query).await?.into_optimized_plan()?;
+    let bytes = logical_plan_to_bytes(&plan)?;
+    let logical_round_trip = logical_plan_from_bytes(&bytes, &ctx)?;
+    assert_eq!(format!("{plan:?}"), format!("{logical_round_trip:?}"));
+    assert!(format!("{plan}").contains("SharedSubplan: id="), "{plan}");
+    Ok(())
+}
+
 #[tokio::test]
 async fn roundtrip_union_query() -> Result<()> {
     let query = "SELECT a FROM t1
@@ -25,8 +25,8 @@ use datafusion_common::{
     tree_node::{TreeNode, TreeNodeRecursion},
     Result,
 };
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder, TableSource};
-use sqlparser::ast::{Query, SetExpr, SetOperator, With};
+use datafusion_expr::{LogicalPlan, LogicalPlanBuilder, SharedSubplan, TableSource};
+use sqlparser::ast::{CteAsMaterialized, Query, SetExpr, SetOperator, With};
 
 impl<S: ContextProvider> SqlToRel<'_, S> {
     pub(super) fn plan_with_clause(
@@ -54,7 +54,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
             // Each `WITH` block can change the column names in the last
             // projection (e.g. "WITH table(t1, t2) AS SELECT 1, 2").
-            let final_plan = self.apply_table_alias(cte_plan, cte.alias)?;
+            let mut final_plan = self.apply_table_alias(cte_plan, cte.alias)?;
+            // `MATERIALIZED` CTEs are evaluated once for all their references,
+            // and `NOT MATERIALIZED` ones are kept from being shared
+            if let Some(materialized) = cte.materialized {
+                final_plan = LogicalPlan::SharedSubplan(SharedSubplan::new(
+                    planner_context.next_shared_subplan_id(),
+                    Arc::new(final_plan),
+                    matches!(materialized, CteAsMaterialized::Materialized),
+                ));
+            }
             // Export the CTE to the outer query
             planner_context.insert_cte(cte_name, final_plan);
         }
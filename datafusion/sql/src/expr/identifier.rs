@@ -68,8 +68,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 return Ok(Expr::Column(column));
             }
 
-            // Check the outer query schema
-            if let Some(outer) = planner_context.outer_query_schema() {
+            // Check the outer query schemas, from the innermost one
+            for outer in planner_context.outer_query_schemas() {
                 if let Ok((qualifier, field)) =
                     outer.qualified_field_with_unqualified_name(normalize_ident.as_str())
                 {
@@ -164,36 +164,24 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     if ids.len() == 5 {
                         not_impl_err!("compound identifier: {ids:?}")
                     } else {
-                        // Check the outer_query_schema and try to find a match
-                        if let Some(outer) = planner_context.outer_query_schema() {
-                            let search_result = search_dfschema(&ids, outer);
-                            match search_result {
-                                // Found matching field with spare identifier(s) for nested field(s) in structure
-                                Some((field, qualifier, nested_names))
-                                    if !nested_names.is_empty() =>
-                                {
-                                    // TODO: remove when can support nested identifiers for OuterReferenceColumn
-                                    not_impl_err!(
-                                        "Nested identifiers are not yet supported for OuterReferenceColumn {}",
-                                        Column::from((qualifier, field)).quoted_flat_name()
-                                    )
-                                }
-                                // Found matching field with no spare identifier(s)
-                                Some((field, qualifier, _nested_names)) => {
-                                    // Found an exact match on a qualified name in the outer plan schema, so this is an outer reference column
-                                    Ok(Expr::OuterReferenceColumn(
-                                        field.data_type().clone(),
-                                        Column::from((qualifier, field)),
-                                    ))
-                                }
-                                // Found no matching field, will return a default
-                                None => {
-                                    let s = &ids[0..ids.len()];
-                                    // safe unwrap as s can never be empty or exceed the bounds
-                                    let (relation, column_name) =
-                                        form_identifier(s).unwrap();
-                                    Ok(Expr::Column(Column::new(relation, column_name)))
-                                }
+                        // Check the outer query schemas, from the innermost one, and
+                        // try to find a match
+                        let search_result = planner_context
+                            .outer_query_schemas()
+                            .find_map(|outer| search_dfschema(&ids, outer));
+                        if let Some((field, qualifier, nested_names)) = search_result {
+                            if !nested_names.is_empty() {
+                                // TODO: remove when can support nested identifiers for OuterReferenceColumn
+                                not_impl_err!(
+                                    "Nested identifiers are not yet supported for OuterReferenceColumn {}",
+                                    Column::from((qualifier, field)).quoted_flat_name()
+                                )
+                            } else {
+                                // Found an exact match on a qualified name in the outer plan schema, so this is an outer reference column
+                                Ok(Expr::OuterReferenceColumn(
+                                    field.data_type().clone(),
+                                    Column::from((qualifier, field)),
+                                ))
                             }
                         } else {
                             let s = &ids[0..ids.len()];
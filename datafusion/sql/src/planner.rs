@@ -200,11 +200,16 @@ pub struct PlannerContext {
     ctes: HashMap<String, Arc<LogicalPlan>>,
     /// The query schema of the outer query plan, used to resolve the columns in subquery
     outer_query_schema: Option<DFSchemaRef>,
+    /// The query schemas of the queries enclosing the outer query plan, from the
+    /// outermost to the innermost, used to resolve the columns in nested subqueries
+    enclosing_query_schemas: Vec<DFSchemaRef>,
     /// The joined schemas of all FROM clauses planned so far. When planning LATERAL
     /// FROM clauses, this should become a suffix of the `outer_query_schema`.
     outer_from_schema: Option<DFSchemaRef>,
     /// The query schema defined by the table
     create_table_schema: Option<DFSchemaRef>,
+    /// The number of shared subplans planned so far, used to number them
+    shared_subplans: usize,
 }
 
 impl Default for PlannerContext {
@@ -220,8 +225,10 @@ impl PlannerContext {
             prepare_param_data_types: Arc::new(vec![]),
             ctes: HashMap::new(),
             outer_query_schema: None,
+            enclosing_query_schemas: vec![],
             outer_from_schema: None,
             create_table_schema: None,
+            shared_subplans: 0,
         }
     }
 
@@ -249,6 +256,30 @@ impl PlannerContext {
         schema
     }
 
+    /// Returns the schemas of the outer queries, from the innermost to the
+    /// outermost
+    pub fn outer_query_schemas(&self) -> impl Iterator<Item = &DFSchema> {
+        self.outer_query_schema
+            .iter()
+            .chain(self.enclosing_query_schemas.iter().rev())
+            .map(|schema| schema.as_ref())
+    }
+
+    /// Sets the outer query schema of a subquery, keeping the existing one
+    /// visible to the subqueries nested within it
+    pub fn push_outer_query_schema(&mut self, schema: DFSchemaRef) {
+        if let Some(outer) = self.outer_query_schema.replace(schema) {
+            self.enclosing_query_schemas.push(outer);
+        }
+    }
+
+    /// Restores the outer query schema replaced by
+    /// [`Self::push_outer_query_schema`], returning the current one
+    pub fn pop_outer_query_schema(&mut self) -> Option<DFSchemaRef> {
+        let outer = self.enclosing_query_schemas.pop();
+        std::mem::replace(&mut self.outer_query_schema, outer)
+    }
+
     pub fn set_table_schema(
         &mut self,
         mut schema: Option<DFSchemaRef>,
@@ -295,6 +326,15 @@ impl PlannerContext {
         self.ctes.contains_key(cte_name)
     }
 
+    /// Returns a new identifier for a [`SharedSubplan`]
+    ///
+    /// [`SharedSubplan`]: datafusion_expr::logical_plan::SharedSubplan
+    pub fn next_shared_subplan_id(&mut self) -> usize {
+        let id = self.shared_subplans;
+        self.shared_subplans += 1;
+        id
+    }
+
     /// Inserts a LogicalPlan for the Common Table Expression (CTE) /
     /// Subquery for the specified name
     pub fn insert_cte(&mut self, cte_name: impl Into<String>, plan: LogicalPlan) {
@@ -44,7 +44,7 @@ use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    cast, col, Analyze, CreateCatalog, CreateCatalogSchema,
+    cast, col, Analyze, AnalyzeTable, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
     CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
     DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
@@ -773,6 +773,52 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name: ident_to_string(&name),
                 },
             ))),
+            Statement::Analyze {
+                table_name,
+                partitions,
+                columns,
+                cache_metadata,
+                noscan,
+                // The columns are analyzed with or without these keywords
+                for_columns: _,
+                compute_statistics: _,
+                has_table_keyword: _,
+            } => {
+                if partitions.is_some() {
+                    return not_impl_err!("ANALYZE TABLE with PARTITION not supported");
+                }
+                if cache_metadata {
+                    return not_impl_err!(
+                        "ANALYZE TABLE with CACHE METADATA not supported"
+                    );
+                }
+                if noscan {
+                    return not_impl_err!("ANALYZE TABLE with NOSCAN not supported");
+                }
+                let table_name = self.object_name_to_table_reference(table_name)?;
+                let schema = self
+                    .context_provider
+                    .get_table_source(table_name.clone())?
+                    .schema();
+                let columns = columns
+                    .into_iter()
+                    .map(|column| {
+                        let column = self.ident_normalizer.normalize(column);
+                        if schema.index_of(&column).is_err() {
+                            return plan_err!(
+                                "Column {column} not found in table {table_name}"
+                            );
+                        }
+                        Ok(column)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(LogicalPlan::Statement(PlanStatement::AnalyzeTable(
+                    AnalyzeTable {
+                        table_name,
+                        columns,
+                    },
+                )))
+            }
 
             Statement::ShowTables {
                 extended,
@@ -113,6 +113,7 @@ impl Unparser<'_> {
             | LogicalPlan::Limit(_)
             | LogicalPlan::Statement(_)
             | LogicalPlan::Values(_)
+            | LogicalPlan::SharedSubplan(_)
             | LogicalPlan::Distinct(_) => self.select_to_sql_statement(&plan),
             LogicalPlan::Dml(_) => self.dml_to_sql(&plan),
             LogicalPlan::Extension(extension) => {
@@ -965,6 +966,11 @@ impl Unparser<'_> {
                     internal_err!("Unnest input is not a Projection: {unnest:?}")
                 }
             }
+            // The references to a shared subplan are unparsed as separate
+            // subqueries, which compute the same result
+            LogicalPlan::SharedSubplan(shared) => {
+                self.select_to_sql_recursively(&shared.input, query, select, relation)
+            }
             LogicalPlan::Subquery(subquery)
                 if find_unnest_node_until_relation(subquery.subquery.as_ref())
                     .is_some() =>
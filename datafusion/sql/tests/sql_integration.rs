@@ -3553,6 +3553,33 @@ Projection: person.id
     );
 }
 
+#[test]
+fn exists_subquery_nested_outer_reference() {
+    // the innermost subquery references the outermost query
+    let sql = "SELECT id FROM person p WHERE EXISTS \
+            (SELECT id FROM person p2 WHERE p2.last_name = p.last_name AND EXISTS \
+            (SELECT id FROM person WHERE state = p2.state AND age < p.age))";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: p.id
+  Filter: EXISTS (<subquery>)
+    Subquery:
+      Projection: p2.id
+        Filter: p2.last_name = outer_ref(p.last_name) AND EXISTS (<subquery>)
+          Subquery:
+            Projection: person.id
+              Filter: person.state = outer_ref(p2.state) AND person.age < outer_ref(p.age)
+                TableScan: person
+          SubqueryAlias: p2
+            TableScan: person
+    SubqueryAlias: p
+      TableScan: person
+"#
+    );
+}
+
 #[test]
 fn in_subquery_uncorrelated() {
     let sql = "SELECT id FROM person p WHERE id IN \
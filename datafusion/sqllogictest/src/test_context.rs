@@ -40,11 +40,12 @@ use datafusion::{
     prelude::{CsvReadOptions, SessionContext},
 };
 
-use crate::is_spark_path;
+use crate::{is_geo_path, is_spark_path};
 use async_trait::async_trait;
 use datafusion::common::cast::as_float64_array;
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::execution::SessionStateBuilder;
+use datafusion_functions_geo::pruning::BoundingBoxPruning;
 use log::info;
 use tempfile::TempDir;
 
@@ -85,8 +86,18 @@ impl TestContext {
             datafusion_spark::register_all(&mut state)
                 .expect("Can not register Spark functions");
         }
+        if is_geo_path(relative_path) {
+            info!("Registering geospatial functions");
+            datafusion_functions_geo::register_all(&mut state)
+                .expect("Can not register geospatial functions");
+        }
 
         let mut test_ctx = TestContext::new(SessionContext::new_with_state(state));
+        if is_geo_path(relative_path) {
+            test_ctx
+                .session_ctx()
+                .add_optimizer_rule(Arc::new(BoundingBoxPruning::new()));
+        }
 
         let file_name = relative_path.file_name().unwrap().to_str().unwrap();
         match file_name {
@@ -104,9 +104,45 @@ pub fn df_value_validator(
         }
     }
 
-    normalized_actual == normalized_expected
+    normalized_actual.len() == normalized_expected.len()
+        && normalized_actual
+            .iter()
+            .zip(&normalized_expected)
+            .all(|(actual, expected)| matches_ignoring(actual, expected))
+}
+
+/// Marker that matches any text in expected values, used for the parts of
+/// the results that change between runs, such as the timings of
+/// `EXPLAIN ANALYZE`
+const IGNORE_MARKER: &str = "<slt:ignore>";
+
+/// Returns true if `actual` matches `expected`, where each [`IGNORE_MARKER`]
+/// in `expected` matches any text
+fn matches_ignoring(actual: &str, expected: &str) -> bool {
+    let mut parts = expected.split(IGNORE_MARKER);
+    // the first part has no marker before it
+    let first = parts.next().unwrap_or_default();
+    let Some(mut remaining) = actual.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // the last part has no marker after it
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.is_empty()
 }
 
 pub fn is_spark_path(relative_path: &Path) -> bool {
     relative_path.starts_with("spark/")
 }
+
+pub fn is_geo_path(relative_path: &Path) -> bool {
+    relative_path.starts_with("geo/")
+}
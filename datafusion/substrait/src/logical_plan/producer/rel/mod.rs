@@ -74,5 +74,8 @@ pub fn to_substrait_rel(
         LogicalPlan::RecursiveQuery(plan) => {
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
+        // The sharing of the result is not represented: each reference is
+        // converted as its input
+        LogicalPlan::SharedSubplan(plan) => producer.handle_plan(&plan.input),
     }
 }